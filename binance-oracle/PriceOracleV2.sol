@@ -15,19 +15,33 @@ contract PriceOracleV2 is Ownable {
         uint256 updateCount;    // Total updates
     }
     
+    struct RoundData {
+        uint80 roundId;         // Upstream round (e.g. Chainlink) of the last mirrored price
+        uint256 updatedAt;      // Upstream updatedAt of that round
+    }
+    
     // Mapping from feed ID string to price data
     mapping(string => PriceData) public prices;
     
+    // Upstream round metadata for mirrored feeds
+    mapping(string => RoundData) public rounds;
+    
+    // Confidence interval (18 decimals) reported with the last price, for feeds that have one
+    mapping(string => uint256) public confidences;
+    
     // Authorized updaters (oracle runners)
     mapping(address => bool) public authorizedUpdaters;
     
     // Events
     event PriceUpdated(string indexed feedId, uint256 price, uint256 timestamp);
     event UpdaterAuthorized(address indexed updater, bool authorized);
+    event RoundMirrored(string indexed feedId, uint80 roundId, uint256 updatedAt);
+    event ConfidenceUpdated(string indexed feedId, uint256 confidence);
     
     // Errors
     error UnauthorizedUpdater(address updater);
     error InvalidPrice();
+    error StaleRound(uint256 provided, uint256 current);
     
     modifier onlyAuthorized() {
         if (!authorizedUpdaters[msg.sender] && msg.sender != owner()) {
@@ -62,6 +76,59 @@ contract PriceOracleV2 is Ownable {
         emit PriceUpdated(feedId, price, block.timestamp);
     }
     
+    /**
+     * @notice Updates the price for a feed mirrored from another oracle
+     * @dev Rounds must increase so a delayed transaction cannot roll the price back
+     * @param feedId The feed identifier (e.g., "BTCUSD", "ETHUSD")
+     * @param price The new price with 18 decimals
+     * @param roundId The upstream round id
+     * @param updatedAt The upstream round's update time
+     */
+    function updatePriceWithRound(
+        string calldata feedId,
+        uint256 price,
+        uint80 roundId,
+        uint256 updatedAt
+    ) external onlyAuthorized {
+        if (price == 0) revert InvalidPrice();
+        
+        RoundData storage round = rounds[feedId];
+        if (roundId <= round.roundId) revert StaleRound(roundId, round.roundId);
+        round.roundId = roundId;
+        round.updatedAt = updatedAt;
+        
+        PriceData storage data = prices[feedId];
+        data.price = price;
+        data.lastUpdate = block.timestamp;
+        data.updateCount++;
+        
+        emit PriceUpdated(feedId, price, block.timestamp);
+        emit RoundMirrored(feedId, roundId, updatedAt);
+    }
+    
+    /**
+     * @notice Updates the price for a feed whose upstream reports a confidence interval (e.g. Pyth)
+     * @param feedId The feed identifier (e.g., "BTCUSD", "ETHUSD")
+     * @param price The new price with 18 decimals
+     * @param confidence The confidence interval with 18 decimals
+     */
+    function updatePriceWithConfidence(
+        string calldata feedId,
+        uint256 price,
+        uint256 confidence
+    ) external onlyAuthorized {
+        if (price == 0) revert InvalidPrice();
+        
+        PriceData storage data = prices[feedId];
+        data.price = price;
+        data.lastUpdate = block.timestamp;
+        data.updateCount++;
+        confidences[feedId] = confidence;
+        
+        emit PriceUpdated(feedId, price, block.timestamp);
+        emit ConfidenceUpdated(feedId, confidence);
+    }
+    
     /**
      * @notice Updates multiple prices in a single transaction
      * @param feedIds Array of feed identifiers
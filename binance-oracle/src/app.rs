@@ -0,0 +1,300 @@
+use anyhow::Result;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys,
+    oracle_error_handler_config, pipeline_depth_from_env, publish_event, reference_from_spec, shadow_mode_enabled, spawn_parse_summary_from_env, spawn_pause_watcher,
+    submitter_for, Aggregation, BinanceMarket, DryRunOrchestrator, ErrorPolicy, EventBus, EventSink, GrpcServer, Heartbeat, HistoryExporter,
+    OracleEvent, PriceStream, ReceiptVerifier, RedisSink, ReorgDetector, RuntimeMonitor, ShadowComparator, ShutdownCoordinator, Smoother,
+    StatusServer, UpdateHistory, WsProxy,
+};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, error, debug};
+
+use crate::websocket::{BinanceWebSocketClient, TradeBackfill, TradeBuffer, BINANCE_WS_URL};
+use crate::twap::{min_trade_notional_from_env, TwapCalculator};
+use crate::triggers::BinanceTwapTrigger;
+use crate::source::BinanceFeedSource;
+
+/// Run the Binance TWAP oracle until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `binance-oracle` binary or `nonzu run binance-oracle`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Binance TWAP Oracle");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("binance-oracle")?;
+
+    // Set SDK defaults early
+    let chain = apply_sdk_defaults().await?;
+    
+    let oracle_address = env::var("PRICE_ORACLE_V2_ADDRESS")
+        .expect("PRICE_ORACLE_V2_ADDRESS must be set in .env");
+    
+    info!("📝 Oracle contract address: {}", oracle_address);
+
+    // Load worker keys only (PRIVATE_KEY_0, PRIVATE_KEY_1, etc.)
+    // The main PRIVATE_KEY is only for contract ownership, not oracle updates
+    let private_keys = load_private_keys(&["PRIVATE_KEY_"])?;
+    
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    // Key rotation policy - underfunded keys are skipped without being removed
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+    if let Some(handle) = spawn_parse_summary_from_env()? {
+        shutdown.register("parse error summary", handle);
+    }
+
+    // Initialize TWAP calculators with 15-second windows, leaving out dust trades
+    let min_notional = min_trade_notional_from_env()?;
+    let btc_calculator = Arc::new(TwapCalculator::new(Duration::from_secs(15)).with_min_notional(min_notional));
+    let eth_calculator = Arc::new(TwapCalculator::new(Duration::from_secs(15)).with_min_notional(min_notional));
+    
+    // Create shared trade buffer
+    let trade_buffer = Arc::new(TradeBuffer::new(10000)); // Keep last 10k trades
+
+    // Create Binance WebSocket client
+    let ws_client = BinanceWebSocketClient::new(
+        vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+        trade_buffer.clone(),
+    )
+    .with_base_url(env::var("BINANCE_WS_URL").unwrap_or_else(|_| BINANCE_WS_URL.to_string()))
+    .with_proxy(WsProxy::from_env()?.map(Arc::new))
+    .with_backfill(TradeBackfill::from_env(BinanceMarket::Futures)?.map(Arc::new));
+
+    // Start WebSocket in background with trade processing
+    let btc_calc_clone = btc_calculator.clone();
+    let eth_calc_clone = eth_calculator.clone();
+    let trade_buffer_clone = trade_buffer.clone();
+    
+    let ws_handle: tokio::task::JoinHandle<()> = tokio::spawn(async move {
+        // Spawn the WebSocket client
+        let _ws_task = tokio::spawn(async move {
+            if let Err(e) = ws_client.run().await {
+                error!("WebSocket client error: {}", e);
+            }
+        });
+
+        // Process trades from buffer
+        let mut interval = tokio::time::interval(Duration::from_millis(100));
+        loop {
+            interval.tick().await;
+            
+            // Process BTC trades
+            let btc_trades = trade_buffer_clone.get_btc_trades();
+            if !btc_trades.is_empty() {
+                debug!("Processing {} BTC trades", btc_trades.len());
+                if let Some(twap) = btc_calc_clone.add_trades_batch(btc_trades) {
+                    publish_event(OracleEvent::ValueComputed { feed: "BTCUSD".to_string(), value: twap.price });
+                    debug!(
+                        "📊 BTC TWAP: ${:.2} ({} trades, {:.2} BTC volume)",
+                        twap.price, twap.num_trades, twap.volume
+                    );
+                }
+                // Clear only BTC trades after processing
+                trade_buffer_clone.clear_btc();
+            }
+            
+            // Process ETH trades
+            let eth_trades = trade_buffer_clone.get_eth_trades();
+            if !eth_trades.is_empty() {
+                debug!("Processing {} ETH trades", eth_trades.len());
+                if let Some(twap) = eth_calc_clone.add_trades_batch(eth_trades) {
+                    publish_event(OracleEvent::ValueComputed { feed: "ETHUSD".to_string(), value: twap.price });
+                    debug!(
+                        "📊 ETH TWAP: ${:.2} ({} trades, {:.2} ETH volume)",
+                        twap.price, twap.num_trades, twap.volume
+                    );
+                }
+                // Clear only ETH trades after processing
+                trade_buffer_clone.clear_eth();
+            }
+        }
+    });
+
+    // The trigger holds updates until the window has filled (WARMUP_MIN_TRADES / WARMUP_MIN_FILL)
+    info!("⏳ Updates start once the trade window has warmed up");
+
+    // Set up error control for coordinating pause/resume
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    if shadow_mode_enabled() {
+        let spec = env::var("SHADOW_REFERENCE")
+            .expect("SHADOW_REFERENCE must be set in shadow mode (chainlink:<addr> or oracle:<addr>)");
+        let reference_rpc = env::var("SHADOW_RPC_URL").unwrap_or_else(|_| chain.rpc_url().to_string());
+        let reference = reference_from_spec(&spec, &reference_rpc, "BTCUSD").await?;
+        let source = Arc::new(BinanceFeedSource::new(btc_calculator, Aggregation::Twap));
+        let comparator = Arc::new(ShadowComparator::new("BTCUSD", source, reference));
+
+        info!("👥 Shadow mode: comparing BTCUSD TWAP against {} (not publishing)", spec);
+
+        if let Some(addr) = StatusServer::addr_from_env() {
+            let server = StatusServer::new(addr).with_status(comparator.clone());
+            shutdown.register("status server", server.spawn());
+        }
+
+        let interval_ms = env::var("SHADOW_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1000);
+        shutdown.register("websocket", ws_handle);
+        shutdown.register("shadow comparator", comparator.clone().spawn(Duration::from_millis(interval_ms), 60));
+        shutdown.wait_for_signal().await?;
+
+        info!("🛑 Shutting down oracle (shadow mode)...");
+        shutdown.shutdown();
+        let report_path = env::var("SHADOW_REPORT_PATH").unwrap_or_else(|_| "shadow-report.json".to_string());
+        comparator.write_report(&report_path)?;
+        return Ok(());
+    }
+
+    // Re-check confirmed updates a few blocks later (RECEIPT_VERIFY_BLOCKS)
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    // Plugins fed by the event bus: dashboard history and, with
+    // HEARTBEAT_URLS, pings only while updates keep landing
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    if let Some((stream, handle)) = PriceStream::from_env()? {
+        shutdown.register("price stream", handle);
+        EventBus::global().subscribe(stream);
+    }
+    if let Some((sink, handle)) = RedisSink::from_env()? {
+        shutdown.register("redis sink", handle);
+        EventBus::global().subscribe(sink);
+    }
+    if let Some((sink, handle)) = EventSink::from_env().await? {
+        shutdown.register("event sink", handle);
+        EventBus::global().subscribe(sink);
+    }
+    let exporter = match HistoryExporter::from_env()? {
+        Some((exporter, handle)) => {
+            shutdown.register("history export", handle);
+            EventBus::global().subscribe(exporter.clone());
+            Some(exporter)
+        }
+        None => None,
+    };
+    shutdown.register("pause watcher", spawn_pause_watcher("binance-oracle", error_control.clone()));
+
+    // Create TWAP trigger with 200ms updates
+    let twap_trigger = Arc::new(
+        BinanceTwapTrigger::new(
+            Address::from_str(&oracle_address)?,
+            btc_calculator,
+            eth_calculator,
+            Duration::from_millis(200), // Update every 200ms
+            error_control.clone(),
+        )
+        .with_receipt_verifier(receipt_verifier)
+        .with_reorg_detector(reorg_detector)
+        .with_trade_buffer(trade_buffer)
+        .with_smoother(Smoother::from_env()?),
+    );
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let server = StatusServer::new(addr)
+            .with_status(twap_trigger.clone())
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+    if let Some(grpc) = GrpcServer::from_env()? {
+        let grpc = grpc.with_stats("BTCUSD", twap_trigger.stats()).with_control("binance-oracle", error_control.clone());
+        shutdown.register("grpc server", grpc.spawn());
+    }
+
+    shutdown.register("websocket", ws_handle);
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(
+            vec![twap_trigger as Arc<dyn TxTrigger>],
+            &private_keys,
+            &chain,
+            Duration::from_millis(190),
+        ).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down oracle (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    // Single worker for low-spec VMs unless pipelining across keys is enabled
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    // Configure error handling with proper nonce reset
+    let error_handler_config = oracle_error_handler_config();
+
+    // Build orchestrator with custom error handling
+    info!("🔧 Building transaction orchestrator...");
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        vec![twap_trigger as Arc<dyn TxTrigger>],
+        private_keys.into_sdk(),
+        worker_count,
+        Duration::from_millis(190), // Check triggers every 190ms for 200ms updates
+        error_handler_config,
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+
+    // Start orchestrator
+    info!("🚀 Starting orchestrator...");
+    let handle = orchestrator.run().await;
+
+    info!("✅ Binance TWAP Oracle is running! Press Ctrl+C to stop.");
+    info!("📡 Streaming real-time trades from Binance USDⓈ-M Futures");
+    info!("🎯 Calculating 15-second TWAP and updating on-chain every 200ms");
+
+    // Run until shutdown
+    shutdown.wait_for_signal().await?;
+    
+    info!("🛑 Shutting down oracle...");
+    
+    // Cleanup
+    shutdown.shutdown();
+    handle.shutdown().await?;
+    
+    // Trades and updates since the last periodic export
+    if let Some(exporter) = exporter {
+        exporter.export()?;
+    }
+
+    info!("👋 Oracle shutdown complete");
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use alloy::primitives::utils::parse_ether;
+use anyhow::Result;
+use oracle_common::admin::fund_workers;
+use oracle_common::rpc_url_from_env;
+use std::env;
+
+/// Top up every worker key to `FUND_TARGET_ETH` from the owner account.
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    let target = parse_ether(&env::var("FUND_TARGET_ETH").unwrap_or_else(|_| "0.05".to_string()))?;
+    fund_workers(&rpc_url_from_env(), target).await
+}
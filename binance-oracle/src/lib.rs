@@ -0,0 +1,7 @@
+pub mod websocket;
+pub mod twap;
+pub mod triggers;
+pub mod source;
+mod app;
+
+pub use app::run;
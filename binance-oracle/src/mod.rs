@@ -1,3 +0,0 @@
-pub mod websocket;
-pub mod twap;
-pub mod triggers;
\ No newline at end of file
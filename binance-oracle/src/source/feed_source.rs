@@ -0,0 +1,115 @@
+use chrono::Utc;
+use oracle_common::{bar_twap, candle_volatility, Aggregation, CandleStore, PricePoint, PriceSource};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::twap::TwapCalculator;
+use crate::websocket::TradeBuffer;
+
+/// Bar length realized volatility is sampled at
+const VOLATILITY_BAR: Duration = Duration::from_secs(1);
+
+/// Candles a feed's value is computed from
+struct CandleInput {
+    store: Arc<CandleStore>,
+    /// Trade buffer key of the symbol
+    key: String,
+    interval_ms: u64,
+    window_ms: u64,
+}
+
+impl CandleInput {
+    fn value(&self, aggregation: Aggregation) -> Option<f64> {
+        let now = Utc::now().timestamp_millis() as u64;
+        let candles = self.store.candles(&self.key, self.interval_ms, now.saturating_sub(self.window_ms));
+        match aggregation {
+            Aggregation::RealizedVol => candle_volatility(&candles, self.interval_ms),
+            _ => bar_twap(&candles, self.interval_ms, now),
+        }
+    }
+}
+
+/// Exposes a [`TwapCalculator`] as a configured feed's price source
+pub struct BinanceFeedSource {
+    calculator: Arc<TwapCalculator>,
+    aggregation: Aggregation,
+    candles: Option<CandleInput>,
+}
+
+impl BinanceFeedSource {
+    pub fn new(calculator: Arc<TwapCalculator>, aggregation: Aggregation) -> Self {
+        Self { calculator, aggregation, candles: None }
+    }
+
+    /// Compute the value from `key`'s `interval_ms` candles over `window`
+    /// instead of the calculator's trades (`bar-twap`, candle-sampled
+    /// `realized-vol`)
+    pub fn with_candles(mut self, store: Arc<CandleStore>, key: String, interval_ms: u64, window: Duration) -> Self {
+        self.candles = Some(CandleInput { store, key, interval_ms, window_ms: window.as_millis() as u64 });
+        self
+    }
+}
+
+impl PriceSource for BinanceFeedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        let twap = self.calculator.get_latest_twap()?;
+        let price = match (self.aggregation, &self.candles) {
+            (Aggregation::Last, _) => self.calculator.get_last_price()?,
+            (Aggregation::RealizedVol | Aggregation::BarTwap, Some(candles)) => candles.value(self.aggregation)?,
+            (Aggregation::RealizedVol, None) => self.calculator.get_realized_volatility(VOLATILITY_BAR)?,
+            (Aggregation::Twap | Aggregation::BarTwap, _) => twap.price,
+        };
+
+        Some(PricePoint {
+            price,
+            num_trades: twap.num_trades,
+            timestamp_ms: twap.timestamp,
+            confidence: None,
+        })
+    }
+
+    fn window_fill(&self) -> Option<f64> {
+        Some(self.calculator.window_fill())
+    }
+}
+
+/// Move buffered trades into every calculator subscribed to their symbol,
+/// and into `candles` when given, every 100ms. Returns the handle of the
+/// processing task.
+pub fn spawn_trade_pump(
+    trade_buffer: Arc<TradeBuffer>,
+    subscriptions: Vec<(String, Arc<TwapCalculator>)>,
+    candles: Option<Arc<CandleStore>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut symbols: Vec<String> = subscriptions.iter().map(|(s, _)| s.clone()).collect();
+        symbols.sort();
+        symbols.dedup();
+
+        let mut interval = tokio::time::interval(Duration::from_millis(100));
+        loop {
+            interval.tick().await;
+
+            for symbol in &symbols {
+                let trades = trade_buffer.get_trades(symbol);
+                if trades.is_empty() {
+                    continue;
+                }
+                debug!("Processing {} {} trades", trades.len(), symbol);
+
+                if let Some(candles) = &candles {
+                    for trade in &trades {
+                        candles.add_trade(symbol, trade.timestamp, trade.price, trade.quantity);
+                    }
+                }
+
+                for (_, calculator) in subscriptions.iter().filter(|(s, _)| s == symbol) {
+                    calculator.add_trades_batch(trades.clone());
+                }
+                trade_buffer.clear_symbol(symbol);
+            }
+        }
+    })
+}
@@ -0,0 +1,3 @@
+pub mod feed_source;
+
+pub use feed_source::*;
@@ -1,27 +1,42 @@
 use nonzu_sdk::prelude::*;
 use nonzu_sdk::error_handling::OrchestratorErrorControl;
-use alloy::primitives::keccak256;
+use oracle_common::{
+    encode_update_price, function_selector, publish_event, CircuitBreaker, OracleEvent, OracleStats, PendingQueue, PreciseTimer, ReceiptVerifier,
+    ReorgDetector, SharedStats, Smoother, StatusSource, WarmupGate, trace_completed, trace_fired,
+};
 use parking_lot::RwLock;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime};
 use tracing::{info, debug};
 use async_trait::async_trait;
-use alloy::hex;
 
 use crate::twap::TwapCalculator;
+use crate::websocket::{trade_gaps_json, TradeBuffer};
 
 pub struct BinanceTwapTrigger {
     oracle_address: Address,
     btc_calculator: Arc<TwapCalculator>,
     eth_calculator: Arc<TwapCalculator>,
-    last_update: Arc<RwLock<Instant>>,
+    /// Fixed-cadence schedule; ticks are only consumed when an update fires
+    timer: Arc<RwLock<PreciseTimer>>,
     update_interval: Duration,
+    last_drift_ms: Arc<RwLock<i64>>,
     min_trades_for_update: u64,
     price_change_threshold: f64, // Percentage change to trigger update
     last_btc_price: Arc<RwLock<Option<f64>>>,
     last_eth_price: Arc<RwLock<Option<f64>>>,
     update_price_selector: [u8; 4],
     error_control: Arc<OrchestratorErrorControl>,
+    stats: SharedStats,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    warmup: Arc<WarmupGate>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    /// Reported in the status alongside the TWAP windows
+    trade_buffer: Option<Arc<TradeBuffer>>,
+    /// Filters the BTC TWAP before it is published (`SMOOTHING`)
+    smoother: Option<Smoother>,
 }
 
 impl BinanceTwapTrigger {
@@ -33,25 +48,55 @@ impl BinanceTwapTrigger {
         error_control: Arc<OrchestratorErrorControl>,
     ) -> Self {
         // Pre-calculate the function selector for updatePrice(string,uint256)
-        let function_signature = "updatePrice(string,uint256)";
-        let selector_bytes = keccak256(function_signature.as_bytes());
-        let mut selector = [0u8; 4];
-        selector.copy_from_slice(&selector_bytes[0..4]);
+        let selector = function_selector("updatePrice(string,uint256)");
         
         Self {
             oracle_address,
             btc_calculator,
             eth_calculator,
-            last_update: Arc::new(RwLock::new(Instant::now())),
+            timer: Arc::new(RwLock::new(PreciseTimer::new(update_interval.as_millis() as u64))),
             update_interval,
+            last_drift_ms: Arc::new(RwLock::new(0)),
             min_trades_for_update: 1, // Reduced to 1 for testing
             price_change_threshold: 0.0, // 0% threshold - update every interval
             last_btc_price: Arc::new(RwLock::new(None)),
             last_eth_price: Arc::new(RwLock::new(None)),
             update_price_selector: selector,
             error_control,
+            stats: OracleStats::shared(),
+            pending: PendingQueue::for_interval("BTCUSD", update_interval),
+            breaker: CircuitBreaker::from_env("BTCUSD"),
+            warmup: WarmupGate::from_env("BTCUSD"),
+            receipt_verifier: None,
+            reorg_detector: None,
+            trade_buffer: None,
+            smoother: None,
         }
     }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+
+    pub fn with_trade_buffer(mut self, buffer: Arc<TradeBuffer>) -> Self {
+        self.trade_buffer = Some(buffer);
+        self
+    }
+
+    pub fn with_smoother(mut self, smoother: Option<Smoother>) -> Self {
+        self.smoother = smoother;
+        self
+    }
+
+    pub fn stats(&self) -> SharedStats {
+        self.stats.clone()
+    }
     
 
     fn should_update(&self, current_price: f64, last_price: Option<f64>) -> bool {
@@ -65,73 +110,29 @@ impl BinanceTwapTrigger {
     }
 
     fn encode_update_price(&self, feed_id: &str, price: U256) -> Bytes {
-        // Manual ABI encoding for function with (string, uint256) parameters
-        let mut encoded_params = Vec::new();
-        
-        // First parameter: offset to string data (64 bytes from start of params)
-        encoded_params.extend_from_slice(&[0u8; 28]); // padding
-        encoded_params.extend_from_slice(&[0, 0, 0, 0x40]); // offset = 64 bytes
-        
-        // Second parameter: uint256 value (32 bytes)
-        let price_bytes = price.to_be_bytes::<32>();
-        encoded_params.extend_from_slice(&price_bytes);
-        
-        // String data at offset 64:
-        // - Length of string (32 bytes)
-        let feed_bytes = feed_id.as_bytes();
-        let mut length_bytes = [0u8; 32];
-        length_bytes[31] = feed_bytes.len() as u8;
-        encoded_params.extend_from_slice(&length_bytes);
-        
-        // - String content (padded to 32 bytes)
-        encoded_params.extend_from_slice(feed_bytes);
-        // Pad to 32 bytes
-        let padding = 32 - (feed_bytes.len() % 32);
-        if padding < 32 {
-            encoded_params.extend_from_slice(&vec![0u8; padding]);
-        }
-        
-        // Combine selector and encoded parameters
-        let mut call_data = Vec::with_capacity(4 + encoded_params.len());
-        call_data.extend_from_slice(&self.update_price_selector);
-        call_data.extend_from_slice(&encoded_params);
-        
-        debug!(
-            "Encoding updatePrice call - feed_id: {}, price: {}, selector: 0x{}, calldata length: {}",
-            feed_id,
-            price,
-            hex::encode(&self.update_price_selector),
-            call_data.len()
-        );
-        
-        debug!("Full calldata: 0x{}", hex::encode(&call_data));
-        
-        Bytes::from(call_data)
+        encode_update_price(self.update_price_selector, feed_id, price)
     }
 }
 
 #[async_trait]
 impl TxTrigger for BinanceTwapTrigger {
     async fn should_trigger(&self) -> Result<Option<TxRequest>> {
-        // Check if worker pool is paused
-        if self.error_control.is_worker_pool_paused().await {
-            debug!("Worker pool paused, skipping trigger");
+        let started = SystemTime::now();
+        // Check if worker pool is paused (or just resumed)
+        let paused = self.error_control.is_worker_pool_paused().await;
+        if !self.warmup.running(paused) {
+            if paused {
+                debug!("Worker pool paused, skipping trigger");
+            }
             return Ok(None);
         }
         
-        let now = Instant::now();
-        let last = *self.last_update.read();
-
-        // Check if enough time has passed
-        let time_since_last = now.duration_since(last);
-        if time_since_last < self.update_interval {
-            debug!("Not enough time passed: {:.2}s < {:.2}s", 
-                time_since_last.as_secs_f64(), 
-                self.update_interval.as_secs_f64()
-            );
+        // Check if the next tick is due
+        if !self.timer.read().is_due() {
+            debug!("Next update in {:.2?}", self.timer.read().time_until_next());
             return Ok(None);
         }
-        info!("Checking trigger conditions (time elapsed: {:.2}s)", time_since_last.as_secs_f64());
+        info!("Checking trigger conditions (interval: {:.2}s)", self.update_interval.as_secs_f64());
 
         // Get latest TWAP values
         let btc_twap = self.btc_calculator.get_latest_twap();
@@ -148,25 +149,45 @@ impl TxTrigger for BinanceTwapTrigger {
                 return Ok(None);
             }
 
+            // Never publish from a window that is still filling
+            if !self.warmup.ready(btc.num_trades, Some(self.btc_calculator.window_fill())) {
+                debug!("BTC window still warming up ({} trades)", btc.num_trades);
+                return Ok(None);
+            }
+
             // Always update based on time interval only
 
+            let price = match &self.smoother {
+                Some(smoother) => smoother.smooth(btc.price, btc.timestamp),
+                None => btc.price,
+            };
+
+            if !self.breaker.try_acquire() {
+                debug!("Circuit breaker open, skipping update");
+                return Ok(None);
+            }
+
             // Convert price to uint256 (multiply by 1e18 for 18 decimals)
             // Using proper scaling to avoid precision loss
-            let price_scaled = (btc.price * 1e18).round() as u128;
+            let price_scaled = (price * 1e18).round() as u128;
             let price_u256 = U256::from(price_scaled);
             
-            debug!("BTC price conversion: ${} -> {} (scaled)", btc.price, price_u256);
+            debug!("BTC price conversion: ${} -> {} (scaled)", price, price_u256);
+
+            self.stats.write().record_trigger();
 
             // Create update transaction for BTC
             let call_data = self.encode_update_price("BTCUSD", price_u256);
 
             // Update state
-            *self.last_update.write() = now;
-            *self.last_btc_price.write() = Some(btc.price);
+            if let Some((target_time, actual_time)) = self.timer.write().should_tick() {
+                *self.last_drift_ms.write() = actual_time as i64 - target_time as i64;
+            }
+            *self.last_btc_price.write() = Some(price);
 
             info!(
                 "🚀 TRIGGER FIRED! Triggering oracle update - BTC: ${:.2} ({} trades, {:.2} BTC volume)",
-                btc.price, btc.num_trades, btc.volume
+                price, btc.num_trades, btc.volume
             );
 
             // Log market quality if available
@@ -177,17 +198,21 @@ impl TxTrigger for BinanceTwapTrigger {
                 btc_quality.volatility, btc_quality.trade_frequency
             );
 
-            let tx_request = TxRequest::new(self.oracle_address, call_data)
+            let tx_request = TxRequest::new(self.oracle_address, call_data.clone())
                 .with_gas_limit(U256::from(300_000))
                 .with_priority(TxPriority::High)
                 .with_metadata("type", "twap_update")
                 .with_metadata("feed_id", "BTCUSD")
-                .with_metadata("price", btc.price.to_string())
+                .with_metadata("price", price.to_string())
                 .with_metadata("price_scaled", price_u256.to_string())
                 .with_metadata("trades", btc.num_trades.to_string())
                 .with_metadata("volume", format!("{:.2}", btc.volume));
             
-            Ok(Some(tx_request))
+            let admitted = self.pending.admit(tx_request).map(|(tx_request, _)| tx_request);
+            if admitted.is_some() {
+                trace_fired("BTCUSD", &call_data, started);
+            }
+            Ok(admitted)
         } else {
             debug!("No TWAP data available yet");
             Ok(None)
@@ -195,19 +220,35 @@ impl TxTrigger for BinanceTwapTrigger {
     }
 
     async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        self.pending.complete();
+        self.breaker.record(success);
+        let drift_ms = *self.last_drift_ms.read();
+        publish_event(OracleEvent::completed("BTCUSD", success, receipt, *self.last_btc_price.read(), latency, drift_ms));
         if success {
+            self.stats.write().record_success(drift_ms, receipt.map(|r| r.gas_used), latency);
             if let Some(receipt) = receipt {
+                self.stats.write().last_block = Some(receipt.block_number);
                 info!(
                     "✅ Oracle update confirmed - tx: {}, block: {}, gas: {}",
                     receipt.transaction_hash, receipt.block_number, receipt.gas_used
                 );
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch("BTCUSD", receipt, &self.stats);
+                }
+                // Updates go out every interval, so a dropped one is replaced by the next
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch("BTCUSD", receipt, None);
+                }
                 if let Some(lat) = latency {
                     debug!("   Transaction latency: {:.2?}", lat);
                 }
             }
         } else {
+            self.stats.write().record_failure();
             tracing::error!("❌ Oracle update failed");
         }
+        trace_completed("BTCUSD", receipt, success, started);
     }
     
     fn metadata(&self) -> TriggerMetadata {
@@ -218,4 +259,46 @@ impl TxTrigger for BinanceTwapTrigger {
             version: "1.0.0".to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+impl StatusSource for BinanceTwapTrigger {
+    fn status(&self) -> serde_json::Value {
+        let twap_json = |calc: &TwapCalculator| {
+            calc.get_latest_twap().map(|t| serde_json::json!({
+                "price": t.price,
+                "volume": t.volume,
+                "num_trades": t.num_trades,
+                "timestamp": t.timestamp,
+            }))
+        };
+
+        let window_json = |calc: &TwapCalculator| {
+            serde_json::json!({ "trades": calc.get_trade_count(), "capacity": calc.capacity(), "dust": calc.dust_json() })
+        };
+
+        serde_json::json!({
+            "oracle": "binance-oracle",
+            "oracle_address": self.oracle_address.to_string(),
+            "update_interval_ms": self.update_interval.as_millis() as u64,
+            "twap": {
+                "BTCUSD": twap_json(&self.btc_calculator),
+                "ETHUSD": twap_json(&self.eth_calculator),
+            },
+            "buffers": {
+                "windows": {
+                    "BTCUSD": window_json(&self.btc_calculator),
+                    "ETHUSD": window_json(&self.eth_calculator),
+                },
+                "pending_trades": self.trade_buffer.as_ref().map(|b| b.to_json()),
+            },
+            "trade_gaps": trade_gaps_json(),
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "warmup": self.warmup.to_json(),
+            "smoothing": self.smoother.as_ref().map(|s| s.to_json()),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
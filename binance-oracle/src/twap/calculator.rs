@@ -1,7 +1,8 @@
 use std::collections::VecDeque;
 use std::time::Duration;
+use anyhow::{Context, Result};
 use chrono::Utc;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 use crate::websocket::Trade;
 
@@ -14,10 +15,48 @@ pub struct TwapResult {
     pub spread: Option<f64>,
 }
 
+/// Keep the window in trade time order: backfilled trades arrive after
+/// newer live ones
+fn insert_ordered(trades: &mut VecDeque<Trade>, trade: Trade) {
+    match trades.back() {
+        Some(last) if trade.timestamp < last.timestamp => {
+            let at = trades.partition_point(|t| t.timestamp <= trade.timestamp);
+            trades.insert(at, trade);
+        }
+        _ => trades.push_back(trade),
+    }
+}
+
+/// Smallest trade notional (price * quantity, in quote units) counted, from
+/// `BINANCE_MIN_TRADE_NOTIONAL`; `None` when unset
+pub fn min_trade_notional_from_env() -> Result<Option<f64>> {
+    match std::env::var("BINANCE_MIN_TRADE_NOTIONAL") {
+        Ok(v) => {
+            let min: f64 = v.parse().context("Invalid BINANCE_MIN_TRADE_NOTIONAL")?;
+            if !(min > 0.0 && min.is_finite()) {
+                anyhow::bail!("BINANCE_MIN_TRADE_NOTIONAL must be positive, got '{}'", v);
+            }
+            Ok(Some(min))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Trades left out of the window for being below the minimum notional
+#[derive(Debug, Default)]
+struct DustTrades {
+    trades: u64,
+    volume: f64,
+    notional: f64,
+}
+
 pub struct TwapCalculator {
     window_size: Duration,
     trades: RwLock<VecDeque<Trade>>,
     last_twap: RwLock<Option<TwapResult>>,
+    /// Dust trades (often spoofed prints on futures) below this are ignored
+    min_notional: Option<f64>,
+    dust: Mutex<DustTrades>,
 }
 
 impl TwapCalculator {
@@ -26,12 +65,40 @@ impl TwapCalculator {
             window_size,
             trades: RwLock::new(VecDeque::new()),
             last_twap: RwLock::new(None),
+            min_notional: None,
+            dust: Mutex::new(DustTrades::default()),
         }
     }
 
+    /// Leave trades with a notional (price * quantity) below `min_notional`
+    /// out of the window, counting them instead
+    pub fn with_min_notional(mut self, min_notional: Option<f64>) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    /// Whether `trade` counts; dust trades are tallied
+    fn admit(&self, trade: &Trade) -> bool {
+        let Some(min_notional) = self.min_notional else {
+            return true;
+        };
+        let notional = trade.price * trade.quantity;
+        if notional >= min_notional {
+            return true;
+        }
+        let mut dust = self.dust.lock();
+        dust.trades += 1;
+        dust.volume += trade.quantity;
+        dust.notional += notional;
+        false
+    }
+
     pub fn add_trade(&self, trade: Trade) -> Option<TwapResult> {
+        if !self.admit(&trade) {
+            return self.get_latest_twap();
+        }
         let mut trades = self.trades.write();
-        trades.push_back(trade);
+        insert_ordered(&mut trades, trade);
         drop(trades); // Release write lock before calling other methods
         
         self.remove_old_trades();
@@ -46,8 +113,8 @@ impl TwapCalculator {
 
     pub fn add_trades_batch(&self, new_trades: Vec<Trade>) -> Option<TwapResult> {
         let mut trades = self.trades.write();
-        for trade in new_trades {
-            trades.push_back(trade);
+        for trade in new_trades.into_iter().filter(|trade| self.admit(trade)) {
+            insert_ordered(&mut trades, trade);
         }
         drop(trades);
         
@@ -125,10 +192,50 @@ impl TwapCalculator {
         self.last_twap.read().clone()
     }
 
+    /// Price of the most recent trade in the window
+    pub fn get_last_price(&self) -> Option<f64> {
+        self.trades.read().back().map(|t| t.price)
+    }
+
+    /// Annualized realized volatility of the window, sampled every `bar`
+    pub fn get_realized_volatility(&self, bar: Duration) -> Option<f64> {
+        let samples: Vec<(u64, f64)> = self.trades.read().iter().map(|t| (t.timestamp, t.price)).collect();
+        oracle_common::realized_volatility(&samples, bar.as_millis() as u64)
+    }
+
+    /// Fraction of the window spanned by the trades in it, from the oldest
+    /// trade to now (1.0 once trades cover the whole window)
+    pub fn window_fill(&self) -> f64 {
+        let Some(oldest) = self.trades.read().front().map(|t| t.timestamp) else {
+            return 0.0;
+        };
+        let now = Utc::now().timestamp_millis() as u64;
+        let window_ms = self.window_size.as_millis().max(1) as f64;
+        (now.saturating_sub(oldest) as f64 / window_ms).min(1.0)
+    }
+
+    /// Trades filtered out as dust and their volume, `None` without a
+    /// minimum notional
+    pub fn dust_json(&self) -> Option<serde_json::Value> {
+        let min_notional = self.min_notional?;
+        let dust = self.dust.lock();
+        Some(serde_json::json!({
+            "min_notional": min_notional,
+            "trades": dust.trades,
+            "volume": dust.volume,
+            "notional": dust.notional,
+        }))
+    }
+
     pub fn get_trade_count(&self) -> usize {
         self.trades.read().len()
     }
 
+    /// Trades the window can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.trades.read().capacity()
+    }
+
     pub fn clear(&self) {
         self.trades.write().clear();
         *self.last_twap.write() = None;
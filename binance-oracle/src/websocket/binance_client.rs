@@ -3,14 +3,44 @@ use futures_util::{SinkExt, StreamExt};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::{sleep, timeout};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use oracle_common::{connect_ws, maybe_drop_stream, parse_quarantine, publish_event, ws_bandwidth, BinanceMarket, OracleEvent, WsProxy};
 use tracing::{info, warn, error, debug};
 
+use super::trade_gaps::{trade_gaps, AggTrade, TradeBackfill, TradeGap};
 use super::trade_parser::{BinanceTradeMessage, Trade, TradeBuffer};
 
+/// Binance USD-M futures combined-stream endpoint
+pub const BINANCE_WS_URL: &str = "wss://fstream.binance.com";
+
+/// Binance spot combined-stream endpoint
+pub const BINANCE_SPOT_WS_URL: &str = "wss://stream.binance.com:9443";
+
+/// Default endpoint of `market`, overridable with `BINANCE_WS_URL` (futures)
+/// or `BINANCE_SPOT_WS_URL` (spot)
+pub fn ws_url_from_env(market: BinanceMarket) -> String {
+    match market {
+        BinanceMarket::Futures => std::env::var("BINANCE_WS_URL").unwrap_or_else(|_| BINANCE_WS_URL.to_string()),
+        BinanceMarket::Spot => std::env::var("BINANCE_SPOT_WS_URL").unwrap_or_else(|_| BINANCE_SPOT_WS_URL.to_string()),
+    }
+}
+
+/// Key `market`'s trades in `symbol` are buffered under: the bare symbol for
+/// futures, `spot:<symbol>` for spot, so both markets can share a buffer
+pub fn trade_buffer_key(market: BinanceMarket, symbol: &str) -> String {
+    match market {
+        BinanceMarket::Futures => symbol.to_string(),
+        BinanceMarket::Spot => format!("spot:{}", symbol),
+    }
+}
+
 pub struct BinanceWebSocketClient {
     symbols: Vec<String>,
     trade_buffer: Arc<TradeBuffer>,
+    market: BinanceMarket,
+    base_url: String,
+    proxy: Option<Arc<WsProxy>>,
+    backfill: Option<Arc<TradeBackfill>>,
     reconnect_delay: Duration,
 }
 
@@ -19,20 +49,70 @@ impl BinanceWebSocketClient {
         Self {
             symbols,
             trade_buffer,
+            market: BinanceMarket::Futures,
+            base_url: BINANCE_WS_URL.to_string(),
+            proxy: None,
+            backfill: None,
             reconnect_delay: Duration::from_secs(5),
         }
     }
 
+    /// Stream `market`'s trades from its default endpoint, buffered under
+    /// [`trade_buffer_key`]; call before [`with_base_url`](Self::with_base_url)
+    pub fn with_market(mut self, market: BinanceMarket) -> Self {
+        self.market = market;
+        self.base_url = match market {
+            BinanceMarket::Futures => BINANCE_WS_URL,
+            BinanceMarket::Spot => BINANCE_SPOT_WS_URL,
+        }
+        .to_string();
+        self
+    }
+
+    /// Source name in events and chaos rolls
+    fn source(&self) -> &'static str {
+        match self.market {
+            BinanceMarket::Futures => "binance",
+            BinanceMarket::Spot => "binance-spot",
+        }
+    }
+
+    /// Connect somewhere other than Binance, e.g. a local mock server
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Connect through an HTTP CONNECT / SOCKS5 proxy (`WS_PROXY_URL`)
+    pub fn with_proxy(mut self, proxy: Option<Arc<WsProxy>>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Fetch the trades of trade id gaps from REST (`BINANCE_BACKFILL`)
+    pub fn with_backfill(mut self, backfill: Option<Arc<TradeBackfill>>) -> Self {
+        self.backfill = backfill;
+        self
+    }
+
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
     pub async fn run(&self) -> Result<()> {
         loop {
-            match self.connect_and_process().await {
+            let error = match self.connect_and_process().await {
                 Ok(_) => {
                     warn!("WebSocket connection closed, reconnecting in {:?}", self.reconnect_delay);
+                    None
                 }
                 Err(e) => {
                     error!("WebSocket error: {}, reconnecting in {:?}", e, self.reconnect_delay);
+                    Some(e.to_string())
                 }
-            }
+            };
+            publish_event(OracleEvent::WsReconnect { source: self.source().to_string(), error });
             
             sleep(self.reconnect_delay).await;
         }
@@ -46,18 +126,20 @@ impl BinanceWebSocketClient {
             .collect::<Vec<_>>()
             .join("/");
         
-        let url = format!("wss://fstream.binance.com/stream?streams={}", streams);
-        info!("Connecting to Binance WebSocket: {}", url);
+        let url = format!("{}/stream?streams={}", self.base_url.trim_end_matches('/'), streams);
+        info!("Connecting to Binance {} WebSocket: {}", self.market.as_str(), url);
 
+        let bandwidth = ws_bandwidth(self.source());
+        let quarantine = parse_quarantine(self.source());
         let (ws_stream, _) = timeout(
             Duration::from_secs(10),
-            connect_async(&url)
+            connect_ws(&url, self.proxy.as_deref(), bandwidth.clone())
         )
         .await
         .map_err(|_| anyhow!("Connection timeout"))?
         .map_err(|e| anyhow!("Failed to connect: {}", e))?;
 
-        info!("Connected to Binance WebSocket");
+        info!("Connected to Binance {} WebSocket", self.market.as_str());
 
         let (mut write, mut read) = ws_stream.split();
 
@@ -80,7 +162,12 @@ impl BinanceWebSocketClient {
                 msg = read.next() => {
                     match msg {
                         Some(Ok(Message::Text(text))) => {
-                            self.process_message(&text)?;
+                            bandwidth.message(text.as_bytes());
+                            maybe_drop_stream(self.source())?;
+                            // One bad message shouldn't cost a reconnect
+                            if let Err(e) = self.process_message(&text) {
+                                quarantine.reject(e, &text);
+                            }
                         }
                         Some(Ok(Message::Ping(data))) => {
                             write.send(Message::Pong(data)).await?;
@@ -124,24 +211,71 @@ impl BinanceWebSocketClient {
                 debug!("Raw message structure: {}", serde_json::to_string_pretty(&data).unwrap_or_default());
             });
             
-            match serde_json::from_value::<BinanceTradeMessage>(data.clone()) {
-                Ok(trade_msg) => {
-                    if trade_msg.event_type == "trade" {
-                        let trade = Trade::from(trade_msg.clone());
-                        self.trade_buffer.add_trade(&trade_msg.symbol, trade);
-                        
-                        debug!(
-                            "Trade: {} @ {} (qty: {}, buyer_maker: {})",
-                            trade_msg.symbol, trade_msg.price, trade_msg.quantity, trade_msg.is_buyer_maker
-                        );
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to parse trade message: {} - Data: {:?}", e, data);
+            match data.get("e").and_then(|e| e.as_str()) {
+                Some("trade") => {}
+                other => {
+                    parse_quarantine(self.source()).unknown_event(other.unwrap_or("(none)"));
+                    return Ok(());
                 }
             }
+            let trade_msg = serde_json::from_value::<BinanceTradeMessage>(data.clone())?;
+            if let Some(gap) = trade_gaps(self.source()).observe(&trade_msg.symbol, trade_msg.trade_id, trade_msg.trade_time) {
+                self.on_gap(gap);
+            }
+            let trade = Trade::from(trade_msg.clone());
+            publish_event(OracleEvent::TradeReceived {
+                source: self.source().to_string(),
+                symbol: trade_msg.symbol.clone(),
+                price: trade.price,
+                quantity: trade.quantity,
+                timestamp_ms: trade.timestamp,
+            });
+            self.trade_buffer.add_trade(&trade_buffer_key(self.market, &trade_msg.symbol), trade);
+
+            debug!(
+                "Trade: {} @ {} (qty: {}, buyer_maker: {})",
+                trade_msg.symbol, trade_msg.price, trade_msg.quantity, trade_msg.is_buyer_maker
+            );
         }
         
         Ok(())
     }
+
+    /// Report a trade id gap and backfill it if configured
+    fn on_gap(&self, gap: TradeGap) {
+        warn!(
+            "Binance {} {}: {} trades missed between ids {} and {} ({}ms)",
+            self.market.as_str(),
+            gap.symbol,
+            gap.missed(),
+            gap.after_id,
+            gap.before_id,
+            gap.before_ms.saturating_sub(gap.after_ms)
+        );
+        publish_event(OracleEvent::TradeGap {
+            source: self.source().to_string(),
+            symbol: gap.symbol.clone(),
+            missed: gap.missed(),
+        });
+
+        let Some(backfill) = self.backfill.clone().filter(|backfill| backfill.covers(&gap)) else {
+            return;
+        };
+        let source = self.source();
+        let key = trade_buffer_key(self.market, &gap.symbol);
+        let trade_buffer = self.trade_buffer.clone();
+        tokio::spawn(async move {
+            match backfill.fetch(&gap).await {
+                Ok(aggs) => {
+                    for agg in &aggs {
+                        trade_buffer.add_trade(&key, Trade::from(agg));
+                    }
+                    let recovered: u64 = aggs.iter().map(AggTrade::trades).sum();
+                    trade_gaps(source).backfilled(&gap.symbol, recovered);
+                    info!("Backfilled {} of {} missed {} trades from aggTrades", recovered, gap.missed(), gap.symbol);
+                }
+                Err(e) => warn!("Backfilling {} missed {} trades failed: {:#}", gap.missed(), gap.symbol, e),
+            }
+        });
+    }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 pub mod binance_client;
+pub mod trade_gaps;
 pub mod trade_parser;
 
 pub use binance_client::*;
+pub use trade_gaps::*;
 pub use trade_parser::*;
\ No newline at end of file
@@ -0,0 +1,323 @@
+//! Trade ID gap detection, with optional backfill from REST.
+//!
+//! Binance numbers each symbol's trades consecutively, so a jump in trade
+//! IDs - typically across a reconnect - says exactly how many trades never
+//! reached the TWAP window. Every gap is logged, published as
+//! [`OracleEvent::TradeGap`](oracle_common::OracleEvent::TradeGap) and
+//! counted per symbol (gap sizes as p50/p90/p99) under `trade_gaps` in
+//! `/status`.
+//!
+//! With `BINANCE_BACKFILL=true` the missed trades are fetched from the REST
+//! `aggTrades` endpoint and added to the trade buffer, so the window is
+//! complete again once they land. An aggregate trade that straddles an edge
+//! of the gap also holds trades that were received live; only its missed
+//! trades are added, with the aggregate's quantity split evenly across its
+//! trades (they share one price and time). The REST time range is limited
+//! to an hour, so the first aggregate is looked up by time an hour at a
+//! time and the rest are paged by aggregate id.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `BINANCE_BACKFILL` | `false` | Backfill gaps from `aggTrades` |
+//! | `BINANCE_BACKFILL_MAX_TRADES` | `10000` | Larger gaps are only reported |
+//! | `BINANCE_REST_URL` | `https://fapi.binance.com` | Futures REST endpoint |
+//! | `BINANCE_SPOT_REST_URL` | `https://api.binance.com` | Spot REST endpoint |
+
+use anyhow::{Context, Result};
+use oracle_common::{BinanceMarket, Distribution};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use super::trade_parser::Trade;
+
+pub const BINANCE_REST_URL: &str = "https://fapi.binance.com";
+pub const BINANCE_SPOT_REST_URL: &str = "https://api.binance.com";
+
+/// Gap sizes kept per symbol
+const MAX_SAMPLES: usize = 1_000;
+
+/// `aggTrades` page size (the endpoint's maximum)
+const PAGE_LIMIT: usize = 1_000;
+
+/// Pages fetched per gap at most
+const MAX_PAGES: usize = 20;
+
+/// Longest `startTime` / `endTime` range `aggTrades` accepts
+const MAX_WINDOW_MS: u64 = 60 * 60 * 1000;
+
+const DEFAULT_MAX_TRADES: u64 = 10_000;
+
+/// Trades missing between two that were received
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeGap {
+    pub symbol: String,
+    /// Last trade received before the gap
+    pub after_id: u64,
+    pub after_ms: u64,
+    /// First trade received after it
+    pub before_id: u64,
+    pub before_ms: u64,
+}
+
+impl TradeGap {
+    pub fn missed(&self) -> u64 {
+        self.before_id - self.after_id - 1
+    }
+}
+
+struct SymbolGaps {
+    gaps: u64,
+    missed: u64,
+    backfilled: u64,
+    largest: u64,
+    sizes: Distribution,
+}
+
+impl Default for SymbolGaps {
+    fn default() -> Self {
+        Self { gaps: 0, missed: 0, backfilled: 0, largest: 0, sizes: Distribution::new(MAX_SAMPLES) }
+    }
+}
+
+#[derive(Default)]
+struct GapState {
+    /// (trade id, trade time) of the newest trade per symbol
+    last: HashMap<String, (u64, u64)>,
+    /// Trades at or below the newest id (replays, out of order)
+    stale: u64,
+    symbols: BTreeMap<String, SymbolGaps>,
+}
+
+/// Trade ID continuity of one source's symbols
+#[derive(Default)]
+pub struct TradeGaps {
+    state: Mutex<GapState>,
+}
+
+impl TradeGaps {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trade `trade_id` of `symbol` arrived; returns the gap before it, if any
+    pub fn observe(&self, symbol: &str, trade_id: u64, trade_ms: u64) -> Option<TradeGap> {
+        let mut state = self.state.lock();
+        let previous = state.last.get(symbol).copied();
+        match previous {
+            Some((last_id, _)) if trade_id <= last_id => {
+                state.stale += 1;
+                return None;
+            }
+            _ => {
+                state.last.insert(symbol.to_string(), (trade_id, trade_ms));
+            }
+        }
+        let (after_id, after_ms) = previous?;
+        if trade_id == after_id + 1 {
+            return None;
+        }
+        let gap = TradeGap { symbol: symbol.to_string(), after_id, after_ms, before_id: trade_id, before_ms: trade_ms };
+        let stats = state.symbols.entry(symbol.to_string()).or_default();
+        stats.gaps += 1;
+        stats.missed += gap.missed();
+        stats.largest = stats.largest.max(gap.missed());
+        stats.sizes.record(gap.missed() as f64);
+        Some(gap)
+    }
+
+    /// `trades` of `symbol`'s missed trades were recovered
+    pub fn backfilled(&self, symbol: &str, trades: u64) {
+        self.state.lock().symbols.entry(symbol.to_string()).or_default().backfilled += trades;
+    }
+
+    pub fn missed(&self, symbol: &str) -> u64 {
+        self.state.lock().symbols.get(symbol).map_or(0, |stats| stats.missed)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.lock();
+        let symbols: serde_json::Map<String, Value> = state
+            .symbols
+            .iter()
+            .map(|(symbol, stats)| {
+                let json = json!({
+                    "gaps": stats.gaps,
+                    "missed_trades": stats.missed,
+                    "backfilled_trades": stats.backfilled,
+                    "largest_gap": stats.largest,
+                    "gap_size": stats.sizes.summary(),
+                });
+                (symbol.clone(), json)
+            })
+            .collect();
+        json!({ "stale_trades": state.stale, "symbols": symbols })
+    }
+}
+
+fn registry() -> &'static Mutex<BTreeMap<String, Arc<TradeGaps>>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<String, Arc<TradeGaps>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Process-wide gap tracking of `source` ("binance", "binance-spot")
+pub fn trade_gaps(source: &str) -> Arc<TradeGaps> {
+    registry().lock().entry(source.to_string()).or_default().clone()
+}
+
+/// Every source's gaps, for status output
+pub fn trade_gaps_json() -> Value {
+    let sources: serde_json::Map<String, Value> =
+        registry().lock().iter().map(|(source, gaps)| (source.clone(), gaps.to_json())).collect();
+    Value::Object(sources)
+}
+
+/// One entry of the `aggTrades` response
+#[derive(Debug, Clone, Deserialize)]
+pub struct AggTrade {
+    #[serde(rename = "a")]
+    pub agg_id: u64,
+    #[serde(rename = "p")]
+    pub price: String,
+    #[serde(rename = "q")]
+    pub quantity: String,
+    #[serde(rename = "f")]
+    pub first_id: u64,
+    #[serde(rename = "l")]
+    pub last_id: u64,
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+impl AggTrade {
+    /// The part of this aggregate within `gap`'s missed trade ids, its
+    /// quantity scaled to the share of its trades that were missed
+    pub fn within(&self, gap: &TradeGap) -> Option<AggTrade> {
+        let first_id = self.first_id.max(gap.after_id + 1);
+        let last_id = self.last_id.min(gap.before_id - 1);
+        if first_id > last_id {
+            return None;
+        }
+        let share = (last_id - first_id + 1) as f64 / (self.last_id - self.first_id + 1) as f64;
+        let quantity = if share < 1.0 {
+            (self.quantity.parse::<f64>().unwrap_or(0.0) * share).to_string()
+        } else {
+            self.quantity.clone()
+        };
+        Some(AggTrade { first_id, last_id, quantity, ..self.clone() })
+    }
+
+    /// Trades this aggregate holds
+    pub fn trades(&self) -> u64 {
+        self.last_id - self.first_id + 1
+    }
+}
+
+impl From<&AggTrade> for Trade {
+    fn from(agg: &AggTrade) -> Self {
+        Self {
+            price: agg.price.parse::<f64>().unwrap_or(0.0),
+            quantity: agg.quantity.parse::<f64>().unwrap_or(0.0),
+            timestamp: agg.trade_time,
+            is_buyer_maker: agg.is_buyer_maker,
+        }
+    }
+}
+
+/// Fetches the trades of a [`TradeGap`] from `aggTrades`
+pub struct TradeBackfill {
+    client: reqwest::Client,
+    url: String,
+    max_trades: u64,
+}
+
+impl TradeBackfill {
+    /// `market`'s `aggTrades` endpoint under `base_url`
+    pub fn new(market: BinanceMarket, base_url: &str, max_trades: u64) -> Self {
+        let path = match market {
+            BinanceMarket::Futures => "/fapi/v1/aggTrades",
+            BinanceMarket::Spot => "/api/v3/aggTrades",
+        };
+        Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(10)).build().expect("static client config"),
+            url: format!("{}{}", base_url.trim_end_matches('/'), path),
+            max_trades,
+        }
+    }
+
+    /// `None` unless `BINANCE_BACKFILL` is set
+    pub fn from_env(market: BinanceMarket) -> Result<Option<Self>> {
+        if !std::env::var("BINANCE_BACKFILL").map(|v| v == "true" || v == "1").unwrap_or(false) {
+            return Ok(None);
+        }
+        let max_trades = match std::env::var("BINANCE_BACKFILL_MAX_TRADES") {
+            Ok(v) => v.parse().context("Invalid BINANCE_BACKFILL_MAX_TRADES")?,
+            Err(_) => DEFAULT_MAX_TRADES,
+        };
+        let base_url = match market {
+            BinanceMarket::Futures => std::env::var("BINANCE_REST_URL").unwrap_or_else(|_| BINANCE_REST_URL.to_string()),
+            BinanceMarket::Spot => std::env::var("BINANCE_SPOT_REST_URL").unwrap_or_else(|_| BINANCE_SPOT_REST_URL.to_string()),
+        };
+        Ok(Some(Self::new(market, &base_url, max_trades)))
+    }
+
+    /// Whether `gap` is small enough to fetch
+    pub fn covers(&self, gap: &TradeGap) -> bool {
+        gap.missed() <= self.max_trades
+    }
+
+    /// The aggregate trades of `gap`'s missed trade ids, oldest first and
+    /// trimmed to the gap (see [`AggTrade::within`])
+    pub async fn fetch(&self, gap: &TradeGap) -> Result<Vec<AggTrade>> {
+        let mut window_start = gap.after_ms;
+        let mut next_id: Option<u64> = None;
+        let mut covered = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let window_end = (window_start + MAX_WINDOW_MS - 1).min(gap.before_ms);
+            let query = match next_id {
+                Some(id) => vec![("symbol", gap.symbol.clone()), ("fromId", id.to_string())],
+                None => vec![
+                    ("symbol", gap.symbol.clone()),
+                    ("startTime", window_start.to_string()),
+                    ("endTime", window_end.to_string()),
+                ],
+            };
+            let page = self.page(query).await?;
+            let Some(last) = page.last() else {
+                if next_id.is_some() || window_end >= gap.before_ms {
+                    break;
+                }
+                // Nothing in this hour of the gap, look in the next one
+                window_start = window_end + 1;
+                continue;
+            };
+            let reached_end = last.last_id + 1 >= gap.before_id;
+            // A short page by id is the newest; by time it may end at the window
+            let exhausted = page.len() < PAGE_LIMIT && (next_id.is_some() || window_end >= gap.before_ms);
+            next_id = Some(last.agg_id + 1);
+            covered.extend(page.iter().filter_map(|agg| agg.within(gap)));
+            if reached_end || exhausted {
+                break;
+            }
+        }
+        Ok(covered)
+    }
+
+    async fn page(&self, mut query: Vec<(&'static str, String)>) -> Result<Vec<AggTrade>> {
+        query.push(("limit", PAGE_LIMIT.to_string()));
+        self.client
+            .get(&self.url)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Invalid aggTrades response")
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use parking_lot::RwLock;
+use dashmap::DashMap;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BinanceTradeMessage {
@@ -41,60 +41,70 @@ impl From<BinanceTradeMessage> for Trade {
     }
 }
 
+/// Per-symbol buffer of trades received since the last processing pass
 #[derive(Clone)]
 pub struct TradeBuffer {
-    btc_trades: Arc<RwLock<Vec<Trade>>>,
-    eth_trades: Arc<RwLock<Vec<Trade>>>,
+    trades: Arc<DashMap<String, Vec<Trade>>>,
     max_buffer_size: usize,
 }
 
 impl TradeBuffer {
     pub fn new(max_buffer_size: usize) -> Self {
         Self {
-            btc_trades: Arc::new(RwLock::new(Vec::new())),
-            eth_trades: Arc::new(RwLock::new(Vec::new())),
+            trades: Arc::new(DashMap::new()),
             max_buffer_size,
         }
     }
 
     pub fn add_trade(&self, symbol: &str, trade: Trade) {
-        match symbol {
-            "BTCUSDT" => {
-                let mut buffer = self.btc_trades.write();
-                buffer.push(trade);
-                if buffer.len() > self.max_buffer_size {
-                    buffer.remove(0);
-                }
-            }
-            "ETHUSDT" => {
-                let mut buffer = self.eth_trades.write();
-                buffer.push(trade);
-                if buffer.len() > self.max_buffer_size {
-                    buffer.remove(0);
-                }
-            }
-            _ => {}
+        let mut buffer = self.trades.entry(symbol.to_string()).or_default();
+        buffer.push(trade);
+        if buffer.len() > self.max_buffer_size {
+            buffer.remove(0);
+        }
+    }
+
+    pub fn get_trades(&self, symbol: &str) -> Vec<Trade> {
+        self.trades.get(symbol).map(|t| t.clone()).unwrap_or_default()
+    }
+
+    pub fn clear_symbol(&self, symbol: &str) {
+        if let Some(mut buffer) = self.trades.get_mut(symbol) {
+            buffer.clear();
         }
     }
 
     pub fn get_btc_trades(&self) -> Vec<Trade> {
-        self.btc_trades.read().clone()
+        self.get_trades("BTCUSDT")
     }
 
     pub fn get_eth_trades(&self) -> Vec<Trade> {
-        self.eth_trades.read().clone()
+        self.get_trades("ETHUSDT")
     }
 
     pub fn clear(&self) {
-        self.btc_trades.write().clear();
-        self.eth_trades.write().clear();
+        self.trades.clear();
     }
     
     pub fn clear_btc(&self) {
-        self.btc_trades.write().clear();
+        self.clear_symbol("BTCUSDT");
     }
     
     pub fn clear_eth(&self) {
-        self.eth_trades.write().clear();
+        self.clear_symbol("ETHUSDT");
+    }
+
+    /// Buffered trades and allocated capacity per symbol; capacity that
+    /// keeps climbing while the counts stay low means bursts aren't released
+    pub fn to_json(&self) -> serde_json::Value {
+        let symbols: serde_json::Map<String, serde_json::Value> = self
+            .trades
+            .iter()
+            .map(|entry| {
+                let stats = serde_json::json!({ "trades": entry.len(), "capacity": entry.capacity() });
+                (entry.key().clone(), stats)
+            })
+            .collect();
+        serde_json::Value::Object(symbols)
     }
-}
\ No newline at end of file
+}
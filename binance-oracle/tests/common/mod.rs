@@ -0,0 +1,181 @@
+//! Scripted stand-in for Binance's combined trade stream.
+//!
+//! Each accepted connection plays the next [`Step`] script: trades, raw
+//! (malformed) frames, pauses, skipped trade ids, and a clean close or an
+//! abrupt disconnect at the end. Once the scripts run out, further
+//! connections are held open without sending anything. [`Step::Repeat`]
+//! plays a block forever, for long-running load.
+
+#![allow(dead_code)]
+
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+#[derive(Clone)]
+pub enum Step {
+    /// One trade in Binance's `<symbol>@trade` format
+    Trade { symbol: &'static str, price: f64, quantity: f64, trade_time_ms: u64 },
+    /// A frame sent as-is, e.g. invalid JSON or an unexpected schema
+    Raw(String),
+    /// Send nothing for a while
+    Pause(Duration),
+    /// Advance `symbol`'s trade ids without sending, as if trades were missed
+    SkipTrades { symbol: &'static str, trades: u64 },
+    /// Close frame, then end the connection
+    Close,
+    /// Drop the TCP connection without a close frame
+    Disconnect,
+    /// Play these steps over and over until the connection fails; trades
+    /// are re-stamped with the current time on every pass
+    Repeat(Vec<Step>),
+}
+
+impl Step {
+    /// Trade stamped `age_ms` before now
+    pub fn trade(symbol: &'static str, price: f64, quantity: f64, age_ms: u64) -> Self {
+        Step::Trade { symbol, price, quantity, trade_time_ms: now_ms().saturating_sub(age_ms) }
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+fn trade_message(symbol: &str, price: f64, quantity: f64, trade_time_ms: u64, trade_id: u64) -> String {
+    serde_json::json!({
+        "stream": format!("{}@trade", symbol.to_lowercase()),
+        "data": {
+            "e": "trade",
+            "E": trade_time_ms,
+            "s": symbol,
+            "t": trade_id,
+            "p": price.to_string(),
+            "q": quantity.to_string(),
+            "T": trade_time_ms,
+            "m": false,
+        }
+    })
+    .to_string()
+}
+
+pub struct MockBinanceServer {
+    pub url: String,
+    /// Request path of every connection, in order
+    pub connections: Arc<Mutex<Vec<String>>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockBinanceServer {
+    pub async fn start(scripts: Vec<Vec<Step>>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let scripts = Arc::new(Mutex::new(scripts.into_iter().collect::<VecDeque<_>>()));
+
+        let recorded = connections.clone();
+        let handle = tokio::spawn(async move {
+            let mut trade_ids = HashMap::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                let record_path = move |request: &Request, response: Response| {
+                    recorded.lock().push(request.uri().to_string());
+                    Ok(response)
+                };
+                let Ok(mut ws) = tokio_tungstenite::accept_hdr_async(stream, record_path).await else {
+                    continue;
+                };
+                let script = scripts.lock().pop_front();
+                let Some(script) = script else {
+                    // Out of scripts: keep the connection open and quiet
+                    tokio::spawn(async move { while ws.next().await.is_some() {} });
+                    continue;
+                };
+
+                'script: for step in script {
+                    match step {
+                        Step::Repeat(steps) => loop {
+                            for step in &steps {
+                                let step = match step {
+                                    Step::Trade { symbol, price, quantity, .. } => Step::trade(symbol, *price, *quantity, 0),
+                                    other => other.clone(),
+                                };
+                                if !play(&mut ws, step, &mut trade_ids).await {
+                                    break 'script;
+                                }
+                            }
+                        },
+                        step => {
+                            if !play(&mut ws, step, &mut trade_ids).await {
+                                break;
+                            }
+                        }
+                    }
+                }
+                drop(ws);
+            }
+        });
+
+        Self { url, connections, handle }
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.connections.lock().len()
+    }
+}
+
+/// Send one step; false once the connection should end
+async fn play<S>(ws: &mut tokio_tungstenite::WebSocketStream<S>, step: Step, trade_ids: &mut HashMap<&'static str, u64>) -> bool
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let sent = match step {
+        Step::Trade { symbol, price, quantity, trade_time_ms } => {
+            // Consecutive per symbol, like Binance's
+            let trade_id = trade_ids.entry(symbol).or_insert(0);
+            *trade_id += 1;
+            let text = trade_message(symbol, price, quantity, trade_time_ms, *trade_id);
+            ws.send(Message::Text(text)).await
+        }
+        Step::Raw(text) => ws.send(Message::Text(text)).await,
+        Step::SkipTrades { symbol, trades } => {
+            *trade_ids.entry(symbol).or_insert(0) += trades;
+            Ok(())
+        }
+        Step::Pause(duration) => {
+            tokio::time::sleep(duration).await;
+            Ok(())
+        }
+        Step::Close => {
+            let _ = ws.send(Message::Close(None)).await;
+            return false;
+        }
+        // Nested repeats never finish; treat them as the end of the block
+        Step::Disconnect | Step::Repeat(_) => return false,
+    };
+    sent.is_ok()
+}
+
+impl Drop for MockBinanceServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Poll `condition` until it holds or `timeout` passes
+pub async fn wait_until(timeout: Duration, condition: impl Fn() -> bool) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    condition()
+}
@@ -0,0 +1,151 @@
+//! Trade id gaps: detection across reconnects and backfill from a mock
+//! `aggTrades` endpoint
+
+mod common;
+
+use binance_oracle::websocket::{trade_gaps, BinanceWebSocketClient, TradeBackfill, TradeBuffer, TradeGap, TradeGaps};
+use common::{wait_until, MockBinanceServer, Step};
+use oracle_common::BinanceMarket;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+#[test]
+fn test_consecutive_ids_have_no_gap() {
+    let gaps = TradeGaps::new();
+    assert_eq!(gaps.observe("BTCUSDT", 1, 1_000), None);
+    assert_eq!(gaps.observe("BTCUSDT", 2, 1_001), None);
+    // Replays and other symbols don't count
+    assert_eq!(gaps.observe("BTCUSDT", 2, 1_001), None);
+    assert_eq!(gaps.observe("ETHUSDT", 7, 1_002), None);
+
+    let gap = gaps.observe("BTCUSDT", 6, 1_500).unwrap();
+    assert_eq!(gap, TradeGap { symbol: "BTCUSDT".into(), after_id: 2, after_ms: 1_001, before_id: 6, before_ms: 1_500 });
+    assert_eq!(gap.missed(), 3);
+    assert_eq!(gaps.missed("BTCUSDT"), 3);
+
+    let json = gaps.to_json();
+    assert_eq!(json["stale_trades"], 1);
+    assert_eq!(json["symbols"]["BTCUSDT"]["largest_gap"], 3);
+}
+
+#[tokio::test]
+async fn test_gap_across_a_reconnect_is_detected() {
+    let server = MockBinanceServer::start(vec![
+        vec![Step::trade("SOLUSDT", 150.0, 1.0, 0), Step::Disconnect],
+        vec![Step::SkipTrades { symbol: "SOLUSDT", trades: 4 }, Step::trade("SOLUSDT", 151.0, 1.0, 0)],
+    ])
+    .await;
+    let buffer = Arc::new(TradeBuffer::new(1000));
+    let client = BinanceWebSocketClient::new(vec!["SOLUSDT".to_string()], buffer.clone())
+        .with_base_url(server.url.clone())
+        .with_reconnect_delay(Duration::from_millis(50));
+    let client = tokio::spawn(async move {
+        let _ = client.run().await;
+    });
+
+    assert!(wait_until(WAIT, || buffer.get_trades("SOLUSDT").len() == 2).await);
+    assert_eq!(trade_gaps("binance").missed("SOLUSDT"), 4);
+    client.abort();
+}
+
+/// HTTP server answering every request with `body`; records request lines
+async fn mock_rest(body: String) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            let request = String::from_utf8(request).unwrap();
+            seen.lock().push(request.lines().next().unwrap().to_string());
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    (url, requests)
+}
+
+fn gap() -> TradeGap {
+    TradeGap { symbol: "BTCUSDT".into(), after_id: 100, after_ms: 1_000, before_id: 110, before_ms: 2_000 }
+}
+
+const AGG_TRADES: &str = r#"[
+    {"a":1,"p":"100.0","q":"1.0","f":95,"l":100,"T":1000,"m":false},
+    {"a":2,"p":"101.0","q":"2.0","f":101,"l":104,"T":1200,"m":true},
+    {"a":3,"p":"102.0","q":"1.5","f":105,"l":111,"T":1900,"m":false}
+]"#;
+
+#[tokio::test]
+async fn test_backfill_fetches_the_covering_agg_trades() {
+    let (url, requests) = mock_rest(AGG_TRADES.to_string()).await;
+    let backfill = TradeBackfill::new(BinanceMarket::Futures, &url, 10_000);
+
+    let aggs = backfill.fetch(&gap()).await.unwrap();
+    // The first ends at the last trade received; the third straddles the end
+    assert_eq!(aggs.iter().map(|agg| agg.agg_id).collect::<Vec<_>>(), [2, 3]);
+    // Only the straddling aggregate's missed trades (105..=109 of 105..=111) count
+    assert_eq!((aggs[1].first_id, aggs[1].last_id), (105, 109));
+    let quantity: f64 = aggs[1].quantity.parse().unwrap();
+    assert!((quantity - 1.5 * 5.0 / 7.0).abs() < 1e-9);
+    assert_eq!(aggs.iter().map(|agg| agg.trades()).sum::<u64>(), 9);
+
+    let requests = requests.lock();
+    assert_eq!(requests.len(), 1, "a short page is the last");
+    assert!(requests[0].starts_with("GET /fapi/v1/aggTrades?symbol=BTCUSDT&startTime=1000&endTime=2000&limit=1000 "));
+}
+
+#[tokio::test]
+async fn test_long_gaps_are_searched_an_hour_at_a_time() {
+    let (url, requests) = mock_rest("[]".to_string()).await;
+    let backfill = TradeBackfill::new(BinanceMarket::Futures, &url, 10_000);
+    let hour = 3_600_000;
+    let gap = TradeGap { symbol: "BTCUSDT".into(), after_id: 100, after_ms: 0, before_id: 110, before_ms: 3 * hour - 1 };
+
+    assert!(backfill.fetch(&gap).await.unwrap().is_empty());
+    let requests = requests.lock();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[1].contains(&format!("startTime={}&endTime={}&", hour, 2 * hour - 1)));
+}
+
+#[tokio::test]
+async fn test_large_gaps_are_not_backfilled() {
+    let backfill = TradeBackfill::new(BinanceMarket::Spot, "http://127.0.0.1:1", 5);
+    assert!(!backfill.covers(&gap()));
+    assert!(TradeBackfill::new(BinanceMarket::Spot, "http://127.0.0.1:1", 9).covers(&gap()));
+}
+
+#[tokio::test]
+async fn test_backfilled_trades_reach_the_buffer() {
+    let (rest_url, _) = mock_rest(r#"[{"a":9,"p":"40.5","q":"3.0","f":2,"l":3,"T":1000,"m":false}]"#.to_string()).await;
+    let server = MockBinanceServer::start(vec![vec![
+        Step::trade("LINKUSDT", 40.0, 1.0, 0),
+        Step::SkipTrades { symbol: "LINKUSDT", trades: 2 },
+        Step::trade("LINKUSDT", 41.0, 1.0, 0),
+    ]])
+    .await;
+    let buffer = Arc::new(TradeBuffer::new(1000));
+    let client = BinanceWebSocketClient::new(vec!["LINKUSDT".to_string()], buffer.clone())
+        .with_base_url(server.url.clone())
+        .with_backfill(Some(Arc::new(TradeBackfill::new(BinanceMarket::Futures, &rest_url, 10_000))));
+    let client = tokio::spawn(async move {
+        let _ = client.run().await;
+    });
+
+    assert!(wait_until(WAIT, || buffer.get_trades("LINKUSDT").len() == 3).await);
+    assert!(buffer.get_trades("LINKUSDT").iter().any(|trade| trade.price == 40.5));
+    assert_eq!(trade_gaps("binance").to_json()["symbols"]["LINKUSDT"]["backfilled_trades"], 2);
+    client.abort();
+}
@@ -0,0 +1,160 @@
+//! BinanceWebSocketClient, TradeBuffer and TwapCalculator against a
+//! scripted mock of Binance's trade stream
+
+mod common;
+
+use binance_oracle::twap::{min_trade_notional_from_env, TwapCalculator};
+use binance_oracle::websocket::{trade_buffer_key, BinanceWebSocketClient, TradeBuffer};
+use common::{wait_until, MockBinanceServer, Step};
+use oracle_common::{parse_quarantine, BinanceMarket};
+use std::sync::Arc;
+use std::time::Duration;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+fn start_client(server: &MockBinanceServer, symbols: &[&str]) -> (Arc<TradeBuffer>, tokio::task::JoinHandle<()>) {
+    let buffer = Arc::new(TradeBuffer::new(1000));
+    let client = BinanceWebSocketClient::new(symbols.iter().map(|s| s.to_string()).collect(), buffer.clone())
+        .with_base_url(server.url.clone())
+        .with_reconnect_delay(Duration::from_millis(50));
+    let handle = tokio::spawn(async move {
+        let _ = client.run().await;
+    });
+    (buffer, handle)
+}
+
+#[tokio::test]
+async fn test_trades_reach_the_buffer() {
+    let server = MockBinanceServer::start(vec![vec![
+        Step::trade("BTCUSDT", 65_000.5, 0.1, 0),
+        Step::trade("ETHUSDT", 3_200.0, 2.0, 0),
+        Step::trade("BTCUSDT", 65_001.0, 0.3, 0),
+    ]])
+    .await;
+    let (buffer, client) = start_client(&server, &["BTCUSDT", "ETHUSDT"]);
+
+    assert!(wait_until(WAIT, || buffer.get_btc_trades().len() == 2).await);
+    let btc = buffer.get_btc_trades();
+    assert_eq!(btc[0].price, 65_000.5);
+    assert_eq!(btc[1].quantity, 0.3);
+    assert_eq!(buffer.get_eth_trades().len(), 1);
+    assert_eq!(server.connections.lock()[0], "/stream?streams=btcusdt@trade/ethusdt@trade");
+    client.abort();
+}
+
+#[tokio::test]
+async fn test_malformed_messages_are_skipped() {
+    let server = MockBinanceServer::start(vec![vec![
+        Step::Raw("not json".to_string()),
+        Step::trade("BTCUSDT", 100.0, 1.0, 0),
+        Step::Raw(r#"{"stream":"btcusdt@trade","data":{"e":"trade","s":"BTCUSDT"}}"#.to_string()),
+        Step::Raw(r#"{"result":null,"id":1}"#.to_string()),
+        Step::Raw(r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","s":"BTCUSDT"}}"#.to_string()),
+        Step::trade("BTCUSDT", 101.0, 1.0, 0),
+    ]])
+    .await;
+    let (buffer, client) = start_client(&server, &["BTCUSDT"]);
+
+    assert!(wait_until(WAIT, || buffer.get_btc_trades().len() == 2).await);
+    assert_eq!(server.connection_count(), 1, "bad frames don't cost a reconnect");
+    // Invalid JSON and the incomplete trade are quarantined, the aggTrade counted
+    let quarantine = parse_quarantine("binance");
+    assert!(quarantine.rejected() >= 2);
+    assert!(quarantine.quarantined().iter().any(|message| message.payload == "not json"));
+    assert_eq!(quarantine.unknown_events().get("aggTrade"), Some(&1));
+    client.abort();
+}
+
+#[tokio::test]
+async fn test_reconnects_after_disconnect_and_close() {
+    let server = MockBinanceServer::start(vec![
+        vec![Step::trade("BTCUSDT", 100.0, 1.0, 0), Step::Disconnect],
+        vec![Step::trade("BTCUSDT", 101.0, 1.0, 0), Step::Close],
+        vec![Step::trade("BTCUSDT", 102.0, 1.0, 0)],
+    ])
+    .await;
+    let (buffer, client) = start_client(&server, &["BTCUSDT"]);
+
+    assert!(wait_until(WAIT, || buffer.get_btc_trades().len() == 3).await);
+    let prices: Vec<f64> = buffer.get_btc_trades().iter().map(|t| t.price).collect();
+    assert_eq!(prices, vec![100.0, 101.0, 102.0]);
+    assert_eq!(server.connection_count(), 3);
+    client.abort();
+}
+
+#[tokio::test]
+async fn test_twap_over_a_gap() {
+    // Two trades from before a 20s silence fall out of a 10s window
+    let server = MockBinanceServer::start(vec![vec![
+        Step::trade("BTCUSDT", 90.0, 1.0, 20_000),
+        Step::trade("BTCUSDT", 95.0, 1.0, 19_000),
+        Step::Pause(Duration::from_millis(50)),
+        Step::trade("BTCUSDT", 100.0, 1.0, 2_000),
+        Step::trade("BTCUSDT", 110.0, 3.0, 1_000),
+    ]])
+    .await;
+    let (buffer, client) = start_client(&server, &["BTCUSDT"]);
+    assert!(wait_until(WAIT, || buffer.get_btc_trades().len() == 4).await);
+
+    let calculator = TwapCalculator::new(Duration::from_secs(10));
+    let twap = calculator.add_trades_batch(buffer.get_btc_trades()).unwrap();
+    assert_eq!(twap.num_trades, 2);
+    assert!((twap.price - 107.5).abs() < 1e-9, "volume-weighted over the window: {}", twap.price);
+    assert!(calculator.window_fill() < 0.5, "the window only covers the last 2s");
+    client.abort();
+}
+
+#[tokio::test]
+async fn test_spot_trades_are_buffered_apart_from_futures() {
+    let server = MockBinanceServer::start(vec![vec![Step::trade("BTCUSDT", 64_990.0, 0.2, 0)]]).await;
+    let buffer = Arc::new(TradeBuffer::new(1000));
+    let client = BinanceWebSocketClient::new(vec!["BTCUSDT".to_string()], buffer.clone())
+        .with_market(BinanceMarket::Spot)
+        .with_base_url(server.url.clone());
+    let client = tokio::spawn(async move {
+        let _ = client.run().await;
+    });
+
+    let key = trade_buffer_key(BinanceMarket::Spot, "BTCUSDT");
+    assert_eq!(key, "spot:BTCUSDT");
+    assert!(wait_until(WAIT, || buffer.get_trades(&key).len() == 1).await);
+    assert_eq!(buffer.get_trades(&key)[0].price, 64_990.0);
+    assert!(buffer.get_btc_trades().is_empty(), "futures BTCUSDT is untouched");
+    client.abort();
+}
+
+#[tokio::test]
+async fn test_dust_trades_are_left_out_of_the_twap() {
+    let server = MockBinanceServer::start(vec![vec![
+        Step::trade("BTCUSDT", 65_000.0, 0.1, 0),
+        Step::trade("BTCUSDT", 66_000.0, 0.0001, 0),
+        Step::trade("BTCUSDT", 64_000.0, 0.001, 0),
+    ]])
+    .await;
+    let (buffer, client) = start_client(&server, &["BTCUSDT"]);
+    assert!(wait_until(WAIT, || buffer.get_btc_trades().len() == 3).await);
+
+    let calculator = TwapCalculator::new(Duration::from_secs(10)).with_min_notional(Some(100.0));
+    let twap = calculator.add_trades_batch(buffer.get_btc_trades()).unwrap();
+    assert_eq!(twap.num_trades, 1);
+    assert_eq!(twap.price, 65_000.0);
+
+    let dust = calculator.dust_json().unwrap();
+    assert_eq!(dust["trades"], 2);
+    assert!((dust["volume"].as_f64().unwrap() - 0.0011).abs() < 1e-12);
+    assert!((dust["notional"].as_f64().unwrap() - 70.6).abs() < 1e-9);
+    assert!(TwapCalculator::new(Duration::from_secs(10)).dust_json().is_none());
+    client.abort();
+}
+
+#[test]
+fn test_min_trade_notional_from_env_must_be_positive() {
+    std::env::set_var("BINANCE_MIN_TRADE_NOTIONAL", "250");
+    assert_eq!(min_trade_notional_from_env().unwrap(), Some(250.0));
+    for invalid in ["0", "-5", "NaN", "inf"] {
+        std::env::set_var("BINANCE_MIN_TRADE_NOTIONAL", invalid);
+        assert!(min_trade_notional_from_env().is_err(), "{}", invalid);
+    }
+    std::env::remove_var("BINANCE_MIN_TRADE_NOTIONAL");
+    assert_eq!(min_trade_notional_from_env().unwrap(), None);
+}
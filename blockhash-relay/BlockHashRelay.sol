@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+import "@openzeppelin/contracts/access/Ownable.sol";
+
+/**
+ * @title BlockHashRelay
+ * @notice Stores block hashes of other chains for lightweight cross-chain proofs
+ * @dev Relayers publish confirmed blocks at a fixed cadence. A relayed hash
+ *      can be overwritten when the source chain reorgs past the relayer's
+ *      confirmation depth; consumers needing finality should check
+ *      `latestBlock` is far enough ahead of the block they prove against.
+ */
+contract BlockHashRelay is Ownable {
+    // Source chain id => block number => block hash
+    mapping(uint256 => mapping(uint256 => bytes32)) public blockHash;
+
+    // Source chain id => highest relayed block number
+    mapping(uint256 => uint256) public latestBlock;
+
+    // Source chain id => block timestamp of the latest relay on this chain
+    mapping(uint256 => uint256) public lastUpdate;
+
+    // Authorized updaters (relayers)
+    mapping(address => bool) public authorizedUpdaters;
+
+    // Events
+    event BlockRelayed(uint256 indexed sourceChainId, uint256 indexed number, bytes32 blockHash, bytes32 parentHash);
+    event BlockHashCorrected(uint256 indexed sourceChainId, uint256 indexed number, bytes32 previous, bytes32 blockHash);
+    event UpdaterAuthorized(address indexed updater, bool authorized);
+
+    // Errors
+    error UnauthorizedUpdater(address updater);
+    error InvalidBlockHash();
+    error ParentMismatch(uint256 number, bytes32 stored, bytes32 parentHash);
+
+    modifier onlyAuthorized() {
+        if (!authorizedUpdaters[msg.sender] && msg.sender != owner()) {
+            revert UnauthorizedUpdater(msg.sender);
+        }
+        _;
+    }
+
+    constructor() Ownable(msg.sender) {
+        // Owner is automatically authorized
+        authorizedUpdaters[msg.sender] = true;
+    }
+
+    /**
+     * @notice Relays a source chain block, or corrects one replaced by a reorg
+     * @param sourceChainId Chain id of the source chain
+     * @param number Block number
+     * @param hash Block hash
+     * @param parentHash Parent block hash; must match the stored parent, if any
+     */
+    function relayBlock(uint256 sourceChainId, uint256 number, bytes32 hash, bytes32 parentHash) external onlyAuthorized {
+        if (hash == bytes32(0)) revert InvalidBlockHash();
+        if (number > 0) {
+            bytes32 storedParent = blockHash[sourceChainId][number - 1];
+            if (storedParent != bytes32(0) && storedParent != parentHash) {
+                revert ParentMismatch(number, storedParent, parentHash);
+            }
+        }
+
+        bytes32 previous = blockHash[sourceChainId][number];
+        blockHash[sourceChainId][number] = hash;
+        if (number > latestBlock[sourceChainId]) {
+            latestBlock[sourceChainId] = number;
+        }
+        lastUpdate[sourceChainId] = block.timestamp;
+
+        if (previous != bytes32(0) && previous != hash) {
+            emit BlockHashCorrected(sourceChainId, number, previous, hash);
+        }
+        emit BlockRelayed(sourceChainId, number, hash, parentHash);
+    }
+
+    /**
+     * @notice Checks if a source chain's relay is stale
+     * @param sourceChainId Chain id of the source chain
+     * @param maxAge Maximum age in seconds
+     * @return True if data is stale
+     */
+    function isStale(uint256 sourceChainId, uint256 maxAge) external view returns (bool) {
+        if (lastUpdate[sourceChainId] == 0) return true;
+        return block.timestamp > lastUpdate[sourceChainId] + maxAge;
+    }
+
+    /**
+     * @notice Authorizes or revokes an updater
+     * @param updater The address to authorize/revoke
+     * @param authorized Whether to authorize or revoke
+     */
+    function setAuthorizedUpdater(address updater, bool authorized) external onlyOwner {
+        authorizedUpdaters[updater] = authorized;
+        emit UpdaterAuthorized(updater, authorized);
+    }
+}
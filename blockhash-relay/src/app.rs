@@ -0,0 +1,203 @@
+use alloy::providers::ProviderBuilder;
+use alloy::sol;
+use anyhow::{bail, Context, Result};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, spawn_pause_watcher, submitter_for, DryRunOrchestrator, ErrorPolicy, EventBus, Heartbeat,
+    ReceiptVerifier, ReorgDetector, RuntimeMonitor, ShutdownCoordinator, StatusServer, UpdateHistory,
+};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::source::SourceChain;
+use crate::trigger::RelayTrigger;
+
+sol!(
+    #[sol(rpc)]
+    interface IBlockHashRelay {
+        function latestBlock(uint256 sourceChainId) external view returns (uint256);
+        function blockHash(uint256 sourceChainId, uint256 number) external view returns (bytes32);
+    }
+);
+
+fn env_u64(key: &str) -> Result<Option<u64>> {
+    match env::var(key) {
+        Ok(v) => Ok(Some(v.parse().with_context(|| format!("Invalid {}", key))?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Run the block hash relay until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `blockhash-relay` binary or `nonzu run blockhash-relay`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Block Hash Relay");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("blockhash-relay")?;
+
+    let chain = apply_sdk_defaults().await?;
+
+    let relay_address = Address::from_str(
+        &env::var("BLOCKHASH_RELAY_ADDRESS").context("BLOCKHASH_RELAY_ADDRESS must be set in .env")?,
+    )?;
+    info!("📝 Relay contract address: {}", relay_address);
+
+    let source_urls: Vec<String> = env::var("RELAY_SOURCE_RPC_URLS")
+        .context("RELAY_SOURCE_RPC_URLS must be set (source chain RPCs, comma-separated)")?
+        .split(',')
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+    if source_urls.is_empty() {
+        bail!("RELAY_SOURCE_RPC_URLS lists no RPC URLs");
+    }
+
+    let confirmations = env_u64("RELAY_CONFIRMATIONS")?.unwrap_or(2);
+    let reorg_depth = env_u64("RELAY_REORG_DEPTH")?.unwrap_or(64);
+    if reorg_depth <= confirmations {
+        bail!("RELAY_REORG_DEPTH ({}) must be greater than RELAY_CONFIRMATIONS ({})", reorg_depth, confirmations);
+    }
+    let source = Arc::new(SourceChain::new(source_urls, confirmations, reorg_depth));
+
+    // Publishing another chain's hashes under the wrong id would be worse than not publishing
+    let source_chain_id = source.chain_id().await.context("Failed to read the source chain id")?;
+    if let Some(expected) = env_u64("RELAY_SOURCE_CHAIN_ID")? {
+        if expected != source_chain_id {
+            bail!("Source RPC serves chain {} but RELAY_SOURCE_CHAIN_ID is {}", source_chain_id, expected);
+        }
+    }
+    info!("⛓️ Relaying chain {} ({} confirmations, reorgs watched {} blocks deep)", source_chain_id, confirmations, reorg_depth);
+
+    let private_keys = load_private_keys(&["RELAY_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+
+    let relay_interval = Duration::from_millis(env_u64("RELAY_INTERVAL_MS")?.unwrap_or(12_000).max(100));
+    let poll_interval = Duration::from_millis(env_u64("RELAY_POLL_MS")?.unwrap_or(2_000).max(100));
+    shutdown.register("source chain poller", source.spawn(poll_interval));
+
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    shutdown.register("pause watcher", spawn_pause_watcher("blockhash-relay", error_control.clone()));
+
+    // Last relayed block, so a restart neither resends it nor misses its reorg
+    let relay = IBlockHashRelay::new(relay_address, ProviderBuilder::new().on_http(chain.rpc_url().parse()?));
+    let chain_id = U256::from(source_chain_id);
+    let last_relayed = match relay.latestBlock(chain_id).call().await {
+        Ok(latest) if latest._0.is_zero() => None,
+        Ok(latest) => {
+            let number: u64 = latest._0.saturating_to();
+            match relay.blockHash(chain_id, latest._0).call().await {
+                Ok(hash) => source.watch(number, hash._0),
+                Err(e) => warn!("⚠️ Could not read the hash of relayed block {}: {}", number, e),
+            }
+            Some(number)
+        }
+        Err(e) => {
+            warn!("⚠️ Could not read the last relayed block: {}", e);
+            None
+        }
+    };
+    info!("⛓️ Last relayed block: {:?}", last_relayed);
+
+    let trigger = Arc::new(
+        RelayTrigger::new(relay_address, source_chain_id, source, relay_interval, error_control.clone())
+            .with_last_relayed(last_relayed)
+            .with_max_lag_blocks(env_u64("RELAY_MAX_LAG_BLOCKS")?)
+            .with_receipt_verifier(receipt_verifier)
+            .with_reorg_detector(reorg_detector),
+    );
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let server = StatusServer::new(addr)
+            .with_status(trigger.clone())
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+
+    // Corrections should go out as soon as the poller spots them
+    let check_interval = Duration::from_millis(250).min(relay_interval);
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(
+            vec![trigger as Arc<dyn TxTrigger>],
+            &private_keys,
+            &chain,
+            check_interval,
+        ).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down relay (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    // One relay is in flight at a time; extra workers only spread keys
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        vec![trigger as Arc<dyn TxTrigger>],
+        private_keys.into_sdk(),
+        worker_count,
+        check_interval,
+        oracle_error_handler_config(),
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+
+    let handle = orchestrator.run().await;
+    info!("✅ Block Hash Relay is running! Press Ctrl+C to stop.");
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down relay...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+
+    info!("👋 Relay shutdown complete");
+    Ok(())
+}
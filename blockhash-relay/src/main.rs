@@ -0,0 +1,16 @@
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    oracle_common::install_crypto_provider();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+        )
+        .init();
+
+    dotenv::dotenv().ok();
+
+    // Runtime tuning (TOKIO_*) comes from the environment, so build it by hand
+    oracle_common::runtime_from_env()?.block_on(blockhash_relay::run())
+}
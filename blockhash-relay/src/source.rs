@@ -0,0 +1,167 @@
+//! Source chain follower.
+//!
+//! Polls the source chain's head over its own RPC endpoints (tried in order)
+//! and keeps the block `confirmations` behind it as the next one to relay.
+//! Relayed blocks stay watched for `reorg_depth` blocks: if the canonical
+//! hash at a watched height changes, the block is queued as a correction.
+
+use alloy::primitives::{B256, U256};
+use anyhow::{anyhow, Context, Result};
+use oracle_common::{fetch_block, BlockRef, RpcEndpoints};
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Block `confirmations` behind `head`, if the chain is that long
+pub fn confirmed_number(head: u64, confirmations: u64) -> Option<u64> {
+    head.checked_sub(confirmations)
+}
+
+#[derive(Default)]
+struct SourceState {
+    head: Option<u64>,
+    /// Newest block with enough confirmations
+    confirmed: Option<BlockRef>,
+    /// Relayed hashes still within the reorg window
+    watched: BTreeMap<u64, B256>,
+    /// Watched heights whose canonical block changed, with the new block
+    corrections: BTreeMap<u64, BlockRef>,
+    reorgs: u64,
+}
+
+pub struct SourceChain {
+    rpc: RpcEndpoints,
+    confirmations: u64,
+    reorg_depth: u64,
+    state: RwLock<SourceState>,
+}
+
+impl SourceChain {
+    pub fn new(rpc_urls: Vec<String>, confirmations: u64, reorg_depth: u64) -> Self {
+        Self {
+            rpc: RpcEndpoints::new(rpc_urls),
+            confirmations,
+            reorg_depth,
+            state: RwLock::new(SourceState::default()),
+        }
+    }
+
+    /// `eth_chainId` of the source chain
+    pub async fn chain_id(&self) -> Result<u64> {
+        let id = self.rpc.call("eth_chainId", json!([])).await.map_err(|e| anyhow!("{:?}", e))?;
+        let id = id.as_str().context("eth_chainId returned no string")?;
+        Ok(U256::from_str_radix(id.trim_start_matches("0x"), 16)?.saturating_to())
+    }
+
+    pub fn head(&self) -> Option<u64> {
+        self.state.read().head
+    }
+
+    /// Next block to relay
+    pub fn confirmed(&self) -> Option<BlockRef> {
+        self.state.read().confirmed
+    }
+
+    /// Lowest block whose relayed hash was replaced by a reorg
+    pub fn next_correction(&self) -> Option<BlockRef> {
+        self.state.read().corrections.values().next().copied()
+    }
+
+    /// Track a block relayed (and confirmed) on the destination
+    pub fn watch(&self, number: u64, hash: B256) {
+        let mut state = self.state.write();
+        state.watched.insert(number, hash);
+        if state.corrections.get(&number).is_some_and(|c| c.hash == hash) {
+            state.corrections.remove(&number);
+        }
+    }
+
+    /// Record one poll: the head, the confirmed block and the canonical
+    /// blocks at watched heights
+    pub fn apply_poll(&self, head: u64, confirmed: Option<BlockRef>, canonical: Vec<BlockRef>) {
+        let mut state = self.state.write();
+        state.head = Some(head);
+        if confirmed.is_some() {
+            state.confirmed = confirmed;
+        }
+
+        for block in canonical {
+            match state.watched.get(&block.number) {
+                Some(relayed) if *relayed != block.hash => {
+                    if !state.corrections.contains_key(&block.number) {
+                        state.reorgs += 1;
+                        warn!(
+                            "🔀 Source block {} was reorged after relaying ({} -> {}), correcting",
+                            block.number, relayed, block.hash
+                        );
+                    }
+                    state.corrections.insert(block.number, block);
+                }
+                _ => {
+                    state.corrections.remove(&block.number);
+                }
+            }
+        }
+
+        // Past the reorg window nothing changes any more
+        let oldest = head.saturating_sub(self.reorg_depth);
+        state.watched = state.watched.split_off(&oldest);
+        state.corrections = state.corrections.split_off(&oldest);
+    }
+
+    /// Watched heights still inside the reorg window
+    fn watched_numbers(&self) -> Vec<u64> {
+        self.state.read().watched.keys().copied().collect()
+    }
+
+    async fn poll(&self) -> Result<()> {
+        let head = fetch_block(&self.rpc, "latest").await?;
+        let confirmed = match confirmed_number(head.number, self.confirmations) {
+            Some(number) if number == head.number => Some(head),
+            Some(number) => Some(fetch_block(&self.rpc, &format!("{:#x}", number)).await?),
+            None => None,
+        };
+
+        let mut canonical = Vec::new();
+        for number in self.watched_numbers() {
+            if number + self.reorg_depth < head.number || number > head.number {
+                continue;
+            }
+            canonical.push(fetch_block(&self.rpc, &format!("{:#x}", number)).await?);
+        }
+
+        debug!("Source head {}, confirmed {:?}", head.number, confirmed.map(|b| b.number));
+        self.apply_poll(head.number, confirmed, canonical);
+        Ok(())
+    }
+
+    /// Poll the source chain every `interval` in the background
+    pub fn spawn(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let source = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = source.poll().await {
+                    warn!("⚠️ Failed to poll the source chain: {}", e);
+                }
+            }
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.read();
+        json!({
+            "head": state.head,
+            "confirmations": self.confirmations,
+            "confirmed": state.confirmed.map(|b| json!({ "number": b.number, "hash": b.hash.to_string() })),
+            "watched_blocks": state.watched.len(),
+            "pending_corrections": state.corrections.keys().collect::<Vec<_>>(),
+            "reorgs": state.reorgs,
+        })
+    }
+}
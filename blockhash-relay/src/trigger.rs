@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    encode_relay_block, function_selector, publish_event, trace_completed, trace_fired, BlockRef, CircuitBreaker,
+    OracleEvent, OracleStats, PendingQueue, PreciseTimer, ReceiptVerifier, ReorgDetector, SharedStats, StatusSource,
+};
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info, warn};
+
+use crate::source::SourceChain;
+
+/// The relay in flight; one at a time so corrections land in order
+#[derive(Debug, Clone, Copy)]
+struct InFlight {
+    block: BlockRef,
+    correction: bool,
+}
+
+/// Relays the source chain's confirmed block every interval, and corrects
+/// relayed blocks that were reorged
+pub struct RelayTrigger {
+    relay_address: Address,
+    source_chain_id: u64,
+    label: String,
+    source: Arc<SourceChain>,
+    timer: Arc<RwLock<PreciseTimer>>,
+    /// Highest block relayed and confirmed on the destination
+    last_relayed: RwLock<Option<u64>>,
+    in_flight: RwLock<Option<InFlight>>,
+    /// Warn once the relay falls this many blocks behind the source head
+    max_lag_blocks: Option<u64>,
+    lagging: AtomicBool,
+    last_drift_ms: RwLock<i64>,
+    selector: [u8; 4],
+    error_control: Arc<OrchestratorErrorControl>,
+    stats: SharedStats,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    /// Set by the reorg detector when a relay was dropped on the destination
+    republish: Arc<AtomicBool>,
+}
+
+impl RelayTrigger {
+    pub fn new(
+        relay_address: Address,
+        source_chain_id: u64,
+        source: Arc<SourceChain>,
+        interval: Duration,
+        error_control: Arc<OrchestratorErrorControl>,
+    ) -> Self {
+        let label = format!("BLOCKHASH-{}", source_chain_id);
+        let pending = PendingQueue::for_interval(&label, interval);
+        Self {
+            relay_address,
+            source_chain_id,
+            breaker: CircuitBreaker::from_env(label.clone()),
+            label,
+            source,
+            timer: Arc::new(RwLock::new(PreciseTimer::new(interval.as_millis() as u64))),
+            last_relayed: RwLock::new(None),
+            in_flight: RwLock::new(None),
+            max_lag_blocks: None,
+            lagging: AtomicBool::new(false),
+            last_drift_ms: RwLock::new(0),
+            selector: function_selector("relayBlock(uint256,uint256,bytes32,bytes32)"),
+            error_control,
+            stats: OracleStats::shared(),
+            pending,
+            receipt_verifier: None,
+            reorg_detector: None,
+            republish: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Highest block already on the contract (read at startup)
+    pub fn with_last_relayed(self, number: Option<u64>) -> Self {
+        *self.last_relayed.write() = number;
+        self
+    }
+
+    /// Warn when the relay is more than `blocks` behind the source head
+    pub fn with_max_lag_blocks(mut self, blocks: Option<u64>) -> Self {
+        self.max_lag_blocks = blocks;
+        self
+    }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+
+    /// Source head minus the last relayed block
+    pub fn lag_blocks(&self) -> Option<u64> {
+        Some(self.source.head()?.saturating_sub((*self.last_relayed.read())?))
+    }
+
+    fn check_lag(&self) {
+        let (Some(max), Some(lag)) = (self.max_lag_blocks, self.lag_blocks()) else {
+            return;
+        };
+        let lagging = lag > max;
+        if self.lagging.swap(lagging, Ordering::Relaxed) != lagging {
+            if lagging {
+                warn!("🐢 {} relay is {} blocks behind the source head (max {})", self.label, lag, max);
+            } else {
+                info!("✅ {} relay caught up ({} blocks behind)", self.label, lag);
+            }
+        }
+    }
+
+    /// Block to send next: a pending correction, else a newer confirmed block when due
+    fn next_block(&self) -> Option<InFlight> {
+        if let Some(block) = self.source.next_correction() {
+            return Some(InFlight { block, correction: true });
+        }
+
+        let block = self.source.confirmed()?;
+        if self.last_relayed.read().is_some_and(|last| block.number <= last) {
+            return None;
+        }
+        if !self.timer.read().is_due() {
+            return None;
+        }
+        if let Some((target_time, actual_time)) = self.timer.write().should_tick() {
+            *self.last_drift_ms.write() = actual_time as i64 - target_time as i64;
+            return Some(InFlight { block, correction: false });
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl TxTrigger for RelayTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let started = SystemTime::now();
+        if self.error_control.is_worker_pool_paused().await {
+            debug!("Worker pool paused, skipping {}", self.label);
+            return Ok(None);
+        }
+
+        // A reorg on the destination dropped the last relay: relay the
+        // confirmed block at the next tick even if it is not newer
+        if self.republish.swap(false, Ordering::Relaxed) {
+            *self.last_relayed.write() = None;
+        }
+
+        self.check_lag();
+        if self.in_flight.read().is_some() {
+            return Ok(None);
+        }
+        let Some(update) = self.next_block() else {
+            return Ok(None);
+        };
+
+        if !self.breaker.try_acquire() {
+            debug!("Circuit breaker open, skipping {}", self.label);
+            return Ok(None);
+        }
+
+        let block = update.block;
+        let call_data = encode_relay_block(self.selector, self.source_chain_id, block.number, block.hash, block.parent);
+        *self.in_flight.write() = Some(update);
+        self.stats.write().record_trigger();
+
+        if update.correction {
+            info!("🔀 {} correcting block {} to {}", self.label, block.number, block.hash);
+        } else {
+            info!("🚀 {} relaying block {}: {}", self.label, block.number, block.hash);
+        }
+
+        let tx_request = TxRequest::new(self.relay_address, call_data.clone())
+            .with_gas_limit(U256::from(120_000))
+            .with_priority(TxPriority::High)
+            .with_metadata("type", if update.correction { "blockhash_correction" } else { "blockhash_relay" })
+            .with_metadata("source_chain_id", self.source_chain_id.to_string())
+            .with_metadata("block_number", block.number.to_string())
+            .with_metadata("block_hash", block.hash.to_string());
+
+        let admitted = self.pending.admit(tx_request).map(|(tx_request, _)| tx_request);
+        if admitted.is_some() {
+            trace_fired(&self.label, &call_data, started);
+        } else {
+            *self.in_flight.write() = None;
+        }
+        Ok(admitted)
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        self.pending.complete();
+        self.breaker.record(success);
+        // A failed relay is retried with the newest confirmed block
+        let update = self.in_flight.write().take();
+        let drift_ms = match update {
+            Some(InFlight { correction: false, .. }) => *self.last_drift_ms.read(),
+            _ => 0,
+        };
+        publish_event(OracleEvent::completed(
+            &self.label,
+            success,
+            receipt,
+            update.map(|u| u.block.number as f64),
+            latency,
+            drift_ms,
+        ));
+
+        if success {
+            if let Some(update) = update {
+                self.source.watch(update.block.number, update.block.hash);
+                let mut last = self.last_relayed.write();
+                *last = (*last).max(Some(update.block.number));
+            }
+            self.stats.write().record_success(drift_ms, receipt.map(|r| r.gas_used), latency);
+            if let Some(receipt) = receipt {
+                info!("✅ {} relayed - tx: {}, block: {}", self.label, receipt.transaction_hash, receipt.block_number);
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(&self.label, receipt, &self.stats);
+                }
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(&self.label, receipt, Some(&self.republish));
+                }
+            }
+        } else {
+            self.stats.write().record_failure();
+            error!("❌ {} relay failed", self.label);
+        }
+        trace_completed(&self.label, receipt, success, started);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: format!("RelayTrigger[{}]", self.source_chain_id),
+            description: format!("Relays chain {} block hashes to {}", self.source_chain_id, self.relay_address),
+            trigger_type: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for RelayTrigger {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "blockhash-relay",
+            "source_chain_id": self.source_chain_id,
+            "source": self.source.to_json(),
+            "last_relayed": *self.last_relayed.read(),
+            "lag_blocks": self.lag_blocks(),
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
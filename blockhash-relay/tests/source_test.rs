@@ -0,0 +1,70 @@
+//! Confirmation depth and reorg corrections of relayed blocks
+
+use alloy::primitives::B256;
+use blockhash_relay::source::{confirmed_number, SourceChain};
+use oracle_common::BlockRef;
+
+fn block(number: u64, fork: u8) -> BlockRef {
+    BlockRef {
+        number,
+        hash: B256::repeat_byte(fork.wrapping_add(number as u8)),
+        parent: B256::repeat_byte(fork.wrapping_add(number as u8).wrapping_sub(1)),
+    }
+}
+
+fn source() -> SourceChain {
+    SourceChain::new(vec!["http://127.0.0.1:1".to_string()], 2, 10)
+}
+
+#[test]
+fn test_confirmed_number() {
+    assert_eq!(confirmed_number(100, 2), Some(98));
+    assert_eq!(confirmed_number(100, 0), Some(100));
+    assert_eq!(confirmed_number(1, 2), None);
+}
+
+#[test]
+fn test_unchanged_blocks_need_no_correction() {
+    let source = source();
+    source.watch(98, block(98, 0).hash);
+    source.apply_poll(100, Some(block(98, 0)), vec![block(98, 0)]);
+    assert_eq!(source.head(), Some(100));
+    assert_eq!(source.confirmed(), Some(block(98, 0)));
+    assert_eq!(source.next_correction(), None);
+}
+
+#[test]
+fn test_reorged_block_is_corrected_lowest_first() {
+    let source = source();
+    source.watch(95, block(95, 0).hash);
+    source.watch(98, block(98, 0).hash);
+    source.apply_poll(101, Some(block(99, 100)), vec![block(95, 100), block(98, 100)]);
+    assert_eq!(source.next_correction(), Some(block(95, 100)));
+
+    // Relaying the new hash clears the correction
+    source.watch(95, block(95, 100).hash);
+    assert_eq!(source.next_correction(), Some(block(98, 100)));
+    source.watch(98, block(98, 100).hash);
+    assert_eq!(source.next_correction(), None);
+}
+
+#[test]
+fn test_reorg_back_to_relayed_hash_cancels_correction() {
+    let source = source();
+    source.watch(98, block(98, 0).hash);
+    source.apply_poll(100, None, vec![block(98, 100)]);
+    assert!(source.next_correction().is_some());
+    source.apply_poll(100, None, vec![block(98, 0)]);
+    assert_eq!(source.next_correction(), None);
+}
+
+#[test]
+fn test_blocks_past_the_reorg_window_are_forgotten() {
+    let source = source();
+    source.watch(50, block(50, 0).hash);
+    source.apply_poll(55, None, vec![block(50, 100)]);
+    assert!(source.next_correction().is_some());
+    // 10 blocks deep: no longer watched or corrected
+    source.apply_poll(61, None, Vec::new());
+    assert_eq!(source.next_correction(), None);
+}
@@ -0,0 +1,187 @@
+use alloy::providers::ProviderBuilder;
+use alloy::sol;
+use anyhow::{Context, Result};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, spawn_pause_watcher, submitter_for, ChainlinkReference, DryRunOrchestrator, ErrorPolicy,
+    EventBus, Heartbeat, ReceiptVerifier, ReorgDetector, RuntimeMonitor, ShutdownCoordinator, StatusServer, StatusSource,
+    UpdateHistory,
+};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::mirror_feeds_from_env;
+use crate::trigger::MirrorTrigger;
+
+sol!(
+    #[sol(rpc)]
+    interface IPriceOracleV2Rounds {
+        function rounds(string calldata feedId) external view returns (uint80 roundId, uint256 updatedAt);
+    }
+);
+
+/// `/status` for every mirrored feed
+struct MirrorStatus {
+    triggers: Vec<Arc<MirrorTrigger>>,
+}
+
+impl StatusSource for MirrorStatus {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "chainlink-mirror",
+            "feeds": self.triggers.iter().map(|t| t.status()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn env_u64(key: &str) -> Result<Option<u64>> {
+    match env::var(key) {
+        Ok(v) => Ok(Some(v.parse().with_context(|| format!("Invalid {}", key))?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Run the Chainlink mirror until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `chainlink-mirror` binary or `nonzu run chainlink-mirror`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Chainlink Mirror");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("chainlink-mirror")?;
+
+    let chain = apply_sdk_defaults().await?;
+
+    let oracle_address = Address::from_str(
+        &env::var("PRICE_ORACLE_V2_ADDRESS").context("PRICE_ORACLE_V2_ADDRESS must be set in .env")?,
+    )?;
+    info!("📝 Oracle contract address: {}", oracle_address);
+
+    let private_keys = load_private_keys(&["MIRROR_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+
+    let poll_interval = Duration::from_millis(env_u64("MIRROR_POLL_MS")?.unwrap_or(5_000).max(100));
+    let max_age = env_u64("MIRROR_MAX_AGE_SECS")?.map(Duration::from_secs);
+
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    shutdown.register("pause watcher", spawn_pause_watcher("chainlink-mirror", error_control.clone()));
+
+    // Rounds already mirrored, so a restart doesn't send a round the contract rejects
+    let oracle = IPriceOracleV2Rounds::new(oracle_address, ProviderBuilder::new().on_http(chain.rpc_url().parse()?));
+
+    let mut triggers = Vec::new();
+    for feed in mirror_feeds_from_env()? {
+        let reference = Arc::new(ChainlinkReference::new(&feed.rpc_url()?, feed.aggregator).await.with_context(|| {
+            format!("Failed to read aggregator {} on {} for {}", feed.aggregator, feed.chain, feed.id)
+        })?);
+        let confirmed_round = match oracle.rounds(feed.id.clone()).call().await {
+            Ok(round) if round.roundId.is_zero() => None,
+            Ok(round) => Some(round.roundId.to::<u128>()),
+            Err(e) => {
+                warn!("⚠️ Could not read the last mirrored round of {}: {}", feed.id, e);
+                None
+            }
+        };
+        info!(
+            "🔗 {} <- Chainlink {} on {} ({} decimals, last mirrored round {:?})",
+            feed.id, feed.aggregator, feed.chain, reference.decimals(), confirmed_round
+        );
+
+        let trigger = MirrorTrigger::new(feed, oracle_address, reference, error_control.clone())
+            .with_max_age(max_age)
+            .with_confirmed_round(confirmed_round)
+            .with_receipt_verifier(receipt_verifier.clone())
+            .with_reorg_detector(reorg_detector.clone());
+        shutdown.register("aggregator poller", trigger.spawn_poller(poll_interval));
+        triggers.push(Arc::new(trigger));
+    }
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let server = StatusServer::new(addr)
+            .with_status(Arc::new(MirrorStatus { triggers: triggers.clone() }))
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+
+    // New rounds are picked up within a poll; checking faster only burns CPU
+    let check_interval = Duration::from_millis(250).min(poll_interval);
+    let triggers: Vec<Arc<dyn TxTrigger>> = triggers.into_iter().map(|t| t as Arc<dyn TxTrigger>).collect();
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(triggers, &private_keys, &chain, check_interval).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down mirror (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        triggers,
+        private_keys.into_sdk(),
+        worker_count,
+        check_interval,
+        oracle_error_handler_config(),
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+
+    let handle = orchestrator.run().await;
+    info!("✅ Chainlink Mirror is running! Press Ctrl+C to stop.");
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down mirror...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+
+    info!("👋 Mirror shutdown complete");
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Aggregators to mirror.
+//!
+//! `MIRROR_FEEDS` lists comma-separated `feed_id=chain:aggregator` entries;
+//! each chain's RPC comes from `MIRROR_RPC_<CHAIN>` (upper-cased, `-` as `_`):
+//!
+//! ```text
+//! MIRROR_FEEDS=BTCUSD=ethereum:0xF4030086522a5bEEa4988F8cA5B36dbC97BeE88c,ETHUSD=arbitrum:0x639Fe6ab55C921f74e7fac1ee960C0B6293ba612
+//! MIRROR_RPC_ETHEREUM=https://eth.llamarpc.com
+//! MIRROR_RPC_ARBITRUM=https://arb1.arbitrum.io/rpc
+//! ```
+
+use alloy::primitives::Address;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone)]
+pub struct MirrorFeed {
+    /// Feed id on the RISE oracle contract
+    pub id: String,
+    /// Source chain name, e.g. `ethereum`
+    pub chain: String,
+    pub aggregator: Address,
+}
+
+impl MirrorFeed {
+    /// Parse one `feed_id=chain:aggregator` entry
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (id, rest) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid mirror feed '{}' (expected feed_id=chain:aggregator)", spec))?;
+        let (chain, aggregator) = rest
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid mirror feed '{}' (expected feed_id=chain:aggregator)", spec))?;
+        let (id, chain) = (id.trim(), chain.trim());
+        if id.is_empty() || chain.is_empty() {
+            bail!("Mirror feed '{}' has an empty feed id or chain", spec);
+        }
+        let aggregator = aggregator
+            .trim()
+            .parse::<Address>()
+            .map_err(|e| anyhow!("Invalid aggregator in mirror feed '{}': {}", spec, e))?;
+
+        Ok(Self { id: id.to_string(), chain: chain.to_lowercase(), aggregator })
+    }
+
+    /// `MIRROR_RPC_<CHAIN>`, the source chain's RPC URL
+    pub fn rpc_env_key(&self) -> String {
+        format!("MIRROR_RPC_{}", self.chain.to_uppercase().replace('-', "_"))
+    }
+
+    pub fn rpc_url(&self) -> Result<String> {
+        let key = self.rpc_env_key();
+        std::env::var(&key).with_context(|| format!("{} must be set to mirror {} from {}", key, self.id, self.chain))
+    }
+}
+
+/// Feeds from `MIRROR_FEEDS`
+pub fn mirror_feeds_from_env() -> Result<Vec<MirrorFeed>> {
+    let specs = std::env::var("MIRROR_FEEDS").context("MIRROR_FEEDS must be set (feed_id=chain:aggregator,...)")?;
+    let feeds = specs
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(MirrorFeed::parse)
+        .collect::<Result<Vec<_>>>()?;
+    if feeds.is_empty() {
+        bail!("MIRROR_FEEDS lists no feeds");
+    }
+
+    let mut ids = HashSet::new();
+    for feed in &feeds {
+        if !ids.insert(feed.id.as_str()) {
+            bail!("Mirror feed '{}' is listed twice", feed.id);
+        }
+    }
+    Ok(feeds)
+}
@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    encode_update_price_with_round, function_selector, publish_event, trace_completed, trace_fired, ChainlinkReference,
+    ChainlinkRound, CircuitBreaker, OracleEvent, OracleStats, PendingQueue, ReceiptVerifier, ReorgDetector, SharedStats,
+    StatusSource,
+};
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::config::MirrorFeed;
+
+/// Republishes one Chainlink aggregator's answer each time its round advances
+pub struct MirrorTrigger {
+    feed: MirrorFeed,
+    oracle_address: Address,
+    reference: Arc<ChainlinkReference>,
+    /// Latest round read from the source chain
+    latest: Arc<RwLock<Option<ChainlinkRound>>>,
+    /// Rounds older than this (upstream stopped updating) are not mirrored
+    max_age: Option<Duration>,
+    /// Round of the update in flight, if any
+    fired_round: RwLock<Option<ChainlinkRound>>,
+    /// Last round confirmed on RISE
+    confirmed_round: RwLock<Option<u128>>,
+    selector: [u8; 4],
+    error_control: Arc<OrchestratorErrorControl>,
+    stats: SharedStats,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    /// Set by the reorg detector when a mirrored round was dropped
+    republish: Arc<AtomicBool>,
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+impl MirrorTrigger {
+    pub fn new(
+        feed: MirrorFeed,
+        oracle_address: Address,
+        reference: Arc<ChainlinkReference>,
+        error_control: Arc<OrchestratorErrorControl>,
+    ) -> Self {
+        Self {
+            breaker: CircuitBreaker::from_env(feed.id.clone()),
+            feed,
+            oracle_address,
+            reference,
+            latest: Arc::new(RwLock::new(None)),
+            max_age: None,
+            fired_round: RwLock::new(None),
+            confirmed_round: RwLock::new(None),
+            selector: function_selector("updatePriceWithRound(string,uint256,uint80,uint256)"),
+            error_control,
+            stats: OracleStats::shared(),
+            pending: PendingQueue::from_env(),
+            receipt_verifier: None,
+            reorg_detector: None,
+            republish: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Skip rounds whose `updatedAt` is older than `max_age`
+    pub fn with_max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Round already on the contract (read at startup), so it isn't sent again
+    pub fn with_confirmed_round(mut self, round_id: Option<u128>) -> Self {
+        self.confirmed_round = RwLock::new(round_id);
+        self
+    }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+
+    pub fn feed_id(&self) -> &str {
+        &self.feed.id
+    }
+
+    /// Read the aggregator every `interval` in the background
+    pub fn spawn_poller(&self, interval: Duration) -> JoinHandle<()> {
+        let reference = self.reference.clone();
+        let latest = self.latest.clone();
+        let feed_id = self.feed.id.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match reference.latest_round().await {
+                    Ok(round) => {
+                        if latest.read().map(|r| r.round_id) != Some(round.round_id) {
+                            debug!("🔗 {} round {}: {}", feed_id, round.round_id, round.answer);
+                        }
+                        *latest.write() = Some(round);
+                    }
+                    Err(e) => warn!("⚠️ Failed to read {} aggregator {}: {}", feed_id, reference.address(), e),
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl TxTrigger for MirrorTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let started = SystemTime::now();
+        if self.error_control.is_worker_pool_paused().await {
+            debug!("Worker pool paused, skipping {}", self.feed.id);
+            return Ok(None);
+        }
+
+        // A reorg dropped the last mirrored round: the contract may accept it again
+        if self.republish.swap(false, Ordering::Relaxed) {
+            *self.confirmed_round.write() = None;
+        }
+
+        let Some(round) = *self.latest.read() else {
+            return Ok(None);
+        };
+        let newest_sent = self.fired_round.read().map(|r| r.round_id).max(*self.confirmed_round.read());
+        if newest_sent.is_some_and(|sent| round.round_id <= sent) {
+            return Ok(None);
+        }
+
+        if let Some(max_age) = self.max_age {
+            let age = unix_secs().saturating_sub(round.updated_at);
+            if age > max_age.as_secs() {
+                debug!("{} round {} is {}s old, not mirroring a stale answer", self.feed.id, round.round_id, age);
+                return Ok(None);
+            }
+        }
+
+        let Some(price) = round.answer_18 else {
+            warn!("{} round {} has a negative answer, skipping", self.feed.id, round.round_id);
+            return Ok(None);
+        };
+
+        if !self.breaker.try_acquire() {
+            debug!("Circuit breaker open, skipping {}", self.feed.id);
+            return Ok(None);
+        }
+
+        let call_data = encode_update_price_with_round(self.selector, &self.feed.id, price, round.round_id, round.updated_at);
+        *self.fired_round.write() = Some(round);
+        self.stats.write().record_trigger();
+
+        info!("🚀 {} mirror: {} (round {} from {})", self.feed.id, round.answer, round.round_id, self.feed.chain);
+
+        let tx_request = TxRequest::new(self.oracle_address, call_data.clone())
+            .with_gas_limit(U256::from(300_000))
+            .with_priority(TxPriority::High)
+            .with_metadata("type", "mirror_update")
+            .with_metadata("feed_id", self.feed.id.clone())
+            .with_metadata("price", round.answer.to_string())
+            .with_metadata("round_id", round.round_id.to_string())
+            .with_metadata("updated_at", round.updated_at.to_string());
+
+        let admitted = self.pending.admit(tx_request).map(|(tx_request, _)| tx_request);
+        if admitted.is_some() {
+            trace_fired(&self.feed.id, &call_data, started);
+        } else {
+            *self.fired_round.write() = None;
+        }
+        Ok(admitted)
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        self.pending.complete();
+        self.breaker.record(success);
+        // A failed round is retried on the next check
+        let round = self.fired_round.write().take();
+        publish_event(OracleEvent::completed(&self.feed.id, success, receipt, round.map(|r| r.answer), latency, 0));
+        if success {
+            if let Some(round) = round {
+                let mut confirmed = self.confirmed_round.write();
+                *confirmed = confirmed.max(Some(round.round_id));
+            }
+            self.stats.write().record_success(0, receipt.map(|r| r.gas_used), latency);
+            if let Some(receipt) = receipt {
+                info!("✅ {} mirrored - tx: {}, block: {}", self.feed.id, receipt.transaction_hash, receipt.block_number);
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(&self.feed.id, receipt, &self.stats);
+                }
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(&self.feed.id, receipt, Some(&self.republish));
+                }
+            }
+        } else {
+            self.stats.write().record_failure();
+            error!("❌ {} mirror update failed", self.feed.id);
+        }
+        trace_completed(&self.feed.id, receipt, success, started);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: format!("MirrorTrigger[{}]", self.feed.id),
+            description: format!("Mirrors Chainlink {} on {} as {}", self.feed.aggregator, self.feed.chain, self.feed.id),
+            trigger_type: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for MirrorTrigger {
+    fn status(&self) -> serde_json::Value {
+        let latest = *self.latest.read();
+        serde_json::json!({
+            "feed_id": self.feed.id,
+            "chain": self.feed.chain,
+            "aggregator": self.feed.aggregator.to_string(),
+            "latest_round": latest.map(|r| serde_json::json!({
+                "round_id": r.round_id.to_string(),
+                "answer": r.answer,
+                "updated_at": r.updated_at,
+                "age_secs": unix_secs().saturating_sub(r.updated_at),
+            })),
+            "confirmed_round": self.confirmed_round.read().map(|r| r.to_string()),
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
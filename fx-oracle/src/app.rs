@@ -0,0 +1,223 @@
+use anyhow::{bail, Result};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, spawn_parse_summary_from_env, spawn_pause_watcher, submitter_for, DryRunOrchestrator, ErrorPolicy, EventBus, EventSink,
+    FeedTrigger, FeedsFile, ForceUpdates, GrpcServer, Heartbeat, HistoryExporter, PriceStream, ReceiptVerifier, RedisSink, ReorgDetector,
+    RuntimeMonitor, ShutdownCoordinator, StatusServer, StatusSource, UpdateHistory,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::market_hours::MarketHours;
+use crate::quotes::FxSources;
+
+/// `/status` for every FX feed
+struct FxStatus {
+    hours: MarketHours,
+    triggers: Vec<Arc<FeedTrigger>>,
+}
+
+impl StatusSource for FxStatus {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "fx-oracle",
+            "market_open": self.hours.is_open_now(),
+            "feeds": self.triggers.iter().map(|t| t.status()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Run the FX rates oracle until Ctrl+C / SIGTERM.
+///
+/// Feeds come from `FX_FEEDS_FILE` (or the first argument, default
+/// `fx-feeds.toml`), using the `exchangerate-host` or `tradermade` sources.
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `fx-oracle` binary or `nonzu run fx-oracle`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting FX Rates Oracle");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("fx-oracle")?;
+
+    let feeds_path = std::env::args()
+        .nth(1)
+        .or_else(|| std::env::var("FX_FEEDS_FILE").ok())
+        .unwrap_or_else(|| "fx-feeds.toml".to_string());
+    let feeds = FeedsFile::load(&feeds_path)?;
+    info!("📄 Loaded {} feeds from {}", feeds.feeds.len(), feeds_path);
+
+    let mut targets = feeds.publish_targets()?;
+    if targets.len() > 1 {
+        bail!("fx-oracle publishes to a single chain; use oracle-runner for several [[targets]]");
+    }
+    let mut target = targets.remove(0);
+    target.chain.resolve_chain_id().await?;
+    target.chain.apply_sdk_defaults();
+
+    let private_keys = load_private_keys(&["FX_ORACLE_PRIVATE_KEY_", target.key_prefix.as_str()])?;
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(target.chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+    if let Some(handle) = spawn_parse_summary_from_env()? {
+        shutdown.register("parse error summary", handle);
+    }
+
+    // --- Sources ---
+    let mut fx_sources = FxSources::from_env()?;
+    let hours = fx_sources.hours();
+    let mut sources = Vec::new();
+    for feed in &feeds.feeds {
+        if !FxSources::is_fx(feed.source) {
+            bail!("Feed '{}': {:?} is not an FX source - run it with oracle-runner", feed.id, feed.source);
+        }
+        if !feed.blend.is_empty() {
+            bail!("Feed '{}': blended feeds are only supported by oracle-runner", feed.id);
+        }
+        sources.push(fx_sources.add(feed)?);
+        info!("💱 {} <- {:?} {} ({}ms, {} decimals)", feed.id, feed.source, feed.symbol, feed.interval_ms, feed.decimals);
+    }
+    fx_sources.spawn(&mut shutdown)?;
+    if !hours.is_open_now() {
+        info!("🌙 FX market is closed - feeds publish once it reopens (Sunday 22:00 UTC)");
+    }
+
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    let receipt_verifier = match ReceiptVerifier::from_env(target.chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(target.chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    // Plugins fed by the event bus: dashboard history and, with
+    // HEARTBEAT_URLS, pings only while updates keep landing
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    if let Some((stream, handle)) = PriceStream::from_env()? {
+        shutdown.register("price stream", handle);
+        EventBus::global().subscribe(stream);
+    }
+    if let Some((sink, handle)) = RedisSink::from_env()? {
+        shutdown.register("redis sink", handle);
+        EventBus::global().subscribe(sink);
+    }
+    if let Some((sink, handle)) = EventSink::from_env().await? {
+        shutdown.register("event sink", handle);
+        EventBus::global().subscribe(sink);
+    }
+    let exporter = match HistoryExporter::from_env()? {
+        Some((exporter, handle)) => {
+            shutdown.register("history export", handle);
+            EventBus::global().subscribe(exporter.clone());
+            Some(exporter)
+        }
+        None => None,
+    };
+    shutdown.register("pause watcher", spawn_pause_watcher("fx-oracle", error_control.clone()));
+    // Out-of-band publishes requested over gRPC (`nonzu force-update`)
+    let force_updates = Arc::new(ForceUpdates::new());
+
+    let triggers: Vec<Arc<FeedTrigger>> = feeds
+        .feeds
+        .iter()
+        .zip(sources)
+        .map(|(feed, source)| {
+            Arc::new(
+                FeedTrigger::new(target.feed_for(feed), source, error_control.clone())
+                    .with_receipt_verifier(receipt_verifier.clone())
+                    .with_reorg_detector(reorg_detector.clone())
+                    .with_force_updates(Some(force_updates.clone())),
+            )
+        })
+        .collect();
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let status = Arc::new(FxStatus { hours, triggers: triggers.clone() });
+        let server = StatusServer::new(addr)
+            .with_status(status)
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+    if let Some(grpc) = GrpcServer::from_env()? {
+        let grpc = triggers
+            .iter()
+            .fold(grpc, |grpc, trigger| grpc.with_stats(trigger.label(), trigger.stats()))
+            .with_control("fx-oracle", error_control.clone())
+            .with_force_updates(Some(force_updates));
+        shutdown.register("grpc server", grpc.spawn());
+    }
+
+    // FX rates move slowly; feeds publish every few seconds at most
+    let min_interval_ms = feeds.feeds.iter().map(|f| f.interval_ms).min().unwrap_or(1000);
+    let check_interval = Duration::from_millis(min_interval_ms.saturating_sub(10).max(50));
+    let triggers: Vec<Arc<dyn TxTrigger>> = triggers.into_iter().map(|t| t as Arc<dyn TxTrigger>).collect();
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(triggers, &private_keys, &target.chain, check_interval).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down oracle (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        triggers,
+        private_keys.into_sdk(),
+        worker_count,
+        check_interval,
+        oracle_error_handler_config(),
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&target.chain)?))
+    .with_error_hook(error_policy);
+
+    let handle = orchestrator.run().await;
+    info!("✅ FX Rates Oracle is running! Press Ctrl+C to stop.");
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down oracle...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+
+    // Trades and updates since the last periodic export
+    if let Some(exporter) = exporter {
+        exporter.export()?;
+    }
+
+    info!("👋 Oracle shutdown complete");
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! exchangerate.host polling.
+//!
+//! One `/live` request per poll returns every currency against USD; the
+//! configured pairs are derived from those (cross rates for non-USD pairs).
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::quotes::{FxQuote, FxQuotes};
+
+const LIVE_URL: &str = "https://api.exchangerate.host/live";
+
+#[derive(Debug, Deserialize)]
+struct LiveResponse {
+    success: bool,
+    /// Unix seconds the rates were produced
+    #[serde(default)]
+    timestamp: u64,
+    /// `USDEUR` -> EUR per USD
+    #[serde(default)]
+    quotes: HashMap<String, f64>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+/// Units of `quote` per `base` from USD-sourced rates (`USDXXX` = XXX per USD)
+pub fn cross_rate(usd_rates: &HashMap<String, f64>, base: &str, quote: &str) -> Option<f64> {
+    let per_usd = |currency: &str| -> Option<f64> {
+        if currency == "USD" {
+            return Some(1.0);
+        }
+        usd_rates.get(&format!("USD{}", currency)).copied().filter(|r| *r > 0.0)
+    };
+    Some(per_usd(quote)? / per_usd(base)?)
+}
+
+async fn poll(
+    client: &reqwest::Client,
+    access_key: &str,
+    currencies: &str,
+    pairs: &[(String, String)],
+    quotes: &FxQuotes,
+) -> Result<()> {
+    let response: LiveResponse = client
+        .get(LIVE_URL)
+        .query(&[("access_key", access_key), ("source", "USD"), ("currencies", currencies)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if !response.success {
+        return Err(anyhow!("exchangerate.host error: {:?}", response.error));
+    }
+
+    for (base, quote) in pairs {
+        match cross_rate(&response.quotes, base, quote) {
+            Some(rate) => {
+                debug!("💱 {}{} = {}", base, quote, rate);
+                quotes.set(&format!("{}{}", base, quote), FxQuote { rate, timestamp_ms: response.timestamp * 1000 });
+            }
+            None => warn!("⚠️ exchangerate.host returned no rate for {}{}", base, quote),
+        }
+    }
+    Ok(())
+}
+
+/// Poll every `interval`, updating `quotes` for each `(base, quote)` pair
+pub fn spawn_exchangerate_host(
+    access_key: String,
+    pairs: Vec<(String, String)>,
+    quotes: Arc<FxQuotes>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut currencies: Vec<&str> = pairs
+            .iter()
+            .flat_map(|(b, q)| [b.as_str(), q.as_str()])
+            .filter(|c| *c != "USD")
+            .collect();
+        currencies.sort();
+        currencies.dedup();
+        let currencies = currencies.join(",");
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = poll(&client, &access_key, &currencies, &pairs, &quotes).await {
+                warn!("⚠️ exchangerate.host poll failed: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,7 @@
+pub mod exchangerate_host;
+pub mod market_hours;
+pub mod quotes;
+pub mod tradermade;
+mod app;
+
+pub use app::run;
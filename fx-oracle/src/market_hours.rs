@@ -0,0 +1,52 @@
+//! FX trading hours.
+//!
+//! The spot FX market trades around the clock on weekdays and closes from
+//! Friday 22:00 UTC (New York close) until Sunday 22:00 UTC (Sydney open).
+//! Providers keep serving Friday's closing rate over the weekend, so a
+//! "fresh" quote is not evidence that the market is trading - feeds are not
+//! published while it is closed.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `FX_MARKET_HOURS` | `weekdays` | `weekdays` (closed over the weekend) or `always` |
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+
+/// Hour (UTC) the market closes on Friday and reopens on Sunday
+const WEEKEND_BOUNDARY_HOUR: u32 = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarketHours {
+    /// Closed from Friday 22:00 UTC to Sunday 22:00 UTC
+    #[default]
+    Weekdays,
+    /// Always open (testing, or providers quoting around the clock)
+    Always,
+}
+
+impl MarketHours {
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("FX_MARKET_HOURS").as_deref() {
+            Err(_) | Ok("weekdays") => Ok(MarketHours::Weekdays),
+            Ok("always") => Ok(MarketHours::Always),
+            Ok(other) => bail!("Invalid FX_MARKET_HOURS '{}' (expected weekdays or always)", other),
+        }
+    }
+
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            MarketHours::Always => true,
+            MarketHours::Weekdays => match now.weekday() {
+                Weekday::Fri => now.hour() < WEEKEND_BOUNDARY_HOUR,
+                Weekday::Sat => false,
+                Weekday::Sun => now.hour() >= WEEKEND_BOUNDARY_HOUR,
+                _ => true,
+            },
+        }
+    }
+
+    pub fn is_open_now(&self) -> bool {
+        self.is_open(Utc::now())
+    }
+}
@@ -0,0 +1,166 @@
+//! Latest FX rates and the feed sources reading them.
+
+use anyhow::{bail, Context, Result};
+use oracle_common::{FeedConfig, PricePoint, PriceSource, ShutdownCoordinator, SourceKind, WsProxy};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::exchangerate_host::spawn_exchangerate_host;
+use crate::market_hours::MarketHours;
+use crate::tradermade::TradermadeClient;
+
+/// One rate: units of the quote currency per unit of the base currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FxQuote {
+    pub rate: f64,
+    /// When the provider produced the rate (unix ms)
+    pub timestamp_ms: u64,
+}
+
+/// Split `EURUSD` / `EUR/USD` into `("EUR", "USD")`
+pub fn parse_pair(symbol: &str) -> Result<(String, String)> {
+    let pair: String = symbol.chars().filter(|c| *c != '/').collect::<String>().to_uppercase();
+    if pair.len() != 6 || !pair.chars().all(|c| c.is_ascii_alphabetic()) {
+        bail!("Invalid FX pair '{}' (expected e.g. EURUSD or EUR/USD)", symbol);
+    }
+    Ok((pair[..3].to_string(), pair[3..].to_string()))
+}
+
+/// Latest rate per pair (`EURUSD`), filled by the provider tasks
+#[derive(Debug, Default)]
+pub struct FxQuotes {
+    rates: RwLock<HashMap<String, FxQuote>>,
+}
+
+impl FxQuotes {
+    pub fn set(&self, pair: &str, quote: FxQuote) {
+        self.rates.write().insert(pair.to_string(), quote);
+    }
+
+    /// Rate for `base`/`quote`, inverting the opposite pair if that is what we have
+    pub fn get(&self, base: &str, quote: &str) -> Option<FxQuote> {
+        let rates = self.rates.read();
+        if let Some(direct) = rates.get(&format!("{}{}", base, quote)) {
+            return Some(*direct);
+        }
+        rates
+            .get(&format!("{}{}", quote, base))
+            .filter(|inverse| inverse.rate > 0.0)
+            .map(|inverse| FxQuote { rate: 1.0 / inverse.rate, timestamp_ms: inverse.timestamp_ms })
+    }
+}
+
+/// Exposes one pair from [`FxQuotes`] as a configured feed's price source.
+/// Nothing is returned while the market is closed.
+pub struct FxFeedSource {
+    feed_id: String,
+    base: String,
+    quote: String,
+    quotes: Arc<FxQuotes>,
+    hours: MarketHours,
+    closed: AtomicBool,
+}
+
+impl PriceSource for FxFeedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        let open = self.hours.is_open_now();
+        if self.closed.swap(!open, Ordering::Relaxed) == open {
+            if open {
+                info!("🔔 FX market open, resuming {}", self.feed_id);
+            } else {
+                info!("🌙 FX market closed, pausing {} until Sunday 22:00 UTC", self.feed_id);
+            }
+        }
+        if !open {
+            return None;
+        }
+
+        let quote = self.quotes.get(&self.base, &self.quote)?;
+        Some(PricePoint {
+            price: quote.rate,
+            num_trades: 1,
+            timestamp_ms: quote.timestamp_ms,
+            confidence: None,
+        })
+    }
+}
+
+/// Collects the pairs each FX provider has to serve, then starts them
+pub struct FxSources {
+    quotes: Arc<FxQuotes>,
+    hours: MarketHours,
+    polled: Vec<(String, String)>,
+    streamed: Vec<String>,
+}
+
+impl FxSources {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            quotes: Arc::new(FxQuotes::default()),
+            hours: MarketHours::from_env()?,
+            polled: Vec::new(),
+            streamed: Vec::new(),
+        })
+    }
+
+    pub fn is_fx(source: SourceKind) -> bool {
+        matches!(source, SourceKind::ExchangerateHost | SourceKind::Tradermade)
+    }
+
+    pub fn hours(&self) -> MarketHours {
+        self.hours
+    }
+
+    /// Source for `feed`, whose symbol is the pair to publish
+    pub fn add(&mut self, feed: &FeedConfig) -> Result<Arc<dyn PriceSource>> {
+        let (base, quote) = parse_pair(&feed.symbol).with_context(|| format!("Feed '{}'", feed.id))?;
+        match feed.source {
+            SourceKind::ExchangerateHost => self.polled.push((base.clone(), quote.clone())),
+            SourceKind::Tradermade => self.streamed.push(format!("{}{}", base, quote)),
+            other => bail!("Feed '{}': {:?} is not an FX source", feed.id, other),
+        }
+        Ok(Arc::new(FxFeedSource {
+            feed_id: feed.id.clone(),
+            base,
+            quote,
+            quotes: self.quotes.clone(),
+            hours: self.hours,
+            closed: AtomicBool::new(false),
+        }))
+    }
+
+    /// Start polling / streaming every pair added so far
+    pub fn spawn(mut self, shutdown: &mut ShutdownCoordinator) -> Result<()> {
+        if !self.polled.is_empty() {
+            let access_key = std::env::var("EXCHANGERATE_HOST_ACCESS_KEY")
+                .context("EXCHANGERATE_HOST_ACCESS_KEY must be set for exchangerate-host feeds")?;
+            let poll_secs: u64 = match std::env::var("FX_POLL_SECS") {
+                Ok(v) => v.parse().context("Invalid FX_POLL_SECS")?,
+                Err(_) => 60,
+            };
+            shutdown.register(
+                "exchangerate.host poller",
+                spawn_exchangerate_host(access_key, self.polled, self.quotes.clone(), Duration::from_secs(poll_secs.max(1))),
+            );
+        }
+
+        if !self.streamed.is_empty() {
+            let api_key = std::env::var("TRADERMADE_API_KEY")
+                .context("TRADERMADE_API_KEY must be set for tradermade feeds")?;
+            self.streamed.sort();
+            self.streamed.dedup();
+            let client = TradermadeClient::new(api_key, self.streamed, self.quotes.clone())
+                .with_proxy(WsProxy::from_env()?.map(Arc::new));
+            shutdown.register("tradermade websocket", tokio::spawn(async move {
+                if let Err(e) = client.run().await {
+                    error!("TraderMade client error: {}", e);
+                }
+            }));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! TraderMade websocket streaming.
+//!
+//! After connecting, the client sends its key and symbols; the server then
+//! streams `{"symbol":"EURUSD","ts":"...","bid":..,"ask":..,"mid":..}`
+//! messages, of which the mid is kept.
+
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use oracle_common::{connect_ws, maybe_drop_stream, parse_quarantine, publish_event, ws_bandwidth, OracleEvent, WsProxy};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tracing::{debug, error, info, warn};
+
+use crate::quotes::{FxQuote, FxQuotes};
+
+const STREAM_URL: &str = "wss://marketdata.tradermade.com/feedadv";
+
+#[derive(Debug, Deserialize)]
+struct TickMessage {
+    symbol: String,
+    /// Milliseconds as a string (occasionally a number)
+    #[serde(default)]
+    ts: Option<serde_json::Value>,
+    mid: f64,
+}
+
+impl TickMessage {
+    fn timestamp_ms(&self) -> u64 {
+        let ts = match &self.ts {
+            Some(serde_json::Value::String(s)) => s.parse::<f64>().ok(),
+            Some(serde_json::Value::Number(n)) => n.as_f64(),
+            _ => None,
+        };
+        match ts {
+            // Seconds rather than milliseconds
+            Some(ts) if ts < 1e12 => (ts * 1000.0) as u64,
+            Some(ts) => ts as u64,
+            None => SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+        }
+    }
+}
+
+pub struct TradermadeClient {
+    api_key: String,
+    symbols: Vec<String>,
+    quotes: Arc<FxQuotes>,
+    proxy: Option<Arc<WsProxy>>,
+    reconnect_delay: Duration,
+}
+
+impl TradermadeClient {
+    pub fn new(api_key: String, symbols: Vec<String>, quotes: Arc<FxQuotes>) -> Self {
+        Self {
+            api_key,
+            symbols,
+            quotes,
+            proxy: None,
+            reconnect_delay: Duration::from_secs(5),
+        }
+    }
+
+    /// Connect through an HTTP CONNECT / SOCKS5 proxy (`WS_PROXY_URL`)
+    pub fn with_proxy(mut self, proxy: Option<Arc<WsProxy>>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            let error = match self.connect_and_process().await {
+                Ok(_) => {
+                    warn!("TraderMade connection closed, reconnecting in {:?}", self.reconnect_delay);
+                    None
+                }
+                Err(e) => {
+                    error!("TraderMade error: {}, reconnecting in {:?}", e, self.reconnect_delay);
+                    Some(e.to_string())
+                }
+            };
+            publish_event(OracleEvent::WsReconnect { source: "tradermade".to_string(), error });
+
+            sleep(self.reconnect_delay).await;
+        }
+    }
+
+    async fn connect_and_process(&self) -> Result<()> {
+        info!("Connecting to TraderMade stream for {}", self.symbols.join(","));
+        let bandwidth = ws_bandwidth("tradermade");
+        let (ws_stream, _) = timeout(Duration::from_secs(10), connect_ws(STREAM_URL, self.proxy.as_deref(), bandwidth.clone()))
+            .await
+            .map_err(|_| anyhow!("Connection timeout"))?
+            .map_err(|e| anyhow!("Failed to connect: {}", e))?;
+
+        let (mut write, mut read) = ws_stream.split();
+        let subscribe = serde_json::json!({ "userKey": self.api_key, "symbol": self.symbols.join(",") });
+        write.send(Message::Text(subscribe.to_string())).await?;
+        info!("Connected to TraderMade stream");
+
+        let mut ping = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    match msg {
+                        Some(Ok(Message::Text(text))) => {
+                            bandwidth.message(text.as_bytes());
+                            maybe_drop_stream("tradermade")?;
+                            self.process_message(&text);
+                        }
+                        Some(Ok(Message::Ping(data))) => {
+                            write.send(Message::Pong(data)).await?;
+                        }
+                        Some(Ok(Message::Close(_))) => {
+                            info!("Received close frame");
+                            break;
+                        }
+                        Some(Err(e)) => return Err(e.into()),
+                        None => {
+                            warn!("TraderMade stream ended");
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                _ = ping.tick() => {
+                    write.send(Message::Ping(vec![])).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn process_message(&self, text: &str) {
+        // The server greets with plain text ("Connected") before ticks
+        if !text.starts_with('{') {
+            debug!("TraderMade: {}", text);
+            return;
+        }
+        match serde_json::from_str::<TickMessage>(text) {
+            Ok(tick) if tick.mid > 0.0 => {
+                self.quotes.set(&tick.symbol, FxQuote { rate: tick.mid, timestamp_ms: tick.timestamp_ms() });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                parse_quarantine("tradermade").reject(e, text);
+            }
+        }
+    }
+}
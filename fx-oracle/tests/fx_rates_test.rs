@@ -0,0 +1,46 @@
+//! Market hours, pair parsing and cross rates
+
+use chrono::{TimeZone, Utc};
+use fx_oracle::exchangerate_host::cross_rate;
+use fx_oracle::market_hours::MarketHours;
+use fx_oracle::quotes::{parse_pair, FxQuote, FxQuotes};
+use std::collections::HashMap;
+
+#[test]
+fn test_market_closed_over_the_weekend() {
+    let hours = MarketHours::Weekdays;
+    // 2026-10-16 is a Friday
+    assert!(hours.is_open(Utc.with_ymd_and_hms(2026, 10, 16, 21, 59, 0).unwrap()));
+    assert!(!hours.is_open(Utc.with_ymd_and_hms(2026, 10, 16, 22, 0, 0).unwrap()));
+    assert!(!hours.is_open(Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap()));
+    assert!(!hours.is_open(Utc.with_ymd_and_hms(2026, 10, 18, 21, 59, 0).unwrap()));
+    assert!(hours.is_open(Utc.with_ymd_and_hms(2026, 10, 18, 22, 0, 0).unwrap()));
+    assert!(hours.is_open(Utc.with_ymd_and_hms(2026, 10, 21, 3, 0, 0).unwrap()));
+
+    assert!(MarketHours::Always.is_open(Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap()));
+}
+
+#[test]
+fn test_parse_pair() {
+    assert_eq!(parse_pair("EURUSD").unwrap(), ("EUR".to_string(), "USD".to_string()));
+    assert_eq!(parse_pair("jpy/usd").unwrap(), ("JPY".to_string(), "USD".to_string()));
+    assert!(parse_pair("BTCUSDT").is_err());
+}
+
+#[test]
+fn test_cross_rates_from_usd_quotes() {
+    let usd_rates = HashMap::from([("USDEUR".to_string(), 0.8), ("USDJPY".to_string(), 160.0)]);
+    assert_eq!(cross_rate(&usd_rates, "EUR", "USD"), Some(1.25));
+    assert_eq!(cross_rate(&usd_rates, "USD", "JPY"), Some(160.0));
+    assert_eq!(cross_rate(&usd_rates, "EUR", "JPY"), Some(200.0));
+    assert_eq!(cross_rate(&usd_rates, "GBP", "USD"), None);
+}
+
+#[test]
+fn test_quotes_invert_the_opposite_pair() {
+    let quotes = FxQuotes::default();
+    quotes.set("USDJPY", FxQuote { rate: 160.0, timestamp_ms: 1 });
+    assert_eq!(quotes.get("JPY", "USD").unwrap().rate, 1.0 / 160.0);
+    assert_eq!(quotes.get("USD", "JPY").unwrap().rate, 160.0);
+    assert!(quotes.get("EUR", "USD").is_none());
+}
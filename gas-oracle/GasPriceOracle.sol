@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+import "@openzeppelin/contracts/access/Ownable.sol";
+
+/**
+ * @title GasPriceOracle
+ * @notice Publishes the chain's current gas prices for on-chain fee estimates
+ * @dev All values in wei. L1 fees are zero on chains without L1 data costs.
+ */
+contract GasPriceOracle is Ownable {
+    struct GasPrices {
+        uint256 baseFee;        // Base fee of the sampled block
+        uint256 priorityFee;    // Suggested priority fee
+        uint256 l1BaseFee;      // L1 base fee (OP-stack L2s)
+        uint256 blobBaseFee;    // L1 blob base fee (OP-stack L2s)
+        uint256 lastUpdate;     // Block timestamp of last update
+        uint256 updateCount;    // Total updates
+    }
+
+    GasPrices public latest;
+
+    // Authorized updaters (oracle runners)
+    mapping(address => bool) public authorizedUpdaters;
+
+    // Events
+    event GasPricesUpdated(uint256 baseFee, uint256 priorityFee, uint256 l1BaseFee, uint256 blobBaseFee, uint256 timestamp);
+    event UpdaterAuthorized(address indexed updater, bool authorized);
+
+    // Errors
+    error UnauthorizedUpdater(address updater);
+    error NoGasPrices();
+
+    modifier onlyAuthorized() {
+        if (!authorizedUpdaters[msg.sender] && msg.sender != owner()) {
+            revert UnauthorizedUpdater(msg.sender);
+        }
+        _;
+    }
+
+    constructor() Ownable(msg.sender) {
+        // Owner is automatically authorized
+        authorizedUpdaters[msg.sender] = true;
+    }
+
+    /**
+     * @notice Publishes new gas prices
+     * @param baseFee Base fee per gas
+     * @param priorityFee Suggested priority fee per gas
+     * @param l1BaseFee L1 base fee (0 if not applicable)
+     * @param blobBaseFee L1 blob base fee (0 if not applicable)
+     */
+    function updateGasPrices(
+        uint256 baseFee,
+        uint256 priorityFee,
+        uint256 l1BaseFee,
+        uint256 blobBaseFee
+    ) external onlyAuthorized {
+        latest.baseFee = baseFee;
+        latest.priorityFee = priorityFee;
+        latest.l1BaseFee = l1BaseFee;
+        latest.blobBaseFee = blobBaseFee;
+        latest.lastUpdate = block.timestamp;
+        latest.updateCount++;
+
+        emit GasPricesUpdated(baseFee, priorityFee, l1BaseFee, blobBaseFee, block.timestamp);
+    }
+
+    /**
+     * @notice Suggested EIP-1559 fees, headroom for the base fee doubling
+     * @return maxFeePerGas 2 * baseFee + priorityFee
+     * @return maxPriorityFeePerGas The published priority fee
+     */
+    function suggestedFees() external view returns (uint256 maxFeePerGas, uint256 maxPriorityFeePerGas) {
+        if (latest.lastUpdate == 0) revert NoGasPrices();
+        return (2 * latest.baseFee + latest.priorityFee, latest.priorityFee);
+    }
+
+    /**
+     * @notice Checks if the published prices are stale
+     * @param maxAge Maximum age in seconds
+     * @return True if data is stale
+     */
+    function isStale(uint256 maxAge) external view returns (bool) {
+        if (latest.lastUpdate == 0) return true;
+        return block.timestamp > latest.lastUpdate + maxAge;
+    }
+
+    /**
+     * @notice Authorizes or revokes an updater
+     * @param updater The address to authorize/revoke
+     * @param authorized Whether to authorize or revoke
+     */
+    function setAuthorizedUpdater(address updater, bool authorized) external onlyOwner {
+        authorizedUpdaters[updater] = authorized;
+        emit UpdaterAuthorized(updater, authorized);
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, spawn_pause_watcher, submitter_for, DryRunOrchestrator, ErrorPolicy, EventBus, Heartbeat,
+    ReceiptVerifier, ReorgDetector, RuntimeMonitor, ShutdownCoordinator, StatusServer, UpdateHistory,
+};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::sampler::{GasSampler, OP_GAS_PRICE_ORACLE};
+use crate::trigger::GasPriceTrigger;
+
+fn env_u64(key: &str, default: u64) -> Result<u64> {
+    match env::var(key) {
+        Ok(v) => v.parse().with_context(|| format!("Invalid {}", key)),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Run the gas price oracle until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `gas-oracle` binary or `nonzu run gas-oracle`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Gas Price Oracle");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("gas-oracle")?;
+
+    let chain = apply_sdk_defaults().await?;
+
+    let oracle_address = env::var("GAS_ORACLE_ADDRESS").context("GAS_ORACLE_ADDRESS must be set in .env")?;
+    info!("📝 Oracle contract address: {}", oracle_address);
+
+    let private_keys = load_private_keys(&["GAS_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+
+    let update_interval = Duration::from_millis(env_u64("GAS_UPDATE_INTERVAL_MS", 12_000)?.max(1));
+    let sample_interval = Duration::from_millis(env_u64("GAS_SAMPLE_INTERVAL_MS", 2_000)?.max(1));
+    let max_sample_age = Duration::from_millis(env_u64("GAS_MAX_SAMPLE_AGE_MS", 30_000)?);
+
+    // L1 data costs: `op` for the OP-stack predeploy, or an address answering
+    // l1BaseFee() / blobBaseFee()
+    let l1_oracle = match env::var("GAS_L1_FEES").ok().as_deref() {
+        None | Some("") | Some("off") | Some("false") => None,
+        Some("op") | Some("true") => Some(OP_GAS_PRICE_ORACLE),
+        Some(address) => Some(Address::from_str(address).context("Invalid GAS_L1_FEES")?),
+    };
+    if let Some(address) = l1_oracle {
+        info!("🌉 Reading L1 data costs from {}", address);
+    }
+
+    let sampler = GasSampler::new(chain.rpc_urls.clone(), l1_oracle);
+    shutdown.register("gas sampler", sampler.spawn(sample_interval));
+
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    shutdown.register("pause watcher", spawn_pause_watcher("gas-oracle", error_control.clone()));
+
+    let gas_trigger = Arc::new(
+        GasPriceTrigger::new(
+            Address::from_str(&oracle_address)?,
+            sampler,
+            update_interval,
+            max_sample_age,
+            error_control.clone(),
+        )
+        .with_receipt_verifier(receipt_verifier)
+        .with_reorg_detector(reorg_detector),
+    );
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let server = StatusServer::new(addr)
+            .with_status(gas_trigger.clone())
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+
+    // Triggers are cheap to poll; a quarter second is plenty for block-scale intervals
+    let check_interval = Duration::from_millis(250).min(update_interval);
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(
+            vec![gas_trigger as Arc<dyn TxTrigger>],
+            &private_keys,
+            &chain,
+            check_interval,
+        ).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down oracle (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        vec![gas_trigger as Arc<dyn TxTrigger>],
+        private_keys.into_sdk(),
+        worker_count,
+        check_interval,
+        oracle_error_handler_config(),
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+
+    let handle = orchestrator.run().await;
+
+    info!("✅ Gas Price Oracle is running! Press Ctrl+C to stop.");
+    info!("⛽ Sampling every {:?}, publishing every {:?}", sample_interval, update_interval);
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down oracle...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+
+    info!("👋 Oracle shutdown complete");
+    Ok(())
+}
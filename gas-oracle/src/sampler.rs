@@ -0,0 +1,155 @@
+//! Samples the chain's current gas prices over JSON-RPC.
+//!
+//! Each sample reads the latest block's `baseFeePerGas` and
+//! `eth_maxPriorityFeePerGas` (falling back to `eth_gasPrice` minus the base
+//! fee on nodes without it). On OP-stack L2s the L1 data costs are read from
+//! the `GasPriceOracle` predeploy as well.
+
+use alloy::hex;
+use alloy::primitives::{address, Address, U256};
+use anyhow::{anyhow, Context, Result};
+use oracle_common::{function_selector, RpcEndpoints};
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// OP-stack `GasPriceOracle` predeploy
+pub const OP_GAS_PRICE_ORACLE: Address = address!("420000000000000000000000000000000000000F");
+
+/// Gas prices at one block, all in wei
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasSample {
+    pub block_number: u64,
+    pub base_fee: u128,
+    pub priority_fee: u128,
+    /// L1 base fee (OP-stack chains with L1 fees enabled)
+    pub l1_base_fee: Option<u128>,
+    /// L1 blob base fee (OP-stack chains with L1 fees enabled)
+    pub blob_base_fee: Option<u128>,
+    pub sampled_at_ms: u64,
+}
+
+impl GasSample {
+    pub fn age(&self) -> Duration {
+        Duration::from_millis(unix_ms().saturating_sub(self.sampled_at_ms))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "block_number": self.block_number,
+            "base_fee_wei": self.base_fee.to_string(),
+            "priority_fee_wei": self.priority_fee.to_string(),
+            "l1_base_fee_wei": self.l1_base_fee.map(|v| v.to_string()),
+            "blob_base_fee_wei": self.blob_base_fee.map(|v| v.to_string()),
+            "age_ms": self.age().as_millis() as u64,
+        })
+    }
+}
+
+pub struct GasSampler {
+    rpc: RpcEndpoints,
+    /// Contract answering `l1BaseFee()` / `blobBaseFee()`, if L1 fees are read
+    l1_oracle: Option<Address>,
+    latest: RwLock<Option<GasSample>>,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+fn parse_quantity(value: &Value) -> Result<u128> {
+    let raw = value.as_str().ok_or_else(|| anyhow!("expected a hex quantity, got {}", value))?;
+    let quantity = U256::from_str_radix(raw.trim_start_matches("0x"), 16)?;
+    u128::try_from(quantity).map_err(|_| anyhow!("quantity {} does not fit in u128", quantity))
+}
+
+impl GasSampler {
+    pub fn new(rpc_urls: Vec<String>, l1_oracle: Option<Address>) -> Arc<Self> {
+        Arc::new(Self {
+            rpc: RpcEndpoints::new(rpc_urls),
+            l1_oracle,
+            latest: RwLock::new(None),
+        })
+    }
+
+    /// Sample every `interval` in the background
+    pub fn spawn(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let sampler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match sampler.sample().await {
+                    Ok(sample) => {
+                        debug!(
+                            "⛽ Block {}: base fee {} wei, priority fee {} wei",
+                            sample.block_number, sample.base_fee, sample.priority_fee
+                        );
+                        *sampler.latest.write() = Some(sample);
+                    }
+                    Err(e) => warn!("⚠️ Gas price sample failed: {:#}", e),
+                }
+            }
+        })
+    }
+
+    /// The most recent successful sample
+    pub fn latest(&self) -> Option<GasSample> {
+        *self.latest.read()
+    }
+
+    pub async fn sample(&self) -> Result<GasSample> {
+        let block = self
+            .rpc
+            .call("eth_getBlockByNumber", json!(["latest", false]))
+            .await
+            .map_err(|e| anyhow!("eth_getBlockByNumber: {:?}", e))?;
+        let block_number = parse_quantity(&block["number"]).context("block has no number")? as u64;
+        // Pre-London chains have no base fee
+        let base_fee = parse_quantity(&block["baseFeePerGas"]).unwrap_or(0);
+
+        let priority_fee = match self.rpc.call("eth_maxPriorityFeePerGas", json!([])).await {
+            Ok(fee) => parse_quantity(&fee)?,
+            Err(e) if e.is_method_not_found() => {
+                let gas_price = self
+                    .rpc
+                    .call("eth_gasPrice", json!([]))
+                    .await
+                    .map_err(|e| anyhow!("eth_gasPrice: {:?}", e))?;
+                parse_quantity(&gas_price)?.saturating_sub(base_fee)
+            }
+            Err(e) => return Err(anyhow!("eth_maxPriorityFeePerGas: {:?}", e)),
+        };
+
+        let (l1_base_fee, blob_base_fee) = match self.l1_oracle {
+            Some(oracle) => (
+                Some(self.read_fee(oracle, "l1BaseFee()").await?),
+                // Pre-Ecotone oracles have no blob base fee
+                self.read_fee(oracle, "blobBaseFee()").await.ok(),
+            ),
+            None => (None, None),
+        };
+
+        Ok(GasSample {
+            block_number,
+            base_fee,
+            priority_fee,
+            l1_base_fee,
+            blob_base_fee,
+            sampled_at_ms: unix_ms(),
+        })
+    }
+
+    async fn read_fee(&self, oracle: Address, signature: &str) -> Result<u128> {
+        let call = json!({ "to": oracle, "data": hex::encode_prefixed(function_selector(signature)) });
+        let result = self
+            .rpc
+            .call("eth_call", json!([call, "latest"]))
+            .await
+            .map_err(|e| anyhow!("{} on {}: {:?}", signature, oracle, e))?;
+        parse_quantity(&result)
+    }
+}
@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    encode_update_gas_prices, function_selector, publish_event, trace_completed, trace_fired, CircuitBreaker, OracleEvent,
+    OracleStats, PendingQueue, PreciseTimer, ReceiptVerifier, ReorgDetector, SharedStats, StatusSource,
+};
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info};
+
+use crate::sampler::{GasSample, GasSampler};
+
+/// Feed name in events, traces and the dashboard
+const FEED: &str = "GAS";
+
+pub struct GasPriceTrigger {
+    oracle_address: Address,
+    sampler: Arc<GasSampler>,
+    timer: Arc<RwLock<PreciseTimer>>,
+    update_interval: Duration,
+    /// Samples older than this are not published
+    max_sample_age: Duration,
+    last_drift_ms: Arc<RwLock<i64>>,
+    last_published: Arc<RwLock<Option<GasSample>>>,
+    selector: [u8; 4],
+    error_control: Arc<OrchestratorErrorControl>,
+    stats: SharedStats,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+}
+
+impl GasPriceTrigger {
+    pub fn new(
+        oracle_address: Address,
+        sampler: Arc<GasSampler>,
+        update_interval: Duration,
+        max_sample_age: Duration,
+        error_control: Arc<OrchestratorErrorControl>,
+    ) -> Self {
+        Self {
+            oracle_address,
+            sampler,
+            timer: Arc::new(RwLock::new(PreciseTimer::new(update_interval.as_millis() as u64))),
+            update_interval,
+            max_sample_age,
+            last_drift_ms: Arc::new(RwLock::new(0)),
+            last_published: Arc::new(RwLock::new(None)),
+            selector: function_selector("updateGasPrices(uint256,uint256,uint256,uint256)"),
+            error_control,
+            stats: OracleStats::shared(),
+            pending: PendingQueue::for_interval(FEED, update_interval),
+            breaker: CircuitBreaker::from_env(FEED),
+            receipt_verifier: None,
+            reorg_detector: None,
+        }
+    }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+}
+
+/// Base fee in gwei, the value charted on the dashboard
+fn base_fee_gwei(sample: &GasSample) -> f64 {
+    sample.base_fee as f64 / 1e9
+}
+
+#[async_trait]
+impl TxTrigger for GasPriceTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let started = SystemTime::now();
+        if self.error_control.is_worker_pool_paused().await {
+            debug!("Worker pool paused, skipping trigger");
+            return Ok(None);
+        }
+
+        if !self.timer.read().is_due() {
+            return Ok(None);
+        }
+
+        let Some(sample) = self.sampler.latest() else {
+            debug!("No gas price sample yet");
+            return Ok(None);
+        };
+        if sample.age() > self.max_sample_age {
+            debug!("Latest gas price sample is {:?} old, skipping", sample.age());
+            return Ok(None);
+        }
+
+        if !self.breaker.try_acquire() {
+            debug!("Circuit breaker open, skipping update");
+            return Ok(None);
+        }
+
+        self.stats.write().record_trigger();
+
+        let call_data = encode_update_gas_prices(
+            self.selector,
+            sample.base_fee,
+            sample.priority_fee,
+            sample.l1_base_fee.unwrap_or(0),
+            sample.blob_base_fee.unwrap_or(0),
+        );
+
+        if let Some((target_time, actual_time)) = self.timer.write().should_tick() {
+            *self.last_drift_ms.write() = actual_time as i64 - target_time as i64;
+        }
+        *self.last_published.write() = Some(sample);
+
+        info!(
+            "🚀 Gas update from block {}: base fee {:.3} gwei, priority fee {} wei",
+            sample.block_number,
+            base_fee_gwei(&sample),
+            sample.priority_fee
+        );
+
+        let tx_request = TxRequest::new(self.oracle_address, call_data.clone())
+            .with_gas_limit(U256::from(120_000))
+            .with_priority(TxPriority::High)
+            .with_metadata("type", "gas_update")
+            .with_metadata("block_number", sample.block_number.to_string())
+            .with_metadata("base_fee", sample.base_fee.to_string())
+            .with_metadata("priority_fee", sample.priority_fee.to_string());
+
+        let admitted = self.pending.admit(tx_request).map(|(tx_request, _)| tx_request);
+        if admitted.is_some() {
+            trace_fired(FEED, &call_data, started);
+        }
+        Ok(admitted)
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        self.pending.complete();
+        self.breaker.record(success);
+        let drift_ms = *self.last_drift_ms.read();
+        let value = self.last_published.read().as_ref().map(base_fee_gwei);
+        publish_event(OracleEvent::completed(FEED, success, receipt, value, latency, drift_ms));
+        if success {
+            self.stats.write().record_success(drift_ms, receipt.map(|r| r.gas_used), latency);
+            if let Some(receipt) = receipt {
+                self.stats.write().last_block = Some(receipt.block_number);
+                info!(
+                    "✅ Gas update confirmed - tx: {}, block: {}, gas: {}",
+                    receipt.transaction_hash, receipt.block_number, receipt.gas_used
+                );
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(FEED, receipt, &self.stats);
+                }
+                // A dropped update is replaced on the next interval
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(FEED, receipt, None);
+                }
+            }
+        } else {
+            self.stats.write().record_failure();
+            error!("❌ Gas update failed");
+        }
+        trace_completed(FEED, receipt, success, started);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: "GasPriceTrigger".to_string(),
+            description: "Publishes the chain's base fee, priority fee and L1 data costs".to_string(),
+            trigger_type: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for GasPriceTrigger {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "gas-oracle",
+            "oracle_address": self.oracle_address.to_string(),
+            "update_interval_ms": self.update_interval.as_millis() as u64,
+            "latest_sample": self.sampler.latest().map(|s| s.to_json()),
+            "last_published": self.last_published.read().as_ref().map(|s| s.to_json()),
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use alloy::providers::ProviderBuilder;
+use anyhow::Result;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, spawn_pause_watcher, submitter_for, DryRunOrchestrator, ErrorPolicy, EventBus, Heartbeat,
+    ReceiptVerifier, ReorgDetector, RuntimeMonitor, ShutdownCoordinator, StatusServer, StatusSource, UpdateHistory,
+};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::JobsFile;
+use crate::trigger::KeeperTrigger;
+
+/// `/status` for every job
+struct KeeperStatus {
+    triggers: Vec<Arc<KeeperTrigger>>,
+}
+
+impl StatusSource for KeeperStatus {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "keeper",
+            "jobs": self.triggers.iter().map(|t| t.status()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Run the keeper until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `keeper` binary or `nonzu run keeper`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Keeper");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("keeper")?;
+
+    let chain = apply_sdk_defaults().await?;
+
+    let jobs_path = env::var("KEEPER_JOBS_FILE").unwrap_or_else(|_| "keeper.toml".to_string());
+    let jobs = JobsFile::load(&jobs_path)?;
+    info!("📄 Loaded {} jobs from {}", jobs.jobs.len(), jobs_path);
+
+    let private_keys = load_private_keys(&["KEEPER_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    shutdown.register("pause watcher", spawn_pause_watcher("keeper", error_control.clone()));
+
+    // Condition reads share one connection pool
+    let provider = ProviderBuilder::new().on_http(chain.rpc_url().parse()?);
+    let mut triggers = Vec::new();
+    for job in jobs.jobs {
+        info!(
+            "🔧 {}: {} every {}s{}",
+            job.name,
+            job.target,
+            job.interval_secs,
+            if job.condition.is_some() { " when its condition holds" } else { "" }
+        );
+        let trigger = KeeperTrigger::new(job, provider.clone(), error_control.clone())?
+            .with_receipt_verifier(receipt_verifier.clone())
+            .with_reorg_detector(reorg_detector.clone());
+        triggers.push(Arc::new(trigger));
+    }
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let server = StatusServer::new(addr)
+            .with_status(Arc::new(KeeperStatus { triggers: triggers.clone() }))
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+
+    // Jobs run on second-scale intervals; checking faster only burns CPU
+    let check_interval = Duration::from_millis(500);
+    let triggers: Vec<Arc<dyn TxTrigger>> = triggers.into_iter().map(|t| t as Arc<dyn TxTrigger>).collect();
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(triggers, &private_keys, &chain, check_interval).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down keeper (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        triggers,
+        private_keys.into_sdk(),
+        worker_count,
+        check_interval,
+        oracle_error_handler_config(),
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+
+    let handle = orchestrator.run().await;
+    info!("✅ Keeper is running! Press Ctrl+C to stop.");
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down keeper...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+
+    info!("👋 Keeper shutdown complete");
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Keeper jobs loaded from a TOML jobs file.
+//!
+//! Each `[[jobs]]` entry names a contract call the keeper sends every
+//! `interval_secs`, optionally only while a view call says it is needed:
+//!
+//! ```toml
+//! [[jobs]]
+//! name = "vault-epoch-rollover"
+//! target = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175"
+//! function = "rollEpoch()"
+//! interval_secs = 60
+//!
+//! [jobs.condition]
+//! function = "epochEnded()"
+//! ```
+//!
+//! `function` takes argument-less signatures; calls with arguments give the
+//! full ABI-encoded `calldata` instead. A condition is met when the first
+//! word it returns is non-zero (a `bool`, or e.g. `checkUpkeep`'s
+//! `upkeepNeeded`).
+
+use alloy::primitives::{Address, Bytes};
+use anyhow::{anyhow, bail, Context, Result};
+use oracle_common::function_selector;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobsFile {
+    pub jobs: Vec<KeeperJob>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeeperJob {
+    /// Name in logs, events and `/status`
+    pub name: String,
+    pub target: Address,
+    /// Argument-less function to call, e.g. `poke()`
+    #[serde(default)]
+    pub function: Option<String>,
+    /// Raw calldata, for calls with arguments
+    #[serde(default)]
+    pub calldata: Option<Bytes>,
+    pub interval_secs: u64,
+    #[serde(default = "default_gas_limit")]
+    pub gas_limit: u64,
+    /// Only send while this view call returns true
+    #[serde(default)]
+    pub condition: Option<JobCondition>,
+}
+
+/// `eth_call` deciding whether a job is due
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobCondition {
+    /// Contract to read; the job's target when unset
+    #[serde(default)]
+    pub contract: Option<Address>,
+    #[serde(default)]
+    pub function: Option<String>,
+    #[serde(default)]
+    pub calldata: Option<Bytes>,
+}
+
+fn default_gas_limit() -> u64 { 500_000 }
+
+/// Calldata from exactly one of `function` / `calldata`
+fn resolve_call(function: &Option<String>, calldata: &Option<Bytes>) -> Result<Bytes> {
+    match (function, calldata) {
+        (Some(_), Some(_)) => bail!("set either function or calldata, not both"),
+        (None, None) => bail!("set function or calldata"),
+        (None, Some(calldata)) if calldata.is_empty() => bail!("calldata is empty"),
+        (None, Some(calldata)) => Ok(calldata.clone()),
+        (Some(function), None) => {
+            let function = function.trim();
+            if !function.ends_with("()") || function.len() <= 2 {
+                bail!("function '{}' must take no arguments - use calldata for calls with arguments", function);
+            }
+            Ok(Bytes::from(function_selector(function).to_vec()))
+        }
+    }
+}
+
+/// Whether a condition call's output means the job is due
+pub fn condition_met(output: &[u8]) -> bool {
+    output.len() >= 32 && output[..32].iter().any(|b| *b != 0)
+}
+
+impl KeeperJob {
+    /// Calldata sent when the job runs
+    pub fn call_data(&self) -> Result<Bytes> {
+        resolve_call(&self.function, &self.calldata).with_context(|| format!("Job '{}'", self.name))
+    }
+
+    /// Contract and calldata of the condition check, if any
+    pub fn condition_call(&self) -> Result<Option<(Address, Bytes)>> {
+        let Some(condition) = &self.condition else {
+            return Ok(None);
+        };
+        let call_data = resolve_call(&condition.function, &condition.calldata)
+            .with_context(|| format!("Job '{}' condition", self.name))?;
+        Ok(Some((condition.contract.unwrap_or(self.target), call_data)))
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+impl JobsFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read jobs file {}", path.display()))?;
+        Self::parse(&raw).with_context(|| format!("Invalid jobs file {}", path.display()))
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        let file: JobsFile = toml::from_str(raw)?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.jobs.is_empty() {
+            bail!("Jobs file defines no jobs");
+        }
+        let mut names = HashSet::new();
+        for job in &self.jobs {
+            if job.name.trim().is_empty() {
+                bail!("A job has an empty name");
+            }
+            if !names.insert(job.name.as_str()) {
+                bail!("Duplicate job name '{}'", job.name);
+            }
+            if job.interval_secs == 0 {
+                return Err(anyhow!("Job '{}' has interval_secs = 0", job.name));
+            }
+            job.call_data()?;
+            job.condition_call()?;
+        }
+        Ok(())
+    }
+}
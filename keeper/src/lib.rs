@@ -0,0 +1,5 @@
+pub mod config;
+pub mod trigger;
+mod app;
+
+pub use app::run;
@@ -0,0 +1,209 @@
+use alloy::primitives::Bytes;
+use alloy::providers::{Provider, RootProvider};
+use alloy::rpc::types::TransactionRequest;
+use alloy::transports::http::{Client, Http};
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    publish_event, trace_completed, trace_fired, CircuitBreaker, OracleEvent, OracleStats, PendingQueue, ReceiptVerifier,
+    ReorgDetector, SharedStats, StatusSource,
+};
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, error, info, warn};
+
+use crate::config::{condition_met, KeeperJob};
+
+/// Condition reads slower than this are treated as failed
+const CONDITION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends one job's call every interval, while its condition (if any) holds
+pub struct KeeperTrigger {
+    job: KeeperJob,
+    call_data: Bytes,
+    condition: Option<(Address, Bytes)>,
+    provider: RootProvider<Http<Client>>,
+    /// When the job is next checked
+    next_due: RwLock<Instant>,
+    in_flight: AtomicBool,
+    /// Result of the last condition read, for `/status`
+    last_condition: RwLock<Option<bool>>,
+    error_control: Arc<OrchestratorErrorControl>,
+    stats: SharedStats,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    /// Set by the reorg detector when an execution was dropped
+    republish: Arc<AtomicBool>,
+}
+
+impl KeeperTrigger {
+    pub fn new(
+        job: KeeperJob,
+        provider: RootProvider<Http<Client>>,
+        error_control: Arc<OrchestratorErrorControl>,
+    ) -> anyhow::Result<Self> {
+        let pending = PendingQueue::for_interval(&job.name, job.interval());
+        Ok(Self {
+            call_data: job.call_data()?,
+            condition: job.condition_call()?,
+            breaker: CircuitBreaker::from_env(job.name.clone()),
+            job,
+            provider,
+            // First check right away
+            next_due: RwLock::new(Instant::now()),
+            in_flight: AtomicBool::new(false),
+            last_condition: RwLock::new(None),
+            error_control,
+            stats: OracleStats::shared(),
+            pending,
+            receipt_verifier: None,
+            reorg_detector: None,
+            republish: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.job.name
+    }
+
+    /// Read the condition; `None` when the read failed
+    async fn check_condition(&self, contract: Address, call_data: &Bytes) -> Option<bool> {
+        let tx = TransactionRequest::default().to(contract).input(call_data.clone().into());
+        let met = match tokio::time::timeout(CONDITION_TIMEOUT, self.provider.call(&tx)).await {
+            Ok(Ok(output)) => Some(condition_met(&output)),
+            Ok(Err(e)) => {
+                warn!("⚠️ Condition of job {} failed: {}", self.job.name, e);
+                None
+            }
+            Err(_) => {
+                warn!("⚠️ Condition of job {} timed out after {:?}", self.job.name, CONDITION_TIMEOUT);
+                None
+            }
+        };
+        *self.last_condition.write() = met;
+        met
+    }
+}
+
+#[async_trait]
+impl TxTrigger for KeeperTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let started = SystemTime::now();
+        if self.error_control.is_worker_pool_paused().await {
+            debug!("Worker pool paused, skipping job {}", self.job.name);
+            return Ok(None);
+        }
+
+        // A reorg dropped the last execution: run the job again now
+        if self.republish.swap(false, Ordering::Relaxed) {
+            *self.next_due.write() = Instant::now();
+        }
+
+        if self.in_flight.load(Ordering::Relaxed) || Instant::now() < *self.next_due.read() {
+            return Ok(None);
+        }
+        // Whatever happens below, the job is next looked at one interval on
+        *self.next_due.write() = Instant::now() + self.job.interval();
+
+        if let Some((contract, call_data)) = &self.condition {
+            match self.check_condition(*contract, call_data).await {
+                Some(true) => {}
+                Some(false) => {
+                    debug!("Job {} not needed", self.job.name);
+                    return Ok(None);
+                }
+                None => return Ok(None),
+            }
+        }
+
+        if !self.breaker.try_acquire() {
+            debug!("Circuit breaker open, skipping job {}", self.job.name);
+            return Ok(None);
+        }
+        self.stats.write().record_trigger();
+
+        info!("🔧 Running job {} on {}", self.job.name, self.job.target);
+
+        let tx_request = TxRequest::new(self.job.target, self.call_data.clone())
+            .with_gas_limit(U256::from(self.job.gas_limit))
+            .with_priority(TxPriority::High)
+            .with_metadata("type", "keeper_job")
+            .with_metadata("job", self.job.name.clone());
+
+        let admitted = self.pending.admit(tx_request).map(|(tx_request, _)| tx_request);
+        if admitted.is_some() {
+            self.in_flight.store(true, Ordering::Relaxed);
+            trace_fired(&self.job.name, &self.call_data, started);
+        }
+        Ok(admitted)
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        self.pending.complete();
+        self.breaker.record(success);
+        self.in_flight.store(false, Ordering::Relaxed);
+        publish_event(OracleEvent::completed(&self.job.name, success, receipt, None, latency, 0));
+        if success {
+            self.stats.write().record_success(0, receipt.map(|r| r.gas_used), latency);
+            if let Some(receipt) = receipt {
+                info!("✅ Job {} done - tx: {}, block: {}", self.job.name, receipt.transaction_hash, receipt.block_number);
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(&self.job.name, receipt, &self.stats);
+                }
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(&self.job.name, receipt, Some(&self.republish));
+                }
+            }
+        } else {
+            // Retried at the next interval, after the condition is re-read
+            self.stats.write().record_failure();
+            error!("❌ Job {} failed", self.job.name);
+        }
+        trace_completed(&self.job.name, receipt, success, started);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: format!("KeeperTrigger[{}]", self.job.name),
+            description: format!("Calls {} every {}s", self.job.target, self.job.interval_secs),
+            trigger_type: "keeper".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for KeeperTrigger {
+    fn status(&self) -> serde_json::Value {
+        let due_in = self.next_due.read().saturating_duration_since(Instant::now());
+        serde_json::json!({
+            "job": self.job.name,
+            "target": self.job.target.to_string(),
+            "interval_secs": self.job.interval_secs,
+            "due_in_secs": due_in.as_secs(),
+            "in_flight": self.in_flight.load(Ordering::Relaxed),
+            "condition": self.condition.as_ref().map(|(contract, _)| contract.to_string()),
+            "last_condition": *self.last_condition.read(),
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
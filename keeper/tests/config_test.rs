@@ -0,0 +1,78 @@
+//! Jobs file parsing and condition results
+
+use keeper::config::{condition_met, JobsFile};
+use oracle_common::function_selector;
+
+const TARGET: &str = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175";
+
+#[test]
+fn test_function_and_condition() {
+    let file = JobsFile::parse(&format!(
+        r#"
+        [[jobs]]
+        name = "rollover"
+        target = "{TARGET}"
+        function = "rollEpoch()"
+        interval_secs = 60
+
+        [jobs.condition]
+        function = "epochEnded()"
+        "#
+    ))
+    .unwrap();
+
+    let job = &file.jobs[0];
+    assert_eq!(job.call_data().unwrap().as_ref(), function_selector("rollEpoch()"));
+    assert_eq!(job.gas_limit, 500_000);
+    let (contract, call_data) = job.condition_call().unwrap().unwrap();
+    assert_eq!(contract, job.target);
+    assert_eq!(call_data.as_ref(), function_selector("epochEnded()"));
+}
+
+#[test]
+fn test_raw_calldata_without_condition() {
+    let file = JobsFile::parse(&format!(
+        r#"
+        [[jobs]]
+        name = "harvest"
+        target = "{TARGET}"
+        calldata = "0xddc632620000000000000000000000000000000000000000000000000000000000000001"
+        interval_secs = 3600
+        gas_limit = 900000
+        "#
+    ))
+    .unwrap();
+
+    let job = &file.jobs[0];
+    assert_eq!(job.call_data().unwrap().len(), 36);
+    assert_eq!(job.gas_limit, 900_000);
+    assert!(job.condition_call().unwrap().is_none());
+}
+
+#[test]
+fn test_invalid_jobs() {
+    let job = |body: &str| format!("[[jobs]]\nname = \"a\"\ntarget = \"{TARGET}\"\n{body}");
+    // Both or neither of function / calldata
+    assert!(JobsFile::parse(&job("function = \"poke()\"\ncalldata = \"0x01\"\ninterval_secs = 1")).is_err());
+    assert!(JobsFile::parse(&job("interval_secs = 1")).is_err());
+    // Functions with arguments need calldata
+    assert!(JobsFile::parse(&job("function = \"poke(uint256)\"\ninterval_secs = 1")).is_err());
+    assert!(JobsFile::parse(&job("function = \"poke()\"\ninterval_secs = 0")).is_err());
+    // Duplicate names
+    let twice = format!("{}\n{}", job("function = \"poke()\"\ninterval_secs = 1"), job("function = \"poke()\"\ninterval_secs = 1"));
+    assert!(JobsFile::parse(&twice).is_err());
+    assert!(JobsFile::parse("jobs = []").is_err());
+}
+
+#[test]
+fn test_condition_met() {
+    let mut word = [0u8; 32];
+    assert!(!condition_met(&word));
+    word[31] = 1;
+    assert!(condition_met(&word));
+    // checkUpkeep returns (bool, bytes): only the first word counts
+    let mut upkeep = vec![0u8; 96];
+    upkeep[63] = 0x40;
+    assert!(!condition_met(&upkeep));
+    assert!(!condition_met(&[]));
+}
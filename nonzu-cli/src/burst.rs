@@ -0,0 +1,147 @@
+//! Burst benchmark: each worker key sends updates back-to-back, starting the
+//! next as soon as the previous receipt returns, for a fixed duration.
+//!
+//! Transactions are signed locally and sent through the same submitter the
+//! oracles use (`SUBMIT_MODE`, HTTP tuning), so the numbers reflect what the
+//! orchestrator can achieve with the same keys and endpoint.
+
+use alloy::consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::TxSigner;
+use alloy::primitives::{Address, Bytes, TxKind, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use nonzu_sdk::submission::TxSubmitter;
+use oracle_common::{submitter_for, ChainConfig, ErrorCategory, PrivateKey};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Outcome of one key's burst
+#[derive(Default)]
+pub struct BurstReport {
+    pub elapsed: Duration,
+    pub keys: usize,
+    pub sent: u64,
+    pub latencies: Vec<Duration>,
+    /// Failed sends by error category
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl BurstReport {
+    fn merge(&mut self, other: BurstReport) {
+        self.sent += other.sent;
+        self.latencies.extend(other.latencies);
+        for (category, count) in other.errors {
+            *self.errors.entry(category).or_default() += count;
+        }
+    }
+
+    pub fn confirmed(&self) -> u64 {
+        self.latencies.len() as u64
+    }
+
+    /// Confirmed updates per second
+    pub fn tps(&self) -> f64 {
+        self.confirmed() as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Send `call()` to `to` from every key for `duration`
+pub async fn run_burst(
+    chain: &ChainConfig,
+    private_keys: &[PrivateKey],
+    to: Address,
+    call: Arc<dyn Fn() -> Bytes + Send + Sync>,
+    gas_limit: u64,
+    duration: Duration,
+) -> Result<BurstReport> {
+    let mut chain = chain.clone();
+    let chain_id = chain.resolve_chain_id().await?;
+    let submitter = submitter_for(&chain)?;
+
+    let started = Instant::now();
+    let deadline = started + duration;
+    let mut tasks = Vec::with_capacity(private_keys.len());
+    for key in private_keys {
+        let signer = key.signer()?;
+        tasks.push(tokio::spawn(burst_key(
+            signer,
+            chain.clone(),
+            chain_id,
+            submitter.clone(),
+            to,
+            call.clone(),
+            gas_limit,
+            deadline,
+        )));
+    }
+
+    let mut report = BurstReport { keys: private_keys.len(), ..Default::default() };
+    for task in tasks {
+        report.merge(task.await??);
+    }
+    report.elapsed = started.elapsed();
+    Ok(report)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn burst_key(
+    signer: PrivateKeySigner,
+    chain: ChainConfig,
+    chain_id: u64,
+    submitter: Arc<dyn TxSubmitter>,
+    to: Address,
+    call: Arc<dyn Fn() -> Bytes + Send + Sync>,
+    gas_limit: u64,
+    deadline: Instant,
+) -> Result<BurstReport> {
+    let provider = ProviderBuilder::new().on_http(chain.rpc_url().parse()?);
+    let mut nonce = provider.get_transaction_count(signer.address()).pending().await?;
+    let mut report = BurstReport::default();
+
+    while Instant::now() < deadline {
+        let raw = sign_call(&signer, &chain, chain_id, nonce, to, call(), gas_limit).await?;
+
+        report.sent += 1;
+        let sent_at = Instant::now();
+        match submitter.submit(raw).await {
+            Ok(_) => {
+                report.latencies.push(sent_at.elapsed());
+                nonce += 1;
+            }
+            Err(e) => {
+                *report.errors.entry(ErrorCategory::classify(&e).to_string()).or_default() += 1;
+                // The nonce may or may not have been consumed - ask the node
+                nonce = provider.get_transaction_count(signer.address()).pending().await?;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Sign an EIP-1559 call at the chain's configured gas price
+pub async fn sign_call(
+    signer: &PrivateKeySigner,
+    chain: &ChainConfig,
+    chain_id: u64,
+    nonce: u64,
+    to: Address,
+    input: Bytes,
+    gas_limit: u64,
+) -> Result<Bytes> {
+    let mut tx = TxEip1559 {
+        chain_id,
+        nonce,
+        gas_limit,
+        max_fee_per_gas: chain.gas_price_wei as u128,
+        max_priority_fee_per_gas: chain.priority_fee_wei as u128,
+        to: TxKind::Call(to),
+        value: U256::ZERO,
+        input,
+        access_list: Default::default(),
+    };
+    let signature = signer.sign_transaction(&mut tx).await?;
+    Ok(TxEnvelope::Eip1559(tx.into_signed(signature)).encoded_2718().into())
+}
@@ -0,0 +1,601 @@
+use alloy::primitives::utils::parse_ether;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use anyhow::Result;
+use nonzu_sdk::submission::TxSubmitter;
+use oracle_common::admin::{authorize_updaters, deploy_oracle, fund_workers, OracleContract};
+use oracle_common::grpc::proto::oracle_client::OracleClient;
+use oracle_common::grpc::proto::ForceUpdateRequest;
+use oracle_common::{
+    encode_commit_chain, encode_relay_block, encode_update_gas_prices, encode_update_price, encode_update_price_with_round, encode_update_timestamp, function_selector, key_addresses, load_private_keys, read_audit_log, rpc_url_from_env, submitter_for,
+    ActualCosts, ChainConfig, CheckStatus, DeadLetter, DeadLetterStore, FeedConfig, FeedCost, FeedsFile, HttpTuning, Preflight,
+    PreflightReport, PreflightTarget, DAY_MS, MONTH_DAYS,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::burst::{run_burst, sign_call};
+use crate::loadtest::{run_load_test, LoadTestConfig};
+use crate::{ContractKind, DeadLetterAction, OracleKind};
+
+pub async fn run(oracle: OracleKind) -> Result<()> {
+    // PREFLIGHT=false skips the checks, e.g. when an RPC is known to be flaky
+    if !matches!(std::env::var("PREFLIGHT").as_deref(), Ok("false" | "0" | "no")) {
+        let report = run_preflight(oracle).await?;
+        if !report.passed() {
+            anyhow::bail!("Preflight failed - fix the checks above or set PREFLIGHT=false to start anyway");
+        }
+    }
+
+    match oracle {
+        OracleKind::TimeOracle => time_oracle::run().await,
+        OracleKind::BinanceOracle => binance_oracle::run().await,
+        OracleKind::GasOracle => gas_oracle::run().await,
+        OracleKind::FxOracle => fx_oracle::run().await,
+        OracleKind::ChainlinkMirror => chainlink_mirror::run().await,
+        OracleKind::RandomnessBeacon => randomness_beacon::run().await,
+        OracleKind::Keeper => keeper::run().await,
+        OracleKind::BlockhashRelay => blockhash_relay::run().await,
+    }
+}
+
+/// Check the RPC, contracts, keys and clock for `oracle` and print the table
+pub async fn preflight(oracle: OracleKind) -> Result<()> {
+    let report = run_preflight(oracle).await?;
+    if !report.passed() {
+        anyhow::bail!("{} preflight check(s) failed", report.count(CheckStatus::Fail));
+    }
+    Ok(())
+}
+
+async fn run_preflight(oracle: OracleKind) -> Result<PreflightReport> {
+    let chain = ChainConfig::from_env()?;
+    let keys = key_addresses(&load_private_keys(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"])?)?;
+    let mut preflight = Preflight::from_env(chain.clone())?.keys(keys);
+    for target in preflight_targets(oracle)? {
+        preflight = preflight.target(target);
+    }
+
+    println!("Preflight for {:?} on {}\n", oracle, chain.name);
+    let report = preflight.run().await;
+    println!("{}", report.table());
+    Ok(report)
+}
+
+/// Contracts each oracle publishes to and the functions it calls on them
+fn preflight_targets(oracle: OracleKind) -> Result<Vec<PreflightTarget>> {
+    let address = |var: &str| -> Result<Address> {
+        Ok(std::env::var(var).map_err(|_| anyhow::anyhow!("{} is not set", var))?.parse()?)
+    };
+    Ok(match oracle {
+        OracleKind::TimeOracle => {
+            let contract = std::env::var("ORACLE_ADDRESS")
+                .or_else(|_| std::env::var("TIME_ORACLE_ADDRESS"))?
+                .parse::<Address>()?;
+            vec![PreflightTarget::new("TimeOracle", contract).function("updateTimestamp(uint256)")]
+        }
+        OracleKind::BinanceOracle => vec![PreflightTarget::new("PriceOracleV2", address("PRICE_ORACLE_V2_ADDRESS")?)
+            .function("updatePrice(string,uint256)")],
+        OracleKind::GasOracle => vec![PreflightTarget::new("GasPriceOracle", address("GAS_ORACLE_ADDRESS")?)
+            .function("updateGasPrices(uint256,uint256,uint256,uint256)")],
+        OracleKind::FxOracle => {
+            let path = std::env::var("FX_FEEDS_FILE").unwrap_or_else(|_| "fx-feeds.toml".to_string());
+            FeedsFile::load(path)?
+                .feeds
+                .iter()
+                .map(|feed| PreflightTarget::new(feed.id.clone(), feed.contract).function(&feed.function))
+                .collect()
+        }
+        OracleKind::ChainlinkMirror => vec![PreflightTarget::new("PriceOracleV2", address("PRICE_ORACLE_V2_ADDRESS")?)
+            .function("updatePriceWithRound(string,uint256,uint80,uint256)")],
+        OracleKind::RandomnessBeacon => vec![PreflightTarget::new("RandomnessBeacon", address("RANDOMNESS_BEACON_ADDRESS")?)
+            .function("commitChain(uint256,bytes32)")
+            .function("reveal(bytes32)")],
+        OracleKind::Keeper => {
+            let path = std::env::var("KEEPER_JOBS_FILE").unwrap_or_else(|_| "keeper.toml".to_string());
+            let mut targets = Vec::new();
+            for job in keeper::config::JobsFile::load(&path)?.jobs {
+                let call_data = job.call_data()?;
+                let mut target = PreflightTarget::new(job.name.clone(), job.target).without_authorization();
+                if let Some(selector) = call_data.get(..4) {
+                    target = target.selector("call", selector.try_into()?);
+                }
+                targets.push(target);
+            }
+            targets
+        }
+        OracleKind::BlockhashRelay => vec![PreflightTarget::new("BlockHashRelay", address("BLOCKHASH_RELAY_ADDRESS")?)
+            .function("relayBlock(uint256,uint256,bytes32,bytes32)")],
+    })
+}
+
+pub async fn deploy(contract: ContractKind, artifact: Option<PathBuf>, env_file: &Path) -> Result<()> {
+    let contract = match contract {
+        ContractKind::TimeOracle => OracleContract::TimeOracle,
+        ContractKind::PriceOracleV2 => OracleContract::PriceOracleV2,
+    };
+    deploy_oracle(&rpc_url_from_env(), contract, artifact.as_deref(), Some(env_file)).await?;
+    Ok(())
+}
+
+pub async fn authorize(oracle: &str) -> Result<()> {
+    authorize_updaters(&rpc_url_from_env(), Address::from_str(oracle)?).await
+}
+
+pub async fn fund(target_eth: &str) -> Result<()> {
+    fund_workers(&rpc_url_from_env(), parse_ether(target_eth)?).await
+}
+
+pub async fn status(url: &str) -> Result<()> {
+    let url = format!("{}/status", url.trim_end_matches('/'));
+    let body: serde_json::Value = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    println!("{}", serde_json::to_string_pretty(&body)?);
+    Ok(())
+}
+
+/// Queue an out-of-band update of `feed` (or a target's feeds) on a running oracle
+pub async fn force_update(url: &str, token: &str, feed: &str) -> Result<()> {
+    let mut client = OracleClient::connect(url.to_string()).await?;
+    let mut request = tonic::Request::new(ForceUpdateRequest { feed: feed.to_string() });
+    request.metadata_mut().insert("authorization", format!("Bearer {}", token).parse()?);
+    let response = client
+        .force_update(request)
+        .await
+        .map_err(|status| anyhow::anyhow!("ForceUpdate refused ({:?}): {}", status.code(), status.message()))?;
+    for feed in response.into_inner().feeds {
+        println!("🔧 {} publishes on its next check", feed);
+    }
+    Ok(())
+}
+
+/// eth_call the update each oracle would send right now, from the first
+/// worker key, and report success or the revert.
+pub async fn simulate(oracle: OracleKind) -> Result<()> {
+    let (contract, call_data) = match oracle {
+        OracleKind::TimeOracle => {
+            let contract = std::env::var("ORACLE_ADDRESS")
+                .or_else(|_| std::env::var("TIME_ORACLE_ADDRESS"))?
+                .parse::<Address>()?;
+            let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            (contract, encode_update_timestamp(now_ms))
+        }
+        OracleKind::BinanceOracle => {
+            let contract = std::env::var("PRICE_ORACLE_V2_ADDRESS")?.parse::<Address>()?;
+            // Any non-zero price exercises authorization and the selector
+            let price = U256::from(1u64) * U256::from(10u64).pow(U256::from(18u64));
+            let selector = function_selector("updatePrice(string,uint256)");
+            (contract, encode_update_price(selector, "BTCUSD", price))
+        }
+        OracleKind::GasOracle => {
+            let contract = std::env::var("GAS_ORACLE_ADDRESS")?.parse::<Address>()?;
+            (contract, sample_gas_update())
+        }
+        OracleKind::FxOracle => {
+            let feed = first_fx_feed()?;
+            (feed.contract, feed.encode_call(U256::from(10u64).pow(U256::from(feed.decimals)))?)
+        }
+        OracleKind::ChainlinkMirror => {
+            let contract = std::env::var("PRICE_ORACLE_V2_ADDRESS")?.parse::<Address>()?;
+            let feed = chainlink_mirror::config::mirror_feeds_from_env()?.remove(0);
+            let selector = function_selector("updatePriceWithRound(string,uint256,uint80,uint256)");
+            let price = U256::from(1u64) * U256::from(10u64).pow(U256::from(18u64));
+            // Highest uint80 so the round is always newer than the stored one
+            let round_id = (1u128 << 80) - 1;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            (contract, encode_update_price_with_round(selector, &feed.id, price, round_id, now))
+        }
+        OracleKind::RandomnessBeacon => {
+            let contract = std::env::var("RANDOMNESS_BEACON_ADDRESS")?.parse::<Address>()?;
+            // A commit always succeeds for an authorized key with an epoch this high
+            let selector = function_selector("commitChain(uint256,bytes32)");
+            (contract, encode_commit_chain(selector, u64::MAX, B256::repeat_byte(0x11)))
+        }
+        OracleKind::Keeper => {
+            let path = std::env::var("KEEPER_JOBS_FILE").unwrap_or_else(|_| "keeper.toml".to_string());
+            let job = keeper::config::JobsFile::load(&path)?.jobs.remove(0);
+            (job.target, job.call_data()?)
+        }
+        OracleKind::BlockhashRelay => {
+            let contract = std::env::var("BLOCKHASH_RELAY_ADDRESS")?.parse::<Address>()?;
+            // A fresh hash on an unused source chain id has no stored parent to contradict
+            let selector = function_selector("relayBlock(uint256,uint256,bytes32,bytes32)");
+            (contract, encode_relay_block(selector, u64::MAX, 1, B256::repeat_byte(0x11), B256::repeat_byte(0x22)))
+        }
+    };
+
+    let keys = load_private_keys(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    let from = keys[0].address()?;
+
+    let provider = ProviderBuilder::new().on_http(rpc_url_from_env().parse()?);
+    let tx = TransactionRequest::default()
+        .from(from)
+        .to(contract)
+        .input(call_data.into());
+
+    println!("Simulating {:?} update to {} from {}", oracle, contract, from);
+    match provider.call(&tx).await {
+        Ok(_) => {
+            let gas = provider.estimate_gas(&tx).await?;
+            println!("✅ Call succeeds (estimated gas: {})", gas);
+            Ok(())
+        }
+        Err(e) => {
+            println!("❌ Call reverts: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+/// A fixed 1 gwei base fee / 0.1 gwei priority fee update
+fn sample_gas_update() -> alloy::primitives::Bytes {
+    let selector = function_selector("updateGasPrices(uint256,uint256,uint256,uint256)");
+    encode_update_gas_prices(selector, 1_000_000_000, 100_000_000, 0, 0)
+}
+
+/// First feed of the FX oracle's feeds file (FX_FEEDS_FILE, default fx-feeds.toml)
+fn first_fx_feed() -> Result<FeedConfig> {
+    let path = std::env::var("FX_FEEDS_FILE").unwrap_or_else(|_| "fx-feeds.toml".to_string());
+    Ok(FeedsFile::load(path)?.feeds.remove(0))
+}
+
+/// Time `requests` eth_blockNumber calls with a fresh client per request
+/// (handshake every time), a default pooled client and the tuned submission
+/// client, and print the latency distribution of each.
+pub async fn bench_rpc(requests: usize, interval_ms: u64) -> Result<()> {
+    let url = rpc_url_from_env();
+    let tuning = HttpTuning::from_env()?;
+    println!("Benchmarking {} ({} requests per client, {}ms apart)", url, requests, interval_ms);
+    println!("Tuned client: {:?}\n", tuning);
+
+    let default_client = reqwest::Client::new();
+    let tuned_client = tuning.build_client()?;
+
+    let cold = bench_client(&url, requests, interval_ms, || reqwest::Client::new()).await?;
+    print_latencies("cold (new connection)", cold);
+    let default = bench_client(&url, requests, interval_ms, || default_client.clone()).await?;
+    print_latencies("default pooled", default);
+    let tuned = bench_client(&url, requests, interval_ms, || tuned_client.clone()).await?;
+    print_latencies("tuned", tuned);
+    Ok(())
+}
+
+async fn bench_client(
+    url: &str,
+    requests: usize,
+    interval_ms: u64,
+    client: impl Fn() -> reqwest::Client,
+) -> Result<Vec<Duration>> {
+    let body = serde_json::json!({ "jsonrpc": "2.0", "method": "eth_blockNumber", "params": [], "id": 1 });
+
+    // One untimed request so pooled clients start warm
+    client().post(url).json(&body).send().await?;
+
+    let mut latencies = Vec::with_capacity(requests);
+    for _ in 0..requests {
+        tokio::time::sleep(Duration::from_millis(interval_ms)).await;
+        let client = client();
+        let started = Instant::now();
+        client.post(url).json(&body).send().await?.bytes().await?;
+        latencies.push(started.elapsed());
+    }
+    Ok(latencies)
+}
+
+fn print_latencies(label: &str, mut latencies: Vec<Duration>) {
+    latencies.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let pct = |p: f64| ms(latencies[((latencies.len() - 1) as f64 * p).round() as usize]);
+    let mean = latencies.iter().map(|d| ms(*d)).sum::<f64>() / latencies.len() as f64;
+    println!(
+        "{:<24} mean {:>7.2}ms  p50 {:>7.2}ms  p90 {:>7.2}ms  p99 {:>7.2}ms  max {:>7.2}ms",
+        label, mean, pct(0.5), pct(0.9), pct(0.99), pct(1.0)
+    );
+}
+
+/// Contract, calldata generator and gas limit for test updates to `oracle`
+type UpdateCall = (Address, Arc<dyn Fn() -> alloy::primitives::Bytes + Send + Sync>, u64);
+
+/// Test updates for `oracle`, refusing oracles where repeated made-up
+/// updates would do damage
+fn update_call(oracle: OracleKind) -> Result<UpdateCall> {
+    let update: UpdateCall = match oracle {
+        OracleKind::TimeOracle => {
+            let contract = std::env::var("ORACLE_ADDRESS")
+                .or_else(|_| std::env::var("TIME_ORACLE_ADDRESS"))?
+                .parse::<Address>()?;
+            let call = Arc::new(|| {
+                let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+                encode_update_timestamp(now_ms)
+            });
+            (contract, call, 60_000)
+        }
+        OracleKind::BinanceOracle => {
+            let contract = std::env::var("PRICE_ORACLE_V2_ADDRESS")?.parse::<Address>()?;
+            let selector = function_selector("updatePrice(string,uint256)");
+            let price = U256::from(1u64) * U256::from(10u64).pow(U256::from(18u64));
+            (contract, Arc::new(move || encode_update_price(selector, "BTCUSD", price)), 300_000)
+        }
+        OracleKind::GasOracle => {
+            let contract = std::env::var("GAS_ORACLE_ADDRESS")?.parse::<Address>()?;
+            (contract, Arc::new(sample_gas_update), 120_000)
+        }
+        OracleKind::FxOracle => {
+            let feed = first_fx_feed()?;
+            let call_data = feed.encode_call(U256::from(10u64).pow(U256::from(feed.decimals)))?;
+            (feed.contract, Arc::new(move || call_data.clone()), feed.gas_limit)
+        }
+        OracleKind::ChainlinkMirror => {
+            // Real updates with made-up round ids would block the mirror until
+            // upstream round ids caught up
+            anyhow::bail!("Test updates to the Chainlink mirror would overwrite its round metadata - use binance-oracle instead");
+        }
+        OracleKind::RandomnessBeacon => {
+            // Reveals chain onto each other, so they cannot be burst-sent
+            anyhow::bail!("Test updates to the randomness beacon would break its hash chain - use binance-oracle instead");
+        }
+        OracleKind::Keeper => {
+            // Maintenance calls have side effects on the target protocol
+            anyhow::bail!("Test updates to the keeper would run its jobs repeatedly - use binance-oracle instead");
+        }
+        OracleKind::BlockhashRelay => {
+            // Made-up hashes would be stored as the source chain's blocks
+            anyhow::bail!("Test updates to the block hash relay would publish fake block hashes - use binance-oracle instead");
+        }
+    };
+    Ok(update)
+}
+
+/// Burst-send updates for `duration` and print throughput, latency and errors
+pub async fn bench(oracle: OracleKind, duration: Duration) -> Result<()> {
+    let (contract, call, gas_limit) = update_call(oracle)?;
+    let chain = ChainConfig::from_env()?;
+    let keys = load_private_keys(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    println!(
+        "Bursting {:?} updates to {} on {} with {} keys for {:?} - these are real transactions",
+        oracle, contract, chain.name, keys.len(), duration
+    );
+
+    let report = run_burst(&chain, &keys, contract, call, gas_limit, duration).await?;
+
+    let failed: u64 = report.errors.values().sum();
+    println!("\nSent {} in {:.1}s: {} confirmed, {} failed", report.sent, report.elapsed.as_secs_f64(), report.confirmed(), failed);
+    println!("Throughput: {:.1} TPS ({:.1} TPS per key)", report.tps(), report.tps() / report.keys as f64);
+    if !report.latencies.is_empty() {
+        print_latencies("latency", report.latencies);
+    }
+    for (category, count) in &report.errors {
+        println!("  {:<20} {}", category, count);
+    }
+    Ok(())
+}
+
+/// Send updates at `config.tps` from `keys` worker keys (all when `None`)
+/// and print achieved throughput, confirmation latency and nonce error rate
+pub async fn loadtest(oracle: OracleKind, keys: Option<usize>, config: LoadTestConfig) -> Result<()> {
+    if !config.tps.is_finite() || config.tps <= 0.0 {
+        anyhow::bail!("--tps must be positive");
+    }
+    if config.max_in_flight == 0 {
+        anyhow::bail!("--max-in-flight must be at least 1");
+    }
+    let (contract, call, gas_limit) = update_call(oracle)?;
+    let chain = ChainConfig::from_env()?;
+    let mut private_keys = load_private_keys(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    if let Some(keys) = keys {
+        if keys == 0 || keys > private_keys.len() {
+            anyhow::bail!("--keys {} but {} worker keys are configured", keys, private_keys.len());
+        }
+        private_keys.truncate(keys);
+    }
+    println!(
+        "Load testing {:?} at {} with {:.1} TPS over {} keys for {:?} (up to {} in flight per key) - these are real transactions",
+        oracle, contract, config.tps, private_keys.len(), config.duration, config.max_in_flight
+    );
+
+    let report = run_load_test(&chain, &private_keys, contract, call, gas_limit, config).await?;
+
+    let failed: u64 = report.errors.values().sum();
+    println!(
+        "\nSent {} in {:.1}s: {} confirmed, {} failed, {} slots skipped with every key at its in-flight limit",
+        report.sent, report.elapsed.as_secs_f64(), report.confirmed(), failed, report.skipped
+    );
+    println!("Throughput: {:.1} TPS achieved of {:.1} TPS target", report.tps(), report.target_tps);
+    if !report.latencies.is_empty() {
+        print_latencies("confirmation latency", report.latencies.clone());
+    }
+    println!("Nonce errors: {} ({:.2}% of sent)", report.nonce_errors(), report.nonce_error_rate() * 100.0);
+    for (category, count) in &report.errors {
+        println!("  {:<20} {}", category, count);
+    }
+    Ok(())
+}
+
+/// Overrides and the actuals source for [`cost_estimate`]
+pub struct CostInputs {
+    pub gas_per_update: Option<u64>,
+    pub gas_price_wei: Option<u64>,
+    pub keys: Option<usize>,
+    pub audit_log: Option<PathBuf>,
+    pub days: u64,
+}
+
+/// Project the daily and monthly gas spend of `feeds_path` per feed and per
+/// key on every target, then compare it with the audit log's recent spend
+pub async fn cost_estimate(feeds_path: &Path, inputs: CostInputs) -> Result<()> {
+    let file = FeedsFile::load(feeds_path)?;
+    let eth = |wei: f64| wei / 1e18;
+    // Feed id -> projected wei per day across targets, as the audit log mixes them
+    let mut projected: BTreeMap<String, f64> = BTreeMap::new();
+
+    for target in file.publish_targets()? {
+        let chain = &target.chain;
+        let (gas_price, price_source) = match inputs.gas_price_wei {
+            Some(price) => (price, "--gas-price-wei"),
+            None => match current_gas_price(chain.rpc_url()).await {
+                Ok(price) => (price, "eth_gasPrice"),
+                Err(e) => {
+                    println!("⚠️ eth_gasPrice on {} failed ({:#}), using the configured gas price", chain.name, e);
+                    (chain.gas_price_wei, "configured")
+                }
+            },
+        };
+        let keys = inputs
+            .keys
+            .or_else(|| load_private_keys(&[target.key_prefix.as_str()]).ok().map(|keys| keys.len()))
+            .filter(|keys| *keys > 0);
+
+        println!(
+            "\n{} - gas price {} wei ({}), {}",
+            chain.name,
+            gas_price,
+            price_source,
+            keys.map(|k| format!("{} key(s)", k)).unwrap_or_else(|| "no keys configured".to_string())
+        );
+        println!("{:<20} {:>10} {:>12} {:>12} {:>14} {:>14}", "feed", "interval", "updates/day", "gas/update", "ETH/day", "ETH/month");
+        let mut daily = 0.0;
+        for feed in &file.feeds {
+            let cost = FeedCost::project(&target.feed_for(feed), inputs.gas_per_update, gas_price);
+            println!(
+                "{:<20} {:>8}ms {:>12.0} {:>12} {:>14.6} {:>14.6}",
+                cost.feed, cost.interval_ms, cost.updates_per_day, cost.gas_per_update, eth(cost.daily_wei()), eth(cost.monthly_wei())
+            );
+            daily += cost.daily_wei();
+            *projected.entry(cost.feed.clone()).or_default() += cost.daily_wei();
+        }
+        println!("{:<57} {:>14.6} {:>14.6}", "total", eth(daily), eth(daily * MONTH_DAYS));
+        if let Some(keys) = keys {
+            let per_key = daily / keys as f64;
+            println!("{:<57} {:>14.6} {:>14.6}", "per key", eth(per_key), eth(per_key * MONTH_DAYS));
+        }
+    }
+    if inputs.gas_per_update.is_none() {
+        println!("\nGas per update is each feed's gas limit - pass --gas-per-update for the average actually used");
+    }
+
+    let Some(audit_log) = &inputs.audit_log else {
+        println!("\nSet AUDIT_LOG_PATH (or --audit-log) to compare with actual spend");
+        return Ok(());
+    };
+    let until_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+    let since_ms = until_ms.saturating_sub(inputs.days * DAY_MS);
+    let actual = ActualCosts::from_entries(&read_audit_log(audit_log)?, since_ms, until_ms);
+    if actual.days == 0.0 {
+        println!("\nNo transactions reached the chain in {} over the last {} day(s)", audit_log.display(), inputs.days);
+        return Ok(());
+    }
+
+    println!("\nActual spend in {} over {:.1} day(s) (gas limit x max fee, an upper bound):", audit_log.display(), actual.days);
+    println!("{:<20} {:>12} {:>14} {:>14} {:>8}", "feed", "tx/day", "ETH/day", "projected", "ratio");
+    let total = ("total".to_string(), actual.total());
+    for (feed, spend) in actual.per_feed.iter().chain([(&total.0, &total.1)]) {
+        let daily = actual.per_day(spend).unwrap_or(0.0);
+        let expected = if feed == &total.0 { Some(projected.values().sum::<f64>()) } else { projected.get(feed).copied() };
+        println!(
+            "{:<20} {:>12.1} {:>14.6} {:>14} {:>8}",
+            feed,
+            spend.transactions as f64 / actual.days,
+            eth(daily),
+            expected.map(|p| format!("{:.6}", eth(p))).unwrap_or_else(|| "-".to_string()),
+            expected.filter(|p| *p > 0.0).map(|p| format!("{:.2}x", daily / p)).unwrap_or_else(|| "-".to_string())
+        );
+    }
+
+    println!("\n{:<44} {:>12} {:>14} {:>14}", "key", "tx/day", "ETH/day", "ETH/month");
+    for (key, spend) in &actual.per_key {
+        let daily = actual.per_day(spend).unwrap_or(0.0);
+        println!(
+            "{:<44} {:>12.1} {:>14.6} {:>14.6}",
+            key.to_string(),
+            spend.transactions as f64 / actual.days,
+            eth(daily),
+            eth(daily * MONTH_DAYS)
+        );
+    }
+    Ok(())
+}
+
+async fn current_gas_price(rpc_url: &str) -> Result<u64> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
+    Ok(u64::try_from(provider.get_gas_price().await?)?)
+}
+
+pub async fn dead_letters(path: &Path, action: DeadLetterAction) -> Result<()> {
+    let letters = DeadLetterStore::read(path)?;
+    let find = |tx_hash: &str| -> Result<DeadLetter> {
+        let tx_hash = B256::from_str(tx_hash)?;
+        letters
+            .iter()
+            .rev()
+            .find(|l| l.tx_hash == tx_hash)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No dead letter for {} in {}", tx_hash, path.display()))
+    };
+
+    match action {
+        DeadLetterAction::List { limit } => {
+            let mut by_category: BTreeMap<&str, u64> = BTreeMap::new();
+            for letter in &letters {
+                *by_category.entry(letter.category.as_str()).or_default() += 1;
+            }
+            println!("{} failed submissions in {}", letters.len(), path.display());
+            for (category, count) in &by_category {
+                println!("  {:<20} {}", category, count);
+            }
+
+            let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            println!();
+            for letter in letters.iter().rev().take(limit) {
+                let error: String = letter.error.chars().take(80).collect();
+                println!(
+                    "{:>8.0}s ago  {}  {:<18} nonce {:<8} {}",
+                    now_ms.saturating_sub(letter.failed_at_ms) as f64 / 1000.0,
+                    letter.tx_hash,
+                    letter.category,
+                    letter.nonce.map_or_else(|| "-".to_string(), |n| n.to_string()),
+                    error
+                );
+            }
+        }
+        DeadLetterAction::Show { tx_hash } => {
+            println!("{}", serde_json::to_string_pretty(&find(&tx_hash)?)?);
+        }
+        DeadLetterAction::Resubmit { tx_hash, fresh_nonce } => {
+            let letter = find(&tx_hash)?;
+            let mut chain = ChainConfig::from_env()?;
+            let submitter = submitter_for(&chain)?;
+
+            let raw = if fresh_nonce {
+                let from = letter.from.ok_or_else(|| anyhow::anyhow!("Record has no sender"))?;
+                let to = letter.to.ok_or_else(|| anyhow::anyhow!("Record has no recipient"))?;
+                let keys = load_private_keys(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+                let signer = keys
+                    .iter()
+                    .filter_map(|k| k.signer().ok())
+                    .find(|s| s.address() == from)
+                    .ok_or_else(|| anyhow::anyhow!("No worker key for {}", from))?;
+                let chain_id = chain.resolve_chain_id().await?;
+                let provider = ProviderBuilder::new().on_http(chain.rpc_url().parse()?);
+                let nonce = provider.get_transaction_count(from).pending().await?;
+                println!("Re-signing {} from {} with nonce {}", letter.tx_hash, from, nonce);
+                let gas_limit = letter.gas_limit.unwrap_or(300_000);
+                sign_call(&signer, &chain, chain_id, nonce, to, letter.calldata.clone(), gas_limit).await?
+            } else {
+                println!("Re-sending {} as signed (nonce {:?})", letter.tx_hash, letter.nonce);
+                letter.raw_tx.clone()
+            };
+
+            let receipt = submitter.submit(raw).await.map_err(|e| anyhow::anyhow!("Resubmission failed: {}", e))?;
+            println!(
+                "{} {} in block {}",
+                if receipt.is_success() { "Confirmed" } else { "Reverted" },
+                receipt.transaction_hash,
+                receipt.block_number
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Load test: updates sent at a fixed total rate, round-robin over the worker
+//! keys, for a fixed duration.
+//!
+//! Unlike the burst benchmark the send rate doesn't wait for receipts: each
+//! key keeps up to `max_in_flight` updates pipelined on locally tracked
+//! nonces, as the oracles do, so the report shows whether the endpoint keeps
+//! up with a target update frequency and how often pipelined nonces collide.
+//! Slots that find every key at its in-flight limit are skipped and counted.
+
+use alloy::primitives::{Address, Bytes};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use nonzu_sdk::submission::TxSubmitter;
+use oracle_common::{submitter_for, ChainConfig, ErrorCategory, PrivateKey};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use crate::burst::sign_call;
+
+pub struct LoadTestConfig {
+    /// Updates per second across all keys
+    pub tps: f64,
+    pub duration: Duration,
+    /// Unconfirmed updates allowed per key
+    pub max_in_flight: usize,
+}
+
+#[derive(Default)]
+pub struct LoadTestReport {
+    pub elapsed: Duration,
+    pub keys: usize,
+    pub target_tps: f64,
+    pub sent: u64,
+    /// Send slots skipped because every key was at its in-flight limit
+    pub skipped: u64,
+    pub latencies: Vec<Duration>,
+    /// Failed sends by error category
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl LoadTestReport {
+    fn record(&mut self, outcome: std::result::Result<Duration, ErrorCategory>) {
+        match outcome {
+            Ok(latency) => self.latencies.push(latency),
+            Err(category) => *self.errors.entry(category.to_string()).or_default() += 1,
+        }
+    }
+
+    pub fn confirmed(&self) -> u64 {
+        self.latencies.len() as u64
+    }
+
+    /// Confirmed updates per second
+    pub fn tps(&self) -> f64 {
+        self.confirmed() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn nonce_errors(&self) -> u64 {
+        [ErrorCategory::NonceTooLow, ErrorCategory::NonceGap]
+            .iter()
+            .filter_map(|category| self.errors.get(category.as_str()))
+            .sum()
+    }
+
+    /// Share of sends rejected for their nonce
+    pub fn nonce_error_rate(&self) -> f64 {
+        if self.sent == 0 {
+            return 0.0;
+        }
+        self.nonce_errors() as f64 / self.sent as f64
+    }
+}
+
+struct KeyState {
+    signer: PrivateKeySigner,
+    next_nonce: AtomicU64,
+    in_flight: AtomicUsize,
+}
+
+/// Send `call()` to `to` at `config.tps` over every key for `config.duration`
+pub async fn run_load_test(
+    chain: &ChainConfig,
+    private_keys: &[PrivateKey],
+    to: Address,
+    call: Arc<dyn Fn() -> Bytes + Send + Sync>,
+    gas_limit: u64,
+    config: LoadTestConfig,
+) -> Result<LoadTestReport> {
+    let mut chain = chain.clone();
+    let chain_id = chain.resolve_chain_id().await?;
+    let submitter = submitter_for(&chain)?;
+    let chain = Arc::new(chain);
+
+    let mut keys = Vec::with_capacity(private_keys.len());
+    for key in private_keys {
+        let signer = key.signer()?;
+        let nonce = pending_nonce(&chain, signer.address()).await?;
+        keys.push(Arc::new(KeyState { signer, next_nonce: AtomicU64::new(nonce), in_flight: AtomicUsize::new(0) }));
+    }
+
+    let mut report = LoadTestReport { keys: keys.len(), target_tps: config.tps, ..Default::default() };
+    let mut sends = JoinSet::new();
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / config.tps));
+    let mut next_key = 0;
+    let started = Instant::now();
+    let deadline = started + config.duration;
+
+    while Instant::now() < deadline {
+        ticker.tick().await;
+        while let Some(outcome) = sends.try_join_next() {
+            report.record(outcome??);
+        }
+
+        let free = (0..keys.len())
+            .map(|i| (next_key + i) % keys.len())
+            .find(|&i| keys[i].in_flight.load(Ordering::Relaxed) < config.max_in_flight);
+        let Some(index) = free else {
+            report.skipped += 1;
+            continue;
+        };
+        next_key = (index + 1) % keys.len();
+
+        let key = keys[index].clone();
+        key.in_flight.fetch_add(1, Ordering::Relaxed);
+        report.sent += 1;
+        sends.spawn(send_update(key, chain.clone(), chain_id, submitter.clone(), to, call(), gas_limit));
+    }
+
+    // Updates still in flight at the deadline count towards the report
+    while let Some(outcome) = sends.join_next().await {
+        report.record(outcome??);
+    }
+    report.elapsed = started.elapsed();
+    Ok(report)
+}
+
+async fn send_update(
+    key: Arc<KeyState>,
+    chain: Arc<ChainConfig>,
+    chain_id: u64,
+    submitter: Arc<dyn TxSubmitter>,
+    to: Address,
+    input: Bytes,
+    gas_limit: u64,
+) -> Result<std::result::Result<Duration, ErrorCategory>> {
+    let nonce = key.next_nonce.fetch_add(1, Ordering::Relaxed);
+    let signed = sign_call(&key.signer, &chain, chain_id, nonce, to, input, gas_limit).await;
+    let outcome = match signed {
+        Ok(raw) => {
+            let sent_at = Instant::now();
+            match submitter.submit(raw).await {
+                Ok(_) => Ok(Ok(sent_at.elapsed())),
+                Err(e) => {
+                    // The nonce may or may not have been consumed - ask the node
+                    if let Ok(nonce) = pending_nonce(&chain, key.signer.address()).await {
+                        key.next_nonce.store(nonce, Ordering::Relaxed);
+                    }
+                    Ok(Err(ErrorCategory::classify(&e)))
+                }
+            }
+        }
+        Err(e) => Err(e),
+    };
+    key.in_flight.fetch_sub(1, Ordering::Relaxed);
+    outcome
+}
+
+async fn pending_nonce(chain: &ChainConfig, address: Address) -> Result<u64> {
+    let provider = ProviderBuilder::new().on_http(chain.rpc_url().parse()?);
+    Ok(provider.get_transaction_count(address).pending().await?)
+}
@@ -0,0 +1,290 @@
+//! `nonzu` - single entry point for running and operating the oracles.
+//!
+//! ```text
+//! nonzu preflight time-oracle
+//! nonzu run time-oracle
+//! nonzu --chaos run time-oracle
+//! nonzu run binance-oracle
+//! nonzu run gas-oracle
+//! nonzu run fx-oracle
+//! nonzu run chainlink-mirror
+//! nonzu deploy time-oracle
+//! nonzu authorize
+//! nonzu fund --target-eth 0.05
+//! nonzu status
+//! nonzu simulate time-oracle
+//! nonzu bench-rpc --requests 200
+//! nonzu bench time-oracle --duration-secs 30
+//! nonzu loadtest binance-oracle --tps 20 --keys 4 --duration-secs 300
+//! nonzu cost-estimate --feeds feeds.toml --gas-per-update 45000
+//! nonzu dead-letters list
+//! ```
+//!
+//! Global flags are applied to the environment before a command runs, so the
+//! oracles keep reading their usual variables.
+
+mod burst;
+mod commands;
+mod loadtest;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "nonzu", about = "Run and operate nonzu oracle deployments", version)]
+pub struct Cli {
+    /// Env file to load instead of ./.env
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Network to target (overrides NETWORK)
+    #[arg(long, global = true, value_enum)]
+    pub network: Option<NetworkArg>,
+
+    /// Exercise the full pipeline without broadcasting transactions
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Inject random failures to rehearse incidents: default rates, or a
+    /// CHAOS spec such as `ws_drop=0.01,delay=0.05:2s,freeze_key=0.01:30s`
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "SPEC")]
+    pub chaos: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum NetworkArg {
+    Testnet,
+    Mainnet,
+    /// Any EVM chain described by CHAIN_ID / RPC_URLS / SYNC_TX
+    Custom,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Check RPC, chain id, contracts, selectors, keys and clock, and print
+    /// a pass/fail table
+    Preflight {
+        #[arg(value_enum)]
+        oracle: OracleKind,
+    },
+    /// Run an oracle until Ctrl+C, after the preflight checks (PREFLIGHT=false skips them)
+    Run {
+        #[arg(value_enum)]
+        oracle: OracleKind,
+    },
+    /// Deploy an oracle contract, authorize the worker keys and record its address
+    Deploy {
+        #[arg(value_enum)]
+        contract: ContractKind,
+        /// Forge artifact (or raw hex) to deploy instead of the embedded bytecode
+        #[arg(long)]
+        artifact: Option<PathBuf>,
+        /// Env file to write the deployed address into (defaults to --config or .env)
+        #[arg(long)]
+        env_file: Option<PathBuf>,
+    },
+    /// Authorize the worker keys as updaters (owner signer from OWNER_SIGNER)
+    Authorize {
+        /// Oracle contract (defaults to PRICE_ORACLE_V2_ADDRESS)
+        #[arg(long, env = "PRICE_ORACLE_V2_ADDRESS")]
+        oracle: String,
+    },
+    /// Top up worker keys from the owner account
+    Fund {
+        /// Target balance per worker in ETH
+        #[arg(long, env = "FUND_TARGET_ETH", default_value = "0.05")]
+        target_eth: String,
+    },
+    /// Query a running oracle's status server
+    Status {
+        /// Status server URL
+        #[arg(long, env = "STATUS_URL", default_value = "http://127.0.0.1:8080")]
+        url: String,
+    },
+    /// Ask a running oracle to publish a feed now, out of band, over its gRPC API
+    ForceUpdate {
+        /// Feed (e.g. `rise/BTCUSD`), or a target to publish all of its feeds
+        feed: String,
+        /// gRPC API of the running oracle
+        #[arg(long, env = "GRPC_URL", default_value = "http://127.0.0.1:50051")]
+        url: String,
+        /// The oracle's GRPC_ADMIN_TOKEN
+        #[arg(long, env = "GRPC_ADMIN_TOKEN", hide_env_values = true)]
+        token: String,
+    },
+    /// eth_call an oracle update from the first worker key without sending it
+    Simulate {
+        #[arg(value_enum)]
+        oracle: OracleKind,
+    },
+    /// Compare RPC round-trip latency with cold, default and tuned HTTP clients
+    BenchRpc {
+        /// Requests per client variant
+        #[arg(long, default_value_t = 200)]
+        requests: usize,
+        /// Delay between requests, mirroring the oracle cadence
+        #[arg(long, default_value_t = 100)]
+        interval_ms: u64,
+    },
+    /// Send updates from every worker key as fast as receipts return and
+    /// report throughput, latency percentiles and errors
+    Bench {
+        #[arg(value_enum)]
+        oracle: OracleKind,
+        /// How long to keep sending
+        #[arg(long, default_value_t = 30)]
+        duration_secs: u64,
+    },
+    /// Send updates at a fixed rate from N keys with pipelined nonces and
+    /// report achieved throughput, confirmation latency and nonce error rate
+    Loadtest {
+        #[arg(value_enum)]
+        oracle: OracleKind,
+        /// Target updates per second across all keys
+        #[arg(long, default_value_t = 10.0)]
+        tps: f64,
+        /// Worker keys to use (defaults to every configured key)
+        #[arg(long)]
+        keys: Option<usize>,
+        /// How long to keep sending
+        #[arg(long, default_value_t = 60)]
+        duration_secs: u64,
+        /// Unconfirmed updates allowed per key; slots beyond it are skipped
+        #[arg(long, default_value_t = 4)]
+        max_in_flight: usize,
+    },
+    /// Project daily and monthly gas spend per feed and per key, and compare
+    /// it with recent actuals from the audit log
+    CostEstimate {
+        /// Feeds file to project
+        #[arg(long, env = "FEEDS_FILE", default_value = "feeds.toml")]
+        feeds: PathBuf,
+        /// Average gas used per update (defaults to each feed's gas limit, an upper bound)
+        #[arg(long)]
+        gas_per_update: Option<u64>,
+        /// Gas price in wei (defaults to each chain's current eth_gasPrice)
+        #[arg(long)]
+        gas_price_wei: Option<u64>,
+        /// Worker keys sharing the spend (defaults to the keys configured per target)
+        #[arg(long)]
+        keys: Option<usize>,
+        /// Audit log with the actual spend
+        #[arg(long, env = "AUDIT_LOG_PATH")]
+        audit_log: Option<PathBuf>,
+        /// Days of the audit log to compare with
+        #[arg(long, default_value_t = 7)]
+        days: u64,
+    },
+    /// Inspect or re-send failed submissions recorded in DEAD_LETTER_PATH
+    DeadLetters {
+        /// Dead-letter file
+        #[arg(long, env = "DEAD_LETTER_PATH", default_value = "dead-letters.jsonl")]
+        path: PathBuf,
+        #[command(subcommand)]
+        action: DeadLetterAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DeadLetterAction {
+    /// Count failures by category and list the newest
+    List {
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Print one record in full
+    Show { tx_hash: String },
+    /// Send a failed transaction again. Updates carrying a stale timestamp
+    /// or price may be rejected by the contract.
+    Resubmit {
+        tx_hash: String,
+        /// Re-sign with the sender's current nonce instead of re-sending the original
+        #[arg(long)]
+        fresh_nonce: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OracleKind {
+    TimeOracle,
+    BinanceOracle,
+    GasOracle,
+    FxOracle,
+    ChainlinkMirror,
+    RandomnessBeacon,
+    Keeper,
+    BlockhashRelay,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ContractKind {
+    TimeOracle,
+    PriceOracleV2,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match &cli.config {
+        Some(path) => {
+            dotenv::from_path(path)?;
+        }
+        None => {
+            dotenv::dotenv().ok();
+        }
+    }
+
+    if let Some(network) = cli.network {
+        let name = match network {
+            NetworkArg::Testnet => "testnet",
+            NetworkArg::Mainnet => "mainnet",
+            NetworkArg::Custom => "custom",
+        };
+        std::env::set_var("NETWORK", name);
+    }
+    if cli.dry_run {
+        std::env::set_var("DRY_RUN", "true");
+    }
+    if let Some(spec) = &cli.chaos {
+        std::env::set_var("CHAOS", spec);
+    }
+
+    oracle_common::install_crypto_provider();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info,nonzu_sdk=warn".into()),
+        )
+        .init();
+
+    match cli.command {
+        Command::Preflight { oracle } => commands::preflight(oracle).await,
+        Command::Run { oracle } => commands::run(oracle).await,
+        Command::Deploy { contract, artifact, env_file } => {
+            let env_file = env_file.or(cli.config).unwrap_or_else(|| PathBuf::from(".env"));
+            commands::deploy(contract, artifact, &env_file).await
+        }
+        Command::Authorize { oracle } => commands::authorize(&oracle).await,
+        Command::Fund { target_eth } => commands::fund(&target_eth).await,
+        Command::Status { url } => commands::status(&url).await,
+        Command::ForceUpdate { feed, url, token } => commands::force_update(&url, &token, &feed).await,
+        Command::Simulate { oracle } => commands::simulate(oracle).await,
+        Command::BenchRpc { requests, interval_ms } => commands::bench_rpc(requests, interval_ms).await,
+        Command::Bench { oracle, duration_secs } => commands::bench(oracle, Duration::from_secs(duration_secs)).await,
+        Command::Loadtest { oracle, tps, keys, duration_secs, max_in_flight } => {
+            let config = loadtest::LoadTestConfig { tps, duration: Duration::from_secs(duration_secs), max_in_flight };
+            commands::loadtest(oracle, keys, config).await
+        }
+        Command::CostEstimate { feeds, gas_per_update, gas_price_wei, keys, audit_log, days } => {
+            let inputs = commands::CostInputs { gas_per_update, gas_price_wei, keys, audit_log, days };
+            commands::cost_estimate(&feeds, inputs).await
+        }
+        Command::DeadLetters { path, action } => commands::dead_letters(&path, action).await,
+    }
+}
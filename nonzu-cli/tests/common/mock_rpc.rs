@@ -0,0 +1,397 @@
+//! Scriptable JSON-RPC node with fault injection.
+//!
+//! Answers the calls the oracles make (chain id, nonces, fees, blocks,
+//! sends and receipts) like a tiny dev chain: every valid transaction is
+//! mined into its own block at once, and out-of-order nonces get the same
+//! errors a real node gives. [`Rule`]s then break specific calls - node
+//! errors, timeouts, slow answers or bogus receipts - for a number of calls
+//! and optionally only for one sender, so the error-handling config can be
+//! exercised end to end. History is bounded so hours-long soak runs don't
+//! measure the mock's own growth.
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{hex, Address, B256, U256};
+use axum::{routing::post, Json, Router};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+pub const MOCK_CHAIN_ID: u64 = 31337;
+
+/// Methods that carry a signed transaction
+const SEND_METHODS: [&str; 2] = ["eth_sendRawTransaction", "eth_sendRawTransactionSync"];
+
+/// Sent transactions and receipts kept; older ones are forgotten
+const HISTORY_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub enum Fault {
+    /// JSON-RPC error object with this code and message
+    Error { code: i64, message: String },
+    /// Hold the request, then answer with a gateway timeout error
+    Timeout(Duration),
+    /// Hold the request, then answer normally
+    Slow(Duration),
+    /// Accept the transaction but return a receipt for block 0 with no gas
+    /// used and someone else's hash
+    BogusReceipt,
+}
+
+impl Fault {
+    pub fn error(code: i64, message: impl Into<String>) -> Self {
+        Fault::Error { code, message: message.into() }
+    }
+
+    pub fn nonce_too_low() -> Self {
+        Self::error(-32000, "nonce too low")
+    }
+
+    /// RISE's wording for a nonce gap
+    pub fn missing_nonce() -> Self {
+        Self::error(
+            -32000,
+            "The transaction was added to the mempool but wasn't processed due to a missing nonce. Please submit a transaction with nonce 0 first.",
+        )
+    }
+
+    pub fn insufficient_funds() -> Self {
+        Self::error(-32000, "insufficient funds for gas * price + value")
+    }
+
+    pub fn revert() -> Self {
+        Self::error(3, "execution reverted")
+    }
+}
+
+/// Apply `fault` to calls of `method`
+#[derive(Debug, Clone)]
+pub struct Rule {
+    method: String,
+    fault: Fault,
+    /// Calls left to break; `None` breaks every call
+    remaining: Option<usize>,
+    /// Only transactions signed by this key (send methods)
+    from: Option<Address>,
+}
+
+impl Rule {
+    pub fn on(method: &str, fault: Fault) -> Self {
+        Self { method: method.to_string(), fault, remaining: None, from: None }
+    }
+
+    /// Any transaction send, sync or not
+    pub fn on_send(fault: Fault) -> Self {
+        Self::on("send", fault)
+    }
+
+    pub fn times(mut self, n: usize) -> Self {
+        self.remaining = Some(n);
+        self
+    }
+
+    pub fn from(mut self, sender: Address) -> Self {
+        self.from = Some(sender);
+        self
+    }
+
+    fn matches(&self, method: &str, sender: Option<Address>) -> bool {
+        let method_matches = self.method == method || (self.method == "send" && SEND_METHODS.contains(&method));
+        method_matches && self.remaining != Some(0) && (self.from.is_none() || self.from == sender)
+    }
+}
+
+/// A transaction the node was sent, accepted or not
+#[derive(Debug, Clone)]
+pub struct SentTx {
+    pub from: Address,
+    pub nonce: u64,
+    pub hash: B256,
+    pub accepted: bool,
+}
+
+#[derive(Default)]
+struct State {
+    rules: Vec<Rule>,
+    calls: HashMap<String, usize>,
+    sent: VecDeque<SentTx>,
+    nonces: HashMap<Address, u64>,
+    receipts: HashMap<B256, Value>,
+    receipt_order: VecDeque<B256>,
+    block: u64,
+}
+
+impl State {
+    fn record(&mut self, tx: SentTx) {
+        if self.sent.len() == HISTORY_LIMIT {
+            self.sent.pop_front();
+        }
+        self.sent.push_back(tx);
+    }
+
+    fn store_receipt(&mut self, hash: B256, receipt: Value) {
+        if self.receipt_order.len() == HISTORY_LIMIT {
+            if let Some(oldest) = self.receipt_order.pop_front() {
+                self.receipts.remove(&oldest);
+            }
+        }
+        self.receipt_order.push_back(hash);
+        self.receipts.insert(hash, receipt);
+    }
+}
+
+pub struct MockRpc {
+    pub url: String,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockRpc {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(State { block: 1, ..Default::default() }));
+        let shared = state.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let state = shared.clone();
+                async move {
+                    Json(match body {
+                        Value::Array(calls) => {
+                            let mut responses = Vec::with_capacity(calls.len());
+                            for call in &calls {
+                                responses.push(handle_call(&state, call).await);
+                            }
+                            Value::Array(responses)
+                        }
+                        call => handle_call(&state, &call).await,
+                    })
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Self { url, state, handle }
+    }
+
+    pub fn inject(&self, rule: Rule) {
+        self.state.lock().rules.push(rule);
+    }
+
+    pub fn clear_faults(&self) {
+        self.state.lock().rules.clear();
+    }
+
+    /// Calls of `method` so far
+    pub fn count(&self, method: &str) -> usize {
+        self.state.lock().calls.get(method).copied().unwrap_or(0)
+    }
+
+    /// Every transaction received, in order (the last `HISTORY_LIMIT`)
+    pub fn sent(&self) -> Vec<SentTx> {
+        self.state.lock().sent.iter().cloned().collect()
+    }
+
+    pub fn accepted(&self) -> Vec<SentTx> {
+        self.sent().into_iter().filter(|tx| tx.accepted).collect()
+    }
+}
+
+impl Drop for MockRpc {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn handle_call(state: &Mutex<State>, call: &Value) -> Value {
+    let id = call["id"].clone();
+    let method = call["method"].as_str().unwrap_or_default().to_string();
+    let params = &call["params"];
+    let tx = SEND_METHODS
+        .contains(&method.as_str())
+        .then(|| decode_tx(params))
+        .flatten();
+
+    let fault = {
+        let mut state = state.lock();
+        *state.calls.entry(method.clone()).or_default() += 1;
+        let sender = tx.as_ref().map(|(from, _)| *from);
+        state.rules.iter_mut().find(|rule| rule.matches(&method, sender)).map(|rule| {
+            if let Some(remaining) = rule.remaining.as_mut() {
+                *remaining -= 1;
+            }
+            rule.fault.clone()
+        })
+    };
+
+    let result = match fault {
+        Some(Fault::Error { code, message }) => {
+            record_rejected(state, tx.as_ref());
+            Err((code, message))
+        }
+        Some(Fault::Timeout(delay)) => {
+            tokio::time::sleep(delay).await;
+            record_rejected(state, tx.as_ref());
+            Err((-32000, "request timed out".to_string()))
+        }
+        Some(Fault::Slow(delay)) => {
+            tokio::time::sleep(delay).await;
+            answer(state, &method, params, tx, false)
+        }
+        Some(Fault::BogusReceipt) => answer(state, &method, params, tx, true),
+        None => answer(state, &method, params, tx, false),
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+    }
+}
+
+/// Sender and envelope of the raw transaction in `params[0]`
+fn decode_tx(params: &Value) -> Option<(Address, TxEnvelope)> {
+    let raw = hex::decode(params[0].as_str()?).ok()?;
+    let tx = TxEnvelope::decode_2718(&mut raw.as_slice()).ok()?;
+    let from = tx.recover_signer().ok()?;
+    Some((from, tx))
+}
+
+fn record_rejected(state: &Mutex<State>, tx: Option<&(Address, TxEnvelope)>) {
+    if let Some((from, tx)) = tx {
+        state.lock().record(SentTx { from: *from, nonce: tx.nonce(), hash: *tx.tx_hash(), accepted: false });
+    }
+}
+
+fn hex_u64(n: u64) -> Value {
+    json!(format!("{:#x}", n))
+}
+
+fn answer(
+    state: &Mutex<State>,
+    method: &str,
+    params: &Value,
+    tx: Option<(Address, TxEnvelope)>,
+    bogus: bool,
+) -> Result<Value, (i64, String)> {
+    let mut state = state.lock();
+    let address = |i: usize| params[i].as_str().and_then(|a| a.parse::<Address>().ok()).unwrap_or_default();
+    Ok(match method {
+        "eth_chainId" => hex_u64(MOCK_CHAIN_ID),
+        "net_version" => json!(MOCK_CHAIN_ID.to_string()),
+        "eth_syncing" => json!(false),
+        "eth_blockNumber" => hex_u64(state.block),
+        "eth_getBlockByNumber" => block(state.block),
+        "eth_gasPrice" => hex_u64(1_000_000_000),
+        "eth_maxPriorityFeePerGas" => hex_u64(1_000_000),
+        "eth_feeHistory" => json!({
+            "oldestBlock": hex_u64(state.block),
+            "baseFeePerGas": [hex_u64(1_000_000), hex_u64(1_000_000)],
+            "gasUsedRatio": [0.5],
+            "reward": [[hex_u64(1_000_000)]],
+        }),
+        "eth_estimateGas" => hex_u64(50_000),
+        "eth_getBalance" => json!(format!("{:#x}", 1_000_000_000_000_000_000_000u128)),
+        "eth_getTransactionCount" => hex_u64(state.nonces.get(&address(0)).copied().unwrap_or(0)),
+        "eth_getCode" => json!("0x6000"),
+        "eth_call" => json!(format!("0x{}", "00".repeat(32))),
+        "eth_getTransactionReceipt" => params[0]
+            .as_str()
+            .and_then(|h| h.parse::<B256>().ok())
+            .and_then(|hash| state.receipts.get(&hash).cloned())
+            .unwrap_or(Value::Null),
+        "eth_sendRawTransaction" | "eth_sendRawTransactionSync" => {
+            let (from, tx) = tx.ok_or((-32602, "invalid raw transaction".to_string()))?;
+            let hash = *tx.tx_hash();
+            let expected = state.nonces.get(&from).copied().unwrap_or(0);
+            let accepted = tx.nonce() == expected;
+            state.record(SentTx { from, nonce: tx.nonce(), hash, accepted });
+            if tx.nonce() < expected {
+                return Err((-32000, "nonce too low".to_string()));
+            }
+            if tx.nonce() > expected {
+                return Err((
+                    -32000,
+                    format!(
+                        "The transaction was added to the mempool but wasn't processed due to a missing nonce. Please submit a transaction with nonce {} first.",
+                        expected
+                    ),
+                ));
+            }
+
+            state.nonces.insert(from, expected + 1);
+            state.block += 1;
+            let receipt = receipt(hash, from, tx.to(), state.block);
+            state.store_receipt(hash, receipt.clone());
+            match (method, bogus) {
+                ("eth_sendRawTransaction", _) => json!(hash),
+                (_, false) => receipt,
+                (_, true) => {
+                    let mut bogus = receipt;
+                    bogus["transactionHash"] = json!(B256::repeat_byte(0xbb));
+                    bogus["blockNumber"] = hex_u64(0);
+                    bogus["gasUsed"] = hex_u64(0);
+                    bogus
+                }
+            }
+        }
+        _ => return Err((-32601, format!("the method {} does not exist/is not available", method))),
+    })
+}
+
+/// Unique per block, and never zero (a zero block hash reads as a bogus receipt)
+fn block_hash(number: u64) -> B256 {
+    B256::from(U256::from(number) + U256::from(1))
+}
+
+fn block(number: u64) -> Value {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    json!({
+        "number": hex_u64(number),
+        "hash": block_hash(number),
+        "parentHash": block_hash(number.saturating_sub(1)),
+        "timestamp": hex_u64(timestamp),
+        "baseFeePerGas": hex_u64(1_000_000),
+        "gasLimit": hex_u64(30_000_000),
+        "gasUsed": hex_u64(0),
+        "miner": Address::ZERO,
+        "difficulty": "0x0",
+        "extraData": "0x",
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "nonce": "0x0000000000000000",
+        "mixHash": B256::ZERO,
+        "sha3Uncles": B256::ZERO,
+        "stateRoot": B256::ZERO,
+        "receiptsRoot": B256::ZERO,
+        "transactionsRoot": B256::ZERO,
+        "size": hex_u64(1000),
+        "totalDifficulty": "0x0",
+        "transactions": [],
+        "uncles": [],
+    })
+}
+
+fn receipt(hash: B256, from: Address, to: Option<Address>, block: u64) -> Value {
+    json!({
+        "transactionHash": hash,
+        "transactionIndex": "0x0",
+        "blockHash": block_hash(block),
+        "blockNumber": hex_u64(block),
+        "from": from,
+        "to": to,
+        "cumulativeGasUsed": hex_u64(30_000),
+        "gasUsed": hex_u64(30_000),
+        "effectiveGasPrice": hex_u64(1_001_000),
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "status": "0x1",
+        "type": "0x2",
+    })
+}
@@ -0,0 +1,124 @@
+//! Anvil harness for end-to-end tests.
+//!
+//! Starts a local anvil node, deploys the real oracle contracts with
+//! `nonzu deploy`'s own code path (the bytecode embedded from the forge
+//! artifacts at build time, worker keys authorized) and points the oracles'
+//! environment at it, so tests read back what the pipeline published
+//! through the contracts' getters.
+//!
+//! [`mock_rpc`] stands in for the node instead when a test needs to break
+//! it on purpose.
+
+#![allow(dead_code)]
+
+pub mod mock_rpc;
+
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::Address;
+use alloy::providers::{ProviderBuilder, RootProvider};
+use alloy::transports::http::{Client, Http};
+use anyhow::Result;
+use oracle_common::admin::{deploy_oracle, OracleContract};
+use std::sync::Once;
+
+/// Worker keys handed to the oracles; the deployer uses the last anvil key
+const WORKER_KEYS: usize = 2;
+
+/// Run an oracle for `duration`; it only returns early on an error
+pub async fn run_for(duration: std::time::Duration, oracle: impl std::future::Future<Output = Result<()>>) {
+    if let Ok(result) = tokio::time::timeout(duration, oracle).await {
+        result.expect("oracle stopped with an error");
+        panic!("oracle stopped before it was shut down");
+    }
+}
+
+/// Install the TLS crypto provider once per test binary
+pub fn init_crypto() {
+    static CRYPTO: Once = Once::new();
+    CRYPTO.call_once(oracle_common::install_crypto_provider);
+}
+
+pub struct Chain {
+    pub anvil: AnvilInstance,
+}
+
+impl Chain {
+    /// Start anvil; panics when it isn't installed
+    pub fn start() -> Self {
+        init_crypto();
+
+        // Zero base fee so the oracles' low default gas price is accepted
+        let anvil = Anvil::new()
+            .arg("--block-base-fee-per-gas")
+            .arg("0")
+            .try_spawn()
+            .unwrap_or_else(|e| panic!("end-to-end tests need anvil (https://getfoundry.sh): {}", e));
+        Self { anvil }
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.anvil.endpoint()
+    }
+
+    /// Deploy `contract` from the embedded bytecode with the last anvil key
+    /// as owner, authorizing the worker keys; call after [`configure_env`](Self::configure_env)
+    pub async fn deploy(&self, contract: OracleContract) -> Result<Address> {
+        let owner = self.anvil.keys().last().expect("anvil has keys");
+        std::env::set_var("OWNER_SIGNER", "key");
+        std::env::set_var("OWNER_PRIVATE_KEY", alloy::hex::encode_prefixed(owner.to_bytes()));
+        deploy_oracle(&self.endpoint(), contract, None, None).await
+    }
+
+    pub fn provider(&self) -> Result<RootProvider<Http<Client>>> {
+        Ok(ProviderBuilder::new().on_http(self.endpoint().parse()?))
+    }
+
+    /// Point the oracles' environment at this chain with anvil's funded keys
+    pub fn configure_env(&self) {
+        let keys: Vec<String> = self.anvil.keys()[..WORKER_KEYS]
+            .iter()
+            .map(|key| alloy::hex::encode_prefixed(key.to_bytes()))
+            .collect();
+        configure_env(&self.endpoint(), self.anvil.chain_id(), &keys);
+    }
+}
+
+/// Point the oracles' environment at `rpc_url` with `keys` as workers, with
+/// background checks that would only slow a test down turned off
+pub fn configure_env(rpc_url: &str, chain_id: u64, keys: &[String]) {
+    let vars = [
+        ("NETWORK", "custom".to_string()),
+        ("RPC_URLS", rpc_url.to_string()),
+        ("CHAIN_ID", chain_id.to_string()),
+        ("SYNC_TX", "false".to_string()),
+        ("RECEIPT_POLL_INTERVAL_MS", "20".to_string()),
+        ("BLOCK_TS_SAMPLE_EVERY", "0".to_string()),
+        ("RECEIPT_VERIFY_BLOCKS", "0".to_string()),
+        ("REORG_DEPTH", "0".to_string()),
+        ("RPC_RATE_LIMIT", "0".to_string()),
+        ("TIME_SOURCE", "system".to_string()),
+    ];
+    for (key, value) in vars {
+        std::env::set_var(key, value);
+    }
+    for key in [
+        "STATUS_ADDR",
+        "DRY_RUN",
+        "SHADOW_MODE",
+        "HEARTBEAT_URLS",
+        "SEQUENCE_NUMBERS",
+        "SUBMIT_MODE",
+        "ERROR_POLICY",
+        "TIME_ORACLE_PRIVATE_KEY_0",
+        "UPDATER_ADDRESSES",
+    ] {
+        std::env::remove_var(key);
+    }
+    // Clear leftovers from an earlier test up to load_private_keys' default limit
+    for index in 0..10 {
+        match keys.get(index) {
+            Some(key) => std::env::set_var(format!("PRIVATE_KEY_{}", index), key),
+            None => std::env::remove_var(format!("PRIVATE_KEY_{}", index)),
+        }
+    }
+}
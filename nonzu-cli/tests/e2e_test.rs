@@ -0,0 +1,99 @@
+//! End-to-end: the time oracle and the Binance TWAP pipeline publishing to
+//! the real contracts on a local anvil chain.
+//!
+//! Ignored by default, as they need anvil installed and the contracts built
+//! before this crate is compiled (their bytecode is embedded at build time):
+//!
+//! ```text
+//! (cd binance-oracle && forge build --contracts PriceOracleV2.sol --out out)
+//! (cd time-oracle/contracts && forge build)
+//! cargo test -p nonzu-cli --test e2e_test -- --ignored
+//! ```
+
+mod common;
+#[path = "../../binance-oracle/tests/common/mod.rs"]
+mod mock_binance;
+
+use alloy::primitives::U256;
+use alloy::providers::Provider;
+use alloy::rpc::types::Filter;
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+use common::{run_for, Chain};
+use mock_binance::{now_ms, MockBinanceServer, Step};
+use oracle_common::admin::OracleContract;
+use std::time::Duration;
+
+sol!(
+    #[sol(rpc)]
+    interface TimeOracle {
+        event TimeUpdated(uint256 indexed timestamp, address indexed updatedBy);
+        function getLatestTimestamp() external view returns (uint256);
+    }
+
+    #[sol(rpc)]
+    interface PriceOracleV2 {
+        function prices(string feedId) external view returns (uint256 price, uint256 lastUpdate, uint256 updateCount);
+    }
+);
+
+/// Both oracles read their configuration from the process environment
+static ENV: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs anvil and the forge artifacts, see the module docs"]
+async fn test_time_oracle_publishes_timestamps() {
+    let _env = ENV.lock().await;
+    let chain = Chain::start();
+    chain.configure_env();
+    let oracle = chain.deploy(OracleContract::TimeOracle).await.unwrap();
+
+    std::env::set_var("ORACLE_ADDRESS", oracle.to_string());
+    std::env::set_var("UPDATE_INTERVAL_MS", "200");
+    let started_ms = now_ms();
+    run_for(Duration::from_secs(4), time_oracle::run()).await;
+
+    let provider = chain.provider().unwrap();
+    let filter = Filter::new().address(oracle).event_signature(TimeOracle::TimeUpdated::SIGNATURE_HASH).from_block(0);
+    let updates = provider.get_logs(&filter).await.unwrap().len();
+    assert!(updates >= 5, "only {} updates in 4s at 200ms", updates);
+
+    let timestamp: u64 = TimeOracle::new(oracle, &provider).getLatestTimestamp().call().await.unwrap()._0.to();
+    assert!(timestamp >= started_ms && timestamp <= now_ms(), "published {} outside the run", timestamp);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs anvil and the forge artifacts, see the module docs"]
+async fn test_twap_pipeline_publishes_price() {
+    let _env = ENV.lock().await;
+    let chain = Chain::start();
+
+    // A steady 50,000 market with a few bad frames mixed in
+    let mut script = Vec::new();
+    for i in 0..40 {
+        script.push(Step::trade("BTCUSDT", 50_000.0, 0.5, 0));
+        script.push(Step::trade("ETHUSDT", 3_000.0, 2.0, 0));
+        if i % 10 == 0 {
+            script.push(Step::Raw("{\"data\":".to_string()));
+        }
+        script.push(Step::Pause(Duration::from_millis(100)));
+    }
+    let binance = MockBinanceServer::start(vec![script]).await;
+
+    chain.configure_env();
+    let oracle = chain.deploy(OracleContract::PriceOracleV2).await.unwrap();
+    std::env::set_var("PRICE_ORACLE_V2_ADDRESS", oracle.to_string());
+    std::env::set_var("BINANCE_WS_URL", &binance.url);
+    std::env::set_var("WARMUP_MIN_TRADES", "1");
+    std::env::set_var("WARMUP_MIN_FILL", "0");
+    run_for(Duration::from_secs(4), binance_oracle::run()).await;
+
+    assert_eq!(binance.connection_count(), 1);
+    let provider = chain.provider().unwrap();
+    let btc = PriceOracleV2::new(oracle, &provider).prices("BTCUSD".to_string()).call().await.unwrap();
+    assert!(btc.updateCount >= U256::from(3), "only {} price updates", btc.updateCount);
+
+    let price: u128 = btc.price.to();
+    let price = price as f64 / 1e18;
+    assert!((price - 50_000.0).abs() < 1e-6, "published BTCUSD {}", price);
+}
@@ -0,0 +1,160 @@
+//! The error-handling config (pauses, nonce resets, key removal, receipt
+//! validation) exercised end to end: the time oracle runs against a mock
+//! node that fails on cue.
+
+mod common;
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use common::mock_rpc::{Fault, MockRpc, Rule, MOCK_CHAIN_ID};
+use common::{configure_env, init_crypto, run_for};
+use oracle_common::{EventBus, EventSubscriber, OracleEvent};
+use parking_lot::Mutex;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// The oracle reads its configuration from the process environment
+static ENV: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+const RUN: Duration = Duration::from_secs(3);
+
+/// Everything published on the event bus since the last `take`
+#[derive(Default)]
+struct Events(Mutex<Vec<OracleEvent>>);
+
+impl EventSubscriber for Events {
+    fn on_event(&self, event: &OracleEvent) {
+        self.0.lock().push(event.clone());
+    }
+}
+
+impl Events {
+    fn take(&self) -> Vec<OracleEvent> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
+
+fn events() -> Arc<Events> {
+    static EVENTS: OnceLock<Arc<Events>> = OnceLock::new();
+    EVENTS
+        .get_or_init(|| {
+            let events = Arc::new(Events::default());
+            EventBus::global().subscribe(events.clone());
+            events
+        })
+        .clone()
+}
+
+fn count(events: &[OracleEvent], kind: &str) -> usize {
+    events.iter().filter(|e| e.kind() == kind).count()
+}
+
+/// Mock node plus two fresh worker keys, with the time oracle's environment
+/// pointed at them and `ERROR_POLICY` set to `policy`
+async fn setup(policy: &str) -> (MockRpc, Vec<Address>) {
+    init_crypto();
+    let rpc = MockRpc::start().await;
+    let signers = [PrivateKeySigner::random(), PrivateKeySigner::random()];
+    let keys: Vec<String> = signers.iter().map(|s| alloy::hex::encode_prefixed(s.to_bytes())).collect();
+
+    configure_env(&rpc.url, MOCK_CHAIN_ID, &keys);
+    std::env::set_var("SYNC_TX", "true");
+    std::env::set_var("SUBMIT_MODE", "sync");
+    std::env::set_var("UPDATE_INTERVAL_MS", "100");
+    std::env::set_var("ORACLE_ADDRESS", Address::repeat_byte(0x42).to_string());
+    std::env::set_var("ERROR_POLICY", policy);
+    events().take();
+    (rpc, signers.iter().map(|s| s.address()).collect())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_nonce_error_pauses_and_resets_nonces() {
+    let _env = ENV.lock().await;
+    let (rpc, _) = setup("nonce_too_low=pause:500ms").await;
+    rpc.inject(Rule::on_send(Fault::nonce_too_low()).times(1));
+
+    run_for(RUN, time_oracle::run()).await;
+
+    let events = events().take();
+    assert!(count(&events, "paused") >= 1, "nonce error paused the workers");
+    assert!(count(&events, "resumed") >= 1, "and they came back after 500ms");
+    // Startup reads every key's nonce once; the reset reads them again
+    assert!(rpc.count("eth_getTransactionCount") > 2, "nonces re-read from chain");
+
+    let sent = rpc.sent();
+    let failed = sent.iter().position(|tx| !tx.accepted).expect("the injected failure");
+    assert!(sent[failed + 1..].iter().filter(|tx| tx.accepted).count() >= 3, "updates land after the reset");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_insufficient_funds_removes_key() {
+    let _env = ENV.lock().await;
+    let (rpc, keys) = setup("insufficient_funds=remove-key").await;
+    rpc.inject(Rule::on_send(Fault::insufficient_funds()).from(keys[0]));
+
+    run_for(RUN, time_oracle::run()).await;
+
+    let events = events().take();
+    assert!(
+        events.iter().any(|e| matches!(e, OracleEvent::KeyRemoved { key } if *key == keys[0])),
+        "the unfunded key is taken out of rotation"
+    );
+    assert_eq!(count(&events, "paused"), 0, "without pausing everyone else");
+
+    let accepted = rpc.accepted();
+    assert!(accepted.len() >= 5, "the funded key keeps publishing");
+    assert!(accepted.iter().all(|tx| tx.from == keys[1]));
+    let attempts_from_removed = rpc.sent().iter().filter(|tx| tx.from == keys[0]).count();
+    assert!(attempts_from_removed <= 2, "removed key sent {} times", attempts_from_removed);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_timeouts_pause_then_recover() {
+    let _env = ENV.lock().await;
+    let (rpc, _) = setup("rpc_timeout=pause:500ms").await;
+    rpc.inject(Rule::on_send(Fault::Timeout(Duration::from_millis(200))).times(2));
+
+    run_for(RUN, time_oracle::run()).await;
+
+    let events = events().take();
+    assert!(count(&events, "paused") >= 1);
+    assert!(count(&events, "resumed") >= 1);
+    assert!(count(&events, "update_failed") >= 1);
+    assert!(rpc.accepted().len() >= 3, "updates resume after the pause");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_slow_responses_still_land() {
+    let _env = ENV.lock().await;
+    let (rpc, _) = setup("").await;
+    rpc.inject(Rule::on_send(Fault::Slow(Duration::from_millis(300))));
+
+    run_for(RUN, time_oracle::run()).await;
+
+    let events = events().take();
+    assert_eq!(count(&events, "update_failed"), 0);
+    assert_eq!(count(&events, "paused"), 0);
+    assert!(count(&events, "update_published") >= 3, "slow sends are still confirmed");
+    assert!(rpc.sent().iter().all(|tx| tx.accepted));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bogus_receipts_count_as_failures() {
+    let _env = ENV.lock().await;
+    // Rejected receipts are `other` errors; skip them rather than pausing 3s
+    let (rpc, _) = setup("other=skip").await;
+    std::env::set_var("RECEIPT_VALIDATION", "basic");
+    rpc.inject(Rule::on_send(Fault::BogusReceipt).times(2));
+
+    run_for(RUN, time_oracle::run()).await;
+    std::env::remove_var("RECEIPT_VALIDATION");
+
+    let events = events().take();
+    let first_published = events.iter().position(|e| e.kind() == "update_published");
+    let failed_before = events[..first_published.unwrap_or(events.len())]
+        .iter()
+        .filter(|e| e.kind() == "update_failed")
+        .count();
+    assert_eq!(failed_before, 2, "both bogus receipts rejected");
+    assert!(first_published.is_some(), "real receipts are accepted afterwards");
+}
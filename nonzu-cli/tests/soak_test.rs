@@ -0,0 +1,151 @@
+//! Soak: the Binance TWAP pipeline against a bursty mock stream and mock
+//! node for hours, sampling RSS, task count and buffer sizes from the
+//! status server and failing once any of them grows past its threshold.
+//!
+//! Ignored by default; run it explicitly:
+//!
+//! ```text
+//! cargo test -p nonzu-cli --test soak_test -- --ignored --nocapture
+//! ```
+//!
+//! Knobs (env): `SOAK_SECS` (default 7200), `SOAK_SAMPLE_SECS` (60),
+//! `SOAK_WARMUP_SECS` (60, before the baseline is taken),
+//! `SOAK_MAX_RSS_GROWTH_MB` (64), `SOAK_MAX_TASK_GROWTH` (50) and
+//! `SOAK_MAX_BUFFER_GROWTH` (10000 trades of buffer capacity).
+
+mod common;
+#[path = "../../binance-oracle/tests/common/mod.rs"]
+mod mock_binance;
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use common::mock_rpc::{MockRpc, MOCK_CHAIN_ID};
+use common::{configure_env, init_crypto};
+use mock_binance::{MockBinanceServer, Step};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+fn knob(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Quiet stretches broken by bursts of a few hundred trades at once
+fn bursty_script() -> Vec<Step> {
+    let mut block = Vec::new();
+    for _ in 0..300 {
+        block.push(Step::trade("BTCUSDT", 50_000.0, 0.01, 0));
+        block.push(Step::trade("ETHUSDT", 3_000.0, 0.1, 0));
+    }
+    for _ in 0..20 {
+        block.push(Step::Pause(Duration::from_millis(250)));
+        block.push(Step::trade("BTCUSDT", 50_000.0, 0.5, 0));
+        block.push(Step::trade("ETHUSDT", 3_000.0, 2.0, 0));
+    }
+    vec![Step::Repeat(block)]
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    rss_bytes: u64,
+    alive_tasks: u64,
+    /// Capacity of the pending trade buffers plus the TWAP windows
+    buffer_capacity: u64,
+    buffered_trades: u64,
+}
+
+impl Sample {
+    fn from_json(runtime: &Value, status: &Value) -> Self {
+        let buffers = &status["buffers"];
+        let stats = buffers["windows"]
+            .as_object()
+            .into_iter()
+            .chain(buffers["pending_trades"].as_object())
+            .flat_map(|symbols| symbols.values());
+        let (mut buffer_capacity, mut buffered_trades) = (0, 0);
+        for stat in stats {
+            buffer_capacity += stat["capacity"].as_u64().unwrap_or(0);
+            buffered_trades += stat["trades"].as_u64().unwrap_or(0);
+        }
+        Self {
+            rss_bytes: runtime["process"]["rss_bytes"].as_u64().unwrap_or(0),
+            alive_tasks: runtime["tokio"]["alive_tasks"].as_u64().unwrap_or(0),
+            buffer_capacity,
+            buffered_trades,
+        }
+    }
+}
+
+async fn sample(client: &reqwest::Client, base: &str) -> Sample {
+    let get = |path: &str| {
+        let url = format!("{}{}", base, path);
+        async move { client.get(url).send().await?.json::<Value>().await }
+    };
+    let runtime = get("/runtime").await.expect("/runtime");
+    let status = get("/status").await.expect("/status");
+    Sample::from_json(&runtime, &status)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "runs for hours; see the module docs"]
+async fn soak_twap_pipeline() {
+    let duration = Duration::from_secs(knob("SOAK_SECS", 7200));
+    let interval = Duration::from_secs(knob("SOAK_SAMPLE_SECS", 60));
+    let warmup = Duration::from_secs(knob("SOAK_WARMUP_SECS", 60));
+    let max_rss_growth = knob("SOAK_MAX_RSS_GROWTH_MB", 64) * 1024 * 1024;
+    let max_task_growth = knob("SOAK_MAX_TASK_GROWTH", 50);
+    let max_buffer_growth = knob("SOAK_MAX_BUFFER_GROWTH", 10_000);
+
+    init_crypto();
+    let rpc = MockRpc::start().await;
+    let binance = MockBinanceServer::start(vec![bursty_script()]).await;
+    let key = alloy::hex::encode_prefixed(PrivateKeySigner::random().to_bytes());
+    let status_addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+    configure_env(&rpc.url, MOCK_CHAIN_ID, &[key]);
+    std::env::set_var("SYNC_TX", "true");
+    std::env::set_var("SUBMIT_MODE", "sync");
+    std::env::set_var("PRICE_ORACLE_V2_ADDRESS", Address::repeat_byte(0x42).to_string());
+    std::env::set_var("BINANCE_WS_URL", &binance.url);
+    std::env::set_var("WARMUP_MIN_TRADES", "1");
+    std::env::set_var("WARMUP_MIN_FILL", "0");
+    std::env::set_var("STATUS_ADDR", status_addr.to_string());
+
+    let oracle = tokio::spawn(binance_oracle::run());
+    let client = reqwest::Client::new();
+    let base = format!("http://{}", status_addr);
+    let started = Instant::now();
+    let mut baseline = None;
+
+    println!("{:>8} {:>10} {:>6} {:>10} {:>8}", "elapsed", "rss_mb", "tasks", "buf_cap", "trades");
+    while started.elapsed() < duration {
+        tokio::time::sleep(interval.min(duration.saturating_sub(started.elapsed()))).await;
+        if oracle.is_finished() {
+            panic!("oracle stopped: {:?}", oracle.await);
+        }
+
+        let now = sample(&client, &base).await;
+        println!(
+            "{:>7}s {:>10.1} {:>6} {:>10} {:>8}",
+            started.elapsed().as_secs(),
+            now.rss_bytes as f64 / (1024.0 * 1024.0),
+            now.alive_tasks,
+            now.buffer_capacity,
+            now.buffered_trades
+        );
+
+        if started.elapsed() < warmup {
+            continue;
+        }
+        let start = *baseline.get_or_insert(now);
+        let rss_growth = now.rss_bytes.saturating_sub(start.rss_bytes);
+        assert!(rss_growth <= max_rss_growth, "RSS grew {} bytes since {:?}", rss_growth, start);
+        let task_growth = now.alive_tasks.saturating_sub(start.alive_tasks);
+        assert!(task_growth <= max_task_growth, "{} more tasks since {:?}", task_growth, start);
+        let buffer_growth = now.buffer_capacity.saturating_sub(start.buffer_capacity);
+        assert!(buffer_growth <= max_buffer_growth, "buffer capacity grew {} since {:?}", buffer_growth, start);
+    }
+
+    oracle.abort();
+    assert!(baseline.is_some(), "SOAK_SECS must be longer than SOAK_WARMUP_SECS");
+    assert!(!rpc.accepted().is_empty(), "prices were published during the soak");
+}
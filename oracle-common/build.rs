@@ -0,0 +1,37 @@
+//! Generates the gRPC bindings in `src/grpc.rs` from `proto/oracle.proto`.
+//! The proto is compiled with protox, so building needs no `protoc`.
+//!
+//! Also embeds the oracle contracts' creation bytecode for `admin::deploy`
+//! from their forge artifacts. A contract whose artifact hasn't been built
+//! is embedded empty, and deploying it then needs an explicit artifact.
+
+use std::path::{Path, PathBuf};
+
+/// Embedded name and forge artifact, relative to the workspace root
+const CONTRACTS: [(&str, &str); 2] = [
+    ("TimeOracle", "time-oracle/contracts/out/TimeOracle.sol/TimeOracle.json"),
+    ("PriceOracleV2", "binance-oracle/out/PriceOracleV2.sol/PriceOracleV2.json"),
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+    let descriptors = protox::compile(["oracle.proto"], ["proto"])?;
+    tonic_build::configure().compile_fds(descriptors)?;
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    for (name, artifact) in CONTRACTS {
+        let artifact = Path::new("..").join(artifact);
+        println!("cargo:rerun-if-changed={}", artifact.display());
+        std::fs::write(out_dir.join(format!("{}.bytecode", name)), artifact_bytecode(&artifact))?;
+    }
+    Ok(())
+}
+
+/// `bytecode.object` of a forge artifact, or empty if it isn't there
+fn artifact_bytecode(artifact: &Path) -> String {
+    let Ok(contents) = std::fs::read_to_string(artifact) else {
+        return String::new();
+    };
+    let json: serde_json::Value = serde_json::from_str(&contents).unwrap_or_default();
+    json["bytecode"]["object"].as_str().unwrap_or_default().to_string()
+}
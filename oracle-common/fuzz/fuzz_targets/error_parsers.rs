@@ -0,0 +1,39 @@
+//! Arbitrary node error messages through the RISE parser pack and the
+//! keyword classifier. Must never panic, and a nonce it extracts must be
+//! one the message actually contains - a made-up nonce would reset the
+//! worker to the wrong value.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nonzu_sdk::RiseError;
+use oracle_common::{ErrorCategory, ErrorParsers};
+use std::sync::OnceLock;
+
+fn parsers() -> &'static ErrorParsers {
+    static PARSERS: OnceLock<ErrorParsers> = OnceLock::new();
+    PARSERS.get_or_init(ErrorParsers::rise)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let message = String::from_utf8_lossy(data);
+
+    if let Some(parsed) = parsers().parse(&message) {
+        for nonce in [parsed.expected_nonce, parsed.tx_nonce].into_iter().flatten() {
+            assert!(
+                message.contains(&nonce.to_string()),
+                "{} extracted nonce {} not in {:?}",
+                parsed.parser,
+                nonce,
+                message
+            );
+        }
+        if matches!(parsed.category, ErrorCategory::NonceGap | ErrorCategory::NonceTooLow) {
+            let lower = message.to_lowercase();
+            assert!(lower.contains("nonce") || lower.contains("already known"), "{:?}", message);
+        }
+    }
+
+    let _ = ErrorCategory::classify_message(&message);
+    let _ = parsers().classify(&RiseError::Rpc(message.into_owned()));
+});
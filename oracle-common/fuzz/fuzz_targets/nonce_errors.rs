@@ -0,0 +1,66 @@
+//! Known nonce error formats with arbitrary nonces and arbitrary text
+//! around them (RPC client wrapping, request ids, trailing JSON). The
+//! nonce the node asked for must come back out exactly.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use oracle_common::{ErrorCategory, ErrorParsers};
+use std::sync::OnceLock;
+
+#[derive(Debug, Arbitrary)]
+enum Format {
+    RiseMissingNonce,
+    GethTooLow,
+    GethTooHigh,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    format: Format,
+    expected: u64,
+    tx_nonce: u64,
+    prefix: String,
+    suffix: String,
+    uppercase: bool,
+}
+
+fn parsers() -> &'static ErrorParsers {
+    static PARSERS: OnceLock<ErrorParsers> = OnceLock::new();
+    PARSERS.get_or_init(ErrorParsers::rise)
+}
+
+fuzz_target!(|input: Input| {
+    // Surrounding text that talks about nonces itself is ambiguous by design
+    if [&input.prefix, &input.suffix].iter().any(|s| s.to_lowercase().contains("nonce")) {
+        return;
+    }
+
+    let (body, category) = match input.format {
+        Format::RiseMissingNonce => (
+            format!(
+                "The transaction was added to the mempool but wasn't processed due to a missing nonce. Please submit a transaction with nonce {} first.",
+                input.expected
+            ),
+            ErrorCategory::NonceGap,
+        ),
+        Format::GethTooLow => (
+            format!("nonce too low: next nonce {}, tx nonce {}", input.expected, input.tx_nonce),
+            ErrorCategory::NonceTooLow,
+        ),
+        Format::GethTooHigh => (
+            format!("nonce too high: next nonce {}, tx nonce {}", input.expected, input.tx_nonce),
+            ErrorCategory::NonceGap,
+        ),
+    };
+    let body = if input.uppercase { body.to_uppercase() } else { body };
+    let message = format!("{}{}{}", input.prefix, body, input.suffix);
+
+    let parsed = parsers().parse(&message).unwrap_or_else(|| panic!("unparsed: {:?}", message));
+    assert_eq!(parsed.category, category, "{:?}", message);
+    assert_eq!(parsed.expected_nonce, Some(input.expected), "{:?}", message);
+    if !matches!(input.format, Format::RiseMissingNonce) {
+        assert_eq!(parsed.tx_nonce, Some(input.tx_nonce), "{:?}", message);
+    }
+});
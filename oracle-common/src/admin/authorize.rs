@@ -0,0 +1,73 @@
+use alloy::primitives::Address;
+use alloy::providers::ProviderBuilder;
+use alloy::sol;
+use anyhow::Result;
+
+use super::signer::{updater_addresses_from_env, OwnerSigner};
+use crate::chain::ChainConfig;
+
+sol!(
+    #[sol(rpc)]
+    interface PriceOracleV2 {
+        function owner() external view returns (address);
+        function authorizedUpdaters(address updater) external view returns (bool);
+        function setAuthorizedUpdater(address updater, bool authorized) external;
+    }
+);
+
+/// Authorize every configured worker key as an updater on `oracle_address`,
+/// signing with the owner signer from the environment.
+pub async fn authorize_updaters(rpc_url: &str, oracle_address: Address) -> Result<()> {
+    // Addresses to authorize (UPDATER_ADDRESSES or derived from worker keys)
+    let addresses_to_authorize = updater_addresses_from_env(&["PRIVATE_KEY_"])?;
+
+    // The chain id is needed up front so the Ledger signs for the right
+    // network, and must match the configured NETWORK / CHAIN_ID
+    let chain_id = ChainConfig::verified_chain_id(rpc_url).await?;
+
+    // Setup provider and owner signer
+    let owner = OwnerSigner::from_env(chain_id).await?;
+    let owner_address = owner.address();
+    println!("Connected with {} wallet: {}", owner.describe(), owner_address);
+
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(owner.into_wallet())
+        .on_http(rpc_url.parse()?);
+
+    let oracle = PriceOracleV2::new(oracle_address, provider);
+
+    println!("Oracle contract: {}", oracle_address);
+
+    // Check if we're the owner
+    let contract_owner = oracle.owner().call().await?._0;
+    println!("Contract owner: {}", contract_owner);
+
+    if contract_owner != owner_address {
+        anyhow::bail!("The configured signer {} is not the contract owner", owner_address);
+    }
+
+    println!("✅ Confirmed: We are the contract owner");
+    println!("\nAuthorizing updaters...");
+
+    for address in addresses_to_authorize {
+        // Check current status
+        let is_authorized = oracle.authorizedUpdaters(address).call().await?._0;
+
+        if is_authorized {
+            println!("✅ {} is already authorized", address);
+        } else {
+            println!("⏳ Authorizing {} (confirm on device if using Ledger)...", address);
+
+            let tx = oracle.setAuthorizedUpdater(address, true);
+            let pending = tx.send().await?;
+            println!("   Transaction sent: {}", pending.tx_hash());
+
+            let receipt = pending.get_receipt().await?;
+            println!("   ✅ Authorized in block {}!", receipt.block_number.unwrap_or_default());
+        }
+    }
+
+    println!("\n✅ Authorization complete!");
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{Address, Bytes};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol;
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::signer::{updater_addresses_from_env, OwnerSigner};
+use crate::chain::ChainConfig;
+
+sol!(
+    #[sol(rpc)]
+    interface TimeOracleAdmin {
+        function isAuthorizedUpdater(address updater) external view returns (bool);
+        function addAuthorizedUpdater(address updater) external;
+    }
+
+    #[sol(rpc)]
+    interface PriceOracleV2Admin {
+        function authorizedUpdaters(address updater) external view returns (bool);
+        function setAuthorizedUpdater(address updater, bool authorized) external;
+    }
+);
+
+/// Oracle contracts the deploy command knows how to bootstrap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleContract {
+    TimeOracle,
+    PriceOracleV2,
+}
+
+/// Creation bytecode embedded from the forge artifacts at build time (see
+/// `build.rs`), empty when the artifact wasn't built
+const TIME_ORACLE_BYTECODE: &str = include_str!(concat!(env!("OUT_DIR"), "/TimeOracle.bytecode"));
+const PRICE_ORACLE_V2_BYTECODE: &str = include_str!(concat!(env!("OUT_DIR"), "/PriceOracleV2.bytecode"));
+
+impl OracleContract {
+    /// Where `forge build` writes the artifact for this contract
+    pub fn default_artifact(&self) -> PathBuf {
+        match self {
+            OracleContract::TimeOracle => "time-oracle/contracts/out/TimeOracle.sol/TimeOracle.json".into(),
+            OracleContract::PriceOracleV2 => "binance-oracle/out/PriceOracleV2.sol/PriceOracleV2.json".into(),
+        }
+    }
+
+    /// Creation bytecode embedded in this build
+    pub fn embedded_bytecode(&self) -> Result<Bytes> {
+        let hex = match self {
+            OracleContract::TimeOracle => TIME_ORACLE_BYTECODE,
+            OracleContract::PriceOracleV2 => PRICE_ORACLE_V2_BYTECODE,
+        };
+        if hex.is_empty() {
+            anyhow::bail!(
+                "No {:?} bytecode embedded in this build: run `forge build` to produce {} and rebuild, or pass an artifact",
+                self,
+                self.default_artifact().display()
+            );
+        }
+        Ok(Bytes::from_str(hex)?)
+    }
+
+    /// Env variable the oracle binaries read the deployed address from
+    pub fn address_env_key(&self) -> &'static str {
+        match self {
+            OracleContract::TimeOracle => "ORACLE_ADDRESS",
+            OracleContract::PriceOracleV2 => "PRICE_ORACLE_V2_ADDRESS",
+        }
+    }
+
+    /// Worker addresses to authorize: UPDATER_ADDRESSES, or the oracle's own worker keys
+    fn updaters(&self) -> Result<Vec<Address>> {
+        match self {
+            OracleContract::PriceOracleV2 => updater_addresses_from_env(&["PRIVATE_KEY_"]),
+            OracleContract::TimeOracle => updater_addresses_from_env(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"]),
+        }
+    }
+}
+
+/// Read creation bytecode from a forge artifact (`bytecode.object`) or a raw hex file.
+pub fn load_bytecode(artifact: &Path) -> Result<Bytes> {
+    let contents = std::fs::read_to_string(artifact)
+        .with_context(|| format!("Failed to read artifact {} (run `forge build` first)", artifact.display()))?;
+
+    let hex = match serde_json::from_str::<serde_json::Value>(&contents) {
+        Ok(json) => json["bytecode"]["object"]
+            .as_str()
+            .or_else(|| json["bytecode"].as_str())
+            .ok_or_else(|| anyhow!("No bytecode in artifact {}", artifact.display()))?
+            .to_string(),
+        Err(_) => contents.trim().to_string(),
+    };
+
+    let bytecode = Bytes::from_str(&hex)?;
+    if bytecode.is_empty() {
+        anyhow::bail!("Artifact {} has empty bytecode", artifact.display());
+    }
+    Ok(bytecode)
+}
+
+/// Set `key=value` in an env file, replacing an existing entry or appending one.
+pub fn write_env_value(path: &Path, key: &str, value: &str) -> Result<()> {
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let prefix = format!("{}=", key);
+    let mut replaced = false;
+
+    let mut lines: Vec<String> = existing
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with(&prefix) {
+                replaced = true;
+                format!("{}{}", prefix, value)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    if !replaced {
+        lines.push(format!("{}{}", prefix, value));
+    }
+
+    std::fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+/// Deploy `contract` with the owner signer, authorize the configured worker
+/// keys and, if `env_file` is given, record the address there. The bytecode
+/// comes from `artifact` if given, else from the build.
+pub async fn deploy_oracle(
+    rpc_url: &str,
+    contract: OracleContract,
+    artifact: Option<&Path>,
+    env_file: Option<&Path>,
+) -> Result<Address> {
+    let bytecode = match artifact {
+        Some(artifact) => load_bytecode(artifact)?,
+        None => contract.embedded_bytecode()?,
+    };
+    let updaters = contract.updaters()?;
+
+    let chain_id = ChainConfig::verified_chain_id(rpc_url).await?;
+
+    let owner = OwnerSigner::from_env(chain_id).await?;
+    println!("Deploying {:?} from {} wallet: {}", contract, owner.describe(), owner.address());
+    match artifact {
+        Some(artifact) => println!("Artifact: {} ({} bytes)", artifact.display(), bytecode.len()),
+        None => println!("Embedded bytecode ({} bytes)", bytecode.len()),
+    }
+
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(owner.into_wallet())
+        .on_http(rpc_url.parse()?);
+
+    println!("⏳ Sending deployment (confirm on device if using Ledger)...");
+    let tx = TransactionRequest::default().with_deploy_code(bytecode);
+    let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+    let address = receipt
+        .contract_address
+        .ok_or_else(|| anyhow!("Deployment receipt has no contract address"))?;
+    println!("✅ Deployed at {} in block {}", address, receipt.block_number.unwrap_or_default());
+
+    println!("\nAuthorizing updaters...");
+    for updater in updaters {
+        let pending = match contract {
+            OracleContract::TimeOracle => {
+                let oracle = TimeOracleAdmin::new(address, &provider);
+                if oracle.isAuthorizedUpdater(updater).call().await?._0 {
+                    println!("✅ {} is already authorized", updater);
+                    continue;
+                }
+                oracle.addAuthorizedUpdater(updater).send().await?
+            }
+            OracleContract::PriceOracleV2 => {
+                let oracle = PriceOracleV2Admin::new(address, &provider);
+                if oracle.authorizedUpdaters(updater).call().await?._0 {
+                    println!("✅ {} is already authorized", updater);
+                    continue;
+                }
+                oracle.setAuthorizedUpdater(updater, true).send().await?
+            }
+        };
+        println!("⏳ Authorizing {} (tx {})...", updater, pending.tx_hash());
+        pending.get_receipt().await?;
+        println!("   ✅ Authorized");
+    }
+
+    if let Some(path) = env_file {
+        write_env_value(path, contract.address_env_key(), &address.to_string())?;
+        println!("\n📝 Wrote {}={} to {}", contract.address_env_key(), address, path.display());
+    }
+
+    println!("\n✅ Deployment complete!");
+    Ok(address)
+}
@@ -0,0 +1,52 @@
+use alloy::network::TransactionBuilder;
+use alloy::primitives::utils::format_ether;
+use alloy::primitives::U256;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use anyhow::Result;
+
+use super::signer::{updater_addresses_from_env, OwnerSigner};
+use crate::chain::ChainConfig;
+
+/// Top up every worker key to `target` wei from the owner account.
+pub async fn fund_workers(rpc_url: &str, target: U256) -> Result<()> {
+    let workers = updater_addresses_from_env(&["PRIVATE_KEY_"])?;
+
+    let chain_id = ChainConfig::verified_chain_id(rpc_url).await?;
+
+    let owner = OwnerSigner::from_env(chain_id).await?;
+    let owner_address = owner.address();
+    println!("Funding from {} wallet: {}", owner.describe(), owner_address);
+
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(owner.into_wallet())
+        .on_http(rpc_url.parse()?);
+
+    println!("Owner balance: {} ETH", format_ether(provider.get_balance(owner_address).await?));
+    println!("Target balance per worker: {} ETH\n", format_ether(target));
+
+    for worker in workers {
+        let balance = provider.get_balance(worker).await?;
+
+        if balance >= target {
+            println!("✅ {} has {} ETH", worker, format_ether(balance));
+            continue;
+        }
+
+        let top_up = target - balance;
+        println!("⏳ Sending {} ETH to {} (confirm on device if using Ledger)...", format_ether(top_up), worker);
+
+        let tx = TransactionRequest::default()
+            .with_to(worker)
+            .with_value(top_up);
+        let pending = provider.send_transaction(tx).await?;
+        println!("   Transaction sent: {}", pending.tx_hash());
+
+        let receipt = pending.get_receipt().await?;
+        println!("   ✅ Funded in block {}!", receipt.block_number.unwrap_or_default());
+    }
+
+    println!("\n✅ Funding complete!");
+    Ok(())
+}
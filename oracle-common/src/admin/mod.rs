@@ -0,0 +1,12 @@
+//! Owner (admin) operations: deploying oracles, authorizing updaters and
+//! funding worker keys.
+
+pub mod authorize;
+pub mod deploy;
+pub mod fund;
+pub mod signer;
+
+pub use authorize::*;
+pub use deploy::*;
+pub use fund::*;
+pub use signer::*;
@@ -0,0 +1,95 @@
+//! Owner signer selection for the admin operations (authorize, fund).
+//!
+//! With `OWNER_SIGNER=ledger` the contract owner key stays on a Ledger device
+//! and every owner transaction is confirmed on the device, so the key never
+//! exists as plaintext on the deployment box. `OWNER_SIGNER=key` (the default)
+//! keeps the old `OWNER_PRIVATE_KEY` behaviour for local/testnet work.
+
+use alloy::network::EthereumWallet;
+use alloy::primitives::Address;
+use alloy::signers::ledger::{HDPath, LedgerSigner};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use anyhow::{anyhow, Result};
+use std::env;
+use std::str::FromStr;
+
+use crate::key_rotation::key_addresses;
+use crate::keys::{load_private_keys, PrivateKey};
+
+pub enum OwnerSigner {
+    Ledger(LedgerSigner),
+    Local(PrivateKeySigner),
+}
+
+impl OwnerSigner {
+    /// Build the owner signer from `OWNER_SIGNER`, `LEDGER_HD_PATH` /
+    /// `LEDGER_ACCOUNT_INDEX` or `OWNER_PRIVATE_KEY`.
+    pub async fn from_env(chain_id: u64) -> Result<Self> {
+        let kind = env::var("OWNER_SIGNER").unwrap_or_else(|_| "key".to_string());
+
+        match kind.as_str() {
+            "ledger" => {
+                let path = match env::var("LEDGER_HD_PATH") {
+                    Ok(path) => HDPath::Other(path),
+                    Err(_) => {
+                        let index = env::var("LEDGER_ACCOUNT_INDEX")
+                            .unwrap_or_else(|_| "0".to_string())
+                            .parse::<usize>()?;
+                        HDPath::LedgerLive(index)
+                    }
+                };
+
+                println!("🔐 Connecting to Ledger ({:?})...", path);
+                let signer = LedgerSigner::new(path, Some(chain_id))
+                    .await
+                    .map_err(|e| anyhow!("Failed to open Ledger device: {}", e))?;
+                Ok(Self::Ledger(signer))
+            }
+            "key" => {
+                let key = PrivateKey::from_env("OWNER_PRIVATE_KEY")
+                    .ok_or_else(|| anyhow!("OWNER_PRIVATE_KEY must be set when OWNER_SIGNER=key"))?;
+                let signer = key.signer()?.with_chain_id(Some(chain_id));
+                Ok(Self::Local(signer))
+            }
+            other => Err(anyhow!(
+                "Unknown OWNER_SIGNER '{}' (expected 'ledger' or 'key')",
+                other
+            )),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Ledger(signer) => signer.address(),
+            Self::Local(signer) => signer.address(),
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Ledger(_) => "Ledger",
+            Self::Local(_) => "local key",
+        }
+    }
+
+    pub fn into_wallet(self) -> EthereumWallet {
+        match self {
+            Self::Ledger(signer) => EthereumWallet::new(signer),
+            Self::Local(signer) => EthereumWallet::new(signer),
+        }
+    }
+}
+
+/// Worker (updater) addresses: `UPDATER_ADDRESSES` if set, otherwise derived
+/// from the worker keys under `prefixes`, loaded exactly as the oracles load
+/// them (see [`load_private_keys`]).
+pub fn updater_addresses_from_env(prefixes: &[&str]) -> Result<Vec<Address>> {
+    if let Ok(list) = env::var("UPDATER_ADDRESSES") {
+        return list
+            .split(',')
+            .map(|s| Address::from_str(s.trim()).map_err(Into::into))
+            .collect();
+    }
+    key_addresses(&load_private_keys(prefixes)?)
+}
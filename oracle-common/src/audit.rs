@@ -0,0 +1,331 @@
+//! Append-only audit log of every submitted transaction.
+//!
+//! With `AUDIT_LOG_PATH` set, each transaction handed to the RPC is appended
+//! to a JSON-lines file once its outcome is known: when it was sent, the
+//! chain and feed, sender, nonce, gas parameters, full calldata, and whether
+//! it confirmed, reverted or failed. Unlike the dead-letter file nothing is
+//! ever trimmed; a file larger than `AUDIT_LOG_MAX_BYTES` is renamed to
+//! `<path>.<unix ms>` and a fresh one started.
+//!
+//! With `AUDIT_LOG_CHAIN=true` entries are hash-chained: each carries the
+//! previous entry's hash and `keccak256(prev_hash ++ entry)`, continuing
+//! across restarts and rotations, so a removed or edited line breaks the
+//! chain. [`AuditLog::verify`] checks a file.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `AUDIT_LOG_PATH` | unset | Audit log file (unset disables) |
+//! | `AUDIT_LOG_MAX_BYTES` | `104857600` | Size at which the file is rotated |
+//! | `AUDIT_LOG_CHAIN` | `false` | Hash-chain entries |
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{keccak256, Address, Bytes, B256, U256};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+
+use crate::error_parsers::ErrorParsers;
+
+const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// One submitted transaction and its outcome
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the log, counting from 1 across restarts and rotations
+    pub seq: u64,
+    pub submitted_at_ms: u64,
+    pub completed_at_ms: u64,
+    pub chain: String,
+    /// Feed id, when the call leads with a `string` id
+    pub feed: Option<String>,
+    pub tx_hash: B256,
+    /// Sending key
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub nonce: Option<u64>,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    pub calldata: Bytes,
+    /// `confirmed`, `reverted` or `error`
+    pub result: String,
+    pub block_number: Option<u64>,
+    /// Error category (see `ErrorCategory`) and message for failures
+    pub category: Option<String>,
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<B256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<B256>,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl AuditEntry {
+    /// Entry for `raw_tx` sent on `chain`, decoding what the envelope tells us
+    pub fn new(raw_tx: &Bytes, chain: &str, submitted_at_ms: u64) -> Self {
+        let mut entry = Self {
+            submitted_at_ms,
+            chain: chain.to_string(),
+            tx_hash: keccak256(raw_tx),
+            ..Default::default()
+        };
+        if let Ok(envelope) = TxEnvelope::decode_2718(&mut raw_tx.as_ref()) {
+            entry.from = envelope.recover_signer().ok();
+            entry.to = envelope.to();
+            entry.nonce = Some(envelope.nonce());
+            entry.gas_limit = Some(envelope.gas_limit());
+            entry.max_fee_per_gas = Some(envelope.max_fee_per_gas());
+            entry.max_priority_fee_per_gas = envelope.max_priority_fee_per_gas();
+            entry.calldata = envelope.input().clone();
+            entry.feed = leading_string(&entry.calldata);
+        }
+        entry
+    }
+
+    /// Fill in the outcome of the submission
+    pub fn with_result(mut self, result: &Result<SyncTransactionReceipt, RiseError>, parsers: &ErrorParsers) -> Self {
+        self.completed_at_ms = unix_ms();
+        match result {
+            Ok(receipt) => {
+                self.result = if receipt.is_success() { "confirmed" } else { "reverted" }.to_string();
+                self.block_number = Some(receipt.block_number.saturating_to());
+            }
+            Err(e) => {
+                self.result = "error".to_string();
+                self.category = Some(parsers.classify(e).as_str().to_string());
+                self.error = Some(e.to_string());
+            }
+        }
+        self
+    }
+
+    /// `keccak256(prev_hash ++ entry)`, the entry serialized without its hash
+    fn chain_hash(&self) -> Result<B256> {
+        let body = serde_json::to_vec(&Self { hash: None, ..self.clone() })?;
+        Ok(keccak256([self.prev_hash.unwrap_or_default().as_slice(), &body].concat()))
+    }
+}
+
+/// The leading `string` argument of `calldata` - the feed id of
+/// `updatePrice(string,...)`-style calls
+fn leading_string(calldata: &[u8]) -> Option<String> {
+    let args = calldata.get(4..)?;
+    let bytes = |at: usize, len: usize| args.get(at..at.checked_add(len)?);
+    let word = |at: usize| -> Option<usize> { U256::from_be_slice(bytes(at, 32)?).try_into().ok() };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let id = std::str::from_utf8(bytes(offset.checked_add(32)?, len)?).ok()?;
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_graphic())).then(|| id.to_string())
+}
+
+/// Appends entries on a background thread
+pub struct AuditLog {
+    path: PathBuf,
+    sender: mpsc::Sender<AuditEntry>,
+}
+
+struct Writer {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    chain: bool,
+    seq: u64,
+    last_hash: Option<B256>,
+}
+
+impl Writer {
+    fn append(&mut self, mut entry: AuditEntry) -> Result<()> {
+        self.seq += 1;
+        entry.seq = self.seq;
+        if self.chain {
+            entry.prev_hash = Some(self.last_hash.unwrap_or_default());
+            let hash = entry.chain_hash()?;
+            entry.hash = Some(hash);
+            self.last_hash = Some(hash);
+        }
+        let line = serde_json::to_string(&entry)? + "\n";
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        let stamp = unix_ms();
+        // Never overwrite an earlier rotation
+        let rotated = (0..)
+            .map(|n| match n {
+                0 => PathBuf::from(format!("{}.{}", self.path.display(), stamp)),
+                n => PathBuf::from(format!("{}.{}-{}", self.path.display(), stamp, n)),
+            })
+            .find(|candidate| !candidate.exists())
+            .expect("unbounded candidates");
+        std::fs::rename(&self.path, &rotated).with_context(|| format!("Failed to rotate {}", self.path.display()))?;
+        info!("🗄️ Audit log rotated to {}", rotated.display());
+        self.file = AuditLog::append_handle(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl AuditLog {
+    /// Open (or create) the log at `path`, continuing its sequence and chain
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64, chain: bool) -> Result<Arc<Self>> {
+        let path = path.into();
+        let last = Self::last_entry(&path)?;
+        let mut writer = Writer {
+            file: Self::append_handle(&path)?,
+            size: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path: path.clone(),
+            max_bytes: max_bytes.max(1),
+            chain,
+            seq: last.as_ref().map_or(0, |entry| entry.seq),
+            last_hash: last.and_then(|entry| entry.hash),
+        };
+
+        let (sender, receiver) = mpsc::channel::<AuditEntry>();
+        std::thread::Builder::new().name("audit-log-writer".into()).spawn(move || {
+            for entry in receiver {
+                let tx_hash = entry.tx_hash;
+                if let Err(e) = writer.append(entry) {
+                    error!("🚨 Could not write audit entry for {} to {}: {:#}", tx_hash, writer.path.display(), e);
+                }
+            }
+        })?;
+
+        Ok(Arc::new(Self { path, sender }))
+    }
+
+    /// From `AUDIT_LOG_PATH` / `AUDIT_LOG_MAX_BYTES` / `AUDIT_LOG_CHAIN`;
+    /// `None` when unset. Every submitter in the process shares the one log.
+    pub fn from_env() -> Result<Option<Arc<Self>>> {
+        static SHARED: Mutex<Option<Arc<AuditLog>>> = Mutex::new(None);
+
+        let Ok(path) = std::env::var("AUDIT_LOG_PATH") else {
+            return Ok(None);
+        };
+        let mut shared = SHARED.lock();
+        if let Some(log) = shared.as_ref() {
+            return Ok(Some(log.clone()));
+        }
+        let max_bytes = match std::env::var("AUDIT_LOG_MAX_BYTES") {
+            Ok(v) => v.parse().context("Invalid AUDIT_LOG_MAX_BYTES")?,
+            Err(_) => DEFAULT_MAX_BYTES,
+        };
+        let chain = match std::env::var("AUDIT_LOG_CHAIN") {
+            Ok(v) => v.parse().context("Invalid AUDIT_LOG_CHAIN")?,
+            Err(_) => false,
+        };
+        info!("🗄️ Auditing submitted transactions to {} (rotate at {} bytes, hash chain: {})", path, max_bytes, chain);
+        let log = Self::open(path, max_bytes, chain)?;
+        *shared = Some(log.clone());
+        Ok(Some(log))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, entry: AuditEntry) {
+        let _ = self.sender.send(entry);
+    }
+
+    /// Every entry in `path`, oldest first
+    pub fn read(path: &Path) -> Result<Vec<AuditEntry>> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        BufReader::new(file)
+            .lines()
+            .enumerate()
+            .map(|(i, line)| serde_json::from_str(&line?).with_context(|| format!("{} line {}", path.display(), i + 1)))
+            .collect()
+    }
+
+    /// Check that `path`'s entries are in sequence and, where hashed, that
+    /// every hash matches its entry and links to the one before. Returns the
+    /// number of entries.
+    pub fn verify(path: &Path) -> Result<usize> {
+        let entries = Self::read(path)?;
+        for (i, entry) in entries.iter().enumerate() {
+            let previous = i.checked_sub(1).map(|p| &entries[p]);
+            if let Some(previous) = previous {
+                if entry.seq != previous.seq + 1 {
+                    bail!("Entry {} follows entry {}", entry.seq, previous.seq);
+                }
+            }
+            let Some(hash) = entry.hash else { continue };
+            if entry.chain_hash()? != hash {
+                bail!("Entry {} does not match its hash", entry.seq);
+            }
+            // The first entry of a rotated file links to the previous file
+            if let Some(previous) = previous {
+                if entry.prev_hash.unwrap_or_default() != previous.hash.unwrap_or_default() {
+                    bail!("Entry {} does not link to entry {}", entry.seq, previous.seq);
+                }
+            }
+        }
+        Ok(entries.len())
+    }
+
+    fn last_entry(path: &Path) -> Result<Option<AuditEntry>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+        };
+        let Some(line) = BufReader::new(file).lines().last() else {
+            return Ok(None);
+        };
+        let entry = serde_json::from_str(&line?).with_context(|| format!("Unreadable last entry in {}", path.display()))?;
+        Ok(Some(entry))
+    }
+
+    fn append_handle(path: &Path) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))
+    }
+}
+
+/// Records every submission of the wrapped submitter
+pub struct AuditSubmitter {
+    inner: Arc<dyn TxSubmitter>,
+    log: Arc<AuditLog>,
+    chain: String,
+    parsers: ErrorParsers,
+}
+
+impl AuditSubmitter {
+    pub fn new(inner: Arc<dyn TxSubmitter>, log: Arc<AuditLog>, chain: impl Into<String>) -> Self {
+        Self { inner, log, chain: chain.into(), parsers: ErrorParsers::rise() }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for AuditSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let entry = AuditEntry::new(&raw_tx, &self.chain, unix_ms());
+        let result = self.inner.submit(raw_tx).await;
+        self.log.record(entry.with_result(&result, &self.parsers));
+        result
+    }
+}
@@ -0,0 +1,293 @@
+//! Bounded pending-transaction queue for oracle triggers.
+//!
+//! A trigger admits each request it hands to the orchestrator and releases
+//! it in `on_complete`, so a slow RPC can't pile up an unbounded backlog
+//! that floods the chain with stale values once it recovers.
+//!
+//! When `MAX_PENDING_TX` requests are outstanding, `QUEUE_POLICY` decides:
+//! - `drop-oldest` (default): the oldest still-queued request is cancelled
+//!   in its build hook before signing, so the freshest value wins
+//! - `reject-new`: the new tick is skipped
+//!
+//! `admit` hands back a ticket with the request. `complete` releases the
+//! ticket whose build hook ran in the calling worker task, so requests that
+//! finish out of order release their own entries; without one it falls back
+//! to the oldest request that never reached its build.
+//!
+//! Independently of trigger logic, requests closer together than
+//! `MIN_SUBMIT_SPACING_MS` (default 0, disabled) are skipped, so a bad
+//! interval or a trigger bug can't spam the mempool and drain the keys.
+//! A spacing above a trigger's own update interval would skip its regular
+//! updates, and is warned about at startup.
+
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::types::rise_tx::RiseTransactionRequest;
+use parking_lot::Mutex;
+use crate::key_rotation::note_sending;
+use crate::task_slots::TaskSlots;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverflowPolicy {
+    DropOldest,
+    RejectNew,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop-oldest" => Ok(Self::DropOldest),
+            "reject-new" => Ok(Self::RejectNew),
+            other => Err(anyhow::anyhow!("Unknown QUEUE_POLICY '{}' (expected drop-oldest or reject-new)", other)),
+        }
+    }
+}
+
+/// Queue counters, served on `/status`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct QueueMetrics {
+    pub depth: usize,
+    pub max_depth_seen: usize,
+    pub admitted: u64,
+    pub rejected: u64,
+    pub dropped: u64,
+    /// Skipped for coming within the minimum spacing of the previous request
+    pub too_soon: u64,
+}
+
+struct Entry {
+    ticket: u64,
+    dropped: bool,
+    built: bool,
+}
+
+/// A released queue entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Completion {
+    pub ticket: u64,
+    /// Superseded under `drop-oldest` and failed before signing
+    pub dropped: bool,
+}
+
+struct QueueState {
+    entries: VecDeque<Entry>,
+    next_ticket: u64,
+    last_admitted: Option<Instant>,
+    metrics: QueueMetrics,
+}
+
+impl QueueState {
+    fn live(&self) -> usize {
+        self.entries.iter().filter(|e| !e.dropped).count()
+    }
+}
+
+pub struct PendingQueue {
+    max_depth: usize,
+    policy: OverflowPolicy,
+    min_spacing: Duration,
+    state: Mutex<QueueState>,
+    /// Ticket built by each worker task, read back in `complete`
+    built: TaskSlots<u64>,
+}
+
+impl PendingQueue {
+    pub fn new(max_depth: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            max_depth: max_depth.max(1),
+            policy,
+            min_spacing: Duration::ZERO,
+            state: Mutex::new(QueueState {
+                entries: VecDeque::new(),
+                next_ticket: 0,
+                last_admitted: None,
+                metrics: QueueMetrics::default(),
+            }),
+            built: TaskSlots::new(),
+        }
+    }
+
+    /// Skip requests admitted less than `spacing` after the previous one
+    pub fn with_min_spacing(mut self, spacing: Duration) -> Self {
+        self.min_spacing = spacing;
+        self
+    }
+
+    /// From `MAX_PENDING_TX` (default 4), `QUEUE_POLICY` (default drop-oldest)
+    /// and `MIN_SUBMIT_SPACING_MS` (default 0).
+    /// Invalid values fall back to the defaults with a warning.
+    pub fn from_env() -> Arc<Self> {
+        let max_depth = match std::env::var("MAX_PENDING_TX") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid MAX_PENDING_TX '{}', using 4", v);
+                4
+            }),
+            Err(_) => 4,
+        };
+        let policy = match std::env::var("QUEUE_POLICY") {
+            Ok(v) => v.parse().unwrap_or_else(|e| {
+                warn!("{}, using drop-oldest", e);
+                OverflowPolicy::DropOldest
+            }),
+            Err(_) => OverflowPolicy::DropOldest,
+        };
+        let min_spacing_ms = match std::env::var("MIN_SUBMIT_SPACING_MS") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid MIN_SUBMIT_SPACING_MS '{}', using 0", v);
+                0
+            }),
+            Err(_) => 0,
+        };
+        Arc::new(Self::new(max_depth, policy).with_min_spacing(Duration::from_millis(min_spacing_ms)))
+    }
+
+    /// [`from_env`](Self::from_env) for a trigger updating every `interval`,
+    /// warning when the minimum spacing would skip its regular updates
+    pub fn for_interval(label: &str, interval: Duration) -> Arc<Self> {
+        let queue = Self::from_env();
+        if queue.min_spacing > interval {
+            warn!(
+                "⚠️ MIN_SUBMIT_SPACING_MS ({}) is above the {} update interval ({}ms): regular updates will be skipped",
+                queue.min_spacing.as_millis(),
+                label,
+                interval.as_millis()
+            );
+        }
+        queue
+    }
+
+    /// Admit `tx_request` with its ticket, or `None` if it comes within the
+    /// minimum spacing of the previous one or the queue is full under
+    /// `reject-new`. The returned request carries a build hook that cancels
+    /// it if dropped.
+    pub fn admit(self: &Arc<Self>, tx_request: TxRequest) -> Option<(TxRequest, u64)> {
+        let mut state = self.state.lock();
+        let now = Instant::now();
+
+        if state.last_admitted.is_some_and(|last| now.duration_since(last) < self.min_spacing) {
+            state.metrics.too_soon += 1;
+            debug!("Update within {:?} of the previous one, skipping", self.min_spacing);
+            return None;
+        }
+
+        if state.live() >= self.max_depth {
+            match self.policy {
+                OverflowPolicy::RejectNew => {
+                    state.metrics.rejected += 1;
+                    warn!("🚧 Pending queue full ({}), skipping update", self.max_depth);
+                    return None;
+                }
+                OverflowPolicy::DropOldest => {
+                    if let Some(oldest) = state.entries.iter_mut().find(|e| !e.dropped) {
+                        oldest.dropped = true;
+                    }
+                    state.metrics.dropped += 1;
+                    warn!("🚧 Pending queue full ({}), dropping oldest update", self.max_depth);
+                }
+            }
+        }
+
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.entries.push_back(Entry { ticket, dropped: false, built: false });
+        state.last_admitted = Some(now);
+        state.metrics.admitted += 1;
+        state.metrics.depth = state.live();
+        state.metrics.max_depth_seen = state.metrics.max_depth_seen.max(state.metrics.depth);
+        drop(state);
+
+        // Key selection in this worker task prices and pins by the request
+        note_sending(&tx_request);
+        let hook = QueueGuardHook {
+            queue: self.clone(),
+            ticket,
+            inner: tx_request.build_hook.clone(),
+        };
+        Some((tx_request.with_build_hook(Arc::new(hook)), ticket))
+    }
+
+    /// Release the request this `on_complete` belongs to; call once per
+    /// `on_complete`. That's the ticket built in the calling task, else the
+    /// oldest request that never reached its build hook, else the oldest.
+    pub fn complete(&self) -> Option<Completion> {
+        if let Some(ticket) = self.built.take() {
+            return self.complete_ticket(ticket);
+        }
+        let mut state = self.state.lock();
+        let index = state.entries.iter().position(|e| !e.built).unwrap_or(0);
+        Self::release(&mut state, index)
+    }
+
+    /// Release exactly `ticket`
+    pub fn complete_ticket(&self, ticket: u64) -> Option<Completion> {
+        let mut state = self.state.lock();
+        let index = state.entries.iter().position(|e| e.ticket == ticket)?;
+        Self::release(&mut state, index)
+    }
+
+    fn release(state: &mut QueueState, index: usize) -> Option<Completion> {
+        let entry = state.entries.remove(index)?;
+        state.metrics.depth = state.live();
+        Some(Completion { ticket: entry.ticket, dropped: entry.dropped })
+    }
+
+    /// Mark `ticket` built by the current task; true if it was dropped
+    fn build(&self, ticket: u64) -> bool {
+        self.built.put(ticket);
+        let mut state = self.state.lock();
+        match state.entries.iter_mut().find(|e| e.ticket == ticket) {
+            Some(entry) => {
+                entry.built = true;
+                entry.dropped
+            }
+            None => false,
+        }
+    }
+
+    pub fn metrics(&self) -> QueueMetrics {
+        self.state.lock().metrics.clone()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "max_depth": self.max_depth,
+            "policy": self.policy,
+            "min_spacing_ms": self.min_spacing.as_millis() as u64,
+            "metrics": self.metrics(),
+        })
+    }
+}
+
+/// Fails the build of a dropped request, then defers to the original hook
+struct QueueGuardHook {
+    queue: Arc<PendingQueue>,
+    ticket: u64,
+    inner: Option<Arc<dyn TxBuildHook>>,
+}
+
+#[async_trait]
+impl TxBuildHook for QueueGuardHook {
+    async fn on_build(
+        &self,
+        tx_request: &TxRequest,
+        tx: RiseTransactionRequest,
+    ) -> Result<RiseTransactionRequest, RiseError> {
+        if self.queue.build(self.ticket) {
+            debug!("Dropping stale queued request {}", self.ticket);
+            return Err(RiseError::Config("Dropped by backpressure (superseded by a newer update)".to_string()));
+        }
+        match &self.inner {
+            Some(inner) => inner.on_build(tx_request, tx).await,
+            None => Ok(tx),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Combining several sources into one feed value.
+//!
+//! A feed with `[[feeds.blend]]` legs publishes the weighted mean of its own
+//! source and each leg, e.g. a Binance TWAP blended with a Uniswap V3 pool
+//! TWAP so a CEX-only print moves the published price less. Legs without a
+//! recent value drop out of the mean; with `max_blend_deviation_bps` set, no
+//! value is published while a leg disagrees with the mean by more than that.
+//!
+//! A [`FeedQuorum`] is stricter: at least `min_sources` sources must have a
+//! value newer than `max_age_ms` within `tolerance_bps` of the median of the
+//! fresh values. Only those agreeing sources are averaged, so one venue
+//! printing a flash crash is outvoted; without a quorum nothing is published
+//! (the chain keeps the last value) and [`OracleEvent::QuorumLost`] is
+//! published for alerting, with [`OracleEvent::QuorumRestored`] once the
+//! sources agree again.
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::events::{publish_event, OracleEvent};
+use crate::feed_trigger::{PricePoint, PriceSource};
+use crate::feeds::FeedQuorum;
+
+/// Weighted mean of `(price, weight)` pairs; `None` without positive weight
+pub fn weighted_mean(values: &[(f64, f64)]) -> Option<f64> {
+    let total: f64 = values.iter().map(|(_, w)| w).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    Some(values.iter().map(|(p, w)| p * w).sum::<f64>() / total)
+}
+
+/// Median of `values`; `None` when empty
+pub fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] })
+}
+
+/// Largest deviation of any price from `mean`, in basis points
+pub fn max_deviation_bps(values: &[(f64, f64)], mean: f64) -> f64 {
+    values.iter().map(|(p, _)| ((p - mean) / mean).abs() * 10_000.0).fold(0.0, f64::max)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Publishes the reciprocal of another source (a pool quoting the pair the other way round)
+pub struct InvertedSource {
+    inner: Arc<dyn PriceSource>,
+}
+
+impl InvertedSource {
+    pub fn new(inner: Arc<dyn PriceSource>) -> Self {
+        Self { inner }
+    }
+}
+
+impl PriceSource for InvertedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        let point = self.inner.latest()?;
+        if point.price <= 0.0 {
+            return None;
+        }
+        Some(PricePoint {
+            price: 1.0 / point.price,
+            // d(1/p) = dp / p^2
+            confidence: point.confidence.map(|c| c / (point.price * point.price)),
+            ..point
+        })
+    }
+
+    fn window_fill(&self) -> Option<f64> {
+        self.inner.window_fill()
+    }
+
+    fn quorum_lost(&self) -> bool {
+        self.inner.quorum_lost()
+    }
+}
+
+/// Weighted mean of several sources for one feed
+pub struct BlendedSource {
+    feed_id: String,
+    legs: Vec<(Arc<dyn PriceSource>, f64)>,
+    max_age: Option<Duration>,
+    max_deviation_bps: Option<u64>,
+    quorum: Option<FeedQuorum>,
+    /// Whether the last check held the value back, so transitions are logged once
+    diverged: Mutex<bool>,
+    /// Whether the quorum held at the last check; `None` until it first has,
+    /// so waiting for the sources at startup isn't alerted on
+    quorum_met: Mutex<Option<bool>>,
+}
+
+impl BlendedSource {
+    pub fn new(feed_id: impl Into<String>, legs: Vec<(Arc<dyn PriceSource>, f64)>) -> Self {
+        Self {
+            feed_id: feed_id.into(),
+            legs,
+            max_age: None,
+            max_deviation_bps: None,
+            quorum: None,
+            diverged: Mutex::new(false),
+            quorum_met: Mutex::new(None),
+        }
+    }
+
+    /// Leave legs whose value is older than `max_age` out of the mean
+    pub fn with_max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Publish nothing while any leg is further than this from the mean
+    pub fn with_max_deviation_bps(mut self, bps: Option<u64>) -> Self {
+        self.max_deviation_bps = bps;
+        self
+    }
+
+    /// Publish only while `quorum` sources are fresh and agree
+    pub fn with_quorum(mut self, quorum: Option<FeedQuorum>) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
+    /// The points agreeing within the quorum's band, or `None` when too few do
+    fn apply_quorum(
+        &self,
+        quorum: &FeedQuorum,
+        points: Vec<(PricePoint, f64)>,
+        now: u64,
+    ) -> Option<Vec<(PricePoint, f64)>> {
+        let fresh: Vec<(PricePoint, f64)> =
+            points.into_iter().filter(|(p, _)| now.saturating_sub(p.timestamp_ms) <= quorum.max_age_ms).collect();
+        let prices: Vec<f64> = fresh.iter().map(|(p, _)| p.price).collect();
+        let agreeing: Vec<(PricePoint, f64)> = match median(&prices) {
+            Some(median) => fresh
+                .iter()
+                .filter(|(p, _)| ((p.price - median) / median).abs() * 10_000.0 <= quorum.tolerance_bps as f64)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        let met = agreeing.len() >= quorum.min_sources;
+
+        let mut was_met = self.quorum_met.lock();
+        match (*was_met, met) {
+            (Some(true), false) => {
+                warn!(
+                    "🚨 {} lost its quorum: {} fresh, {} agreeing within {}bps, {} required - holding the last value: {:?}",
+                    self.feed_id,
+                    fresh.len(),
+                    agreeing.len(),
+                    quorum.tolerance_bps,
+                    quorum.min_sources,
+                    prices
+                );
+                publish_event(OracleEvent::QuorumLost {
+                    feed: self.feed_id.clone(),
+                    fresh: fresh.len(),
+                    agreeing: agreeing.len(),
+                    required: quorum.min_sources,
+                });
+                *was_met = Some(false);
+            }
+            (Some(false), true) => {
+                info!("✅ {} quorum restored ({} sources agree)", self.feed_id, agreeing.len());
+                publish_event(OracleEvent::QuorumRestored { feed: self.feed_id.clone() });
+                *was_met = Some(true);
+            }
+            (None, true) => *was_met = Some(true),
+            _ => {}
+        }
+        met.then_some(agreeing)
+    }
+}
+
+impl PriceSource for BlendedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        let now = now_ms();
+        let points: Vec<(PricePoint, f64)> = self
+            .legs
+            .iter()
+            .filter_map(|(source, weight)| source.latest().map(|p| (p, *weight)))
+            .filter(|(p, _)| p.price > 0.0 && p.price.is_finite())
+            .filter(|(p, _)| {
+                self.max_age
+                    .map_or(true, |max_age| now.saturating_sub(p.timestamp_ms) <= max_age.as_millis() as u64)
+            })
+            .collect();
+        let points = match &self.quorum {
+            Some(quorum) => self.apply_quorum(quorum, points, now)?,
+            None => points,
+        };
+
+        let values: Vec<(f64, f64)> = points.iter().map(|(p, w)| (p.price, *w)).collect();
+        let mean = weighted_mean(&values)?;
+
+        if let Some(limit) = self.max_deviation_bps {
+            let deviation = max_deviation_bps(&values, mean);
+            let diverged = deviation > limit as f64;
+            let mut was_diverged = self.diverged.lock();
+            if diverged != *was_diverged {
+                if diverged {
+                    warn!(
+                        "⚠️ {} sources disagree by {:.0}bps (limit {}bps), holding updates: {:?}",
+                        self.feed_id,
+                        deviation,
+                        limit,
+                        values.iter().map(|(p, _)| *p).collect::<Vec<_>>()
+                    );
+                } else {
+                    info!("✅ {} sources agree again ({:.0}bps), resuming", self.feed_id, deviation);
+                }
+                *was_diverged = diverged;
+            }
+            if diverged {
+                return None;
+            }
+        }
+
+        Some(PricePoint {
+            price: mean,
+            num_trades: points.iter().map(|(p, _)| p.num_trades).sum(),
+            timestamp_ms: points.iter().map(|(p, _)| p.timestamp_ms).min().unwrap_or(now),
+            confidence: None,
+        })
+    }
+
+    /// The least-filled windowed leg
+    fn window_fill(&self) -> Option<f64> {
+        self.legs.iter().filter_map(|(source, _)| source.window_fill()).reduce(f64::min)
+    }
+
+    fn quorum_lost(&self) -> bool {
+        *self.quorum_met.lock() == Some(false)
+    }
+}
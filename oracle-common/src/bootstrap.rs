@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use crate::chain::ChainConfig;
+
+/// Default gas price for RISE (300,000 wei = 0.0003 gwei)
+pub const DEFAULT_GAS_PRICE_WEI: u64 = 300_000;
+
+/// Install the rustls crypto provider used by the RPC and WebSocket clients.
+pub fn install_crypto_provider() {
+    rustls::crypto::aws_lc_rs::default_provider()
+        .install_default()
+        .expect("Failed to install rustls crypto provider");
+}
+
+/// Primary RPC URL from `RPC_URLS` / `RPC_URL`, defaulting to RISE testnet.
+pub fn rpc_url_from_env() -> String {
+    std::env::var("RPC_URLS")
+        .ok()
+        .and_then(|urls| urls.split(',').next().map(|s| s.trim().to_string()))
+        .filter(|url| !url.is_empty())
+        .or_else(|| std::env::var("RPC_URL").ok())
+        .unwrap_or_else(|| "https://testnet.riselabs.xyz".to_string())
+}
+
+/// Load the chain from the environment (`NETWORK`, `RPC_URLS`, ...), verify
+/// the RPC serves its chain id and apply its RPC and gas defaults to the SDK.
+pub async fn apply_sdk_defaults() -> Result<ChainConfig> {
+    let mut chain = ChainConfig::from_env()?;
+    chain.resolve_chain_id().await?;
+    chain.apply_sdk_defaults();
+    Ok(chain)
+}
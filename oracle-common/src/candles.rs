@@ -0,0 +1,287 @@
+//! OHLCV candles built from a trade stream.
+//!
+//! [`CandleStore`] folds every trade into open/high/low/close/volume bars at
+//! each configured interval and keeps the most recent ones per symbol. Bars
+//! are a cheaper and steadier input than raw trades when a window holds
+//! thousands of them:
+//!
+//! - `aggregation = "bar-twap"` averages the closes of a feed's
+//!   `candle_secs` bars over its window, each bar weighted equally (bars
+//!   without trades carry the previous close), so a burst of trades at one
+//!   price can't dominate the value the way it does a volume-weighted TWAP
+//! - `realized-vol` feeds with `candle_secs` set sample bar closes instead
+//!   of raw trades
+//!
+//! Late trades (e.g. backfilled ones) update the bar they belong to as long
+//! as it is still kept. Candles are served at `/candles` by the status
+//! server.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `CANDLE_INTERVALS` | `1s,5s,1m` | Bar intervals built (`ms`, `s`, `m` or `h` suffix) |
+//! | `CANDLE_BARS` | `900` | Bars kept per symbol and interval |
+
+use anyhow::{anyhow, bail, Context, Result};
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::volatility::{bar_closes, realized_volatility};
+
+const DEFAULT_INTERVALS: &str = "1s,5s,1m";
+const DEFAULT_BARS: usize = 900;
+
+/// One bar; `open_ms` is the start of its interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Candle {
+    pub open_ms: u64,
+    /// Times of the bar's first and last trade, so late trades land in order
+    pub first_trade_ms: u64,
+    pub last_trade_ms: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub trades: u64,
+}
+
+impl Candle {
+    fn new(open_ms: u64, timestamp_ms: u64, price: f64, quantity: f64) -> Self {
+        Self {
+            open_ms,
+            first_trade_ms: timestamp_ms,
+            last_trade_ms: timestamp_ms,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: quantity,
+            trades: 1,
+        }
+    }
+
+    fn add(&mut self, timestamp_ms: u64, price: f64, quantity: f64) {
+        if timestamp_ms < self.first_trade_ms {
+            self.first_trade_ms = timestamp_ms;
+            self.open = price;
+        }
+        if timestamp_ms >= self.last_trade_ms {
+            self.last_trade_ms = timestamp_ms;
+            self.close = price;
+        }
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.volume += quantity;
+        self.trades += 1;
+    }
+}
+
+/// `1s`, `5s`, `1m`, `250ms`, `1h` in milliseconds
+pub fn parse_candle_interval(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let (number, unit_ms) = if let Some(ms) = s.strip_suffix("ms") {
+        (ms, 1)
+    } else if let Some(secs) = s.strip_suffix('s') {
+        (secs, 1_000)
+    } else if let Some(mins) = s.strip_suffix('m') {
+        (mins, 60_000)
+    } else if let Some(hours) = s.strip_suffix('h') {
+        (hours, 3_600_000)
+    } else {
+        bail!("Candle interval '{}' needs a unit (ms, s, m or h)", s);
+    };
+    let ms = number.parse::<u64>().with_context(|| format!("Invalid candle interval '{}'", s))? * unit_ms;
+    if ms == 0 {
+        bail!("Candle interval '{}' is zero", s);
+    }
+    Ok(ms)
+}
+
+/// `1s`, `1m`, ... for `interval_ms`
+pub fn candle_interval_label(interval_ms: u64) -> String {
+    match interval_ms {
+        ms if ms % 3_600_000 == 0 => format!("{}h", ms / 3_600_000),
+        ms if ms % 60_000 == 0 => format!("{}m", ms / 60_000),
+        ms if ms % 1_000 == 0 => format!("{}s", ms / 1_000),
+        ms => format!("{}ms", ms),
+    }
+}
+
+/// Bars of one symbol at one interval, oldest first
+#[derive(Debug, Clone)]
+pub struct CandleSeries {
+    interval_ms: u64,
+    max_bars: usize,
+    bars: VecDeque<Candle>,
+}
+
+impl CandleSeries {
+    pub fn new(interval_ms: u64, max_bars: usize) -> Self {
+        Self { interval_ms: interval_ms.max(1), max_bars: max_bars.max(1), bars: VecDeque::new() }
+    }
+
+    pub fn add(&mut self, timestamp_ms: u64, price: f64, quantity: f64) {
+        if price <= 0.0 {
+            return;
+        }
+        let open_ms = timestamp_ms - timestamp_ms % self.interval_ms;
+        match self.bars.back_mut() {
+            Some(last) if last.open_ms == open_ms => return last.add(timestamp_ms, price, quantity),
+            Some(last) if last.open_ms > open_ms => {}
+            _ => {
+                self.bars.push_back(Candle::new(open_ms, timestamp_ms, price, quantity));
+                if self.bars.len() > self.max_bars {
+                    self.bars.pop_front();
+                }
+                return;
+            }
+        }
+
+        // A late trade: its bar, if still kept, or a new bar in its place
+        let at = self.bars.partition_point(|bar| bar.open_ms < open_ms);
+        match self.bars.get_mut(at) {
+            Some(bar) if bar.open_ms == open_ms => bar.add(timestamp_ms, price, quantity),
+            _ if at == 0 && self.bars.len() == self.max_bars => {}
+            _ => {
+                self.bars.insert(at, Candle::new(open_ms, timestamp_ms, price, quantity));
+                if self.bars.len() > self.max_bars {
+                    self.bars.pop_front();
+                }
+            }
+        }
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    /// Bars opened at or after `since_ms`
+    pub fn since(&self, since_ms: u64) -> Vec<Candle> {
+        let from = self.bars.partition_point(|bar| bar.open_ms < since_ms);
+        self.bars.range(from..).copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bars.is_empty()
+    }
+}
+
+/// Equal-weighted mean close of every `interval_ms` bar from the first of
+/// `candles` to `now_ms`, carrying closes over bars without trades
+pub fn bar_twap(candles: &[Candle], interval_ms: u64, now_ms: u64) -> Option<f64> {
+    let last = candles.last()?;
+    let mut samples: Vec<(u64, f64)> = candles.iter().map(|bar| (bar.open_ms, bar.close)).collect();
+    // Carry the last close up to the current bar
+    let now_bar = now_ms - now_ms % interval_ms.max(1);
+    if now_bar > last.open_ms {
+        samples.push((now_bar, last.close));
+    }
+    let closes = bar_closes(&samples, interval_ms);
+    (!closes.is_empty()).then(|| closes.iter().sum::<f64>() / closes.len() as f64)
+}
+
+/// Annualized realized volatility of `candles`' closes
+pub fn candle_volatility(candles: &[Candle], interval_ms: u64) -> Option<f64> {
+    let samples: Vec<(u64, f64)> = candles.iter().map(|bar| (bar.open_ms, bar.close)).collect();
+    realized_volatility(&samples, interval_ms)
+}
+
+/// Candles of every symbol at every configured interval
+pub struct CandleStore {
+    intervals_ms: Vec<u64>,
+    max_bars: usize,
+    symbols: RwLock<BTreeMap<String, Vec<CandleSeries>>>,
+}
+
+impl CandleStore {
+    pub fn new(mut intervals_ms: Vec<u64>, max_bars: usize) -> Self {
+        intervals_ms.sort();
+        intervals_ms.dedup();
+        Self { intervals_ms, max_bars, symbols: RwLock::new(BTreeMap::new()) }
+    }
+
+    /// `CANDLE_INTERVALS` and `CANDLE_BARS`
+    pub fn from_env() -> Result<Self> {
+        let intervals = std::env::var("CANDLE_INTERVALS").unwrap_or_else(|_| DEFAULT_INTERVALS.to_string());
+        let intervals_ms = intervals
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(parse_candle_interval)
+            .collect::<Result<Vec<_>>>()
+            .context("Invalid CANDLE_INTERVALS")?;
+        let max_bars = match std::env::var("CANDLE_BARS") {
+            Ok(bars) => bars.parse().map_err(|_| anyhow!("Invalid CANDLE_BARS '{}'", bars))?,
+            Err(_) => DEFAULT_BARS,
+        };
+        Ok(Self::new(intervals_ms, max_bars))
+    }
+
+    pub fn intervals_ms(&self) -> &[u64] {
+        &self.intervals_ms
+    }
+
+    pub fn has_interval(&self, interval_ms: u64) -> bool {
+        self.intervals_ms.contains(&interval_ms)
+    }
+
+    /// Fold a trade of `symbol` into each interval's bars
+    pub fn add_trade(&self, symbol: &str, timestamp_ms: u64, price: f64, quantity: f64) {
+        let mut symbols = self.symbols.write();
+        let series = symbols.entry(symbol.to_string()).or_insert_with(|| {
+            self.intervals_ms.iter().map(|interval| CandleSeries::new(*interval, self.max_bars)).collect()
+        });
+        for bars in series {
+            bars.add(timestamp_ms, price, quantity);
+        }
+    }
+
+    /// `symbol`'s `interval_ms` bars opened at or after `since_ms`, oldest first
+    pub fn candles(&self, symbol: &str, interval_ms: u64, since_ms: u64) -> Vec<Candle> {
+        let symbols = self.symbols.read();
+        symbols
+            .get(symbol)
+            .and_then(|series| series.iter().find(|bars| bars.interval_ms() == interval_ms))
+            .map(|bars| bars.since(since_ms))
+            .unwrap_or_default()
+    }
+
+    /// The newest `limit` bars of `symbol` at `interval_ms`
+    pub fn candles_json(&self, symbol: &str, interval_ms: u64, limit: usize) -> Value {
+        let candles = self.candles(symbol, interval_ms, 0);
+        let skip = candles.len().saturating_sub(limit);
+        json!({
+            "symbol": symbol,
+            "interval": candle_interval_label(interval_ms),
+            "candles": &candles[skip..],
+        })
+    }
+
+    /// Bars kept and the latest bar per symbol and interval
+    pub fn to_json(&self) -> Value {
+        let symbols = self.symbols.read();
+        let symbols: serde_json::Map<String, Value> = symbols
+            .iter()
+            .map(|(symbol, series)| {
+                let intervals: serde_json::Map<String, Value> = series
+                    .iter()
+                    .map(|bars| {
+                        let latest = bars.since(0).last().copied();
+                        (candle_interval_label(bars.interval_ms()), json!({ "bars": bars.len(), "latest": latest }))
+                    })
+                    .collect();
+                (symbol.clone(), Value::Object(intervals))
+            })
+            .collect();
+        json!({
+            "intervals": self.intervals_ms.iter().map(|ms| candle_interval_label(*ms)).collect::<Vec<_>>(),
+            "max_bars": self.max_bars,
+            "symbols": symbols,
+        })
+    }
+}
@@ -0,0 +1,240 @@
+//! Generic EVM chain configuration.
+//!
+//! `NETWORK` selects a preset (`testnet`, `mainnet` for RISE, or `custom`)
+//! and individual fields can be overridden from the environment, so the
+//! same binaries can publish to any EVM chain - with or without
+//! `eth_sendRawTransactionSync`.
+//!
+//! | Variable | Meaning |
+//! |----------|---------|
+//! | `CHAIN_ID` | Expected chain id, verified against every RPC (required for `mainnet` and `custom`; testnet pins its own) |
+//! | `RPC_URLS` | Comma-separated RPC endpoints, tried in order (falls back to `RPC_URL`) |
+//! | `SYNC_TX` | Whether the RPC supports `eth_sendRawTransactionSync` |
+//! | `GAS_PRICE_WEI` | Default gas price |
+//! | `PRIORITY_FEE_WEI` | Default max priority fee |
+//! | `RECEIPT_POLL_INTERVAL_MS` | Receipt polling interval for async submission |
+//! | `RECEIPT_TIMEOUT_MS` | Give up waiting for a receipt after this long |
+
+use alloy::providers::{Provider, ProviderBuilder};
+use anyhow::{anyhow, Result};
+use nonzu_sdk::prelude::*;
+use serde::Deserialize;
+use std::env;
+use tracing::{info, warn};
+
+use crate::bootstrap::DEFAULT_GAS_PRICE_WEI;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainConfig {
+    pub name: String,
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+    pub rpc_urls: Vec<String>,
+    #[serde(default = "default_sync_tx")]
+    pub sync_tx: bool,
+    #[serde(default = "default_gas_price_wei")]
+    pub gas_price_wei: u64,
+    #[serde(default = "default_gas_price_wei")]
+    pub priority_fee_wei: u64,
+    #[serde(default = "default_receipt_poll_interval_ms")]
+    pub receipt_poll_interval_ms: u64,
+    #[serde(default = "default_receipt_timeout_ms")]
+    pub receipt_timeout_ms: u64,
+}
+
+fn default_sync_tx() -> bool {
+    true
+}
+
+fn default_gas_price_wei() -> u64 {
+    DEFAULT_GAS_PRICE_WEI
+}
+
+fn default_receipt_poll_interval_ms() -> u64 {
+    250
+}
+
+fn default_receipt_timeout_ms() -> u64 {
+    30_000
+}
+
+impl ChainConfig {
+    pub fn rise_testnet() -> Self {
+        Self {
+            name: "rise-testnet".to_string(),
+            chain_id: Some(11155931),
+            rpc_urls: vec!["https://testnet.riselabs.xyz".to_string()],
+            sync_tx: true,
+            gas_price_wei: DEFAULT_GAS_PRICE_WEI,
+            priority_fee_wei: DEFAULT_GAS_PRICE_WEI,
+            receipt_poll_interval_ms: default_receipt_poll_interval_ms(),
+            receipt_timeout_ms: default_receipt_timeout_ms(),
+        }
+    }
+
+    /// RISE mainnet - the RPC must be supplied via `RPC_URLS` / `RPC_URL`
+    pub fn rise_mainnet() -> Self {
+        Self {
+            name: "rise-mainnet".to_string(),
+            chain_id: None,
+            rpc_urls: Vec::new(),
+            sync_tx: true,
+            gas_price_wei: DEFAULT_GAS_PRICE_WEI,
+            priority_fee_wei: DEFAULT_GAS_PRICE_WEI,
+            receipt_poll_interval_ms: default_receipt_poll_interval_ms(),
+            receipt_timeout_ms: default_receipt_timeout_ms(),
+        }
+    }
+
+    /// Any other EVM chain - everything comes from the environment
+    pub fn custom() -> Self {
+        Self {
+            name: env::var("CHAIN_NAME").unwrap_or_else(|_| "custom".to_string()),
+            chain_id: None,
+            rpc_urls: Vec::new(),
+            sync_tx: false,
+            gas_price_wei: DEFAULT_GAS_PRICE_WEI,
+            priority_fee_wei: DEFAULT_GAS_PRICE_WEI,
+            receipt_poll_interval_ms: default_receipt_poll_interval_ms(),
+            receipt_timeout_ms: default_receipt_timeout_ms(),
+        }
+    }
+
+    /// Preset from `NETWORK` with environment overrides applied
+    pub fn from_env() -> Result<Self> {
+        let chain = Self::preset_from_env()?;
+        chain.validate()?;
+        Ok(chain)
+    }
+
+    fn preset_from_env() -> Result<Self> {
+        let network = env::var("NETWORK").unwrap_or_else(|_| "testnet".to_string());
+        let mut chain = match network.as_str() {
+            "mainnet" => Self::rise_mainnet(),
+            "custom" => Self::custom(),
+            _ => Self::rise_testnet(),
+        };
+        chain.apply_env_overrides()?;
+        // Adopting whatever the RPC reports would verify nothing
+        if chain.chain_id.is_none() {
+            anyhow::bail!("NETWORK={} needs CHAIN_ID, the chain id every RPC endpoint is verified against", network);
+        }
+        Ok(chain)
+    }
+
+    /// Chain id `rpc_url` serves, checked against the `NETWORK` / `CHAIN_ID`
+    /// configuration like [`resolve_chain_id`](Self::resolve_chain_id), for
+    /// tools that are handed a single endpoint (owner operations, test
+    /// clients).
+    pub async fn verified_chain_id(rpc_url: &str) -> Result<u64> {
+        let mut chain = Self::preset_from_env()?;
+        chain.rpc_urls = vec![rpc_url.to_string()];
+        chain.resolve_chain_id().await
+    }
+
+    /// Override fields that are set in the environment
+    pub fn apply_env_overrides(&mut self) -> Result<()> {
+        if let Ok(urls) = env::var("RPC_URLS") {
+            self.rpc_urls = urls.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        } else if let Ok(url) = env::var("RPC_URL") {
+            self.rpc_urls = vec![url];
+        }
+        if let Ok(id) = env::var("CHAIN_ID") {
+            self.chain_id = Some(id.parse().map_err(|_| anyhow!("Invalid CHAIN_ID '{}'", id))?);
+        }
+        if let Ok(sync) = env::var("SYNC_TX") {
+            self.sync_tx = matches!(sync.as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(price) = env::var("GAS_PRICE_WEI") {
+            self.gas_price_wei = price.parse().map_err(|_| anyhow!("Invalid GAS_PRICE_WEI '{}'", price))?;
+        }
+        if let Ok(fee) = env::var("PRIORITY_FEE_WEI") {
+            self.priority_fee_wei = fee.parse().map_err(|_| anyhow!("Invalid PRIORITY_FEE_WEI '{}'", fee))?;
+        }
+        if let Ok(ms) = env::var("RECEIPT_POLL_INTERVAL_MS") {
+            self.receipt_poll_interval_ms = ms.parse().map_err(|_| anyhow!("Invalid RECEIPT_POLL_INTERVAL_MS '{}'", ms))?;
+        }
+        if let Ok(ms) = env::var("RECEIPT_TIMEOUT_MS") {
+            self.receipt_timeout_ms = ms.parse().map_err(|_| anyhow!("Invalid RECEIPT_TIMEOUT_MS '{}'", ms))?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.rpc_urls.is_empty() {
+            anyhow::bail!("Chain '{}' has no RPC endpoints - set RPC_URLS or RPC_URL", self.name);
+        }
+        Ok(())
+    }
+
+    /// Primary RPC endpoint
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_urls[0]
+    }
+
+    /// Closest SDK network preset, for APIs that still take one
+    pub fn sdk_network(&self) -> Network {
+        match self.name.as_str() {
+            "rise-mainnet" => Network::Mainnet,
+            _ => Network::Testnet,
+        }
+    }
+
+    /// Chain id the RPC endpoints serve, checked against the configured one.
+    ///
+    /// Worker keys are shared across networks, so a `NETWORK` / `RPC_URLS`
+    /// mix-up must stop startup rather than sign for the wrong chain. Every
+    /// endpoint is asked; a mismatch on any of them is an error, as is an
+    /// unreachable primary. An unreachable fallback is checked again before
+    /// submission first fails over to it (see [`crate::submit::RpcEndpoints::with_chain_id`]).
+    /// Without a configured id (`[[targets]]` entries may omit it) the
+    /// primary's is adopted and the others must agree with it.
+    pub async fn resolve_chain_id(&mut self) -> Result<u64> {
+        let mut expected = self.chain_id;
+        for (index, url) in self.rpc_urls.iter().enumerate() {
+            let id = match query_chain_id(url).await {
+                Ok(id) => id,
+                Err(e) if index == 0 => {
+                    return Err(e.context(format!("Failed to read the chain id from {}", url)));
+                }
+                Err(e) => {
+                    warn!("⚠️ Could not verify the chain id of fallback RPC {} yet, checking it before first use: {}", url, e);
+                    continue;
+                }
+            };
+            match expected {
+                Some(expected) if expected != id => anyhow::bail!(
+                    "RPC {} serves chain id {} but {} expects {} - refusing to sign for the wrong network",
+                    url,
+                    id,
+                    self.name,
+                    expected
+                ),
+                Some(_) => {}
+                None => expected = Some(id),
+            }
+        }
+        let id = expected.ok_or_else(|| anyhow!("Chain '{}' has no RPC endpoints", self.name))?;
+        self.chain_id = Some(id);
+        info!("🔗 Verified chain id {} on {} RPC endpoint(s)", id, self.rpc_urls.len());
+        Ok(id)
+    }
+
+    /// Point the SDK at this chain (RPC and gas defaults)
+    pub fn apply_sdk_defaults(&self) {
+        info!(
+            "🔗 Chain: {} (chain id {}, {} RPC endpoint(s), sync tx: {})",
+            self.name,
+            self.chain_id.map(|id| id.to_string()).unwrap_or_else(|| "auto".to_string()),
+            self.rpc_urls.len(),
+            self.sync_tx
+        );
+        set_default_rpc(self.rpc_url().to_string());
+        set_default_gas_price(self.gas_price_wei);
+        info!("⛽ Set default gas price to {} wei", self.gas_price_wei);
+    }
+}
+
+async fn query_chain_id(url: &str) -> Result<u64> {
+    Ok(ProviderBuilder::new().on_http(url.parse()?).get_chain_id().await?)
+}
@@ -0,0 +1,126 @@
+//! Rate-of-change limit on published values.
+//!
+//! A feed with `[feeds.change_limit]` never moves its published value by
+//! more than `max_change_bps` per `per_ms` (the feed's interval by default)
+//! from the last value it sent: a larger computed move is clamped to the
+//! limit, so one glitched upstream print reaches leveraged consumers as a
+//! bounded step rather than a cliff. The allowance grows with the time since
+//! the last update, so a feed that was held back can catch up.
+//!
+//! Only updates that landed count as sent: one that failed or was never
+//! admitted leaves the previous value as the baseline.
+//!
+//! A real move that outruns the limit would otherwise be followed with a lag;
+//! once the computed value has been beyond the limit for
+//! `override_after_secs` it is published unclamped.
+//!
+//! The limit is relative to the last value, so it is for unsigned feeds
+//! only (a signed one could sit at zero); a move away from a last value of
+//! zero is published as computed.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::feeds::FeedChangeLimit;
+
+#[derive(Default)]
+struct LimitState {
+    /// Last value sent and when
+    last: Option<(f64, Instant)>,
+    /// Since when computed values have been beyond the limit
+    clamping_since: Option<Instant>,
+    clamped: u64,
+    overrides: u64,
+}
+
+pub struct ChangeLimiter {
+    feed_id: String,
+    max_change_bps: u64,
+    per: Duration,
+    override_after: Option<Duration>,
+    state: Mutex<LimitState>,
+}
+
+impl ChangeLimiter {
+    /// Limiter for `config`, with `interval_ms` as the default period
+    pub fn new(feed_id: impl Into<String>, config: &FeedChangeLimit, interval_ms: u64) -> Self {
+        Self {
+            feed_id: feed_id.into(),
+            max_change_bps: config.max_change_bps,
+            per: Duration::from_millis(config.per_ms.unwrap_or(interval_ms).max(1)),
+            override_after: config.override_after_secs.map(Duration::from_secs),
+            state: Mutex::new(LimitState::default()),
+        }
+    }
+
+    /// The value to publish for a computed `value` at `now`
+    pub fn limit(&self, value: f64, now: Instant) -> f64 {
+        let mut state = self.state.lock();
+        // A zero last value gives a relative limit nothing to scale from
+        let Some((last, at)) = state.last.filter(|(last, _)| *last != 0.0) else {
+            state.clamping_since = None;
+            return value;
+        };
+
+        let periods = (now.saturating_duration_since(at).as_secs_f64() / self.per.as_secs_f64()).max(1.0);
+        let max_move = last.abs() * self.max_change_bps as f64 / 10_000.0 * periods;
+        let change = value - last;
+        if change.abs() <= max_move {
+            state.clamping_since = None;
+            return value;
+        }
+
+        let since = *state.clamping_since.get_or_insert(now);
+        let change_bps = change / last * 10_000.0;
+        if let Some(after) = self.override_after {
+            let sustained = now.saturating_duration_since(since);
+            if sustained >= after {
+                warn!(
+                    "⚠️ {} has been {:.0}bps from its last update for {:?}, publishing {} unclamped",
+                    self.feed_id, change_bps, sustained, value
+                );
+                state.clamping_since = None;
+                state.overrides += 1;
+                return value;
+            }
+        }
+
+        if since == now {
+            warn!(
+                "⚠️ {} moved {:.0}bps (limit {}bps per {:?}), clamping {} to {}",
+                self.feed_id,
+                change_bps,
+                self.max_change_bps,
+                self.per,
+                value,
+                last + max_move.copysign(change)
+            );
+        }
+        state.clamped += 1;
+        last + max_move.copysign(change)
+    }
+
+    /// `value`, sent at `sent_at`, landed: the next move is measured from
+    /// it, unless an update sent later landed first
+    pub fn record_sent(&self, value: f64, sent_at: Instant) {
+        let mut state = self.state.lock();
+        if state.last.is_some_and(|(_, at)| at > sent_at) {
+            return;
+        }
+        state.last = Some((value, sent_at));
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.lock();
+        json!({
+            "max_change_bps": self.max_change_bps,
+            "per_ms": self.per.as_millis() as u64,
+            "override_after_secs": self.override_after.map(|d| d.as_secs()),
+            "clamping_ms": state.clamping_since.map(|since| since.elapsed().as_millis() as u64),
+            "clamped": state.clamped,
+            "overrides": state.overrides,
+        })
+    }
+}
@@ -0,0 +1,332 @@
+//! Chaos mode: random failure injection for incident rehearsal.
+//!
+//! With `CHAOS` set (`nonzu --chaos`), the running pipeline breaks itself
+//! at random so runbooks and alerting can be exercised before a real
+//! incident. Nothing is faked downstream of the injection point: a dropped
+//! stream goes through the normal reconnect path, a corrupted receipt is
+//! caught (or not) by receipt validation, a frozen key hangs its worker
+//! until the error handler deals with the timeout.
+//!
+//! ```text
+//! CHAOS=true                                   # default rates below
+//! CHAOS=ws_drop=0.001,delay=0.05:2s,corrupt_receipt=0.01,freeze_key=0.005:30s
+//! ```
+//!
+//! | Fault | Default | Effect |
+//! |-------|---------|--------|
+//! | `ws_drop` | `0.001` per message | Drop the price stream connection (Binance, TraderMade, Pyth) |
+//! | `delay` | `0.02`, `2s` | Hold a submission before sending it |
+//! | `corrupt_receipt` | `0.01` | Zero the block number and gas used of a returned receipt |
+//! | `freeze_key` | `0.005`, `30s` | Stall every submission from the sending key, then fail it as timed out |
+//!
+//! Faults left out of a spec are off. `CHAOS_SEED` makes a rehearsal
+//! repeatable. Every injection is logged and published as
+//! [`OracleEvent::ChaosInjected`].
+
+use alloy::consensus::TxEnvelope;
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{Address, Bytes, U256};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::events::{publish_event, OracleEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaosFault {
+    WsDrop,
+    Delay,
+    CorruptReceipt,
+    FreezeKey,
+}
+
+impl ChaosFault {
+    pub const ALL: [ChaosFault; 4] = [Self::WsDrop, Self::Delay, Self::CorruptReceipt, Self::FreezeKey];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WsDrop => "ws_drop",
+            Self::Delay => "delay",
+            Self::CorruptReceipt => "corrupt_receipt",
+            Self::FreezeKey => "freeze_key",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl fmt::Display for ChaosFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Injection rates (0..=1 per opportunity) and how long faults last
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosConfig {
+    pub ws_drop: f64,
+    pub delay: f64,
+    pub delay_for: Duration,
+    pub corrupt_receipt: f64,
+    pub freeze_key: f64,
+    pub freeze_for: Duration,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            ws_drop: 0.001,
+            delay: 0.02,
+            delay_for: Duration::from_secs(2),
+            corrupt_receipt: 0.01,
+            freeze_key: 0.005,
+            freeze_for: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Every fault off
+    pub fn none() -> Self {
+        Self { ws_drop: 0.0, delay: 0.0, corrupt_receipt: 0.0, freeze_key: 0.0, ..Self::default() }
+    }
+
+    /// `true` for the defaults, or `fault=rate[:duration],...`
+    pub fn parse(spec: &str) -> Result<Self> {
+        if matches!(spec.trim(), "1" | "true" | "yes" | "default") {
+            return Ok(Self::default());
+        }
+        let defaults = Self::default();
+        let mut config = Self::none();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (fault, value) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid chaos entry '{}' (expected fault=rate[:duration])", entry))?;
+            let (rate, duration) = match value.split_once(':') {
+                Some((rate, duration)) => (rate, Some(parse_duration(duration.trim())?)),
+                None => (value, None),
+            };
+            let rate: f64 = rate.trim().parse().map_err(|_| anyhow!("Invalid chaos rate '{}'", rate))?;
+            if !(0.0..=1.0).contains(&rate) {
+                bail!("Chaos rate for {} must be between 0 and 1, got {}", fault, rate);
+            }
+            match fault.trim() {
+                "ws_drop" => config.ws_drop = rate,
+                "delay" => {
+                    config.delay = rate;
+                    config.delay_for = duration.unwrap_or(defaults.delay_for);
+                }
+                "corrupt_receipt" => config.corrupt_receipt = rate,
+                "freeze_key" => {
+                    config.freeze_key = rate;
+                    config.freeze_for = duration.unwrap_or(defaults.freeze_for);
+                }
+                other => bail!(
+                    "Unknown chaos fault '{}' (expected ws_drop, delay, corrupt_receipt or freeze_key)",
+                    other
+                ),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn rate(&self, fault: ChaosFault) -> f64 {
+        match fault {
+            ChaosFault::WsDrop => self.ws_drop,
+            ChaosFault::Delay => self.delay,
+            ChaosFault::CorruptReceipt => self.corrupt_receipt,
+            ChaosFault::FreezeKey => self.freeze_key,
+        }
+    }
+}
+
+/// `500ms`, `30s` or a bare number of seconds
+fn parse_duration(s: &str) -> Result<Duration> {
+    if let Some(ms) = s.strip_suffix("ms") {
+        Ok(Duration::from_millis(ms.parse()?))
+    } else if let Some(secs) = s.strip_suffix('s') {
+        Ok(Duration::from_secs_f64(secs.parse()?))
+    } else {
+        Ok(Duration::from_secs_f64(s.parse()?))
+    }
+}
+
+/// Dice and bookkeeping for the injected faults
+pub struct Chaos {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+    injected: [AtomicU64; 4],
+    /// Frozen keys and when they thaw
+    frozen: Mutex<HashMap<Address, Instant>>,
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Self {
+        Self {
+            config,
+            rng: Mutex::new(StdRng::from_entropy()),
+            injected: Default::default(),
+            frozen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Repeatable dice rolls
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// From `CHAOS` / `CHAOS_SEED`; None when unset or `false`
+    pub fn from_env() -> Result<Option<Self>> {
+        let spec = match std::env::var("CHAOS") {
+            Ok(spec) if !matches!(spec.trim(), "" | "0" | "false" | "no") => spec,
+            _ => return Ok(None),
+        };
+        let chaos = Self::new(ChaosConfig::parse(&spec)?);
+        Ok(Some(match std::env::var("CHAOS_SEED") {
+            Ok(seed) => chaos.with_seed(seed.parse().map_err(|_| anyhow!("Invalid CHAOS_SEED '{}'", seed))?),
+            Err(_) => chaos,
+        }))
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    /// Roll for `fault`; a hit is counted, logged and published
+    pub fn roll(&self, fault: ChaosFault, context: &str) -> bool {
+        let rate = self.config.rate(fault);
+        if rate <= 0.0 || !self.rng.lock().gen_bool(rate) {
+            return false;
+        }
+        self.injected[fault.index()].fetch_add(1, Ordering::Relaxed);
+        warn!("🐒 Chaos: injecting {} ({})", fault, context);
+        publish_event(OracleEvent::ChaosInjected { fault: fault.as_str().to_string(), context: context.to_string() });
+        true
+    }
+
+    /// Times `fault` was injected
+    pub fn injected(&self, fault: ChaosFault) -> u64 {
+        self.injected[fault.index()].load(Ordering::Relaxed)
+    }
+
+    pub fn freeze(&self, key: Address) {
+        self.frozen.lock().insert(key, Instant::now() + self.config.freeze_for);
+    }
+
+    /// Time until `key` thaws, None if it isn't frozen
+    pub fn frozen_for(&self, key: Address) -> Option<Duration> {
+        let mut frozen = self.frozen.lock();
+        let until = *frozen.get(&key)?;
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            frozen.remove(&key);
+            return None;
+        }
+        Some(left)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let injected: serde_json::Map<String, Value> = ChaosFault::ALL
+            .iter()
+            .map(|fault| (fault.as_str().to_string(), json!(self.injected(*fault))))
+            .collect();
+        json!({
+            "rates": {
+                "ws_drop": self.config.ws_drop,
+                "delay": self.config.delay,
+                "corrupt_receipt": self.config.corrupt_receipt,
+                "freeze_key": self.config.freeze_key,
+            },
+            "injected": injected,
+            "frozen_keys": self.frozen.lock().keys().map(|k| k.to_string()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// The process-wide chaos injector, None outside chaos mode. An invalid
+/// `CHAOS` spec is reported once and leaves chaos off.
+pub fn chaos_mode() -> Option<&'static Chaos> {
+    static CHAOS: OnceLock<Option<Chaos>> = OnceLock::new();
+    CHAOS
+        .get_or_init(|| match Chaos::from_env() {
+            Ok(Some(chaos)) => {
+                warn!("🐒 Chaos mode on: {:?}", chaos.config());
+                Some(chaos)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("⚠️ Ignoring CHAOS: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// In chaos mode, maybe fail a price stream so it goes through its
+/// reconnect path; call once per received message
+pub fn maybe_drop_stream(source: &str) -> Result<()> {
+    match chaos_mode() {
+        Some(chaos) if chaos.roll(ChaosFault::WsDrop, source) => Err(anyhow!("chaos: dropped {} stream", source)),
+        _ => Ok(()),
+    }
+}
+
+/// Submitter that delays submissions, corrupts receipts and freezes keys
+/// at the configured rates before / after the wrapped one
+pub struct ChaosSubmitter {
+    inner: Arc<dyn TxSubmitter>,
+    chaos: &'static Chaos,
+}
+
+impl ChaosSubmitter {
+    pub fn new(inner: Arc<dyn TxSubmitter>, chaos: &'static Chaos) -> Self {
+        info!("🐒 Chaos submitter installed");
+        Self { inner, chaos }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for ChaosSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let sender = TxEnvelope::decode_2718(&mut raw_tx.as_ref())
+            .ok()
+            .and_then(|envelope| envelope.recover_signer().ok());
+
+        if let Some(key) = sender {
+            if self.chaos.frozen_for(key).is_none() && self.chaos.roll(ChaosFault::FreezeKey, &key.to_string()) {
+                self.chaos.freeze(key);
+            }
+            // A frozen key's requests hang, then time out like a stuck node
+            if let Some(left) = self.chaos.frozen_for(key) {
+                tokio::time::sleep(left).await;
+                return Err(RiseError::Rpc(format!("chaos: key {} frozen, request timed out", key)));
+            }
+        }
+
+        if self.chaos.roll(ChaosFault::Delay, "submission") {
+            tokio::time::sleep(self.chaos.config().delay_for).await;
+        }
+
+        let mut receipt = self.inner.submit(raw_tx).await?;
+        if self.chaos.roll(ChaosFault::CorruptReceipt, &receipt.transaction_hash.to_string()) {
+            receipt.block_number = U256::ZERO;
+            receipt.gas_used = U256::ZERO;
+        }
+        Ok(receipt)
+    }
+}
@@ -0,0 +1,158 @@
+//! Circuit breaker on consecutive failed updates.
+//!
+//! Separate from the SDK's error-handler pause (which reacts to individual
+//! errors for a few seconds): after `CIRCUIT_BREAKER_THRESHOLD` consecutive
+//! failed updates the breaker opens and the trigger stops submitting. After
+//! `CIRCUIT_BREAKER_COOLDOWN_SECS` it half-opens and lets a single probe
+//! update through; the probe's success closes it, a failure re-opens it.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `CIRCUIT_BREAKER_THRESHOLD` | `5` | Consecutive failures that open the breaker (0 disables) |
+//! | `CIRCUIT_BREAKER_COOLDOWN_SECS` | `30` | Time open before a probe is allowed |
+
+use parking_lot::Mutex;
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone, Copy)]
+enum BreakerState {
+    Closed { consecutive_failures: u32 },
+    Open { since: Instant },
+    /// A probe was let through at the given time and hasn't completed
+    HalfOpen { probe_sent: Instant },
+}
+
+pub struct CircuitBreaker {
+    name: String,
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+    trips: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name: name.into(),
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::Closed { consecutive_failures: 0 }),
+            trips: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_env(name: impl Into<String>) -> Arc<Self> {
+        let threshold = match std::env::var("CIRCUIT_BREAKER_THRESHOLD") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid CIRCUIT_BREAKER_THRESHOLD '{}', using 5", v);
+                5
+            }),
+            Err(_) => 5,
+        };
+        let cooldown_secs = match std::env::var("CIRCUIT_BREAKER_COOLDOWN_SECS") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid CIRCUIT_BREAKER_COOLDOWN_SECS '{}', using 30", v);
+                30
+            }),
+            Err(_) => 30,
+        };
+        Arc::new(Self::new(name, threshold, Duration::from_secs(cooldown_secs)))
+    }
+
+    /// Whether an update may be submitted now. While half-open this admits
+    /// one probe; a probe that never completes is replaced after a cool-down.
+    pub fn try_acquire(&self) -> bool {
+        if self.threshold == 0 {
+            return true;
+        }
+        let mut state = self.state.lock();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { since } | BreakerState::HalfOpen { probe_sent: since } => {
+                if since.elapsed() < self.cooldown {
+                    return false;
+                }
+                info!("🔌 Circuit breaker for {} half-open, sending probe update", self.name);
+                *state = BreakerState::HalfOpen { probe_sent: Instant::now() };
+                true
+            }
+        }
+    }
+
+    /// An acquired update wasn't submitted after all: a probe that never
+    /// went out doesn't hold the half-open slot until it times out
+    pub fn cancel(&self) {
+        let mut state = self.state.lock();
+        if let BreakerState::HalfOpen { probe_sent } = *state {
+            // Open with the cool-down already served, so the next try probes
+            *state = BreakerState::Open { since: probe_sent.checked_sub(self.cooldown).unwrap_or(probe_sent) };
+        }
+    }
+
+    /// Record the outcome of a submitted update
+    pub fn record(&self, success: bool) {
+        if self.threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock();
+        match (*state, success) {
+            (BreakerState::Closed { .. }, true) => {
+                *state = BreakerState::Closed { consecutive_failures: 0 };
+            }
+            (_, true) => {
+                info!("✅ Circuit breaker for {} closed, updates resumed", self.name);
+                *state = BreakerState::Closed { consecutive_failures: 0 };
+            }
+            (BreakerState::Closed { consecutive_failures }, false) => {
+                let consecutive_failures = consecutive_failures + 1;
+                if consecutive_failures >= self.threshold {
+                    self.trips.fetch_add(1, Ordering::Relaxed);
+                    error!(
+                        "🚨 Circuit breaker for {} OPEN after {} consecutive failures - updates stopped for {:?}",
+                        self.name, consecutive_failures, self.cooldown
+                    );
+                    *state = BreakerState::Open { since: Instant::now() };
+                } else {
+                    *state = BreakerState::Closed { consecutive_failures };
+                }
+            }
+            (BreakerState::HalfOpen { .. }, false) => {
+                warn!("🚨 Circuit breaker probe for {} failed, re-opening for {:?}", self.name, self.cooldown);
+                *state = BreakerState::Open { since: Instant::now() };
+            }
+            // Stragglers submitted before the breaker opened
+            (BreakerState::Open { .. }, false) => {}
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !matches!(*self.state.lock(), BreakerState::Closed { .. })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut status = json!({
+            "threshold": self.threshold,
+            "cooldown_secs": self.cooldown.as_secs(),
+            "trips": self.trips.load(Ordering::Relaxed),
+        });
+        match *self.state.lock() {
+            BreakerState::Closed { consecutive_failures } => {
+                status["state"] = "closed".into();
+                status["consecutive_failures"] = consecutive_failures.into();
+            }
+            BreakerState::Open { since } => {
+                status["state"] = "open".into();
+                status["open_secs"] = since.elapsed().as_secs().into();
+            }
+            BreakerState::HalfOpen { probe_sent } => {
+                status["state"] = "half_open".into();
+                status["probe_age_secs"] = probe_sent.elapsed().as_secs().into();
+            }
+        }
+        status
+    }
+}
@@ -0,0 +1,274 @@
+//! Time sources for published timestamps.
+//!
+//! Raw `SystemTime` drifts on cheap VMs. With `TIME_SOURCE=ntp` the oracle
+//! measures its clock offset against NTP servers (SNTP, best of several
+//! samples per poll) and applies it, and reports the remaining uncertainty
+//! alongside the drift stats.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `TIME_SOURCE` | `system` | `system` or `ntp` |
+//! | `NTP_SERVERS` | `time.cloudflare.com,pool.ntp.org` | Servers queried each poll |
+//! | `NTP_POLL_SECS` | `64` | Offset re-measurement interval (at least 1) |
+
+use anyhow::{anyhow, Result};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET_SECS: f64 = 2_208_988_800.0;
+
+/// Source of the current Unix time in milliseconds
+pub trait TimeSource: Send + Sync {
+    fn now_ms(&self) -> u64;
+
+    /// Applied correction and its error bound, when the source measures them
+    fn offset_ms(&self) -> Option<f64> {
+        None
+    }
+
+    fn uncertainty_ms(&self) -> Option<f64> {
+        None
+    }
+
+    /// Status JSON for `/status`
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({ "source": "system" })
+    }
+}
+
+/// The local system clock, uncorrected
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default()
+    }
+}
+
+fn unix_now_secs_f64() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// One SNTP measurement
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct NtpSample {
+    /// Server clock minus local clock
+    pub offset_ms: f64,
+    /// Round-trip delay excluding server processing
+    pub delay_ms: f64,
+    /// Bound on the error of `offset_ms`: half the round trip plus the
+    /// server's own root delay / dispersion
+    pub uncertainty_ms: f64,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ClockStatus {
+    pub offset_ms: Option<f64>,
+    pub uncertainty_ms: Option<f64>,
+    pub last_sync_unix_ms: Option<u64>,
+    pub successful_polls: u64,
+    pub failed_polls: u64,
+}
+
+/// System clock corrected by the last measured NTP offset
+pub struct NtpClock {
+    servers: Vec<String>,
+    status: RwLock<ClockStatus>,
+}
+
+impl NtpClock {
+    pub fn new(servers: Vec<String>) -> Self {
+        Self {
+            servers,
+            status: RwLock::new(ClockStatus::default()),
+        }
+    }
+
+    pub fn clock_status(&self) -> ClockStatus {
+        self.status.read().clone()
+    }
+
+    /// Query every server and keep the lowest-delay sample
+    pub async fn poll(&self) -> Result<NtpSample> {
+        let mut best: Option<NtpSample> = None;
+        for server in &self.servers {
+            match query_sntp(server, Duration::from_secs(2)).await {
+                Ok(sample) => {
+                    debug!("NTP {}: offset {:.3}ms, delay {:.3}ms", server, sample.offset_ms, sample.delay_ms);
+                    if best.map_or(true, |b| sample.delay_ms < b.delay_ms) {
+                        best = Some(sample);
+                    }
+                }
+                Err(e) => warn!("⚠️ NTP query to {} failed: {}", server, e),
+            }
+        }
+
+        let mut status = self.status.write();
+        match best {
+            Some(sample) => {
+                status.offset_ms = Some(sample.offset_ms);
+                status.uncertainty_ms = Some(sample.uncertainty_ms);
+                status.last_sync_unix_ms = Some(SystemTimeSource.now_ms());
+                status.successful_polls += 1;
+                Ok(sample)
+            }
+            None => {
+                status.failed_polls += 1;
+                Err(anyhow!("No NTP server answered"))
+            }
+        }
+    }
+
+    /// Measure now and then every `interval`
+    pub fn spawn_refresher(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.poll().await {
+                    Ok(sample) => info!(
+                        "🕰️ NTP offset {:+.3}ms (uncertainty ±{:.3}ms)",
+                        sample.offset_ms, sample.uncertainty_ms
+                    ),
+                    Err(e) => warn!("⚠️ NTP poll failed, keeping previous offset: {}", e),
+                }
+            }
+        })
+    }
+}
+
+impl TimeSource for NtpClock {
+    fn now_ms(&self) -> u64 {
+        let offset = self.status.read().offset_ms.unwrap_or(0.0);
+        (unix_now_secs_f64() * 1000.0 + offset).round() as u64
+    }
+
+    fn offset_ms(&self) -> Option<f64> {
+        self.status.read().offset_ms
+    }
+
+    fn uncertainty_ms(&self) -> Option<f64> {
+        self.status.read().uncertainty_ms
+    }
+
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "source": "ntp",
+            "servers": self.servers,
+            "clock": self.clock_status(),
+        })
+    }
+}
+
+fn ntp_timestamp(bytes: &[u8]) -> f64 {
+    let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let frac = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64 / 4_294_967_296.0;
+    secs + frac - NTP_UNIX_OFFSET_SECS
+}
+
+/// `unix_secs` as a 64-bit NTP timestamp
+fn to_ntp_timestamp(unix_secs: f64) -> [u8; 8] {
+    let ntp_secs = unix_secs + NTP_UNIX_OFFSET_SECS;
+    let secs = ntp_secs.trunc() as u32;
+    let frac = (ntp_secs.fract() * 4_294_967_296.0) as u32;
+    let mut bytes = [0u8; 8];
+    bytes[..4].copy_from_slice(&secs.to_be_bytes());
+    bytes[4..].copy_from_slice(&frac.to_be_bytes());
+    bytes
+}
+
+fn ntp_short(bytes: &[u8]) -> f64 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64 / 65_536.0
+}
+
+/// One SNTP (RFC 4330) client exchange
+pub async fn query_sntp(server: &str, timeout: Duration) -> Result<NtpSample> {
+    let addr = if server.contains(':') { server.to_string() } else { format!("{}:123", server) };
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(&addr).await?;
+
+    // LI = 0, VN = 4, Mode = 3 (client), t1 as the transmit timestamp
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+    let t1 = unix_now_secs_f64();
+    request[40..48].copy_from_slice(&to_ntp_timestamp(t1));
+    socket.send(&request).await?;
+
+    let mut response = [0u8; 48];
+    let len = tokio::time::timeout(timeout, socket.recv(&mut response))
+        .await
+        .map_err(|_| anyhow!("timed out"))??;
+    let t4 = unix_now_secs_f64();
+
+    if len < 48 {
+        anyhow::bail!("short response ({} bytes)", len);
+    }
+    if response[0] & 0x07 != 4 {
+        anyhow::bail!("not a server response (mode {})", response[0] & 0x07);
+    }
+    if response[1] == 0 {
+        anyhow::bail!("kiss-of-death / unsynchronized server");
+    }
+    // The server echoes our transmit timestamp; anything else answers
+    // another request (stale or spoofed)
+    if response[24..32] != request[40..48] {
+        anyhow::bail!("originate timestamp doesn't match the request");
+    }
+
+    let root_delay = ntp_short(&response[4..8]);
+    let root_dispersion = ntp_short(&response[8..12]);
+    let t2 = ntp_timestamp(&response[32..40]);
+    let t3 = ntp_timestamp(&response[40..48]);
+
+    let offset = ((t2 - t1) + (t3 - t4)) / 2.0;
+    let delay = (t4 - t1) - (t3 - t2);
+
+    Ok(NtpSample {
+        offset_ms: offset * 1000.0,
+        delay_ms: delay * 1000.0,
+        uncertainty_ms: (delay / 2.0 + root_delay / 2.0 + root_dispersion) * 1000.0,
+    })
+}
+
+/// Time source from `TIME_SOURCE`, plus the NTP refresher task when enabled
+pub async fn time_source_from_env() -> Result<(Arc<dyn TimeSource>, Option<JoinHandle<()>>)> {
+    match std::env::var("TIME_SOURCE").as_deref() {
+        Err(_) | Ok("system") => Ok((Arc::new(SystemTimeSource), None)),
+        Ok("ntp") => {
+            let servers: Vec<String> = std::env::var("NTP_SERVERS")
+                .unwrap_or_else(|_| "time.cloudflare.com,pool.ntp.org".to_string())
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            let poll_secs: u64 = std::env::var("NTP_POLL_SECS")
+                .unwrap_or_else(|_| "64".to_string())
+                .parse()?;
+            if poll_secs == 0 {
+                anyhow::bail!("NTP_POLL_SECS must be at least 1");
+            }
+
+            let clock = Arc::new(NtpClock::new(servers));
+            // Measure once before publishing so the first timestamps are corrected
+            if let Err(e) = clock.poll().await {
+                warn!("⚠️ Initial NTP poll failed, starting uncorrected: {}", e);
+            }
+            let handle = clock.clone().spawn_refresher(Duration::from_secs(poll_secs));
+            info!("🕰️ Time source: NTP (poll every {}s)", poll_secs);
+            Ok((clock, Some(handle)))
+        }
+        Ok(other) => Err(anyhow!("Unknown TIME_SOURCE '{}' (expected system or ntp)", other)),
+    }
+}
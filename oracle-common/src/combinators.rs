@@ -0,0 +1,389 @@
+//! Composable trigger policies.
+//!
+//! A policy such as "(deviation > 0.1% OR 30s heartbeat) AND market healthy
+//! AND not paused" is built from [`TriggerCondition`]s and put in front of
+//! any [`TxTrigger`] with [`Gated`], rather than re-implemented inside each
+//! trigger:
+//!
+//! ```ignore
+//! let policy = AllOf::new()
+//!     .with(AnyOf::new().with(Deviation::new(source.clone(), 10)).with(Elapsed::new(Duration::from_secs(30))))
+//!     .with(Predicate::new("market healthy", move || health.is_healthy()))
+//!     .with(Not::new(Paused::new(error_control.clone())));
+//! let trigger = Throttle::new(Arc::new(Gated::new(trigger, policy)), Duration::from_millis(500));
+//! ```
+//!
+//! Conditions are checked before the wrapped trigger is polled, so a closed
+//! gate never touches its queue, breaker or stats. `AllOf` / `AnyOf` check
+//! every child on every poll (no short-circuit) so stateful children such as
+//! [`Debounce`] see each poll, and every child hears about completions to
+//! reset heartbeats and deviation baselines.
+
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::feed_trigger::PriceSource;
+use crate::timer::{SystemTimerClock, TimerClock};
+
+/// A yes/no input to a trigger policy
+#[async_trait]
+pub trait TriggerCondition: Send + Sync {
+    /// Whether an update may go out now
+    async fn check(&self) -> bool;
+
+    /// An update let through by this condition completed
+    fn on_complete(&self, _success: bool) {}
+
+    /// Short description for logs and trigger metadata
+    fn describe(&self) -> String;
+}
+
+fn default_clock() -> Arc<dyn TimerClock> {
+    Arc::new(SystemTimerClock::default())
+}
+
+/// Holds when every child holds (and when there are none)
+#[derive(Default)]
+pub struct AllOf {
+    conditions: Vec<Arc<dyn TriggerCondition>>,
+}
+
+impl AllOf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, condition: impl TriggerCondition + 'static) -> Self {
+        self.conditions.push(Arc::new(condition));
+        self
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for AllOf {
+    async fn check(&self) -> bool {
+        let mut all = true;
+        for condition in &self.conditions {
+            all &= condition.check().await;
+        }
+        all
+    }
+
+    fn on_complete(&self, success: bool) {
+        self.conditions.iter().for_each(|c| c.on_complete(success));
+    }
+
+    fn describe(&self) -> String {
+        let parts: Vec<_> = self.conditions.iter().map(|c| c.describe()).collect();
+        format!("({})", parts.join(" AND "))
+    }
+}
+
+/// Holds when any child holds (never when there are none)
+#[derive(Default)]
+pub struct AnyOf {
+    conditions: Vec<Arc<dyn TriggerCondition>>,
+}
+
+impl AnyOf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, condition: impl TriggerCondition + 'static) -> Self {
+        self.conditions.push(Arc::new(condition));
+        self
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for AnyOf {
+    async fn check(&self) -> bool {
+        let mut any = false;
+        for condition in &self.conditions {
+            any |= condition.check().await;
+        }
+        any
+    }
+
+    fn on_complete(&self, success: bool) {
+        self.conditions.iter().for_each(|c| c.on_complete(success));
+    }
+
+    fn describe(&self) -> String {
+        let parts: Vec<_> = self.conditions.iter().map(|c| c.describe()).collect();
+        format!("({})", parts.join(" OR "))
+    }
+}
+
+pub struct Not {
+    inner: Box<dyn TriggerCondition>,
+}
+
+impl Not {
+    pub fn new(inner: impl TriggerCondition + 'static) -> Self {
+        Self { inner: Box::new(inner) }
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for Not {
+    async fn check(&self) -> bool {
+        !self.inner.check().await
+    }
+
+    fn on_complete(&self, success: bool) {
+        self.inner.on_complete(success);
+    }
+
+    fn describe(&self) -> String {
+        format!("NOT {}", self.inner.describe())
+    }
+}
+
+/// Holds once `inner` has held on every check for `hold`, so a flapping
+/// signal doesn't fire on each flap
+pub struct Debounce {
+    inner: Box<dyn TriggerCondition>,
+    hold: Duration,
+    clock: Arc<dyn TimerClock>,
+    /// When `inner` started holding
+    since: RwLock<Option<Duration>>,
+}
+
+impl Debounce {
+    pub fn new(inner: impl TriggerCondition + 'static, hold: Duration) -> Self {
+        Self { inner: Box::new(inner), hold, clock: default_clock(), since: RwLock::new(None) }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn TimerClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for Debounce {
+    async fn check(&self) -> bool {
+        let now = self.clock.monotonic();
+        if !self.inner.check().await {
+            *self.since.write() = None;
+            return false;
+        }
+        let since = *self.since.write().get_or_insert(now);
+        now.saturating_sub(since) >= self.hold
+    }
+
+    fn on_complete(&self, success: bool) {
+        self.inner.on_complete(success);
+    }
+
+    fn describe(&self) -> String {
+        format!("{} for {:?}", self.inner.describe(), self.hold)
+    }
+}
+
+/// Holds when no update succeeded for `interval` (and before the first) - a heartbeat
+pub struct Elapsed {
+    interval: Duration,
+    clock: Arc<dyn TimerClock>,
+    last_success: RwLock<Option<Duration>>,
+}
+
+impl Elapsed {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, clock: default_clock(), last_success: RwLock::new(None) }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn TimerClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for Elapsed {
+    async fn check(&self) -> bool {
+        self.last_success
+            .read()
+            .map_or(true, |last| self.clock.monotonic().saturating_sub(last) >= self.interval)
+    }
+
+    fn on_complete(&self, success: bool) {
+        if success {
+            *self.last_success.write() = Some(self.clock.monotonic());
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?} elapsed", self.interval)
+    }
+}
+
+/// Holds when the source's price moved `threshold_bps` from the last
+/// published one (and before the first publish)
+pub struct Deviation {
+    source: Arc<dyn PriceSource>,
+    threshold_bps: u64,
+    /// Price seen at the last check, published if the update succeeds
+    candidate: RwLock<Option<f64>>,
+    published: RwLock<Option<f64>>,
+}
+
+impl Deviation {
+    pub fn new(source: Arc<dyn PriceSource>, threshold_bps: u64) -> Self {
+        Self { source, threshold_bps, candidate: RwLock::new(None), published: RwLock::new(None) }
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for Deviation {
+    async fn check(&self) -> bool {
+        let Some(price) = self.source.latest().map(|p| p.price) else {
+            return false;
+        };
+        *self.candidate.write() = Some(price);
+        match *self.published.read() {
+            Some(published) if published != 0.0 => {
+                ((price - published) / published).abs() * 10_000.0 >= self.threshold_bps as f64
+            }
+            _ => true,
+        }
+    }
+
+    fn on_complete(&self, success: bool) {
+        if success {
+            *self.published.write() = *self.candidate.read();
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("deviation >= {}bps", self.threshold_bps)
+    }
+}
+
+/// Holds while the orchestrator's worker pool is paused; use as `Not::new(Paused::new(..))`
+pub struct Paused {
+    error_control: Arc<OrchestratorErrorControl>,
+}
+
+impl Paused {
+    pub fn new(error_control: Arc<OrchestratorErrorControl>) -> Self {
+        Self { error_control }
+    }
+}
+
+#[async_trait]
+impl TriggerCondition for Paused {
+    async fn check(&self) -> bool {
+        self.error_control.is_worker_pool_paused().await
+    }
+
+    fn describe(&self) -> String {
+        "paused".to_string()
+    }
+}
+
+/// Named closure, e.g. a market health flag
+pub struct Predicate<F> {
+    name: String,
+    check: F,
+}
+
+impl<F: Fn() -> bool + Send + Sync> Predicate<F> {
+    pub fn new(name: impl Into<String>, check: F) -> Self {
+        Self { name: name.into(), check }
+    }
+}
+
+#[async_trait]
+impl<F: Fn() -> bool + Send + Sync> TriggerCondition for Predicate<F> {
+    async fn check(&self) -> bool {
+        (self.check)()
+    }
+
+    fn describe(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Polls `inner` only while `condition` holds
+pub struct Gated {
+    inner: Arc<dyn TxTrigger>,
+    condition: Box<dyn TriggerCondition>,
+}
+
+impl Gated {
+    pub fn new(inner: Arc<dyn TxTrigger>, condition: impl TriggerCondition + 'static) -> Self {
+        Self { inner, condition: Box::new(condition) }
+    }
+}
+
+#[async_trait]
+impl TxTrigger for Gated {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        if !self.condition.check().await {
+            return Ok(None);
+        }
+        self.inner.should_trigger().await
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        self.inner.on_complete(success, receipt, latency).await;
+        self.condition.on_complete(success);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        let mut metadata = self.inner.metadata();
+        metadata.description = format!("{} when {}", metadata.description, self.condition.describe());
+        metadata
+    }
+}
+
+/// Lets `inner` fire at most once per `min_interval`
+pub struct Throttle {
+    inner: Arc<dyn TxTrigger>,
+    min_interval: Duration,
+    clock: Arc<dyn TimerClock>,
+    last_fired: RwLock<Option<Duration>>,
+}
+
+impl Throttle {
+    pub fn new(inner: Arc<dyn TxTrigger>, min_interval: Duration) -> Self {
+        Self { inner, min_interval, clock: default_clock(), last_fired: RwLock::new(None) }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn TimerClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+#[async_trait]
+impl TxTrigger for Throttle {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let now = self.clock.monotonic();
+        if self.last_fired.read().is_some_and(|last| now.saturating_sub(last) < self.min_interval) {
+            return Ok(None);
+        }
+        let request = self.inner.should_trigger().await?;
+        if request.is_some() {
+            *self.last_fired.write() = Some(now);
+        }
+        Ok(request)
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        self.inner.on_complete(success, receipt, latency).await;
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        let mut metadata = self.inner.metadata();
+        metadata.description = format!("{} (at most every {:?})", metadata.description, self.min_interval);
+        metadata
+    }
+}
@@ -0,0 +1,173 @@
+//! On-chain conditions gating feed updates.
+//!
+//! A feed with `[feeds.condition]` reads a view on the target chain every
+//! `poll_ms` in the background and only publishes while the last read says an
+//! update is warranted, so quiet markets cost no gas:
+//!
+//! - `needs-update`: the view returns a bool (e.g. `needsUpdate(string)`)
+//! - `deviation`: the view returns the value on the contract (first word,
+//!   scaled like the feed, e.g. `getLatestPrice(string)`); publish when ours
+//!   differs by at least `deviation_bps`, or once `heartbeat_secs` passed
+//!   since our last update
+//!
+//! When the view cannot be read (RPC down, or a revert such as no value yet)
+//! updates go out as if there were no condition, so a broken read never
+//! freezes a feed.
+
+use alloy::primitives::{Address, Bytes, U256};
+use anyhow::{anyhow, Result};
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::feeds::{ConditionKind, FeedCondition, FeedConfig};
+use crate::rpc_batch::RpcBatcher;
+
+/// Relative difference of `value` from `reference`, in whole basis points
+pub fn deviation_bps(reference: U256, value: U256) -> Option<u64> {
+    if reference.is_zero() {
+        return None;
+    }
+    let diff = if value > reference { value - reference } else { reference - value };
+    Some(match diff.checked_mul(U256::from(10_000u64)) {
+        Some(scaled) => (scaled / reference).saturating_to(),
+        None => u64::MAX,
+    })
+}
+
+/// First 32-byte word of a call's output
+fn first_word(output: &[u8]) -> Option<U256> {
+    (output.len() >= 32).then(|| U256::from_be_slice(&output[..32]))
+}
+
+#[derive(Default)]
+struct LastRead {
+    /// First word of the view's output; `None` when the last read failed
+    word: Option<U256>,
+    at: Option<Instant>,
+    failures: u64,
+}
+
+pub struct OnChainCondition {
+    feed_id: String,
+    kind: ConditionKind,
+    contract: Address,
+    call_data: Bytes,
+    deviation_bps: Option<u64>,
+    heartbeat: Option<Duration>,
+    poll_interval: Duration,
+    last: RwLock<LastRead>,
+}
+
+impl OnChainCondition {
+    pub fn new(feed: &FeedConfig, condition: &FeedCondition) -> Result<Self> {
+        Ok(Self {
+            feed_id: feed.id.clone(),
+            kind: condition.kind,
+            contract: condition.contract.unwrap_or(feed.contract),
+            call_data: condition.call_data(&feed.id)?,
+            deviation_bps: condition.deviation_bps,
+            heartbeat: condition.heartbeat_secs.map(Duration::from_secs),
+            poll_interval: Duration::from_millis(condition.poll_ms.unwrap_or(feed.interval_ms).max(50)),
+            last: RwLock::new(LastRead::default()),
+        })
+    }
+
+    /// Record a read of the view (`None` when it failed)
+    pub fn record_read(&self, output: Option<&[u8]>) {
+        let mut last = self.last.write();
+        last.word = output.and_then(first_word);
+        last.at = Some(Instant::now());
+        if last.word.is_none() {
+            last.failures += 1;
+        }
+    }
+
+    /// Our update landed: the contract now holds `value`, so the old read
+    /// must not trigger another update before the next one
+    pub fn record_published(&self, value: U256) {
+        let mut last = self.last.write();
+        if last.word.is_some() {
+            last.word = Some(match self.kind {
+                ConditionKind::NeedsUpdate => U256::ZERO,
+                ConditionKind::Deviation => value,
+            });
+        }
+    }
+
+    /// Whether publishing `value` is warranted, `since_last` after our last update
+    pub fn allows(&self, value: U256, since_last: Option<Duration>) -> bool {
+        if let (Some(heartbeat), Some(since_last)) = (self.heartbeat, since_last) {
+            if since_last >= heartbeat {
+                return true;
+            }
+        }
+
+        let last = self.last.read();
+        // Reads older than a few polls are as good as failed
+        let fresh = last.at.is_some_and(|at| at.elapsed() < self.poll_interval * 3);
+        let Some(word) = last.word.filter(|_| fresh) else {
+            return true;
+        };
+
+        match self.kind {
+            ConditionKind::NeedsUpdate => !word.is_zero(),
+            ConditionKind::Deviation => match (deviation_bps(word, value), self.deviation_bps) {
+                (None, _) => true,
+                (Some(deviation), Some(threshold)) => deviation >= threshold,
+                // Heartbeat only
+                (Some(_), None) => since_last.is_none(),
+            },
+        }
+    }
+
+    async fn read(&self, rpc: &RpcBatcher) -> Result<Vec<u8>> {
+        let output = rpc
+            .call("eth_call", json!([{ "to": self.contract, "data": self.call_data }, "latest"]))
+            .await
+            .map_err(|e| anyhow!("{:?}", e))?;
+        let output = output.as_str().ok_or_else(|| anyhow!("eth_call returned no data"))?;
+        Ok(alloy::hex::decode(output)?)
+    }
+
+    /// Read the view every poll interval over `rpc_urls`
+    pub fn spawn(self: &Arc<Self>, rpc_urls: Vec<String>) -> JoinHandle<()> {
+        let condition = self.clone();
+        tokio::spawn(async move {
+            let rpc = RpcBatcher::shared(&rpc_urls);
+            let mut ticker = tokio::time::interval(condition.poll_interval);
+            loop {
+                ticker.tick().await;
+                match condition.read(&rpc).await {
+                    Ok(output) => {
+                        debug!("{} condition read: {:?}", condition.feed_id, first_word(&output));
+                        condition.record_read(Some(&output));
+                    }
+                    Err(e) => {
+                        // Logged at debug after the first failure; /status counts them
+                        if condition.last.read().failures == 0 {
+                            warn!("⚠️ {} condition read failed, publishing unconditionally: {}", condition.feed_id, e);
+                        } else {
+                            debug!("{} condition read failed: {}", condition.feed_id, e);
+                        }
+                        condition.record_read(None);
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let last = self.last.read();
+        json!({
+            "kind": format!("{:?}", self.kind),
+            "contract": self.contract.to_string(),
+            "last_value": last.word.map(|w| w.to_string()),
+            "read_age_ms": last.at.map(|at| at.elapsed().as_millis() as u64),
+            "read_failures": last.failures,
+        })
+    }
+}
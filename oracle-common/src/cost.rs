@@ -0,0 +1,151 @@
+//! Gas spend projections, and the actual spend recorded in the audit log.
+//!
+//! `nonzu cost-estimate` projects what a feeds file costs to run: each feed
+//! publishes `86_400_000 / interval_ms` updates a day at its gas per update
+//! (its `gas_limit` unless an average is given) and the gas price (capped by
+//! the feed's `max_fee_per_gas_wei`). Feeds gated by a condition or by
+//! requests publish less, so the projection is an upper bound for them.
+//!
+//! The audit log (`AUDIT_LOG_PATH`, see [`crate::audit`]) has every
+//! transaction that reached the chain with its gas limit and max fee, so
+//! [`ActualCosts`] sums recent spend per feed and per key to compare with.
+//! It doesn't record gas used, so actuals are bounded from above the same way.
+
+use alloy::primitives::Address;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::audit::{AuditEntry, AuditLog};
+use crate::feeds::FeedConfig;
+
+pub const DAY_MS: u64 = 86_400_000;
+
+/// Days in the projected month
+pub const MONTH_DAYS: f64 = 30.0;
+
+/// Projected spend of one feed on one chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedCost {
+    pub feed: String,
+    pub interval_ms: u64,
+    pub updates_per_day: f64,
+    pub gas_per_update: u64,
+    pub gas_price_wei: u64,
+}
+
+impl FeedCost {
+    /// `feed` at `gas_per_update` (its gas limit when `None`) and `gas_price_wei`
+    pub fn project(feed: &FeedConfig, gas_per_update: Option<u64>, gas_price_wei: u64) -> Self {
+        Self {
+            feed: feed.id.clone(),
+            interval_ms: feed.interval_ms,
+            updates_per_day: DAY_MS as f64 / feed.interval_ms.max(1) as f64,
+            gas_per_update: gas_per_update.unwrap_or(feed.gas_limit),
+            gas_price_wei: feed.max_fee_per_gas_wei.map_or(gas_price_wei, |max| max.min(gas_price_wei)),
+        }
+    }
+
+    pub fn daily_wei(&self) -> f64 {
+        self.updates_per_day * self.gas_per_update as f64 * self.gas_price_wei as f64
+    }
+
+    pub fn monthly_wei(&self) -> f64 {
+        self.daily_wei() * MONTH_DAYS
+    }
+}
+
+/// Transactions and their worst-case cost (gas limit times max fee)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Spend {
+    pub transactions: u64,
+    pub max_wei: f64,
+}
+
+impl Spend {
+    fn add(&mut self, entry: &AuditEntry) {
+        self.transactions += 1;
+        self.max_wei += entry.gas_limit.unwrap_or(0) as f64 * entry.max_fee_per_gas.unwrap_or(0) as f64;
+    }
+}
+
+/// Spend recorded in the audit log over a window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActualCosts {
+    /// Days the entries cover: from the first one in the window to its end
+    pub days: f64,
+    pub per_feed: BTreeMap<String, Spend>,
+    pub per_key: BTreeMap<Address, Spend>,
+}
+
+impl ActualCosts {
+    /// Entries submitted in `[since_ms, until_ms)` that reached the chain
+    /// (confirmed or reverted - both pay for gas)
+    pub fn from_entries(entries: &[AuditEntry], since_ms: u64, until_ms: u64) -> Self {
+        let mut costs = Self::default();
+        let mut first_ms = None;
+        for entry in entries {
+            if entry.submitted_at_ms < since_ms || entry.submitted_at_ms >= until_ms {
+                continue;
+            }
+            if !matches!(entry.result.as_str(), "confirmed" | "reverted") {
+                continue;
+            }
+            first_ms = Some(first_ms.map_or(entry.submitted_at_ms, |first: u64| first.min(entry.submitted_at_ms)));
+            let feed = entry.feed.clone().unwrap_or_else(|| "(unknown)".to_string());
+            costs.per_feed.entry(feed).or_default().add(entry);
+            if let Some(from) = entry.from {
+                costs.per_key.entry(from).or_default().add(entry);
+            }
+        }
+        costs.days = first_ms.map_or(0.0, |first| (until_ms - first) as f64 / DAY_MS as f64);
+        costs
+    }
+
+    /// `spend` per day of the window; `None` without entries
+    pub fn per_day(&self, spend: &Spend) -> Option<f64> {
+        (self.days > 0.0).then(|| spend.max_wei / self.days)
+    }
+
+    pub fn total(&self) -> Spend {
+        self.per_feed.values().fold(Spend::default(), |total, spend| Spend {
+            transactions: total.transactions + spend.transactions,
+            max_wei: total.max_wei + spend.max_wei,
+        })
+    }
+}
+
+/// The audit log at `path` and its rotated files (`<path>.<unix ms>`), oldest first
+pub fn audit_log_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut rotated = Vec::new();
+    if let (Some(name), Some(dir)) = (path.file_name().and_then(|n| n.to_str()), path.parent()) {
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        let prefix = format!("{}.", name);
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let rotated_at = file_name
+                .to_str()
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|suffix| suffix.parse::<u64>().ok());
+            if let Some(rotated_at) = rotated_at {
+                rotated.push((rotated_at, entry.path()));
+            }
+        }
+    }
+    rotated.sort();
+    let mut files: Vec<PathBuf> = rotated.into_iter().map(|(_, path)| path).collect();
+    if path.exists() {
+        files.push(path.to_path_buf());
+    }
+    Ok(files)
+}
+
+/// Every entry of the audit log at `path`, rotated files included
+pub fn read_audit_log(path: &Path) -> Result<Vec<AuditEntry>> {
+    let mut entries = Vec::new();
+    for file in audit_log_files(path)? {
+        entries.extend(AuditLog::read(&file)?);
+    }
+    Ok(entries)
+}
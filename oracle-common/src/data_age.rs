@@ -0,0 +1,135 @@
+//! Age of the data an update actually publishes.
+//!
+//! Publish latency alone understates staleness: a TWAP whose last trade was
+//! seconds before the tick is that much older again by the time it lands.
+//! [`DataAge`] follows each update from the data's own timestamp (the last
+//! trade in the window, or the moment a timestamp was captured) to:
+//!
+//! - trigger time - when the update was built from it
+//! - inclusion time - when its receipt came back (with synchronous
+//!   submission this is when the transaction was included, at millisecond
+//!   resolution rather than `block.timestamp`'s seconds)
+//!
+//! Every confirmed update logs its age, and both ages are kept as rolling
+//! distributions under `data_age` in `/status`, which is what a freshness
+//! SLA to consumers should quote.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+use crate::stats::Distribution;
+
+/// Samples kept per distribution
+const MAX_SAMPLES: usize = 10_000;
+
+/// Updates in flight whose data time is tracked; more are not measured
+const MAX_IN_FLIGHT: usize = 1_024;
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// One update's data, trigger and inclusion times (unix ms)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataAgeSample {
+    pub data_ms: u64,
+    pub triggered_ms: u64,
+    pub included_ms: u64,
+}
+
+impl DataAgeSample {
+    /// How old the data was when the update was built
+    pub fn at_trigger_ms(&self) -> u64 {
+        self.triggered_ms.saturating_sub(self.data_ms)
+    }
+
+    /// How old the data was when the update landed
+    pub fn at_inclusion_ms(&self) -> u64 {
+        self.included_ms.saturating_sub(self.data_ms)
+    }
+}
+
+struct DataAgeState {
+    /// `(data_ms, triggered_ms)` of updates sent and not yet completed, oldest first
+    in_flight: VecDeque<(u64, u64)>,
+    at_trigger_ms: Distribution,
+    at_inclusion_ms: Distribution,
+    last: Option<DataAgeSample>,
+}
+
+/// Data age of one feed's updates
+pub struct DataAge {
+    feed: String,
+    state: Mutex<DataAgeState>,
+}
+
+impl DataAge {
+    pub fn new(feed: impl Into<String>) -> Self {
+        Self {
+            feed: feed.into(),
+            state: Mutex::new(DataAgeState {
+                in_flight: VecDeque::new(),
+                at_trigger_ms: Distribution::new(MAX_SAMPLES),
+                at_inclusion_ms: Distribution::new(MAX_SAMPLES),
+                last: None,
+            }),
+        }
+    }
+
+    /// An update built now from data timestamped `data_ms`
+    pub fn sent(&self, data_ms: u64) {
+        self.sent_at(data_ms, now_ms());
+    }
+
+    pub fn sent_at(&self, data_ms: u64, triggered_ms: u64) {
+        let mut state = self.state.lock();
+        if state.in_flight.len() < MAX_IN_FLIGHT {
+            state.in_flight.push_back((data_ms, triggered_ms));
+        }
+    }
+
+    /// The oldest update in flight completed now; returns its ages when it landed
+    pub fn completed(&self, success: bool) -> Option<DataAgeSample> {
+        self.completed_at(success, now_ms())
+    }
+
+    pub fn completed_at(&self, success: bool, included_ms: u64) -> Option<DataAgeSample> {
+        let (data_ms, triggered_ms) = self.state.lock().in_flight.pop_front()?;
+        if !success {
+            return None;
+        }
+        let sample = DataAgeSample { data_ms, triggered_ms, included_ms };
+        self.record(sample);
+        Some(sample)
+    }
+
+    /// Record a landed update whose times the caller tracked itself
+    pub fn record(&self, sample: DataAgeSample) {
+        let mut state = self.state.lock();
+        state.at_trigger_ms.record(sample.at_trigger_ms() as f64);
+        state.at_inclusion_ms.record(sample.at_inclusion_ms() as f64);
+        state.last = Some(sample);
+        debug!(
+            "{} data age: {}ms at trigger, {}ms at inclusion",
+            self.feed,
+            sample.at_trigger_ms(),
+            sample.at_inclusion_ms()
+        );
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.lock();
+        json!({
+            "at_trigger_ms": state.at_trigger_ms.summary(),
+            "at_inclusion_ms": state.at_inclusion_ms.summary(),
+            "last": state.last.map(|sample| json!({
+                "data_ms": sample.data_ms,
+                "at_trigger_ms": sample.at_trigger_ms(),
+                "at_inclusion_ms": sample.at_inclusion_ms(),
+            })),
+        })
+    }
+}
@@ -0,0 +1,234 @@
+//! Dead-letter record of failed submissions.
+//!
+//! With `DEAD_LETTER_PATH` set, every submission that errors (or lands
+//! reverted) is appended to a JSON-lines file together with the signed
+//! transaction, so incidents can be analysed after the logs have rotated
+//! and updates re-sent with `nonzu dead-letters resubmit`. The file keeps
+//! the newest `DEAD_LETTER_MAX_ENTRIES` records (default 10000).
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{keccak256, Address, Bytes, B256};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::error_parsers::ErrorParsers;
+use crate::error_policy::ErrorCategory;
+
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// One failed submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub tx_hash: B256,
+    /// Sending key
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub nonce: Option<u64>,
+    pub gas_limit: Option<u64>,
+    pub calldata: Bytes,
+    /// Error category (see `ErrorCategory`)
+    pub category: String,
+    pub error: String,
+    pub submitted_at_ms: u64,
+    pub failed_at_ms: u64,
+    /// The signed transaction as sent
+    pub raw_tx: Bytes,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl DeadLetter {
+    /// Record for `raw_tx`, decoding what the envelope tells us
+    pub fn new(raw_tx: &Bytes, category: &str, error: String, submitted_at_ms: u64) -> Self {
+        let mut letter = Self {
+            tx_hash: keccak256(raw_tx),
+            from: None,
+            to: None,
+            nonce: None,
+            gas_limit: None,
+            calldata: Bytes::new(),
+            category: category.to_string(),
+            error,
+            submitted_at_ms,
+            failed_at_ms: unix_ms(),
+            raw_tx: raw_tx.clone(),
+        };
+        if let Ok(envelope) = TxEnvelope::decode_2718(&mut raw_tx.as_ref()) {
+            letter.from = envelope.recover_signer().ok();
+            letter.to = envelope.to();
+            letter.nonce = Some(envelope.nonce());
+            letter.gas_limit = Some(envelope.gas_limit());
+            letter.calldata = envelope.input().clone();
+        }
+        letter
+    }
+}
+
+/// Append-only JSON-lines store, trimmed to the newest `max_entries`
+pub struct DeadLetterStore {
+    path: PathBuf,
+    sender: mpsc::Sender<DeadLetter>,
+}
+
+impl DeadLetterStore {
+    /// Open (or create) the store; writes happen on a background thread
+    pub fn open(path: impl Into<PathBuf>, max_entries: usize) -> Result<Arc<Self>> {
+        let path = path.into();
+        let mut entries = Self::count(&path)?;
+        let mut file = Self::append_handle(&path)?;
+
+        let (sender, receiver) = mpsc::channel::<DeadLetter>();
+        let writer_path = path.clone();
+        std::thread::Builder::new().name("dead-letter-writer".into()).spawn(move || {
+            for letter in receiver {
+                let line = match serde_json::to_string(&letter) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        warn!("⚠️ Could not serialize dead letter {}: {}", letter.tx_hash, e);
+                        continue;
+                    }
+                };
+                if let Err(e) = writeln!(file, "{}", line) {
+                    warn!("⚠️ Could not write dead letter {}: {}", letter.tx_hash, e);
+                    continue;
+                }
+                entries += 1;
+                // Trim to three quarters so compaction doesn't run on every write
+                if entries > max_entries {
+                    match Self::compact(&writer_path, max_entries * 3 / 4) {
+                        Ok(kept) => entries = kept,
+                        Err(e) => warn!("⚠️ Could not compact {}: {}", writer_path.display(), e),
+                    }
+                    match Self::append_handle(&writer_path) {
+                        Ok(handle) => file = handle,
+                        Err(e) => {
+                            warn!("⚠️ Dead letter store {} unavailable: {}", writer_path.display(), e);
+                            return;
+                        }
+                    }
+                }
+            }
+        })?;
+
+        Ok(Arc::new(Self { path, sender }))
+    }
+
+    /// From `DEAD_LETTER_PATH` / `DEAD_LETTER_MAX_ENTRIES`; `None` when unset.
+    /// Every submitter in the process shares the one store.
+    pub fn from_env() -> Result<Option<Arc<Self>>> {
+        static SHARED: Mutex<Option<Arc<DeadLetterStore>>> = Mutex::new(None);
+
+        let Ok(path) = std::env::var("DEAD_LETTER_PATH") else {
+            return Ok(None);
+        };
+        let mut shared = SHARED.lock();
+        if let Some(store) = shared.as_ref() {
+            return Ok(Some(store.clone()));
+        }
+        let max_entries = match std::env::var("DEAD_LETTER_MAX_ENTRIES") {
+            Ok(v) => v.parse().context("Invalid DEAD_LETTER_MAX_ENTRIES")?,
+            Err(_) => DEFAULT_MAX_ENTRIES,
+        };
+        info!("🪦 Recording failed submissions to {} (last {})", path, max_entries);
+        let store = Self::open(path, max_entries.max(1))?;
+        *shared = Some(store.clone());
+        Ok(Some(store))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, letter: DeadLetter) {
+        let _ = self.sender.send(letter);
+    }
+
+    /// Every record in `path`, oldest first. Unparseable lines are skipped.
+    pub fn read(path: &Path) -> Result<Vec<DeadLetter>> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut letters = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(letter) = serde_json::from_str(&line?) {
+                letters.push(letter);
+            }
+        }
+        Ok(letters)
+    }
+
+    fn append_handle(path: &Path) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))
+    }
+
+    fn count(path: &Path) -> Result<usize> {
+        match File::open(path) {
+            Ok(file) => Ok(BufReader::new(file).lines().count()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Keep the newest `keep` lines; returns how many remain
+    fn compact(path: &Path, keep: usize) -> Result<usize> {
+        let lines: Vec<String> = BufReader::new(File::open(path)?).lines().collect::<Result<_, _>>()?;
+        let kept = &lines[lines.len().saturating_sub(keep)..];
+        let tmp = path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        for line in kept {
+            writeln!(file, "{}", line)?;
+        }
+        std::fs::rename(&tmp, path)?;
+        Ok(kept.len())
+    }
+}
+
+/// Records failed submissions of the wrapped submitter
+pub struct DeadLetterSubmitter {
+    inner: Arc<dyn TxSubmitter>,
+    store: Arc<DeadLetterStore>,
+    parsers: ErrorParsers,
+}
+
+impl DeadLetterSubmitter {
+    pub fn new(inner: Arc<dyn TxSubmitter>, store: Arc<DeadLetterStore>) -> Self {
+        Self { inner, store, parsers: ErrorParsers::rise() }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for DeadLetterSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let submitted_at_ms = unix_ms();
+        let result = self.inner.submit(raw_tx.clone()).await;
+        match &result {
+            Ok(receipt) if !receipt.is_success() => {
+                let error = format!("reverted in block {}", receipt.block_number);
+                self.store.record(DeadLetter::new(&raw_tx, ErrorCategory::Revert.as_str(), error, submitted_at_ms));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let category = self.parsers.classify(e);
+                self.store.record(DeadLetter::new(&raw_tx, category.as_str(), e.to_string(), submitted_at_ms));
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,149 @@
+//! Feeds derived from other feeds.
+//!
+//! A feed with `source = "derived"` has no upstream of its own: its symbol is
+//! an expression over the ids of feeds declared before it, evaluated on their
+//! latest values and published alongside them.
+//!
+//! ```toml
+//! [[feeds]]
+//! id = "ETHBTC"
+//! source = "derived"
+//! symbol = "ETHUSD / BTCUSD"
+//! contract = "0x..."
+//! ```
+//!
+//! Expressions multiply and divide feed ids and constants left to right
+//! (`ETHUSD / BTCUSD`, `1 / EURUSD`, `BTCUSD * EURUSD / GBPUSD`); `invert`
+//! works as for any other source. A derived value carries the timestamp and
+//! trade count of its oldest / thinnest input, and relative confidence
+//! intervals add up.
+
+use anyhow::{anyhow, bail, Result};
+use std::sync::Arc;
+
+use crate::feed_trigger::{PricePoint, PriceSource};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivedOp {
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DerivedTerm {
+    Feed(String),
+    Constant(f64),
+}
+
+/// Parsed `term (op term)*` expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedExpr {
+    first: DerivedTerm,
+    rest: Vec<(DerivedOp, DerivedTerm)>,
+}
+
+impl DerivedExpr {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let spaced = expr.replace('*', " * ").replace('/', " / ");
+        let mut tokens = spaced.split_whitespace();
+        let first = Self::term(tokens.next(), expr)?;
+        let mut rest = Vec::new();
+        while let Some(op) = tokens.next() {
+            let op = match op {
+                "*" => DerivedOp::Mul,
+                "/" => DerivedOp::Div,
+                other => bail!("Expected * or / before '{}' in '{}'", other, expr),
+            };
+            rest.push((op, Self::term(tokens.next(), expr)?));
+        }
+        let parsed = Self { first, rest };
+        if parsed.feed_ids().next().is_none() {
+            bail!("Derived expression '{}' uses no feed", expr);
+        }
+        Ok(parsed)
+    }
+
+    fn term(token: Option<&str>, expr: &str) -> Result<DerivedTerm> {
+        let token = token.ok_or_else(|| anyhow!("Derived expression '{}' ends without a term", expr))?;
+        // Ids may start with a digit (`1INCHUSD`); only whole numbers are constants
+        match token.parse::<f64>() {
+            Ok(value) if token.starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+                if !value.is_finite() || value <= 0.0 {
+                    bail!("Constants must be positive, got '{}' in '{}'", token, expr);
+                }
+                Ok(DerivedTerm::Constant(value))
+            }
+            _ => Ok(DerivedTerm::Feed(token.to_string())),
+        }
+    }
+
+    fn terms(&self) -> impl Iterator<Item = (DerivedOp, &DerivedTerm)> {
+        std::iter::once((DerivedOp::Mul, &self.first)).chain(self.rest.iter().map(|(op, term)| (*op, term)))
+    }
+
+    /// Feed ids the expression reads, in order
+    pub fn feed_ids(&self) -> impl Iterator<Item = &str> {
+        self.terms().filter_map(|(_, term)| match term {
+            DerivedTerm::Feed(id) => Some(id.as_str()),
+            DerivedTerm::Constant(_) => None,
+        })
+    }
+}
+
+/// Source evaluating a [`DerivedExpr`] on other feeds' sources
+pub struct DerivedSource {
+    expr: DerivedExpr,
+    /// One source per feed term, in `feed_ids` order
+    inputs: Vec<Arc<dyn PriceSource>>,
+}
+
+impl DerivedSource {
+    /// Source for `expr`, looking each feed id up with `source_for`
+    pub fn new(expr: &str, source_for: impl Fn(&str) -> Option<Arc<dyn PriceSource>>) -> Result<Self> {
+        let expr = DerivedExpr::parse(expr)?;
+        let inputs = expr
+            .feed_ids()
+            .map(|id| source_for(id).ok_or_else(|| anyhow!("Derived expression uses unknown feed '{}'", id)))
+            .collect::<Result<_>>()?;
+        Ok(Self { expr, inputs })
+    }
+}
+
+impl PriceSource for DerivedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        let mut inputs = self.inputs.iter();
+        let mut price = 1.0;
+        let mut relative_confidence: Option<f64> = None;
+        let mut num_trades = u64::MAX;
+        let mut timestamp_ms = u64::MAX;
+
+        for (op, term) in self.expr.terms() {
+            let value = match term {
+                DerivedTerm::Constant(value) => *value,
+                DerivedTerm::Feed(_) => {
+                    let point = inputs.next()?.latest()?;
+                    if !point.price.is_finite() || point.price <= 0.0 {
+                        return None;
+                    }
+                    num_trades = num_trades.min(point.num_trades);
+                    timestamp_ms = timestamp_ms.min(point.timestamp_ms);
+                    if let Some(confidence) = point.confidence {
+                        *relative_confidence.get_or_insert(0.0) += confidence / point.price;
+                    }
+                    point.price
+                }
+            };
+            match op {
+                DerivedOp::Mul => price *= value,
+                DerivedOp::Div => price /= value,
+            }
+        }
+
+        price.is_finite().then(|| PricePoint {
+            price,
+            num_trades,
+            timestamp_ms,
+            confidence: relative_confidence.map(|r| r * price),
+        })
+    }
+}
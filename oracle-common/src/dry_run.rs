@@ -0,0 +1,154 @@
+//! Dry-run mode: the full pipeline without broadcasting.
+//!
+//! With `DRY_RUN=true` (or `nonzu --dry-run`) the oracles keep their sources,
+//! triggers, build hooks, encoding and signing, but instead of handing
+//! requests to the SDK orchestrator they are signed locally and logged. This
+//! validates config and selectors safely against mainnet before going live.
+
+use alloy::consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::hex;
+use alloy::network::TxSigner;
+use alloy::primitives::{keccak256, TxKind, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::types::rise_tx::RiseTransactionRequest;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::access_list::AccessListConfig;
+use crate::bootstrap::DEFAULT_GAS_PRICE_WEI;
+use crate::chain::ChainConfig;
+use crate::gas_fees::GasFees;
+use crate::keys::PrivateKey;
+
+/// Whether `DRY_RUN` is set to a truthy value
+pub fn dry_run_enabled() -> bool {
+    matches!(
+        std::env::var("DRY_RUN").as_deref(),
+        Ok("1") | Ok("true") | Ok("yes")
+    )
+}
+
+struct DryRunKey {
+    signer: PrivateKeySigner,
+    nonce: u64,
+}
+
+/// Stand-in for `SimpleOrchestrator` that signs and logs instead of sending
+pub struct DryRunOrchestrator {
+    triggers: Vec<Arc<dyn TxTrigger>>,
+    keys: Vec<DryRunKey>,
+    chain_id: u64,
+    check_interval: Duration,
+}
+
+impl DryRunOrchestrator {
+    /// Signs for `chain`'s chain id, verified against its RPC endpoints, and
+    /// reads pending nonces from its primary RPC (read-only calls).
+    pub async fn new(
+        triggers: Vec<Arc<dyn TxTrigger>>,
+        private_keys: &[PrivateKey],
+        chain: &ChainConfig,
+        check_interval: Duration,
+    ) -> Result<Self> {
+        let chain_id = chain.clone().resolve_chain_id().await?;
+        let provider = ProviderBuilder::new().on_http(chain.rpc_url().parse()?);
+
+        let mut keys = Vec::with_capacity(private_keys.len());
+        for key in private_keys {
+            let signer = key.signer()?;
+            let nonce = provider.get_transaction_count(signer.address()).pending().await?;
+            keys.push(DryRunKey { signer, nonce });
+        }
+
+        if keys.is_empty() {
+            anyhow::bail!("Dry run needs at least one key to sign with");
+        }
+
+        warn!("🧪 DRY RUN - transactions are signed and logged, never broadcast (chain id {})", chain_id);
+        Ok(Self { triggers, keys, chain_id, check_interval })
+    }
+
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.check_interval);
+            let mut key_index = 0usize;
+
+            loop {
+                ticker.tick().await;
+
+                for trigger in self.triggers.clone() {
+                    let tx_request = match trigger.should_trigger().await {
+                        Ok(Some(tx_request)) => tx_request,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            error!("Trigger {} failed: {}", trigger.metadata().name, e);
+                            continue;
+                        }
+                    };
+
+                    let started = Instant::now();
+                    let key = &mut self.keys[key_index % self.keys.len()];
+                    key_index += 1;
+
+                    match Self::sign(&tx_request, key, self.chain_id).await {
+                        Ok(()) => trigger.on_complete(true, None, Some(started.elapsed())).await,
+                        Err(e) => {
+                            error!("🧪 Dry run signing failed: {}", e);
+                            trigger.on_complete(false, None, None).await;
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    async fn sign(tx_request: &TxRequest, key: &mut DryRunKey, chain_id: u64) -> Result<()> {
+        let gas_limit = tx_request.gas_limit.unwrap_or(U256::from(300_000));
+
+        // Run build hooks exactly as the SDK would before signing
+        let mut rise_tx = RiseTransactionRequest::new()
+            .to(tx_request.to)
+            .data(tx_request.data.clone())
+            .gas(gas_limit);
+        if let Some(hook) = &tx_request.build_hook {
+            rise_tx = hook.on_build(tx_request, rise_tx).await?;
+        }
+        let input = rise_tx.data.clone().unwrap_or_default();
+        let (max_fee, priority_fee) = GasFees::from_request(tx_request).resolve(DEFAULT_GAS_PRICE_WEI, DEFAULT_GAS_PRICE_WEI);
+
+        let mut tx = TxEip1559 {
+            chain_id,
+            nonce: key.nonce,
+            gas_limit: gas_limit.saturating_to::<u64>(),
+            max_fee_per_gas: max_fee as u128,
+            max_priority_fee_per_gas: priority_fee as u128,
+            to: TxKind::Call(tx_request.to),
+            value: U256::ZERO,
+            input: input.clone(),
+            access_list: AccessListConfig::from_request(tx_request)?.unwrap_or_default(),
+        };
+
+        let signature = key.signer.sign_transaction(&mut tx).await?;
+        let signed = TxEnvelope::Eip1559(tx.into_signed(signature));
+        let raw = signed.encoded_2718();
+        key.nonce += 1;
+
+        info!(
+            "🧪 Would send tx {} from {} (nonce {}) to {} - metadata: {:?}",
+            keccak256(&raw),
+            key.signer.address(),
+            key.nonce - 1,
+            tx_request.to,
+            tx_request.metadata
+        );
+        info!("🧪 Calldata: 0x{}", hex::encode(&input));
+        info!("🧪 Raw tx: 0x{}", hex::encode(&raw));
+        Ok(())
+    }
+}
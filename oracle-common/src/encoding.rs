@@ -0,0 +1,209 @@
+//! Calldata encoding for the oracle contracts.
+//!
+//! Encoding is done by hand to keep the exact byte layout visible and avoid
+//! per-update allocations from the generic ABI encoder.
+
+use alloy::hex;
+use alloy::primitives::{keccak256, Bytes, B256, I256, U256};
+use tracing::debug;
+
+/// Selector for `updateTimestamp(uint256)` on TimeOracle
+pub const UPDATE_TIMESTAMP_SELECTOR: [u8; 4] = [0x51, 0xab, 0x28, 0xa9];
+
+/// Selector for `updateTimestampWithRound(uint256,uint256)` on TimeOracle
+pub const UPDATE_TIMESTAMP_WITH_ROUND_SELECTOR: [u8; 4] = [0x0f, 0x47, 0x5b, 0xd1];
+
+/// Selector for `getLatestRound()` on TimeOracle
+pub const GET_LATEST_ROUND_SELECTOR: [u8; 4] = [0x70, 0x8f, 0xe4, 0xdf];
+
+/// Compute the 4-byte function selector for a signature like
+/// `updatePrice(string,uint256)`.
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hash[0..4]);
+    selector
+}
+
+/// A feed's id as passed to the contract: the id itself as a `string`, or a
+/// `bytes32` key (by convention keccak256 of the id, see [`FeedId::keccak`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedId<'a> {
+    String(&'a str),
+    Bytes32(B256),
+}
+
+impl<'a> FeedId<'a> {
+    /// The `bytes32` key for `id`: keccak256 of its UTF-8 bytes
+    pub fn keccak(id: &str) -> Self {
+        FeedId::Bytes32(keccak256(id.as_bytes()))
+    }
+}
+
+/// Encode a call taking the feed id followed by static `uint256` / `int256`
+/// words, e.g. `updatePrice(string,uint256)` or
+/// `updatePrice(bytes32,uint256,uint256)`.
+pub fn encode_feed_call(selector: [u8; 4], feed_id: FeedId<'_>, words: &[U256]) -> Bytes {
+    let tail = match feed_id {
+        FeedId::String(id) => id.as_bytes(),
+        FeedId::Bytes32(_) => &[],
+    };
+    let padded_len = tail.len().div_ceil(32) * 32;
+    let mut call_data = Vec::with_capacity(4 + (words.len() + 2) * 32 + padded_len);
+    call_data.extend_from_slice(&selector);
+
+    // Head: the key, or the offset to the string past the static words
+    match feed_id {
+        FeedId::String(_) => call_data.extend_from_slice(&U256::from((words.len() + 1) * 32).to_be_bytes::<32>()),
+        FeedId::Bytes32(key) => call_data.extend_from_slice(key.as_slice()),
+    }
+    for word in words {
+        call_data.extend_from_slice(&word.to_be_bytes::<32>());
+    }
+
+    // Tail: string length and padded content
+    if let FeedId::String(_) = feed_id {
+        call_data.extend_from_slice(&U256::from(tail.len()).to_be_bytes::<32>());
+        call_data.extend_from_slice(tail);
+        call_data.resize(call_data.len() + padded_len - tail.len(), 0);
+    }
+
+    Bytes::from(call_data)
+}
+
+/// Encode `updateTimestamp(uint256)` with a millisecond timestamp.
+pub fn encode_update_timestamp(timestamp: u64) -> Bytes {
+    let mut encoded = Vec::with_capacity(36);
+    encoded.extend_from_slice(&UPDATE_TIMESTAMP_SELECTOR);
+    let mut timestamp_bytes = [0u8; 32];
+    timestamp_bytes[24..].copy_from_slice(&timestamp.to_be_bytes());
+    encoded.extend_from_slice(&timestamp_bytes);
+    Bytes::from(encoded)
+}
+
+/// Encode `updateTimestampWithRound(uint256,uint256)` with a millisecond
+/// timestamp and the update's sequence number.
+pub fn encode_update_timestamp_with_round(timestamp: u64, round: u64) -> Bytes {
+    let mut encoded = Vec::with_capacity(68);
+    encoded.extend_from_slice(&UPDATE_TIMESTAMP_WITH_ROUND_SELECTOR);
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&timestamp.to_be_bytes());
+    encoded.extend_from_slice(&word);
+    word[24..].copy_from_slice(&round.to_be_bytes());
+    encoded.extend_from_slice(&word);
+    Bytes::from(encoded)
+}
+
+/// Encode a `(string, uint256)` call such as `updatePrice(string,uint256)`.
+pub fn encode_update_price(selector: [u8; 4], feed_id: &str, price: U256) -> Bytes {
+    let call_data = encode_feed_call(selector, FeedId::String(feed_id), &[price]);
+
+    debug!(
+        "Encoding updatePrice call - feed_id: {}, price: {}, selector: 0x{}, calldata length: {}",
+        feed_id,
+        price,
+        hex::encode(selector),
+        call_data.len()
+    );
+
+    call_data
+}
+
+/// Encode a `(string, int256)` call such as `updateValue(string,int256)`,
+/// for values that can go negative (funding rates, basis, spreads). An
+/// `int256` word is the value's two's complement, so the layout is the
+/// `uint256` one.
+pub fn encode_update_price_signed(selector: [u8; 4], feed_id: &str, value: I256) -> Bytes {
+    encode_update_price(selector, feed_id, value.into_raw())
+}
+
+/// Encode a call with four `uint256` params such as
+/// `updateGasPrices(uint256,uint256,uint256,uint256)` (base fee, priority
+/// fee, L1 base fee, L1 blob base fee - all in wei).
+pub fn encode_update_gas_prices(
+    selector: [u8; 4],
+    base_fee: u128,
+    priority_fee: u128,
+    l1_base_fee: u128,
+    blob_base_fee: u128,
+) -> Bytes {
+    let mut call_data = Vec::with_capacity(4 + 4 * 32);
+    call_data.extend_from_slice(&selector);
+    for value in [base_fee, priority_fee, l1_base_fee, blob_base_fee] {
+        call_data.extend_from_slice(&U256::from(value).to_be_bytes::<32>());
+    }
+    Bytes::from(call_data)
+}
+
+/// Encode a `(string, uint256, uint80, uint256)` call such as
+/// `updatePriceWithRound(string,uint256,uint80,uint256)`: the price plus the
+/// upstream round id and its `updatedAt` (unix seconds).
+pub fn encode_update_price_with_round(
+    selector: [u8; 4],
+    feed_id: &str,
+    price: U256,
+    round_id: u128,
+    updated_at: u64,
+) -> Bytes {
+    encode_feed_call(selector, FeedId::String(feed_id), &[price, U256::from(round_id), U256::from(updated_at)])
+}
+
+/// Encode a `(string, uint256, uint256)` call such as
+/// `updatePriceWithConfidence(string,uint256,uint256)`.
+pub fn encode_update_price_with_confidence(selector: [u8; 4], feed_id: &str, price: U256, confidence: U256) -> Bytes {
+    encode_feed_call(selector, FeedId::String(feed_id), &[price, confidence])
+}
+
+/// Encode `commitChain(uint256,bytes32)`: the hash-chain epoch and its anchor.
+pub fn encode_commit_chain(selector: [u8; 4], epoch: u64, anchor: B256) -> Bytes {
+    let mut call_data = Vec::with_capacity(4 + 2 * 32);
+    call_data.extend_from_slice(&selector);
+    call_data.extend_from_slice(&U256::from(epoch).to_be_bytes::<32>());
+    call_data.extend_from_slice(anchor.as_slice());
+    Bytes::from(call_data)
+}
+
+/// Encode `reveal(bytes32)` with the next hash-chain link.
+pub fn encode_reveal(selector: [u8; 4], preimage: B256) -> Bytes {
+    let mut call_data = Vec::with_capacity(4 + 32);
+    call_data.extend_from_slice(&selector);
+    call_data.extend_from_slice(preimage.as_slice());
+    Bytes::from(call_data)
+}
+
+/// Encode a `(uint64, bytes)` call such as `relayDrand(uint64,bytes)`: a
+/// drand round and its BLS signature.
+pub fn encode_relay_drand(selector: [u8; 4], round: u64, signature: &[u8]) -> Bytes {
+    let padded_len = signature.len().div_ceil(32) * 32;
+    let mut call_data = Vec::with_capacity(4 + 3 * 32 + padded_len);
+    call_data.extend_from_slice(&selector);
+
+    // Head: the round, then the offset to the bytes (2 words)
+    call_data.extend_from_slice(&U256::from(round).to_be_bytes::<32>());
+    call_data.extend_from_slice(&U256::from(2 * 32).to_be_bytes::<32>());
+
+    // Tail: length and padded content
+    call_data.extend_from_slice(&U256::from(signature.len()).to_be_bytes::<32>());
+    call_data.extend_from_slice(signature);
+    call_data.resize(call_data.len() + padded_len - signature.len(), 0);
+
+    Bytes::from(call_data)
+}
+
+/// Encode a `(string)` call such as `needsUpdate(string)`: a feed id.
+pub fn encode_string_call(selector: [u8; 4], value: &str) -> Bytes {
+    encode_feed_call(selector, FeedId::String(value), &[])
+}
+
+/// Encode a `(uint256, uint256, bytes32, bytes32)` call such as
+/// `relayBlock(uint256,uint256,bytes32,bytes32)`: a source chain id, block
+/// number, block hash and parent hash.
+pub fn encode_relay_block(selector: [u8; 4], chain_id: u64, number: u64, hash: B256, parent: B256) -> Bytes {
+    let mut call_data = Vec::with_capacity(4 + 4 * 32);
+    call_data.extend_from_slice(&selector);
+    call_data.extend_from_slice(&U256::from(chain_id).to_be_bytes::<32>());
+    call_data.extend_from_slice(&U256::from(number).to_be_bytes::<32>());
+    call_data.extend_from_slice(hash.as_slice());
+    call_data.extend_from_slice(parent.as_slice());
+    Bytes::from(call_data)
+}
@@ -0,0 +1,17 @@
+use nonzu_sdk::error_handling::generic_error_handler::ErrorHandlerConfig;
+use std::time::Duration;
+
+/// Error handling shared by the oracles: pause everything for 3 seconds, reset
+/// nonces from chain and never retry (a retried update would carry stale data).
+pub fn oracle_error_handler_config() -> ErrorHandlerConfig {
+    ErrorHandlerConfig {
+        pause_duration: Duration::from_secs(3), // Give more time for recovery
+        queue_while_paused: false, // Don't accumulate jobs during pause
+        retry_failed_tx: false, // Don't retry - we want fresh data for each tx
+        max_retries: 3,
+        check_rpc_on_error: true,
+        reset_nonces_on_error: true, // Critical for handling nonce errors
+        parse_errors: true, // Enable parsing with custom parser
+        log_raw_errors: true, // Log raw error messages for debugging
+    }
+}
@@ -0,0 +1,141 @@
+//! Failed-update counts by [`ErrorCategory`] over time.
+//!
+//! Keeps lifetime totals plus per-minute buckets for the last hour, so a bad
+//! night can be told apart (nonce churn vs. RPC flakiness) without grepping
+//! logs. Served at `/errors` by the status server and optionally logged every
+//! `ERROR_METRICS_LOG_SECS`.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::error_policy::ErrorCategory;
+
+/// Per-minute buckets kept
+const RETENTION_MINUTES: u64 = 60;
+
+type Counts = BTreeMap<ErrorCategory, u64>;
+
+#[derive(Debug, Default)]
+struct Inner {
+    totals: Counts,
+    /// (unix minute, counts), oldest first
+    minutes: VecDeque<(u64, Counts)>,
+}
+
+impl Inner {
+    fn prune(&mut self, now_minute: u64) {
+        while let Some((minute, _)) = self.minutes.front() {
+            if minute + RETENTION_MINUTES > now_minute {
+                break;
+            }
+            self.minutes.pop_front();
+        }
+    }
+
+    /// Sum of the buckets within the last `minutes` minutes
+    fn sum_recent(&self, minutes: u64, now_minute: u64) -> Counts {
+        let mut counts = Counts::new();
+        for (_, bucket) in self.minutes.iter().filter(|(m, _)| m + minutes > now_minute) {
+            for (category, count) in bucket {
+                *counts.entry(*category).or_default() += count;
+            }
+        }
+        counts
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ErrorMetrics {
+    inner: Mutex<Inner>,
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+impl ErrorMetrics {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn record(&self, category: ErrorCategory) {
+        self.record_at(category, unix_secs());
+    }
+
+    pub fn record_at(&self, category: ErrorCategory, unix_secs: u64) {
+        let minute = unix_secs / 60;
+        let mut inner = self.inner.lock();
+        *inner.totals.entry(category).or_default() += 1;
+        match inner.minutes.back_mut() {
+            Some((last, counts)) if *last == minute => *counts.entry(category).or_default() += 1,
+            _ => inner.minutes.push_back((minute, BTreeMap::from([(category, 1)]))),
+        }
+        inner.prune(minute);
+    }
+
+    pub fn total(&self, category: ErrorCategory) -> u64 {
+        self.inner.lock().totals.get(&category).copied().unwrap_or(0)
+    }
+
+    /// Counts over the trailing `window` (whole minutes, at most an hour)
+    pub fn recent(&self, window: Duration) -> BTreeMap<ErrorCategory, u64> {
+        self.recent_at(window, unix_secs())
+    }
+
+    pub fn recent_at(&self, window: Duration, unix_secs: u64) -> BTreeMap<ErrorCategory, u64> {
+        let minutes = window.as_secs().div_ceil(60).max(1);
+        self.inner.lock().sum_recent(minutes, unix_secs / 60)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let now_minute = unix_secs() / 60;
+        let mut inner = self.inner.lock();
+        inner.prune(now_minute);
+        json!({
+            "total": inner.totals,
+            "last_5m": inner.sum_recent(5, now_minute),
+            "last_hour": inner.sum_recent(RETENTION_MINUTES, now_minute),
+            "per_minute": inner
+                .minutes
+                .iter()
+                .map(|(minute, counts)| json!({ "minute": minute * 60, "counts": counts }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// One-line summary of the trailing `window`
+    pub fn summary(&self, window: Duration) -> String {
+        let counts = self.recent(window);
+        if counts.is_empty() {
+            return "none".to_string();
+        }
+        counts.iter().map(|(c, n)| format!("{}={}", c, n)).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Log the summary every `ERROR_METRICS_LOG_SECS`, if set
+    pub fn spawn_reporter_from_env(self: &Arc<Self>) -> Result<Option<JoinHandle<()>>> {
+        let Ok(secs) = std::env::var("ERROR_METRICS_LOG_SECS") else {
+            return Ok(None);
+        };
+        let secs: u64 = secs.parse().context("Invalid ERROR_METRICS_LOG_SECS")?;
+        if secs == 0 {
+            return Ok(None);
+        }
+        let metrics = self.clone();
+        let period = Duration::from_secs(secs);
+        Ok(Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                info!("📉 Errors in the last {}s: {}", secs, metrics.summary(period));
+            }
+        })))
+    }
+}
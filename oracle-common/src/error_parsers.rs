@@ -0,0 +1,175 @@
+//! Pluggable parsers for node-specific error strings.
+//!
+//! Nodes report many failures as free-form JSON-RPC messages (RISE's
+//! "missing nonce ... with nonce X first", geth's "nonce too low: next nonce
+//! X, tx nonce Y", ...). Parsers turn those into an [`ErrorCategory`] plus any
+//! numbers worth acting on, so handling can be tuned per node without
+//! forking the SDK handler.
+//!
+//! Deployments register extra parsers on an [`ErrorParsers`] registry (a
+//! [`RegexParser`] covers most cases); [`ErrorParsers::rise`] is the pack
+//! used by the oracles.
+//!
+//! `fuzz/` holds cargo-fuzz targets for the pack (`cargo +nightly fuzz run
+//! error_parsers fuzz/corpus/error_parsers fuzz/seeds/error_parsers`), seeded
+//! with production messages; add new node error formats to the seeds.
+
+use anyhow::Result;
+use nonzu_sdk::RiseError;
+use regex::Regex;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error_policy::ErrorCategory;
+
+/// What a parser extracted from an error message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedError {
+    pub category: ErrorCategory,
+    /// Name of the parser that matched
+    pub parser: String,
+    /// Nonce the node expects next, when the message says
+    pub expected_nonce: Option<u64>,
+    /// Nonce the failed transaction used, when the message says
+    pub tx_nonce: Option<u64>,
+    /// Revert reason or other free-text detail
+    pub detail: Option<String>,
+}
+
+/// Recognises one family of error messages
+pub trait ErrorMessageParser: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn parse(&self, message: &str) -> Option<ParsedError>;
+}
+
+/// Regex-based parser. Named groups `expected`, `nonce` (both read as the
+/// expected nonce), `tx_nonce` and `detail` are extracted when present.
+pub struct RegexParser {
+    name: String,
+    regex: Regex,
+    category: ErrorCategory,
+}
+
+impl RegexParser {
+    /// `pattern` is matched case-insensitively
+    pub fn new(name: impl Into<String>, pattern: &str, category: ErrorCategory) -> Result<Self> {
+        Ok(Self {
+            name: name.into(),
+            regex: Regex::new(&format!("(?i){}", pattern))?,
+            category,
+        })
+    }
+}
+
+impl ErrorMessageParser for RegexParser {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parse(&self, message: &str) -> Option<ParsedError> {
+        let captures = self.regex.captures(message)?;
+        let number = |group: &str| captures.name(group).and_then(|m| m.as_str().parse().ok());
+        Some(ParsedError {
+            category: self.category,
+            parser: self.name.clone(),
+            expected_nonce: number("expected").or_else(|| number("nonce")),
+            tx_nonce: number("tx_nonce"),
+            detail: captures
+                .name("detail")
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|d| !d.is_empty()),
+        })
+    }
+}
+
+/// Ordered parser registry; the first match wins
+#[derive(Clone, Default)]
+pub struct ErrorParsers {
+    parsers: Vec<Arc<dyn ErrorMessageParser>>,
+}
+
+impl fmt::Debug for ErrorParsers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.parsers.iter().map(|p| p.name())).finish()
+    }
+}
+
+impl ErrorParsers {
+    /// Parsers for RISE nodes (and the geth-style messages they pass through)
+    pub fn rise() -> Self {
+        let rules: [(&str, &str, ErrorCategory); 9] = [
+            (
+                "rise_missing_nonce",
+                r"missing nonce.*with nonce (?P<expected>\d+) first",
+                ErrorCategory::NonceGap,
+            ),
+            (
+                "nonce_too_low",
+                r"nonce too low(?:: next nonce (?P<expected>\d+), tx nonce (?P<tx_nonce>\d+))?",
+                ErrorCategory::NonceTooLow,
+            ),
+            (
+                "nonce_too_high",
+                r"nonce too high(?:: next nonce (?P<expected>\d+), tx nonce (?P<tx_nonce>\d+))?",
+                ErrorCategory::NonceGap,
+            ),
+            ("already_known", r"already known", ErrorCategory::NonceTooLow),
+            ("underpriced", r"(?:replacement )?transaction underpriced", ErrorCategory::Underpriced),
+            (
+                "insufficient_funds",
+                r"insufficient funds for (?P<detail>gas \* price \+ value(?:: have \d+ want \d+)?)",
+                ErrorCategory::InsufficientFunds,
+            ),
+            ("reverted", r"execution reverted(?::\s*(?P<detail>.*))?", ErrorCategory::Revert),
+            (
+                "timeout",
+                r"timed out waiting for receipt|request timed out|operation timed out|deadline has elapsed",
+                ErrorCategory::RpcTimeout,
+            ),
+            (
+                "connection",
+                r"error sending request|connection (?:refused|reset|closed)|broken pipe|tcp connect error",
+                ErrorCategory::Connection,
+            ),
+        ];
+
+        let mut parsers = Self::default();
+        for (name, pattern, category) in rules {
+            parsers.register(Arc::new(
+                RegexParser::new(name, pattern, category).expect("built-in error pattern is valid"),
+            ));
+        }
+        parsers
+    }
+
+    /// Add a parser, consulted after the ones already registered
+    pub fn register(&mut self, parser: Arc<dyn ErrorMessageParser>) -> &mut Self {
+        self.parsers.push(parser);
+        self
+    }
+
+    /// Add a parser ahead of the existing ones, to override them
+    pub fn register_first(&mut self, parser: Arc<dyn ErrorMessageParser>) -> &mut Self {
+        self.parsers.insert(0, parser);
+        self
+    }
+
+    pub fn parse(&self, message: &str) -> Option<ParsedError> {
+        self.parsers.iter().find_map(|p| p.parse(message))
+    }
+
+    /// Category for `error`: typed SDK variants first, then the parsers, then
+    /// keyword matching
+    pub fn classify(&self, error: &RiseError) -> ErrorCategory {
+        match ErrorCategory::classify_typed(error) {
+            Some(category) => category,
+            None => {
+                let message = error.to_string();
+                self.parse(&message)
+                    .map(|parsed| parsed.category)
+                    .unwrap_or_else(|| ErrorCategory::classify_message(&message))
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Per-category error handling policy.
+//!
+//! `oracle_error_handler_config()` treats every failure alike (pause 3s,
+//! reset nonces, no retry). `ERROR_POLICY` overrides the action per error
+//! category, leaving the SDK's nonce reset and RPC checks in place:
+//!
+//! ```text
+//! ERROR_POLICY=nonce_gap=pause:1s,revert=skip,underpriced=retry:3,rpc_timeout=pause:5s,insufficient_funds=remove-key
+//! ```
+//!
+//! Actions are `retry[:max_retries]` (then skip), `pause[:duration]`, `skip`
+//! and `remove-key`. Categories not listed keep the default handling.
+//!
+//! A pause for a failed feed update in a feed-scoped category
+//! (`FEED_SCOPED_ERRORS`, see [`crate::feed_pause`]) holds just that feed
+//! instead of the worker pool.
+
+use alloy::primitives::Address;
+use anyhow::{anyhow, bail, Result};
+use nonzu_sdk::error_handling::{ErrorAction, ErrorActionHook};
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::RiseError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error};
+
+use crate::error_metrics::ErrorMetrics;
+use crate::error_parsers::ErrorParsers;
+use crate::events::{publish_event, OracleEvent};
+use crate::feed_pause::FeedPauses;
+use crate::revert::RevertDecoder;
+
+/// Coarse error buckets shared by policies, metrics and reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Nonce already used
+    NonceTooLow,
+    /// "missing nonce X first" - an earlier nonce never landed
+    NonceGap,
+    Revert,
+    Underpriced,
+    RpcTimeout,
+    InsufficientFunds,
+    Connection,
+    Other,
+}
+
+impl ErrorCategory {
+    pub const ALL: [ErrorCategory; 8] = [
+        Self::NonceTooLow,
+        Self::NonceGap,
+        Self::Revert,
+        Self::Underpriced,
+        Self::RpcTimeout,
+        Self::InsufficientFunds,
+        Self::Connection,
+        Self::Other,
+    ];
+
+    pub fn classify(error: &RiseError) -> Self {
+        Self::classify_typed(error).unwrap_or_else(|| Self::classify_message(&error.to_string()))
+    }
+
+    /// Category of the typed SDK variants; `None` for message-only errors
+    pub fn classify_typed(error: &RiseError) -> Option<Self> {
+        match error {
+            RiseError::NonceTooLow { .. } => Some(Self::NonceTooLow),
+            RiseError::ContractReverted { .. } => Some(Self::Revert),
+            RiseError::TransactionUnderpriced { .. } => Some(Self::Underpriced),
+            RiseError::RpcTimeout { .. } => Some(Self::RpcTimeout),
+            RiseError::InsufficientFunds { .. } => Some(Self::InsufficientFunds),
+            _ => None,
+        }
+    }
+
+    /// Bucket a raw node / transport error message
+    pub fn classify_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("nonce too low") || message.contains("already known") {
+            Self::NonceTooLow
+        } else if message.contains("missing nonce") || message.contains("nonce too high") || message.contains("nonce gap") {
+            Self::NonceGap
+        } else if message.contains("underpriced") {
+            Self::Underpriced
+        } else if message.contains("insufficient funds") {
+            Self::InsufficientFunds
+        } else if message.contains("revert") {
+            Self::Revert
+        } else if message.contains("timeout") || message.contains("timed out") {
+            Self::RpcTimeout
+        } else if message.contains("connect") || message.contains("connection") || message.contains("broken pipe") {
+            Self::Connection
+        } else {
+            Self::Other
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NonceTooLow => "nonce_too_low",
+            Self::NonceGap => "nonce_gap",
+            Self::Revert => "revert",
+            Self::Underpriced => "underpriced",
+            Self::RpcTimeout => "rpc_timeout",
+            Self::InsufficientFunds => "insufficient_funds",
+            Self::Connection => "connection",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ErrorCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s.trim().replace('-', "_"))
+            .ok_or_else(|| anyhow!("Unknown error category '{}'", s))
+    }
+}
+
+/// What to do with a failed update in a category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyAction {
+    /// Retry the same request up to `max_retries` times, then skip it
+    Retry { max_retries: u32 },
+    /// Pause the worker pool (SDK default duration when `None`)
+    Pause(Option<Duration>),
+    /// Drop the request and carry on
+    Skip,
+    /// Take the sending key out of rotation
+    RemoveKey,
+}
+
+impl FromStr for PolicyAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (action, arg) = match s.trim().split_once(':') {
+            Some((action, arg)) => (action, Some(arg.trim())),
+            None => (s.trim(), None),
+        };
+        match (action, arg) {
+            ("retry", None) => Ok(Self::Retry { max_retries: 3 }),
+            ("retry", Some(n)) => Ok(Self::Retry { max_retries: n.parse()? }),
+            ("pause", None) => Ok(Self::Pause(None)),
+            ("pause", Some(d)) => Ok(Self::Pause(Some(parse_duration(d)?))),
+            ("skip", None) => Ok(Self::Skip),
+            ("remove-key", None) | ("remove_key", None) => Ok(Self::RemoveKey),
+            _ => bail!("Invalid error policy action '{}' (expected retry[:n], pause[:duration], skip or remove-key)", s),
+        }
+    }
+}
+
+/// `500ms`, `3s` or a bare number of seconds
+fn parse_duration(s: &str) -> Result<Duration> {
+    if let Some(ms) = s.strip_suffix("ms") {
+        Ok(Duration::from_millis(ms.parse()?))
+    } else if let Some(secs) = s.strip_suffix('s') {
+        Ok(Duration::from_secs_f64(secs.parse()?))
+    } else {
+        Ok(Duration::from_secs_f64(s.parse()?))
+    }
+}
+
+/// Category -> action overrides on top of the SDK's default handling
+#[derive(Debug, Clone, Default)]
+pub struct ErrorPolicy {
+    actions: HashMap<ErrorCategory, PolicyAction>,
+    parsers: ErrorParsers,
+    metrics: Arc<ErrorMetrics>,
+    reverts: RevertDecoder,
+    feed_pauses: Arc<FeedPauses>,
+}
+
+impl ErrorPolicy {
+    /// Classify message-only errors with `parsers` instead of keywords alone
+    pub fn with_parsers(mut self, parsers: ErrorParsers) -> Self {
+        self.parsers = parsers;
+        self
+    }
+
+    pub fn with(mut self, category: ErrorCategory, action: PolicyAction) -> Self {
+        self.actions.insert(category, action);
+        self
+    }
+
+    /// Parse `category=action,...`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut policy = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (category, action) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid error policy entry '{}' (expected category=action)", entry))?;
+            policy.actions.insert(category.parse()?, action.parse()?);
+        }
+        Ok(policy)
+    }
+
+    /// Decode revert data with `reverts` (oracle contract errors by default)
+    pub fn with_revert_decoder(mut self, reverts: RevertDecoder) -> Self {
+        self.reverts = reverts;
+        self
+    }
+
+    /// Hold failing feeds with `feed_pauses` rather than pausing the pool
+    pub fn with_feed_pauses(mut self, feed_pauses: Arc<FeedPauses>) -> Self {
+        self.feed_pauses = feed_pauses;
+        self
+    }
+
+    /// From `ERROR_POLICY`; empty (SDK defaults only) when unset. Errors are
+    /// classified with the RISE parser pack, reverts decoded with
+    /// [`RevertDecoder::from_env`] and feed-scoped categories read with
+    /// [`FeedPauses::from_env`].
+    pub fn from_env() -> Result<Arc<Self>> {
+        let policy = match std::env::var("ERROR_POLICY") {
+            Ok(spec) => Self::parse(&spec)?,
+            Err(_) => Self::default(),
+        };
+        Ok(Arc::new(
+            policy
+                .with_parsers(ErrorParsers::rise())
+                .with_revert_decoder(RevertDecoder::from_env()?)
+                .with_feed_pauses(Arc::new(FeedPauses::from_env()?)),
+        ))
+    }
+
+    /// Counts of every error seen, by category
+    pub fn metrics(&self) -> Arc<ErrorMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Feeds held by feed-scoped failures
+    pub fn feed_pauses(&self) -> Arc<FeedPauses> {
+        self.feed_pauses.clone()
+    }
+
+    pub fn action_for(&self, category: ErrorCategory) -> Option<PolicyAction> {
+        self.actions.get(&category).copied()
+    }
+
+    /// Action for `error` given the SDK's `default` choice
+    pub fn resolve(&self, error: &RiseError, key: Address, attempt: u32, default: ErrorAction) -> ErrorAction {
+        self.resolve_category(self.parsers.classify(error), key, attempt, default)
+    }
+
+    /// Like [`resolve`](Self::resolve) for an update of `feed`: a pause in a
+    /// feed-scoped category holds that feed and lets the pool carry on
+    pub fn resolve_for_feed(&self, error: &RiseError, feed: Option<&str>, key: Address, attempt: u32, default: ErrorAction) -> ErrorAction {
+        let category = self.parsers.classify(error);
+        match (self.resolve_category(category, key, attempt, default), feed) {
+            (ErrorAction::Pause(duration), Some(feed)) if self.feed_pauses.is_scoped(category) => {
+                self.feed_pauses.pause(feed, duration, category);
+                ErrorAction::Continue
+            }
+            (action, _) => action,
+        }
+    }
+
+    fn resolve_category(&self, category: ErrorCategory, key: Address, attempt: u32, default: ErrorAction) -> ErrorAction {
+        self.metrics.record(category);
+        let Some(action) = self.action_for(category) else {
+            return default;
+        };
+        debug!("Error policy for {}: {:?} (attempt {})", category, action, attempt);
+        match action {
+            PolicyAction::Retry { max_retries } if attempt < max_retries => ErrorAction::Retry,
+            PolicyAction::Retry { .. } | PolicyAction::Skip => ErrorAction::Continue,
+            PolicyAction::Pause(Some(duration)) => ErrorAction::Pause(duration),
+            PolicyAction::Pause(None) => match default {
+                ErrorAction::Pause(duration) => ErrorAction::Pause(duration),
+                _ => ErrorAction::Pause(crate::oracle_error_handler_config().pause_duration),
+            },
+            PolicyAction::RemoveKey => ErrorAction::RemoveKey(key),
+        }
+    }
+}
+
+impl ErrorActionHook for ErrorPolicy {
+    fn on_error(&self, error: &RiseError, tx_request: &TxRequest, key: Address, attempt: u32, default: ErrorAction) -> ErrorAction {
+        if let Some(reason) = self.reverts.describe(error) {
+            let label = ["target", "feed_id"]
+                .iter()
+                .find_map(|k| tx_request.metadata.get(*k).cloned())
+                .unwrap_or_else(|| tx_request.to.to_string());
+            error!("↩️ [{}] Oracle update reverted: {}", label, reason);
+        }
+        let feed = tx_request.metadata.get("pause_scope").map(String::as_str);
+        let action = self.resolve_for_feed(error, feed, key, attempt, default);
+        if let ErrorAction::RemoveKey(key) = action {
+            publish_event(OracleEvent::KeyRemoved { key });
+        }
+        action
+    }
+}
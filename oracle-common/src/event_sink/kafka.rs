@@ -0,0 +1,71 @@
+//! Kafka producer for the event streams. Pure Rust (rskafka), so the images
+//! need no librdkafka.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use rskafka::client::partition::{Compression, PartitionClient, UnknownTopicHandling};
+use rskafka::client::{Client, ClientBuilder};
+use rskafka::record::Record;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::{SinkRecord, StreamSink};
+
+/// Records go to partition 0 of each topic, which keeps them in emission
+/// order; topics must exist (no auto-creation)
+pub struct KafkaSink {
+    client: Client,
+    partitions: Mutex<HashMap<String, Arc<PartitionClient>>>,
+}
+
+impl KafkaSink {
+    /// Connect to comma-separated bootstrap `brokers`
+    pub async fn connect(brokers: &str) -> Result<Self> {
+        let brokers: Vec<String> = brokers.split(',').map(str::trim).filter(|b| !b.is_empty()).map(String::from).collect();
+        let client = ClientBuilder::new(brokers.clone())
+            .build()
+            .await
+            .with_context(|| format!("Failed to connect to Kafka at {}", brokers.join(",")))?;
+        Ok(Self { client, partitions: Mutex::new(HashMap::new()) })
+    }
+
+    async fn partition(&self, topic: &str) -> Result<Arc<PartitionClient>> {
+        let mut partitions = self.partitions.lock().await;
+        if let Some(partition) = partitions.get(topic) {
+            return Ok(partition.clone());
+        }
+        let partition = self
+            .client
+            .partition_client(topic, 0, UnknownTopicHandling::Error)
+            .await
+            .with_context(|| format!("Kafka topic {} is not available", topic))?;
+        let partition = Arc::new(partition);
+        partitions.insert(topic.to_string(), partition.clone());
+        Ok(partition)
+    }
+}
+
+#[async_trait]
+impl StreamSink for KafkaSink {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn send(&self, records: &[SinkRecord]) -> Result<()> {
+        let mut by_topic: BTreeMap<&str, Vec<Record>> = BTreeMap::new();
+        for record in records {
+            by_topic.entry(&record.topic).or_default().push(Record {
+                key: Some(record.key.as_bytes().to_vec()),
+                value: Some(record.payload.as_bytes().to_vec()),
+                headers: BTreeMap::new(),
+                timestamp: Utc::now(),
+            });
+        }
+        for (topic, batch) in by_topic {
+            self.partition(topic).await?.produce(batch, Compression::NoCompression).await?;
+        }
+        Ok(())
+    }
+}
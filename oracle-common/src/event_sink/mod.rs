@@ -0,0 +1,286 @@
+//! Structured event streams for analytics, sent to Kafka or NATS.
+//!
+//! With `EVENT_SINK=kafka` or `EVENT_SINK=nats` every trade a source
+//! receives, every value a feed computes and every update outcome is taken
+//! off the [`EventBus`](crate::events::EventBus) and sent, as one JSON
+//! record each, to three streams:
+//!
+//! | Stream | Key | Events |
+//! |--------|-----|--------|
+//! | `<prefix>.trades` | symbol | `trade_received` |
+//! | `<prefix>.prices` | feed | `price_computed` |
+//! | `<prefix>.updates` | feed | `update_published`, `update_failed`, `gap_detected` |
+//!
+//! Kafka gets one topic per stream (records keyed as above); NATS gets the
+//! key appended to the subject, so `nonzu.prices.BTCUSD` can be subscribed
+//! to on its own and `nonzu.prices.>` follows every feed.
+//!
+//! Every record carries `event`, `emitted_ms` and the event's fields, e.g.
+//!
+//! ```text
+//! {"event":"price_computed","feed":"BTCUSD","value":97012.5,"emitted_ms":1735689600037}
+//! ```
+//!
+//! Records are queued without blocking the publisher and sent in batches by
+//! a task of their own; when the broker can't keep up, records beyond
+//! `EVENT_SINK_QUEUE` are dropped and counted. New brokers implement
+//! [`StreamSink`].
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `EVENT_SINK` | unset | `kafka` or `nats` (unset disables) |
+//! | `EVENT_SINK_URL` | required | Kafka bootstrap brokers (`host:port,...`) or NATS server URL |
+//! | `EVENT_SINK_PREFIX` | `nonzu` | Topic / subject prefix |
+//! | `EVENT_SINK_TRADES` | `true` | Stream trades (by far the busiest stream) |
+//! | `EVENT_SINK_QUEUE` | `8192` | Records buffered while the broker is slow |
+
+pub mod kafka;
+pub mod nats;
+
+pub use kafka::*;
+pub use nats::*;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::events::{EventSubscriber, OracleEvent};
+
+/// Most records handed to a sink at once
+const MAX_BATCH: usize = 512;
+
+/// Pause after a failed send before taking the next batch
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// The stream an event belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventStream {
+    Trades,
+    Prices,
+    Updates,
+}
+
+impl EventStream {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventStream::Trades => "trades",
+            EventStream::Prices => "prices",
+            EventStream::Updates => "updates",
+        }
+    }
+}
+
+/// One record: `topic` is `<prefix>.<stream>`
+#[derive(Debug, Clone)]
+pub struct SinkRecord {
+    pub stream: EventStream,
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+}
+
+/// A broker records are sent to
+#[async_trait]
+pub trait StreamSink: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Send a batch; on error the whole batch counts as lost
+    async fn send(&self, records: &[SinkRecord]) -> Result<()>;
+}
+
+#[derive(Debug, Clone)]
+pub struct EventSinkConfig {
+    pub prefix: String,
+    pub trades: bool,
+    pub queue: usize,
+}
+
+impl Default for EventSinkConfig {
+    fn default() -> Self {
+        Self { prefix: "nonzu".to_string(), trades: true, queue: 8192 }
+    }
+}
+
+pub struct EventSink {
+    config: EventSinkConfig,
+    broker: &'static str,
+    sender: mpsc::Sender<SinkRecord>,
+    sent: AtomicU64,
+    dropped: AtomicU64,
+    failed_batches: AtomicU64,
+}
+
+impl EventSink {
+    pub fn spawn(sink: Arc<dyn StreamSink>, config: EventSinkConfig) -> (Arc<Self>, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(config.queue.max(1));
+        let events = Arc::new(Self {
+            config,
+            broker: sink.name(),
+            sender,
+            sent: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            failed_batches: AtomicU64::new(0),
+        });
+        let handle = tokio::spawn(events.clone().run(sink, receiver));
+        (events, handle)
+    }
+
+    /// From `EVENT_SINK` and friends; `None` when unset. Connects to the
+    /// broker before returning. Subscribe the sink to the event bus to feed it.
+    pub async fn from_env() -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let kind = match std::env::var("EVENT_SINK") {
+            Ok(kind) if !kind.is_empty() => kind.to_lowercase(),
+            _ => return Ok(None),
+        };
+        let url = std::env::var("EVENT_SINK_URL").context("EVENT_SINK_URL must be set with EVENT_SINK")?;
+        let mut config = EventSinkConfig::default();
+        if let Ok(prefix) = std::env::var("EVENT_SINK_PREFIX") {
+            config.prefix = prefix;
+        }
+        if let Ok(v) = std::env::var("EVENT_SINK_TRADES") {
+            config.trades = v.parse().context("Invalid EVENT_SINK_TRADES")?;
+        }
+        if let Ok(v) = std::env::var("EVENT_SINK_QUEUE") {
+            config.queue = v.parse().context("Invalid EVENT_SINK_QUEUE")?;
+        }
+
+        let sink: Arc<dyn StreamSink> = match kind.as_str() {
+            "kafka" => Arc::new(KafkaSink::connect(&url).await?),
+            "nats" => Arc::new(NatsSink::connect(&url).await?),
+            other => bail!("Unknown EVENT_SINK '{}' (expected kafka or nats)", other),
+        };
+        info!("📤 Streaming oracle events to {} under {}.*", kind, config.prefix);
+        Ok(Some(Self::spawn(sink, config)))
+    }
+
+    async fn run(self: Arc<Self>, sink: Arc<dyn StreamSink>, mut receiver: mpsc::Receiver<SinkRecord>) {
+        let mut batch = Vec::with_capacity(MAX_BATCH);
+        while receiver.recv_many(&mut batch, MAX_BATCH).await > 0 {
+            match sink.send(&batch).await {
+                Ok(()) => {
+                    self.sent.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    self.failed_batches.fetch_add(1, Ordering::Relaxed);
+                    self.dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    warn!("⚠️ {} send of {} record(s) failed: {:#}", self.broker, batch.len(), e);
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            }
+            batch.clear();
+        }
+    }
+
+    /// The record for `event`, if it belongs to a stream
+    pub fn record(&self, event: &OracleEvent) -> Option<SinkRecord> {
+        let (stream, key, mut payload) = match event {
+            OracleEvent::TradeReceived { source, symbol, price, quantity, timestamp_ms } => {
+                if !self.config.trades {
+                    return None;
+                }
+                (
+                    EventStream::Trades,
+                    symbol,
+                    json!({
+                        "source": source,
+                        "symbol": symbol,
+                        "price": price,
+                        "quantity": quantity,
+                        "trade_time_ms": timestamp_ms,
+                    }),
+                )
+            }
+            OracleEvent::ValueComputed { feed, value } => {
+                (EventStream::Prices, feed, json!({ "feed": feed, "value": value }))
+            }
+            OracleEvent::UpdatePublished { feed, value, tx_hash, block_number, latency, drift_ms } => (
+                EventStream::Updates,
+                feed,
+                json!({
+                    "feed": feed,
+                    "value": value,
+                    "tx_hash": tx_hash,
+                    "block_number": block_number.map(|b| b.saturating_to::<u64>()),
+                    "latency_ms": latency.map(|l| l.as_secs_f64() * 1000.0),
+                    "drift_ms": drift_ms,
+                }),
+            ),
+            OracleEvent::UpdateFailed { feed, value, latency, drift_ms } => (
+                EventStream::Updates,
+                feed,
+                json!({
+                    "feed": feed,
+                    "value": value,
+                    "latency_ms": latency.map(|l| l.as_secs_f64() * 1000.0),
+                    "drift_ms": drift_ms,
+                }),
+            ),
+            OracleEvent::GapDetected { feed, from_ms, to_ms } => (
+                EventStream::Updates,
+                feed,
+                json!({
+                    "feed": feed,
+                    "gap_from_ms": from_ms,
+                    "gap_to_ms": to_ms,
+                    "gap_ms": to_ms.saturating_sub(*from_ms),
+                }),
+            ),
+            _ => return None,
+        };
+
+        let event_name = match event {
+            OracleEvent::ValueComputed { .. } => "price_computed",
+            other => other.kind(),
+        };
+        if let Value::Object(fields) = &mut payload {
+            fields.insert("event".into(), event_name.into());
+            fields.insert("emitted_ms".into(), now_ms().into());
+        }
+        Some(SinkRecord {
+            stream,
+            topic: format!("{}.{}", self.config.prefix, stream.as_str()),
+            key: key.clone(),
+            payload: payload.to_string(),
+        })
+    }
+
+    /// Records handed to the broker
+    pub fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    /// Records lost to a full queue or a failed send
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "broker": self.broker,
+            "prefix": self.config.prefix,
+            "trades": self.config.trades,
+            "sent": self.sent(),
+            "dropped": self.dropped(),
+            "failed_batches": self.failed_batches.load(Ordering::Relaxed),
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl EventSubscriber for EventSink {
+    fn on_event(&self, event: &OracleEvent) {
+        let Some(record) = self.record(event) else { return };
+        if self.sender.try_send(record).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! NATS publisher for the event streams (core NATS, no JetStream - add a
+//! stream on the server to keep history).
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::{SinkRecord, StreamSink};
+
+/// Publishes each record to `<topic>.<key>`
+pub struct NatsSink {
+    client: async_nats::Client,
+}
+
+impl NatsSink {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = async_nats::connect(url).await.with_context(|| format!("Failed to connect to NATS at {}", url))?;
+        Ok(Self { client })
+    }
+}
+
+/// `topic.key`, with the dots and whitespace a key may contain replaced so
+/// it stays a single subject token
+pub fn nats_subject(record: &SinkRecord) -> String {
+    let key: String = record.key.chars().map(|c| if c == '.' || c.is_whitespace() { '_' } else { c }).collect();
+    format!("{}.{}", record.topic, key)
+}
+
+#[async_trait]
+impl StreamSink for NatsSink {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn send(&self, records: &[SinkRecord]) -> Result<()> {
+        for record in records {
+            self.client.publish(nats_subject(record), record.payload.clone().into()).await?;
+        }
+        self.client.flush().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! In-process event bus.
+//!
+//! Triggers, the error policy and sources publish [`OracleEvent`]s to the
+//! process-wide [`EventBus`]; alerting, persistence and metrics subscribe with
+//! an [`EventSubscriber`] instead of being wired into every trigger's
+//! `on_complete`. [`UpdateHistory`](crate::history::UpdateHistory) and
+//! [`Heartbeat`](crate::heartbeat::Heartbeat) are subscribers.
+//!
+//! Delivery is synchronous on the publishing task, in subscription order.
+//! Subscribers must return quickly - anything slow belongs on a channel to
+//! a task of its own.
+
+use alloy::primitives::{Address, B256, U256};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::RwLock;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How often the pause watcher looks at the worker pool
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub enum OracleEvent {
+    /// A source received a trade (high volume - most subscribers skip these)
+    TradeReceived { source: String, symbol: String, price: f64, quantity: f64, timestamp_ms: u64 },
+    /// A feed computed a fresh value (e.g. a TWAP), published or not
+    ValueComputed { feed: String, value: f64 },
+    /// An update was confirmed on chain
+    UpdatePublished {
+        feed: String,
+        value: Option<f64>,
+        tx_hash: Option<B256>,
+        block_number: Option<U256>,
+        latency: Option<Duration>,
+        drift_ms: i64,
+    },
+    /// An update failed to build, submit or land
+    UpdateFailed {
+        feed: String,
+        value: Option<f64>,
+        latency: Option<Duration>,
+        drift_ms: i64,
+    },
+    /// Nothing was published between the timestamps `from_ms` and `to_ms`
+    /// (longer than the feed's gap threshold)
+    GapDetected { feed: String, from_ms: u64, to_ms: u64 },
+    /// Too few of a feed's sources are fresh and agree; updates are held
+    QuorumLost { feed: String, fresh: usize, agreeing: usize, required: usize },
+    QuorumRestored { feed: String },
+    /// A feed's value is further than allowed from its reference; updates are held
+    ReferenceDiverged { feed: String, value: f64, reference: f64, divergence_bps: f64 },
+    ReferenceRestored { feed: String },
+    /// The worker pool behind `scope` (a target or chain) was paused
+    Paused { scope: String },
+    Resumed { scope: String },
+    /// A feed was halted until an operator resumes it (see [`crate::halt`])
+    Halted { scope: String, reason: String },
+    HaltCleared { scope: String },
+    /// The error handler took a key out of rotation
+    KeyRemoved { key: Address },
+    /// A source's websocket dropped and is about to reconnect
+    WsReconnect { source: String, error: Option<String> },
+    /// Trade ids jumped: `missed` trades of `symbol` never arrived
+    TradeGap { source: String, symbol: String, missed: u64 },
+    /// Chaos mode broke something on purpose (see [`crate::chaos`])
+    ChaosInjected { fault: String, context: String },
+}
+
+impl OracleEvent {
+    /// `UpdatePublished` or `UpdateFailed` for a trigger's `on_complete`
+    pub fn completed(
+        feed: &str,
+        success: bool,
+        receipt: Option<&SyncTransactionReceipt>,
+        value: Option<f64>,
+        latency: Option<Duration>,
+        drift_ms: i64,
+    ) -> Self {
+        let feed = feed.to_string();
+        if success {
+            OracleEvent::UpdatePublished {
+                feed,
+                value,
+                tx_hash: receipt.map(|r| r.transaction_hash),
+                block_number: receipt.map(|r| r.block_number),
+                latency,
+                drift_ms,
+            }
+        } else {
+            OracleEvent::UpdateFailed { feed, value, latency, drift_ms }
+        }
+    }
+
+    /// Short name, e.g. for logs and metric labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OracleEvent::TradeReceived { .. } => "trade_received",
+            OracleEvent::ValueComputed { .. } => "value_computed",
+            OracleEvent::UpdatePublished { .. } => "update_published",
+            OracleEvent::UpdateFailed { .. } => "update_failed",
+            OracleEvent::GapDetected { .. } => "gap_detected",
+            OracleEvent::QuorumLost { .. } => "quorum_lost",
+            OracleEvent::QuorumRestored { .. } => "quorum_restored",
+            OracleEvent::ReferenceDiverged { .. } => "reference_diverged",
+            OracleEvent::ReferenceRestored { .. } => "reference_restored",
+            OracleEvent::Paused { .. } => "paused",
+            OracleEvent::Resumed { .. } => "resumed",
+            OracleEvent::Halted { .. } => "halted",
+            OracleEvent::HaltCleared { .. } => "halt_cleared",
+            OracleEvent::KeyRemoved { .. } => "key_removed",
+            OracleEvent::WsReconnect { .. } => "ws_reconnect",
+            OracleEvent::TradeGap { .. } => "trade_gap",
+            OracleEvent::ChaosInjected { .. } => "chaos_injected",
+        }
+    }
+}
+
+pub trait EventSubscriber: Send + Sync {
+    fn on_event(&self, event: &OracleEvent);
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: RwLock<Vec<Arc<dyn EventSubscriber>>>,
+}
+
+static BUS: OnceLock<EventBus> = OnceLock::new();
+
+impl EventBus {
+    /// The process-wide bus
+    pub fn global() -> &'static EventBus {
+        BUS.get_or_init(EventBus::default)
+    }
+
+    pub fn subscribe(&self, subscriber: Arc<dyn EventSubscriber>) {
+        self.subscribers.write().push(subscriber);
+    }
+
+    pub fn publish(&self, event: OracleEvent) {
+        for subscriber in self.subscribers.read().iter() {
+            subscriber.on_event(&event);
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.read().len()
+    }
+}
+
+/// Publish `event` on the process-wide bus
+pub fn publish_event(event: OracleEvent) {
+    EventBus::global().publish(event);
+}
+
+/// Publish [`OracleEvent::Paused`] / [`OracleEvent::Resumed`] as the worker
+/// pool behind `error_control` is paused and resumed
+pub fn spawn_pause_watcher(scope: impl Into<String>, error_control: Arc<OrchestratorErrorControl>) -> JoinHandle<()> {
+    let scope = scope.into();
+    tokio::spawn(async move {
+        let mut paused = false;
+        let mut ticker = tokio::time::interval(PAUSE_POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let now_paused = error_control.is_worker_pool_paused().await;
+            if now_paused == paused {
+                continue;
+            }
+            paused = now_paused;
+            if paused {
+                warn!("⏸️ Worker pool for {} paused", scope);
+                publish_event(OracleEvent::Paused { scope: scope.clone() });
+            } else {
+                info!("▶️ Worker pool for {} resumed", scope);
+                publish_event(OracleEvent::Resumed { scope: scope.clone() });
+            }
+        }
+    })
+}
@@ -0,0 +1,122 @@
+//! Per-feed pauses, so one failing feed doesn't silence the rest.
+//!
+//! The SDK's error handler pauses the whole worker pool, which is right for
+//! failures every feed on the key shares (nonce churn, a dead RPC) but turns
+//! one feed's ABI mismatch or contract-side revert into an outage for all of
+//! them. Categories listed in `FEED_SCOPED_ERRORS` are charged to the feed
+//! whose update failed instead: the pause the pool would have taken applies
+//! to that feed only, and the others keep publishing. A feed that keeps
+//! failing still trips its own circuit breaker.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `FEED_SCOPED_ERRORS` | `revert` | Error categories that pause only the failing feed (empty: pause the pool for everything) |
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::error_policy::ErrorCategory;
+use crate::events::{publish_event, OracleEvent};
+
+/// Feeds currently paused, and which error categories pause a feed rather
+/// than the pool
+#[derive(Debug)]
+pub struct FeedPauses {
+    scoped: BTreeSet<ErrorCategory>,
+    /// Feed label -> (paused until, category that paused it)
+    paused: Mutex<BTreeMap<String, (Instant, ErrorCategory)>>,
+}
+
+impl Default for FeedPauses {
+    fn default() -> Self {
+        Self::new([ErrorCategory::Revert])
+    }
+}
+
+impl FeedPauses {
+    pub fn new(scoped: impl IntoIterator<Item = ErrorCategory>) -> Self {
+        Self { scoped: scoped.into_iter().collect(), paused: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Parse a comma-separated category list
+    pub fn parse(spec: &str) -> Result<Self> {
+        let scoped = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(scoped))
+    }
+
+    /// From `FEED_SCOPED_ERRORS` (default `revert`)
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("FEED_SCOPED_ERRORS") {
+            Ok(spec) => Self::parse(&spec),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Whether failures in `category` pause only the failing feed
+    pub fn is_scoped(&self, category: ErrorCategory) -> bool {
+        self.scoped.contains(&category)
+    }
+
+    /// Hold `feed` for `duration` after a `category` failure
+    pub fn pause(&self, feed: &str, duration: Duration, category: ErrorCategory) {
+        let until = Instant::now() + duration;
+        let mut paused = self.paused.lock();
+        match paused.get_mut(feed) {
+            // Several in-flight updates failing together extend one pause
+            Some(entry) => *entry = (entry.0.max(until), category),
+            None => {
+                warn!("⏸️ {} paused for {:?} after a {} error, other feeds carry on", feed, duration, category);
+                publish_event(OracleEvent::Paused { scope: feed.to_string() });
+                paused.insert(feed.to_string(), (until, category));
+            }
+        }
+    }
+
+    /// Whether `feed` is paused now; an expired pause is lifted here
+    pub fn is_paused(&self, feed: &str) -> bool {
+        let mut paused = self.paused.lock();
+        let Some((until, _)) = paused.get(feed) else {
+            return false;
+        };
+        if Instant::now() < *until {
+            return true;
+        }
+        paused.remove(feed);
+        info!("▶️ {} resumed", feed);
+        publish_event(OracleEvent::Resumed { scope: feed.to_string() });
+        false
+    }
+
+    /// Time left on `feed`'s pause
+    pub fn remaining(&self, feed: &str) -> Option<Duration> {
+        let paused = self.paused.lock();
+        let (until, _) = paused.get(feed)?;
+        until.checked_duration_since(Instant::now())
+    }
+
+    pub fn to_json(&self) -> Value {
+        let now = Instant::now();
+        let paused: BTreeMap<_, _> = self
+            .paused
+            .lock()
+            .iter()
+            .filter(|(_, (until, _))| *until > now)
+            .map(|(feed, (until, category))| {
+                (feed.clone(), json!({ "remaining_ms": (*until - now).as_millis() as u64, "category": category }))
+            })
+            .collect();
+        json!({
+            "scoped": self.scoped,
+            "paused": paused,
+        })
+    }
+}
@@ -0,0 +1,521 @@
+//! Generic interval trigger publishing one configured feed.
+
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
+
+use crate::backpressure::PendingQueue;
+use crate::change_limit::ChangeLimiter;
+use crate::events::{publish_event, OracleEvent};
+use crate::circuit_breaker::CircuitBreaker;
+use crate::condition::OnChainCondition;
+use crate::data_age::DataAge;
+use crate::feed_pause::FeedPauses;
+use crate::feeds::{scale_price, FeedConfig};
+use crate::force_update::ForceUpdates;
+use crate::halt::{HaltCondition, Halts};
+use crate::receipt_verifier::ReceiptVerifier;
+use crate::reference_check::ReferenceCheck;
+use crate::reorg::ReorgDetector;
+use crate::requests::RequestWatcher;
+use crate::rounds::RoundStore;
+use crate::smoothing::Smoother;
+use crate::stats::{OracleStats, SharedStats};
+use crate::status_server::StatusSource;
+use crate::telemetry::{trace_completed, trace_fired};
+use crate::warmup::WarmupGate;
+
+/// Latest aggregated value from a source
+#[derive(Debug, Clone)]
+pub struct PricePoint {
+    pub price: f64,
+    pub num_trades: u64,
+    pub timestamp_ms: u64,
+    /// Confidence interval in price units, for sources that report one
+    pub confidence: Option<f64>,
+}
+
+/// A source of aggregated values for a feed
+pub trait PriceSource: Send + Sync {
+    fn latest(&self) -> Option<PricePoint>;
+
+    /// Fraction of the aggregation window covered by data (0..=1), for
+    /// sources aggregating a trade window; `None` for the others
+    fn window_fill(&self) -> Option<f64> {
+        None
+    }
+
+    /// Whether values are held because the source lost its quorum
+    /// (blended sources with `[feeds.quorum]`)
+    fn quorum_lost(&self) -> bool {
+        false
+    }
+}
+
+/// An update in flight, committed to the limiter and condition once it lands
+struct SentUpdate {
+    price: f64,
+    value: U256,
+    round: Option<u64>,
+    at: Instant,
+    republish: bool,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+pub struct FeedTrigger {
+    config: FeedConfig,
+    source: Arc<dyn PriceSource>,
+    interval: Duration,
+    last_update: RwLock<Option<Instant>>,
+    last_price: RwLock<Option<f64>>,
+    /// On-chain read that must allow each update
+    condition: Option<Arc<OnChainCondition>>,
+    /// Independent price each update must agree with
+    reference: Option<Arc<ReferenceCheck>>,
+    /// Clamp on how far one update moves the published value
+    change_limiter: Option<ChangeLimiter>,
+    /// Filter computed values go through before publishing
+    smoother: Option<Smoother>,
+    /// Round ids, for feeds whose signature takes one
+    rounds: Option<Arc<RoundStore>>,
+    /// Updates in flight by queue ticket
+    sent: Mutex<HashMap<u64, SentUpdate>>,
+    /// Request events this feed answers; without them it publishes every interval
+    requests: Option<Arc<RequestWatcher>>,
+    /// Requests answered by the update in flight
+    serving: AtomicU64,
+    error_control: Arc<OrchestratorErrorControl>,
+    /// Pauses charged to this feed alone by the error policy
+    feed_pauses: Option<Arc<FeedPauses>>,
+    /// Halts only an operator lifts
+    halts: Option<Arc<Halts>>,
+    /// When a due update was last held back, counted once per interval
+    last_held: Mutex<Option<Instant>>,
+    /// Out-of-band publishes an operator asked for
+    force_updates: Option<Arc<ForceUpdates>>,
+    stats: SharedStats,
+    /// Age of the published data at trigger and inclusion time
+    data_age: DataAge,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    warmup: Arc<WarmupGate>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    /// Name used in published events (the feed id unless overridden)
+    label: String,
+    /// Set by the reorg detector when an update was dropped
+    republish: Arc<AtomicBool>,
+}
+
+impl FeedTrigger {
+    pub fn new(
+        config: FeedConfig,
+        source: Arc<dyn PriceSource>,
+        error_control: Arc<OrchestratorErrorControl>,
+    ) -> Self {
+        Self {
+            interval: Duration::from_millis(config.interval_ms),
+            breaker: CircuitBreaker::from_env(config.id.clone()),
+            warmup: WarmupGate::from_env(config.id.clone()),
+            label: config.id.clone(),
+            change_limiter: config
+                .change_limit
+                .as_ref()
+                .map(|limit| ChangeLimiter::new(config.id.clone(), limit, config.interval_ms)),
+            smoother: config.smoothing.as_ref().map(Smoother::new),
+            rounds: config.takes_round().then(|| Arc::new(RoundStore::in_memory())),
+            sent: Mutex::new(HashMap::new()),
+            data_age: DataAge::new(config.id.clone()),
+            pending: PendingQueue::for_interval(&config.id, Duration::from_millis(config.interval_ms)),
+            config,
+            source,
+            last_update: RwLock::new(None),
+            last_price: RwLock::new(None),
+            condition: None,
+            reference: None,
+            requests: None,
+            serving: AtomicU64::new(0),
+            error_control,
+            feed_pauses: None,
+            halts: None,
+            last_held: Mutex::new(None),
+            force_updates: None,
+            stats: OracleStats::shared(),
+            receipt_verifier: None,
+            reorg_detector: None,
+            republish: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+
+    /// Sit out pauses the error policy charges to this feed
+    pub fn with_feed_pauses(mut self, feed_pauses: Option<Arc<FeedPauses>>) -> Self {
+        self.feed_pauses = feed_pauses;
+        self
+    }
+
+    /// Publish nothing while `halts` holds this feed
+    pub fn with_halts(mut self, halts: Option<Arc<Halts>>) -> Self {
+        self.halts = halts;
+        self
+    }
+
+    /// Publish at once when an operator forces an update. Registers the
+    /// current label, so call it after [`with_label`](Self::with_label).
+    pub fn with_force_updates(mut self, force_updates: Option<Arc<ForceUpdates>>) -> Self {
+        if let Some(force_updates) = &force_updates {
+            force_updates.register(&self.label);
+        }
+        self.force_updates = force_updates;
+        self
+    }
+
+    /// Only publish while `condition` allows it
+    pub fn with_condition(mut self, condition: Option<Arc<OnChainCondition>>) -> Self {
+        self.condition = condition;
+        self
+    }
+
+    /// Skip updates diverging from `reference`
+    pub fn with_reference(mut self, reference: Option<Arc<ReferenceCheck>>) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Continue round ids from `rounds` (feeds publishing with a round only;
+    /// the others ignore it)
+    pub fn with_rounds(mut self, rounds: Option<Arc<RoundStore>>) -> Self {
+        if let Some(rounds) = rounds.filter(|_| self.config.takes_round()) {
+            self.rounds = Some(rounds);
+        }
+        self
+    }
+
+    /// Only publish in response to request events
+    pub fn with_requests(mut self, requests: Option<Arc<RequestWatcher>>) -> Self {
+        self.requests = requests;
+        self
+    }
+
+    /// Publish events under `label` instead of the feed id
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn feed_id(&self) -> &str {
+        &self.config.id
+    }
+
+    /// Name used in published events
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn stats(&self) -> SharedStats {
+        self.stats.clone()
+    }
+
+    /// Count a due update held back by `condition` towards its halt. Polls
+    /// keep seeing the same held update until the next interval, so it
+    /// counts once per interval.
+    fn held(&self, condition: HaltCondition, now: Instant) {
+        let Some(halts) = &self.halts else { return };
+        let mut last_held = self.last_held.lock();
+        if last_held.is_some_and(|last| now.duration_since(last) < self.interval) {
+            return;
+        }
+        *last_held = Some(now);
+        halts.held(condition, &self.label);
+    }
+}
+
+#[async_trait]
+impl TxTrigger for FeedTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let started = SystemTime::now();
+        let paused = self.error_control.is_worker_pool_paused().await;
+        if !self.warmup.running(paused) {
+            if paused {
+                debug!("Worker pool paused, skipping {}", self.config.id);
+            }
+            return Ok(None);
+        }
+        if self.feed_pauses.as_ref().is_some_and(|p| p.is_paused(&self.label)) {
+            debug!("{} paused after a failed update, skipping", self.label);
+            return Ok(None);
+        }
+        if self.halts.as_ref().is_some_and(|h| h.is_halted(&self.label)) {
+            debug!("{} halted until resumed, skipping", self.label);
+            return Ok(None);
+        }
+
+        // An operator forced an update, or a reorg dropped the last one and
+        // no republish is in flight yet: publish again without waiting
+        let forced = self.force_updates.as_ref().is_some_and(|f| f.is_requested(&self.label));
+        let republishing = self.sent.lock().values().any(|sent| sent.republish);
+        let reorged = self.republish.load(Ordering::Relaxed) && !republishing;
+        let republish = reorged || forced;
+        let now = Instant::now();
+        if let Some(last) = *self.last_update.read() {
+            if now.duration_since(last) < self.interval && !republish {
+                return Ok(None);
+            }
+        }
+
+        if let Some(requests) = &self.requests {
+            if requests.pending(&self.config.id) == 0 && !republish {
+                return Ok(None);
+            }
+        }
+
+        let Some(point) = self.source.latest() else {
+            if self.source.quorum_lost() {
+                self.held(HaltCondition::QuorumLost, now);
+            }
+            debug!("No data for {} yet", self.config.id);
+            return Ok(None);
+        };
+
+        if let Some(max_age) = self.config.max_age_secs {
+            let age_ms = now_ms().saturating_sub(point.timestamp_ms);
+            if age_ms > max_age * 1000 {
+                debug!("{} value is {}ms old, not publishing a stale value", self.config.id, age_ms);
+                return Ok(None);
+            }
+        }
+
+        if point.num_trades < self.config.min_trades {
+            debug!("Not enough trades for {}: {}", self.config.id, point.num_trades);
+            return Ok(None);
+        }
+
+        if !self.warmup.ready(point.num_trades, self.source.window_fill()) {
+            debug!("{} still warming up ({} trades)", self.config.id, point.num_trades);
+            return Ok(None);
+        }
+        publish_event(OracleEvent::ValueComputed { feed: self.label.clone(), value: point.price });
+
+        let smoothed = match &self.smoother {
+            Some(smoother) => smoother.smooth(point.price, point.timestamp_ms),
+            None => point.price,
+        };
+        let price = match &self.change_limiter {
+            Some(limiter) => limiter.limit(smoothed, now),
+            None => smoothed,
+        };
+        let Some(value) = self.config.scale_value(price) else {
+            warn!("Cannot scale {} price {} to {} decimals", self.config.id, price, self.config.decimals);
+            return Ok(None);
+        };
+
+        if let Some(reference) = &self.reference {
+            if !reference.allows(price) {
+                self.held(HaltCondition::ReferenceDiverged, now);
+                return Ok(None);
+            }
+        }
+
+        if let Some(condition) = &self.condition {
+            let since_last = self.last_update.read().map(|last| now.duration_since(last));
+            if !republish && !condition.allows(value, since_last) {
+                debug!("On-chain condition holds back {} update", self.config.id);
+                return Ok(None);
+            }
+        }
+
+        // Issued only once the update is admitted
+        let round = match &self.rounds {
+            Some(rounds) => match rounds.next_round(&self.label) {
+                Ok(round) => Some(round),
+                Err(e) => {
+                    error!("❌ Cannot reserve a round for {}, not publishing: {:#}", self.label, e);
+                    return Ok(None);
+                }
+            },
+            None => None,
+        };
+
+        if !self.breaker.try_acquire() {
+            debug!("Circuit breaker open, skipping {}", self.config.id);
+            return Ok(None);
+        }
+
+        let confidence = point
+            .confidence
+            .and_then(|c| scale_price(c, self.config.decimals))
+            .unwrap_or(U256::ZERO);
+        let call_data = self
+            .config
+            .encode_update(value, confidence, round.unwrap_or(0), point.timestamp_ms / 1000)
+            .map_err(|e| RiseError::Config(e.to_string()))?;
+
+        let tx_request = TxRequest::new(self.config.contract, call_data.clone())
+            .with_gas_limit(U256::from(self.config.gas_limit))
+            .with_priority(TxPriority::High)
+            .with_metadata("type", "feed_update")
+            .with_metadata("feed_id", self.config.id.clone())
+            .with_metadata("price", price.to_string())
+            .with_metadata("price_scaled", self.config.format_value(value))
+            .with_metadata("confidence_scaled", confidence.to_string())
+            .with_metadata("round", round.map(|r| r.to_string()).unwrap_or_default())
+            .with_metadata("data_ms", point.timestamp_ms.to_string());
+        // Only feeds that honour per-feed pauses may be paused on their own
+        let tx_request = match &self.feed_pauses {
+            Some(_) => tx_request.with_metadata("pause_scope", self.label.clone()),
+            None => tx_request,
+        };
+        let tx_request = if forced { tx_request.with_metadata("forced", "operator") } else { tx_request };
+        let tx_request = self.config.gas_fees().apply(tx_request);
+        let tx_request = self.config.access_list().apply(tx_request);
+
+        let Some((tx_request, ticket)) = self.pending.admit(tx_request) else {
+            // Nothing goes out: a half-open probe stays available
+            self.breaker.cancel();
+            return Ok(None);
+        };
+
+        if forced {
+            info!("🔧 {} forced update: {} ({} trades)", self.config.id, price, point.num_trades);
+        } else {
+            info!("🚀 {} update: {} ({} trades)", self.config.id, price, point.num_trades);
+        }
+        *self.last_update.write() = Some(now);
+        *self.last_price.write() = Some(price);
+        self.stats.write().record_trigger();
+        if let (Some(rounds), Some(round)) = (&self.rounds, round) {
+            rounds.issue(&self.label, round);
+        }
+        self.sent.lock().insert(
+            ticket,
+            SentUpdate { price, value, round, at: now, republish: reorged },
+        );
+        self.data_age.sent(point.timestamp_ms);
+        if let (true, Some(force_updates)) = (forced, &self.force_updates) {
+            force_updates.take(&self.label);
+        }
+        if let Some(requests) = &self.requests {
+            self.serving.fetch_add(requests.take(&self.config.id), Ordering::Relaxed);
+        }
+        trace_fired(&self.config.id, &call_data, started);
+        Ok(Some(tx_request))
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        let completion = self.pending.complete();
+        let sent = completion.and_then(|completion| self.sent.lock().remove(&completion.ticket));
+        self.breaker.record(success);
+        if let (Some(rounds), Some(round)) = (&self.rounds, sent.as_ref().and_then(|sent| sent.round)) {
+            // The next update reuses the round only if this one surely didn't
+            // publish it: cancelled before signing, or reverted. A failure
+            // without a receipt (e.g. a receipt timeout) may still land, so
+            // its round is skipped rather than sent twice.
+            let unpublished = completion.is_some_and(|completion| completion.dropped) || receipt.is_some();
+            if !success && unpublished {
+                rounds.release(&self.label, round);
+            } else if !success {
+                debug!("{} round {} may still land, not reusing it", self.label, round);
+            }
+        }
+        publish_event(OracleEvent::completed(&self.label, success, receipt, sent.as_ref().map(|sent| sent.price), latency, 0));
+        let data_age = self.data_age.completed(success);
+        let served = self.serving.swap(0, Ordering::Relaxed);
+        if let Some(requests) = &self.requests {
+            if success {
+                if served > 0 {
+                    info!("📬 {} answered {} request(s)", self.config.id, served);
+                }
+            } else {
+                requests.restore(&self.config.id, served);
+            }
+        }
+        if success {
+            self.stats.write().record_success(0, receipt.map(|r| r.gas_used), latency);
+            // The reorg detector re-arms it if this update is dropped too
+            self.republish.store(false, Ordering::Relaxed);
+            if let Some(sent) = &sent {
+                if let Some(limiter) = &self.change_limiter {
+                    limiter.record_sent(sent.price, sent.at);
+                }
+                if let Some(condition) = &self.condition {
+                    condition.record_published(sent.value);
+                }
+            }
+            if let Some(receipt) = receipt {
+                info!(
+                    "✅ {} confirmed - tx: {}, block: {}, data age: {}",
+                    self.config.id,
+                    receipt.transaction_hash,
+                    receipt.block_number,
+                    data_age.map(|age| format!("{}ms", age.at_inclusion_ms())).unwrap_or_else(|| "n/a".to_string())
+                );
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(&self.config.id, receipt, &self.stats);
+                }
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(&self.config.id, receipt, Some(&self.republish));
+                }
+            }
+        } else {
+            self.stats.write().record_failure();
+            error!("❌ {} update failed", self.config.id);
+        }
+        trace_completed(&self.config.id, receipt, success, started);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: format!("FeedTrigger[{}]", self.config.id),
+            description: format!("Publishes {} from {:?} {} every {}ms", self.config.id, self.config.source, self.config.symbol, self.config.interval_ms),
+            trigger_type: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for FeedTrigger {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "feed_id": self.config.id,
+            "symbol": self.config.symbol,
+            "contract": self.config.contract.to_string(),
+            "interval_ms": self.config.interval_ms,
+            "last_price": *self.last_price.read(),
+            "condition": self.condition.as_ref().map(|c| c.to_json()),
+            "reference": self.reference.as_ref().map(|r| r.to_json()),
+            "change_limit": self.change_limiter.as_ref().map(|l| l.to_json()),
+            "smoothing": self.smoother.as_ref().map(|s| s.to_json()),
+            "round": self.rounds.as_ref().and_then(|r| r.last_round(&self.label)),
+            "requests": self.requests.as_ref().map(|r| r.to_json()),
+            "stats": self.stats.read().to_json(),
+            "data_age": self.data_age.to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "halted": self.halts.as_ref().and_then(|h| h.halted(&self.label)),
+            "forced_update_pending": self.force_updates.as_ref().map(|f| f.is_requested(&self.label)),
+            "paused_ms": self.feed_pauses.as_ref().and_then(|p| p.remaining(&self.label)).map(|d| d.as_millis() as u64),
+            "warmup": self.warmup.to_json(),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
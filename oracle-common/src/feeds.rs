@@ -0,0 +1,1054 @@
+//! Feed registry loaded from a TOML feeds file.
+//!
+//! Each `[[feeds]]` entry describes where a value comes from (source type,
+//! symbol, aggregation) and how it is published (contract, function
+//! signature, interval, decimals), so adding a feed is a config change.
+//!
+//! ```toml
+//! [[feeds]]
+//! id = "BTCUSD"
+//! source = "binance"
+//! symbol = "BTCUSDT"
+//! aggregation = "twap"
+//! window_secs = 15
+//! contract = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175"
+//! function = "updatePrice(string,uint256)"
+//! interval_ms = 200
+//! decimals = 18
+//! ```
+//!
+//! With `function = "updatePriceWithRound(string,uint256,uint80,uint256)"`
+//! every update also carries a round id, counting up per feed across
+//! restarts (see `rounds`), and the unix time the value was observed, so
+//! consumers can detect gaps and staleness on chain.
+//!
+//! Contracts keying feeds by `bytes32` take keccak256 of the id instead:
+//! set `id_encoding = "bytes32"` with e.g.
+//! `function = "updatePrice(bytes32,uint256)"`.
+//!
+//! Values that can go negative (funding rates, basis, spreads) are published
+//! with `int256` in place of the value's `uint256`, e.g.
+//! `function = "updateValue(string,int256)"`, as two's complement.
+//!
+//! `binance` sources read USDⓈ-M futures trades unless `market = "spot"`
+//! picks spot instead, per feed or per blend leg - e.g. for consumers that
+//! want spot-referenced prices:
+//!
+//! ```toml
+//! [[feeds]]
+//! id = "BTCUSD-SPOT"
+//! source = "binance"
+//! market = "spot"
+//! symbol = "BTCUSDT"
+//! ```
+//!
+//! A feed can blend further sources into its value, e.g. a Uniswap V3 pool
+//! TWAP next to the Binance TWAP (weighted mean, see `blend`):
+//!
+//! ```toml
+//! [[feeds.blend]]
+//! source = "uniswap-v3"
+//! symbol = "ethereum:0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"
+//! window_secs = 300
+//! invert = true
+//! weight = 0.5
+//! ```
+//!
+//! An optional `[feeds.condition]` reads the contract first and holds
+//! updates while it says none is needed (see `condition`):
+//!
+//! ```toml
+//! [feeds.condition]
+//! kind = "deviation"
+//! function = "getLatestPrice(string)"
+//! deviation_bps = 10
+//! heartbeat_secs = 60
+//! ```
+//!
+//! A `derived` feed is computed from feeds declared before it (see
+//! `derived`):
+//!
+//! ```toml
+//! [[feeds]]
+//! id = "ETHBTC"
+//! source = "derived"
+//! symbol = "ETHUSD / BTCUSD"
+//! contract = "0x..."
+//! ```
+//!
+//! With `[feeds.requests]` a feed is published only when a request event for
+//! it is logged (at most once per `interval_ms`, see `requests`):
+//!
+//! ```toml
+//! [feeds.requests]
+//! event = "RequestPrice(string)"
+//! ```
+//!
+//! With several sources, `[feeds.quorum]` holds updates unless at least
+//! `min_sources` of them have fresh data agreeing with their median (see
+//! `blend`), so a single venue's flash crash can't move the feed:
+//!
+//! ```toml
+//! [feeds.quorum]
+//! min_sources = 2
+//! tolerance_bps = 50
+//! max_age_ms = 5000
+//! ```
+//!
+//! `[feeds.reference]` cross-checks each update against an independent
+//! price (a Chainlink aggregator, another PriceOracleV2 or a REST endpoint)
+//! and holds it while the two diverge by more than `max_divergence_bps` (see
+//! `reference_check`):
+//!
+//! ```toml
+//! [feeds.reference]
+//! kind = "rest"
+//! url = "https://api.coinbase.com/v2/prices/BTC-USD/spot"
+//! json_path = "$.data.amount"
+//! max_divergence_bps = 100
+//! ```
+//!
+//! `gas_limit`, `max_fee_per_gas_wei` and `priority_fee_wei` override the
+//! chain's gas settings for one feed, e.g. a batch update that needs far
+//! more gas than the single-value feeds next to it (see `gas_fees`):
+//!
+//! ```toml
+//! gas_limit = 1500000
+//! max_fee_per_gas_wei = 2000000000
+//! priority_fee_wei = 500000000
+//! ```
+//!
+//! `[[feeds.access_list]]` declares storage slots (of the feed's contract
+//! unless `address` is set) that every update touches, warming them through
+//! an EIP-2930 access list (see `access_list`):
+//!
+//! ```toml
+//! [[feeds.access_list]]
+//! storage_keys = ["0x0000000000000000000000000000000000000000000000000000000000000003"]
+//! ```
+//!
+//! `[feeds.change_limit]` caps how far one update may move the published
+//! value, letting the full move through once it has persisted for
+//! `override_after_secs` (see `change_limit`):
+//!
+//! ```toml
+//! [feeds.change_limit]
+//! max_change_bps = 200
+//! per_ms = 1000
+//! override_after_secs = 30
+//! ```
+//!
+//! `[feeds.smoothing]` publishes an EWMA or Kalman-filtered value instead of
+//! the raw one (see `smoothing`):
+//!
+//! ```toml
+//! [feeds.smoothing]
+//! kind = "ewma"
+//! half_life_ms = 10000
+//! ```
+//!
+//! To publish the same feeds to several chains from one process, add
+//! `[[targets]]` entries instead of a single `[chain]`; each target gets its
+//! own orchestrator, worker keys (`key_prefix`) and error control.
+//!
+//! ```toml
+//! [[targets]]
+//! name = "rise-testnet"
+//! rpc_urls = ["https://testnet.riselabs.xyz"]
+//! key_prefix = "TESTNET_PRIVATE_KEY_"
+//!
+//! [[targets]]
+//! name = "rise-mainnet"
+//! rpc_urls = ["https://mainnet.example"]
+//! key_prefix = "MAINNET_PRIVATE_KEY_"
+//! contracts = { BTCUSD = "0x..." }
+//! ```
+
+use alloy::primitives::{Address, Bytes, I256, U256};
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::access_list::{AccessListConfig, AccessListEntry};
+use crate::chain::ChainConfig;
+use crate::encoding::{encode_feed_call, encode_string_call, function_selector, FeedId};
+use crate::derived::DerivedExpr;
+use crate::gas_fees::GasFees;
+use crate::rest::JsonPath;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedsFile {
+    /// Optional `[chain]` section; the environment is used when absent
+    #[serde(default)]
+    pub chain: Option<ChainConfig>,
+    /// Chains to publish every feed to; overrides `chain` when present
+    #[serde(default)]
+    pub targets: Vec<PublishTarget>,
+    pub feeds: Vec<FeedConfig>,
+}
+
+/// One chain every feed is published to, with its own worker keys
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishTarget {
+    #[serde(flatten)]
+    pub chain: ChainConfig,
+    /// Env prefix of this target's worker keys, e.g. `MAINNET_PRIVATE_KEY_`
+    #[serde(default = "default_key_prefix")]
+    pub key_prefix: String,
+    /// Per-feed contract overrides (feed id -> address) for this chain
+    #[serde(default)]
+    pub contracts: HashMap<String, Address>,
+}
+
+fn default_key_prefix() -> String { "PRIVATE_KEY_".to_string() }
+
+/// Upstream data source for a feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceKind {
+    Binance,
+    /// exchangerate.host REST polling (fiat FX)
+    ExchangerateHost,
+    /// TraderMade websocket streaming (fiat FX)
+    Tradermade,
+    /// Pyth Hermes price stream; `symbol` is the price feed id
+    Pyth,
+    /// Uniswap V3 pool TWAP over `window_secs`; `symbol` is `chain:pool`
+    UniswapV3,
+    /// Any JSON endpoint polled every `poll_ms`; `symbol` is the URL and
+    /// `json_path` selects the value
+    Rest,
+    /// Computed from feeds declared earlier; `symbol` is an expression such
+    /// as `ETHUSD / BTCUSD`
+    Derived,
+}
+
+/// Binance market a `binance` source takes its trades from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BinanceMarket {
+    /// USDⓈ-M perpetual futures (fstream.binance.com)
+    #[default]
+    Futures,
+    /// Spot (stream.binance.com)
+    Spot,
+}
+
+impl BinanceMarket {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinanceMarket::Futures => "futures",
+            BinanceMarket::Spot => "spot",
+        }
+    }
+}
+
+/// How a feed's id is encoded in calldata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdEncoding {
+    /// The id as a `string`
+    #[default]
+    String,
+    /// keccak256 of the id as a `bytes32`
+    Bytes32,
+}
+
+impl IdEncoding {
+    fn abi_type(&self) -> &'static str {
+        match self {
+            IdEncoding::String => "string",
+            IdEncoding::Bytes32 => "bytes32",
+        }
+    }
+}
+
+/// How trades in the window are reduced to a single value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Aggregation {
+    #[default]
+    Twap,
+    Last,
+    /// Annualized realized volatility of the window's 1-second returns
+    /// (Binance only), published as a fraction (0.55 = 55%)
+    RealizedVol,
+    /// Mean close of the window's `candle_secs` bars, each weighted equally
+    /// (Binance only)
+    BarTwap,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    /// Feed identifier passed to the contract (e.g. "BTCUSD")
+    pub id: String,
+    pub source: SourceKind,
+    /// Symbol at the source (e.g. "BTCUSDT")
+    pub symbol: String,
+    /// Market of a `binance` source: `futures` (default) or `spot`
+    #[serde(default)]
+    pub market: BinanceMarket,
+    #[serde(default)]
+    pub aggregation: Aggregation,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    /// Candle interval of `bar-twap` feeds (default 1), and of `realized-vol`
+    /// feeds sampling candles instead of raw trades; must be one of
+    /// `CANDLE_INTERVALS`
+    #[serde(default)]
+    pub candle_secs: Option<u64>,
+    /// `binance` sources: trades with a smaller notional (price * quantity,
+    /// in quote units) are left out of the window. Not for feeds sampling
+    /// candles, which are built from every trade.
+    #[serde(default)]
+    pub min_trade_notional: Option<f64>,
+    pub contract: Address,
+    /// Solidity signature with `(string,uint256)`, `(string,uint256,uint256)`
+    /// (value and confidence) or `(uint256)` params; `int256` in place of
+    /// the value's `uint256` publishes values that can go negative, and
+    /// `bytes32` in place of `string` goes with `id_encoding = "bytes32"`;
+    /// `(string,uint256,uint80,uint256)` adds a round id and observation time
+    #[serde(default = "default_function")]
+    pub function: String,
+    /// How the feed id is passed to the contract
+    #[serde(default)]
+    pub id_encoding: IdEncoding,
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    #[serde(default = "default_gas_limit")]
+    pub gas_limit: u64,
+    /// Max fee per gas for this feed's updates (the chain's `GAS_PRICE_WEI` when unset)
+    #[serde(default)]
+    pub max_fee_per_gas_wei: Option<u64>,
+    /// Max priority fee for this feed's updates (the chain's `PRIORITY_FEE_WEI` when unset)
+    #[serde(default)]
+    pub priority_fee_wei: Option<u64>,
+    /// Storage slots declared warm in every update's EIP-2930 access list
+    #[serde(default)]
+    pub access_list: Vec<AccessListEntry>,
+    #[serde(default = "default_min_trades")]
+    pub min_trades: u64,
+    /// Skip publishing when the source's latest value is older than this
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+    /// Publish the reciprocal of the source's price (e.g. a USDC/WETH pool for ETHUSD)
+    #[serde(default)]
+    pub invert: bool,
+    /// Weight of this feed's own source when blended with `blend` legs
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Further sources averaged (by weight) into the published value
+    #[serde(default)]
+    pub blend: Vec<BlendLeg>,
+    /// Hold updates while a blended source is further than this from the mean
+    #[serde(default)]
+    pub max_blend_deviation_bps: Option<u64>,
+    /// Publish only while enough of the blended sources are fresh and agree
+    #[serde(default)]
+    pub quorum: Option<FeedQuorum>,
+    /// Where the value sits in a `rest` source's response (e.g. `$.data.price`)
+    #[serde(default)]
+    pub json_path: Option<String>,
+    /// Fixed-point decimals of the raw value at a `rest` source
+    #[serde(default)]
+    pub source_decimals: u8,
+    /// How often a `rest` source is polled (default 5000)
+    #[serde(default)]
+    pub poll_ms: Option<u64>,
+    /// Only publish while an on-chain read says an update is warranted
+    #[serde(default)]
+    pub condition: Option<FeedCondition>,
+    /// Independent price each update is cross-checked against
+    #[serde(default)]
+    pub reference: Option<FeedReference>,
+    /// Cap on how far the published value may move per update
+    #[serde(default)]
+    pub change_limit: Option<FeedChangeLimit>,
+    /// Filter applied to computed values before publishing
+    #[serde(default)]
+    pub smoothing: Option<FeedSmoothing>,
+    /// Publish only in response to request events on chain
+    #[serde(default)]
+    pub requests: Option<FeedRequests>,
+}
+
+/// K-of-N agreement required of a blended feed's sources before publishing
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedQuorum {
+    /// Sources (the feed's own plus its blend legs) that must be fresh and agree
+    pub min_sources: usize,
+    /// Largest distance from the median of the fresh values that still agrees
+    pub tolerance_bps: u64,
+    /// Values older than this don't count towards the quorum
+    #[serde(default = "default_quorum_max_age_ms")]
+    pub max_age_ms: u64,
+}
+
+fn default_quorum_max_age_ms() -> u64 {
+    5000
+}
+
+impl FeedQuorum {
+    fn validate(&self, sources: usize) -> Result<()> {
+        if sources < 2 {
+            anyhow::bail!("a quorum needs [[feeds.blend]] legs to count");
+        }
+        if self.min_sources == 0 || self.min_sources > sources {
+            anyhow::bail!("quorum min_sources must be between 1 and the {} configured sources", sources);
+        }
+        if self.tolerance_bps == 0 || self.max_age_ms == 0 {
+            anyhow::bail!("quorum tolerance_bps and max_age_ms must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// Rate-of-change clamp on a feed's published value
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedChangeLimit {
+    /// Largest move from the last published value per `per_ms`
+    pub max_change_bps: u64,
+    /// Period `max_change_bps` applies to; the feed's `interval_ms` when unset
+    #[serde(default)]
+    pub per_ms: Option<u64>,
+    /// Publish the unclamped value once it has been beyond the limit this
+    /// long; clamped indefinitely when unset
+    #[serde(default)]
+    pub override_after_secs: Option<u64>,
+}
+
+impl FeedChangeLimit {
+    fn validate(&self) -> Result<()> {
+        if self.max_change_bps == 0 || self.per_ms == Some(0) || self.override_after_secs == Some(0) {
+            anyhow::bail!("change_limit max_change_bps, per_ms and override_after_secs must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// Filter a `[feeds.smoothing]` applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmoothingKind {
+    Ewma,
+    Kalman,
+}
+
+impl SmoothingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmoothingKind::Ewma => "ewma",
+            SmoothingKind::Kalman => "kalman",
+        }
+    }
+}
+
+/// Smoothing of a feed's computed values before publishing
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedSmoothing {
+    pub kind: SmoothingKind,
+    /// `ewma`: data time over which a value's weight halves
+    #[serde(default)]
+    pub half_life_ms: Option<u64>,
+    /// `kalman`: expected move of the true value per second (std dev)
+    #[serde(default)]
+    pub process_noise_bps: Option<f64>,
+    /// `kalman`: noise of each computed value (std dev)
+    #[serde(default)]
+    pub measurement_noise_bps: Option<f64>,
+}
+
+impl FeedSmoothing {
+    pub(crate) fn validate(&self) -> Result<()> {
+        match self.kind {
+            SmoothingKind::Ewma => {
+                if !self.half_life_ms.is_some_and(|ms| ms > 0) {
+                    anyhow::bail!("ewma smoothing needs a positive half_life_ms");
+                }
+            }
+            SmoothingKind::Kalman => {
+                let positive = |bps: Option<f64>| bps.is_some_and(|bps| bps > 0.0 && bps.is_finite());
+                if !positive(self.process_noise_bps) || !positive(self.measurement_noise_bps) {
+                    anyhow::bail!("kalman smoothing needs positive process_noise_bps and measurement_noise_bps");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a `[feeds.reference]` price comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReferenceKind {
+    /// `latestRoundData()` of a Chainlink aggregator at `address`
+    Chainlink,
+    /// `getLatestPrice(<feed id>)` of another PriceOracleV2 at `address`
+    Oracle,
+    /// A number in a JSON response (`url` and `json_path`)
+    Rest,
+}
+
+/// Independent price a feed's updates must agree with
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedReference {
+    pub kind: ReferenceKind,
+    /// `chainlink` / `oracle`: the contract to read
+    #[serde(default)]
+    pub address: Option<Address>,
+    /// `chainlink` / `oracle`: RPC of the reference's chain; the first
+    /// target's when unset
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+    /// `rest`: endpoint polled for the value
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub json_path: Option<String>,
+    /// `rest`: fixed-point decimals of the raw value
+    #[serde(default)]
+    pub source_decimals: u8,
+    /// Updates further than this from the reference are held
+    pub max_divergence_bps: u64,
+    /// How often the reference is read (default 5000)
+    #[serde(default = "default_reference_poll_ms")]
+    pub poll_ms: u64,
+}
+
+fn default_reference_poll_ms() -> u64 {
+    5000
+}
+
+impl FeedReference {
+    fn validate(&self) -> Result<()> {
+        match self.kind {
+            ReferenceKind::Chainlink | ReferenceKind::Oracle => {
+                if self.address.is_none() {
+                    anyhow::bail!("{:?} references need an address", self.kind);
+                }
+            }
+            ReferenceKind::Rest => {
+                let url = self.url.as_deref().unwrap_or_default();
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    anyhow::bail!("rest references need an http(s) url");
+                }
+                let path = self.json_path.as_deref().ok_or_else(|| anyhow!("rest references need a json_path"))?;
+                JsonPath::parse(path)?;
+            }
+        }
+        if self.max_divergence_bps == 0 || self.poll_ms == 0 {
+            anyhow::bail!("reference max_divergence_bps and poll_ms must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// Request events a feed answers (request/response instead of push)
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedRequests {
+    /// Contract emitting the requests; the feed's contract when unset
+    #[serde(default)]
+    pub contract: Option<Address>,
+    /// Event signature whose first parameter (indexed or not) is the feed id
+    #[serde(default = "default_request_event")]
+    pub event: String,
+    /// How often logs are fetched; the feed's `interval_ms` when unset
+    #[serde(default)]
+    pub poll_ms: Option<u64>,
+}
+
+fn default_request_event() -> String {
+    "RequestPrice(string)".to_string()
+}
+
+impl FeedRequests {
+    fn validate(&self) -> Result<()> {
+        match self.event.split_once('(') {
+            Some((name, params)) if !name.is_empty() && (params == "string)" || params.starts_with("string,")) => Ok(()),
+            _ => Err(anyhow!("Request event '{}' must take the feed id (string) as its first parameter", self.event)),
+        }
+    }
+}
+
+/// How a `[feeds.condition]` read is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConditionKind {
+    /// The view returns a bool (e.g. `needsUpdate(string)`)
+    #[default]
+    NeedsUpdate,
+    /// The view returns the published value (scaled like the feed); publish
+    /// when ours deviates by `deviation_bps` or after `heartbeat_secs`
+    Deviation,
+}
+
+/// On-chain read gating a feed's updates
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedCondition {
+    #[serde(default)]
+    pub kind: ConditionKind,
+    /// Contract to read; the feed's contract when unset
+    #[serde(default)]
+    pub contract: Option<Address>,
+    /// View signature, taking no arguments or the feed id as `(string)` or
+    /// `(bytes32)` (keccak256 of the id)
+    pub function: String,
+    #[serde(default)]
+    pub deviation_bps: Option<u64>,
+    /// Publish at least this often, whatever the read says
+    #[serde(default)]
+    pub heartbeat_secs: Option<u64>,
+    /// How often the view is read; the feed's `interval_ms` when unset
+    #[serde(default)]
+    pub poll_ms: Option<u64>,
+}
+
+impl FeedCondition {
+    /// Calldata of the view call for `feed_id`
+    pub fn call_data(&self, feed_id: &str) -> Result<Bytes> {
+        let selector = function_selector(&self.function);
+        match self.function.split_once('(').map(|(_, rest)| rest) {
+            Some(")") => Ok(Bytes::from(selector.to_vec())),
+            Some("string)") => Ok(encode_string_call(selector, feed_id)),
+            Some("bytes32)") => Ok(encode_feed_call(selector, FeedId::keccak(feed_id), &[])),
+            _ => Err(anyhow!(
+                "Condition '{}' must take no arguments or the feed id as (string) or (bytes32)",
+                self.function
+            )),
+        }
+    }
+
+    fn validate(&self, feed_id: &str) -> Result<()> {
+        self.call_data(feed_id)?;
+        if self.kind == ConditionKind::Deviation && self.deviation_bps.is_none() && self.heartbeat_secs.is_none() {
+            anyhow::bail!("Deviation condition needs deviation_bps and/or heartbeat_secs");
+        }
+        if self.kind == ConditionKind::NeedsUpdate && self.deviation_bps.is_some() {
+            anyhow::bail!("deviation_bps only applies to kind = \"deviation\"");
+        }
+        Ok(())
+    }
+}
+
+/// An additional source blended into a feed, e.g. a DEX pool next to a CEX
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlendLeg {
+    pub source: SourceKind,
+    pub symbol: String,
+    /// `binance` legs: the feed's `market` when unset
+    #[serde(default)]
+    pub market: Option<BinanceMarket>,
+    #[serde(default)]
+    pub aggregation: Option<Aggregation>,
+    /// TWAP window; the feed's `window_secs` when unset
+    #[serde(default)]
+    pub window_secs: Option<u64>,
+    #[serde(default)]
+    pub invert: bool,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// `rest` legs: the value's JSON path and raw decimals
+    #[serde(default)]
+    pub json_path: Option<String>,
+    #[serde(default)]
+    pub source_decimals: Option<u8>,
+}
+
+fn default_window_secs() -> u64 { 15 }
+fn default_function() -> String { "updatePrice(string,uint256)".to_string() }
+fn default_interval_ms() -> u64 { 200 }
+fn default_decimals() -> u8 { 18 }
+fn default_gas_limit() -> u64 { 300_000 }
+fn default_min_trades() -> u64 { 1 }
+fn default_weight() -> f64 { 1.0 }
+
+/// Calldata layouts the runner knows how to build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallEncoding {
+    /// `fn(string feedId, uint256 value)` (or `bytes32 feedId`)
+    FeedIdAndValue,
+    /// `fn(string feedId, uint256 value, uint256 confidence)` (or `bytes32 feedId`)
+    FeedIdValueAndConfidence,
+    /// `fn(string feedId, uint256 value, uint80 roundId, uint256 observedAt)`
+    /// (or `bytes32 feedId`), `observedAt` in unix seconds
+    FeedIdValueAndRound,
+    /// `fn(uint256 value)`
+    ValueOnly,
+}
+
+impl FeedsFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read feeds file {}", path.display()))?;
+        Self::parse(&raw).with_context(|| format!("Invalid feeds file {}", path.display()))
+    }
+
+    /// Parse and validate the contents of a feeds file
+    pub fn parse(raw: &str) -> Result<Self> {
+        let file: FeedsFile = toml::from_str(raw).context("Failed to parse feeds file")?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Chains to publish to: the `[[targets]]` list, or a single target from
+    /// `[chain]` / the environment with the default `PRIVATE_KEY_` keys.
+    pub fn publish_targets(&self) -> Result<Vec<PublishTarget>> {
+        if !self.targets.is_empty() {
+            return Ok(self.targets.clone());
+        }
+
+        let chain = match &self.chain {
+            Some(chain) => {
+                let mut chain = chain.clone();
+                chain.apply_env_overrides()?;
+                chain.validate()?;
+                chain
+            }
+            None => ChainConfig::from_env()?,
+        };
+        Ok(vec![PublishTarget {
+            chain,
+            key_prefix: default_key_prefix(),
+            contracts: HashMap::new(),
+        }])
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.feeds.is_empty() {
+            anyhow::bail!("Feeds file defines no feeds");
+        }
+        if self.chain.is_some() && !self.targets.is_empty() {
+            anyhow::bail!("Use either [chain] or [[targets]], not both");
+        }
+
+        let mut names = HashSet::new();
+        for target in &self.targets {
+            if !names.insert(target.chain.name.as_str()) {
+                anyhow::bail!("Duplicate target name '{}'", target.chain.name);
+            }
+            target.chain.validate()?;
+            for feed_id in target.contracts.keys() {
+                if !self.feeds.iter().any(|f| &f.id == feed_id) {
+                    anyhow::bail!("Target '{}' overrides unknown feed '{}'", target.chain.name, feed_id);
+                }
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for feed in &self.feeds {
+            if !ids.insert(feed.id.as_str()) {
+                anyhow::bail!("Duplicate feed id '{}'", feed.id);
+            }
+            if feed.interval_ms == 0 {
+                anyhow::bail!("Feed '{}' has interval_ms = 0", feed.id);
+            }
+            if feed.access_list.iter().any(|entry| entry.address.is_none() && entry.storage_keys.is_empty()) {
+                anyhow::bail!("Feed '{}': access_list entries need an address or storage_keys", feed.id);
+            }
+            if feed.gas_limit == 0 {
+                anyhow::bail!("Feed '{}' has gas_limit = 0", feed.id);
+            }
+            if let (Some(max_fee), Some(priority_fee)) = (feed.max_fee_per_gas_wei, feed.priority_fee_wei) {
+                if priority_fee > max_fee {
+                    anyhow::bail!(
+                        "Feed '{}': priority_fee_wei ({}) exceeds max_fee_per_gas_wei ({})",
+                        feed.id, priority_fee, max_fee
+                    );
+                }
+            }
+            feed.encoding()?;
+            if feed.signed() {
+                // Blending, inversion and deviation reads assume positive prices
+                if feed.invert || !feed.blend.is_empty() {
+                    anyhow::bail!("Feed '{}': signed (int256) feeds cannot be inverted or blended", feed.id);
+                }
+                if feed.condition.as_ref().is_some_and(|c| c.kind == ConditionKind::Deviation) {
+                    anyhow::bail!("Feed '{}': deviation conditions need an unsigned (uint256) value", feed.id);
+                }
+            }
+            if feed.aggregation == Aggregation::RealizedVol {
+                if feed.source != SourceKind::Binance {
+                    anyhow::bail!("Feed '{}': realized-vol aggregation needs a binance source", feed.id);
+                }
+                if feed.invert || !feed.blend.is_empty() {
+                    anyhow::bail!("Feed '{}': realized-vol feeds cannot be inverted or blended", feed.id);
+                }
+            }
+            if feed.min_trade_notional.is_some_and(|min| !(min > 0.0 && min.is_finite())) {
+                anyhow::bail!("Feed '{}': min_trade_notional must be positive", feed.id);
+            }
+            if feed.min_trade_notional.is_some() && feed.source != SourceKind::Binance {
+                anyhow::bail!("Feed '{}': min_trade_notional is for binance sources only", feed.id);
+            }
+            if let Some(candle_secs) = feed.candle_secs {
+                if candle_secs == 0 || candle_secs > feed.window_secs {
+                    anyhow::bail!("Feed '{}': candle_secs must be between 1 and window_secs", feed.id);
+                }
+            }
+            for leg in std::iter::once(feed.clone()).chain(feed.blend_legs()) {
+                if leg.candle_ms().is_some() && leg.source != SourceKind::Binance {
+                    anyhow::bail!("Feed '{}': bar-twap and candle_secs need a binance source", feed.id);
+                }
+                // Candles are shared by every feed on the symbol and built from all its trades
+                if leg.candle_ms().is_some() && leg.min_trade_notional.is_some() {
+                    anyhow::bail!("Feed '{}': min_trade_notional doesn't apply to bar-twap or candle_secs", feed.id);
+                }
+            }
+            if feed.source == SourceKind::Derived {
+                let expr = DerivedExpr::parse(&feed.symbol).with_context(|| format!("Feed '{}'", feed.id))?;
+                for input in expr.feed_ids() {
+                    if !self.feeds.iter().take_while(|f| f.id != feed.id).any(|f| f.id == input) {
+                        anyhow::bail!("Feed '{}' derives from '{}', which must be declared before it", feed.id, input);
+                    }
+                }
+            }
+            if feed.blend.iter().any(|leg| leg.source == SourceKind::Derived) {
+                anyhow::bail!("Feed '{}': blend legs cannot be derived", feed.id);
+            }
+            if feed.blend.iter().any(|leg| leg.aggregation == Some(Aggregation::RealizedVol)) {
+                anyhow::bail!("Feed '{}': blend legs cannot use realized-vol aggregation", feed.id);
+            }
+            if let Some(condition) = &feed.condition {
+                condition.validate(&feed.id).with_context(|| format!("Feed '{}'", feed.id))?;
+            }
+            if let Some(requests) = &feed.requests {
+                requests.validate().with_context(|| format!("Feed '{}'", feed.id))?;
+            }
+            if let Some(quorum) = &feed.quorum {
+                quorum.validate(1 + feed.blend.len()).with_context(|| format!("Feed '{}'", feed.id))?;
+            }
+            if let Some(reference) = &feed.reference {
+                reference.validate().with_context(|| format!("Feed '{}'", feed.id))?;
+            }
+            if let Some(limit) = &feed.change_limit {
+                limit.validate().with_context(|| format!("Feed '{}'", feed.id))?;
+                // The limit is relative to the last value, which a signed feed can cross zero with
+                if feed.signed() {
+                    anyhow::bail!("Feed '{}': change_limit needs an unsigned (uint256) value", feed.id);
+                }
+            }
+            if let Some(smoothing) = &feed.smoothing {
+                smoothing.validate().with_context(|| format!("Feed '{}'", feed.id))?;
+                // Noise is relative to the value, which a signed feed can cross zero with
+                if smoothing.kind == SmoothingKind::Kalman && feed.signed() {
+                    anyhow::bail!("Feed '{}': kalman smoothing needs an unsigned (uint256) value", feed.id);
+                }
+            }
+            for source in std::iter::once(feed.clone()).chain(feed.blend_legs()) {
+                if source.market != BinanceMarket::Futures && source.source != SourceKind::Binance {
+                    anyhow::bail!("Feed '{}': market = \"{}\" is for binance sources only", feed.id, source.market.as_str());
+                }
+                if source.source == SourceKind::Rest {
+                    if !source.symbol.starts_with("https://") && !source.symbol.starts_with("http://") {
+                        anyhow::bail!("Feed '{}': rest source symbol must be an http(s) URL", feed.id);
+                    }
+                    let path = source.json_path.as_deref().ok_or_else(|| anyhow!("Feed '{}': rest sources need a json_path", feed.id))?;
+                    JsonPath::parse(path).with_context(|| format!("Feed '{}'", feed.id))?;
+                }
+            }
+            for weight in std::iter::once(feed.weight).chain(feed.blend.iter().map(|leg| leg.weight)) {
+                if !weight.is_finite() || weight <= 0.0 {
+                    anyhow::bail!("Feed '{}' has a non-positive blend weight {}", feed.id, weight);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PublishTarget {
+    /// `feed` as published on this target, with any contract override applied
+    pub fn feed_for(&self, feed: &FeedConfig) -> FeedConfig {
+        let mut feed = feed.clone();
+        if let Some(contract) = self.contracts.get(&feed.id) {
+            feed.contract = *contract;
+        }
+        feed
+    }
+}
+
+impl FeedConfig {
+    pub fn encoding(&self) -> Result<CallEncoding> {
+        let params = self
+            .function
+            .split_once('(')
+            .and_then(|(_, rest)| rest.strip_suffix(')'))
+            .ok_or_else(|| anyhow!("Feed '{}': invalid function signature '{}'", self.id, self.function))?;
+
+        let (id_param, values) = match params.split_once(',') {
+            Some((first, rest)) if first == "string" || first == "bytes32" => (Some(first), rest),
+            _ => (None, params),
+        };
+        if let Some(id_param) = id_param {
+            if id_param != self.id_encoding.abi_type() {
+                return Err(anyhow!(
+                    "Feed '{}': '{}' takes the feed id as {} but id_encoding is \"{}\"",
+                    self.id,
+                    self.function,
+                    id_param,
+                    self.id_encoding.abi_type()
+                ));
+            }
+        }
+
+        // The value's type is checked by `signed`; what follows it picks the layout
+        let after_value = values.strip_prefix("uint256").or_else(|| values.strip_prefix("int256"));
+        match (id_param, after_value) {
+            (Some(_), Some("")) => Ok(CallEncoding::FeedIdAndValue),
+            (Some(_), Some(",uint256")) => Ok(CallEncoding::FeedIdValueAndConfidence),
+            (Some(_), Some(",uint80,uint256")) => Ok(CallEncoding::FeedIdValueAndRound),
+            (None, Some("")) => Ok(CallEncoding::ValueOnly),
+            _ => Err(anyhow!(
+                "Feed '{}': unsupported parameter list '({})' - expected (string,uint256), (string,uint256,uint256), (string,uint256,uint80,uint256) or (uint256), with int256 for signed values and bytes32 for hashed feed ids",
+                self.id,
+                params
+            )),
+        }
+    }
+
+    /// This feed's gas fee overrides
+    pub fn gas_fees(&self) -> GasFees {
+        GasFees::new(self.max_fee_per_gas_wei, self.priority_fee_wei)
+    }
+
+    /// This feed's access list, for its (possibly overridden) contract
+    pub fn access_list(&self) -> AccessListConfig {
+        AccessListConfig::new(&self.access_list, self.contract)
+    }
+
+    /// Whether updates carry a round id and observation time
+    pub fn takes_round(&self) -> bool {
+        matches!(self.encoding(), Ok(CallEncoding::FeedIdValueAndRound))
+    }
+
+    /// The feed id as passed to the contract
+    pub fn feed_key(&self) -> FeedId<'_> {
+        match self.id_encoding {
+            IdEncoding::String => FeedId::String(&self.id),
+            IdEncoding::Bytes32 => FeedId::keccak(&self.id),
+        }
+    }
+
+    /// Each `blend` leg as a feed of its own, inheriting this feed's settings
+    pub fn blend_legs(&self) -> Vec<FeedConfig> {
+        self.blend
+            .iter()
+            .map(|leg| FeedConfig {
+                source: leg.source,
+                symbol: leg.symbol.clone(),
+                market: leg.market.unwrap_or(match leg.source {
+                    SourceKind::Binance => self.market,
+                    _ => BinanceMarket::default(),
+                }),
+                aggregation: leg.aggregation.unwrap_or(self.aggregation),
+                min_trade_notional: match leg.source {
+                    SourceKind::Binance => self.min_trade_notional,
+                    _ => None,
+                },
+                window_secs: leg.window_secs.unwrap_or(self.window_secs),
+                invert: leg.invert,
+                weight: leg.weight,
+                blend: Vec::new(),
+                quorum: None,
+                reference: None,
+                change_limit: None,
+                smoothing: None,
+                json_path: leg.json_path.clone().or_else(|| self.json_path.clone()),
+                source_decimals: leg.source_decimals.unwrap_or(self.source_decimals),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// Candle interval the value is computed from, if any
+    pub fn candle_ms(&self) -> Option<u64> {
+        match self.aggregation {
+            Aggregation::BarTwap => Some(self.candle_secs.unwrap_or(1) * 1000),
+            Aggregation::RealizedVol => self.candle_secs.map(|secs| secs * 1000),
+            _ => None,
+        }
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        function_selector(&self.function)
+    }
+
+    /// Whether the value is published as an `int256`
+    pub fn signed(&self) -> bool {
+        self.function.contains("(int256") || self.function.contains(",int256")
+    }
+
+    /// `price` scaled to `decimals` as the value's ABI word: unsigned, or
+    /// two's complement for signed feeds. `None` when it doesn't fit.
+    pub fn scale_value(&self, price: f64) -> Option<U256> {
+        if self.signed() {
+            scale_price_signed(price, self.decimals).map(I256::into_raw)
+        } else {
+            scale_price(price, self.decimals)
+        }
+    }
+
+    /// A scaled value as the number it stands for
+    pub fn format_value(&self, value: U256) -> String {
+        if self.signed() {
+            I256::from_raw(value).to_string()
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Build calldata publishing `value` (already scaled, see
+    /// [`scale_value`](Self::scale_value)) for this feed
+    pub fn encode_call(&self, value: U256) -> Result<Bytes> {
+        self.encode_call_with_confidence(value, U256::ZERO)
+    }
+
+    /// Like [`encode_call`](Self::encode_call), with the confidence interval
+    /// (scaled like the value) for signatures that take one
+    pub fn encode_call_with_confidence(&self, value: U256, confidence: U256) -> Result<Bytes> {
+        self.encode_update(value, confidence, 0, 0)
+    }
+
+    /// Calldata for whichever fields this feed's signature takes: the value,
+    /// its confidence, or the round id and observation time (unix seconds)
+    pub fn encode_update(&self, value: U256, confidence: U256, round_id: u64, observed_at: u64) -> Result<Bytes> {
+        let selector = self.selector();
+        Ok(match self.encoding()? {
+            CallEncoding::FeedIdAndValue => encode_feed_call(selector, self.feed_key(), &[value]),
+            CallEncoding::FeedIdValueAndConfidence => encode_feed_call(selector, self.feed_key(), &[value, confidence]),
+            CallEncoding::FeedIdValueAndRound => {
+                encode_feed_call(selector, self.feed_key(), &[value, U256::from(round_id), U256::from(observed_at)])
+            }
+            CallEncoding::ValueOnly => {
+                let mut call_data = Vec::with_capacity(36);
+                call_data.extend_from_slice(&selector);
+                call_data.extend_from_slice(&value.to_be_bytes::<32>());
+                Bytes::from(call_data)
+            }
+        })
+    }
+}
+
+/// Scale a decimal price to an integer with `decimals` places.
+/// Returns `None` for negative or non-finite prices.
+pub fn scale_price(price: f64, decimals: u8) -> Option<U256> {
+    let scaled = (price * 10f64.powi(decimals as i32)).round();
+    if !scaled.is_finite() || scaled < 0.0 || scaled > u128::MAX as f64 {
+        return None;
+    }
+    Some(U256::from(scaled as u128))
+}
+
+/// Scale a decimal value that may be negative to an integer with `decimals`
+/// places. Returns `None` for non-finite values or ones beyond `i128`.
+pub fn scale_price_signed(price: f64, decimals: u8) -> Option<I256> {
+    let scaled = (price * 10f64.powi(decimals as i32)).round();
+    if !scaled.is_finite() || scaled.abs() >= i128::MAX as f64 {
+        return None;
+    }
+    I256::try_from(scaled as i128).ok()
+}
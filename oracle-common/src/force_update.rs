@@ -0,0 +1,64 @@
+//! Out-of-band publishes on operator request.
+//!
+//! After an incident is resolved, or when a consumer reports a stale value,
+//! an operator can ask for a feed to publish now instead of waiting for its
+//! interval, deviation or heartbeat: the gRPC `ForceUpdate` call (or
+//! `nonzu force-update <feed>`) queues the feeds in scope and each publishes
+//! on its next check. A forced update still goes through the normal path -
+//! pauses, halts, quorum, reference checks, the circuit breaker and the
+//! submitter chain all apply - and carries `forced` metadata so it can be
+//! told apart from scheduled ones.
+
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// Feeds accepting forced updates, and the ones queued for one
+#[derive(Debug, Default)]
+pub struct ForceUpdates {
+    feeds: RwLock<BTreeSet<String>>,
+    requested: Mutex<BTreeSet<String>>,
+}
+
+impl ForceUpdates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept forced updates for `feed`
+    pub fn register(&self, feed: &str) {
+        self.feeds.write().insert(feed.to_string());
+    }
+
+    /// Queue the feeds in `scope`: a feed, a target (its `<target>/` feeds) or
+    /// every feed when empty. Returns the feeds queued, none for an unknown scope.
+    pub fn request(&self, scope: &str) -> Vec<String> {
+        let target_prefix = format!("{}/", scope);
+        let feeds: Vec<String> = self
+            .feeds
+            .read()
+            .iter()
+            .filter(|feed| scope.is_empty() || *feed == scope || feed.starts_with(&target_prefix))
+            .cloned()
+            .collect();
+        self.requested.lock().extend(feeds.iter().cloned());
+        feeds
+    }
+
+    /// Whether `feed` has a forced update queued
+    pub fn is_requested(&self, feed: &str) -> bool {
+        self.requested.lock().contains(feed)
+    }
+
+    /// Mark `feed`'s forced update as sent
+    pub fn take(&self, feed: &str) -> bool {
+        self.requested.lock().remove(feed)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "feeds": self.feeds.read().len(),
+            "requested": self.requested.lock().iter().collect::<Vec<_>>(),
+        })
+    }
+}
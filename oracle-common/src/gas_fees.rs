@@ -0,0 +1,93 @@
+//! Per-request gas fee overrides.
+//!
+//! The chain's `GAS_PRICE_WEI` / `PRIORITY_FEE_WEI` are process-wide SDK
+//! defaults. A feed whose call is much heavier or more urgent than the rest
+//! (a batch update next to single-value ones) sets its own fees in the feeds
+//! file; [`GasFees::apply`] attaches them to each of its requests:
+//!
+//! - as `max_fee_per_gas` / `max_priority_fee_per_gas` metadata, read by the
+//!   dry-run signer and the key balance check
+//! - as a build hook setting them on the transaction, ahead of any hook the
+//!   request already had
+//!
+//! Unset fees keep the chain defaults.
+
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::types::rise_tx::RiseTransactionRequest;
+use std::sync::Arc;
+
+const MAX_FEE_KEY: &str = "max_fee_per_gas";
+const PRIORITY_FEE_KEY: &str = "max_priority_fee_per_gas";
+
+/// Fee overrides for one request; `None` keeps the chain default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasFees {
+    pub max_fee_per_gas: Option<u64>,
+    pub priority_fee: Option<u64>,
+}
+
+impl GasFees {
+    pub fn new(max_fee_per_gas: Option<u64>, priority_fee: Option<u64>) -> Self {
+        Self { max_fee_per_gas, priority_fee }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max_fee_per_gas.is_none() && self.priority_fee.is_none()
+    }
+
+    /// The overrides `tx_request` carries (see [`apply`](Self::apply))
+    pub fn from_request(tx_request: &TxRequest) -> Self {
+        let read = |key: &str| tx_request.metadata.get(key).and_then(|v| v.parse().ok());
+        Self { max_fee_per_gas: read(MAX_FEE_KEY), priority_fee: read(PRIORITY_FEE_KEY) }
+    }
+
+    /// `(max fee, priority fee)` with unset ones taken from the defaults;
+    /// the priority fee never exceeds the max fee
+    pub fn resolve(&self, default_max_fee: u64, default_priority_fee: u64) -> (u64, u64) {
+        let max_fee = self.max_fee_per_gas.unwrap_or(default_max_fee);
+        (max_fee, self.priority_fee.unwrap_or(default_priority_fee).min(max_fee))
+    }
+
+    /// Attach the overrides to `tx_request`; a no-op when there are none
+    pub fn apply(&self, tx_request: TxRequest) -> TxRequest {
+        if self.is_empty() {
+            return tx_request;
+        }
+        let mut tx_request = tx_request;
+        if let Some(fee) = self.max_fee_per_gas {
+            tx_request = tx_request.with_metadata(MAX_FEE_KEY, fee.to_string());
+        }
+        if let Some(fee) = self.priority_fee {
+            tx_request = tx_request.with_metadata(PRIORITY_FEE_KEY, fee.to_string());
+        }
+        let hook = GasFeeHook { fees: *self, inner: tx_request.build_hook.clone() };
+        tx_request.with_build_hook(Arc::new(hook))
+    }
+}
+
+/// Sets the request's fees, then defers to the original hook
+struct GasFeeHook {
+    fees: GasFees,
+    inner: Option<Arc<dyn TxBuildHook>>,
+}
+
+#[async_trait]
+impl TxBuildHook for GasFeeHook {
+    async fn on_build(
+        &self,
+        tx_request: &TxRequest,
+        mut tx: RiseTransactionRequest,
+    ) -> Result<RiseTransactionRequest, RiseError> {
+        if let Some(fee) = self.fees.max_fee_per_gas {
+            tx = tx.max_fee_per_gas(U256::from(fee));
+        }
+        if let Some(fee) = self.fees.priority_fee {
+            tx = tx.max_priority_fee_per_gas(U256::from(fee));
+        }
+        match &self.inner {
+            Some(inner) => inner.on_build(tx_request, tx).await,
+            None => Ok(tx),
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! gRPC API for internal services that prefer typed clients to scraping the
+//! JSON status endpoint.
+//!
+//! The service is generated from `proto/oracle.proto` (package
+//! `nonzu.oracle.v1`); consumers in other languages generate their clients
+//! from the same file. With `GRPC_ADDR` set (e.g. `0.0.0.0:50051`) it serves:
+//!
+//! - `GetLatestPrice`: a feed's last computed and last published value
+//! - `StreamPrices`: computed and published values as they happen, for every
+//!   feed or the ones requested
+//! - `GetStats`: update statistics of every trigger registered with
+//!   [`GrpcServer::with_stats`]
+//! - `Pause` / `Resume`: stop and restart the worker pools registered with
+//!   [`GrpcServer::with_control`], one scope or all of them. `Resume` also
+//!   lifts [halts](crate::halt) on the feeds in its scope
+//! - `ForceUpdate`: publish a feed, a target's feeds or every feed now
+//!   through the normal submission path (see [`crate::force_update`]), for
+//!   the feeds registered with [`GrpcServer::with_force_updates`]
+//!
+//! Prices come from the [`EventBus`](crate::events::EventBus), like the
+//! [`PriceStream`](crate::price_stream::PriceStream). `Pause`, `Resume` and
+//! `ForceUpdate` need `authorization: Bearer <GRPC_ADMIN_TOKEN>` metadata and are refused
+//! outright when no token is configured. Streams falling more than
+//! `GRPC_STREAM_BUFFER` events behind end with `DATA_LOSS`; reconnect to
+//! pick up again.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `GRPC_ADDR` | unset | Bind address (unset disables) |
+//! | `GRPC_ADMIN_TOKEN` | unset | Bearer token for `Pause` / `Resume` / `ForceUpdate` (unset disables them) |
+//! | `GRPC_STREAM_BUFFER` | `1024` | Events buffered per `StreamPrices` call |
+
+use anyhow::{Context, Result};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, TcpListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, warn};
+
+use crate::events::{EventBus, EventSubscriber, OracleEvent};
+use crate::force_update::ForceUpdates;
+use crate::halt::Halts;
+use crate::stats::SharedStats;
+
+/// Generated messages, server and client
+pub mod proto {
+    tonic::include_proto!("nonzu.oracle.v1");
+}
+
+use proto::oracle_server::{Oracle, OracleServer};
+use proto::{
+    ForceUpdateRequest, ForceUpdateResponse, GetLatestPriceRequest, GetStatsRequest, GetStatsResponse, LatestPrice,
+    PauseRequest, PauseResponse, PriceEvent, PriceEventKind, ResumeRequest, ResumeResponse, ScopeState,
+    StreamPricesRequest, TriggerStats,
+};
+
+pub struct GrpcServer {
+    bind: SocketAddr,
+    buffer: usize,
+    admin_token: Option<String>,
+    stats: Vec<(String, SharedStats)>,
+    controls: Vec<(String, Arc<OrchestratorErrorControl>)>,
+    halts: Option<Arc<Halts>>,
+    force_updates: Option<Arc<ForceUpdates>>,
+}
+
+impl GrpcServer {
+    pub fn new(bind: SocketAddr) -> Self {
+        Self {
+            bind,
+            buffer: 1024,
+            admin_token: None,
+            stats: Vec::new(),
+            controls: Vec::new(),
+            halts: None,
+            force_updates: None,
+        }
+    }
+
+    /// From `GRPC_ADDR` / `GRPC_ADMIN_TOKEN` / `GRPC_STREAM_BUFFER`; `None`
+    /// when the API is disabled
+    pub fn from_env() -> Result<Option<Self>> {
+        let bind: SocketAddr = match std::env::var("GRPC_ADDR") {
+            Ok(v) => v.parse().context("Invalid GRPC_ADDR")?,
+            Err(_) => return Ok(None),
+        };
+        let buffer = match std::env::var("GRPC_STREAM_BUFFER") {
+            Ok(v) => v.parse().context("Invalid GRPC_STREAM_BUFFER")?,
+            Err(_) => 1024,
+        };
+        let admin_token = std::env::var("GRPC_ADMIN_TOKEN").ok().filter(|token| !token.is_empty());
+        if admin_token.is_none() {
+            info!("🔒 GRPC_ADMIN_TOKEN unset - gRPC Pause/Resume/ForceUpdate are disabled");
+        }
+        Ok(Some(Self::new(bind).with_stream_buffer(buffer).with_admin_token(admin_token)))
+    }
+
+    /// Events buffered per `StreamPrices` call before it is cut off
+    pub fn with_stream_buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer.max(1);
+        self
+    }
+
+    /// Token `Pause` / `Resume` / `ForceUpdate` callers must present; `None` refuses them all
+    pub fn with_admin_token(mut self, token: Option<String>) -> Self {
+        self.admin_token = token;
+        self
+    }
+
+    /// Report `stats` in `GetStats` as `trigger`
+    pub fn with_stats(mut self, trigger: impl Into<String>, stats: SharedStats) -> Self {
+        self.stats.push((trigger.into(), stats));
+        self
+    }
+
+    /// Let `Pause` / `Resume` control the worker pool behind `scope`
+    pub fn with_control(mut self, scope: impl Into<String>, error_control: Arc<OrchestratorErrorControl>) -> Self {
+        self.controls.push((scope.into(), error_control));
+        self
+    }
+
+    /// Let `Resume` lift `halts`
+    pub fn with_halts(mut self, halts: Option<Arc<Halts>>) -> Self {
+        self.halts = halts;
+        self
+    }
+
+    /// Let `ForceUpdate` queue updates for the feeds registered with `force_updates`
+    pub fn with_force_updates(mut self, force_updates: Option<Arc<ForceUpdates>>) -> Self {
+        self.force_updates = force_updates;
+        self
+    }
+
+    /// Subscribe to the event bus and serve on `listener` until it fails
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        let (events, _) = broadcast::channel(self.buffer);
+        let api = Arc::new(OracleApi {
+            latest: RwLock::new(HashMap::new()),
+            events,
+            admin_token: self.admin_token,
+            stats: self.stats,
+            controls: self.controls,
+            halts: self.halts,
+            force_updates: self.force_updates,
+        });
+        EventBus::global().subscribe(api.clone());
+
+        tonic::transport::Server::builder()
+            .add_service(OracleServer::from_arc(api))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await?;
+        Ok(())
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let listener = match TcpListener::bind(self.bind).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to bind gRPC server on {}: {}", self.bind, e);
+                    return;
+                }
+            };
+
+            info!("🔌 gRPC API listening on {}", self.bind);
+            let bind = self.bind;
+            if let Err(e) = self.serve(listener).await {
+                error!("gRPC server error on {}: {}", bind, e);
+            }
+        })
+    }
+}
+
+struct OracleApi {
+    latest: RwLock<HashMap<String, LatestPrice>>,
+    events: broadcast::Sender<PriceEvent>,
+    admin_token: Option<String>,
+    stats: Vec<(String, SharedStats)>,
+    controls: Vec<(String, Arc<OrchestratorErrorControl>)>,
+    halts: Option<Arc<Halts>>,
+    force_updates: Option<Arc<ForceUpdates>>,
+}
+
+impl OracleApi {
+    /// Why an admin call is refused, if it is
+    fn rejection<T>(&self, request: &Request<T>) -> Option<Status> {
+        let Some(token) = &self.admin_token else {
+            return Some(Status::permission_denied("admin calls are disabled (GRPC_ADMIN_TOKEN unset)"));
+        };
+        let presented = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match presented {
+            Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => None,
+            Some(_) => Some(Status::permission_denied("invalid admin token")),
+            None => Some(Status::unauthenticated("missing bearer token")),
+        }
+    }
+
+    /// Pause or resume `scope`, or every scope when it is empty
+    async fn set_paused(&self, scope: &str, pause: bool) -> Result<Vec<ScopeState>, Status> {
+        let controls: Vec<_> = self.controls.iter().filter(|(name, _)| scope.is_empty() || name == scope).collect();
+        if controls.is_empty() {
+            return Err(Status::not_found(format!("unknown scope '{}'", scope)));
+        }
+        let mut states = Vec::new();
+        for (name, control) in controls {
+            if pause {
+                control.pause().await;
+            } else {
+                control.resume().await;
+            }
+            warn!("🔌 gRPC {} of {}", if pause { "pause" } else { "resume" }, name);
+            states.push(ScopeState { scope: name.clone(), paused: control.is_worker_pool_paused().await });
+        }
+        Ok(states)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+type PriceEventStream = Pin<Box<dyn Stream<Item = Result<PriceEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Oracle for OracleApi {
+    async fn get_latest_price(&self, request: Request<GetLatestPriceRequest>) -> Result<Response<LatestPrice>, Status> {
+        let feed = request.into_inner().feed;
+        match self.latest.read().get(&feed) {
+            Some(latest) => Ok(Response::new(latest.clone())),
+            None => Err(Status::not_found(format!("no values for feed '{}' yet", feed))),
+        }
+    }
+
+    type StreamPricesStream = PriceEventStream;
+
+    async fn stream_prices(
+        &self,
+        request: Request<StreamPricesRequest>,
+    ) -> Result<Response<Self::StreamPricesStream>, Status> {
+        let feeds: HashSet<String> = request.into_inner().feeds.into_iter().collect();
+        let stream = BroadcastStream::new(self.events.subscribe()).filter_map(move |event| match event {
+            Ok(event) if feeds.is_empty() || feeds.contains(&event.feed) => Some(Ok(event)),
+            Ok(_) => None,
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                Some(Err(Status::data_loss(format!("consumer fell {} events behind", missed))))
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn get_stats(&self, request: Request<GetStatsRequest>) -> Result<Response<GetStatsResponse>, Status> {
+        let trigger = request.into_inner().trigger;
+        let triggers: Vec<TriggerStats> = self
+            .stats
+            .iter()
+            .filter(|(name, _)| trigger.is_empty() || *name == trigger)
+            .map(|(name, stats)| {
+                let stats = stats.read();
+                TriggerStats {
+                    trigger: name.clone(),
+                    total_triggers: stats.total_triggers,
+                    successful_updates: stats.successful_updates,
+                    failed_updates: stats.failed_updates,
+                    dropped_updates: stats.dropped_updates,
+                    success_rate: stats.success_rate(),
+                    avg_drift_ms: stats.avg_drift_ms(),
+                    max_drift_ms: stats.max_drift_ms,
+                    avg_latency_ms: stats.avg_latency_ms(),
+                    max_latency_ms: stats.max_latency_ms,
+                    last_block: stats.last_block.map(|b| b.saturating_to::<u64>()),
+                }
+            })
+            .collect();
+        if triggers.is_empty() && !trigger.is_empty() {
+            return Err(Status::not_found(format!("unknown trigger '{}'", trigger)));
+        }
+        Ok(Response::new(GetStatsResponse { triggers }))
+    }
+
+    async fn pause(&self, request: Request<PauseRequest>) -> Result<Response<PauseResponse>, Status> {
+        if let Some(rejection) = self.rejection(&request) {
+            return Err(rejection);
+        }
+        let scopes = self.set_paused(&request.into_inner().scope, true).await?;
+        Ok(Response::new(PauseResponse { scopes }))
+    }
+
+    async fn resume(&self, request: Request<ResumeRequest>) -> Result<Response<ResumeResponse>, Status> {
+        if let Some(rejection) = self.rejection(&request) {
+            return Err(rejection);
+        }
+        let scope = request.into_inner().scope;
+        let cleared_halts = self.halts.as_ref().map(|halts| halts.resume(&scope)).unwrap_or_default();
+        let scopes = match self.set_paused(&scope, false).await {
+            Ok(scopes) => scopes,
+            // A halted feed's name rather than a worker pool's
+            Err(_) if !cleared_halts.is_empty() => Vec::new(),
+            Err(status) => return Err(status),
+        };
+        Ok(Response::new(ResumeResponse { scopes, cleared_halts }))
+    }
+
+    async fn force_update(&self, request: Request<ForceUpdateRequest>) -> Result<Response<ForceUpdateResponse>, Status> {
+        if let Some(rejection) = self.rejection(&request) {
+            return Err(rejection);
+        }
+        let Some(force_updates) = &self.force_updates else {
+            return Err(Status::unimplemented("this oracle has no feeds that take forced updates"));
+        };
+        let feed = request.into_inner().feed;
+        let feeds = force_updates.request(&feed);
+        if feeds.is_empty() {
+            return Err(Status::not_found(format!("unknown feed '{}'", feed)));
+        }
+        warn!("🔌 gRPC forced update of {}", feeds.join(", "));
+        Ok(Response::new(ForceUpdateResponse { feeds }))
+    }
+}
+
+impl EventSubscriber for OracleApi {
+    fn on_event(&self, event: &OracleEvent) {
+        let event = match event {
+            OracleEvent::ValueComputed { feed, value } => PriceEvent {
+                kind: PriceEventKind::Computed.into(),
+                feed: feed.clone(),
+                value: Some(*value),
+                timestamp_ms: now_ms(),
+                ..Default::default()
+            },
+            OracleEvent::UpdatePublished { feed, value, tx_hash, block_number, latency, .. } => PriceEvent {
+                kind: PriceEventKind::Published.into(),
+                feed: feed.clone(),
+                value: *value,
+                timestamp_ms: now_ms(),
+                tx_hash: tx_hash.map(|hash| hash.to_string()),
+                block_number: block_number.map(|b| b.saturating_to::<u64>()),
+                latency_ms: latency.map(|l| l.as_secs_f64() * 1000.0),
+            },
+            _ => return,
+        };
+
+        {
+            let mut latest = self.latest.write();
+            let entry = latest
+                .entry(event.feed.clone())
+                .or_insert_with(|| LatestPrice { feed: event.feed.clone(), ..Default::default() });
+            if event.kind == i32::from(PriceEventKind::Computed) {
+                entry.computed = Some(event.clone());
+            } else {
+                entry.published = Some(event.clone());
+            }
+        }
+        // Fails only when nobody is streaming
+        let _ = self.events.send(event);
+    }
+}
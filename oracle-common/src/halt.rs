@@ -0,0 +1,244 @@
+//! Halts that stay in place until an operator lifts them.
+//!
+//! Error-handler pauses, feed pauses and circuit breakers all resume on
+//! their own, which is right for transient failures but not for conditions
+//! that need a person to look first: a feed that keeps diverging from its
+//! reference, or loses its source quorum. With `HALT_ON` set, [`Halts`]
+//! halts a feed once a listed condition has happened `count` times within
+//! `HALT_WINDOW_SECS`:
+//!
+//! ```text
+//! HALT_ON=quorum_lost,reference_diverged:3,update_failed:20
+//! ```
+//!
+//! `quorum_lost` and `reference_diverged` count the updates each trigger
+//! holds back for that reason, under the trigger's label (`<target>/<feed>`
+//! with several targets), once per feed interval, so a sustained divergence
+//! counts every held update rather than every poll, and each target halts
+//! on its own. `update_failed` counts the
+//! [`OracleEvent::UpdateFailed`] events on the event bus.
+//!
+//! A halted feed publishes nothing until it is resumed with the gRPC
+//! `Resume` call (scope: the feed, its target, or empty for everything).
+//! Halting is logged as an error and published as [`OracleEvent::Halted`]
+//! for alerting sinks; lifting it publishes [`OracleEvent::HaltCleared`].
+//! Halts are not persisted: a restart lifts them.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `HALT_ON` | unset | `condition[:count]` list of `quorum_lost`, `reference_diverged`, `update_failed` (unset disables) |
+//! | `HALT_WINDOW_SECS` | `600` | Window the counts are taken over |
+
+use anyhow::{anyhow, bail, Context, Result};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
+
+use crate::events::{publish_event, EventSubscriber, OracleEvent};
+
+/// Conditions that can halt a feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltCondition {
+    QuorumLost,
+    ReferenceDiverged,
+    UpdateFailed,
+}
+
+impl HaltCondition {
+    pub const ALL: [HaltCondition; 3] = [Self::QuorumLost, Self::ReferenceDiverged, Self::UpdateFailed];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::QuorumLost => "quorum_lost",
+            Self::ReferenceDiverged => "reference_diverged",
+            Self::UpdateFailed => "update_failed",
+        }
+    }
+
+    /// The condition `event` reports, and for which feed. `QuorumLost` and
+    /// `ReferenceDiverged` are published by shared sources under the bare
+    /// feed id when their state changes; triggers count those through
+    /// [`Halts::held`] instead.
+    fn of(event: &OracleEvent) -> Option<(Self, &str)> {
+        match event {
+            OracleEvent::UpdateFailed { feed, .. } => Some((Self::UpdateFailed, feed)),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for HaltCondition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s.trim().replace('-', "_"))
+            .ok_or_else(|| anyhow!("Unknown halt condition '{}' (expected quorum_lost, reference_diverged or update_failed)", s))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Halt {
+    reason: String,
+    since_ms: u64,
+}
+
+pub struct Halts {
+    /// Condition -> occurrences within `window` that halt a feed
+    rules: BTreeMap<HaltCondition, u32>,
+    window: Duration,
+    seen: Mutex<HashMap<(String, HaltCondition), VecDeque<Instant>>>,
+    halted: Mutex<BTreeMap<String, Halt>>,
+}
+
+impl Halts {
+    pub fn new(window: Duration) -> Self {
+        Self { rules: BTreeMap::new(), window, seen: Mutex::new(HashMap::new()), halted: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Halt a feed after `count` occurrences of `condition` within the window
+    pub fn with_rule(mut self, condition: HaltCondition, count: u32) -> Self {
+        self.rules.insert(condition, count.max(1));
+        self
+    }
+
+    /// Parse `condition[:count],...`
+    pub fn parse(spec: &str, window: Duration) -> Result<Self> {
+        let mut halts = Self::new(window);
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (condition, count) = match entry.split_once(':') {
+                Some((condition, count)) => {
+                    (condition, count.trim().parse().with_context(|| format!("Invalid count in halt rule '{}'", entry))?)
+                }
+                None => (entry, 1),
+            };
+            halts = halts.with_rule(condition.parse()?, count);
+        }
+        if halts.rules.is_empty() {
+            bail!("HALT_ON lists no conditions");
+        }
+        Ok(halts)
+    }
+
+    /// From `HALT_ON` / `HALT_WINDOW_SECS`; `None` when `HALT_ON` is unset
+    pub fn from_env() -> Result<Option<Arc<Self>>> {
+        let Ok(spec) = std::env::var("HALT_ON") else {
+            return Ok(None);
+        };
+        let window = match std::env::var("HALT_WINDOW_SECS") {
+            Ok(v) => Duration::from_secs(v.parse().context("Invalid HALT_WINDOW_SECS")?),
+            Err(_) => Duration::from_secs(600),
+        };
+        let halts = Self::parse(&spec, window)?;
+        info!("🛑 Feeds halt until resumed on: {} (within {:?})", spec, window);
+        Ok(Some(Arc::new(halts)))
+    }
+
+    /// Halt `feed` until it is resumed
+    pub fn halt(&self, feed: &str, reason: impl Into<String>) {
+        let reason = reason.into();
+        {
+            let mut halted = self.halted.lock();
+            if halted.contains_key(feed) {
+                return;
+            }
+            halted.insert(feed.to_string(), Halt { reason: reason.clone(), since_ms: now_ms() });
+        }
+        error!("🛑 {} HALTED: {} - no updates until an operator resumes it", feed, reason);
+        publish_event(OracleEvent::Halted { scope: feed.to_string(), reason });
+    }
+
+    /// Why `feed` is halted, if it is
+    pub fn halted(&self, feed: &str) -> Option<String> {
+        self.halted.lock().get(feed).map(|halt| halt.reason.clone())
+    }
+
+    pub fn is_halted(&self, feed: &str) -> bool {
+        self.halted.lock().contains_key(feed)
+    }
+
+    /// Lift the halts in `scope`: a feed, a target (its `<target>/` feeds)
+    /// or everything when empty. Returns the feeds resumed.
+    pub fn resume(&self, scope: &str) -> Vec<String> {
+        let target_prefix = format!("{}/", scope);
+        let cleared: Vec<String> = {
+            let mut halted = self.halted.lock();
+            let cleared: Vec<String> = halted
+                .keys()
+                .filter(|feed| scope.is_empty() || *feed == scope || feed.starts_with(&target_prefix))
+                .cloned()
+                .collect();
+            for feed in &cleared {
+                halted.remove(feed);
+            }
+            cleared
+        };
+
+        self.seen.lock().retain(|(feed, _), _| !cleared.contains(feed));
+        for feed in &cleared {
+            warn!("▶️ Halt on {} lifted by operator", feed);
+            publish_event(OracleEvent::HaltCleared { scope: feed.clone() });
+        }
+        cleared
+    }
+
+    /// An update of the trigger labelled `label` was held back by `condition`
+    pub fn held(&self, condition: HaltCondition, label: &str) {
+        if !self.is_halted(label) {
+            self.record(condition, label);
+        }
+    }
+
+    /// Count an occurrence of `condition` on `feed`, halting it at the limit
+    fn record(&self, condition: HaltCondition, feed: &str) {
+        let Some(&count) = self.rules.get(&condition) else { return };
+        let now = Instant::now();
+        let occurrences = {
+            let mut seen = self.seen.lock();
+            let times = seen.entry((feed.to_string(), condition)).or_default();
+            times.push_back(now);
+            while times.front().is_some_and(|t| now.duration_since(*t) > self.window) {
+                times.pop_front();
+            }
+            times.len()
+        };
+        if occurrences >= count as usize {
+            self.halt(feed, format!("{} {} time(s) within {:?}", condition.as_str(), occurrences, self.window));
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let halted: BTreeMap<_, _> = self
+            .halted
+            .lock()
+            .iter()
+            .map(|(feed, halt)| (feed.clone(), json!({ "reason": halt.reason, "since_ms": halt.since_ms })))
+            .collect();
+        json!({
+            "rules": self.rules.iter().map(|(c, n)| (c.as_str(), *n)).collect::<BTreeMap<_, _>>(),
+            "window_secs": self.window.as_secs(),
+            "halted": halted,
+        })
+    }
+}
+
+impl EventSubscriber for Halts {
+    fn on_event(&self, event: &OracleEvent) {
+        if let Some((condition, feed)) = HaltCondition::of(event) {
+            if !self.is_halted(feed) {
+                self.record(condition, feed);
+            }
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
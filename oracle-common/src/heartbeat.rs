@@ -0,0 +1,112 @@
+//! Dead-man's-switch heartbeat pings.
+//!
+//! Errors get alerted on, but an oracle that silently stops publishing does
+//! not. With `HEARTBEAT_URLS` set, confirmed updates (published on the
+//! [`EventBus`](crate::events::EventBus)) mark the heartbeat and a
+//! background task GETs every URL (healthchecks.io, Cronitor, Uptime Kuma
+//! push monitors, ...) at most once per `HEARTBEAT_INTERVAL_SECS` - but only
+//! if something was published since the last ping. When publishing stops,
+//! the pings stop and the monitor's grace period runs out.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `HEARTBEAT_URLS` | unset | Comma-separated ping URLs (unset disables) |
+//! | `HEARTBEAT_INTERVAL_SECS` | `60` | Minimum time between pings |
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::events::{EventSubscriber, OracleEvent};
+
+pub struct Heartbeat {
+    urls: Vec<String>,
+    interval: Duration,
+    /// A confirmed update happened since the last ping
+    alive: AtomicBool,
+    pings: AtomicU64,
+    ping_failures: AtomicU64,
+}
+
+impl Heartbeat {
+    pub fn spawn(urls: Vec<String>, interval: Duration) -> (Arc<Self>, JoinHandle<()>) {
+        let heartbeat = Arc::new(Self {
+            urls,
+            interval,
+            alive: AtomicBool::new(false),
+            pings: AtomicU64::new(0),
+            ping_failures: AtomicU64::new(0),
+        });
+        let handle = tokio::spawn(heartbeat.clone().run());
+        (heartbeat, handle)
+    }
+
+    /// From `HEARTBEAT_URLS` / `HEARTBEAT_INTERVAL_SECS`; `None` when unset
+    pub fn from_env() -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let urls: Vec<String> = match std::env::var("HEARTBEAT_URLS") {
+            Ok(v) => v.split(',').map(str::trim).filter(|u| !u.is_empty()).map(String::from).collect(),
+            Err(_) => return Ok(None),
+        };
+        if urls.is_empty() {
+            return Ok(None);
+        }
+        let secs: u64 = match std::env::var("HEARTBEAT_INTERVAL_SECS") {
+            Ok(v) => v.parse().context("Invalid HEARTBEAT_INTERVAL_SECS")?,
+            Err(_) => 60,
+        };
+        info!("💓 Heartbeat to {} URL(s) every {}s while publishing", urls.len(), secs);
+        Ok(Some(Self::spawn(urls, Duration::from_secs(secs.max(1)))))
+    }
+
+    /// Record a confirmed update; cheap enough to call on every one
+    pub fn beat(&self) {
+        self.alive.store(true, Ordering::Relaxed);
+    }
+
+    async fn run(self: Arc<Self>) {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            ticker.tick().await;
+            if !self.alive.swap(false, Ordering::Relaxed) {
+                debug!("No confirmed updates since the last heartbeat, not pinging");
+                continue;
+            }
+            for url in &self.urls {
+                match client.get(url).send().await.and_then(|r| r.error_for_status()) {
+                    Ok(_) => {
+                        self.pings.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        self.ping_failures.fetch_add(1, Ordering::Relaxed);
+                        warn!("⚠️ Heartbeat ping to {} failed: {}", url, e);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "urls": self.urls.len(),
+            "interval_secs": self.interval.as_secs(),
+            "pings": self.pings.load(Ordering::Relaxed),
+            "ping_failures": self.ping_failures.load(Ordering::Relaxed),
+        })
+    }
+}
+
+impl EventSubscriber for Heartbeat {
+    fn on_event(&self, event: &OracleEvent) {
+        if let OracleEvent::UpdatePublished { .. } = event {
+            self.beat();
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! In-memory history of recent updates.
+//!
+//! Every completed update (confirmed or failed) is appended to a bounded ring
+//! buffer with its feed, published value, latency and scheduling drift -
+//! subscribe it to the [`EventBus`](crate::events::EventBus). The status
+//! server serves it at `/history` and charts it on the dashboard.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `HISTORY_SIZE` | `2000` | Updates kept across all feeds |
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::events::{EventSubscriber, OracleEvent};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateRecord {
+    pub unix_ms: u64,
+    pub feed: String,
+    pub success: bool,
+    /// Published value (price feeds only)
+    pub value: Option<f64>,
+    pub latency_ms: Option<u64>,
+    pub drift_ms: i64,
+}
+
+impl UpdateRecord {
+    /// A completed update of `feed`, stamped now
+    pub fn new(feed: &str, success: bool, value: Option<f64>, latency: Option<Duration>, drift_ms: i64) -> Self {
+        Self {
+            unix_ms: unix_ms(),
+            feed: feed.to_string(),
+            success,
+            value,
+            latency_ms: latency.map(|l| l.as_millis() as u64),
+            drift_ms,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateHistory {
+    records: Mutex<VecDeque<UpdateRecord>>,
+    capacity: usize,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl UpdateHistory {
+    pub fn new(capacity: usize) -> Arc<Self> {
+        let capacity = capacity.max(1);
+        Arc::new(Self {
+            records: Mutex::new(VecDeque::with_capacity(capacity.min(4096))),
+            capacity,
+        })
+    }
+
+    pub fn from_env() -> Arc<Self> {
+        let capacity = match std::env::var("HISTORY_SIZE") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid HISTORY_SIZE '{}', using 2000", v);
+                2000
+            }),
+            Err(_) => 2000,
+        };
+        Self::new(capacity)
+    }
+
+    /// Record a completed update of `feed`
+    pub fn record(&self, feed: &str, success: bool, value: Option<f64>, latency: Option<Duration>, drift_ms: i64) {
+        self.push(UpdateRecord::new(feed, success, value, latency, drift_ms));
+    }
+
+    pub fn push(&self, record: UpdateRecord) {
+        let mut records = self.records.lock();
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.lock().is_empty()
+    }
+
+    /// Records newer than `since_ms` (unix ms), oldest first
+    pub fn since(&self, since_ms: u64) -> Vec<UpdateRecord> {
+        self.records.lock().iter().filter(|r| r.unix_ms > since_ms).cloned().collect()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "capacity": self.capacity,
+            "updates": self.since(0),
+        })
+    }
+}
+
+impl EventSubscriber for UpdateHistory {
+    fn on_event(&self, event: &OracleEvent) {
+        match event {
+            OracleEvent::UpdatePublished { feed, value, latency, drift_ms, .. } => {
+                self.record(feed, true, *value, *latency, *drift_ms)
+            }
+            OracleEvent::UpdateFailed { feed, value, latency, drift_ms } => {
+                self.record(feed, false, *value, *latency, *drift_ms)
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Periodic Parquet / CSV export of trades and update outcomes.
+//!
+//! For research on feed quality the in-memory windows are too short-lived:
+//! trades are consumed by the TWAP and [`UpdateHistory`](crate::history::UpdateHistory)
+//! is a ring buffer. With `EXPORT_DIR` set, the exporter collects every
+//! trade a source receives and every completed update from the
+//! [`EventBus`](crate::events::EventBus) and writes them out every
+//! `EXPORT_INTERVAL_SECS`, one file per kind and interval:
+//!
+//! ```text
+//! trades-20250101T000000.000Z.parquet   unix_ms, source, symbol, price, quantity, trade_time_ms
+//! updates-20250101T000000.000Z.parquet  unix_ms, feed, success, value, latency_ms, drift_ms
+//! ```
+//!
+//! Files are written under a temporary name and renamed, so readers never
+//! see a partial file. Exports older than `EXPORT_RETENTION_DAYS` are
+//! deleted after each run. Whatever is pending at shutdown is written by the
+//! final [`HistoryExporter::export`] the apps call on their way out.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `EXPORT_DIR` | unset | Output directory, created if missing (unset disables) |
+//! | `EXPORT_FORMAT` | `parquet` | `parquet` or `csv` |
+//! | `EXPORT_INTERVAL_SECS` | `3600` | Time between exports |
+//! | `EXPORT_RETENTION_DAYS` | `30` | Age after which exports are deleted (0 keeps them) |
+//! | `EXPORT_MAX_TRADES` | `1000000` | Trades held between exports; later ones are dropped and counted |
+
+use anyhow::{bail, Context, Result};
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use chrono::Utc;
+use parking_lot::Mutex;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+use serde_json::json;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::events::{EventSubscriber, OracleEvent};
+use crate::history::UpdateRecord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "parquet" => Ok(ExportFormat::Parquet),
+            "csv" => Ok(ExportFormat::Csv),
+            other => bail!("Unknown export format '{}' (expected parquet or csv)", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeRecord {
+    /// When the trade was received
+    pub unix_ms: u64,
+    pub source: String,
+    pub symbol: String,
+    pub price: f64,
+    pub quantity: f64,
+    /// Exchange timestamp of the trade
+    pub trade_time_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryExportConfig {
+    pub dir: PathBuf,
+    pub format: ExportFormat,
+    pub interval: Duration,
+    /// Age after which exports are deleted, `None` to keep them
+    pub retention: Option<Duration>,
+    pub max_trades: usize,
+}
+
+impl HistoryExportConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            format: ExportFormat::Parquet,
+            interval: Duration::from_secs(3600),
+            retention: Some(Duration::from_secs(30 * 86_400)),
+            max_trades: 1_000_000,
+        }
+    }
+
+    /// From `EXPORT_DIR` and friends; `None` when unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let mut config = match std::env::var("EXPORT_DIR") {
+            Ok(dir) if !dir.is_empty() => Self::new(dir),
+            _ => return Ok(None),
+        };
+        if let Ok(v) = std::env::var("EXPORT_FORMAT") {
+            config.format = v.parse()?;
+        }
+        if let Ok(v) = std::env::var("EXPORT_INTERVAL_SECS") {
+            let secs: u64 = v.parse().context("Invalid EXPORT_INTERVAL_SECS")?;
+            config.interval = Duration::from_secs(secs.max(1));
+        }
+        if let Ok(v) = std::env::var("EXPORT_RETENTION_DAYS") {
+            let days: u64 = v.parse().context("Invalid EXPORT_RETENTION_DAYS")?;
+            config.retention = (days > 0).then(|| Duration::from_secs(days * 86_400));
+        }
+        if let Ok(v) = std::env::var("EXPORT_MAX_TRADES") {
+            config.max_trades = v.parse().context("Invalid EXPORT_MAX_TRADES")?;
+        }
+        Ok(Some(config))
+    }
+}
+
+pub struct HistoryExporter {
+    config: HistoryExportConfig,
+    trades: Mutex<Vec<TradeRecord>>,
+    updates: Mutex<Vec<UpdateRecord>>,
+    dropped_trades: AtomicU64,
+    files_written: AtomicU64,
+    files_deleted: AtomicU64,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl HistoryExporter {
+    /// Exporter writing to `config.dir`, which is created if missing
+    pub fn new(config: HistoryExportConfig) -> Result<Arc<Self>> {
+        std::fs::create_dir_all(&config.dir)
+            .with_context(|| format!("Failed to create export directory {}", config.dir.display()))?;
+        Ok(Arc::new(Self {
+            config,
+            trades: Mutex::new(Vec::new()),
+            updates: Mutex::new(Vec::new()),
+            dropped_trades: AtomicU64::new(0),
+            files_written: AtomicU64::new(0),
+            files_deleted: AtomicU64::new(0),
+        }))
+    }
+
+    pub fn spawn(config: HistoryExportConfig) -> Result<(Arc<Self>, JoinHandle<()>)> {
+        let exporter = Self::new(config)?;
+        let handle = tokio::spawn(exporter.clone().run());
+        Ok((exporter, handle))
+    }
+
+    /// From `EXPORT_DIR` and friends; `None` when unset. Subscribe the
+    /// exporter to the event bus to feed it.
+    pub fn from_env() -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let Some(config) = HistoryExportConfig::from_env()? else { return Ok(None) };
+        info!(
+            "🗄️ Exporting trades and updates to {} as {} every {}s",
+            config.dir.display(),
+            config.format.extension(),
+            config.interval.as_secs()
+        );
+        Self::spawn(config).map(Some)
+    }
+
+    async fn run(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(self.config.interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let exporter = self.clone();
+            match tokio::task::spawn_blocking(move || exporter.export()).await {
+                Ok(Ok(files)) => debug!("Exported {} file(s)", files.len()),
+                Ok(Err(e)) => warn!("⚠️ History export failed: {:#}", e),
+                Err(e) => warn!("⚠️ History export task failed: {}", e),
+            }
+        }
+    }
+
+    /// Write out everything collected since the last export and apply the
+    /// retention; returns the files written. Blocks on file IO.
+    pub fn export(&self) -> Result<Vec<PathBuf>> {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+        let trades = std::mem::take(&mut *self.trades.lock());
+        let updates = std::mem::take(&mut *self.updates.lock());
+
+        let mut written = Vec::new();
+        if !trades.is_empty() {
+            let path = self.path("trades", &stamp);
+            write_atomically(&path, |file| match self.config.format {
+                ExportFormat::Parquet => write_parquet(file, trades_batch(&trades)?),
+                ExportFormat::Csv => write_csv(file, &trades),
+            })?;
+            written.push(path);
+        }
+        if !updates.is_empty() {
+            let path = self.path("updates", &stamp);
+            write_atomically(&path, |file| match self.config.format {
+                ExportFormat::Parquet => write_parquet(file, updates_batch(&updates)?),
+                ExportFormat::Csv => write_csv(file, &updates),
+            })?;
+            written.push(path);
+        }
+        self.files_written.fetch_add(written.len() as u64, Ordering::Relaxed);
+
+        if let Some(retention) = self.config.retention {
+            let deleted = self.prune(retention)?;
+            self.files_deleted.fetch_add(deleted as u64, Ordering::Relaxed);
+        }
+        Ok(written)
+    }
+
+    fn path(&self, kind: &str, stamp: &str) -> PathBuf {
+        self.config.dir.join(format!("{}-{}.{}", kind, stamp, self.config.format.extension()))
+    }
+
+    /// Delete exports (of either format) last modified more than `retention` ago
+    fn prune(&self, retention: Duration) -> Result<usize> {
+        let cutoff = SystemTime::now() - retention;
+        let mut deleted = 0;
+        for entry in std::fs::read_dir(&self.config.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_export = (name.starts_with("trades-") || name.starts_with("updates-"))
+                && (name.ends_with(".parquet") || name.ends_with(".csv"));
+            if !is_export || entry.metadata()?.modified()? >= cutoff {
+                continue;
+            }
+            std::fs::remove_file(entry.path())?;
+            deleted += 1;
+        }
+        if deleted > 0 {
+            info!("🗑️ Deleted {} export(s) older than {} days", deleted, retention.as_secs() / 86_400);
+        }
+        Ok(deleted)
+    }
+
+    /// Trades and updates waiting for the next export
+    pub fn pending(&self) -> (usize, usize) {
+        (self.trades.lock().len(), self.updates.lock().len())
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let (trades, updates) = self.pending();
+        json!({
+            "dir": self.config.dir.display().to_string(),
+            "format": self.config.format.extension(),
+            "pending_trades": trades,
+            "pending_updates": updates,
+            "dropped_trades": self.dropped_trades.load(Ordering::Relaxed),
+            "files_written": self.files_written.load(Ordering::Relaxed),
+            "files_deleted": self.files_deleted.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Write through `write` to a temporary file next to `path`, then rename
+fn write_atomically(path: &Path, write: impl FnOnce(File) -> Result<()>) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let file = File::create(&tmp).with_context(|| format!("Failed to create {}", tmp.display()))?;
+    if let Err(e) = write(file) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.context(format!("Failed to write {}", path.display())));
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn write_csv<T: Serialize>(file: File, rows: &[T]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(file);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parquet(file: File, batch: RecordBatch) -> Result<()> {
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+fn trades_batch(trades: &[TradeRecord]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("unix_ms", DataType::UInt64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("price", DataType::Float64, false),
+        Field::new("quantity", DataType::Float64, false),
+        Field::new("trade_time_ms", DataType::UInt64, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(trades.iter().map(|t| t.unix_ms))),
+        Arc::new(StringArray::from_iter_values(trades.iter().map(|t| &t.source))),
+        Arc::new(StringArray::from_iter_values(trades.iter().map(|t| &t.symbol))),
+        Arc::new(Float64Array::from_iter_values(trades.iter().map(|t| t.price))),
+        Arc::new(Float64Array::from_iter_values(trades.iter().map(|t| t.quantity))),
+        Arc::new(UInt64Array::from_iter_values(trades.iter().map(|t| t.trade_time_ms))),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+fn updates_batch(updates: &[UpdateRecord]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("unix_ms", DataType::UInt64, false),
+        Field::new("feed", DataType::Utf8, false),
+        Field::new("success", DataType::Boolean, false),
+        Field::new("value", DataType::Float64, true),
+        Field::new("latency_ms", DataType::UInt64, true),
+        Field::new("drift_ms", DataType::Int64, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(updates.iter().map(|u| u.unix_ms))),
+        Arc::new(StringArray::from_iter_values(updates.iter().map(|u| &u.feed))),
+        Arc::new(BooleanArray::from_iter(updates.iter().map(|u| Some(u.success)))),
+        Arc::new(Float64Array::from_iter(updates.iter().map(|u| u.value))),
+        Arc::new(UInt64Array::from_iter(updates.iter().map(|u| u.latency_ms))),
+        Arc::new(Int64Array::from_iter_values(updates.iter().map(|u| u.drift_ms))),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+impl EventSubscriber for HistoryExporter {
+    fn on_event(&self, event: &OracleEvent) {
+        match event {
+            OracleEvent::TradeReceived { source, symbol, price, quantity, timestamp_ms } => {
+                let mut trades = self.trades.lock();
+                if trades.len() >= self.config.max_trades {
+                    self.dropped_trades.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                trades.push(TradeRecord {
+                    unix_ms: unix_ms(),
+                    source: source.clone(),
+                    symbol: symbol.clone(),
+                    price: *price,
+                    quantity: *quantity,
+                    trade_time_ms: *timestamp_ms,
+                });
+            }
+            OracleEvent::UpdatePublished { feed, value, latency, drift_ms, .. } => {
+                self.updates.lock().push(UpdateRecord::new(feed, true, *value, *latency, *drift_ms))
+            }
+            OracleEvent::UpdateFailed { feed, value, latency, drift_ms } => {
+                self.updates.lock().push(UpdateRecord::new(feed, false, *value, *latency, *drift_ms))
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! HTTP client tuning for the submission path.
+//!
+//! At a 100ms cadence a fresh TCP + TLS handshake costs a meaningful share
+//! of the update budget, so the submission client keeps a warm connection
+//! pool, disables Nagle and can pin the HTTP version.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `HTTP_VERSION` | `auto` | `auto` (ALPN), `http1` or `http2` (prior knowledge) |
+//! | `HTTP_POOL_MAX_IDLE` | `8` | Idle connections kept per host |
+//! | `HTTP_POOL_IDLE_TIMEOUT_SECS` | `90` | Drop idle connections after this long |
+//! | `HTTP_TCP_KEEPALIVE_SECS` | `30` | TCP keep-alive probe interval |
+//! | `HTTP_TCP_NODELAY` | `true` | Disable Nagle's algorithm |
+//! | `HTTP_KEEP_WARM_SECS` | `15` | Ping each RPC this often so the pool never goes cold (0 = off) |
+
+use anyhow::{anyhow, Result};
+use std::env;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpVersion {
+    Auto,
+    Http1,
+    Http2,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpTuning {
+    pub version: HttpVersion,
+    pub pool_max_idle: usize,
+    pub pool_idle_timeout: Duration,
+    pub tcp_keepalive: Duration,
+    pub tcp_nodelay: bool,
+    pub keep_warm: Option<Duration>,
+}
+
+impl Default for HttpTuning {
+    fn default() -> Self {
+        Self {
+            version: HttpVersion::Auto,
+            pool_max_idle: 8,
+            pool_idle_timeout: Duration::from_secs(90),
+            tcp_keepalive: Duration::from_secs(30),
+            tcp_nodelay: true,
+            keep_warm: Some(Duration::from_secs(15)),
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str) -> Result<Option<T>> {
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| anyhow!("Invalid {} '{}'", key, value)),
+        Err(_) => Ok(None),
+    }
+}
+
+impl HttpTuning {
+    pub fn from_env() -> Result<Self> {
+        let mut tuning = Self::default();
+
+        if let Ok(version) = env::var("HTTP_VERSION") {
+            tuning.version = match version.as_str() {
+                "auto" => HttpVersion::Auto,
+                "http1" => HttpVersion::Http1,
+                "http2" => HttpVersion::Http2,
+                other => anyhow::bail!("Unknown HTTP_VERSION '{}' - expected auto, http1 or http2", other),
+            };
+        }
+        if let Some(n) = env_parse("HTTP_POOL_MAX_IDLE")? {
+            tuning.pool_max_idle = n;
+        }
+        if let Some(secs) = env_parse("HTTP_POOL_IDLE_TIMEOUT_SECS")? {
+            tuning.pool_idle_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = env_parse("HTTP_TCP_KEEPALIVE_SECS")? {
+            tuning.tcp_keepalive = Duration::from_secs(secs);
+        }
+        if let Some(nodelay) = env_parse("HTTP_TCP_NODELAY")? {
+            tuning.tcp_nodelay = nodelay;
+        }
+        if let Some(secs) = env_parse::<u64>("HTTP_KEEP_WARM_SECS")? {
+            tuning.keep_warm = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+
+        Ok(tuning)
+    }
+
+    /// Build a client with these settings
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .pool_max_idle_per_host(self.pool_max_idle)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive)
+            .tcp_nodelay(self.tcp_nodelay);
+
+        builder = match self.version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2 => builder
+                .http2_prior_knowledge()
+                .http2_keep_alive_interval(self.tcp_keepalive)
+                .http2_keep_alive_while_idle(true),
+        };
+
+        Ok(builder.build()?)
+    }
+}
@@ -0,0 +1,574 @@
+//! Key selection for the orchestrator.
+//!
+//! The rotation policy (`KEY_ROTATION`) decides which funded key sends the
+//! next transaction; some contracts rate-limit per sender, so sticky or
+//! least-recently-errored rotation can beat strict round-robin.
+//!
+//! Keys whose balance is below `min_balance` are skipped (but stay in the
+//! rotation, so they come back once topped up) and the remaining keys are
+//! picked with probability proportional to their spendable balance. This
+//! spreads gas spend evenly instead of round-robin hammering a nearly-empty
+//! key until it fails with InsufficientFunds.
+//!
+//! With pipelining (`PIPELINE_DEPTH` > 1) several workers submit at once, so
+//! a key is held from selection until its result comes back and concurrent
+//! transactions always go out from different keys. The SDK's `KeySelector`
+//! only sees the candidate keys, so results are reported by wrapping the
+//! submitter with [`KeyRotation::track_results`], which releases the key
+//! selected in the same worker task.
+//!
+//! With `PRESUBMIT_BALANCE_CHECK=true` each transaction's worst-case cost
+//! (gas limit times the request's own max fee, or else the gas price cached
+//! by the balance refresher) is checked against the cached balances before a
+//! key is picked: keys that couldn't pay are passed over, and when none can the transaction is skipped before
+//! signing rather than sent to fail with InsufficientFunds. The cost is
+//! deducted from the cached balance on selection, so a burst between two
+//! refreshes can't overdraw a key either.
+//!
+//! The SDK doesn't pass the request being sent to the selector either, so
+//! [`PendingQueue::admit`](crate::backpressure::PendingQueue::admit) notes
+//! each admitted request for its worker task ([`note_sending`]) and the
+//! selection in that task prices and pins by it. A selection without a
+//! noted request (outside a worker task) prices at the default gas limit
+//! and pins one key for every `sticky-per-feed` feed.
+
+use alloy::primitives::{Address, Bytes, U256};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use nonzu_sdk::management::KeySelector;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::traits::TxRequest;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::RwLock;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::gas_fees::GasFees;
+use crate::keys::PrivateKey;
+use crate::rpc_batch::RpcBatcher;
+use crate::task_slots::TaskSlots;
+
+/// What key selection prices and pins a request by
+#[derive(Debug, Clone)]
+struct SendingRequest {
+    feed: String,
+    gas_limit: Option<U256>,
+    max_fee_per_gas: Option<u64>,
+}
+
+impl SendingRequest {
+    fn of(tx_request: &TxRequest) -> Self {
+        Self {
+            feed: tx_request
+                .metadata
+                .get("feed_id")
+                .cloned()
+                .unwrap_or_else(|| tx_request.to.to_string()),
+            gas_limit: tx_request.gas_limit,
+            max_fee_per_gas: GasFees::from_request(tx_request).max_fee_per_gas,
+        }
+    }
+}
+
+/// Requests admitted in each worker task, read by its key selection
+static SENDING: TaskSlots<SendingRequest> = TaskSlots::new();
+
+/// `tx_request` is the next one the current worker task signs
+pub fn note_sending(tx_request: &TxRequest) {
+    SENDING.put(SendingRequest::of(tx_request));
+}
+
+pub struct BalanceAwareSelector {
+    balances: RwLock<HashMap<Address, U256>>,
+    min_balance: U256,
+    /// Gas price as of the last refresh
+    gas_price: RwLock<Option<U256>>,
+    rng_state: AtomicU64,
+}
+
+impl BalanceAwareSelector {
+    pub fn new(min_balance: U256) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15);
+
+        Self {
+            balances: RwLock::new(HashMap::new()),
+            min_balance,
+            gas_price: RwLock::new(None),
+            rng_state: AtomicU64::new(seed | 1),
+        }
+    }
+
+    pub fn update_balance(&self, address: Address, balance: U256) {
+        if balance < self.min_balance {
+            warn!("💸 Key {} below minimum balance ({} < {} wei), skipping it in rotation",
+                address, balance, self.min_balance);
+        }
+        self.balances.write().insert(address, balance);
+    }
+
+    pub fn update_gas_price(&self, gas_price: U256) {
+        *self.gas_price.write() = Some(gas_price);
+    }
+
+    /// Most `tx_request` can cost at its own max fee, or the cached gas price;
+    /// `None` until the gas price is known
+    pub fn max_cost(&self, tx_request: &TxRequest) -> Option<U256> {
+        self.cost_of(&SendingRequest::of(tx_request))
+    }
+
+    fn cost_of(&self, request: &SendingRequest) -> Option<U256> {
+        let gas_price = match request.max_fee_per_gas {
+            Some(fee) => U256::from(fee),
+            None => (*self.gas_price.read())?,
+        };
+        Some(request.gas_limit.unwrap_or(U256::from(DEFAULT_GAS_LIMIT)).saturating_mul(gas_price))
+    }
+
+    /// Most a request can cost at the default gas limit and the cached gas
+    /// price, for selections that don't see the request
+    pub fn default_cost(&self) -> Option<U256> {
+        Some(U256::from(DEFAULT_GAS_LIMIT).saturating_mul((*self.gas_price.read())?))
+    }
+
+    /// Keys from `candidates` whose cached balance covers `cost` (or whose
+    /// balance is not known yet)
+    pub fn affording(&self, candidates: &[Address], cost: U256) -> Vec<Address> {
+        let balances = self.balances.read();
+        candidates
+            .iter()
+            .filter(|addr| balances.get(*addr).map_or(true, |b| *b >= cost))
+            .copied()
+            .collect()
+    }
+
+    /// Take `cost` off `address`'s cached balance until the next refresh
+    pub fn reserve(&self, address: Address, cost: U256) {
+        if let Some(balance) = self.balances.write().get_mut(&address) {
+            *balance = balance.saturating_sub(cost);
+        }
+    }
+
+    /// Pick a key from `candidates`, weighted by balance above the minimum.
+    ///
+    /// Keys with unknown balance are treated as funded until their first
+    /// refresh, weighted like an average known key. If every key is below
+    /// the minimum, the richest one is returned so the error handler still
+    /// sees the failure.
+    pub fn select(&self, candidates: &[Address]) -> Option<Address> {
+        let balances = self.balances.read();
+
+        // Weight above the minimum, `None` while the balance is unknown
+        let weights: Vec<(Address, Option<u128>)> = candidates
+            .iter()
+            .filter_map(|addr| match balances.get(addr) {
+                Some(balance) if *balance < self.min_balance => None,
+                Some(balance) => Some((*addr, Some((*balance - self.min_balance).saturating_to::<u128>().max(1)))),
+                None => Some((*addr, None)),
+            })
+            .collect();
+        let known_weights: Vec<u128> = weights.iter().filter_map(|(_, w)| *w).collect();
+        let unknown_weight = match known_weights.len() {
+            0 => 1,
+            n => (known_weights.iter().fold(0u128, |acc, w| acc.saturating_add(*w)) / n as u128).max(1),
+        };
+        let weighted: Vec<(Address, u128)> =
+            weights.into_iter().map(|(addr, w)| (addr, w.unwrap_or(unknown_weight))).collect();
+
+        if weighted.is_empty() {
+            return candidates
+                .iter()
+                .max_by_key(|addr| balances.get(*addr).copied().unwrap_or_default())
+                .copied();
+        }
+
+        let total: u128 = weighted.iter().map(|(_, w)| *w).fold(0u128, |acc, w| acc.saturating_add(w));
+        let mut pick = (self.next_random() as u128) % total.max(1);
+
+        for (addr, weight) in &weighted {
+            if pick < *weight {
+                return Some(*addr);
+            }
+            pick -= weight;
+        }
+
+        weighted.last().map(|(addr, _)| *addr)
+    }
+
+    /// Periodically refresh balances for `addresses` from the RPC.
+    pub fn spawn_balance_refresher(
+        self: Arc<Self>,
+        rpc_url: String,
+        addresses: Vec<Address>,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let rpc = RpcBatcher::shared(&[rpc_url]);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let (gas_price, balances) = tokio::join!(
+                    rpc.call("eth_gasPrice", json!([])),
+                    join_all(
+                        addresses
+                            .iter()
+                            .map(|address| rpc.call("eth_getBalance", json!([address, "latest"]))),
+                    )
+                );
+                match gas_price.map_err(|e| format!("{:?}", e)).and_then(|v| {
+                    let hex = v.as_str().ok_or_else(|| format!("unexpected gas price {}", v))?;
+                    U256::from_str(hex).map_err(|e| e.to_string())
+                }) {
+                    Ok(gas_price) => self.update_gas_price(gas_price),
+                    Err(e) => warn!("Failed to fetch gas price: {}", e),
+                }
+                for (address, balance) in addresses.iter().zip(balances) {
+                    let balance = balance.map_err(|e| format!("{:?}", e)).and_then(|v| {
+                        let hex = v.as_str().ok_or_else(|| format!("unexpected balance {}", v))?;
+                        U256::from_str(hex).map_err(|e| e.to_string())
+                    });
+                    match balance {
+                        Ok(balance) => {
+                            debug!("Balance {}: {} wei", address, balance);
+                            self.update_balance(*address, balance);
+                        }
+                        Err(e) => warn!("Failed to fetch balance for {}: {}", address, e),
+                    }
+                }
+            }
+        })
+    }
+
+    // xorshift64 - quality is plenty for spreading load across keys
+    fn next_random(&self) -> u64 {
+        let mut x = self.rng_state.load(Ordering::Relaxed);
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state.store(x, Ordering::Relaxed);
+        x
+    }
+
+    /// Keys from `candidates` that are at or above the minimum balance (or
+    /// whose balance is not known yet).
+    pub fn funded(&self, candidates: &[Address]) -> Vec<Address> {
+        let balances = self.balances.read();
+        candidates
+            .iter()
+            .filter(|addr| balances.get(*addr).map_or(true, |b| *b >= self.min_balance))
+            .copied()
+            .collect()
+    }
+}
+
+/// Key rotation policy, selected with `KEY_ROTATION`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationPolicy {
+    /// Strict round-robin over funded keys
+    RoundRobin,
+    /// Uniformly random funded key
+    Random,
+    /// Same key for a feed for as long as it stays funded and healthy
+    StickyPerFeed,
+    /// Key whose last error is oldest (never-errored keys first)
+    LeastRecentlyErrored,
+    /// Random, weighted by balance above the minimum
+    BalanceWeighted,
+}
+
+impl FromStr for RotationPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(Self::RoundRobin),
+            "random" => Ok(Self::Random),
+            "sticky-per-feed" => Ok(Self::StickyPerFeed),
+            "least-recently-errored" => Ok(Self::LeastRecentlyErrored),
+            "balance-weighted" => Ok(Self::BalanceWeighted),
+            other => Err(anyhow::anyhow!(
+                "Unknown KEY_ROTATION '{}' (expected round-robin, random, sticky-per-feed, least-recently-errored or balance-weighted)",
+                other
+            )),
+        }
+    }
+}
+
+/// Orchestrator key selector applying a [`RotationPolicy`] on top of the
+/// balance filter. Underfunded keys are skipped under every policy.
+pub struct KeyRotation {
+    policy: RotationPolicy,
+    balances: Arc<BalanceAwareSelector>,
+    /// Check each transaction's cost against cached balances before picking
+    presubmit_check: bool,
+    /// Transactions skipped because no key could pay for them
+    preempted: AtomicU64,
+    /// Whether the last checked transaction was skipped (warn once per run)
+    preempting: AtomicBool,
+    next_index: AtomicU64,
+    sticky: RwLock<HashMap<String, Address>>,
+    last_error: RwLock<HashMap<Address, Instant>>,
+    in_flight: RwLock<HashMap<Address, Instant>>,
+    /// Key selected in each worker task, released by its submission's result
+    selected: TaskSlots<Address>,
+}
+
+/// A key whose result never arrived is released after this long
+const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Gas limit assumed for requests that don't set one
+const DEFAULT_GAS_LIMIT: u64 = 300_000;
+
+impl KeyRotation {
+    pub fn new(policy: RotationPolicy, balances: Arc<BalanceAwareSelector>) -> Self {
+        Self {
+            policy,
+            balances,
+            presubmit_check: false,
+            preempted: AtomicU64::new(0),
+            preempting: AtomicBool::new(false),
+            next_index: AtomicU64::new(0),
+            sticky: RwLock::new(HashMap::new()),
+            last_error: RwLock::new(HashMap::new()),
+            in_flight: RwLock::new(HashMap::new()),
+            selected: TaskSlots::new(),
+        }
+    }
+
+    /// Keys from `candidates` without a transaction in flight
+    fn idle(&self, candidates: &[Address]) -> Vec<Address> {
+        let mut in_flight = self.in_flight.write();
+        in_flight.retain(|_, since| since.elapsed() < IN_FLIGHT_TIMEOUT);
+        candidates
+            .iter()
+            .filter(|addr| !in_flight.contains_key(*addr))
+            .copied()
+            .collect()
+    }
+
+    /// Skip keys (or, when none can pay, the transaction) whose cached
+    /// balance doesn't cover the transaction's cost
+    pub fn with_presubmit_check(mut self, enabled: bool) -> Self {
+        self.presubmit_check = enabled;
+        self
+    }
+
+    pub fn policy(&self) -> RotationPolicy {
+        self.policy
+    }
+
+    /// Transactions skipped by the pre-submit balance check
+    pub fn preempted(&self) -> u64 {
+        self.preempted.load(Ordering::Relaxed)
+    }
+
+    fn feed_key(request: Option<&SendingRequest>) -> String {
+        request.map_or_else(|| "*".to_string(), |request| request.feed.clone())
+    }
+
+    fn round_robin(&self, funded: &[Address]) -> Option<Address> {
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed) as usize;
+        funded.get(index % funded.len()).copied()
+    }
+}
+
+impl KeyRotation {
+    /// Pick a key for `tx_request`, pricing the pre-submit check and pinning
+    /// `sticky-per-feed` keys by the request
+    pub fn select_for(&self, tx_request: &TxRequest, candidates: &[Address]) -> Option<Address> {
+        self.pick(Some(&SendingRequest::of(tx_request)), candidates)
+    }
+
+    fn pick(&self, request: Option<&SendingRequest>, candidates: &[Address]) -> Option<Address> {
+        let idle = self.idle(candidates);
+        if idle.is_empty() {
+            debug!("All keys have a transaction in flight");
+            return None;
+        }
+
+        let cost = self
+            .presubmit_check
+            .then(|| match request {
+                Some(request) => self.balances.cost_of(request),
+                None => self.balances.default_cost(),
+            })
+            .flatten();
+        let idle = match cost {
+            Some(cost) => {
+                let affording = self.balances.affording(&idle, cost);
+                if affording.is_empty() {
+                    self.preempted.fetch_add(1, Ordering::Relaxed);
+                    if !self.preempting.swap(true, Ordering::Relaxed) {
+                        warn!(
+                            "💸 No key can pay for {} ({} wei at most), skipping updates before signing until one is funded",
+                            Self::feed_key(request),
+                            cost
+                        );
+                    }
+                    return None;
+                }
+                if self.preempting.swap(false, Ordering::Relaxed) {
+                    info!("💰 Keys can pay for updates again");
+                }
+                affording
+            }
+            None => idle,
+        };
+
+        let selected = self.select_idle(request, &idle)?;
+        if let Some(cost) = cost {
+            self.balances.reserve(selected, cost);
+        }
+        self.in_flight.write().insert(selected, Instant::now());
+        self.selected.put(selected);
+        Some(selected)
+    }
+
+    /// The transaction sent from `address` came back: release the key, and
+    /// remember a failure for `least-recently-errored` and `sticky-per-feed`
+    pub fn on_result(&self, address: Address, success: bool) {
+        self.in_flight.write().remove(&address);
+        if success {
+            return;
+        }
+        self.last_error.write().insert(address, Instant::now());
+        // Move sticky feeds off a key that just failed
+        self.sticky.write().retain(|_, addr| *addr != address);
+    }
+
+    /// Wrap `inner` so each submission's result reaches [`on_result`](Self::on_result)
+    pub fn track_results(self: &Arc<Self>, inner: Arc<dyn TxSubmitter>) -> Arc<dyn TxSubmitter> {
+        Arc::new(KeyResultSubmitter { inner, rotation: self.clone() })
+    }
+}
+
+impl KeySelector for KeyRotation {
+    fn select_key(&self, candidates: &[Address]) -> Option<Address> {
+        self.pick(SENDING.take().as_ref(), candidates)
+    }
+}
+
+/// Reports the result of every submission to the rotation that picked its key
+struct KeyResultSubmitter {
+    inner: Arc<dyn TxSubmitter>,
+    rotation: Arc<KeyRotation>,
+}
+
+#[async_trait]
+impl TxSubmitter for KeyResultSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let selected = self.rotation.selected.take();
+        let result = self.inner.submit(raw_tx).await;
+        match selected {
+            Some(address) => self.rotation.on_result(address, result.is_ok()),
+            None => debug!("No key selected in this task, its key is released after {:?}", IN_FLIGHT_TIMEOUT),
+        }
+        result
+    }
+}
+
+impl KeyRotation {
+    fn select_idle(&self, request: Option<&SendingRequest>, candidates: &[Address]) -> Option<Address> {
+        let funded = self.balances.funded(candidates);
+        if funded.is_empty() {
+            // Nothing funded - let the balance selector pick the least-bad key
+            return self.balances.select(candidates);
+        }
+
+        match self.policy {
+            RotationPolicy::RoundRobin => self.round_robin(&funded),
+            RotationPolicy::Random => {
+                let index = self.balances.next_random() as usize % funded.len();
+                funded.get(index).copied()
+            }
+            RotationPolicy::StickyPerFeed => {
+                let feed = Self::feed_key(request);
+                if let Some(addr) = self.sticky.read().get(&feed).copied() {
+                    if funded.contains(&addr) {
+                        return Some(addr);
+                    }
+                    if self.in_flight.read().contains_key(&addr) {
+                        // Pinned key is busy (pipelining) - borrow another without re-pinning
+                        return self.round_robin(&funded);
+                    }
+                }
+                let addr = self.round_robin(&funded)?;
+                debug!("📌 Pinning feed {} to key {}", feed, addr);
+                self.sticky.write().insert(feed, addr);
+                Some(addr)
+            }
+            RotationPolicy::LeastRecentlyErrored => {
+                let last_error = self.last_error.read();
+                funded
+                    .iter()
+                    .min_by_key(|addr| last_error.get(*addr).copied())
+                    .copied()
+            }
+            RotationPolicy::BalanceWeighted => self.balances.select(&funded),
+        }
+    }
+}
+
+/// Number of orchestrator workers from `PIPELINE_DEPTH` (default 1), capped
+/// at the number of keys. With more than one, the next tick's transaction is
+/// submitted from another key while the previous receipt is outstanding.
+pub fn pipeline_depth_from_env(num_keys: usize) -> anyhow::Result<usize> {
+    let depth: usize = std::env::var("PIPELINE_DEPTH")
+        .unwrap_or_else(|_| "1".to_string())
+        .parse()?;
+    let depth = depth.clamp(1, num_keys.max(1));
+    if depth > 1 {
+        info!("🚇 Pipelining up to {} transactions across keys", depth);
+    }
+    Ok(depth)
+}
+
+/// Derive the signer addresses for a set of private keys.
+pub fn key_addresses(private_keys: &[PrivateKey]) -> anyhow::Result<Vec<Address>> {
+    private_keys.iter().map(PrivateKey::address).collect()
+}
+
+/// Build the key rotation and its balance refresher from `KEY_ROTATION`,
+/// `MIN_KEY_BALANCE_WEI`, `BALANCE_REFRESH_SECS` and `PRESUBMIT_BALANCE_CHECK`.
+pub fn key_rotation_from_env(
+    rpc_url: String,
+    private_keys: &[PrivateKey],
+) -> anyhow::Result<(Arc<KeyRotation>, JoinHandle<()>)> {
+    let policy: RotationPolicy = std::env::var("KEY_ROTATION")
+        .unwrap_or_else(|_| "balance-weighted".to_string())
+        .parse()?;
+    let min_balance = U256::from_str(
+        &std::env::var("MIN_KEY_BALANCE_WEI").unwrap_or_else(|_| "1000000000000000".to_string()),
+    )?;
+    let refresh_secs: u64 = std::env::var("BALANCE_REFRESH_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()?;
+    if refresh_secs == 0 {
+        anyhow::bail!("BALANCE_REFRESH_SECS must be at least 1");
+    }
+    let presubmit_check: bool = std::env::var("PRESUBMIT_BALANCE_CHECK")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()?;
+
+    let selector = Arc::new(BalanceAwareSelector::new(min_balance));
+    let handle = selector.clone().spawn_balance_refresher(
+        rpc_url,
+        key_addresses(private_keys)?,
+        Duration::from_secs(refresh_secs),
+    );
+
+    info!(
+        "⚖️ Key rotation: {:?} (min balance: {} wei, refresh: {}s, pre-submit check: {})",
+        policy, min_balance, refresh_secs, presubmit_check
+    );
+    Ok((Arc::new(KeyRotation::new(policy, selector).with_presubmit_check(presubmit_check)), handle))
+}
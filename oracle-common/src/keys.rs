@@ -0,0 +1,124 @@
+//! Worker private keys, loaded from numbered variables such as `PRIVATE_KEY_1`.
+//!
+//! Keys are held in [`Zeroizing`] storage so they are wiped when dropped,
+//! and their `Debug` output and parse errors never show them.
+//!
+//! That protection ends at the SDK: its orchestrator takes keys as plain
+//! `String`s, so [`PrivateKeys::into_sdk`] copies each one out of zeroizing
+//! storage and the copies live, unwiped, for as long as the orchestrator
+//! keeps them (and may remain in freed memory after). Until the SDK accepts
+//! signers, keep that hand-off to orchestrator construction; everything
+//! else (dry runs, admin tools, address derivation) signs through
+//! [`PrivateKey::signer`] without leaving zeroizing storage.
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{anyhow, Result};
+use std::env;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+use tracing::debug;
+use zeroize::Zeroizing;
+
+/// Maximum key index probed when `NUM_KEYS` is not set
+const DEFAULT_MAX_KEYS: usize = 10;
+
+/// A private key, wiped from memory when dropped. `Debug` never shows it,
+/// and parse errors don't echo it.
+#[derive(Clone)]
+pub struct PrivateKey(Zeroizing<String>);
+
+impl PrivateKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(Zeroizing::new(key.into()))
+    }
+
+    /// Read `var`; `None` when unset
+    pub fn from_env(var: &str) -> Option<Self> {
+        env::var(var).ok().map(Self::new)
+    }
+
+    /// The key itself, for code that has to hand it on (keep the borrow short)
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    pub fn signer(&self) -> Result<PrivateKeySigner> {
+        PrivateKeySigner::from_str(self.expose_secret()).map_err(|_| anyhow!("Invalid private key"))
+    }
+
+    pub fn address(&self) -> Result<Address> {
+        Ok(self.signer()?.address())
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateKey(<redacted>)")
+    }
+}
+
+/// Worker keys, as loaded by [`load_private_keys`]
+#[derive(Clone, Debug)]
+pub struct PrivateKeys(Vec<PrivateKey>);
+
+impl PrivateKeys {
+    pub fn new(keys: Vec<PrivateKey>) -> Self {
+        Self(keys)
+    }
+
+    /// Keep the first `len` keys
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// The keys as plain strings for the SDK orchestrator, which builds its
+    /// signers from them. This is the one place keys leave zeroizing storage
+    /// and the returned strings are not wiped (see the module docs); call it
+    /// at orchestrator construction and nowhere else.
+    pub fn into_sdk(self) -> Vec<String> {
+        self.0.iter().map(|key| key.expose_secret().to_string()).collect()
+    }
+}
+
+impl Deref for PrivateKeys {
+    type Target = [PrivateKey];
+
+    fn deref(&self) -> &[PrivateKey] {
+        &self.0
+    }
+}
+
+/// Load worker private keys from `<PREFIX>0`, `<PREFIX>1`, ...
+///
+/// Prefixes are tried in order and the first one that yields any keys wins,
+/// e.g. `&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"]`. The number of indices
+/// probed comes from `NUM_KEYS`; gaps are skipped. The owner `PRIVATE_KEY` is
+/// never loaded here - it is for contract ownership, not oracle updates.
+pub fn load_private_keys(prefixes: &[&str]) -> Result<PrivateKeys> {
+    let num_keys = env::var("NUM_KEYS")
+        .ok()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_KEYS);
+
+    for prefix in prefixes {
+        let keys: Vec<PrivateKey> = (0..num_keys)
+            .filter_map(|i| PrivateKey::from_env(&format!("{}{}", prefix, i)))
+            .collect();
+
+        if !keys.is_empty() {
+            debug!("Loaded {} keys with prefix {}", keys.len(), prefix);
+            return Ok(PrivateKeys::new(keys));
+        }
+    }
+
+    anyhow::bail!(
+        "No worker keys found. Set {}",
+        prefixes
+            .iter()
+            .map(|p| format!("{}0, {}1, ...", p, p))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    )
+}
@@ -0,0 +1,149 @@
+//! Shared building blocks for the nonzu oracle deployments.
+//!
+//! Both `time-oracle` and `binance-oracle` use these for key loading, key
+//! rotation, error-handler defaults, stats, shutdown handling, calldata
+//! encoding, chain configuration, transaction submission and the HTTP status
+//! server.
+
+pub mod access_list;
+pub mod admin;
+pub mod audit;
+pub mod backpressure;
+pub mod blend;
+pub mod bootstrap;
+pub mod candles;
+pub mod chain;
+pub mod change_limit;
+pub mod chaos;
+pub mod circuit_breaker;
+pub mod clock;
+pub mod combinators;
+pub mod condition;
+pub mod cost;
+pub mod data_age;
+pub mod dead_letter;
+pub mod derived;
+pub mod dry_run;
+pub mod encoding;
+pub mod error_config;
+pub mod error_metrics;
+pub mod error_parsers;
+pub mod error_policy;
+pub mod event_sink;
+pub mod events;
+pub mod feed_pause;
+pub mod feed_trigger;
+pub mod feeds;
+pub mod force_update;
+pub mod gas_fees;
+pub mod grpc;
+pub mod halt;
+pub mod heartbeat;
+pub mod history;
+pub mod history_export;
+pub mod http_client;
+pub mod key_rotation;
+pub mod keys;
+pub mod parse_quarantine;
+pub mod preflight;
+pub mod price_stream;
+pub mod pyth;
+pub mod rate_limit;
+pub mod receipt_validation;
+pub mod redis_sink;
+pub mod reference_check;
+pub mod receipt_verifier;
+pub mod reorg;
+pub mod rounds;
+pub mod rpc_batch;
+pub mod requests;
+pub mod rest;
+pub mod runtime;
+pub mod runtime_metrics;
+pub mod revert;
+pub mod scheduler;
+pub mod shadow;
+pub mod shutdown;
+pub mod smoothing;
+pub mod stats;
+pub mod status_server;
+pub mod submit;
+pub mod task_slots;
+pub mod telemetry;
+pub mod timer;
+pub mod uniswap;
+pub mod volatility;
+pub mod warmup;
+pub mod ws_bandwidth;
+pub mod ws_proxy;
+
+pub use access_list::*;
+pub use audit::*;
+pub use backpressure::*;
+pub use blend::*;
+pub use bootstrap::*;
+pub use candles::*;
+pub use chain::*;
+pub use change_limit::*;
+pub use chaos::*;
+pub use circuit_breaker::*;
+pub use clock::*;
+pub use combinators::*;
+pub use condition::*;
+pub use cost::*;
+pub use data_age::*;
+pub use dead_letter::*;
+pub use derived::*;
+pub use dry_run::*;
+pub use encoding::*;
+pub use error_config::*;
+pub use error_metrics::*;
+pub use error_parsers::*;
+pub use error_policy::*;
+pub use event_sink::*;
+pub use events::*;
+pub use feed_pause::*;
+pub use feed_trigger::*;
+pub use feeds::*;
+pub use force_update::*;
+pub use gas_fees::*;
+pub use grpc::*;
+pub use halt::*;
+pub use heartbeat::*;
+pub use history::*;
+pub use history_export::*;
+pub use http_client::*;
+pub use key_rotation::*;
+pub use keys::*;
+pub use parse_quarantine::*;
+pub use preflight::*;
+pub use price_stream::*;
+pub use pyth::*;
+pub use rate_limit::*;
+pub use receipt_validation::*;
+pub use redis_sink::*;
+pub use reference_check::*;
+pub use receipt_verifier::*;
+pub use reorg::*;
+pub use rounds::*;
+pub use rpc_batch::*;
+pub use requests::*;
+pub use rest::*;
+pub use runtime::*;
+pub use runtime_metrics::*;
+pub use revert::*;
+pub use scheduler::*;
+pub use shadow::*;
+pub use shutdown::*;
+pub use smoothing::*;
+pub use stats::*;
+pub use status_server::*;
+pub use submit::*;
+pub use task_slots::*;
+pub use telemetry::*;
+pub use timer::*;
+pub use uniswap::*;
+pub use volatility::*;
+pub use warmup::*;
+pub use ws_bandwidth::*;
+pub use ws_proxy::*;
@@ -0,0 +1,216 @@
+//! Quarantine for stream messages that fail to parse.
+//!
+//! When an exchange changes its message format every message fails at once,
+//! and logging each payload at error level buries everything else. Instead
+//! each source counts its rejects, keeps the last few payloads for
+//! inspection and logs one sample per `PARSE_ERROR_LOG_SECS`, with the
+//! number suppressed since the previous one. Well-formed messages of event
+//! types a client doesn't handle are counted by type rather than rejected.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `PARSE_ERROR_LOG_SECS` | `60` | At most one rejected payload logged per source this often |
+//! | `PARSE_SUMMARY_SECS` | `300` | Log new rejects and unknown event types per source this often (0 = off) |
+//!
+//! Counts, quarantined payloads and unknown event types are served at
+//! `/parse-errors` by the status server.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Quarantined payloads kept per source
+const MAX_QUARANTINED: usize = 20;
+
+/// Characters of a payload kept in quarantine
+const MAX_PAYLOAD_CHARS: usize = 1024;
+
+const DEFAULT_LOG_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_SUMMARY_SECS: u64 = 300;
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedMessage {
+    pub at_ms: u64,
+    pub error: String,
+    pub payload: String,
+}
+
+#[derive(Default)]
+struct QuarantineState {
+    rejected: u64,
+    /// Rejects not logged since the last sample was
+    suppressed: u64,
+    last_logged: Option<Instant>,
+    quarantined: VecDeque<QuarantinedMessage>,
+    unknown_events: BTreeMap<String, u64>,
+    /// `rejected` and `unknown_events` at the last summary
+    summarized: (u64, BTreeMap<String, u64>),
+}
+
+/// Parse failures and unknown event types of one source
+pub struct ParseQuarantine {
+    source: String,
+    log_interval: Duration,
+    state: Mutex<QuarantineState>,
+}
+
+impl ParseQuarantine {
+    pub fn new(source: impl Into<String>, log_interval: Duration) -> Self {
+        Self { source: source.into(), log_interval, state: Mutex::new(QuarantineState::default()) }
+    }
+
+    /// `payload` failed to parse; returns whether it was logged
+    pub fn reject(&self, error: impl Display, payload: &str) -> bool {
+        self.reject_at(error, payload, Instant::now())
+    }
+
+    pub fn reject_at(&self, error: impl Display, payload: &str, now: Instant) -> bool {
+        let error = format!("{:#}", error);
+        let mut state = self.state.lock();
+        state.rejected += 1;
+        if state.quarantined.len() == MAX_QUARANTINED {
+            state.quarantined.pop_front();
+        }
+        state.quarantined.push_back(QuarantinedMessage {
+            at_ms: unix_ms(),
+            error: error.clone(),
+            payload: payload.chars().take(MAX_PAYLOAD_CHARS).collect(),
+        });
+
+        let due = !matches!(state.last_logged, Some(last) if now.duration_since(last) < self.log_interval);
+        if !due {
+            state.suppressed += 1;
+            debug!("{}: quarantined unparseable message: {}", self.source, error);
+            return false;
+        }
+        warn!(
+            "{}: quarantined unparseable message ({} more since the last sample, {} total): {} - {:.200}",
+            self.source, state.suppressed, state.rejected, error, payload
+        );
+        state.suppressed = 0;
+        state.last_logged = Some(now);
+        true
+    }
+
+    /// A well-formed message of an event type the client doesn't handle
+    pub fn unknown_event(&self, event_type: &str) {
+        let mut state = self.state.lock();
+        let count = state.unknown_events.entry(event_type.to_string()).or_default();
+        *count += 1;
+        if *count == 1 {
+            debug!("{}: ignoring unknown event type '{}'", self.source, event_type);
+        }
+    }
+
+    pub fn rejected(&self) -> u64 {
+        self.state.lock().rejected
+    }
+
+    pub fn unknown_events(&self) -> BTreeMap<String, u64> {
+        self.state.lock().unknown_events.clone()
+    }
+
+    pub fn quarantined(&self) -> Vec<QuarantinedMessage> {
+        self.state.lock().quarantined.iter().cloned().collect()
+    }
+
+    /// What changed since the previous call, e.g. `12 rejected, unknown
+    /// events: aggTrade=40`; `None` when nothing did
+    pub fn summary(&self) -> Option<String> {
+        let mut state = self.state.lock();
+        let (last_rejected, last_unknown) = &state.summarized;
+        let rejected = state.rejected - last_rejected;
+        let unknown: Vec<String> = state
+            .unknown_events
+            .iter()
+            .map(|(event, count)| (event, count - last_unknown.get(event).copied().unwrap_or(0)))
+            .filter(|(_, new)| *new > 0)
+            .map(|(event, new)| format!("{}={}", event, new))
+            .collect();
+        state.summarized = (state.rejected, state.unknown_events.clone());
+        if rejected == 0 && unknown.is_empty() {
+            return None;
+        }
+        let mut summary = format!("{} rejected", rejected);
+        if !unknown.is_empty() {
+            summary.push_str(&format!(", unknown events: {}", unknown.join(", ")));
+        }
+        Some(summary)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.lock();
+        json!({
+            "rejected": state.rejected,
+            "unknown_events": state.unknown_events,
+            "quarantined": state.quarantined.iter().map(|message| json!({
+                "at_ms": message.at_ms,
+                "error": message.error,
+                "payload": message.payload,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn log_interval_from_env() -> Duration {
+    std::env::var("PARSE_ERROR_LOG_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map_or(DEFAULT_LOG_INTERVAL, Duration::from_secs)
+}
+
+fn registry() -> &'static Mutex<BTreeMap<String, Arc<ParseQuarantine>>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<String, Arc<ParseQuarantine>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Process-wide quarantine of `source` ("binance", "tradermade", "pyth", ...)
+pub fn parse_quarantine(source: &str) -> Arc<ParseQuarantine> {
+    registry()
+        .lock()
+        .entry(source.to_string())
+        .or_insert_with(|| Arc::new(ParseQuarantine::new(source, log_interval_from_env())))
+        .clone()
+}
+
+/// Every source's quarantine, for `/parse-errors`
+pub fn parse_quarantine_json() -> Value {
+    let sources: serde_json::Map<String, Value> =
+        registry().lock().iter().map(|(source, quarantine)| (source.clone(), quarantine.to_json())).collect();
+    Value::Object(sources)
+}
+
+/// Log each source's [`summary`](ParseQuarantine::summary) every
+/// `PARSE_SUMMARY_SECS`
+pub fn spawn_parse_summary_from_env() -> Result<Option<JoinHandle<()>>> {
+    let secs: u64 = match std::env::var("PARSE_SUMMARY_SECS") {
+        Ok(secs) => secs.parse().context("Invalid PARSE_SUMMARY_SECS")?,
+        Err(_) => DEFAULT_SUMMARY_SECS,
+    };
+    if secs == 0 {
+        return Ok(None);
+    }
+    Ok(Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(secs));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let quarantines: Vec<_> = registry().lock().values().cloned().collect();
+            for quarantine in quarantines {
+                if let Some(summary) = quarantine.summary() {
+                    info!("🧹 {} messages in the last {}s: {}", quarantine.source, secs, summary);
+                }
+            }
+        }
+    })))
+}
@@ -0,0 +1,408 @@
+//! Startup preflight checks.
+//!
+//! Misconfigurations (wrong chain, an undeployed or different contract,
+//! unfunded or unauthorized keys, a skewed clock) otherwise only show up as
+//! reverts once the orchestrator is running. [`Preflight`] checks them all
+//! up front and returns a [`PreflightReport`] that prints as a pass/fail
+//! table:
+//!
+//! - every RPC endpoint answers and reports the configured chain id
+//! - each target contract has code, containing the selectors we'll call
+//! - each worker key is an authorized updater and holds `MIN_KEY_BALANCE_WEI`
+//! - the system clock is within `PREFLIGHT_MAX_CLOCK_SKEW_MS` of NTP
+//!   (`NTP_SERVERS`) and the chain head is recent
+//!
+//! Checks that can't be completed (an NTP server that doesn't answer) are
+//! warnings; anything that would make updates revert is a failure.
+
+use alloy::primitives::{Address, U256};
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::chain::ChainConfig;
+use crate::clock::query_sntp;
+use crate::encoding::function_selector;
+use crate::submit::{RpcCallError, RpcEndpoints};
+
+/// Code shorter than this is assumed to be a proxy, whose selectors live
+/// in the implementation
+const PROXY_CODE_LEN: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn icon(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️",
+            CheckStatus::Fail => "❌",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status, detail: detail.into() }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl PreflightReport {
+    /// No check failed (warnings are allowed)
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Fail)
+    }
+
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|check| check.status == status).count()
+    }
+
+    /// Pass/fail table, one row per check
+    pub fn table(&self) -> String {
+        let width = self.checks.iter().map(|check| check.name.chars().count()).max().unwrap_or(0);
+        let mut table = String::new();
+        for check in &self.checks {
+            let _ = writeln!(table, "{}  {:<width$}  {}", check.status.icon(), check.name, check.detail);
+        }
+        let _ = writeln!(
+            table,
+            "\n{} passed, {} warnings, {} failed",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        );
+        table
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "passed": self.passed(),
+            "checks": self.checks.iter().map(|check| json!({
+                "name": check.name,
+                "status": format!("{:?}", check.status).to_lowercase(),
+                "detail": check.detail,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// A contract the oracle will call, and the functions it will call on it
+#[derive(Debug, Clone)]
+pub struct PreflightTarget {
+    label: String,
+    contract: Address,
+    functions: Vec<(String, [u8; 4])>,
+    check_authorization: bool,
+}
+
+impl PreflightTarget {
+    /// Target whose worker keys must be `authorizedUpdaters`
+    pub fn new(label: impl Into<String>, contract: Address) -> Self {
+        Self { label: label.into(), contract, functions: Vec::new(), check_authorization: true }
+    }
+
+    /// A function the oracle calls, e.g. `updatePrice(string,uint256)`
+    pub fn function(mut self, signature: &str) -> Self {
+        self.functions.push((signature.to_string(), function_selector(signature)));
+        self
+    }
+
+    /// A function known only by its selector (e.g. from prepared calldata)
+    pub fn selector(mut self, name: impl Into<String>, selector: [u8; 4]) -> Self {
+        self.functions.push((name.into(), selector));
+        self
+    }
+
+    /// Third-party contract without an updater allow-list
+    pub fn without_authorization(mut self) -> Self {
+        self.check_authorization = false;
+        self
+    }
+}
+
+pub struct Preflight {
+    chain: ChainConfig,
+    targets: Vec<PreflightTarget>,
+    keys: Vec<Address>,
+    min_balance: U256,
+    ntp_servers: Vec<String>,
+    max_clock_skew: Duration,
+    max_head_age: Duration,
+}
+
+impl Preflight {
+    pub fn new(chain: ChainConfig) -> Self {
+        Self {
+            chain,
+            targets: Vec::new(),
+            keys: Vec::new(),
+            min_balance: U256::ZERO,
+            ntp_servers: Vec::new(),
+            max_clock_skew: Duration::from_secs(1),
+            max_head_age: Duration::from_secs(60),
+        }
+    }
+
+    /// Preflight with `MIN_KEY_BALANCE_WEI`, `NTP_SERVERS` and
+    /// `PREFLIGHT_MAX_CLOCK_SKEW_MS` (default 1000) from the environment
+    pub fn from_env(chain: ChainConfig) -> anyhow::Result<Self> {
+        let min_balance = U256::from_str(
+            &std::env::var("MIN_KEY_BALANCE_WEI").unwrap_or_else(|_| "1000000000000000".to_string()),
+        )?;
+        let ntp_servers = std::env::var("NTP_SERVERS")
+            .unwrap_or_else(|_| "time.cloudflare.com,pool.ntp.org".to_string())
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let max_skew_ms: u64 = std::env::var("PREFLIGHT_MAX_CLOCK_SKEW_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid PREFLIGHT_MAX_CLOCK_SKEW_MS"))?;
+        Ok(Self::new(chain)
+            .min_balance(min_balance)
+            .ntp_servers(ntp_servers)
+            .max_clock_skew(Duration::from_millis(max_skew_ms)))
+    }
+
+    pub fn target(mut self, target: PreflightTarget) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    pub fn keys(mut self, keys: Vec<Address>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    pub fn min_balance(mut self, min_balance: U256) -> Self {
+        self.min_balance = min_balance;
+        self
+    }
+
+    /// NTP servers for the clock check (none skips it)
+    pub fn ntp_servers(mut self, servers: Vec<String>) -> Self {
+        self.ntp_servers = servers;
+        self
+    }
+
+    pub fn max_clock_skew(mut self, skew: Duration) -> Self {
+        self.max_clock_skew = skew;
+        self
+    }
+
+    /// Run every check; never fails itself, problems end up in the report
+    pub async fn run(&self) -> PreflightReport {
+        let mut report = PreflightReport::default();
+        self.check_endpoints(&mut report).await;
+
+        let rpc = RpcEndpoints::new(self.chain.rpc_urls.clone());
+        for target in &self.targets {
+            self.check_target(&rpc, target, &mut report).await;
+        }
+        self.check_balances(&rpc, &mut report).await;
+        self.check_clock(&rpc, &mut report).await;
+        report
+    }
+
+    async fn check_endpoints(&self, report: &mut PreflightReport) {
+        for url in &self.chain.rpc_urls {
+            let name = format!("RPC {}", url);
+            let rpc = RpcEndpoints::new(vec![url.clone()]);
+            let started = Instant::now();
+            let chain_id = match rpc.call("eth_chainId", json!([])).await {
+                Ok(id) => id.as_str().and_then(|id| u64::from_str_radix(id.trim_start_matches("0x"), 16).ok()),
+                Err(e) => {
+                    report.checks.push(CheckResult::new(name, CheckStatus::Fail, describe(&e)));
+                    continue;
+                }
+            };
+            let latency = started.elapsed();
+            let check = match (chain_id, self.chain.chain_id) {
+                (None, _) => CheckResult::new(name, CheckStatus::Fail, "invalid eth_chainId response"),
+                (Some(actual), Some(expected)) if actual != expected => CheckResult::new(
+                    name,
+                    CheckStatus::Fail,
+                    format!("chain id {} but {} expects {}", actual, self.chain.name, expected),
+                ),
+                (Some(actual), Some(_)) => {
+                    CheckResult::new(name, CheckStatus::Pass, format!("chain id {} in {:?}", actual, latency))
+                }
+                (Some(actual), None) => CheckResult::new(
+                    name,
+                    CheckStatus::Warn,
+                    format!("chain id {} in {:?}, no CHAIN_ID configured to compare", actual, latency),
+                ),
+            };
+            report.checks.push(check);
+        }
+    }
+
+    async fn check_target(&self, rpc: &RpcEndpoints, target: &PreflightTarget, report: &mut PreflightReport) {
+        let code = match rpc.call("eth_getCode", json!([target.contract, "latest"])).await {
+            Ok(code) => code.as_str().and_then(|c| alloy::hex::decode(c).ok()).unwrap_or_default(),
+            Err(e) => {
+                report.checks.push(CheckResult::new(format!("{} code", target.label), CheckStatus::Fail, describe(&e)));
+                return;
+            }
+        };
+        if code.is_empty() {
+            report.checks.push(CheckResult::new(
+                format!("{} code", target.label),
+                CheckStatus::Fail,
+                format!("no contract at {} on {}", target.contract, self.chain.name),
+            ));
+            return;
+        }
+        report.checks.push(CheckResult::new(
+            format!("{} code", target.label),
+            CheckStatus::Pass,
+            format!("{} bytes at {}", code.len(), target.contract),
+        ));
+
+        for (name, selector) in &target.functions {
+            let check_name = format!("{} {}", target.label, name);
+            let check = if has_selector(&code, *selector) {
+                CheckResult::new(check_name, CheckStatus::Pass, format!("0x{}", alloy::hex::encode(selector)))
+            } else if code.len() < PROXY_CODE_LEN {
+                CheckResult::new(check_name, CheckStatus::Warn, "contract looks like a proxy, selector not checked")
+            } else {
+                CheckResult::new(
+                    check_name,
+                    CheckStatus::Fail,
+                    format!("selector 0x{} not in the contract's code", alloy::hex::encode(selector)),
+                )
+            };
+            report.checks.push(check);
+        }
+
+        if !target.check_authorization {
+            return;
+        }
+        let authorized_selector = function_selector("authorizedUpdaters(address)");
+        for key in &self.keys {
+            let mut call_data = authorized_selector.to_vec();
+            call_data.extend_from_slice(&[0u8; 12]);
+            call_data.extend_from_slice(key.as_slice());
+            let call = json!([{ "to": target.contract, "data": alloy::hex::encode_prefixed(&call_data) }, "latest"]);
+            let check_name = format!("{} authorizes {}", target.label, key);
+            let check = match rpc.call("eth_call", call).await {
+                Ok(output) => {
+                    let output = output.as_str().and_then(|o| alloy::hex::decode(o).ok()).unwrap_or_default();
+                    match output.get(..32).map(U256::from_be_slice) {
+                        Some(flag) if flag == U256::from(1) => CheckResult::new(check_name, CheckStatus::Pass, "authorized"),
+                        Some(_) => CheckResult::new(check_name, CheckStatus::Fail, "not an authorized updater (run `nonzu authorize`)"),
+                        None => CheckResult::new(check_name, CheckStatus::Fail, "authorizedUpdaters returned nothing"),
+                    }
+                }
+                Err(e) => CheckResult::new(check_name, CheckStatus::Fail, describe(&e)),
+            };
+            report.checks.push(check);
+        }
+    }
+
+    async fn check_balances(&self, rpc: &RpcEndpoints, report: &mut PreflightReport) {
+        for key in &self.keys {
+            let name = format!("Balance {}", key);
+            let balance = match rpc.call("eth_getBalance", json!([key, "latest"])).await {
+                Ok(balance) => balance.as_str().and_then(|b| U256::from_str(b).ok()),
+                Err(e) => {
+                    report.checks.push(CheckResult::new(name, CheckStatus::Fail, describe(&e)));
+                    continue;
+                }
+            };
+            let check = match balance {
+                Some(balance) if balance >= self.min_balance => {
+                    CheckResult::new(name, CheckStatus::Pass, format!("{} wei", balance))
+                }
+                Some(balance) => CheckResult::new(
+                    name,
+                    CheckStatus::Fail,
+                    format!("{} wei, below {} (run `nonzu fund`)", balance, self.min_balance),
+                ),
+                None => CheckResult::new(name, CheckStatus::Fail, "invalid eth_getBalance response"),
+            };
+            report.checks.push(check);
+        }
+    }
+
+    async fn check_clock(&self, rpc: &RpcEndpoints, report: &mut PreflightReport) {
+        if !self.ntp_servers.is_empty() {
+            let mut last_error = String::new();
+            let mut offset = None;
+            for server in &self.ntp_servers {
+                match query_sntp(server, Duration::from_secs(2)).await {
+                    Ok(sample) => {
+                        offset = Some((server, sample.offset_ms));
+                        break;
+                    }
+                    Err(e) => last_error = format!("{}: {}", server, e),
+                }
+            }
+            let max_ms = self.max_clock_skew.as_millis() as f64;
+            report.checks.push(match offset {
+                Some((server, offset)) if offset.abs() <= max_ms => {
+                    CheckResult::new("Clock", CheckStatus::Pass, format!("{:+.1}ms from {}", offset, server))
+                }
+                Some((server, offset)) => CheckResult::new(
+                    "Clock",
+                    CheckStatus::Fail,
+                    format!("{:+.1}ms from {}, more than {}ms (fix NTP or set TIME_SOURCE=ntp)", offset, server, max_ms),
+                ),
+                None => CheckResult::new("Clock", CheckStatus::Warn, format!("no NTP server answered ({})", last_error)),
+            });
+        }
+
+        let head = match rpc.call("eth_getBlockByNumber", json!(["latest", false])).await {
+            Ok(block) => block["timestamp"].as_str().and_then(|t| u64::from_str_radix(t.trim_start_matches("0x"), 16).ok()),
+            Err(e) => {
+                report.checks.push(CheckResult::new("Chain head", CheckStatus::Fail, describe(&e)));
+                return;
+            }
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        report.checks.push(match head {
+            Some(timestamp) if now.abs_diff(timestamp) <= self.max_head_age.as_secs() => CheckResult::new(
+                "Chain head",
+                CheckStatus::Pass,
+                format!("latest block {}s old", now as i64 - timestamp as i64),
+            ),
+            Some(timestamp) => CheckResult::new(
+                "Chain head",
+                CheckStatus::Warn,
+                format!("latest block {}s from local time - chain stalled or clock off", now as i64 - timestamp as i64),
+            ),
+            None => CheckResult::new("Chain head", CheckStatus::Fail, "invalid eth_getBlockByNumber response"),
+        });
+    }
+}
+
+/// Whether `code` contains `PUSH4 selector`, as solc's dispatcher does
+pub fn has_selector(code: &[u8], selector: [u8; 4]) -> bool {
+    code.windows(5).any(|window| window[0] == 0x63 && window[1..] == selector)
+}
+
+fn describe(error: &RpcCallError) -> String {
+    match error {
+        RpcCallError::Transport(message) => message.clone(),
+        RpcCallError::Node(error) => format!("node error: {}", error),
+    }
+}
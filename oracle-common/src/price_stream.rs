@@ -0,0 +1,203 @@
+//! WebSocket stream of computed and published prices.
+//!
+//! With `PRICE_STREAM_ADDR` set (e.g. `0.0.0.0:8090`) a WebSocket server,
+//! separate from the status server so consumers get no admin endpoints,
+//! forwards from the [`EventBus`](crate::events::EventBus) every value a
+//! feed computes (TWAPs included) and every update confirmed on chain, one
+//! JSON text frame each:
+//!
+//! ```text
+//! {"type":"computed","feed":"BTCUSD","value":97012.5,"timestamp_ms":1735689600037}
+//! {"type":"published","feed":"BTCUSD","value":97012.5,"tx_hash":"0x..","block_number":1234,"latency_ms":4.2,"timestamp_ms":1735689600051}
+//! ```
+//!
+//! A connection receives every feed until it narrows the set, either at
+//! connect time with `?feeds=BTCUSD,ETHUSD` or later by sending
+//! `{"subscribe":["BTCUSD"]}` / `{"unsubscribe":["BTCUSD"]}`; each change is
+//! acknowledged with `{"type":"subscribed","feeds":[...]}` (empty = all).
+//! Consumers more than `PRICE_STREAM_BUFFER` messages behind skip ahead and
+//! get `{"type":"lagged","missed":n}`.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `PRICE_STREAM_ADDR` | unset | Bind address (unset disables) |
+//! | `PRICE_STREAM_BUFFER` | `1024` | Messages buffered per connection |
+
+use anyhow::{Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::events::{EventSubscriber, OracleEvent};
+
+/// An event rendered once for every connection
+#[derive(Debug, Clone)]
+struct StreamMessage {
+    feed: String,
+    json: String,
+}
+
+/// Filter changes a consumer can send
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Request {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+pub struct PriceStream {
+    sender: broadcast::Sender<Arc<StreamMessage>>,
+    connections: AtomicU64,
+}
+
+impl PriceStream {
+    /// Stream buffering up to `buffer` messages per connection
+    pub fn new(buffer: usize) -> Arc<Self> {
+        let (sender, _) = broadcast::channel(buffer.max(1));
+        Arc::new(Self { sender, connections: AtomicU64::new(0) })
+    }
+
+    /// From `PRICE_STREAM_ADDR` / `PRICE_STREAM_BUFFER`; `None` when unset.
+    /// Subscribe the stream to the event bus to feed it.
+    pub fn from_env() -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let addr: SocketAddr = match std::env::var("PRICE_STREAM_ADDR") {
+            Ok(v) => v.parse().context("Invalid PRICE_STREAM_ADDR")?,
+            Err(_) => return Ok(None),
+        };
+        let buffer: usize = match std::env::var("PRICE_STREAM_BUFFER") {
+            Ok(v) => v.parse().context("Invalid PRICE_STREAM_BUFFER")?,
+            Err(_) => 1024,
+        };
+        let stream = Self::new(buffer);
+        let handle = stream.clone().spawn(addr);
+        Ok(Some((stream, handle)))
+    }
+
+    /// Open WebSocket connections
+    pub fn connections(&self) -> u64 {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// WebSocket endpoint at `/`, for serving on a listener of your own
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new().route("/", get(upgrade)).with_state(self)
+    }
+
+    pub fn spawn(self: Arc<Self>, addr: SocketAddr) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let listener = match tokio::net::TcpListener::bind(addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to bind price stream on {}: {}", addr, e);
+                    return;
+                }
+            };
+
+            info!("📡 Price stream listening on ws://{}", addr);
+            if let Err(e) = axum::serve(listener, self.router()).await {
+                error!("Price stream error: {}", e);
+            }
+        })
+    }
+
+    async fn serve(self: Arc<Self>, mut socket: WebSocket, mut feeds: BTreeSet<String>) {
+        let mut updates = self.sender.subscribe();
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        debug!("Price stream consumer connected (feeds: {:?})", feeds);
+
+        loop {
+            let outgoing = tokio::select! {
+                update = updates.recv() => match update {
+                    Ok(message) if feeds.is_empty() || feeds.contains(&message.feed) => message.json.clone(),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => json!({ "type": "lagged", "missed": missed }).to_string(),
+                    Err(RecvError::Closed) => break,
+                },
+                incoming = socket.recv() => match incoming {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<Request>(&text) {
+                        Ok(request) => {
+                            match request {
+                                Request::Subscribe(add) => feeds.extend(add),
+                                Request::Unsubscribe(remove) => {
+                                    for feed in &remove {
+                                        feeds.remove(feed);
+                                    }
+                                }
+                            }
+                            json!({ "type": "subscribed", "feeds": feeds }).to_string()
+                        }
+                        Err(e) => json!({ "type": "error", "message": e.to_string() }).to_string(),
+                    },
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                },
+            };
+            if socket.send(Message::Text(outgoing)).await.is_err() {
+                break;
+            }
+        }
+
+        self.connections.fetch_sub(1, Ordering::Relaxed);
+        debug!("Price stream consumer disconnected");
+    }
+}
+
+async fn upgrade(
+    State(stream): State<Arc<PriceStream>>,
+    Query(params): Query<HashMap<String, String>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let feeds = params
+        .get("feeds")
+        .map(|feeds| feeds.split(',').map(str::trim).filter(|f| !f.is_empty()).map(String::from).collect())
+        .unwrap_or_default();
+    ws.on_upgrade(move |socket| stream.serve(socket, feeds))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// `(feed, message)` for computed values and confirmed updates in the
+/// stream's JSON format, `None` for other events
+pub fn price_message(event: &OracleEvent) -> Option<(&str, serde_json::Value)> {
+    match event {
+        OracleEvent::ValueComputed { feed, value } => Some((
+            feed,
+            json!({ "type": "computed", "feed": feed, "value": value, "timestamp_ms": now_ms() }),
+        )),
+        OracleEvent::UpdatePublished { feed, value, tx_hash, block_number, latency, .. } => Some((
+            feed,
+            json!({
+                "type": "published",
+                "feed": feed,
+                "value": value,
+                "tx_hash": tx_hash,
+                "block_number": block_number.map(|b| b.saturating_to::<u64>()),
+                "latency_ms": latency.map(|l| l.as_secs_f64() * 1000.0),
+                "timestamp_ms": now_ms(),
+            }),
+        )),
+        _ => None,
+    }
+}
+
+impl EventSubscriber for PriceStream {
+    fn on_event(&self, event: &OracleEvent) {
+        let Some((feed, json)) = price_message(event) else { return };
+        // Fails only when nobody is connected
+        let _ = self.sender.send(Arc::new(StreamMessage { feed: feed.to_string(), json: json.to_string() }));
+    }
+}
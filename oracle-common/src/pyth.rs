@@ -0,0 +1,267 @@
+//! Pyth Hermes price stream.
+//!
+//! Hermes serves server-sent events at `/v2/updates/price/stream`; each
+//! `data:` line carries `{"parsed":[{"id":"..","price":{"price":"..","conf":"..","expo":-8,"publish_time":..}}]}`
+//! for the subscribed price ids. Price and confidence are integers scaled by
+//! `10^expo`. Feeds with `source = "pyth"` use the price id as their symbol.
+
+use anyhow::{anyhow, bail, Context, Result};
+use futures_util::StreamExt;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+use tracing::{debug, error, info, warn};
+
+use crate::chaos::maybe_drop_stream;
+use crate::events::{publish_event, OracleEvent};
+use crate::feed_trigger::{PricePoint, PriceSource};
+use crate::feeds::{FeedConfig, SourceKind};
+use crate::parse_quarantine::parse_quarantine;
+use crate::shutdown::ShutdownCoordinator;
+
+const DEFAULT_HERMES_URL: &str = "https://hermes.pyth.network";
+
+/// Hermes pushes several updates a second; this long without one means the stream is stuck
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One price with its confidence interval, both in price units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    pub price: f64,
+    pub conf: f64,
+    pub publish_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesEvent {
+    #[serde(default)]
+    parsed: Vec<HermesPriceUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPriceUpdate {
+    id: String,
+    price: HermesPrice,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    publish_time: u64,
+}
+
+/// Lower-case hex price id without `0x`, as Hermes reports it
+pub fn normalize_price_id(id: &str) -> Result<String> {
+    let id = id.trim();
+    let hex = id.strip_prefix("0x").unwrap_or(id).to_lowercase();
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Invalid Pyth price id '{}' (expected 32 bytes of hex)", id);
+    }
+    Ok(hex)
+}
+
+/// Prices in one Hermes `data:` payload, keyed by normalized price id
+pub fn parse_hermes_event(data: &str) -> Result<Vec<(String, PythPrice)>> {
+    let event: HermesEvent = serde_json::from_str(data).context("Invalid Hermes price update")?;
+    event
+        .parsed
+        .into_iter()
+        .map(|update| {
+            let scale = 10f64.powi(update.price.expo);
+            let price: i64 = update.price.price.parse().with_context(|| format!("Invalid price for {}", update.id))?;
+            let conf: u64 = update.price.conf.parse().with_context(|| format!("Invalid conf for {}", update.id))?;
+            Ok((
+                normalize_price_id(&update.id)?,
+                PythPrice {
+                    price: price as f64 * scale,
+                    conf: conf as f64 * scale,
+                    publish_time_ms: update.price.publish_time * 1000,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Latest price per price id, filled by [`PythHermesClient`]
+#[derive(Debug, Default)]
+pub struct PythPrices {
+    prices: RwLock<HashMap<String, PythPrice>>,
+}
+
+impl PythPrices {
+    pub fn set(&self, price_id: &str, price: PythPrice) {
+        self.prices.write().insert(price_id.to_string(), price);
+    }
+
+    pub fn get(&self, price_id: &str) -> Option<PythPrice> {
+        self.prices.read().get(price_id).copied()
+    }
+}
+
+/// Exposes one price id from [`PythPrices`] as a configured feed's price source
+pub struct PythFeedSource {
+    price_id: String,
+    prices: Arc<PythPrices>,
+}
+
+impl PythFeedSource {
+    pub fn new(price_id: String, prices: Arc<PythPrices>) -> Self {
+        Self { price_id, prices }
+    }
+}
+
+impl PriceSource for PythFeedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        let price = self.prices.get(&self.price_id)?;
+        // Pyth can report a non-positive aggregate while publishers disagree
+        if price.price <= 0.0 {
+            return None;
+        }
+        Some(PricePoint {
+            price: price.price,
+            num_trades: 1,
+            timestamp_ms: price.publish_time_ms,
+            confidence: Some(price.conf),
+        })
+    }
+}
+
+/// Streams the subscribed price ids from Hermes, reconnecting on error
+pub struct PythHermesClient {
+    base_url: String,
+    price_ids: Vec<String>,
+    prices: Arc<PythPrices>,
+    reconnect_delay: Duration,
+}
+
+impl PythHermesClient {
+    pub fn new(base_url: String, price_ids: Vec<String>, prices: Arc<PythPrices>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            price_ids,
+            prices,
+            reconnect_delay: Duration::from_secs(5),
+        }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .build()?;
+        loop {
+            let error = match self.connect_and_process(&client).await {
+                Ok(_) => {
+                    warn!("Pyth Hermes stream closed, reconnecting in {:?}", self.reconnect_delay);
+                    None
+                }
+                Err(e) => {
+                    error!("Pyth Hermes error: {}, reconnecting in {:?}", e, self.reconnect_delay);
+                    Some(e.to_string())
+                }
+            };
+            publish_event(OracleEvent::WsReconnect { source: "pyth".to_string(), error });
+
+            sleep(self.reconnect_delay).await;
+        }
+    }
+
+    async fn connect_and_process(&self, client: &reqwest::Client) -> Result<()> {
+        let mut query: Vec<(&str, &str)> = self.price_ids.iter().map(|id| ("ids[]", id.as_str())).collect();
+        query.push(("parsed", "true"));
+        query.push(("allow_unordered", "false"));
+
+        info!("Connecting to Pyth Hermes stream for {} price id(s)", self.price_ids.len());
+        let response = client
+            .get(format!("{}/v2/updates/price/stream", self.base_url))
+            .query(&query)
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?
+            .error_for_status()?;
+        info!("Connected to Pyth Hermes stream");
+
+        let mut stream = response.bytes_stream();
+        let mut buffer = String::new();
+        loop {
+            let chunk = match timeout(IDLE_TIMEOUT, stream.next()).await {
+                Ok(Some(chunk)) => chunk?,
+                Ok(None) => return Ok(()),
+                Err(_) => return Err(anyhow!("No update for {:?}", IDLE_TIMEOUT)),
+            };
+            buffer.push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n"));
+
+            // Events end with a blank line; keep any partial event for the next chunk
+            while let Some(end) = buffer.find("\n\n") {
+                let event: String = buffer.drain(..end + 2).collect();
+                let data: Vec<&str> = event
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(str::trim_start)
+                    .collect();
+                if !data.is_empty() {
+                    maybe_drop_stream("pyth")?;
+                    self.process_data(&data.join("\n"));
+                }
+            }
+        }
+    }
+
+    fn process_data(&self, data: &str) {
+        match parse_hermes_event(data) {
+            Ok(updates) => {
+                for (id, price) in updates {
+                    debug!("Pyth {}: {} ± {}", id, price.price, price.conf);
+                    self.prices.set(&id, price);
+                }
+            }
+            Err(e) => {
+                parse_quarantine("pyth").reject(e, data);
+            }
+        }
+    }
+}
+
+/// Collects the price ids to subscribe to, then starts the Hermes stream
+#[derive(Default)]
+pub struct PythSources {
+    prices: Arc<PythPrices>,
+    price_ids: Vec<String>,
+}
+
+impl PythSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Source for `feed`, whose symbol is the Pyth price id
+    pub fn add(&mut self, feed: &FeedConfig) -> Result<Arc<dyn PriceSource>> {
+        if feed.source != SourceKind::Pyth {
+            bail!("Feed '{}': {:?} is not a Pyth source", feed.id, feed.source);
+        }
+        let price_id = normalize_price_id(&feed.symbol).with_context(|| format!("Feed '{}'", feed.id))?;
+        self.price_ids.push(price_id.clone());
+        Ok(Arc::new(PythFeedSource::new(price_id, self.prices.clone())))
+    }
+
+    /// Start streaming every price id added so far (`PYTH_HERMES_URL`)
+    pub fn spawn(mut self, shutdown: &mut ShutdownCoordinator) {
+        if self.price_ids.is_empty() {
+            return;
+        }
+        let base_url = std::env::var("PYTH_HERMES_URL").unwrap_or_else(|_| DEFAULT_HERMES_URL.to_string());
+        self.price_ids.sort();
+        self.price_ids.dedup();
+
+        let client = PythHermesClient::new(base_url, self.price_ids, self.prices);
+        shutdown.register("pyth hermes stream", tokio::spawn(async move {
+            if let Err(e) = client.run().await {
+                error!("Pyth Hermes client error: {}", e);
+            }
+        }));
+    }
+}
@@ -0,0 +1,211 @@
+//! Per-endpoint RPC rate limiting.
+//!
+//! Every [`crate::submit::RpcEndpoints`] request takes a token from the
+//! bucket of the endpoint it goes to. Buckets are process-wide per URL, so
+//! submission, the auxiliary batcher, gas sampling and block reads all draw
+//! from the same budget and a runaway loop can't get the IP rate-limited by
+//! a public endpoint. Background calls leave the last quarter of the burst
+//! to transaction submission, so they run dry first.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `RPC_RATE_LIMIT` | `20` | Requests per second per endpoint (0 disables) |
+//! | `RPC_RATE_BURST` | `2 × RPC_RATE_LIMIT` | Requests that may go out at once after idling |
+//! | `RPC_RATE_LIMITS` | | Per-endpoint overrides, `<url substring>=<per second>` comma-separated |
+//!
+//! Calls made through alloy providers (startup chain-id checks, admin
+//! commands, shadow / Uniswap reads) and the SDK's own nonce handling don't
+//! go through these buckets.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::timer::{SystemTimerClock, TimerClock};
+
+/// Share of the burst only submission may use
+const SUBMISSION_RESERVE: f64 = 0.25;
+
+/// Who is asking: submission may use the whole bucket, background calls
+/// stop short of the reserve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcClass {
+    Submission,
+    Background,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: f64,
+}
+
+impl RateLimit {
+    pub fn new(per_second: f64) -> Self {
+        Self { per_second, burst: (per_second * 2.0).max(1.0) }
+    }
+
+    pub fn with_burst(mut self, burst: f64) -> Self {
+        self.burst = burst.max(1.0);
+        self
+    }
+}
+
+pub struct TokenBucket {
+    label: String,
+    limit: RateLimit,
+    clock: Arc<dyn TimerClock>,
+    /// Tokens and when they were last topped up
+    state: Mutex<(f64, Duration)>,
+    throttled: AtomicU64,
+}
+
+impl TokenBucket {
+    pub fn new(label: impl Into<String>, limit: RateLimit) -> Self {
+        let clock: Arc<dyn TimerClock> = Arc::new(SystemTimerClock::default());
+        Self {
+            label: label.into(),
+            limit,
+            state: Mutex::new((limit.burst, clock.monotonic())),
+            clock,
+            throttled: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn TimerClock>) -> Self {
+        self.state = Mutex::new((self.limit.burst, clock.monotonic()));
+        self.clock = clock;
+        self
+    }
+
+    /// Take a token now, or how long until one is available to `class`
+    pub fn try_acquire(&self, class: RpcClass) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+        let now = self.clock.monotonic();
+        let refill = now.saturating_sub(state.1).as_secs_f64() * self.limit.per_second;
+        state.0 = (state.0 + refill).min(self.limit.burst);
+        state.1 = now;
+
+        let floor = match class {
+            RpcClass::Submission => 0.0,
+            RpcClass::Background => self.limit.burst * SUBMISSION_RESERVE,
+        };
+        if state.0 - 1.0 >= floor {
+            state.0 -= 1.0;
+            return Ok(());
+        }
+        let missing = floor + 1.0 - state.0;
+        Err(Duration::from_secs_f64(missing / self.limit.per_second))
+    }
+
+    /// Wait for a token
+    pub async fn acquire(&self, class: RpcClass) {
+        let mut throttled = false;
+        while let Err(wait) = self.try_acquire(class) {
+            if !throttled {
+                throttled = true;
+                self.throttled.fetch_add(1, Ordering::Relaxed);
+                debug!("🚦 {:?} RPC call to {} held for {:?}", class, self.label, wait);
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "endpoint": self.label,
+            "per_second": self.limit.per_second,
+            "burst": self.limit.burst,
+            "throttled": self.throttled.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Limits from `RPC_RATE_LIMIT`, `RPC_RATE_BURST` and `RPC_RATE_LIMITS`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimits {
+    default: Option<RateLimit>,
+    /// URL substring and limit, first match wins
+    overrides: Vec<(String, RateLimit)>,
+}
+
+impl RateLimits {
+    pub fn new(default: Option<RateLimit>) -> Self {
+        Self { default, overrides: Vec::new() }
+    }
+
+    pub fn with_override(mut self, url_contains: impl Into<String>, limit: RateLimit) -> Self {
+        self.overrides.push((url_contains.into(), limit));
+        self
+    }
+
+    pub fn from_env() -> Self {
+        let per_second = match std::env::var("RPC_RATE_LIMIT") {
+            Ok(v) => v.parse::<f64>().ok().filter(|r| *r >= 0.0).unwrap_or_else(|| {
+                warn!("Invalid RPC_RATE_LIMIT '{}', using 20", v);
+                20.0
+            }),
+            Err(_) => 20.0,
+        };
+        let burst = std::env::var("RPC_RATE_BURST").ok().and_then(|v| {
+            v.parse::<f64>().ok().filter(|b| *b >= 1.0).or_else(|| {
+                warn!("Invalid RPC_RATE_BURST '{}', using twice the rate", v);
+                None
+            })
+        });
+        let limit = |per_second: f64| {
+            let limit = RateLimit::new(per_second);
+            burst.map_or(limit, |burst| limit.with_burst(burst))
+        };
+
+        let mut limits = Self::new((per_second > 0.0).then(|| limit(per_second)));
+        for entry in std::env::var("RPC_RATE_LIMITS").unwrap_or_default().split(',') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            match entry.rsplit_once('=').map(|(url, rate)| (url.trim(), rate.trim().parse::<f64>())) {
+                Some((url, Ok(rate))) if !url.is_empty() && rate > 0.0 => {
+                    limits = limits.with_override(url, limit(rate));
+                }
+                _ => warn!("Ignoring RPC_RATE_LIMITS entry '{}' (expected <url>=<per second>)", entry),
+            }
+        }
+        limits
+    }
+
+    /// Limit for `url`, None when unlimited
+    pub fn for_url(&self, url: &str) -> Option<RateLimit> {
+        self.overrides
+            .iter()
+            .find(|(pattern, _)| url.contains(pattern.as_str()))
+            .map(|(_, limit)| *limit)
+            .or(self.default)
+    }
+}
+
+fn buckets() -> &'static Mutex<HashMap<String, Option<Arc<TokenBucket>>>> {
+    static BUCKETS: OnceLock<Mutex<HashMap<String, Option<Arc<TokenBucket>>>>> = OnceLock::new();
+    BUCKETS.get_or_init(Default::default)
+}
+
+/// The process-wide bucket for `url` (None when unlimited), created on
+/// first use from [`RateLimits::from_env`]
+pub fn bucket_for(url: &str) -> Option<Arc<TokenBucket>> {
+    static LIMITS: OnceLock<RateLimits> = OnceLock::new();
+    let limits = LIMITS.get_or_init(RateLimits::from_env);
+    buckets()
+        .lock()
+        .entry(url.to_string())
+        .or_insert_with(|| limits.for_url(url).map(|limit| Arc::new(TokenBucket::new(url, limit))))
+        .clone()
+}
+
+/// Every limited endpoint's bucket, for status output
+pub fn rate_limits_json() -> Value {
+    Value::Array(buckets().lock().values().flatten().map(|bucket| bucket.to_json()).collect())
+}
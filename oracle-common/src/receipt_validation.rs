@@ -0,0 +1,205 @@
+//! Sanity checks on receipts returned by the submitter.
+//!
+//! `eth_sendRawTransactionSync` has been seen to return receipts with a zero
+//! block number or zero gas used for transactions that never landed. With
+//! `RECEIPT_VALIDATION` (default `basic`) such receipts count as failures;
+//! `verify` first asks `eth_getTransactionReceipt` for the real receipt and
+//! only fails if that is missing or bogus too. `off` trusts every receipt.
+//!
+//! Receipts are parsed leniently with [`parse_receipt`]: quantities in odd
+//! encodings are canonicalised and missing block fields read as zero, so a
+//! half-filled receipt is reported by the checks above rather than failing
+//! deserialization with an opaque error.
+
+use alloy::primitives::{keccak256, Bytes, B256, U256};
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::submit::RpcEndpoints;
+
+/// Receipt fields holding JSON-RPC quantities
+const QUANTITY_FIELDS: [&str; 7] =
+    ["blockNumber", "transactionIndex", "gasUsed", "cumulativeGasUsed", "effectiveGasPrice", "status", "type"];
+
+/// Read as zero when absent; the block ones are then flagged by [`receipt_issues`]
+const ZERO_WHEN_MISSING: [&str; 5] = ["blockNumber", "transactionIndex", "gasUsed", "cumulativeGasUsed", "effectiveGasPrice"];
+
+/// A JSON-RPC quantity in any encoding nodes have been seen to use: `0x`
+/// hex (any case, leading zeros, or bare `0x` for zero), decimal strings,
+/// JSON numbers, and booleans for `status`
+pub fn quantity(value: &Value) -> Option<U256> {
+    match value {
+        Value::Number(n) => n.as_u64().map(U256::from),
+        Value::Bool(b) => Some(U256::from(*b as u8)),
+        Value::String(s) => {
+            let s = s.trim();
+            match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                Some("") => Some(U256::ZERO),
+                Some(hex) => U256::from_str_radix(hex, 16).ok(),
+                None => U256::from_str_radix(s, 10).ok(),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Parse a receipt from `eth_sendRawTransactionSync` or
+/// `eth_getTransactionReceipt`, canonicalising quantities and filling
+/// missing block fields with zero first
+pub fn parse_receipt(result: Value) -> Result<SyncTransactionReceipt, String> {
+    let Value::Object(mut fields) = result else {
+        return Err(format!("expected a receipt object, got {}", result));
+    };
+    for name in QUANTITY_FIELDS {
+        let Some(value) = fields.get_mut(name).filter(|v| !v.is_null()) else { continue };
+        let parsed = quantity(value).ok_or_else(|| format!("{} is not a quantity: {}", name, value))?;
+        *value = json!(parsed);
+    }
+    for name in ZERO_WHEN_MISSING {
+        fields.entry(name).and_modify(|v| if v.is_null() { *v = json!("0x0") }).or_insert(json!("0x0"));
+    }
+    if fields.get("blockHash").map_or(true, Value::is_null) {
+        fields.insert("blockHash".to_string(), json!(B256::ZERO));
+    }
+    fields.entry("logs").or_insert(json!([]));
+    fields.entry("logsBloom").or_insert(json!(alloy::primitives::Bloom::ZERO));
+    serde_json::from_value(Value::Object(fields)).map_err(|e| e.to_string())
+}
+
+/// What looks wrong with `receipt`, empty if nothing does. `tx_hash` is the
+/// hash of the transaction that was sent, when known.
+pub fn receipt_issues(receipt: &SyncTransactionReceipt, tx_hash: Option<B256>) -> Vec<String> {
+    let mut issues = Vec::new();
+    if receipt.block_number == U256::ZERO {
+        issues.push("block number is 0".to_string());
+    }
+    if receipt.block_hash == B256::ZERO {
+        issues.push("block hash is 0".to_string());
+    }
+    if receipt.gas_used == U256::ZERO {
+        issues.push("gas used is 0".to_string());
+    }
+    if receipt.effective_gas_price == U256::ZERO {
+        issues.push("gas price is 0".to_string());
+    }
+    if let Some(tx_hash) = tx_hash {
+        if receipt.transaction_hash != tx_hash {
+            issues.push(format!("receipt is for {} not {}", receipt.transaction_hash, tx_hash));
+        }
+    }
+    issues
+}
+
+/// How much to trust submitter receipts (`RECEIPT_VALIDATION`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptValidation {
+    Off,
+    /// Reject suspicious receipts
+    Basic,
+    /// Re-check suspicious receipts with `eth_getTransactionReceipt`
+    Verify,
+}
+
+impl FromStr for ReceiptValidation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "off" => Ok(Self::Off),
+            "basic" => Ok(Self::Basic),
+            "verify" => Ok(Self::Verify),
+            other => Err(anyhow::anyhow!(
+                "Unknown RECEIPT_VALIDATION '{}' - expected off, basic or verify",
+                other
+            )),
+        }
+    }
+}
+
+impl ReceiptValidation {
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var("RECEIPT_VALIDATION") {
+            Ok(mode) => mode.parse(),
+            Err(_) => Ok(Self::Basic),
+        }
+    }
+}
+
+/// Wraps a submitter, turning bogus receipts into errors
+pub struct ValidatingSubmitter {
+    inner: Arc<dyn TxSubmitter>,
+    rpc: Arc<RpcEndpoints>,
+    mode: ReceiptValidation,
+    rejected: AtomicU64,
+    recovered: AtomicU64,
+}
+
+impl ValidatingSubmitter {
+    pub fn new(inner: Arc<dyn TxSubmitter>, rpc: Arc<RpcEndpoints>, mode: ReceiptValidation) -> Self {
+        Self {
+            inner,
+            rpc,
+            mode,
+            rejected: AtomicU64::new(0),
+            recovered: AtomicU64::new(0),
+        }
+    }
+
+    /// Receipts rejected as bogus
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Bogus receipts replaced by a valid one from `eth_getTransactionReceipt`
+    pub fn recovered(&self) -> u64 {
+        self.recovered.load(Ordering::Relaxed)
+    }
+
+    async fn fetch_receipt(&self, tx_hash: B256) -> Option<SyncTransactionReceipt> {
+        match self.rpc.call("eth_getTransactionReceipt", json!([tx_hash])).await {
+            Ok(receipt) if !receipt.is_null() => parse_receipt(receipt).ok(),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("⚠️ Could not re-verify receipt for {}: {:?}", tx_hash, e);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for ValidatingSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let tx_hash = keccak256(&raw_tx);
+        let receipt = self.inner.submit(raw_tx).await?;
+        if self.mode == ReceiptValidation::Off {
+            return Ok(receipt);
+        }
+
+        let issues = receipt_issues(&receipt, Some(tx_hash));
+        if issues.is_empty() {
+            return Ok(receipt);
+        }
+
+        if self.mode == ReceiptValidation::Verify {
+            if let Some(verified) = self.fetch_receipt(tx_hash).await {
+                if receipt_issues(&verified, Some(tx_hash)).is_empty() {
+                    self.recovered.fetch_add(1, Ordering::Relaxed);
+                    warn!("🩹 Suspicious receipt for {} ({}), using eth_getTransactionReceipt", tx_hash, issues.join(", "));
+                    return Ok(verified);
+                }
+            }
+        }
+
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        error!("🚫 Rejecting suspicious receipt for {}: {}", tx_hash, issues.join(", "));
+        Err(RiseError::Rpc(format!("Suspicious receipt for {}: {}", tx_hash, issues.join(", "))))
+    }
+}
@@ -0,0 +1,200 @@
+//! Background re-verification of confirmed updates.
+//!
+//! A sync receipt says the transaction landed, but the sync endpoint has
+//! been wrong before. Once an update is `RECEIPT_VERIFY_BLOCKS` blocks deep,
+//! re-fetch its receipt with `eth_getTransactionReceipt`:
+//!
+//! - missing: the update never landed (or was reorged out) and is moved
+//!   from the trigger's successes to its failures
+//! - status 0: same, it reverted after all
+//! - different block hash: reorged but re-included, only counted
+
+use alloy::primitives::{B256, U256};
+use anyhow::{Context, Result};
+use futures_util::future::join_all;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::receipt_validation::quantity;
+use crate::rpc_batch::RpcBatcher;
+use crate::stats::SharedStats;
+
+/// How often the worker looks at the chain head
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Updates waiting for verification before new ones are dropped unchecked
+const MAX_PENDING: usize = 10_000;
+
+/// Outcome counts
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct VerificationCounts {
+    pub verified: u64,
+    /// Reported confirmed but no receipt a few blocks later
+    pub dropped: u64,
+    /// Reported successful but the canonical receipt has status 0
+    pub reverted: u64,
+    /// Included, but in a different block than reported
+    pub moved: u64,
+    /// Not checked because the queue was full
+    pub skipped: u64,
+}
+
+struct Watched {
+    label: String,
+    tx_hash: B256,
+    block_number: u64,
+    block_hash: B256,
+    stats: SharedStats,
+}
+
+pub struct ReceiptVerifier {
+    confirmations: u64,
+    counts: RwLock<VerificationCounts>,
+    queue: mpsc::UnboundedSender<Watched>,
+}
+
+impl ReceiptVerifier {
+    /// Verifier re-checking receipts `confirmations` blocks after inclusion
+    pub fn spawn(rpc_urls: Vec<String>, confirmations: u64) -> (Arc<Self>, JoinHandle<()>) {
+        let (queue, receiver) = mpsc::unbounded_channel();
+        let verifier = Arc::new(Self {
+            confirmations,
+            counts: RwLock::new(VerificationCounts::default()),
+            queue,
+        });
+        let handle = tokio::spawn(verifier.clone().run(RpcBatcher::shared(&rpc_urls), receiver));
+        (verifier, handle)
+    }
+
+    /// From `RECEIPT_VERIFY_BLOCKS`; `None` when unset or 0
+    pub fn from_env(rpc_urls: Vec<String>) -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let confirmations: u64 = match std::env::var("RECEIPT_VERIFY_BLOCKS") {
+            Ok(v) => v.parse().context("Invalid RECEIPT_VERIFY_BLOCKS")?,
+            Err(_) => 0,
+        };
+        if confirmations == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self::spawn(rpc_urls, confirmations)))
+    }
+
+    /// Queue a confirmed update; `stats` is corrected if it turns out bogus
+    pub fn watch(&self, label: &str, receipt: &SyncTransactionReceipt, stats: &SharedStats) {
+        let _ = self.queue.send(Watched {
+            label: label.to_string(),
+            tx_hash: receipt.transaction_hash,
+            block_number: receipt.block_number.saturating_to(),
+            block_hash: receipt.block_hash,
+            stats: stats.clone(),
+        });
+    }
+
+    pub fn counts(&self) -> VerificationCounts {
+        self.counts.read().clone()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "confirmations": self.confirmations,
+            "counts": self.counts(),
+        })
+    }
+
+    async fn run(self: Arc<Self>, rpc: Arc<RpcBatcher>, mut receiver: mpsc::UnboundedReceiver<Watched>) {
+        let mut pending: VecDeque<Watched> = VecDeque::new();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                watched = receiver.recv() => match watched {
+                    Some(watched) if pending.len() < MAX_PENDING => pending.push_back(watched),
+                    Some(_) => self.counts.write().skipped += 1,
+                    None => return,
+                },
+                _ = ticker.tick() => {
+                    if !pending.is_empty() {
+                        self.verify_due(&rpc, &mut pending).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check every pending update that is deep enough
+    async fn verify_due(&self, rpc: &RpcBatcher, pending: &mut VecDeque<Watched>) {
+        let head = match rpc.call("eth_blockNumber", json!([])).await {
+            Ok(head) => match head.as_str().and_then(|h| U256::from_str_radix(h.trim_start_matches("0x"), 16).ok()) {
+                Some(head) => head.saturating_to::<u64>(),
+                None => return,
+            },
+            Err(e) => {
+                debug!("Receipt verifier could not read block number: {:?}", e);
+                return;
+            }
+        };
+
+        // Updates are queued in inclusion order, so stop at the first one not yet deep enough.
+        // The due receipts are looked up together so they share one batch.
+        let due = pending.iter().take_while(|w| w.block_number + self.confirmations <= head).count();
+        let receipts = join_all(
+            pending
+                .iter()
+                .take(due)
+                .map(|watched| rpc.call("eth_getTransactionReceipt", json!([watched.tx_hash]))),
+        )
+        .await;
+        for receipt in receipts {
+            let receipt = match receipt {
+                Ok(receipt) => receipt,
+                Err(e) => {
+                    debug!("Receipt verifier lookup for {} failed: {:?}", pending[0].tx_hash, e);
+                    return;
+                }
+            };
+            let watched = pending.pop_front().expect("front exists");
+            self.settle(&watched, &receipt);
+        }
+    }
+
+    fn settle(&self, watched: &Watched, receipt: &Value) {
+        if receipt.is_null() {
+            self.counts.write().dropped += 1;
+            watched.stats.write().record_dropped();
+            error!(
+                "🚨 [{}] Update {} reported in block {} is not on chain",
+                watched.label, watched.tx_hash, watched.block_number
+            );
+            return;
+        }
+
+        if quantity(&receipt["status"]) == Some(U256::ZERO) {
+            self.counts.write().reverted += 1;
+            watched.stats.write().record_dropped();
+            error!("🚨 [{}] Update {} was reported successful but reverted", watched.label, watched.tx_hash);
+            return;
+        }
+
+        let block_hash = receipt["blockHash"].as_str().and_then(|h| h.parse::<B256>().ok());
+        if block_hash != Some(watched.block_hash) {
+            self.counts.write().moved += 1;
+            warn!(
+                "🔀 [{}] Update {} moved from block {} ({}) to {}",
+                watched.label,
+                watched.tx_hash,
+                watched.block_number,
+                watched.block_hash,
+                receipt["blockNumber"]
+            );
+            return;
+        }
+
+        self.counts.write().verified += 1;
+    }
+}
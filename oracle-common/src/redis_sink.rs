@@ -0,0 +1,196 @@
+//! Redis pub/sub publication of computed and published prices.
+//!
+//! With `REDIS_URL` set, every value a feed computes (TWAPs included) and
+//! every update confirmed on chain is published from the
+//! [`EventBus`](crate::events::EventBus) to Redis in the
+//! [`PriceStream`](crate::price_stream::PriceStream) JSON format, so services
+//! in the same infra react within milliseconds without polling the chain:
+//!
+//! - `PUBLISH <prefix>:computed:<feed>` / `<prefix>:published:<feed>` -
+//!   `PSUBSCRIBE <prefix>:*:BTCUSD` follows one feed
+//! - `SET <prefix>:latest:computed:<feed>` / `<prefix>:latest:published:<feed>`
+//!   for consumers that start up between updates, expiring after
+//!   `REDIS_TTL_SECS` so a stopped oracle doesn't leave stale prices behind
+//!
+//! Events are queued without blocking the trigger that published them and
+//! written in pipelined batches; while Redis is unreachable the connection
+//! is retried and events beyond `REDIS_QUEUE` are dropped (and counted).
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `REDIS_URL` | unset | e.g. `redis://10.0.0.5:6379` (unset disables) |
+//! | `REDIS_PREFIX` | `nonzu` | Channel and key prefix |
+//! | `REDIS_TTL_SECS` | `300` | Expiry of the latest-value keys (0 = never) |
+//! | `REDIS_QUEUE` | `1024` | Events buffered while Redis is slow or down |
+
+use anyhow::{Context, Result};
+use redis::aio::ConnectionManager;
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::events::{EventSubscriber, OracleEvent};
+use crate::price_stream::price_message;
+
+/// Most events written in one pipeline
+const MAX_BATCH: usize = 256;
+
+/// Longest wait between connection attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// One rendered event
+struct PriceMessage {
+    kind: &'static str,
+    feed: String,
+    json: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisSinkConfig {
+    pub url: String,
+    pub prefix: String,
+    /// Expiry of the latest-value keys, `None` to keep them
+    pub ttl: Option<Duration>,
+    pub queue: usize,
+}
+
+impl RedisSinkConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), prefix: "nonzu".to_string(), ttl: Some(Duration::from_secs(300)), queue: 1024 }
+    }
+}
+
+pub struct RedisSink {
+    prefix: String,
+    ttl: Option<Duration>,
+    sender: mpsc::Sender<PriceMessage>,
+    published: AtomicU64,
+    dropped: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl RedisSink {
+    pub fn spawn(config: RedisSinkConfig) -> Result<(Arc<Self>, JoinHandle<()>)> {
+        let client = redis::Client::open(config.url.as_str()).context("Invalid REDIS_URL")?;
+        let (sender, receiver) = mpsc::channel(config.queue.max(1));
+        let sink = Arc::new(Self {
+            prefix: config.prefix,
+            ttl: config.ttl,
+            sender,
+            published: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+        });
+        let handle = tokio::spawn(sink.clone().run(client, receiver));
+        Ok((sink, handle))
+    }
+
+    /// From `REDIS_URL` / `REDIS_PREFIX` / `REDIS_TTL_SECS` / `REDIS_QUEUE`;
+    /// `None` when unset. Subscribe the sink to the event bus to feed it.
+    pub fn from_env() -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let mut config = match std::env::var("REDIS_URL") {
+            Ok(url) if !url.is_empty() => RedisSinkConfig::new(url),
+            _ => return Ok(None),
+        };
+        if let Ok(prefix) = std::env::var("REDIS_PREFIX") {
+            config.prefix = prefix;
+        }
+        if let Ok(v) = std::env::var("REDIS_TTL_SECS") {
+            let secs: u64 = v.parse().context("Invalid REDIS_TTL_SECS")?;
+            config.ttl = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+        if let Ok(v) = std::env::var("REDIS_QUEUE") {
+            config.queue = v.parse().context("Invalid REDIS_QUEUE")?;
+        }
+        info!("🧱 Publishing prices to Redis under {}:*", config.prefix);
+        Self::spawn(config).map(Some)
+    }
+
+    async fn run(self: Arc<Self>, client: redis::Client, mut receiver: mpsc::Receiver<PriceMessage>) {
+        let mut connection = self.connect(&client).await;
+        let mut batch = Vec::with_capacity(MAX_BATCH);
+        while receiver.recv_many(&mut batch, MAX_BATCH).await > 0 {
+            let mut pipe = redis::pipe();
+            for message in &batch {
+                pipe.cmd("PUBLISH")
+                    .arg(format!("{}:{}:{}", self.prefix, message.kind, message.feed))
+                    .arg(&message.json)
+                    .ignore();
+                let key = format!("{}:latest:{}:{}", self.prefix, message.kind, message.feed);
+                match self.ttl {
+                    Some(ttl) => pipe.cmd("SET").arg(key).arg(&message.json).arg("PX").arg(ttl.as_millis() as u64).ignore(),
+                    None => pipe.cmd("SET").arg(key).arg(&message.json).ignore(),
+                };
+            }
+            // The connection manager reconnects on the next write after a failure
+            let written: redis::RedisResult<()> = pipe.query_async(&mut connection).await;
+            match written {
+                Ok(()) => {
+                    self.published.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    self.errors.fetch_add(1, Ordering::Relaxed);
+                    self.dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    warn!("⚠️ Redis publish of {} event(s) failed: {}", batch.len(), e);
+                }
+            }
+            batch.clear();
+        }
+    }
+
+    /// Connect, retrying with backoff until Redis is reachable
+    async fn connect(&self, client: &redis::Client) -> ConnectionManager {
+        let mut delay = Duration::from_millis(500);
+        loop {
+            match ConnectionManager::new(client.clone()).await {
+                Ok(connection) => {
+                    debug!("Connected to Redis");
+                    return connection;
+                }
+                Err(e) => {
+                    self.errors.fetch_add(1, Ordering::Relaxed);
+                    warn!("⚠️ Redis unreachable ({}), retrying in {:?}", e, delay);
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Events written to Redis
+    pub fn published(&self) -> u64 {
+        self.published.load(Ordering::Relaxed)
+    }
+
+    /// Events lost to a full queue or a failed write
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "prefix": self.prefix,
+            "published": self.published(),
+            "dropped": self.dropped(),
+            "errors": self.errors.load(Ordering::Relaxed),
+        })
+    }
+}
+
+impl EventSubscriber for RedisSink {
+    fn on_event(&self, event: &OracleEvent) {
+        let Some((feed, json)) = price_message(event) else { return };
+        let kind = match event {
+            OracleEvent::ValueComputed { .. } => "computed",
+            _ => "published",
+        };
+        let message = PriceMessage { kind, feed: feed.to_string(), json: json.to_string() };
+        if self.sender.try_send(message).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
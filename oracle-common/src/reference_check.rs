@@ -0,0 +1,205 @@
+//! Cross-checking updates against an independent reference price.
+//!
+//! A feed with `[feeds.reference]` reads a second opinion every `poll_ms` in
+//! the background - a Chainlink aggregator, another PriceOracleV2 deployment
+//! or a REST endpoint such as Coinbase spot - and each update is compared
+//! with the last read before it is sent. When the two differ by more than
+//! `max_divergence_bps` the update is skipped and the divergence escalated:
+//! logged as an error and published as [`OracleEvent::ReferenceDiverged`]
+//! for alerting, with [`OracleEvent::ReferenceRestored`] once they agree
+//! again.
+//!
+//! Every check records the divergence, so `/status` shows its distribution
+//! (p50 / p99 / max bps) alongside how many updates were held. When the
+//! reference cannot be read (or its last read is older than a few polls)
+//! updates go out unchecked, so an outage elsewhere never freezes a feed.
+
+use anyhow::{anyhow, Result};
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::events::{publish_event, OracleEvent};
+use crate::feeds::{FeedConfig, FeedReference, ReferenceKind};
+use crate::shadow::{ChainlinkReference, PriceOracleReference, ReferenceOracle, RestReference};
+use crate::stats::Distribution;
+
+/// Divergences kept for the `/status` distribution
+const MAX_SAMPLES: usize = 10_000;
+
+#[derive(Default)]
+struct LastRead {
+    /// `None` when the last read failed
+    price: Option<f64>,
+    at: Option<Instant>,
+    failures: u64,
+}
+
+struct DivergenceMetrics {
+    /// Signed divergence of each checked value from the reference, in bps
+    divergence_bps: Distribution,
+    last_bps: Option<f64>,
+    checked: u64,
+    held: u64,
+    /// Values let through without a fresh reference
+    unchecked: u64,
+}
+
+pub struct ReferenceCheck {
+    feed_id: String,
+    reference: Arc<dyn ReferenceOracle>,
+    max_divergence_bps: u64,
+    poll_interval: Duration,
+    last: RwLock<LastRead>,
+    metrics: RwLock<DivergenceMetrics>,
+    /// Whether the last check held the value back, so transitions are escalated once
+    diverged: Mutex<bool>,
+}
+
+impl ReferenceCheck {
+    pub fn new(feed_id: impl Into<String>, reference: Arc<dyn ReferenceOracle>, config: &FeedReference) -> Self {
+        Self {
+            feed_id: feed_id.into(),
+            reference,
+            max_divergence_bps: config.max_divergence_bps,
+            poll_interval: Duration::from_millis(config.poll_ms.max(50)),
+            last: RwLock::new(LastRead::default()),
+            metrics: RwLock::new(DivergenceMetrics {
+                divergence_bps: Distribution::new(MAX_SAMPLES),
+                last_bps: None,
+                checked: 0,
+                held: 0,
+                unchecked: 0,
+            }),
+            diverged: Mutex::new(false),
+        }
+    }
+
+    /// The check for `feed`'s `[feeds.reference]`; chain references read
+    /// over `default_rpc` unless they name their own `rpc_url`
+    pub async fn for_feed(feed: &FeedConfig, config: &FeedReference, default_rpc: &str) -> Result<Self> {
+        let rpc_url = config.rpc_url.as_deref().unwrap_or(default_rpc);
+        let address = config.address.ok_or_else(|| anyhow!("Feed '{}': reference needs an address", feed.id));
+        let reference: Arc<dyn ReferenceOracle> = match config.kind {
+            ReferenceKind::Chainlink => Arc::new(ChainlinkReference::new(rpc_url, address?).await?),
+            ReferenceKind::Oracle => Arc::new(PriceOracleReference::new(rpc_url, address?, feed.id.clone())?),
+            ReferenceKind::Rest => Arc::new(RestReference::new(
+                config.url.clone().unwrap_or_default(),
+                config.json_path.as_deref().unwrap_or_default(),
+                config.source_decimals,
+            )?),
+        };
+        Ok(Self::new(feed.id.clone(), reference, config))
+    }
+
+    /// Record a read of the reference (`None` when it failed)
+    pub fn record_read(&self, price: Option<f64>) {
+        let mut last = self.last.write();
+        last.price = price.filter(|p| p.is_finite() && *p > 0.0);
+        last.at = Some(Instant::now());
+        if last.price.is_none() {
+            last.failures += 1;
+        }
+    }
+
+    /// Whether `value` may be published; records its divergence and
+    /// escalates when it is held back
+    pub fn allows(&self, value: f64) -> bool {
+        let reference = {
+            let last = self.last.read();
+            // Reads older than a few polls are as good as failed
+            let fresh = last.at.is_some_and(|at| at.elapsed() < self.poll_interval * 3);
+            last.price.filter(|_| fresh)
+        };
+        let Some(reference) = reference else {
+            self.metrics.write().unchecked += 1;
+            debug!("No fresh reference for {}, publishing unchecked", self.feed_id);
+            return true;
+        };
+
+        let divergence_bps = (value - reference) / reference * 10_000.0;
+        let diverged = divergence_bps.abs() > self.max_divergence_bps as f64;
+        {
+            let mut metrics = self.metrics.write();
+            metrics.divergence_bps.record(divergence_bps);
+            metrics.last_bps = Some(divergence_bps);
+            metrics.checked += 1;
+            if diverged {
+                metrics.held += 1;
+            }
+        }
+
+        let mut was_diverged = self.diverged.lock();
+        if diverged != *was_diverged {
+            if diverged {
+                error!(
+                    "🚨 {} value {} is {:.0}bps from {} ({}), limit {}bps - holding updates",
+                    self.feed_id,
+                    value,
+                    divergence_bps,
+                    self.reference.name(),
+                    reference,
+                    self.max_divergence_bps
+                );
+                publish_event(OracleEvent::ReferenceDiverged {
+                    feed: self.feed_id.clone(),
+                    value,
+                    reference,
+                    divergence_bps,
+                });
+            } else {
+                info!("✅ {} agrees with {} again ({:.1}bps)", self.feed_id, self.reference.name(), divergence_bps);
+                publish_event(OracleEvent::ReferenceRestored { feed: self.feed_id.clone() });
+            }
+            *was_diverged = diverged;
+        }
+        !diverged
+    }
+
+    /// Read the reference every poll interval
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let check = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(check.poll_interval);
+            loop {
+                ticker.tick().await;
+                match check.reference.latest_price().await {
+                    Ok(price) => {
+                        debug!("{} reference {}: {}", check.feed_id, check.reference.name(), price);
+                        check.record_read(Some(price));
+                    }
+                    Err(e) => {
+                        // Logged at debug after the first failure; /status counts them
+                        if check.last.read().failures == 0 {
+                            warn!("⚠️ {} reference read failed, publishing unchecked: {}", check.feed_id, e);
+                        } else {
+                            debug!("{} reference read failed: {}", check.feed_id, e);
+                        }
+                        check.record_read(None);
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let last = self.last.read();
+        let metrics = self.metrics.read();
+        json!({
+            "reference": self.reference.name(),
+            "max_divergence_bps": self.max_divergence_bps,
+            "last_reference": last.price,
+            "read_age_ms": last.at.map(|at| at.elapsed().as_millis() as u64),
+            "read_failures": last.failures,
+            "diverged": *self.diverged.lock(),
+            "last_divergence_bps": metrics.last_bps,
+            "divergence_bps": metrics.divergence_bps.abs_summary(),
+            "checked": metrics.checked,
+            "held": metrics.held,
+            "unchecked": metrics.unchecked,
+        })
+    }
+}
@@ -0,0 +1,239 @@
+//! Chain reorg detection for published updates.
+//!
+//! Follows the chain head, remembering the last `REORG_DEPTH` block hashes,
+//! and records the block each confirmed update landed in. When a block
+//! holding one of our updates is replaced, the update's receipt is looked up
+//! again: if it is gone the owning trigger is asked to re-publish, otherwise
+//! it was simply re-included. Reorgs and dropped updates are counted.
+
+use alloy::primitives::{B256, U256};
+use anyhow::{Context, Result};
+use futures_util::future::join_all;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::rpc_batch::RpcBatcher;
+use crate::submit::JsonRpc;
+
+/// How often the chain head is polled
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ReorgCounts {
+    /// Reorgs seen (of any block, ours or not)
+    pub reorgs: u64,
+    /// Deepest reorg seen, in blocks
+    pub max_depth: u64,
+    /// Updates that disappeared and were re-published
+    pub dropped_updates: u64,
+    /// Updates whose block was replaced but that landed again
+    pub reincluded_updates: u64,
+}
+
+struct Inclusion {
+    label: String,
+    tx_hash: B256,
+    block_hash: B256,
+    /// Set to make the owning trigger publish again
+    republish: Option<Arc<AtomicBool>>,
+}
+
+#[derive(Default)]
+struct ChainView {
+    /// Canonical hashes of recent blocks
+    blocks: BTreeMap<u64, B256>,
+    /// Our updates by inclusion block
+    inclusions: BTreeMap<u64, Vec<Inclusion>>,
+}
+
+pub struct ReorgDetector {
+    depth: u64,
+    view: RwLock<ChainView>,
+    counts: RwLock<ReorgCounts>,
+}
+
+impl ReorgDetector {
+    /// Detector following the head of `rpc_urls`, `depth` blocks deep
+    pub fn spawn(rpc_urls: Vec<String>, depth: u64) -> (Arc<Self>, JoinHandle<()>) {
+        let detector = Arc::new(Self {
+            depth: depth.max(1),
+            view: RwLock::new(ChainView::default()),
+            counts: RwLock::new(ReorgCounts::default()),
+        });
+        let rpc = RpcBatcher::shared(&rpc_urls);
+        let worker = detector.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = worker.poll(rpc.as_ref()).await {
+                    debug!("Reorg detector poll failed: {}", e);
+                }
+            }
+        });
+        (detector, handle)
+    }
+
+    /// From `REORG_DEPTH`; `None` when unset or 0
+    pub fn from_env(rpc_urls: Vec<String>) -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let depth: u64 = match std::env::var("REORG_DEPTH") {
+            Ok(v) => v.parse().context("Invalid REORG_DEPTH")?,
+            Err(_) => 0,
+        };
+        if depth == 0 {
+            return Ok(None);
+        }
+        info!("🔀 Reorg detection enabled ({} blocks deep)", depth);
+        Ok(Some(Self::spawn(rpc_urls, depth)))
+    }
+
+    /// Track a confirmed update. `republish` is set if a reorg drops it;
+    /// triggers that publish again on their next tick anyway pass `None`.
+    pub fn watch(&self, label: &str, receipt: &SyncTransactionReceipt, republish: Option<&Arc<AtomicBool>>) {
+        self.view
+            .write()
+            .inclusions
+            .entry(receipt.block_number.saturating_to())
+            .or_default()
+            .push(Inclusion {
+                label: label.to_string(),
+                tx_hash: receipt.transaction_hash,
+                block_hash: receipt.block_hash,
+                republish: republish.cloned(),
+            });
+    }
+
+    pub fn counts(&self) -> ReorgCounts {
+        self.counts.read().clone()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "depth": self.depth, "counts": self.counts() })
+    }
+
+    async fn poll(&self, rpc: &dyn JsonRpc) -> Result<()> {
+        let head = fetch_block(rpc, "latest").await?;
+        let head_number = head.number;
+        let floor = head_number.saturating_sub(self.depth);
+        let last_seen = self.view.read().blocks.keys().next_back().copied();
+        if self.view.read().blocks.get(&head_number) == Some(&head.hash) {
+            return Ok(());
+        }
+
+        // Blocks since the last poll
+        let from = last_seen.map_or(head_number, |last| (last + 1).min(head_number).max(floor));
+        // Fetched together so they share one batch
+        let blocks = join_all((from..head_number).map(|number| async move {
+            Ok::<_, anyhow::Error>((number, fetch_block(rpc, &format!("{:#x}", number)).await?))
+        }))
+        .await;
+        let mut fetched = blocks.into_iter().collect::<Result<BTreeMap<_, _>>>()?;
+        fetched.insert(head_number, head);
+
+        // Walk back while the oldest fetched block's parent disagrees with
+        // what we saw before - those blocks were replaced
+        let mut lowest = from;
+        loop {
+            let parent = fetched[&lowest].parent;
+            let Some(previous) = lowest.checked_sub(1).filter(|p| *p > floor) else { break };
+            let Some(known) = self.view.read().blocks.get(&previous).copied() else { break };
+            if known == parent {
+                break;
+            }
+            fetched.insert(previous, fetch_block(rpc, &format!("{:#x}", previous)).await?);
+            lowest = previous;
+        }
+
+        let replaced: Vec<u64> = {
+            let view = self.view.read();
+            fetched
+                .iter()
+                .filter(|(number, block)| view.blocks.get(*number).is_some_and(|hash| *hash != block.hash))
+                .map(|(number, _)| *number)
+                .collect()
+        };
+        if let Some(first) = replaced.first() {
+            let mut counts = self.counts.write();
+            counts.reorgs += 1;
+            counts.max_depth = counts.max_depth.max(replaced.len() as u64);
+            warn!("🔀 Reorg of {} block(s) detected from height {}", replaced.len(), first);
+        }
+
+        let suspects = {
+            let mut view = self.view.write();
+            // A shorter chain leaves stale blocks above the head
+            view.blocks.retain(|number, _| *number <= head_number);
+            view.blocks.extend(fetched.into_iter().map(|(number, block)| (number, block.hash)));
+            view.blocks = view.blocks.split_off(&floor);
+            view.inclusions = view.inclusions.split_off(&floor);
+
+            // Updates whose block is no longer canonical
+            let ChainView { blocks, inclusions } = &mut *view;
+            let mut suspects = Vec::new();
+            for (number, updates) in inclusions.iter_mut() {
+                let Some(canonical) = blocks.get(number) else { continue };
+                let (stale, kept): (Vec<_>, Vec<_>) = updates.drain(..).partition(|u| u.block_hash != *canonical);
+                *updates = kept;
+                suspects.extend(stale.into_iter().map(|u| (*number, u)));
+            }
+            inclusions.retain(|_, updates| !updates.is_empty());
+            suspects
+        };
+
+        for (number, update) in suspects {
+            self.resolve(rpc, number, update).await;
+        }
+        Ok(())
+    }
+
+    /// An update's block was replaced: was it re-included?
+    async fn resolve(&self, rpc: &dyn JsonRpc, number: u64, update: Inclusion) {
+        let receipt = match rpc.call("eth_getTransactionReceipt", json!([update.tx_hash])).await {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                warn!("⚠️ [{}] Could not look up reorged update {}: {:?}", update.label, update.tx_hash, e);
+                Value::Null
+            }
+        };
+
+        if receipt.is_null() {
+            self.counts.write().dropped_updates += 1;
+            error!("🚨 [{}] Update {} in block {} was dropped by a reorg, re-publishing", update.label, update.tx_hash, number);
+            if let Some(republish) = &update.republish {
+                republish.store(true, Ordering::Relaxed);
+            }
+        } else {
+            self.counts.write().reincluded_updates += 1;
+            info!("🔀 [{}] Update {} re-included in block {}", update.label, update.tx_hash, receipt["blockNumber"]);
+        }
+    }
+}
+
+/// Number, hash and parent hash of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRef {
+    pub number: u64,
+    pub hash: B256,
+    pub parent: B256,
+}
+
+/// `eth_getBlockByNumber` for `block` (`latest` or a hex number)
+pub async fn fetch_block(rpc: &dyn JsonRpc, block: &str) -> Result<BlockRef> {
+    let block = rpc
+        .call("eth_getBlockByNumber", json!([block, false]))
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    let number = block["number"].as_str().context("block has no number")?;
+    let number = U256::from_str_radix(number.trim_start_matches("0x"), 16)?.saturating_to();
+    let hash = block["hash"].as_str().context("block has no hash")?.parse()?;
+    let parent = block["parentHash"].as_str().context("block has no parent hash")?.parse()?;
+    Ok(BlockRef { number, hash, parent })
+}
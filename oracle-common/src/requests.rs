@@ -0,0 +1,210 @@
+//! Request events from chain logs.
+//!
+//! A feed with `[feeds.requests]` publishes only when a contract logs a
+//! request for it (e.g. `RequestPrice(string feedId)`), turning the push
+//! pipeline into request/response. A [`RequestWatcher`] polls `eth_getLogs`
+//! for one contract and event and counts unserved requests per feed id; the
+//! feed's trigger answers them at its next tick, so a request is served
+//! within one `interval_ms`.
+//!
+//! The feed id is the event's first parameter: an indexed string is matched
+//! by its topic hash, a non-indexed one is decoded from the log data.
+//! Watching starts at the chain head when spawned; earlier requests are not
+//! replayed.
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use anyhow::{anyhow, Context, Result};
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::feeds::FeedRequests;
+use crate::reorg::fetch_block;
+use crate::rpc_batch::RpcBatcher;
+
+/// Most blocks asked for in one `eth_getLogs`, so catching up after an
+/// outage doesn't hit provider range limits
+const MAX_BLOCK_RANGE: u64 = 1_000;
+
+/// First ABI-encoded `string` in `data`
+fn decode_first_string(data: &[u8]) -> Option<String> {
+    let word = |at: usize| -> Option<usize> {
+        let word = U256::from_be_slice(data.get(at..at.checked_add(32)?)?);
+        (word <= U256::from(usize::MAX)).then(|| word.to::<usize>())
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let bytes = data.get(start..start.checked_add(len)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+#[derive(Default)]
+struct RequestState {
+    /// Unserved requests by feed id
+    pending: HashMap<String, u64>,
+    /// Last block whose logs were read
+    last_block: Option<u64>,
+    received: u64,
+    poll_failures: u64,
+}
+
+pub struct RequestWatcher {
+    contract: Address,
+    event: String,
+    topic: B256,
+    poll_interval: Duration,
+    /// Watched feed ids by `keccak256(id)`, for indexed string parameters
+    feeds: RwLock<HashMap<B256, String>>,
+    state: RwLock<RequestState>,
+}
+
+impl RequestWatcher {
+    pub fn new(contract: Address, event: impl Into<String>, poll_interval: Duration) -> Self {
+        let event = event.into();
+        Self {
+            contract,
+            topic: keccak256(event.as_bytes()),
+            event,
+            poll_interval: poll_interval.max(Duration::from_millis(50)),
+            feeds: RwLock::new(HashMap::new()),
+            state: RwLock::new(RequestState::default()),
+        }
+    }
+
+    /// Watcher for a feed's `[feeds.requests]`, before feed ids are added
+    pub fn for_feed(contract: Address, interval_ms: u64, requests: &FeedRequests) -> Self {
+        Self::new(
+            requests.contract.unwrap_or(contract),
+            requests.event.clone(),
+            Duration::from_millis(requests.poll_ms.unwrap_or(interval_ms)),
+        )
+    }
+
+    pub fn contract(&self) -> Address {
+        self.contract
+    }
+
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// Poll at least every `interval` (a feed sharing the watcher wants it faster)
+    pub fn poll_at_least_every(&mut self, interval: Duration) {
+        self.poll_interval = self.poll_interval.min(interval.max(Duration::from_millis(50)));
+    }
+
+    /// Count requests for `feed_id`
+    pub fn watch(&self, feed_id: &str) {
+        self.feeds.write().insert(keccak256(feed_id.as_bytes()), feed_id.to_string());
+    }
+
+    /// Watched feed a log requests, if it is our event
+    pub fn match_log(&self, topics: &[B256], data: &[u8]) -> Option<String> {
+        if topics.first() != Some(&self.topic) {
+            return None;
+        }
+        let feeds = self.feeds.read();
+        if let Some(feed_id) = topics.get(1).and_then(|topic| feeds.get(topic)) {
+            return Some(feed_id.clone());
+        }
+        decode_first_string(data).filter(|id| feeds.values().any(|watched| watched == id))
+    }
+
+    /// Count a log if it requests a watched feed
+    pub fn record_log(&self, topics: &[B256], data: &[u8]) -> Option<String> {
+        let feed_id = self.match_log(topics, data)?;
+        let mut state = self.state.write();
+        *state.pending.entry(feed_id.clone()).or_default() += 1;
+        state.received += 1;
+        Some(feed_id)
+    }
+
+    /// Unserved requests for `feed_id`
+    pub fn pending(&self, feed_id: &str) -> u64 {
+        self.state.read().pending.get(feed_id).copied().unwrap_or(0)
+    }
+
+    /// Claim the unserved requests for `feed_id` for an update about to go out
+    pub fn take(&self, feed_id: &str) -> u64 {
+        self.state.write().pending.remove(feed_id).unwrap_or(0)
+    }
+
+    /// The update answering `count` requests failed; serve them again
+    pub fn restore(&self, feed_id: &str, count: u64) {
+        if count > 0 {
+            *self.state.write().pending.entry(feed_id.to_string()).or_default() += count;
+        }
+    }
+
+    async fn poll(&self, rpc: &RpcBatcher) -> Result<()> {
+        let head = fetch_block(rpc, "latest").await?.number;
+        let Some(last) = self.state.read().last_block else {
+            self.state.write().last_block = Some(head);
+            return Ok(());
+        };
+        if head <= last {
+            return Ok(());
+        }
+
+        let to = head.min(last + MAX_BLOCK_RANGE);
+        let logs = rpc
+            .call(
+                "eth_getLogs",
+                json!([{
+                    "address": self.contract,
+                    "topics": [self.topic],
+                    "fromBlock": format!("{:#x}", last + 1),
+                    "toBlock": format!("{:#x}", to),
+                }]),
+            )
+            .await
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        for log in logs.as_array().context("eth_getLogs returned no array")? {
+            let topics = log["topics"]
+                .as_array()
+                .map(|topics| topics.iter().filter_map(|t| t.as_str()?.parse().ok()).collect::<Vec<B256>>())
+                .unwrap_or_default();
+            let data = log["data"].as_str().and_then(|d| alloy::hex::decode(d).ok()).unwrap_or_default();
+            if let Some(feed_id) = self.record_log(&topics, &data) {
+                info!("📨 {} requested via {} (tx {})", feed_id, self.event, log["transactionHash"]);
+            }
+        }
+        self.state.write().last_block = Some(to);
+        debug!("{} logs read up to block {}", self.event, to);
+        Ok(())
+    }
+
+    /// Read request logs every poll interval over `rpc_urls`
+    pub fn spawn(self: &Arc<Self>, rpc_urls: Vec<String>) -> JoinHandle<()> {
+        let watcher = self.clone();
+        tokio::spawn(async move {
+            let rpc = RpcBatcher::shared(&rpc_urls);
+            let mut ticker = tokio::time::interval(watcher.poll_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = watcher.poll(&rpc).await {
+                    watcher.state.write().poll_failures += 1;
+                    warn!("⚠️ Failed to read {} logs from {}: {}", watcher.event, watcher.contract, e);
+                }
+            }
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.read();
+        json!({
+            "contract": self.contract.to_string(),
+            "event": self.event,
+            "last_block": state.last_block,
+            "pending": state.pending,
+            "received": state.received,
+            "poll_failures": state.poll_failures,
+        })
+    }
+}
@@ -0,0 +1,245 @@
+//! Generic REST JSON poller source.
+//!
+//! Covers one-off feeds without new code: feeds with `source = "rest"` use
+//! the endpoint URL as their symbol and pick the value out of the response
+//! with `json_path`. A raw integer value (e.g. 8-decimal fixed point) is
+//! scaled down by `source_decimals` before the feed's own `decimals` apply.
+//!
+//! ```toml
+//! [[feeds]]
+//! id = "STETHETH"
+//! source = "rest"
+//! symbol = "https://api.example.com/v1/rates?pair=steth-eth"
+//! json_path = "$.data.rates[0].value"
+//! source_decimals = 8
+//! poll_ms = 10000
+//! ```
+//!
+//! Paths support `$`, `.key`, `['key']` and `[index]`; numbers and numeric
+//! strings are accepted. Feeds reading the same URL share one poller.
+
+use anyhow::{anyhow, bail, Context, Result};
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::feed_trigger::{PricePoint, PriceSource};
+use crate::feeds::{FeedConfig, SourceKind};
+use crate::shutdown::ShutdownCoordinator;
+
+const DEFAULT_POLL_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parsed JSONPath (the `$.a.b[0]['c']` subset)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let raw = path.trim();
+        let mut rest = raw.strip_prefix('$').unwrap_or(raw);
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                if end == 0 {
+                    bail!("Empty key in JSON path '{}'", raw);
+                }
+                segments.push(Segment::Key(after[..end].to_string()));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(|| anyhow!("Unclosed '[' in JSON path '{}'", raw))?;
+                let inner = after[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                segments.push(match quoted {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None => Segment::Index(inner.parse().map_err(|_| anyhow!("Invalid index '{}' in JSON path '{}'", inner, raw))?),
+                });
+                rest = &after[end + 1..];
+            } else if segments.is_empty() && raw == rest {
+                // Bare `key.other` without the leading `$.`
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                segments.push(Segment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+            } else {
+                bail!("Unexpected '{}' in JSON path '{}'", rest, raw);
+            }
+        }
+
+        if segments.is_empty() {
+            bail!("JSON path '{}' selects no value", raw);
+        }
+        Ok(Self { raw: raw.to_string(), segments })
+    }
+
+    /// The number at this path in `value`
+    pub fn extract(&self, value: &Value) -> Result<f64> {
+        let mut current = value;
+        for segment in &self.segments {
+            current = match segment {
+                Segment::Key(key) => current.get(key.as_str()),
+                Segment::Index(index) => current.get(*index),
+            }
+            .ok_or_else(|| anyhow!("Nothing at '{}'", self.raw))?;
+        }
+        let number = match current {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+        number
+            .filter(|n| n.is_finite())
+            .ok_or_else(|| anyhow!("Value at '{}' is not a number: {}", self.raw, current))
+    }
+}
+
+impl std::fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// `raw / 10^decimals`
+pub fn scale_down(raw: f64, decimals: u8) -> f64 {
+    raw / 10f64.powi(decimals as i32)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Latest value one poller extracted for a feed
+pub struct RestJsonSource {
+    latest: Arc<RwLock<Option<PricePoint>>>,
+}
+
+impl PriceSource for RestJsonSource {
+    fn latest(&self) -> Option<PricePoint> {
+        self.latest.read().clone()
+    }
+}
+
+struct Extraction {
+    path: JsonPath,
+    source_decimals: u8,
+    latest: Arc<RwLock<Option<PricePoint>>>,
+}
+
+struct EndpointPoller {
+    url: String,
+    interval: Duration,
+    extractions: Vec<Extraction>,
+}
+
+impl EndpointPoller {
+    fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut ticker = tokio::time::interval(self.interval);
+            let mut first = true;
+            loop {
+                ticker.tick().await;
+
+                let body = match client.get(&self.url).timeout(Duration::from_secs(10)).send().await {
+                    Ok(response) => match response.error_for_status() {
+                        Ok(response) => response.json::<Value>().await,
+                        Err(e) => Err(e),
+                    },
+                    Err(e) => Err(e),
+                };
+                let body = match body {
+                    Ok(body) => body,
+                    Err(e) => {
+                        warn!("⚠️ Failed to poll {}: {}", self.url, e);
+                        continue;
+                    }
+                };
+
+                for extraction in &self.extractions {
+                    match extraction.path.extract(&body) {
+                        Ok(raw) => {
+                            let value = scale_down(raw, extraction.source_decimals);
+                            if first {
+                                info!("🌐 {} {}: {}", self.url, extraction.path, value);
+                            }
+                            debug!("🌐 {} {}: {}", self.url, extraction.path, value);
+                            *extraction.latest.write() = Some(PricePoint {
+                                price: value,
+                                num_trades: 1,
+                                timestamp_ms: now_ms(),
+                                confidence: None,
+                            });
+                        }
+                        Err(e) => warn!("⚠️ {}: {}", self.url, e),
+                    }
+                }
+                first = false;
+            }
+        })
+    }
+}
+
+/// Collects the endpoints to poll, then starts one poller per URL
+#[derive(Default)]
+pub struct RestSources {
+    pollers: Vec<EndpointPoller>,
+}
+
+impl RestSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Source for `feed`, whose symbol is the URL and whose `json_path`
+    /// selects the value
+    pub fn add(&mut self, feed: &FeedConfig) -> Result<Arc<dyn PriceSource>> {
+        if feed.source != SourceKind::Rest {
+            bail!("Feed '{}': {:?} is not a REST source", feed.id, feed.source);
+        }
+        let path = feed
+            .json_path
+            .as_deref()
+            .ok_or_else(|| anyhow!("Feed '{}': rest sources need a json_path", feed.id))
+            .and_then(JsonPath::parse)
+            .with_context(|| format!("Feed '{}'", feed.id))?;
+        let interval = Duration::from_millis(feed.poll_ms.unwrap_or(DEFAULT_POLL_MS).max(100));
+
+        let latest = Arc::new(RwLock::new(None));
+        let extraction = Extraction { path, source_decimals: feed.source_decimals, latest: latest.clone() };
+        match self.pollers.iter_mut().find(|p| p.url == feed.symbol) {
+            // Shared endpoints follow the most frequent feed
+            Some(poller) => {
+                poller.interval = poller.interval.min(interval);
+                poller.extractions.push(extraction);
+            }
+            None => self.pollers.push(EndpointPoller {
+                url: feed.symbol.clone(),
+                interval,
+                extractions: vec![extraction],
+            }),
+        }
+        Ok(Arc::new(RestJsonSource { latest }))
+    }
+
+    /// Start polling every endpoint added so far
+    pub fn spawn(self, shutdown: &mut ShutdownCoordinator) {
+        for poller in self.pollers {
+            shutdown.register("rest poller", poller.spawn());
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Revert data decoding.
+//!
+//! Turns revert data into a readable reason: `Error(string)`,
+//! `Panic(uint256)` and custom errors, both the ones our oracle contracts
+//! declare and any from ABI files listed in `REVERT_ABI_PATHS`
+//! (comma-separated, plain ABI arrays or forge artifacts).
+
+use alloy::dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy::json_abi::{Error as AbiError, JsonAbi};
+use alloy::primitives::{hex, Selector, U256};
+use alloy::sol_types::{Panic, Revert, SolError};
+use anyhow::{Context, Result};
+use nonzu_sdk::RiseError;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Custom errors declared by TimeOracle.sol and PriceOracleV2.sol
+const ORACLE_ERRORS: &[&str] = &[
+    "error InvalidTimestamp(uint256 provided, uint256 current)",
+    "error UnauthorizedUpdater(address caller)",
+    "error TimestampValidationFailed(string reason)",
+    "error RoundOutOfOrder(uint256 provided, uint256 current)",
+    "error InvalidPrice()",
+    "error StaleRound(uint256 provided, uint256 current)",
+    "error InvalidReveal(bytes32 preimage, bytes32 lastReveal)",
+    "error StaleEpoch(uint256 provided, uint256 current)",
+    "error InvalidBlockHash()",
+    "error ParentMismatch(uint256 number, bytes32 stored, bytes32 parentHash)",
+];
+
+/// A decoded revert
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// `require(..., "message")` / `revert("message")`
+    Message(String),
+    /// Compiler-inserted `Panic(uint256)`
+    Panic(U256),
+    /// Custom error, arguments already formatted
+    Custom { name: String, args: Vec<(String, String)> },
+    /// Data we could not decode
+    Unknown(Vec<u8>),
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Message(message) => write!(f, "\"{}\"", message),
+            Self::Panic(code) => write!(f, "Panic(0x{:02x}): {}", code, panic_description(*code)),
+            Self::Custom { name, args } => {
+                let args: Vec<String> = args
+                    .iter()
+                    .map(|(name, value)| if name.is_empty() { value.clone() } else { format!("{}={}", name, value) })
+                    .collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Self::Unknown(data) if data.is_empty() => f.write_str("no revert data"),
+            Self::Unknown(data) => write!(f, "unknown revert 0x{}", hex::encode(data)),
+        }
+    }
+}
+
+fn panic_description(code: U256) -> &'static str {
+    match code.saturating_to::<u64>() {
+        0x00 => "generic panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "corrupt storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => "unknown panic code",
+    }
+}
+
+fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Int(i, _) => i.to_string(),
+        DynSolValue::Uint(u, _) => u.to_string(),
+        DynSolValue::Address(a) => a.to_checksum(None),
+        DynSolValue::String(s) => format!("\"{}\"", s),
+        DynSolValue::Bytes(b) => format!("0x{}", hex::encode(b)),
+        DynSolValue::FixedBytes(b, size) => format!("0x{}", hex::encode(&b[..*size])),
+        DynSolValue::Array(items) | DynSolValue::FixedArray(items) | DynSolValue::Tuple(items) => {
+            format!("[{}]", items.iter().map(format_value).collect::<Vec<_>>().join(", "))
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// Selector -> custom error lookup
+#[derive(Debug, Clone)]
+pub struct RevertDecoder {
+    errors: HashMap<Selector, AbiError>,
+}
+
+impl Default for RevertDecoder {
+    /// Knows the oracle contracts' custom errors
+    fn default() -> Self {
+        let mut decoder = Self::empty();
+        for signature in ORACLE_ERRORS {
+            decoder.add_error(AbiError::parse(signature).expect("built-in error signature is valid"));
+        }
+        decoder
+    }
+}
+
+impl RevertDecoder {
+    /// Only `Error(string)` and `Panic(uint256)`
+    pub fn empty() -> Self {
+        Self { errors: HashMap::new() }
+    }
+
+    pub fn add_error(&mut self, error: AbiError) -> &mut Self {
+        self.errors.insert(error.selector(), error);
+        self
+    }
+
+    /// Register every custom error in `abi`
+    pub fn with_abi(mut self, abi: &JsonAbi) -> Self {
+        for error in abi.errors() {
+            self.add_error(error.clone());
+        }
+        self
+    }
+
+    /// Register the errors from an ABI JSON array or a forge artifact
+    pub fn with_abi_json(self, json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let abi = value.get("abi").cloned().unwrap_or(value);
+        let abi: JsonAbi = serde_json::from_value(abi)?;
+        Ok(self.with_abi(&abi))
+    }
+
+    /// Oracle errors plus the ABIs in `REVERT_ABI_PATHS`
+    pub fn from_env() -> Result<Self> {
+        let mut decoder = Self::default();
+        if let Ok(paths) = std::env::var("REVERT_ABI_PATHS") {
+            for path in paths.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read ABI {}", path))?;
+                decoder = decoder.with_abi_json(&json).with_context(|| format!("Invalid ABI {}", path))?;
+            }
+        }
+        Ok(decoder)
+    }
+
+    pub fn decode(&self, data: &[u8]) -> RevertReason {
+        if data.len() < 4 {
+            return RevertReason::Unknown(data.to_vec());
+        }
+        if let Ok(revert) = Revert::abi_decode(data, true) {
+            return RevertReason::Message(revert.reason);
+        }
+        if let Ok(panic) = Panic::abi_decode(data, true) {
+            return RevertReason::Panic(panic.code);
+        }
+        let selector = Selector::from_slice(&data[..4]);
+        if let Some(error) = self.errors.get(&selector) {
+            if let Ok(values) = error.abi_decode_input(&data[4..], true) {
+                return RevertReason::Custom {
+                    name: error.name.clone(),
+                    args: error.inputs.iter().map(|p| p.name.clone()).zip(values.iter().map(format_value)).collect(),
+                };
+            }
+        }
+        RevertReason::Unknown(data.to_vec())
+    }
+
+    /// Readable reason for a reverted transaction, or `None` if `error` is
+    /// not a revert. Falls back to the node's own reason string.
+    pub fn describe(&self, error: &RiseError) -> Option<String> {
+        match error {
+            RiseError::ContractReverted { reason, data } => match data.as_deref().map(|d| self.decode(d)) {
+                Some(RevertReason::Unknown(_)) | None if !reason.is_empty() => Some(reason.clone()),
+                Some(decoded) => Some(decoded.to_string()),
+                None => Some("no revert data".to_string()),
+            },
+            other => {
+                let message = other.to_string();
+                if !message.to_lowercase().contains("revert") {
+                    return None;
+                }
+                // Nodes often append the revert data to the message
+                let data = message
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .filter_map(|word| word.strip_prefix("0x"))
+                    .filter(|word| word.len() >= 8)
+                    .find_map(|word| hex::decode(word).ok());
+                Some(match data.map(|d| self.decode(&d)) {
+                    Some(RevertReason::Unknown(_)) | None => message,
+                    Some(decoded) => decoded.to_string(),
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Per-feed round ids, kept across restarts.
+//!
+//! Feeds published with a round (`updatePriceWithRound(string,uint256,uint80,uint256)`)
+//! number their updates 1, 2, 3, ... so consumers can spot a missed update
+//! as a gap and reject a late transaction whose round doesn't advance.
+//!
+//! A round is only issued once its update has been admitted for sending,
+//! and an update that surely didn't publish (cancelled before signing, or
+//! reverted) hands its round to the next one while no later round was
+//! issued. Held and cancelled updates leave no gaps; an update whose fate
+//! is unknown, such as a receipt timeout, leaves one rather than risk
+//! sending its round twice.
+//!
+//! Rounds are reserved in blocks of `ROUND_BLOCK`: the end of a block is
+//! written to a small JSON file before any round in it is used, and a
+//! restart continues after it. A crash therefore skips at most one block's
+//! unused rounds but never hands out a round the contract has already
+//! seen. A clean shutdown writes the exact last round, so a restart
+//! continues without a gap.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `ROUND_STATE_PATH` | `feed-rounds.json` | Where the reserved rounds per feed are kept |
+//! | `ROUND_BLOCK` | `100` | Rounds reserved per write |
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+const DEFAULT_BLOCK: u64 = 100;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FeedRounds {
+    /// Last round issued
+    issued: u64,
+    /// Highest round on disk; rounds up to it may be used
+    reserved: u64,
+}
+
+#[derive(Default)]
+struct RoundState {
+    feeds: BTreeMap<String, FeedRounds>,
+    /// Rounds issued since the last write
+    dirty: bool,
+}
+
+pub struct RoundStore {
+    /// `None` keeps rounds in memory only
+    path: Option<PathBuf>,
+    block: u64,
+    state: Mutex<RoundState>,
+}
+
+impl RoundStore {
+    /// Rounds starting from 1 on every start
+    pub fn in_memory() -> Self {
+        Self { path: None, block: DEFAULT_BLOCK, state: Mutex::new(RoundState::default()) }
+    }
+
+    /// Rounds continued from `path`, which is created on the first reservation
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let rounds: BTreeMap<String, u64> = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).with_context(|| format!("Invalid round state in {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let feeds = rounds.into_iter().map(|(feed, round)| (feed, FeedRounds { issued: round, reserved: round })).collect();
+        Ok(Self { path: Some(path), block: DEFAULT_BLOCK, state: Mutex::new(RoundState { feeds, dirty: false }) })
+    }
+
+    /// Reserve `block` rounds per write
+    pub fn with_block(mut self, block: u64) -> Self {
+        self.block = block.max(1);
+        self
+    }
+
+    /// From `ROUND_STATE_PATH` (default `feed-rounds.json`) and `ROUND_BLOCK`
+    pub fn from_env() -> Result<Self> {
+        let path = std::env::var("ROUND_STATE_PATH").unwrap_or_else(|_| "feed-rounds.json".to_string());
+        let block = match std::env::var("ROUND_BLOCK") {
+            Ok(v) => v.parse().context("Invalid ROUND_BLOCK")?,
+            Err(_) => DEFAULT_BLOCK,
+        };
+        let store = Self::open(&path)?.with_block(block);
+        info!("🔢 Feed rounds kept in {} ({} feed(s) continued)", path, store.state.lock().feeds.len());
+        Ok(store)
+    }
+
+    /// The round `feed`'s next update uses, reserving a new block on disk
+    /// first when it is past the reserved ones. Nothing is issued until
+    /// [`issue`](Self::issue).
+    pub fn next_round(&self, feed: &str) -> Result<u64> {
+        let mut state = self.state.lock();
+        let rounds = state.feeds.get(feed).copied().unwrap_or_default();
+        let round = rounds.issued + 1;
+        if let Some(path) = self.path.as_ref().filter(|_| round > rounds.reserved) {
+            let mut reserved: BTreeMap<&str, u64> =
+                state.feeds.iter().map(|(feed, rounds)| (feed.as_str(), rounds.reserved)).collect();
+            let end = rounds.issued + self.block;
+            reserved.insert(feed, end);
+            write_atomically(path, &serde_json::to_string_pretty(&reserved)?)?;
+            state.feeds.entry(feed.to_string()).or_default().reserved = end;
+        }
+        Ok(round)
+    }
+
+    /// `round` of `feed` was admitted for sending
+    pub fn issue(&self, feed: &str, round: u64) {
+        let mut state = self.state.lock();
+        let rounds = state.feeds.entry(feed.to_string()).or_default();
+        rounds.issued = rounds.issued.max(round);
+        state.dirty = true;
+    }
+
+    /// The update carrying `round` surely didn't publish it: its round goes
+    /// to the next update unless a later one was issued meanwhile. The round
+    /// stays within the reservation on disk, so a crash after reusing it
+    /// restarts past it.
+    pub fn release(&self, feed: &str, round: u64) {
+        let mut state = self.state.lock();
+        if let Some(rounds) = state.feeds.get_mut(feed) {
+            if rounds.issued == round {
+                rounds.issued = round - 1;
+                state.dirty = true;
+            }
+        }
+    }
+
+    /// The last round issued for `feed`
+    pub fn last_round(&self, feed: &str) -> Option<u64> {
+        self.state.lock().feeds.get(feed).map(|rounds| rounds.issued).filter(|issued| *issued > 0)
+    }
+
+    /// Write the exact last rounds, giving back the unused reservations, if
+    /// any were issued since the last write. Called on shutdown: a restart
+    /// then continues without a gap.
+    pub fn flush(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let mut state = self.state.lock();
+        if !state.dirty {
+            return Ok(());
+        }
+        let issued: BTreeMap<&str, u64> = state.feeds.iter().map(|(feed, rounds)| (feed.as_str(), rounds.issued)).collect();
+        write_atomically(path, &serde_json::to_string_pretty(&issued)?)?;
+        for rounds in state.feeds.values_mut() {
+            rounds.reserved = rounds.issued;
+        }
+        state.dirty = false;
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.lock();
+        json!({
+            "path": self.path.as_ref().map(|p| p.display().to_string()),
+            "block": self.block,
+            "rounds": state.feeds.iter().map(|(feed, rounds)| (feed.clone(), rounds.issued)).collect::<BTreeMap<_, _>>(),
+            "reserved": state.feeds.iter().map(|(feed, rounds)| (feed.clone(), rounds.reserved)).collect::<BTreeMap<_, _>>(),
+        })
+    }
+}
+
+fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
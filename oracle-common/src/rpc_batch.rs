@@ -0,0 +1,203 @@
+//! JSON-RPC batching for auxiliary calls.
+//!
+//! Balance checks, receipt read-backs, reorg checks and on-chain condition /
+//! request polls don't need to be fast, but each one used to take a request
+//! (and on a busy moment a connection) from the same small VM that sends the
+//! latency-critical updates. They now go through an [`RpcBatcher`], which
+//! queues calls and sends them every `RPC_BATCH_MS` (default 50, 0 disables
+//! batching) as one JSON-RPC batch of at most `RPC_BATCH_MAX` calls (default
+//! 20) over its own connection pool. Transaction submission never does.
+//!
+//! Endpoints that reject batches get the queued calls one by one instead.
+
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+
+use crate::submit::{JsonRpc, RpcCallError, RpcEndpoints};
+
+struct Queued {
+    method: String,
+    params: Value,
+    reply: oneshot::Sender<Result<Value, RpcCallError>>,
+}
+
+pub struct RpcBatcher {
+    rpc: Arc<RpcEndpoints>,
+    /// `None` when batching is disabled and calls go straight out
+    queue: Option<mpsc::UnboundedSender<Queued>>,
+    interval: Duration,
+    max_batch: usize,
+    /// Set once an endpoint rejected a batch
+    unsupported: AtomicBool,
+    batches: AtomicU64,
+    calls: AtomicU64,
+}
+
+impl RpcBatcher {
+    /// Batcher sending queued calls every `interval` (zero disables batching)
+    pub fn spawn(rpc_urls: Vec<String>, interval: Duration, max_batch: usize) -> Arc<Self> {
+        let rpc = Arc::new(RpcEndpoints::new(rpc_urls));
+        if interval.is_zero() {
+            return Arc::new(Self::unbatched(rpc));
+        }
+
+        let (queue, receiver) = mpsc::unbounded_channel();
+        let batcher = Arc::new(Self {
+            rpc,
+            queue: Some(queue),
+            interval,
+            max_batch: max_batch.max(1),
+            unsupported: AtomicBool::new(false),
+            batches: AtomicU64::new(0),
+            calls: AtomicU64::new(0),
+        });
+        tokio::spawn(batcher.clone().run(receiver));
+        batcher
+    }
+
+    fn unbatched(rpc: Arc<RpcEndpoints>) -> Self {
+        Self {
+            rpc,
+            queue: None,
+            interval: Duration::ZERO,
+            max_batch: 1,
+            unsupported: AtomicBool::new(false),
+            batches: AtomicU64::new(0),
+            calls: AtomicU64::new(0),
+        }
+    }
+
+    /// The process-wide batcher for `rpc_urls`, started on first use with
+    /// `RPC_BATCH_MS` and `RPC_BATCH_MAX`
+    pub fn shared(rpc_urls: &[String]) -> Arc<Self> {
+        static BATCHERS: OnceLock<Mutex<HashMap<Vec<String>, Arc<RpcBatcher>>>> = OnceLock::new();
+        BATCHERS
+            .get_or_init(Default::default)
+            .lock()
+            .entry(rpc_urls.to_vec())
+            .or_insert_with(|| {
+                let (interval, max_batch) = batching_from_env();
+                Self::spawn(rpc_urls.to_vec(), interval, max_batch)
+            })
+            .clone()
+    }
+
+    /// Queue `method` for the next batch and wait for its result
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, RpcCallError> {
+        let Some(queue) = &self.queue else {
+            return self.rpc.call(method, params).await;
+        };
+        let (reply, result) = oneshot::channel();
+        queue
+            .send(Queued { method: method.to_string(), params, reply })
+            .map_err(|_| RpcCallError::Transport("RPC batcher stopped".to_string()))?;
+        result
+            .await
+            .unwrap_or_else(|_| Err(RpcCallError::Transport("RPC batcher dropped the call".to_string())))
+    }
+
+    async fn run(self: Arc<Self>, mut receiver: mpsc::UnboundedReceiver<Queued>) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut queued = Vec::new();
+        loop {
+            tokio::select! {
+                call = receiver.recv() => match call {
+                    Some(call) => {
+                        queued.push(call);
+                        if queued.len() >= self.max_batch {
+                            self.flush(std::mem::take(&mut queued)).await;
+                        }
+                    }
+                    None => return,
+                },
+                _ = ticker.tick() => {
+                    if !queued.is_empty() {
+                        self.flush(std::mem::take(&mut queued)).await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn flush(&self, queued: Vec<Queued>) {
+        self.calls.fetch_add(queued.len() as u64, Ordering::Relaxed);
+        if queued.len() == 1 || self.unsupported.load(Ordering::Relaxed) {
+            self.send_each(queued).await;
+            return;
+        }
+
+        let calls: Vec<(String, Value)> = queued.iter().map(|q| (q.method.clone(), q.params.clone())).collect();
+        match self.rpc.call_batch(&calls).await {
+            Ok(results) => {
+                self.batches.fetch_add(1, Ordering::Relaxed);
+                debug!("📦 Sent {} auxiliary RPC calls in one batch", calls.len());
+                for (call, result) in queued.into_iter().zip(results) {
+                    let _ = call.reply.send(result);
+                }
+            }
+            Err(RpcCallError::Node(error)) => {
+                warn!("⚠️ RPC endpoint rejected a JSON-RPC batch ({}), sending calls one by one", error);
+                self.unsupported.store(true, Ordering::Relaxed);
+                self.send_each(queued).await;
+            }
+            Err(e) => {
+                for call in queued {
+                    let _ = call.reply.send(Err(e.clone()));
+                }
+            }
+        }
+    }
+
+    async fn send_each(&self, queued: Vec<Queued>) {
+        join_all(queued.into_iter().map(|call| async move {
+            let result = self.rpc.call(&call.method, call.params).await;
+            let _ = call.reply.send(result);
+        }))
+        .await;
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "interval_ms": self.interval.as_millis() as u64,
+            "max_batch": self.max_batch,
+            "batches": self.batches.load(Ordering::Relaxed),
+            "calls": self.calls.load(Ordering::Relaxed),
+            "batches_supported": !self.unsupported.load(Ordering::Relaxed),
+        })
+    }
+}
+
+#[async_trait]
+impl JsonRpc for RpcBatcher {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcCallError> {
+        RpcBatcher::call(self, method, params).await
+    }
+}
+
+/// `RPC_BATCH_MS` (default 50) and `RPC_BATCH_MAX` (default 20)
+fn batching_from_env() -> (Duration, usize) {
+    let interval_ms = match std::env::var("RPC_BATCH_MS") {
+        Ok(v) => v.parse().unwrap_or_else(|_| {
+            warn!("Invalid RPC_BATCH_MS '{}', using 50", v);
+            50
+        }),
+        Err(_) => 50,
+    };
+    let max_batch = match std::env::var("RPC_BATCH_MAX") {
+        Ok(v) => v.parse().unwrap_or_else(|_| {
+            warn!("Invalid RPC_BATCH_MAX '{}', using 20", v);
+            20
+        }),
+        Err(_) => 20,
+    };
+    (Duration::from_millis(interval_ms), max_batch)
+}
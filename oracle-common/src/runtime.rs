@@ -0,0 +1,115 @@
+//! Tokio runtime construction from the environment.
+//!
+//! The oracles run on low-spec shared VMs where the default runtime (one
+//! worker per visible CPU, 512 blocking threads, threads free to migrate)
+//! adds jitter. Binaries build their runtime with [`runtime_from_env`]:
+//!
+//! - `TOKIO_WORKER_THREADS`: worker count (tokio's default: one per CPU)
+//! - `TOKIO_MAX_BLOCKING_THREADS`: blocking pool cap (tokio's default: 512)
+//! - `TOKIO_PIN_CORES`: `auto` or a core list like `0,2-3`; worker threads
+//!   are pinned round-robin to those cores (unset: no pinning). Blocking
+//!   pool threads come and go and are left to the scheduler.
+
+use anyhow::{bail, Context, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+use tracing::{debug, warn};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    pub worker_threads: Option<usize>,
+    pub max_blocking_threads: Option<usize>,
+    /// Core ids to pin worker threads to
+    pub pin_cores: Option<Vec<usize>>,
+}
+
+/// Parse a core list like `0,2-3` into `[0, 2, 3]` (sorted, without repeats)
+pub fn parse_core_list(spec: &str) -> Result<Vec<usize>> {
+    let mut cores = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start: usize = start.trim().parse().with_context(|| format!("Invalid core '{}'", part))?;
+                let end: usize = end.trim().parse().with_context(|| format!("Invalid core '{}'", part))?;
+                if end < start {
+                    bail!("Invalid core range '{}'", part);
+                }
+                cores.extend(start..=end);
+            }
+            None => cores.push(part.parse().with_context(|| format!("Invalid core '{}'", part))?),
+        }
+    }
+    if cores.is_empty() {
+        bail!("Empty core list");
+    }
+    cores.sort_unstable();
+    cores.dedup();
+    Ok(cores)
+}
+
+impl RuntimeConfig {
+    pub fn from_env() -> Result<Self> {
+        let number = |name: &str| -> Result<Option<usize>> {
+            match std::env::var(name) {
+                Ok(v) => match v.parse::<usize>() {
+                    Ok(0) | Err(_) => bail!("Invalid {} '{}' (expected a positive number)", name, v),
+                    Ok(n) => Ok(Some(n)),
+                },
+                Err(_) => Ok(None),
+            }
+        };
+
+        let pin_cores = match std::env::var("TOKIO_PIN_CORES").as_deref() {
+            Err(_) | Ok("") | Ok("off") => None,
+            Ok("auto") => {
+                let cores = core_affinity::get_core_ids().context("Cannot list CPU cores for TOKIO_PIN_CORES=auto")?;
+                Some(cores.into_iter().map(|c| c.id).collect())
+            }
+            Ok(spec) => Some(parse_core_list(spec).context("Invalid TOKIO_PIN_CORES")?),
+        };
+
+        Ok(Self {
+            worker_threads: number("TOKIO_WORKER_THREADS")?,
+            max_blocking_threads: number("TOKIO_MAX_BLOCKING_THREADS")?,
+            pin_cores,
+        })
+    }
+
+    pub fn build(&self) -> Result<Runtime> {
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all();
+        if let Some(blocking) = self.max_blocking_threads {
+            builder.max_blocking_threads(blocking);
+        }
+        // tokio's default, made explicit so pinning knows how many workers start
+        let workers = self
+            .worker_threads
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+        builder.worker_threads(workers);
+        if let Some(cores) = self.pin_cores.clone() {
+            // The workers are the first threads the runtime starts, so the
+            // first `workers` starts get the worker indexes and blocking pool
+            // threads after them are left alone
+            let next = Arc::new(AtomicUsize::new(0));
+            builder.on_thread_start(move || {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= workers {
+                    return;
+                }
+                let core = cores[index % cores.len()];
+                if core_affinity::set_for_current(core_affinity::CoreId { id: core }) {
+                    debug!("📌 Pinned runtime worker {} to core {}", index, core);
+                } else {
+                    warn!("Failed to pin runtime worker {} to core {}", index, core);
+                }
+            });
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Runtime configured by `TOKIO_*`; call after loading `.env`
+pub fn runtime_from_env() -> Result<Runtime> {
+    RuntimeConfig::from_env()?.build()
+}
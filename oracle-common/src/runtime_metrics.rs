@@ -0,0 +1,235 @@
+//! Tokio runtime and process resource metrics.
+//!
+//! The oracles run on shared-CPU VMs where drift spikes usually mean the
+//! runtime was starved rather than the chain being slow. A probe task sleeps
+//! `RUNTIME_PROBE_MS` (default 10, 0 disables) at a time and records how
+//! late it wakes up; a watchdog thread flags stalls where the runtime made
+//! no progress for `RUNTIME_STALL_MS` (default 250). Process CPU, RSS, run
+//! queue wait and VM steal time come from `/proc` (Linux only).
+//!
+//! Served at `/runtime` by the status server, along with the exchange
+//! websockets' bandwidth (see `ws_bandwidth`).
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::stats::Distribution;
+use crate::ws_bandwidth::ws_bandwidth_json;
+
+/// Wake-up lateness samples kept (~10s at the default probe interval)
+const MAX_SAMPLES: usize = 1000;
+
+/// Linux USER_HZ, the unit of /proc CPU times
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// CPU counters at the previous `/runtime` read, for rates
+struct CpuSample {
+    at: Instant,
+    process_ticks: u64,
+    runqueue_wait_ns: Option<u64>,
+    steal_ticks: Option<u64>,
+    total_ticks: Option<u64>,
+}
+
+pub struct RuntimeMonitor {
+    probe_interval: Duration,
+    stall_threshold: Duration,
+    started: Instant,
+    lateness_ms: Mutex<Distribution>,
+    max_lateness_ms: Mutex<f64>,
+    /// Milliseconds since `started` at the last probe wake-up
+    heartbeat_ms: AtomicU64,
+    stalls: AtomicU64,
+    longest_stall_ms: AtomicU64,
+    last_cpu: Mutex<Option<CpuSample>>,
+}
+
+impl RuntimeMonitor {
+    pub fn spawn(probe_interval: Duration, stall_threshold: Duration) -> (Arc<Self>, JoinHandle<()>) {
+        let monitor = Arc::new(Self {
+            probe_interval,
+            stall_threshold,
+            started: Instant::now(),
+            lateness_ms: Mutex::new(Distribution::new(MAX_SAMPLES)),
+            max_lateness_ms: Mutex::new(0.0),
+            heartbeat_ms: AtomicU64::new(0),
+            stalls: AtomicU64::new(0),
+            longest_stall_ms: AtomicU64::new(0),
+            last_cpu: Mutex::new(None),
+        });
+
+        // The watchdog is an OS thread so it keeps running when the runtime doesn't
+        let watchdog = Arc::downgrade(&monitor);
+        std::thread::Builder::new()
+            .name("runtime-watchdog".into())
+            .spawn(move || {
+                let mut stalled_since: Option<u64> = None;
+                while let Some(monitor) = watchdog.upgrade() {
+                    monitor.check_stall(&mut stalled_since);
+                    drop(monitor);
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+            })
+            .expect("spawn runtime watchdog");
+
+        let probe = monitor.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let scheduled = Instant::now() + probe.probe_interval;
+                tokio::time::sleep_until(scheduled.into()).await;
+                let late_ms = Instant::now().saturating_duration_since(scheduled).as_secs_f64() * 1000.0;
+                probe.lateness_ms.lock().record(late_ms);
+                let mut max = probe.max_lateness_ms.lock();
+                *max = max.max(late_ms);
+                drop(max);
+                probe.heartbeat_ms.store(probe.started.elapsed().as_millis() as u64, Ordering::Relaxed);
+            }
+        });
+
+        (monitor, handle)
+    }
+
+    /// From `RUNTIME_PROBE_MS` / `RUNTIME_STALL_MS`; `None` when disabled
+    pub fn from_env() -> Result<Option<(Arc<Self>, JoinHandle<()>)>> {
+        let probe_ms: u64 = match std::env::var("RUNTIME_PROBE_MS") {
+            Ok(v) => v.parse().context("Invalid RUNTIME_PROBE_MS")?,
+            Err(_) => 10,
+        };
+        if probe_ms == 0 {
+            return Ok(None);
+        }
+        let stall_ms: u64 = match std::env::var("RUNTIME_STALL_MS") {
+            Ok(v) => v.parse().context("Invalid RUNTIME_STALL_MS")?,
+            Err(_) => 250,
+        };
+        info!("🩻 Runtime probe every {}ms, stall threshold {}ms", probe_ms, stall_ms);
+        Ok(Some(Self::spawn(Duration::from_millis(probe_ms), Duration::from_millis(stall_ms))))
+    }
+
+    fn check_stall(&self, stalled_since: &mut Option<u64>) {
+        let now_ms = self.started.elapsed().as_millis() as u64;
+        let heartbeat = self.heartbeat_ms.load(Ordering::Relaxed);
+        let silent_ms = now_ms.saturating_sub(heartbeat);
+        let threshold_ms = (self.probe_interval + self.stall_threshold).as_millis() as u64;
+
+        match *stalled_since {
+            // Don't flag the start-up gap before the first probe
+            None if heartbeat > 0 && silent_ms > threshold_ms => {
+                *stalled_since = Some(heartbeat);
+                self.stalls.fetch_add(1, Ordering::Relaxed);
+                warn!("🐌 Tokio runtime stalled: no progress for {}ms", silent_ms);
+            }
+            Some(since) if heartbeat != since => {
+                let stall_ms = heartbeat.saturating_sub(since);
+                self.longest_stall_ms.fetch_max(stall_ms, Ordering::Relaxed);
+                warn!("🐌 Tokio runtime recovered after ~{}ms stall", stall_ms);
+                *stalled_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let runtime = tokio::runtime::Handle::try_current().ok().map(|handle| {
+            let metrics = handle.metrics();
+            json!({
+                "workers": metrics.num_workers(),
+                "alive_tasks": metrics.num_alive_tasks(),
+                "global_queue_depth": metrics.global_queue_depth(),
+            })
+        });
+
+        json!({
+            "probe_interval_ms": self.probe_interval.as_millis() as u64,
+            "wake_lateness_ms": self.lateness_ms.lock().summary(),
+            "max_wake_lateness_ms": *self.max_lateness_ms.lock(),
+            "stalls": self.stalls.load(Ordering::Relaxed),
+            "longest_stall_ms": self.longest_stall_ms.load(Ordering::Relaxed),
+            "tokio": runtime,
+            "process": self.process_json(),
+            "websockets": ws_bandwidth_json(),
+        })
+    }
+
+    /// CPU rates are over the time since the previous call
+    fn process_json(&self) -> Value {
+        let Some(process_ticks) = proc_self_cpu_ticks() else {
+            return Value::Null;
+        };
+        let (steal_ticks, total_ticks) = proc_steal_ticks().unzip();
+        let sample = CpuSample {
+            at: Instant::now(),
+            process_ticks,
+            runqueue_wait_ns: proc_runqueue_wait_ns(),
+            steal_ticks,
+            total_ticks,
+        };
+
+        let mut last = self.last_cpu.lock();
+        let rates = last.as_ref().map(|prev| {
+            let elapsed = sample.at.duration_since(prev.at).as_secs_f64().max(1e-3);
+            let cpu_percent = sample.process_ticks.saturating_sub(prev.process_ticks) as f64 / CLOCK_TICKS_PER_SEC / elapsed * 100.0;
+            let runqueue_wait_ms_per_s = sample
+                .runqueue_wait_ns
+                .zip(prev.runqueue_wait_ns)
+                .map(|(now, then)| now.saturating_sub(then) as f64 / 1e6 / elapsed);
+            let steal_percent = match (sample.steal_ticks, prev.steal_ticks, sample.total_ticks, prev.total_ticks) {
+                (Some(s), Some(ps), Some(t), Some(pt)) if t > pt => Some(s.saturating_sub(ps) as f64 / (t - pt) as f64 * 100.0),
+                _ => None,
+            };
+            json!({
+                "cpu_percent": cpu_percent,
+                "runqueue_wait_ms_per_s": runqueue_wait_ms_per_s,
+                "steal_percent": steal_percent,
+                "window_secs": elapsed,
+            })
+        });
+        *last = Some(sample);
+
+        json!({
+            "rss_bytes": proc_rss_bytes(),
+            "cpu_seconds": process_ticks as f64 / CLOCK_TICKS_PER_SEC,
+            "since_last_read": rates,
+        })
+    }
+}
+
+/// utime + stime of this process, in clock ticks
+fn proc_self_cpu_ticks() -> Option<u64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // Fields after the parenthesised command name; utime and stime are 14 and 15
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    Some(fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?)
+}
+
+fn proc_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Time this process's threads spent runnable but waiting for a CPU
+fn proc_runqueue_wait_ns() -> Option<u64> {
+    let schedstat = std::fs::read_to_string("/proc/self/schedstat").ok()?;
+    schedstat.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// (steal, total) ticks across all CPUs from `/proc/stat`
+fn proc_steal_ticks() -> Option<(u64, u64)> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    let values: Vec<u64> = stat
+        .lines()
+        .next()?
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    Some((*values.get(7)?, values.iter().sum()))
+}
@@ -0,0 +1,88 @@
+//! Deadline-driven trigger scheduling.
+//!
+//! Instead of polling triggers at "interval minus 10ms" and hoping a poll
+//! lands just after the deadline, the orchestrator polls once per interval
+//! and the trigger sleeps until its exact next deadline (`sleep_until`)
+//! before building the request. `SCHEDULER=poll` restores the old behaviour.
+//!
+//! Timer-based sleeps are only accurate to about a millisecond, plus
+//! scheduler wake-up latency on a loaded VM. For 10-50ms cadences,
+//! `SCHEDULER=precise` sleeps until `SPIN_THRESHOLD_US` (default 2000)
+//! before the deadline and spins the rest of the way, trading some CPU for
+//! sub-millisecond tick accuracy.
+
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulerMode {
+    /// Poll triggers slightly faster than the update interval
+    Poll,
+    /// Poll once per interval and sleep until the exact deadline
+    Deadline,
+    /// Like `Deadline`, but sleep only until `spin` before the deadline and
+    /// busy-wait the remainder
+    Precise { spin: Duration },
+}
+
+/// Default spin window for `SCHEDULER=precise`
+const DEFAULT_SPIN_THRESHOLD_US: u64 = 2_000;
+
+impl SchedulerMode {
+    /// From `SCHEDULER` (`deadline` by default, `poll` or `precise`)
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var("SCHEDULER").as_deref() {
+            Err(_) | Ok("deadline") => Ok(Self::Deadline),
+            Ok("poll") => Ok(Self::Poll),
+            Ok("precise") => {
+                let spin_us = match std::env::var("SPIN_THRESHOLD_US") {
+                    Ok(v) => v.parse()?,
+                    Err(_) => DEFAULT_SPIN_THRESHOLD_US,
+                };
+                Ok(Self::Precise { spin: Duration::from_micros(spin_us) })
+            }
+            Ok(other) => Err(anyhow::anyhow!("Unknown SCHEDULER '{}' (expected deadline, poll or precise)", other)),
+        }
+    }
+
+    /// Orchestrator trigger-check interval for an update interval
+    pub fn check_interval(&self, update_interval: Duration) -> Duration {
+        match self {
+            Self::Poll => update_interval.saturating_sub(Duration::from_millis(10)).max(Duration::from_millis(50)),
+            Self::Deadline | Self::Precise { .. } => update_interval,
+        }
+    }
+
+    /// In deadline/precise mode, wait until `deadline` if it is at most
+    /// `horizon` away. Returns immediately in poll mode or when the deadline
+    /// is further.
+    pub async fn wait_for(&self, deadline: std::time::Instant, horizon: Duration) {
+        let spin = match self {
+            Self::Poll => return,
+            Self::Deadline => Duration::ZERO,
+            Self::Precise { spin } => *spin,
+        };
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() || remaining > horizon {
+            return;
+        }
+
+        if remaining > spin {
+            debug!("Sleeping {:?} until next deadline", remaining - spin);
+            tokio::time::sleep_until(Instant::now() + (remaining - spin)).await;
+        }
+        if spin.is_zero() {
+            return;
+        }
+
+        // Spin out the rest, yielding to other tasks while there is slack
+        while std::time::Instant::now() < deadline {
+            if deadline.saturating_duration_since(std::time::Instant::now()) > Duration::from_micros(200) {
+                tokio::task::yield_now().await;
+            } else {
+                std::hint::spin_loop();
+            }
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Shadow mode: compute prices but compare them against a reference oracle
+//! instead of publishing.
+//!
+//! Each interval the locally computed price is compared with the reference
+//! (a Chainlink aggregator or another PriceOracleV2 deployment) and the
+//! deviation is recorded. The resulting distribution is logged periodically,
+//! served on `/status` and written as a JSON report on shutdown, so we can
+//! gain confidence before switching consumers over.
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider};
+use alloy::sol;
+use alloy::transports::http::{Client, Http};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::feed_trigger::PriceSource;
+use crate::rest::{scale_down, JsonPath};
+use crate::stats::Distribution;
+use crate::status_server::StatusSource;
+
+sol!(
+    #[sol(rpc)]
+    interface AggregatorV3Interface {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+    }
+
+    #[sol(rpc)]
+    interface IPriceOracleV2 {
+        function getLatestPrice(string calldata feedId) external view returns (uint256 price, uint256 lastUpdate);
+    }
+);
+
+type HttpProvider = RootProvider<Http<Client>>;
+
+/// Whether `SHADOW_MODE` is set to compare against a reference instead of publishing
+pub fn shadow_mode_enabled() -> bool {
+    matches!(
+        std::env::var("SHADOW_MODE").as_deref(),
+        Ok("1") | Ok("true") | Ok("yes")
+    )
+}
+
+/// A price published by some other oracle
+#[async_trait]
+pub trait ReferenceOracle: Send + Sync {
+    fn name(&self) -> String;
+    async fn latest_price(&self) -> Result<f64>;
+}
+
+pub struct ChainlinkReference {
+    aggregator: AggregatorV3Interface::AggregatorV3InterfaceInstance<Http<Client>, HttpProvider>,
+    decimals: u8,
+}
+
+/// `latestRoundData()` of a Chainlink aggregator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainlinkRound {
+    pub round_id: u128,
+    /// Answer scaled by the aggregator's decimals
+    pub answer: f64,
+    /// Answer as an 18-decimal fixed-point value; `None` if negative
+    pub answer_18: Option<U256>,
+    /// When the answer was last updated (unix seconds)
+    pub updated_at: u64,
+}
+
+impl ChainlinkReference {
+    pub async fn new(rpc_url: &str, aggregator: Address) -> Result<Self> {
+        let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
+        let aggregator = AggregatorV3Interface::new(aggregator, provider);
+        let decimals = aggregator.decimals().call().await?._0;
+        Ok(Self { aggregator, decimals })
+    }
+
+    pub fn address(&self) -> Address {
+        *self.aggregator.address()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    pub async fn latest_round(&self) -> Result<ChainlinkRound> {
+        let round = self.aggregator.latestRoundData().call().await?;
+        let answer: f64 = round.answer.to_string().parse()?;
+        let answer_18 = U256::try_from(round.answer).ok().and_then(|raw| {
+            if self.decimals <= 18 {
+                raw.checked_mul(U256::from(10u64).pow(U256::from(18 - self.decimals)))
+            } else {
+                Some(raw / U256::from(10u64).pow(U256::from(self.decimals - 18)))
+            }
+        });
+        Ok(ChainlinkRound {
+            round_id: round.roundId.to::<u128>(),
+            answer: answer / 10f64.powi(self.decimals as i32),
+            answer_18,
+            updated_at: round.updatedAt.saturating_to(),
+        })
+    }
+}
+
+#[async_trait]
+impl ReferenceOracle for ChainlinkReference {
+    fn name(&self) -> String {
+        format!("chainlink:{}", self.aggregator.address())
+    }
+
+    async fn latest_price(&self) -> Result<f64> {
+        Ok(self.latest_round().await?.answer)
+    }
+}
+
+/// Another PriceOracleV2 deployment (18-decimal prices keyed by feed id)
+pub struct PriceOracleReference {
+    oracle: IPriceOracleV2::IPriceOracleV2Instance<Http<Client>, HttpProvider>,
+    feed_id: String,
+}
+
+impl PriceOracleReference {
+    pub fn new(rpc_url: &str, oracle: Address, feed_id: impl Into<String>) -> Result<Self> {
+        let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
+        Ok(Self {
+            oracle: IPriceOracleV2::new(oracle, provider),
+            feed_id: feed_id.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl ReferenceOracle for PriceOracleReference {
+    fn name(&self) -> String {
+        format!("oracle:{}:{}", self.oracle.address(), self.feed_id)
+    }
+
+    async fn latest_price(&self) -> Result<f64> {
+        let price: U256 = self.oracle.getLatestPrice(self.feed_id.clone()).call().await?.price;
+        Ok(price.to_string().parse::<f64>()? / 1e18)
+    }
+}
+
+/// A number in a JSON response, e.g. Coinbase spot at `$.data.amount`
+pub struct RestReference {
+    client: reqwest::Client,
+    url: String,
+    path: JsonPath,
+    source_decimals: u8,
+}
+
+impl RestReference {
+    pub fn new(url: impl Into<String>, json_path: &str, source_decimals: u8) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            path: JsonPath::parse(json_path)?,
+            source_decimals,
+        })
+    }
+}
+
+#[async_trait]
+impl ReferenceOracle for RestReference {
+    fn name(&self) -> String {
+        format!("rest:{}", self.url)
+    }
+
+    async fn latest_price(&self) -> Result<f64> {
+        let body: serde_json::Value = self
+            .client
+            .get(&self.url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(scale_down(self.path.extract(&body)?, self.source_decimals))
+    }
+}
+
+/// Build a reference from a spec like `chainlink:0x...` or `oracle:0x...`
+pub async fn reference_from_spec(spec: &str, rpc_url: &str, feed_id: &str) -> Result<Arc<dyn ReferenceOracle>> {
+    let (kind, address) = spec
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Invalid reference '{}', expected chainlink:<addr> or oracle:<addr>", spec))?;
+    let address = Address::from_str(address)?;
+
+    match kind {
+        "chainlink" => Ok(Arc::new(ChainlinkReference::new(rpc_url, address).await?)),
+        "oracle" => Ok(Arc::new(PriceOracleReference::new(rpc_url, address, feed_id)?)),
+        other => Err(anyhow!("Unknown reference kind '{}'", other)),
+    }
+}
+
+/// Distribution of relative deviations (in basis points) from the reference
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct DeviationReport {
+    pub feed_id: String,
+    pub reference: String,
+    pub samples: usize,
+    pub reference_errors: u64,
+    pub mean_bps: f64,
+    pub mean_abs_bps: f64,
+    pub p50_abs_bps: f64,
+    pub p90_abs_bps: f64,
+    pub p99_abs_bps: f64,
+    pub max_abs_bps: f64,
+}
+
+struct DeviationRecorder {
+    deviations_bps: Distribution,
+    reference_errors: u64,
+}
+
+/// Maximum retained samples - roughly a day at one sample per second
+const MAX_SAMPLES: usize = 86_400;
+
+impl DeviationRecorder {
+    fn record(&mut self, ours: f64, reference: f64) {
+        if reference <= 0.0 {
+            return;
+        }
+        self.deviations_bps.record((ours - reference) / reference * 10_000.0);
+    }
+
+    fn report(&self, feed_id: &str, reference: &str) -> DeviationReport {
+        let signed = self.deviations_bps.summary();
+        let abs = self.deviations_bps.abs_summary();
+
+        DeviationReport {
+            feed_id: feed_id.to_string(),
+            reference: reference.to_string(),
+            samples: abs.count,
+            reference_errors: self.reference_errors,
+            mean_bps: signed.mean,
+            mean_abs_bps: abs.mean,
+            p50_abs_bps: abs.p50,
+            p90_abs_bps: abs.p90,
+            p99_abs_bps: abs.p99,
+            max_abs_bps: abs.max,
+        }
+    }
+}
+
+/// Samples our price and the reference price for one feed each interval
+pub struct ShadowComparator {
+    feed_id: String,
+    source: Arc<dyn PriceSource>,
+    reference: Arc<dyn ReferenceOracle>,
+    recorder: RwLock<DeviationRecorder>,
+}
+
+impl ShadowComparator {
+    pub fn new(feed_id: impl Into<String>, source: Arc<dyn PriceSource>, reference: Arc<dyn ReferenceOracle>) -> Self {
+        Self {
+            feed_id: feed_id.into(),
+            source,
+            reference,
+            recorder: RwLock::new(DeviationRecorder {
+                deviations_bps: Distribution::new(MAX_SAMPLES),
+                reference_errors: 0,
+            }),
+        }
+    }
+
+    pub fn report(&self) -> DeviationReport {
+        self.recorder.read().report(&self.feed_id, &self.reference.name())
+    }
+
+    async fn sample(&self) {
+        let Some(ours) = self.source.latest() else {
+            debug!("Shadow {}: no local price yet", self.feed_id);
+            return;
+        };
+
+        match self.reference.latest_price().await {
+            Ok(reference) => {
+                debug!("Shadow {}: ours {} vs reference {}", self.feed_id, ours.price, reference);
+                self.recorder.write().record(ours.price, reference);
+            }
+            Err(e) => {
+                warn!("Shadow {}: reference read failed: {}", self.feed_id, e);
+                self.recorder.write().reference_errors += 1;
+            }
+        }
+    }
+
+    /// Sample every `interval` and log the report every `report_every` samples
+    pub fn spawn(self: Arc<Self>, interval: Duration, report_every: u64) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut count = 0u64;
+            loop {
+                ticker.tick().await;
+                self.sample().await;
+                count += 1;
+
+                if count % report_every.max(1) == 0 {
+                    let r = self.report();
+                    info!(
+                        "👥 Shadow {} vs {} - samples: {}, mean: {:.2}bps, p50: {:.2}bps, p99: {:.2}bps, max: {:.2}bps",
+                        r.feed_id, r.reference, r.samples, r.mean_bps, r.p50_abs_bps, r.p99_abs_bps, r.max_abs_bps
+                    );
+                }
+            }
+        })
+    }
+
+    /// Write the current report as JSON
+    pub fn write_report(&self, path: &str) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.report())?)?;
+        info!("📝 Shadow report written to {}", path);
+        Ok(())
+    }
+}
+
+impl StatusSource for ShadowComparator {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "mode": "shadow",
+            "report": self.report(),
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+/// Coordinates graceful shutdown on Ctrl+C / SIGTERM.
+///
+/// Background tasks either subscribe and exit when the flag flips, or are
+/// registered here to be aborted once shutdown starts.
+pub struct ShutdownCoordinator {
+    sender: watch::Sender<bool>,
+    tasks: Vec<(String, JoinHandle<()>)>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self { sender, tasks: Vec::new() }
+    }
+
+    /// Receiver that flips to `true` when shutdown begins
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.sender.subscribe()
+    }
+
+    /// Register a background task to abort on shutdown
+    pub fn register(&mut self, name: impl Into<String>, handle: JoinHandle<()>) {
+        self.tasks.push((name.into(), handle));
+    }
+
+    /// Wait for Ctrl+C (or SIGTERM on Unix, which is what Fly sends)
+    pub async fn wait_for_signal(&self) -> anyhow::Result<()> {
+        #[cfg(unix)]
+        {
+            let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+            tokio::select! {
+                res = tokio::signal::ctrl_c() => res?,
+                _ = sigterm.recv() => info!("Received SIGTERM"),
+            }
+        }
+
+        #[cfg(not(unix))]
+        tokio::signal::ctrl_c().await?;
+
+        Ok(())
+    }
+
+    /// Notify subscribers and abort registered tasks
+    pub fn shutdown(self) {
+        let _ = self.sender.send(true);
+        for (name, handle) in self.tasks {
+            info!("Stopping {}", name);
+            handle.abort();
+        }
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,152 @@
+//! Smoothing of computed values before they are published.
+//!
+//! A feed with `[feeds.smoothing]` publishes a filtered value instead of the
+//! raw one its source computes, for consumers that prefer a steadier
+//! on-chain series over the last tick of responsiveness. Both filters weigh
+//! each value by the data time since the previous one, so the result doesn't
+//! depend on how often the feed publishes, and a value the source hasn't
+//! updated since the last one (same data time) doesn't count again:
+//!
+//! - `ewma`: exponentially weighted moving average; a value's weight halves
+//!   every `half_life_ms`
+//! - `kalman`: a random-walk Kalman filter; `process_noise_bps` is how far
+//!   the true value is expected to move per second (as a standard
+//!   deviation), `measurement_noise_bps` the noise of each computed value.
+//!   A higher ratio of the first to the second follows moves faster.
+//!
+//! The smoothed value then goes through `[feeds.change_limit]` and the
+//! other checks like a raw one would. Raw and smoothed values are reported
+//! under `smoothing` in each feed's status.
+//!
+//! Oracles configured from the environment rather than a feeds file (the
+//! binance oracle) take the same options from:
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `SMOOTHING` | unset | `ewma` or `kalman` (unset publishes raw values) |
+//! | `SMOOTHING_HALF_LIFE_MS` | unset | `half_life_ms` |
+//! | `SMOOTHING_PROCESS_NOISE_BPS` | unset | `process_noise_bps` |
+//! | `SMOOTHING_MEASUREMENT_NOISE_BPS` | unset | `measurement_noise_bps` |
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::feeds::{FeedSmoothing, SmoothingKind};
+
+#[derive(Default)]
+struct SmoothState {
+    /// Smoothed value and the data time it is for
+    estimate: Option<(f64, u64)>,
+    /// Kalman estimate variance, in squared value units
+    variance: f64,
+    raw: Option<f64>,
+    /// Weight given to the latest raw value (EWMA alpha or Kalman gain)
+    gain: Option<f64>,
+    samples: u64,
+}
+
+pub struct Smoother {
+    config: FeedSmoothing,
+    state: Mutex<SmoothState>,
+}
+
+/// `bps` of `value` in value units
+fn bps_of(value: f64, bps: f64) -> f64 {
+    value.abs() * bps / 10_000.0
+}
+
+impl Smoother {
+    pub fn new(config: &FeedSmoothing) -> Self {
+        Self { config: config.clone(), state: Mutex::new(SmoothState::default()) }
+    }
+
+    /// From `SMOOTHING` and its options; `None` when `SMOOTHING` is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(kind) = std::env::var("SMOOTHING") else {
+            return Ok(None);
+        };
+        let kind = match kind.trim() {
+            "ewma" => SmoothingKind::Ewma,
+            "kalman" => SmoothingKind::Kalman,
+            other => anyhow::bail!("Unknown SMOOTHING '{}' (expected ewma or kalman)", other),
+        };
+        let config = FeedSmoothing {
+            kind,
+            half_life_ms: env_opt("SMOOTHING_HALF_LIFE_MS")?,
+            process_noise_bps: env_opt("SMOOTHING_PROCESS_NOISE_BPS")?,
+            measurement_noise_bps: env_opt("SMOOTHING_MEASUREMENT_NOISE_BPS")?,
+        };
+        config.validate().context("Invalid SMOOTHING options")?;
+        info!("🧮 Smoothing published values: {}", kind.as_str());
+        Ok(Some(Self::new(&config)))
+    }
+
+    /// The value to publish for a computed `value` observed at `timestamp_ms`
+    pub fn smooth(&self, value: f64, timestamp_ms: u64) -> f64 {
+        let mut state = self.state.lock();
+        state.raw = Some(value);
+        if let Some((estimate, at)) = state.estimate {
+            // Nothing new from the source since the last call
+            if timestamp_ms <= at {
+                return estimate;
+            }
+        }
+        state.samples += 1;
+        let Some((estimate, at)) = state.estimate else {
+            state.estimate = Some((value, timestamp_ms));
+            state.variance = bps_of(value, self.config.measurement_noise_bps.unwrap_or(0.0)).powi(2);
+            state.gain = Some(1.0);
+            return value;
+        };
+        let dt_secs = timestamp_ms.saturating_sub(at) as f64 / 1000.0;
+
+        let gain = match self.config.kind {
+            SmoothingKind::Ewma => {
+                let half_life_secs = self.config.half_life_ms.unwrap_or(1).max(1) as f64 / 1000.0;
+                1.0 - 0.5f64.powf(dt_secs / half_life_secs)
+            }
+            SmoothingKind::Kalman => {
+                let process = bps_of(estimate, self.config.process_noise_bps.unwrap_or(0.0));
+                let measurement = bps_of(estimate, self.config.measurement_noise_bps.unwrap_or(0.0));
+                let predicted = state.variance + process.powi(2) * dt_secs;
+                let total = predicted + measurement.powi(2);
+                let gain = if total > 0.0 { predicted / total } else { 1.0 };
+                state.variance = (1.0 - gain) * predicted;
+                gain
+            }
+        };
+
+        let smoothed = estimate + gain * (value - estimate);
+        state.estimate = Some((smoothed, timestamp_ms));
+        state.gain = Some(gain);
+        smoothed
+    }
+
+    /// Latest smoothed value
+    pub fn value(&self) -> Option<f64> {
+        self.state.lock().estimate.map(|(value, _)| value)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let state = self.state.lock();
+        json!({
+            "kind": self.config.kind.as_str(),
+            "half_life_ms": self.config.half_life_ms,
+            "process_noise_bps": self.config.process_noise_bps,
+            "measurement_noise_bps": self.config.measurement_noise_bps,
+            "raw": state.raw,
+            "smoothed": state.estimate.map(|(value, _)| value),
+            "gain": state.gain,
+            "samples": state.samples,
+        })
+    }
+}
+
+fn env_opt<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+    match std::env::var(name) {
+        Ok(v) => Ok(Some(v.trim().parse().map_err(|_| anyhow::anyhow!("Invalid {} '{}'", name, v))?)),
+        Err(_) => Ok(None),
+    }
+}
@@ -0,0 +1,170 @@
+use alloy::primitives::U256;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Update statistics shared between a trigger and the status server
+pub type SharedStats = Arc<RwLock<OracleStats>>;
+
+#[derive(Default, Clone, Debug, Serialize)]
+pub struct OracleStats {
+    pub total_triggers: u64,
+    pub successful_updates: u64,
+    pub failed_updates: u64,
+    /// Successes later found not to have landed (counted in `failed_updates`)
+    pub dropped_updates: u64,
+    pub total_drift_ms: i64,
+    pub max_drift_ms: i64,
+    pub min_gas_used: Option<U256>,
+    pub max_gas_used: Option<U256>,
+    pub total_latency_ms: u64,
+    pub max_latency_ms: u64,
+    pub last_block: Option<U256>,
+}
+
+impl OracleStats {
+    pub fn shared() -> SharedStats {
+        Arc::new(RwLock::new(Self::default()))
+    }
+
+    pub fn record_trigger(&mut self) {
+        self.total_triggers += 1;
+    }
+
+    /// Record a confirmed update. `drift_ms` is the scheduling drift of the
+    /// tick that produced it (0 for triggers without a fixed schedule).
+    pub fn record_success(&mut self, drift_ms: i64, gas_used: Option<U256>, latency: Option<Duration>) {
+        self.successful_updates += 1;
+        self.total_drift_ms += drift_ms;
+        self.max_drift_ms = self.max_drift_ms.max(drift_ms.abs());
+
+        if let Some(gas_used) = gas_used {
+            self.min_gas_used = Some(self.min_gas_used.map_or(gas_used, |min| min.min(gas_used)));
+            self.max_gas_used = Some(self.max_gas_used.map_or(gas_used, |max| max.max(gas_used)));
+        }
+
+        if let Some(latency) = latency {
+            let latency_ms = latency.as_millis() as u64;
+            self.total_latency_ms += latency_ms;
+            self.max_latency_ms = self.max_latency_ms.max(latency_ms);
+        }
+    }
+
+    pub fn record_failure(&mut self) {
+        self.failed_updates += 1;
+    }
+
+    /// A confirmed update turned out not to be on chain after all
+    pub fn record_dropped(&mut self) {
+        self.successful_updates = self.successful_updates.saturating_sub(1);
+        self.failed_updates += 1;
+        self.dropped_updates += 1;
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        if self.total_triggers > 0 {
+            (self.successful_updates as f64 / self.total_triggers as f64) * 100.0
+        } else {
+            100.0
+        }
+    }
+
+    pub fn avg_drift_ms(&self) -> f64 {
+        if self.successful_updates > 0 {
+            self.total_drift_ms as f64 / self.successful_updates as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn avg_latency_ms(&self) -> f64 {
+        if self.successful_updates > 0 {
+            self.total_latency_ms as f64 / self.successful_updates as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// JSON summary for the status server, including derived rates
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("success_rate".into(), self.success_rate().into());
+            obj.insert("avg_drift_ms".into(), self.avg_drift_ms().into());
+            obj.insert("avg_latency_ms".into(), self.avg_latency_ms().into());
+        }
+        value
+    }
+}
+
+/// Rolling window of samples with percentile summaries
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    samples: VecDeque<f64>,
+    capacity: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct DistributionSummary {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl Distribution {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(4096)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&mut self, value: f64) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Summary of the signed values
+    pub fn summary(&self) -> DistributionSummary {
+        Self::summarize(self.samples.iter().copied().collect())
+    }
+
+    /// Summary of the absolute values
+    pub fn abs_summary(&self) -> DistributionSummary {
+        Self::summarize(self.samples.iter().map(|v| v.abs()).collect())
+    }
+
+    fn summarize(mut values: Vec<f64>) -> DistributionSummary {
+        if values.is_empty() {
+            return DistributionSummary::default();
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+
+        DistributionSummary {
+            count: values.len(),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            min: values[0],
+            max: values[values.len() - 1],
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p99: percentile(0.99),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! HTTP status server.
+//!
+//! Serves `/health`, `/rpc` (RPC rate limit buckets), `/chaos` (faults
+//! injected in chaos mode), `/parse-errors` (quarantined stream messages),
+//! `/status` (JSON from a [`StatusSource`]) and optionally
+//! `/errors` ([`ErrorMetrics`]), `/runtime` ([`RuntimeMonitor`]) and
+//! `/candles` ([`CandleStore`]); other
+//! endpoints can be merged in with [`StatusServer::merge`]. Enabled when
+//! `STATUS_ADDR` is set, e.g. `STATUS_ADDR=0.0.0.0:8080`.
+//!
+//! With an [`UpdateHistory`] attached it also serves `/history` and an
+//! embedded dashboard at `/` charting recent values, latencies and errors.
+
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::candles::{parse_candle_interval, CandleStore};
+use crate::chaos::chaos_mode;
+use crate::error_metrics::ErrorMetrics;
+use crate::history::UpdateHistory;
+use crate::parse_quarantine::parse_quarantine_json;
+use crate::rate_limit::rate_limits_json;
+use crate::runtime_metrics::RuntimeMonitor;
+
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// Something that can describe its current state as JSON
+pub trait StatusSource: Send + Sync {
+    fn status(&self) -> serde_json::Value;
+}
+
+pub struct StatusServer {
+    bind: SocketAddr,
+    router: Router,
+}
+
+impl StatusServer {
+    pub fn new(bind: SocketAddr) -> Self {
+        Self {
+            bind,
+            router: Router::new()
+                .route("/health", get(|| async { "ok" }))
+                .route("/rpc", get(|| async { Json(rate_limits_json()) }))
+                .route("/parse-errors", get(|| async { Json(parse_quarantine_json()) }))
+                .route(
+                    "/chaos",
+                    get(|| async {
+                        Json(chaos_mode().map_or_else(|| serde_json::json!({ "enabled": false }), |chaos| chaos.to_json()))
+                    }),
+                ),
+        }
+    }
+
+    /// Bind address from `STATUS_ADDR`, if the status server is enabled
+    pub fn addr_from_env() -> Option<SocketAddr> {
+        std::env::var("STATUS_ADDR").ok()?.parse().ok()
+    }
+
+    pub fn with_status(mut self, source: Arc<dyn StatusSource>) -> Self {
+        self.router = self.router.route(
+            "/status",
+            get(move || {
+                let source = source.clone();
+                async move { Json(source.status()) }
+            }),
+        );
+        self
+    }
+
+    /// Serve error counts by category at `/errors`
+    pub fn with_error_metrics(mut self, metrics: Arc<ErrorMetrics>) -> Self {
+        self.router = self.router.route(
+            "/errors",
+            get(move || {
+                let metrics = metrics.clone();
+                async move { Json(metrics.to_json()) }
+            }),
+        );
+        self
+    }
+
+    /// Serve runtime and process metrics at `/runtime`
+    pub fn with_runtime_metrics(mut self, monitor: Arc<RuntimeMonitor>) -> Self {
+        self.router = self.router.route(
+            "/runtime",
+            get(move || {
+                let monitor = monitor.clone();
+                async move { Json(monitor.to_json()) }
+            }),
+        );
+        self
+    }
+
+    /// Serve a summary of `store` at `/candles`, and one symbol's bars at
+    /// `/candles?symbol=BTCUSDT&interval=5s&limit=100` (spot symbols are
+    /// `spot:BTCUSDT`; the interval defaults to the shortest built)
+    pub fn with_candles(mut self, store: Arc<CandleStore>) -> Self {
+        self.router = self.router.route(
+            "/candles",
+            get(move |Query(params): Query<HashMap<String, String>>| {
+                let store = store.clone();
+                async move {
+                    let Some(symbol) = params.get("symbol") else {
+                        return Ok(Json(store.to_json()));
+                    };
+                    let interval_ms = match params.get("interval") {
+                        Some(interval) => parse_candle_interval(interval).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?,
+                        None => store.intervals_ms().first().copied().unwrap_or(1_000),
+                    };
+                    if !store.has_interval(interval_ms) {
+                        return Err((StatusCode::NOT_FOUND, format!("No {}ms candles are built", interval_ms)));
+                    }
+                    let limit = params.get("limit").and_then(|limit| limit.parse().ok()).unwrap_or(100);
+                    Ok(Json(store.candles_json(symbol, interval_ms, limit)))
+                }
+            }),
+        );
+        self
+    }
+
+    /// Serve recent updates at `/history` and the dashboard at `/`
+    pub fn with_dashboard(mut self, history: Arc<UpdateHistory>) -> Self {
+        self.router = self
+            .router
+            .route(
+                "/history",
+                get(move || {
+                    let history = history.clone();
+                    async move { Json(history.to_json()) }
+                }),
+            )
+            .route("/", get(|| async { Html(DASHBOARD_HTML) }))
+            .route("/dashboard", get(|| async { Html(DASHBOARD_HTML) }));
+        self
+    }
+
+    /// Merge additional routes into the server
+    pub fn merge(mut self, router: Router) -> Self {
+        self.router = self.router.merge(router);
+        self
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let listener = match tokio::net::TcpListener::bind(self.bind).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to bind status server on {}: {}", self.bind, e);
+                    return;
+                }
+            };
+
+            info!("🩺 Status server listening on http://{}", self.bind);
+            if let Err(e) = axum::serve(listener, self.router).await {
+                error!("Status server error: {}", e);
+            }
+        })
+    }
+}
@@ -0,0 +1,498 @@
+//! Raw transaction submission for the orchestrator.
+//!
+//! RISE returns the receipt directly from `eth_sendRawTransactionSync`.
+//! Chains without it get `eth_sendRawTransaction` followed by receipt
+//! polling, and on RISE the polling path doubles as a fallback while the
+//! sync endpoint is unavailable. All of them walk the chain's RPC list,
+//! moving to the next endpoint on transport errors, and their receipts are
+//! sanity-checked (see [`crate::receipt_validation`]). Failures can be kept
+//! in a dead-letter file (see [`crate::dead_letter`]), every submission in
+//! an audit log (see [`crate::audit`]), and submissions are timed for
+//! pipeline traces (see [`crate::telemetry`]).
+
+use alloy::hex;
+use alloy::primitives::{keccak256, Bytes};
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::audit::{AuditLog, AuditSubmitter};
+use crate::chain::ChainConfig;
+use crate::chaos::{chaos_mode, ChaosSubmitter};
+use crate::dead_letter::{DeadLetterStore, DeadLetterSubmitter};
+use crate::http_client::HttpTuning;
+use crate::rate_limit::{bucket_for, RpcClass, TokenBucket};
+use crate::receipt_validation::{parse_receipt, ReceiptValidation, ValidatingSubmitter};
+use crate::telemetry::TracingSubmitter;
+
+/// Chain id check state of an endpoint
+const UNCHECKED: u8 = 0;
+const VERIFIED: u8 = 1;
+const WRONG_CHAIN: u8 = 2;
+
+/// JSON-RPC client over a list of endpoints with failover
+pub struct RpcEndpoints {
+    client: reqwest::Client,
+    urls: Vec<String>,
+    /// Rate limit bucket per URL, shared process-wide
+    limiters: Vec<Option<Arc<TokenBucket>>>,
+    class: RpcClass,
+    current: AtomicUsize,
+    /// Chain id every endpoint must serve before it is used
+    chain_id: Option<u64>,
+    /// Per endpoint: `UNCHECKED`, `VERIFIED` or `WRONG_CHAIN`
+    checked: Vec<AtomicU8>,
+}
+
+impl RpcEndpoints {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self::with_client(urls, reqwest::Client::new())
+    }
+
+    pub fn with_client(urls: Vec<String>, client: reqwest::Client) -> Self {
+        Self {
+            client,
+            limiters: urls.iter().map(|url| bucket_for(url)).collect(),
+            checked: urls.iter().map(|_| AtomicU8::new(UNCHECKED)).collect(),
+            urls,
+            class: RpcClass::Background,
+            current: AtomicUsize::new(0),
+            chain_id: None,
+        }
+    }
+
+    /// Only use endpoints serving `chain_id`, asking each one before its
+    /// first use. One serving another chain is never used; an unreachable
+    /// one is asked again next time.
+    pub fn with_chain_id(mut self, chain_id: Option<u64>) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Rate limit class of this client's calls (background by default)
+    pub fn with_class(mut self, class: RpcClass) -> Self {
+        self.class = class;
+        self
+    }
+
+    async fn throttle(&self, index: usize) {
+        if let Some(bucket) = &self.limiters[index] {
+            bucket.acquire(self.class).await;
+        }
+    }
+
+    /// Open a connection to every endpoint so the first transaction doesn't
+    /// pay for the TCP / TLS handshake
+    pub async fn warm_up(&self) {
+        let body = json!({ "jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1 });
+        for (index, url) in self.urls.iter().enumerate() {
+            self.throttle(index).await;
+            let started = Instant::now();
+            match self.client.post(url).json(&body).send().await {
+                Ok(_) => debug!("🔥 Warmed {} in {:?}", url, started.elapsed()),
+                Err(e) => warn!("⚠️ Failed to warm {}: {}", url, e),
+            }
+        }
+    }
+
+    /// Warm up now and then every `interval`, keeping pooled connections alive
+    pub fn spawn_keep_warm(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.warm_up().await;
+            }
+        })
+    }
+
+    /// Call `method`, trying each endpoint once starting from the last healthy one
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, RpcCallError> {
+        let body = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        let value = self.post(&body).await?;
+        // JSON-RPC errors come from the node itself, so don't fail over on them
+        if let Some(error) = value.get("error") {
+            return Err(RpcCallError::Node(error.clone()));
+        }
+        Ok(value.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Send `calls` as one JSON-RPC batch; results come back in call order.
+    /// An endpoint that rejects batches answers with a single error object,
+    /// returned as the outer error.
+    pub async fn call_batch(&self, calls: &[(String, Value)]) -> Result<Vec<Result<Value, RpcCallError>>, RpcCallError> {
+        let body: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": id }))
+            .collect();
+        let value = self.post(&Value::Array(body)).await?;
+        let Some(responses) = value.as_array() else {
+            return Err(RpcCallError::Node(value.get("error").cloned().unwrap_or(value)));
+        };
+
+        let mut results = vec![Err(RpcCallError::Transport("No response in batch".to_string())); calls.len()];
+        for response in responses {
+            let Some(slot) = response["id"].as_u64().and_then(|id| results.get_mut(id as usize)) else {
+                continue;
+            };
+            *slot = match response.get("error") {
+                Some(error) => Err(RpcCallError::Node(error.clone())),
+                None => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+            };
+        }
+        Ok(results)
+    }
+
+    /// Whether endpoint `index` serves the expected chain id, asking it the
+    /// first time
+    async fn serves_chain(&self, index: usize) -> bool {
+        let Some(expected) = self.chain_id else { return true };
+        match self.checked[index].load(Ordering::Relaxed) {
+            VERIFIED => return true,
+            WRONG_CHAIN => return false,
+            _ => {}
+        }
+
+        let url = &self.urls[index];
+        let body = json!({ "jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1 });
+        self.throttle(index).await;
+        let id = match self.client.post(url).json(&body).send().await {
+            Ok(response) => response.json::<Value>().await.ok().and_then(|value| {
+                value["result"].as_str().and_then(|id| u64::from_str_radix(id.trim_start_matches("0x"), 16).ok())
+            }),
+            Err(_) => None,
+        };
+        match id {
+            Some(id) if id == expected => {
+                debug!("🔗 RPC {} serves chain id {}", url, id);
+                self.checked[index].store(VERIFIED, Ordering::Relaxed);
+                true
+            }
+            Some(id) => {
+                error!("🚨 RPC {} serves chain id {} not {} - never using it", url, id, expected);
+                self.checked[index].store(WRONG_CHAIN, Ordering::Relaxed);
+                false
+            }
+            None => {
+                warn!("⚠️ Could not read the chain id of RPC {}, skipping it", url);
+                false
+            }
+        }
+    }
+
+    /// POST `body`, failing over to the next endpoint on transport errors
+    async fn post(&self, body: &Value) -> Result<Value, RpcCallError> {
+        let start = self.current.load(Ordering::Relaxed);
+        let mut last_error = String::new();
+
+        for offset in 0..self.urls.len() {
+            let index = (start + offset) % self.urls.len();
+            let url = &self.urls[index];
+
+            if !self.serves_chain(index).await {
+                last_error = format!("{} is not verified to serve chain id {:?}", url, self.chain_id);
+                continue;
+            }
+            self.throttle(index).await;
+            let response = match self.client.post(url).json(body).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("⚠️ RPC {} unreachable: {}", url, e);
+                    last_error = e.to_string();
+                    continue;
+                }
+            };
+
+            let value: Value = match response.json().await {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("⚠️ RPC {} returned an invalid response: {}", url, e);
+                    last_error = e.to_string();
+                    continue;
+                }
+            };
+
+            if index != start {
+                warn!("🔀 Switched RPC endpoint to {}", url);
+                self.current.store(index, Ordering::Relaxed);
+            }
+            return Ok(value);
+        }
+
+        Err(RpcCallError::Transport(format!("All RPC endpoints failed: {}", last_error)))
+    }
+}
+
+/// Something JSON-RPC calls can be made through: an endpoint list directly,
+/// or a [`crate::rpc_batch::RpcBatcher`] batching them
+#[async_trait]
+pub trait JsonRpc: Send + Sync {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcCallError>;
+}
+
+#[async_trait]
+impl JsonRpc for RpcEndpoints {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcCallError> {
+        RpcEndpoints::call(self, method, params).await
+    }
+}
+
+/// Why a JSON-RPC call failed
+#[derive(Debug, Clone)]
+pub enum RpcCallError {
+    /// No endpoint could be reached or returned valid JSON
+    Transport(String),
+    /// The node answered with a JSON-RPC error object
+    Node(Value),
+}
+
+impl RpcCallError {
+    /// JSON-RPC "method not found" - the endpoint lacks the method entirely
+    pub fn is_method_not_found(&self) -> bool {
+        matches!(self, RpcCallError::Node(error) if error.get("code").and_then(Value::as_i64) == Some(-32601))
+    }
+
+    /// The node already has this transaction (e.g. a sync send that timed out client-side)
+    pub fn is_already_known(&self) -> bool {
+        match self {
+            RpcCallError::Node(error) => {
+                let message = error.get("message").and_then(Value::as_str).unwrap_or_default().to_lowercase();
+                message.contains("already known") || message.contains("known transaction")
+            }
+            RpcCallError::Transport(_) => false,
+        }
+    }
+}
+
+impl From<RpcCallError> for RiseError {
+    fn from(e: RpcCallError) -> Self {
+        match e {
+            RpcCallError::Transport(message) => RiseError::Rpc(message),
+            RpcCallError::Node(error) => RiseError::Rpc(error.to_string()),
+        }
+    }
+}
+
+/// `eth_sendRawTransactionSync` - the receipt comes back with the send
+pub struct SyncSubmitter {
+    rpc: Arc<RpcEndpoints>,
+}
+
+impl SyncSubmitter {
+    pub fn new(rpc: Arc<RpcEndpoints>) -> Self {
+        Self { rpc }
+    }
+}
+
+impl SyncSubmitter {
+    async fn send(&self, raw_tx: &Bytes) -> Result<Value, RpcCallError> {
+        self.rpc
+            .call("eth_sendRawTransactionSync", json!([hex::encode_prefixed(raw_tx)]))
+            .await
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for SyncSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let result = self.send(&raw_tx).await?;
+        parse_receipt(result).map_err(|e| RiseError::Rpc(format!("Invalid sync receipt: {}", e)))
+    }
+}
+
+/// `eth_sendRawTransaction` followed by `eth_getTransactionReceipt` polling
+pub struct PollingSubmitter {
+    rpc: Arc<RpcEndpoints>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl PollingSubmitter {
+    pub fn new(rpc: Arc<RpcEndpoints>, poll_interval: Duration, timeout: Duration) -> Self {
+        Self { rpc, poll_interval, timeout }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for PollingSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let tx_hash = match self
+            .rpc
+            .call("eth_sendRawTransaction", json!([hex::encode_prefixed(&raw_tx)]))
+            .await
+        {
+            Ok(hash) => hash,
+            // Already in the mempool - just wait for it
+            Err(e) if e.is_already_known() => json!(keccak256(&raw_tx)),
+            Err(e) => return Err(e.into()),
+        };
+
+        let started = Instant::now();
+        loop {
+            tokio::time::sleep(self.poll_interval).await;
+
+            let receipt = self.rpc.call("eth_getTransactionReceipt", json!([tx_hash.clone()])).await?;
+            if !receipt.is_null() {
+                debug!("Receipt for {} after {:?}", tx_hash, started.elapsed());
+                return parse_receipt(receipt).map_err(|e| RiseError::Rpc(format!("Invalid receipt: {}", e)));
+            }
+
+            if started.elapsed() >= self.timeout {
+                return Err(RiseError::Rpc(format!("Timed out waiting for receipt of {}", tx_hash)));
+            }
+        }
+    }
+}
+
+/// Sync submission that falls back to send + poll while the sync path is down.
+///
+/// Transport failures or a missing `eth_sendRawTransactionSync` switch to
+/// polling for `retry_sync_after`, after which the sync path is tried again.
+pub struct FallbackSubmitter {
+    sync: SyncSubmitter,
+    polling: PollingSubmitter,
+    retry_sync_after: Duration,
+    sync_down_since: RwLock<Option<Instant>>,
+}
+
+impl FallbackSubmitter {
+    pub fn new(sync: SyncSubmitter, polling: PollingSubmitter, retry_sync_after: Duration) -> Self {
+        Self {
+            sync,
+            polling,
+            retry_sync_after,
+            sync_down_since: RwLock::new(None),
+        }
+    }
+
+    fn sync_available(&self) -> bool {
+        match *self.sync_down_since.read() {
+            Some(since) => since.elapsed() >= self.retry_sync_after,
+            None => true,
+        }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for FallbackSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        if !self.sync_available() {
+            return self.polling.submit(raw_tx).await;
+        }
+
+        match self.sync.send(&raw_tx).await {
+            Ok(result) => {
+                if self.sync_down_since.write().take().is_some() {
+                    info!("✅ eth_sendRawTransactionSync is back, leaving polling fallback");
+                }
+                parse_receipt(result).map_err(|e| RiseError::Rpc(format!("Invalid sync receipt: {}", e)))
+            }
+            Err(e) if matches!(e, RpcCallError::Transport(_)) || e.is_method_not_found() => {
+                warn!("⚠️ Sync submission unavailable ({:?}), falling back to send + receipt polling", e);
+                *self.sync_down_since.write() = Some(Instant::now());
+                self.polling.submit(raw_tx).await
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// How transactions are handed to the RPC (`SUBMIT_MODE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitMode {
+    /// `eth_sendRawTransactionSync` only
+    Sync,
+    /// `eth_sendRawTransaction` + receipt polling only
+    Async,
+    /// Sync, falling back to async during outages
+    SyncWithFallback,
+}
+
+impl FromStr for SubmitMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "sync" => Ok(SubmitMode::Sync),
+            "async" => Ok(SubmitMode::Async),
+            "sync-with-fallback" | "fallback" => Ok(SubmitMode::SyncWithFallback),
+            other => Err(anyhow::anyhow!(
+                "Unknown SUBMIT_MODE '{}' - expected sync, async or sync-with-fallback",
+                other
+            )),
+        }
+    }
+}
+
+/// Submitter matching the chain's capabilities. `SUBMIT_MODE` overrides the
+/// default (sync with fallback on sync-capable chains, async otherwise).
+pub fn submitter_for(chain: &ChainConfig) -> anyhow::Result<Arc<dyn TxSubmitter>> {
+    let mode = match std::env::var("SUBMIT_MODE") {
+        Ok(mode) => mode.parse()?,
+        Err(_) if chain.sync_tx => SubmitMode::SyncWithFallback,
+        Err(_) => SubmitMode::Async,
+    };
+    if mode != SubmitMode::Async && !chain.sync_tx {
+        anyhow::bail!("SUBMIT_MODE={:?} needs a chain with SYNC_TX=true", mode);
+    }
+
+    let tuning = HttpTuning::from_env()?;
+    let rpc = Arc::new(
+        RpcEndpoints::with_client(chain.rpc_urls.clone(), tuning.build_client()?)
+            .with_class(RpcClass::Submission)
+            .with_chain_id(chain.chain_id),
+    );
+    info!("🌐 Submission HTTP client: {:?}", tuning);
+    // Lives for the process, like the submitter that owns the pool
+    if let Some(interval) = tuning.keep_warm {
+        rpc.clone().spawn_keep_warm(interval);
+    }
+
+    let polling = || {
+        PollingSubmitter::new(
+            rpc.clone(),
+            Duration::from_millis(chain.receipt_poll_interval_ms),
+            Duration::from_millis(chain.receipt_timeout_ms),
+        )
+    };
+
+    let validation = ReceiptValidation::from_env()?;
+    info!(
+        "📨 Submission mode: {:?} (receipt poll {}ms, timeout {}ms, validation {:?})",
+        mode, chain.receipt_poll_interval_ms, chain.receipt_timeout_ms, validation
+    );
+    let submitter: Arc<dyn TxSubmitter> = match mode {
+        SubmitMode::Sync => Arc::new(SyncSubmitter::new(rpc.clone())),
+        SubmitMode::Async => Arc::new(polling()),
+        SubmitMode::SyncWithFallback => Arc::new(FallbackSubmitter::new(
+            SyncSubmitter::new(rpc.clone()),
+            polling(),
+            Duration::from_secs(30),
+        )),
+    };
+    // Chaos sits inside validation so corrupted receipts meet the real checks
+    let submitter: Arc<dyn TxSubmitter> = match chaos_mode() {
+        Some(chaos) => Arc::new(ChaosSubmitter::new(submitter, chaos)),
+        None => submitter,
+    };
+    let submitter: Arc<dyn TxSubmitter> = Arc::new(ValidatingSubmitter::new(submitter, rpc, validation));
+    let submitter: Arc<dyn TxSubmitter> = match DeadLetterStore::from_env()? {
+        Some(store) => Arc::new(DeadLetterSubmitter::new(submitter, store)),
+        None => submitter,
+    };
+    let submitter: Arc<dyn TxSubmitter> = match AuditLog::from_env()? {
+        Some(log) => Arc::new(AuditSubmitter::new(submitter, log, &chain.name)),
+        None => submitter,
+    };
+    Ok(Arc::new(TracingSubmitter::new(submitter)))
+}
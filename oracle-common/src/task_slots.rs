@@ -0,0 +1,57 @@
+//! Values handed between the stages of one orchestrator worker.
+//!
+//! The orchestrator runs a request's trigger check, key selection, build
+//! hooks, submission and `on_complete` in the worker task that picked it
+//! up, but passes nothing between those callbacks besides the request
+//! itself. A [`TaskSlots`] keeps one value per tokio task, so a later
+//! stage can pick up what an earlier stage of the same request left,
+//! e.g. which queue ticket was built or which key was selected.
+//!
+//! Outside a tokio task nothing is stored and `take` returns `None`;
+//! callers keep a fallback for that case. Slots left behind by tasks
+//! that ended are evicted oldest first once `CAPACITY` is reached.
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use tokio::task::Id;
+
+const CAPACITY: usize = 256;
+
+pub struct TaskSlots<T> {
+    slots: Mutex<VecDeque<(Id, T)>>,
+}
+
+impl<T> Default for TaskSlots<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TaskSlots<T> {
+    pub const fn new() -> Self {
+        Self { slots: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Leave `value` for the current task, replacing what it left before.
+    /// Returns false outside a tokio task.
+    pub fn put(&self, value: T) -> bool {
+        let Some(id) = tokio::task::try_id() else {
+            return false;
+        };
+        let mut slots = self.slots.lock();
+        slots.retain(|(task, _)| *task != id);
+        if slots.len() >= CAPACITY {
+            slots.pop_front();
+        }
+        slots.push_back((id, value));
+        true
+    }
+
+    /// Take what the current task left, if anything
+    pub fn take(&self) -> Option<T> {
+        let id = tokio::task::try_id()?;
+        let mut slots = self.slots.lock();
+        let index = slots.iter().position(|(task, _)| *task == id)?;
+        slots.remove(index).map(|(_, value)| value)
+    }
+}
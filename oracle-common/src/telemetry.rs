@@ -0,0 +1,268 @@
+//! OpenTelemetry traces of the update pipeline.
+//!
+//! With `OTEL_EXPORTER_OTLP_ENDPOINT` set (OTLP over HTTP, e.g.
+//! `http://collector:4318`), every update is exported as one trace: an
+//! `oracle.update` root span with a child per stage:
+//!
+//! - `trigger`: `should_trigger` deciding to fire and encoding the calldata
+//! - `build_hook`: build hooks rewriting the calldata (time oracle only)
+//! - `sign`: from the trigger (or hook) to submission - waiting for a worker,
+//!   nonce assignment and signing inside the SDK
+//! - `submit`: the RPC round trip, including receipt polling and validation;
+//!   the receipt is attached as an event
+//! - `on_complete`: the trigger's completion handler
+//!
+//! The SDK runs its stages on its own tasks, so stages are matched up by
+//! calldata and transaction hash, timestamps collected as the update moves
+//! through, and the spans emitted when it completes. `OTEL_SERVICE_NAME`
+//! overrides the service name.
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{keccak256, Bytes, B256};
+use anyhow::Context as _;
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use opentelemetry::trace::{Span as _, TraceContextExt, Tracer as _, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+/// Updates tracked at once; older ones (e.g. dropped by the queue) are discarded
+const MAX_IN_FLIGHT: usize = 256;
+
+/// Timestamps of one update on its way through the pipeline
+#[derive(Debug, Clone)]
+struct InFlight {
+    feed: String,
+    calldata: Bytes,
+    fired: (SystemTime, SystemTime),
+    build: Option<(SystemTime, SystemTime)>,
+    submit: Option<(SystemTime, SystemTime)>,
+    tx_hash: Option<B256>,
+    error: Option<String>,
+}
+
+pub struct PipelineTracer {
+    tracer: Tracer,
+    in_flight: Mutex<VecDeque<InFlight>>,
+}
+
+static TRACER: OnceLock<PipelineTracer> = OnceLock::new();
+
+/// Flushes pending spans when dropped
+pub struct TelemetryGuard {
+    provider: TracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            warn!("⚠️ Failed to flush traces: {}", e);
+        }
+    }
+}
+
+/// Start exporting pipeline traces if `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+/// Must run inside the tokio runtime; keep the guard alive until exit.
+pub fn init_telemetry(service_name: &str) -> anyhow::Result<Option<TelemetryGuard>> {
+    let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        return Ok(None);
+    };
+    let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| service_name.to_string());
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .context("Invalid OTLP exporter configuration")?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", service_name.clone())]))
+        .build();
+
+    let tracer = PipelineTracer {
+        tracer: provider.tracer("oracle-pipeline"),
+        in_flight: Mutex::new(VecDeque::new()),
+    };
+    if TRACER.set(tracer).is_err() {
+        anyhow::bail!("Telemetry already initialised");
+    }
+    info!("🔭 Exporting update traces to {} as {}", endpoint, service_name);
+    Ok(Some(TelemetryGuard { provider }))
+}
+
+impl PipelineTracer {
+    /// The process-wide tracer, if telemetry is enabled
+    pub fn global() -> Option<&'static PipelineTracer> {
+        TRACER.get()
+    }
+
+    fn fired(&self, feed: &str, calldata: &Bytes, started: SystemTime) {
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.len() >= MAX_IN_FLIGHT {
+            in_flight.pop_front();
+        }
+        in_flight.push_back(InFlight {
+            feed: feed.to_string(),
+            calldata: calldata.clone(),
+            fired: (started, SystemTime::now()),
+            build: None,
+            submit: None,
+            tx_hash: None,
+            error: None,
+        });
+    }
+
+    fn built(&self, feed: &str, before: &Bytes, after: &Bytes, started: SystemTime) {
+        let mut in_flight = self.in_flight.lock();
+        if let Some(update) = in_flight
+            .iter_mut()
+            .find(|u| u.build.is_none() && u.feed == feed && u.calldata == *before)
+        {
+            update.build = Some((started, SystemTime::now()));
+            update.calldata = after.clone();
+        }
+    }
+
+    fn submitted(&self, raw_tx: &Bytes, started: SystemTime, error: Option<String>) {
+        let Ok(envelope) = TxEnvelope::decode_2718(&mut raw_tx.as_ref()) else {
+            return;
+        };
+        let mut in_flight = self.in_flight.lock();
+        if let Some(update) = in_flight.iter_mut().find(|u| u.submit.is_none() && u.calldata == *envelope.input()) {
+            update.submit = Some((started, SystemTime::now()));
+            update.tx_hash = Some(keccak256(raw_tx));
+            update.error = error;
+        }
+    }
+
+    fn completed(&self, feed: &str, receipt: Option<&SyncTransactionReceipt>, success: bool, started: SystemTime) {
+        let tx_hash = receipt.map(|r| r.transaction_hash);
+        let update = {
+            let mut in_flight = self.in_flight.lock();
+            // Failures carry no receipt: take the oldest submitted update of this feed
+            let index = in_flight
+                .iter()
+                .position(|u| tx_hash.is_some() && u.tx_hash == tx_hash)
+                .or_else(|| in_flight.iter().position(|u| u.feed == feed && u.submit.is_some()))
+                .or_else(|| in_flight.iter().position(|u| u.feed == feed));
+            match index.and_then(|i| in_flight.remove(i)) {
+                Some(update) => update,
+                None => return,
+            }
+        };
+        self.emit(update, receipt, success, (started, SystemTime::now()));
+    }
+
+    fn emit(
+        &self,
+        update: InFlight,
+        receipt: Option<&SyncTransactionReceipt>,
+        success: bool,
+        complete: (SystemTime, SystemTime),
+    ) {
+        let mut attributes = vec![KeyValue::new("feed", update.feed.clone()), KeyValue::new("success", success)];
+        if let Some(tx_hash) = update.tx_hash {
+            attributes.push(KeyValue::new("tx_hash", tx_hash.to_string()));
+        }
+        let root = self
+            .tracer
+            .span_builder("oracle.update")
+            .with_start_time(update.fired.0)
+            .with_attributes(attributes)
+            .start(&self.tracer);
+        let cx = Context::current_with_span(root);
+
+        let stage = |name: &'static str, (start, end): (SystemTime, SystemTime)| {
+            let mut span = self.tracer.span_builder(name).with_start_time(start).start_with_context(&self.tracer, &cx);
+            span.end_with_timestamp(end.max(start));
+        };
+
+        stage("trigger", update.fired);
+        let signing_from = match update.build {
+            Some(build) => {
+                stage("build_hook", build);
+                build.1
+            }
+            None => update.fired.1,
+        };
+        if let Some((submit_start, submit_end)) = update.submit {
+            stage("sign", (signing_from, submit_start));
+            let mut span = self
+                .tracer
+                .span_builder("submit")
+                .with_start_time(submit_start)
+                .start_with_context(&self.tracer, &cx);
+            if let Some(receipt) = receipt {
+                span.add_event_with_timestamp(
+                    "receipt",
+                    submit_end,
+                    vec![
+                        KeyValue::new("block_number", receipt.block_number.to_string()),
+                        KeyValue::new("gas_used", receipt.gas_used.to_string()),
+                    ],
+                );
+            }
+            if let Some(error) = update.error {
+                span.set_attribute(KeyValue::new("error", error));
+            }
+            span.end_with_timestamp(submit_end.max(submit_start));
+        }
+        stage("on_complete", complete);
+
+        cx.span().end_with_timestamp(complete.1);
+    }
+}
+
+/// Record that a trigger fired `feed` with `calldata`; `started` is when
+/// `should_trigger` was entered
+pub fn trace_fired(feed: &str, calldata: &Bytes, started: SystemTime) {
+    if let Some(tracer) = PipelineTracer::global() {
+        tracer.fired(feed, calldata, started);
+    }
+}
+
+/// Record a build hook replacing `feed`'s calldata `before` with `after`
+pub fn trace_built(feed: &str, before: &Bytes, after: &Bytes, started: SystemTime) {
+    if let Some(tracer) = PipelineTracer::global() {
+        tracer.built(feed, before, after, started);
+    }
+}
+
+/// Finish the trace of `feed`'s update; call at the end of `on_complete`
+pub fn trace_completed(feed: &str, receipt: Option<&SyncTransactionReceipt>, success: bool, started: SystemTime) {
+    if let Some(tracer) = PipelineTracer::global() {
+        tracer.completed(feed, receipt, success, started);
+    }
+}
+
+/// Times each submission for the pipeline trace
+pub struct TracingSubmitter {
+    inner: Arc<dyn TxSubmitter>,
+}
+
+impl TracingSubmitter {
+    pub fn new(inner: Arc<dyn TxSubmitter>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl TxSubmitter for TracingSubmitter {
+    async fn submit(&self, raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        let Some(tracer) = PipelineTracer::global() else {
+            return self.inner.submit(raw_tx).await;
+        };
+        let started = SystemTime::now();
+        let result = self.inner.submit(raw_tx.clone()).await;
+        tracer.submitted(&raw_tx, started, result.as_ref().err().map(|e| e.to_string()));
+        result
+    }
+}
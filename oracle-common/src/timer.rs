@@ -0,0 +1,220 @@
+//! Drift-compensated interval timer.
+//!
+//! Ticks are scheduled at fixed multiples of the interval from the timer's
+//! origin rather than "interval after the last tick", so processing delays
+//! don't accumulate. A tick that fires more than one interval late skips the
+//! missed intervals instead of bursting to catch up.
+//!
+//! Time comes from an injectable [`TimerClock`]; [`MockClock`] lets tests
+//! drive tick/skip behaviour deterministically.
+
+use crate::clock::{SystemTimeSource, TimeSource};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Monotonic and wall-clock time for [`PreciseTimer`]
+pub trait TimerClock: Send + Sync {
+    /// Monotonic time since an arbitrary fixed origin
+    fn monotonic(&self) -> Duration;
+
+    /// Unix time in milliseconds
+    fn wall_ms(&self) -> u64;
+}
+
+/// `Instant` for monotonic time and a [`TimeSource`] (system clock by
+/// default, NTP-corrected if configured) for wall-clock alignment
+pub struct SystemTimerClock {
+    origin: Instant,
+    time_source: Arc<dyn TimeSource>,
+}
+
+impl SystemTimerClock {
+    pub fn new(time_source: Arc<dyn TimeSource>) -> Self {
+        Self { origin: Instant::now(), time_source }
+    }
+}
+
+impl Default for SystemTimerClock {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemTimeSource))
+    }
+}
+
+impl TimerClock for SystemTimerClock {
+    fn monotonic(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn wall_ms(&self) -> u64 {
+        self.time_source.now_ms()
+    }
+}
+
+/// Manually advanced clock for tests. Wall and monotonic time move together
+/// unless the wall clock is stepped with [`MockClock::set_wall_ms`].
+#[derive(Debug)]
+pub struct MockClock {
+    state: Mutex<(Duration, u64)>,
+}
+
+impl MockClock {
+    pub fn new(wall_ms: u64) -> Arc<Self> {
+        Arc::new(Self { state: Mutex::new((Duration::ZERO, wall_ms)) })
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut state = self.state.lock();
+        state.0 += by;
+        state.1 += by.as_millis() as u64;
+    }
+
+    pub fn advance_ms(&self, ms: u64) {
+        self.advance(Duration::from_millis(ms));
+    }
+
+    /// Step the wall clock only, like an NTP correction
+    pub fn set_wall_ms(&self, wall_ms: u64) {
+        self.state.lock().1 = wall_ms;
+    }
+}
+
+impl TimerClock for MockClock {
+    fn monotonic(&self) -> Duration {
+        self.state.lock().0
+    }
+
+    fn wall_ms(&self) -> u64 {
+        self.state.lock().1
+    }
+}
+
+/// A precise timer that tracks when ticks should occur
+pub struct PreciseTimer {
+    clock: Arc<dyn TimerClock>,
+    /// Target interval in milliseconds
+    interval_ms: u64,
+    /// Clock reading when the timer started
+    start: Duration,
+    /// Next target tick time
+    next_tick: u64,
+    /// Target of the first tick under the current interval; later ticks are
+    /// counted from here
+    epoch_ms: u64,
+    /// Ticks fired since `epoch_ms`
+    tick_count: u64,
+    /// Wall-clock boundary (Unix ms) the ticks are counted from, when aligned
+    wall_anchor_ms: Option<u64>,
+}
+
+impl PreciseTimer {
+    /// Create a new precise timer with the given interval
+    pub fn new(interval_ms: u64) -> Self {
+        Self::with_clock(interval_ms, Arc::new(SystemTimerClock::default()))
+    }
+
+    /// Create a timer whose ticks land on wall-clock multiples of the
+    /// interval (e.g. :00.000, :00.100, ...) regardless of process start time,
+    /// so every instance updates at the same predictable timestamps
+    pub fn new_aligned(interval_ms: u64) -> Self {
+        Self::aligned_with_clock(interval_ms, Arc::new(SystemTimerClock::default()))
+    }
+
+    pub fn with_clock(interval_ms: u64, clock: Arc<dyn TimerClock>) -> Self {
+        Self {
+            start: clock.monotonic(),
+            clock,
+            interval_ms,
+            next_tick: interval_ms,
+            epoch_ms: interval_ms,
+            tick_count: 0,
+            wall_anchor_ms: None,
+        }
+    }
+
+    pub fn aligned_with_clock(interval_ms: u64, clock: Arc<dyn TimerClock>) -> Self {
+        let now_ms = clock.wall_ms();
+        Self {
+            wall_anchor_ms: Some(now_ms - now_ms % interval_ms),
+            ..Self::with_clock(interval_ms, clock)
+        }
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    /// Switch to a new interval. The already scheduled tick still fires;
+    /// later ones follow the new interval (on its wall-clock multiples when aligned).
+    pub fn set_interval(&mut self, interval_ms: u64) {
+        if interval_ms == self.interval_ms {
+            return;
+        }
+        let epoch_ms = match self.wall_anchor_ms {
+            Some(anchor) => {
+                let wall = anchor + self.next_tick;
+                wall.div_ceil(interval_ms) * interval_ms - anchor
+            }
+            None => self.next_tick,
+        };
+        self.interval_ms = interval_ms;
+        self.epoch_ms = epoch_ms;
+        self.tick_count = 0;
+        self.next_tick = epoch_ms;
+    }
+
+    /// Time since the timer's origin. Aligned timers follow the wall clock so
+    /// they stay on the boundaries even if it is slewed.
+    fn elapsed(&self) -> Duration {
+        match self.wall_anchor_ms {
+            Some(anchor) => Duration::from_millis(self.clock.wall_ms().saturating_sub(anchor)),
+            None => self.clock.monotonic().saturating_sub(self.start),
+        }
+    }
+
+    /// Time left until the next target tick (zero when it is due)
+    pub fn time_until_next(&self) -> Duration {
+        Duration::from_millis(self.next_tick).saturating_sub(self.elapsed())
+    }
+
+    /// Monotonic instant of the next target tick
+    pub fn next_deadline(&self) -> Instant {
+        Instant::now() + self.time_until_next()
+    }
+
+    /// Whether a tick is due, without consuming it
+    pub fn is_due(&self) -> bool {
+        self.elapsed().as_millis() as u64 >= self.next_tick
+    }
+
+    /// Check if it's time for the next tick
+    /// Returns Some((target_time_ms, actual_time_ms)) if tick should occur
+    pub fn should_tick(&mut self) -> Option<(u64, u64)> {
+        let elapsed_ms = self.elapsed().as_millis() as u64;
+
+        if elapsed_ms >= self.next_tick {
+            let target_time = self.next_tick;
+            let actual_time = elapsed_ms;
+
+            // If we're running behind, skip to the current time interval
+            // This prevents trying to catch up on all missed ticks
+            if elapsed_ms > self.next_tick + self.interval_ms {
+                // Calculate how many intervals we've missed
+                let missed_intervals = (elapsed_ms - self.next_tick) / self.interval_ms;
+                self.tick_count += missed_intervals + 1;
+                self.next_tick = self.epoch_ms + self.tick_count * self.interval_ms;
+
+                debug!("Skipped {} missed intervals, jumping to current time", missed_intervals);
+            } else {
+                // Normal case: just increment by one
+                self.tick_count += 1;
+                self.next_tick = self.epoch_ms + self.tick_count * self.interval_ms;
+            }
+
+            Some((target_time, actual_time))
+        } else {
+            None
+        }
+    }
+}
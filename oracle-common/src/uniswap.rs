@@ -0,0 +1,226 @@
+//! Uniswap V3 pool TWAP source.
+//!
+//! Each poll calls `observe([window, 0])` on the pool and turns the tick
+//! cumulatives into the time-weighted average tick, so a single block's
+//! swaps cannot move the price much. Feeds with `source = "uniswap-v3"` use
+//! `chain:pool` as their symbol, with the chain's RPC in
+//! `UNISWAP_RPC_<CHAIN>`:
+//!
+//! ```text
+//! symbol = "ethereum:0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"
+//! UNISWAP_RPC_ETHEREUM=https://eth.llamarpc.com
+//! ```
+//!
+//! The price is token1 per token0 in whole tokens; set `invert` on the feed
+//! when the pool quotes the pair the other way round (e.g. USDC/WETH).
+
+use alloy::primitives::Address;
+use alloy::providers::ProviderBuilder;
+use alloy::sol;
+use anyhow::{anyhow, bail, Context, Result};
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::feed_trigger::{PricePoint, PriceSource};
+use crate::feeds::{FeedConfig, SourceKind};
+use crate::shutdown::ShutdownCoordinator;
+
+sol!(
+    #[sol(rpc)]
+    interface IUniswapV3Pool {
+        function token0() external view returns (address);
+        function token1() external view returns (address);
+        function observe(uint32[] calldata secondsAgos) external view returns (int56[] memory tickCumulatives, uint160[] memory secondsPerLiquidityCumulativeX128s);
+    }
+
+    #[sol(rpc)]
+    interface IERC20Decimals {
+        function decimals() external view returns (uint8);
+    }
+);
+
+/// Average tick over `window_secs` from the cumulatives at its start and end
+pub fn twap_tick(cumulative_start: i64, cumulative_end: i64, window_secs: u32) -> f64 {
+    (cumulative_end - cumulative_start) as f64 / window_secs.max(1) as f64
+}
+
+/// Whole token1 per whole token0 at `tick`
+pub fn tick_to_price(tick: f64, decimals0: u8, decimals1: u8) -> f64 {
+    1.0001f64.powf(tick) * 10f64.powi(decimals0 as i32 - decimals1 as i32)
+}
+
+/// A pool named by a `chain:pool` feed symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniswapPool {
+    pub chain: String,
+    pub pool: Address,
+}
+
+impl UniswapPool {
+    pub fn parse(symbol: &str) -> Result<Self> {
+        let (chain, pool) = symbol
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid Uniswap pool '{}' (expected chain:pool)", symbol))?;
+        let chain = chain.trim();
+        if chain.is_empty() {
+            bail!("Uniswap pool '{}' has an empty chain", symbol);
+        }
+        let pool = pool
+            .trim()
+            .parse::<Address>()
+            .map_err(|e| anyhow!("Invalid pool address in '{}': {}", symbol, e))?;
+        Ok(Self { chain: chain.to_lowercase(), pool })
+    }
+
+    /// `UNISWAP_RPC_<CHAIN>`, the pool chain's RPC URL
+    pub fn rpc_env_key(&self) -> String {
+        format!("UNISWAP_RPC_{}", self.chain.to_uppercase().replace('-', "_"))
+    }
+
+    pub fn rpc_url(&self) -> Result<String> {
+        let key = self.rpc_env_key();
+        std::env::var(&key).with_context(|| format!("{} must be set to read pool {} on {}", key, self.pool, self.chain))
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Latest TWAP of one pool, refreshed by a background poller
+pub struct UniswapV3Source {
+    latest: Arc<RwLock<Option<PricePoint>>>,
+}
+
+impl PriceSource for UniswapV3Source {
+    fn latest(&self) -> Option<PricePoint> {
+        self.latest.read().clone()
+    }
+}
+
+struct PoolPoller {
+    pool: UniswapPool,
+    rpc_url: String,
+    window_secs: u32,
+    latest: Arc<RwLock<Option<PricePoint>>>,
+}
+
+impl PoolPoller {
+    fn spawn(self, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let label = format!("{}:{}", self.pool.chain, self.pool.pool);
+            let provider = match self.rpc_url.parse() {
+                Ok(url) => ProviderBuilder::new().on_http(url),
+                Err(e) => {
+                    warn!("⚠️ Invalid RPC URL for Uniswap pool {}: {}", label, e);
+                    return;
+                }
+            };
+            let pool = IUniswapV3Pool::new(self.pool.pool, provider.clone());
+            let mut decimals: Option<(u8, u8)> = None;
+
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                // Token decimals never change; read them once the RPC answers
+                let (decimals0, decimals1) = match decimals {
+                    Some(d) => d,
+                    None => {
+                        let read = async {
+                            let token0 = pool.token0().call().await?._0;
+                            let token1 = pool.token1().call().await?._0;
+                            let d0 = IERC20Decimals::new(token0, provider.clone()).decimals().call().await?._0;
+                            let d1 = IERC20Decimals::new(token1, provider.clone()).decimals().call().await?._0;
+                            anyhow::Ok((d0, d1))
+                        };
+                        match read.await {
+                            Ok(d) => {
+                                info!("🦄 Uniswap pool {}: token decimals {}/{}, {}s TWAP", label, d.0, d.1, self.window_secs);
+                                decimals = Some(d);
+                                d
+                            }
+                            Err(e) => {
+                                warn!("⚠️ Failed to read Uniswap pool {} tokens: {}", label, e);
+                                continue;
+                            }
+                        }
+                    }
+                };
+
+                match pool.observe(vec![self.window_secs, 0]).call().await {
+                    Ok(observed) if observed.tickCumulatives.len() == 2 => {
+                        let cumulatives: Result<Vec<i64>, _> =
+                            observed.tickCumulatives.iter().map(|c| i64::try_from(*c)).collect();
+                        let Ok(cumulatives) = cumulatives else {
+                            warn!("⚠️ Uniswap pool {} returned an out-of-range tick cumulative", label);
+                            continue;
+                        };
+                        let tick = twap_tick(cumulatives[0], cumulatives[1], self.window_secs);
+                        let price = tick_to_price(tick, decimals0, decimals1);
+                        debug!("🦄 {} TWAP tick {:.2}: {}", label, tick, price);
+                        *self.latest.write() = Some(PricePoint {
+                            price,
+                            num_trades: 1,
+                            timestamp_ms: now_ms(),
+                            confidence: None,
+                        });
+                    }
+                    Ok(_) => warn!("⚠️ Uniswap pool {} returned an unexpected observation count", label),
+                    // Reverts with "OLD" when the window predates the pool's oldest observation
+                    Err(e) => warn!("⚠️ Failed to observe Uniswap pool {} over {}s: {}", label, self.window_secs, e),
+                }
+            }
+        })
+    }
+}
+
+/// Collects the pools to read, then starts one poller per pool and window
+#[derive(Default)]
+pub struct UniswapSources {
+    pollers: Vec<PoolPoller>,
+}
+
+impl UniswapSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Source for `feed`, whose symbol is `chain:pool` and whose
+    /// `window_secs` is the TWAP window
+    pub fn add(&mut self, feed: &FeedConfig) -> Result<Arc<dyn PriceSource>> {
+        if feed.source != SourceKind::UniswapV3 {
+            bail!("Feed '{}': {:?} is not a Uniswap V3 source", feed.id, feed.source);
+        }
+        let pool = UniswapPool::parse(&feed.symbol).with_context(|| format!("Feed '{}'", feed.id))?;
+        let window_secs = u32::try_from(feed.window_secs)
+            .ok()
+            .filter(|w| *w > 0)
+            .ok_or_else(|| anyhow!("Feed '{}': invalid Uniswap TWAP window {}s", feed.id, feed.window_secs))?;
+
+        if let Some(existing) = self.pollers.iter().find(|p| p.pool == pool && p.window_secs == window_secs) {
+            return Ok(Arc::new(UniswapV3Source { latest: existing.latest.clone() }));
+        }
+        let latest = Arc::new(RwLock::new(None));
+        self.pollers.push(PoolPoller { rpc_url: pool.rpc_url()?, pool, window_secs, latest: latest.clone() });
+        Ok(Arc::new(UniswapV3Source { latest }))
+    }
+
+    /// Start polling every pool added so far (`UNISWAP_POLL_MS`, default 5000)
+    pub fn spawn(self, shutdown: &mut ShutdownCoordinator) -> Result<()> {
+        if self.pollers.is_empty() {
+            return Ok(());
+        }
+        let poll_ms: u64 = match std::env::var("UNISWAP_POLL_MS") {
+            Ok(v) => v.parse().context("Invalid UNISWAP_POLL_MS")?,
+            Err(_) => 5_000,
+        };
+        for poller in self.pollers {
+            shutdown.register("uniswap pool poller", poller.spawn(Duration::from_millis(poll_ms.max(100))));
+        }
+        Ok(())
+    }
+}
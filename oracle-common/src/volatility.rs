@@ -0,0 +1,58 @@
+//! Realized volatility of a trade window.
+//!
+//! Trades are sampled into fixed bars (last price per bar, carried forward
+//! over empty bars), and the sum of squared log returns between bars is
+//! annualized over the sampled span:
+//! `sqrt(sum(r^2) * SECONDS_PER_YEAR / span_secs)`. The result is a fraction
+//! (0.55 = 55% annualized), published like a price with the feed's decimals.
+
+/// 365 days; crypto trades around the clock
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// Fewest bar returns a volatility is computed from
+pub const MIN_RETURNS: usize = 10;
+
+/// Last price of each `bar_ms` bar between the first and last trade, carried
+/// forward over bars without trades. `trades` are `(timestamp_ms, price)` in
+/// time order.
+pub fn bar_closes(trades: &[(u64, f64)], bar_ms: u64) -> Vec<f64> {
+    let (Some(first), Some(last)) = (trades.first(), trades.last()) else {
+        return Vec::new();
+    };
+    let bar_ms = bar_ms.max(1);
+    let start = first.0 / bar_ms;
+    let count = (last.0 / bar_ms).saturating_sub(start) as usize + 1;
+
+    let mut closes: Vec<Option<f64>> = vec![None; count];
+    for (timestamp, price) in trades {
+        if *price > 0.0 {
+            let bar = (timestamp / bar_ms).saturating_sub(start) as usize;
+            if let Some(close) = closes.get_mut(bar) {
+                *close = Some(*price);
+            }
+        }
+    }
+
+    let mut previous = None;
+    closes
+        .into_iter()
+        .filter_map(|close| {
+            previous = close.or(previous);
+            previous
+        })
+        .collect()
+}
+
+/// Annualized realized volatility of `trades` sampled every `bar_ms`;
+/// `None` with fewer than [`MIN_RETURNS`] bar returns
+pub fn realized_volatility(trades: &[(u64, f64)], bar_ms: u64) -> Option<f64> {
+    let closes = bar_closes(trades, bar_ms);
+    let returns = closes.len().saturating_sub(1);
+    if returns < MIN_RETURNS {
+        return None;
+    }
+
+    let variance: f64 = closes.windows(2).map(|w| (w[1] / w[0]).ln().powi(2)).sum();
+    let span_secs = returns as f64 * bar_ms.max(1) as f64 / 1000.0;
+    Some((variance * SECONDS_PER_YEAR / span_secs).sqrt())
+}
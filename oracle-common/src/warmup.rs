@@ -0,0 +1,168 @@
+//! Warm-up before the first publish and hold-off after pauses.
+//!
+//! A trade window that just started filling (at startup, or after the
+//! worker pool was paused) gives a price from a handful of trades. Each
+//! trigger holds a [`WarmupGate`] that withholds updates until the window
+//! has `WARMUP_MIN_TRADES` trades covering `WARMUP_MIN_FILL` of its length,
+//! and for `RESUME_HOLDOFF_MS` after a pause ends, so the ticks that came due
+//! during the pause don't all fire at once.
+//!
+//! Only windowed sources (Binance trades) report a fill; sources without a
+//! trade window (Pyth, REST, FX, Uniswap) are ready at their first value.
+
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::timer::{SystemTimerClock, TimerClock};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WarmupConfig {
+    /// Trades in the window before publishing (windowed sources only)
+    pub min_trades: u64,
+    /// Fraction of the window covered by trades before publishing, 0..=1
+    pub min_fill: f64,
+    /// Updates withheld for this long after a pause ends
+    pub resume_holdoff: Duration,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self { min_trades: 10, min_fill: 0.5, resume_holdoff: Duration::from_secs(1) }
+    }
+}
+
+impl WarmupConfig {
+    /// Disabled warm-up: publish from the first value, right after pauses
+    pub fn none() -> Self {
+        Self { min_trades: 0, min_fill: 0.0, resume_holdoff: Duration::ZERO }
+    }
+
+    /// `WARMUP_MIN_TRADES` (default 10), `WARMUP_MIN_FILL` (default 0.5) and
+    /// `RESUME_HOLDOFF_MS` (default 1000)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let min_trades = match std::env::var("WARMUP_MIN_TRADES") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                warn!("Invalid WARMUP_MIN_TRADES '{}', using {}", v, defaults.min_trades);
+                defaults.min_trades
+            }),
+            Err(_) => defaults.min_trades,
+        };
+        let min_fill = match std::env::var("WARMUP_MIN_FILL") {
+            Ok(v) => match v.parse::<f64>() {
+                Ok(fill) if (0.0..=1.0).contains(&fill) => fill,
+                _ => {
+                    warn!("Invalid WARMUP_MIN_FILL '{}' (0 to 1), using {}", v, defaults.min_fill);
+                    defaults.min_fill
+                }
+            },
+            Err(_) => defaults.min_fill,
+        };
+        let resume_holdoff = match std::env::var("RESUME_HOLDOFF_MS") {
+            Ok(v) => v.parse().map(Duration::from_millis).unwrap_or_else(|_| {
+                warn!("Invalid RESUME_HOLDOFF_MS '{}', using {:?}", v, defaults.resume_holdoff);
+                defaults.resume_holdoff
+            }),
+            Err(_) => defaults.resume_holdoff,
+        };
+        Self { min_trades, min_fill, resume_holdoff }
+    }
+}
+
+/// Per-trigger warm-up and post-pause hold-off state
+pub struct WarmupGate {
+    label: String,
+    config: WarmupConfig,
+    clock: Arc<dyn TimerClock>,
+    /// Window was full enough since startup / the last pause
+    warmed: AtomicBool,
+    paused: AtomicBool,
+    resumed_at: RwLock<Option<Duration>>,
+    /// Updates withheld while warming up or holding off
+    withheld: AtomicU64,
+}
+
+impl WarmupGate {
+    pub fn new(label: impl Into<String>, config: WarmupConfig) -> Self {
+        Self {
+            label: label.into(),
+            config,
+            clock: Arc::new(SystemTimerClock::default()),
+            warmed: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            resumed_at: RwLock::new(None),
+            withheld: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_env(label: impl Into<String>) -> Arc<Self> {
+        Arc::new(Self::new(label, WarmupConfig::from_env()))
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn TimerClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Record this poll's pause state. False while paused and during the
+    /// hold-off after it ends; a pause also restarts the warm-up.
+    pub fn running(&self, paused: bool) -> bool {
+        if paused {
+            self.paused.store(true, Ordering::Relaxed);
+            self.warmed.store(false, Ordering::Relaxed);
+            return false;
+        }
+        let now = self.clock.monotonic();
+        if self.paused.swap(false, Ordering::Relaxed) {
+            *self.resumed_at.write() = Some(now);
+            if !self.config.resume_holdoff.is_zero() {
+                info!("⏸️ {} resumed, holding updates for {:?}", self.label, self.config.resume_holdoff);
+            }
+        }
+        let holding = self
+            .resumed_at
+            .read()
+            .is_some_and(|resumed| now.saturating_sub(resumed) < self.config.resume_holdoff);
+        if holding {
+            self.withheld.fetch_add(1, Ordering::Relaxed);
+        }
+        !holding
+    }
+
+    /// Whether a value from `num_trades` trades covering `window_fill` of the
+    /// window (None for sources without a trade window) may be published
+    pub fn ready(&self, num_trades: u64, window_fill: Option<f64>) -> bool {
+        if self.warmed.load(Ordering::Relaxed) {
+            return true;
+        }
+        let Some(fill) = window_fill else {
+            self.warmed.store(true, Ordering::Relaxed);
+            return true;
+        };
+        if num_trades < self.config.min_trades || fill < self.config.min_fill {
+            self.withheld.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        info!("✅ {} warmed up ({} trades, {:.0}% of the window)", self.label, num_trades, fill * 100.0);
+        self.warmed.store(true, Ordering::Relaxed);
+        true
+    }
+
+    pub fn is_warm(&self) -> bool {
+        self.warmed.load(Ordering::Relaxed)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "warm": self.is_warm(),
+            "min_trades": self.config.min_trades,
+            "min_fill": self.config.min_fill,
+            "resume_holdoff_ms": self.config.resume_holdoff.as_millis() as u64,
+            "withheld": self.withheld.load(Ordering::Relaxed),
+        })
+    }
+}
@@ -0,0 +1,121 @@
+//! Bandwidth of the exchange websockets.
+//!
+//! In volatile markets the trade streams carry thousands of messages a
+//! second, which is a real share of a small VM's link. Every connection made
+//! with [`connect_ws`](crate::connect_ws) counts the bytes it moves on the
+//! socket (TLS and framing included) and the text payload it delivers, per
+//! source, served under `websockets` at `/runtime`.
+//!
+//! Compression is not negotiated: tokio-tungstenite 0.24 doesn't implement
+//! permessage-deflate, so the clients can't offer it. Enabling it is blocked
+//! on a websocket client that does; until then these counters are the
+//! baseline to measure it against.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Byte counters of one source's websocket connections
+#[derive(Default)]
+pub struct WsBandwidth {
+    wire_in: AtomicU64,
+    wire_out: AtomicU64,
+    messages: AtomicU64,
+    payload_bytes: AtomicU64,
+}
+
+impl WsBandwidth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A message delivered to the client
+    pub fn message(&self, payload: &[u8]) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.payload_bytes.fetch_add(payload.len() as u64, Ordering::Relaxed);
+    }
+
+    pub fn payload_bytes(&self) -> u64 {
+        self.payload_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn wire_in_bytes(&self) -> u64 {
+        self.wire_in.load(Ordering::Relaxed)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "wire_in_bytes": self.wire_in_bytes(),
+            "wire_out_bytes": self.wire_out.load(Ordering::Relaxed),
+            "messages": self.messages.load(Ordering::Relaxed),
+            "payload_bytes": self.payload_bytes(),
+        })
+    }
+}
+
+fn registry() -> &'static Mutex<BTreeMap<String, Arc<WsBandwidth>>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<String, Arc<WsBandwidth>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Process-wide counters of `source` ("binance", "tradermade", ...)
+pub fn ws_bandwidth(source: &str) -> Arc<WsBandwidth> {
+    registry()
+        .lock()
+        .entry(source.to_string())
+        .or_insert_with(|| Arc::new(WsBandwidth::new()))
+        .clone()
+}
+
+/// Every source's counters, for `/runtime`
+pub fn ws_bandwidth_json() -> Value {
+    let sources: serde_json::Map<String, Value> =
+        registry().lock().iter().map(|(source, bandwidth)| (source.clone(), bandwidth.to_json())).collect();
+    Value::Object(sources)
+}
+
+/// Socket wrapper counting the bytes read and written into a [`WsBandwidth`]
+pub struct CountingStream<S> {
+    inner: S,
+    bandwidth: Arc<WsBandwidth>,
+}
+
+impl<S> CountingStream<S> {
+    pub fn new(inner: S, bandwidth: Arc<WsBandwidth>) -> Self {
+        Self { inner, bandwidth }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CountingStream<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        let read = buf.filled().len() - before;
+        self.bandwidth.wire_in.fetch_add(read as u64, Ordering::Relaxed);
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CountingStream<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = &result {
+            self.bandwidth.wire_out.fetch_add(*written as u64, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
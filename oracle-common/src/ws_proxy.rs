@@ -0,0 +1,168 @@
+//! Proxies for the exchange websocket clients.
+//!
+//! Some deployment regions can't reach exchanges directly. With
+//! `WS_PROXY_URL` set, the Binance and TraderMade clients open their
+//! connection through an HTTP `CONNECT` or SOCKS5 proxy instead, and TLS and
+//! the websocket handshake run end to end through the tunnel.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `WS_PROXY_URL` | unset (direct) | `http://[user:pass@]host:port` (HTTP `CONNECT`) or `socks5://[user:pass@]host:port` |
+//! | `WS_NO_PROXY` | unset | Comma-separated hosts connected to directly, e.g. a local mock |
+//!
+//! SOCKS5 passes hostnames to the proxy, so exchange names resolve on its
+//! side (`socks5h://` is accepted as a synonym). Credentials may be
+//! percent-encoded. HTTP traffic (RPC, REST sources) is not affected: use
+//! the standard `HTTPS_PROXY` for it.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use percent_encoding::percent_decode_str;
+use reqwest::Url;
+use std::env;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::debug;
+
+use crate::ws_bandwidth::{CountingStream, WsBandwidth};
+
+/// Longest `CONNECT` response header accepted
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+pub type WsStream = WebSocketStream<MaybeTlsStream<CountingStream<TcpStream>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsProxyKind {
+    HttpConnect,
+    Socks5,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsProxy {
+    pub kind: WsProxyKind,
+    /// `host:port` of the proxy
+    pub addr: String,
+    pub credentials: Option<(String, String)>,
+    /// Hosts connected to directly
+    pub no_proxy: Vec<String>,
+}
+
+fn decode(part: &str) -> Result<String> {
+    Ok(percent_decode_str(part).decode_utf8().context("Invalid proxy credentials")?.into_owned())
+}
+
+impl WsProxy {
+    /// Parse a proxy URL (see the module docs for the schemes)
+    pub fn parse(url: &str) -> Result<Self> {
+        let parsed = Url::parse(url).with_context(|| format!("Invalid proxy URL '{}'", url))?;
+        let (kind, default_port) = match parsed.scheme() {
+            "http" => (WsProxyKind::HttpConnect, 80),
+            "socks5" | "socks5h" => (WsProxyKind::Socks5, 1080),
+            other => bail!("Unsupported proxy scheme '{}' (use http, socks5 or socks5h)", other),
+        };
+        let host = parsed.host_str().ok_or_else(|| anyhow!("Proxy URL '{}' has no host", url))?;
+        let credentials = match parsed.username() {
+            "" => None,
+            user => Some((decode(user)?, decode(parsed.password().unwrap_or(""))?)),
+        };
+        Ok(Self {
+            kind,
+            addr: format!("{}:{}", host, parsed.port().unwrap_or(default_port)),
+            credentials,
+            no_proxy: Vec::new(),
+        })
+    }
+
+    /// `WS_PROXY_URL` and `WS_NO_PROXY`; `None` when no proxy is set
+    pub fn from_env() -> Result<Option<Self>> {
+        let url = match env::var("WS_PROXY_URL") {
+            Ok(url) if !url.trim().is_empty() => url,
+            _ => return Ok(None),
+        };
+        let mut proxy = Self::parse(url.trim()).context("WS_PROXY_URL")?;
+        proxy.no_proxy = env::var("WS_NO_PROXY")
+            .unwrap_or_default()
+            .split(',')
+            .map(|host| host.trim().to_ascii_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect();
+        Ok(Some(proxy))
+    }
+
+    pub fn bypasses(&self, host: &str) -> bool {
+        self.no_proxy.iter().any(|skip| skip.eq_ignore_ascii_case(host))
+    }
+
+    /// A TCP stream to `host:port` through the proxy
+    pub async fn tunnel(&self, host: &str, port: u16) -> Result<TcpStream> {
+        match self.kind {
+            WsProxyKind::HttpConnect => self.http_connect(host, port).await,
+            WsProxyKind::Socks5 => {
+                let stream = match &self.credentials {
+                    Some((user, pass)) => {
+                        Socks5Stream::connect_with_password(self.addr.as_str(), (host, port), user, pass).await
+                    }
+                    None => Socks5Stream::connect(self.addr.as_str(), (host, port)).await,
+                }
+                .with_context(|| format!("SOCKS5 proxy {} failed to reach {}:{}", self.addr, host, port))?;
+                Ok(stream.into_inner())
+            }
+        }
+    }
+
+    async fn http_connect(&self, host: &str, port: u16) -> Result<TcpStream> {
+        let mut stream = TcpStream::connect(&self.addr)
+            .await
+            .with_context(|| format!("Failed to connect to proxy {}", self.addr))?;
+        let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+        if let Some((user, pass)) = &self.credentials {
+            request.push_str(&format!(
+                "Proxy-Authorization: Basic {}\r\n",
+                STANDARD.encode(format!("{}:{}", user, pass))
+            ));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Read the response header byte by byte so nothing past it is consumed
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_CONNECT_RESPONSE {
+                bail!("Proxy {} sent an oversized CONNECT response", self.addr);
+            }
+            let byte = stream.read_u8().await.with_context(|| format!("Proxy {} closed during CONNECT", self.addr))?;
+            response.push(byte);
+        }
+        let status_line = String::from_utf8_lossy(&response);
+        let status_line = status_line.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(status) if status.starts_with('2') => Ok(stream),
+            _ => bail!("Proxy {} refused CONNECT to {}:{}: {}", self.addr, host, port, status_line),
+        }
+    }
+}
+
+/// Open a websocket to `url`, through `proxy` unless it is unset or bypasses
+/// the host, counting its traffic into `bandwidth`
+pub async fn connect_ws(url: &str, proxy: Option<&WsProxy>, bandwidth: Arc<WsBandwidth>) -> Result<(WsStream, Response)> {
+    let parsed = Url::parse(url).with_context(|| format!("Invalid websocket URL '{}'", url))?;
+    let host = parsed.host_str().ok_or_else(|| anyhow!("Websocket URL '{}' has no host", url))?;
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| anyhow!("Websocket URL '{}' has no port", url))?;
+    let stream = match proxy.filter(|proxy| !proxy.bypasses(host)) {
+        Some(proxy) => {
+            debug!("Connecting to {}:{} via proxy {}", host, port, proxy.addr);
+            proxy.tunnel(host, port).await?
+        }
+        None => TcpStream::connect((host, port)).await?,
+    };
+    stream.set_nodelay(true)?;
+    let stream = CountingStream::new(stream, bandwidth);
+    Ok(tokio_tungstenite::client_async_tls(url, stream).await?)
+}
@@ -0,0 +1,69 @@
+//! Per-feed access lists: parsing, the contract default (including target
+//! overrides) and the metadata the dry-run signer reads
+
+use alloy::eips::eip2930::AccessListItem;
+use alloy::primitives::{b256, Address, Bytes, B256};
+use nonzu_sdk::traits::TxRequest;
+use oracle_common::{AccessListConfig, FeedsFile};
+
+const SLOT: B256 = b256!("0000000000000000000000000000000000000000000000000000000000000003");
+
+const FEEDS: &str = r#"
+[[targets]]
+name = "rise-mainnet"
+rpc_urls = ["https://mainnet.example"]
+contracts = { BTCUSD = "0x2222222222222222222222222222222222222222" }
+
+[[feeds]]
+id = "BTCUSD"
+source = "binance"
+symbol = "BTCUSDT"
+contract = "0x1111111111111111111111111111111111111111"
+
+[[feeds.access_list]]
+storage_keys = ["0x0000000000000000000000000000000000000000000000000000000000000003"]
+
+[[feeds.access_list]]
+address = "0x3333333333333333333333333333333333333333"
+"#;
+
+fn update() -> TxRequest {
+    TxRequest::new(Address::repeat_byte(0xaa), Bytes::new())
+}
+
+#[test]
+fn test_entries_default_to_the_feed_contract() {
+    let file = FeedsFile::parse(FEEDS).unwrap();
+    let feed = &file.feeds[0];
+    assert_eq!(
+        feed.access_list().list().0,
+        vec![
+            AccessListItem { address: Address::repeat_byte(0x11), storage_keys: vec![SLOT] },
+            AccessListItem { address: Address::repeat_byte(0x33), storage_keys: vec![] },
+        ]
+    );
+
+    // A target's contract override moves the default with it
+    let on_target = file.targets[0].feed_for(feed);
+    assert_eq!(on_target.access_list().list().0[0].address, Address::repeat_byte(0x22));
+}
+
+#[test]
+fn test_empty_entries_are_rejected() {
+    let feeds = FEEDS.replace("address = \"0x3333333333333333333333333333333333333333\"", "");
+    let err = FeedsFile::parse(&feeds).unwrap_err();
+    assert!(err.to_string().contains("access_list entries need an address or storage_keys"));
+}
+
+#[test]
+fn test_access_list_travels_with_the_request() {
+    let plain = AccessListConfig::default().apply(update());
+    assert!(plain.build_hook.is_none());
+    assert_eq!(AccessListConfig::from_request(&plain).unwrap(), None);
+
+    let file = FeedsFile::parse(FEEDS).unwrap();
+    let config = file.feeds[0].access_list();
+    let request = config.apply(update());
+    assert!(request.build_hook.is_some());
+    assert_eq!(AccessListConfig::from_request(&request).unwrap().as_ref(), Some(config.list()));
+}
@@ -0,0 +1,135 @@
+//! Audit entries, the hash chain and rotation
+
+use alloy::consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::TxSigner;
+use alloy::primitives::{Address, Bytes, TxKind, U256};
+use alloy::signers::local::PrivateKeySigner;
+use oracle_common::{encode_update_price, encode_update_timestamp, function_selector, AuditEntry, AuditLog};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+async fn signed(signer: &PrivateKeySigner, nonce: u64, input: Bytes) -> Bytes {
+    let mut tx = TxEip1559 {
+        chain_id: 11155931,
+        nonce,
+        gas_limit: 80_000,
+        max_fee_per_gas: 300_000,
+        max_priority_fee_per_gas: 100_000,
+        to: TxKind::Call(Address::repeat_byte(0x42)),
+        value: U256::ZERO,
+        input,
+        access_list: Default::default(),
+    };
+    let signature = signer.sign_transaction(&mut tx).await.unwrap();
+    TxEnvelope::Eip1559(tx.into_signed(signature)).encoded_2718().into()
+}
+
+fn update_price(feed: &str) -> Bytes {
+    encode_update_price(function_selector("updatePrice(string,uint256)"), feed, U256::from(97_000u64))
+}
+
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("audit-{}-{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Wait for the writer thread to get `count` entries into `path`
+async fn entries(path: &Path, count: usize) -> Vec<AuditEntry> {
+    for _ in 0..50 {
+        if let Ok(entries) = AuditLog::read(path) {
+            if entries.len() >= count {
+                return entries;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("{} never had {} entries", path.display(), count);
+}
+
+#[tokio::test]
+async fn test_entry_decodes_transaction() {
+    let signer = PrivateKeySigner::random();
+    let calldata = update_price("BTCUSD");
+    let entry = AuditEntry::new(&signed(&signer, 3, calldata.clone()).await, "rise", 1);
+
+    assert_eq!((entry.from, entry.nonce, entry.gas_limit), (Some(signer.address()), Some(3), Some(80_000)));
+    assert_eq!((entry.max_fee_per_gas, entry.max_priority_fee_per_gas), (Some(300_000), Some(100_000)));
+    assert_eq!(entry.calldata, calldata);
+    assert_eq!(entry.feed.as_deref(), Some("BTCUSD"));
+
+    // Calls without a leading string id have no feed
+    let entry = AuditEntry::new(&signed(&signer, 4, encode_update_timestamp(1_700_000_000_000)).await, "rise", 1);
+    assert_eq!(entry.feed, None);
+}
+
+#[tokio::test]
+async fn test_chain_continues_across_restarts_and_detects_edits() {
+    let path = log_path("chain");
+    let signer = PrivateKeySigner::random();
+
+    let log = AuditLog::open(&path, u64::MAX, true).unwrap();
+    for nonce in 0..3 {
+        log.record(AuditEntry::new(&signed(&signer, nonce, update_price("ETHUSD")).await, "rise", nonce));
+    }
+    entries(&path, 3).await;
+    drop(log);
+
+    let log = AuditLog::open(&path, u64::MAX, true).unwrap();
+    log.record(AuditEntry::new(&signed(&signer, 3, update_price("ETHUSD")).await, "rise", 3));
+    let written = entries(&path, 4).await;
+    assert_eq!(written.iter().map(|e| e.seq).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert_eq!(written[3].prev_hash, written[2].hash);
+    assert_eq!(AuditLog::verify(&path).unwrap(), 4);
+
+    // Editing any entry breaks the chain
+    let edited = std::fs::read_to_string(&path).unwrap().replacen("\"nonce\":1", "\"nonce\":9", 1);
+    std::fs::write(&path, edited).unwrap();
+    assert!(AuditLog::verify(&path).is_err());
+
+    // So does removing one
+    let lines: Vec<String> = written.iter().map(|e| serde_json::to_string(e).unwrap()).collect();
+    std::fs::write(&path, [&lines[..1], &lines[2..]].concat().join("\n") + "\n").unwrap();
+    assert!(AuditLog::verify(&path).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_rotation_keeps_every_entry() {
+    let path = log_path("rotate");
+    let signer = PrivateKeySigner::random();
+    let log = AuditLog::open(&path, 1_500, false).unwrap();
+    for nonce in 0..6 {
+        log.record(AuditEntry::new(&signed(&signer, nonce, update_price("SOLUSD")).await, "rise", nonce));
+    }
+
+    let dir = path.parent().unwrap();
+    let name = path.file_name().unwrap().to_str().unwrap().to_string();
+    let files = |dir: &Path| -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.file_name().unwrap().to_str().unwrap().starts_with(&name))
+            .collect();
+        files.sort();
+        files
+    };
+
+    let mut total = 0;
+    for _ in 0..50 {
+        total = files(dir).iter().map(|f| AuditLog::read(f).unwrap().len()).sum();
+        if total == 6 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(total, 6);
+    let files = files(dir);
+    assert!(files.len() > 1, "rotated into {:?}", files);
+    for file in files {
+        assert!(std::fs::metadata(&file).unwrap().len() <= 1_500);
+        std::fs::remove_file(file).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+//! Pending queue limits and minimum spacing between updates
+
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::types::rise_tx::RiseTransactionRequest;
+use oracle_common::{OverflowPolicy, PendingQueue};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn request() -> TxRequest {
+    TxRequest::new(Address::ZERO, vec![0x01].into())
+}
+
+#[test]
+fn test_reject_new_when_full() {
+    let queue = Arc::new(PendingQueue::new(2, OverflowPolicy::RejectNew));
+    assert!(queue.admit(request()).is_some());
+    assert!(queue.admit(request()).is_some());
+    assert!(queue.admit(request()).is_none());
+    assert_eq!(queue.metrics().rejected, 1);
+
+    queue.complete();
+    assert!(queue.admit(request()).is_some());
+}
+
+#[test]
+fn test_drop_oldest_admits_new() {
+    let queue = Arc::new(PendingQueue::new(1, OverflowPolicy::DropOldest));
+    assert!(queue.admit(request()).is_some());
+    assert!(queue.admit(request()).is_some());
+    assert_eq!(queue.metrics().dropped, 1);
+    assert_eq!(queue.metrics().depth, 1);
+}
+
+#[test]
+fn test_min_spacing() {
+    let queue = Arc::new(
+        PendingQueue::new(4, OverflowPolicy::DropOldest).with_min_spacing(Duration::from_millis(200)),
+    );
+    assert!(queue.admit(request()).is_some());
+    assert!(queue.admit(request()).is_none(), "right after the previous update");
+    assert_eq!(queue.metrics().too_soon, 1);
+
+    // Completing doesn't lift the floor
+    queue.complete();
+    assert!(queue.admit(request()).is_none());
+
+    std::thread::sleep(Duration::from_millis(250));
+    assert!(queue.admit(request()).is_some());
+    assert_eq!(queue.metrics().admitted, 2);
+}
+
+#[tokio::test]
+async fn test_complete_releases_own_ticket() {
+    let queue = Arc::new(PendingQueue::new(4, OverflowPolicy::DropOldest));
+    let (_, first) = queue.admit(request()).unwrap();
+    let (second_request, second) = queue.admit(request()).unwrap();
+
+    // The second request is built and completes first, in its own worker task
+    let worker = queue.clone();
+    let completed = tokio::spawn(async move {
+        let hook = second_request.build_hook.clone().unwrap();
+        hook.on_build(&second_request, RiseTransactionRequest::new()).await.unwrap();
+        worker.complete()
+    })
+    .await
+    .unwrap();
+    assert_eq!(completed.map(|c| c.ticket), Some(second));
+
+    // Without a build in this task, the oldest unbuilt request is released
+    assert_eq!(queue.complete().map(|c| c.ticket), Some(first));
+    assert_eq!(queue.metrics().depth, 0);
+}
+
+#[test]
+fn test_complete_ticket_reports_dropped() {
+    let queue = Arc::new(PendingQueue::new(1, OverflowPolicy::DropOldest));
+    let (_, first) = queue.admit(request()).unwrap();
+    let (_, second) = queue.admit(request()).unwrap();
+
+    let completion = queue.complete_ticket(first).unwrap();
+    assert!(completion.dropped);
+    assert!(!queue.complete_ticket(second).unwrap().dropped);
+    assert!(queue.complete_ticket(second).is_none());
+}
+
+#[test]
+fn test_no_spacing_by_default() {
+    std::env::remove_var("MIN_SUBMIT_SPACING_MS");
+    let queue = PendingQueue::for_interval("BTCUSD", Duration::from_millis(10));
+    assert_eq!(queue.to_json()["min_spacing_ms"], 0);
+    assert!(queue.admit(request()).is_some());
+    assert!(queue.admit(request()).is_some());
+}
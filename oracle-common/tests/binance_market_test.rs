@@ -0,0 +1,50 @@
+//! Binance spot vs futures market selection per feed and blend leg
+
+use oracle_common::{BinanceMarket, FeedsFile};
+
+const FEEDS: &str = r#"
+[[feeds]]
+id = "BTCUSD-SPOT"
+source = "binance"
+market = "spot"
+symbol = "BTCUSDT"
+contract = "0x1111111111111111111111111111111111111111"
+
+[[feeds.blend]]
+source = "binance"
+symbol = "BTCUSDC"
+
+[[feeds.blend]]
+source = "binance"
+market = "futures"
+symbol = "BTCUSDT"
+
+[[feeds.blend]]
+source = "uniswap-v3"
+symbol = "ethereum:0x99ac8cA7087fA4A2A1FB6357269965A2014ABc35"
+"#;
+
+#[test]
+fn test_futures_is_the_default() {
+    let file = FeedsFile::parse(&FEEDS.replace("market = \"spot\"\n", "")).unwrap();
+    assert_eq!(file.feeds[0].market, BinanceMarket::Futures);
+}
+
+#[test]
+fn test_binance_legs_inherit_the_feed_market() {
+    let file = FeedsFile::parse(FEEDS).unwrap();
+    let feed = &file.feeds[0];
+    assert_eq!(feed.market, BinanceMarket::Spot);
+    let markets: Vec<_> = feed.blend_legs().iter().map(|leg| leg.market).collect();
+    assert_eq!(markets, [BinanceMarket::Spot, BinanceMarket::Futures, BinanceMarket::Futures]);
+}
+
+#[test]
+fn test_spot_is_rejected_off_binance() {
+    let feeds = FEEDS.replace(
+        "source = \"uniswap-v3\"\n",
+        "source = \"uniswap-v3\"\nmarket = \"spot\"\n",
+    );
+    let err = FeedsFile::parse(&feeds).unwrap_err();
+    assert!(err.to_string().contains("market = \"spot\" is for binance sources only"));
+}
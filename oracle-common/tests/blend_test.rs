@@ -0,0 +1,133 @@
+//! Blending several sources into one feed value
+
+use oracle_common::{median, BlendedSource, FeedQuorum, InvertedSource, PricePoint, PriceSource};
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+/// Source returning whatever the test last set
+#[derive(Default)]
+struct FixedSource(RwLock<Option<PricePoint>>);
+
+impl FixedSource {
+    fn set(&self, price: f64, age_ms: u64) {
+        *self.0.write() = Some(PricePoint { price, num_trades: 3, timestamp_ms: now_ms() - age_ms, confidence: None });
+    }
+}
+
+impl PriceSource for FixedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        self.0.read().clone()
+    }
+}
+
+#[test]
+fn test_weighted_mean_of_available_legs() {
+    let cex = Arc::new(FixedSource::default());
+    let dex = Arc::new(FixedSource::default());
+    let blended = BlendedSource::new("ETHUSD", vec![(cex.clone() as Arc<dyn PriceSource>, 1.0), (dex.clone(), 0.5)]);
+    assert!(blended.latest().is_none());
+
+    // Only one leg has data: it is used alone
+    cex.set(3000.0, 0);
+    assert_eq!(blended.latest().unwrap().price, 3000.0);
+
+    dex.set(3030.0, 0);
+    let point = blended.latest().unwrap();
+    assert!((point.price - 3010.0).abs() < 1e-9);
+    assert_eq!(point.num_trades, 6);
+}
+
+#[test]
+fn test_stale_legs_drop_out() {
+    let cex = Arc::new(FixedSource::default());
+    let dex = Arc::new(FixedSource::default());
+    let blended = BlendedSource::new("ETHUSD", vec![(cex.clone() as Arc<dyn PriceSource>, 1.0), (dex.clone(), 1.0)])
+        .with_max_age(Some(Duration::from_secs(10)));
+
+    cex.set(3000.0, 0);
+    dex.set(2000.0, 60_000);
+    assert_eq!(blended.latest().unwrap().price, 3000.0);
+}
+
+#[test]
+fn test_divergence_holds_updates() {
+    let cex = Arc::new(FixedSource::default());
+    let dex = Arc::new(FixedSource::default());
+    let blended = BlendedSource::new("ETHUSD", vec![(cex.clone() as Arc<dyn PriceSource>, 1.0), (dex.clone(), 1.0)])
+        .with_max_deviation_bps(Some(100));
+
+    cex.set(3000.0, 0);
+    dex.set(3020.0, 0);
+    assert!(blended.latest().is_some());
+
+    // A CEX-only spike is held back rather than averaged in
+    cex.set(3300.0, 0);
+    assert!(blended.latest().is_none());
+
+    cex.set(3010.0, 0);
+    assert!(blended.latest().is_some());
+}
+
+fn three_venues(quorum: FeedQuorum) -> (Vec<Arc<FixedSource>>, BlendedSource) {
+    let venues: Vec<Arc<FixedSource>> = (0..3).map(|_| Arc::new(FixedSource::default())).collect();
+    let legs = venues.iter().map(|v| (v.clone() as Arc<dyn PriceSource>, 1.0)).collect();
+    (venues, BlendedSource::new("BTCUSD", legs).with_quorum(Some(quorum)))
+}
+
+#[test]
+fn test_median() {
+    assert_eq!(median(&[]), None);
+    assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+    assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), Some(2.5));
+}
+
+#[test]
+fn test_quorum_outvotes_an_outlier() {
+    let (venues, blended) = three_venues(FeedQuorum { min_sources: 2, tolerance_bps: 50, max_age_ms: 5000 });
+    venues[0].set(97_000.0, 0);
+    venues[1].set(97_100.0, 0);
+    // A flash crash on one venue is left out of the mean
+    venues[2].set(80_000.0, 0);
+
+    let point = blended.latest().unwrap();
+    assert!((point.price - 97_050.0).abs() < 1e-9);
+    assert_eq!(point.num_trades, 6);
+}
+
+#[test]
+fn test_quorum_lost_holds_updates_until_restored() {
+    let (venues, blended) = three_venues(FeedQuorum { min_sources: 2, tolerance_bps: 50, max_age_ms: 5000 });
+    venues[0].set(97_000.0, 0);
+    venues[1].set(97_100.0, 0);
+    assert!(blended.latest().is_some());
+
+    // One venue goes stale: only one fresh source left
+    venues[1].set(97_100.0, 10_000);
+    assert!(blended.latest().is_none());
+
+    // Fresh again but disagreeing with everyone else
+    venues[1].set(99_000.0, 0);
+    venues[2].set(95_000.0, 0);
+    assert!(blended.latest().is_none());
+
+    venues[2].set(97_050.0, 0);
+    assert!((blended.latest().unwrap().price - 97_025.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_inverted_source() {
+    let pool = Arc::new(FixedSource::default());
+    let inverted = InvertedSource::new(pool.clone());
+    assert!(inverted.latest().is_none());
+
+    pool.set(0.0004, 0);
+    assert!((inverted.latest().unwrap().price - 2500.0).abs() < 1e-9);
+
+    pool.set(0.0, 0);
+    assert!(inverted.latest().is_none());
+}
@@ -0,0 +1,156 @@
+//! OHLCV candles: bar building, late trades, bar TWAP / volatility and the
+//! `bar-twap` feed options
+
+use oracle_common::{
+    bar_twap, candle_interval_label, candle_volatility, parse_candle_interval, Aggregation, CandleSeries, CandleStore,
+    FeedsFile,
+};
+
+#[test]
+fn test_trades_fold_into_bars() {
+    let mut series = CandleSeries::new(1_000, 10);
+    series.add(1_100, 100.0, 1.0);
+    series.add(1_500, 103.0, 2.0);
+    series.add(1_900, 99.0, 0.5);
+    series.add(2_000, 101.0, 1.0);
+
+    let bars = series.since(0);
+    assert_eq!(bars.len(), 2);
+    let first = bars[0];
+    assert_eq!((first.open_ms, first.open, first.high, first.low, first.close), (1_000, 100.0, 103.0, 99.0, 99.0));
+    assert_eq!((first.volume, first.trades), (3.5, 3));
+    assert_eq!((bars[1].open_ms, bars[1].close), (2_000, 101.0));
+}
+
+#[test]
+fn test_late_trades_land_in_their_bar() {
+    let mut series = CandleSeries::new(1_000, 10);
+    series.add(1_500, 100.0, 1.0);
+    series.add(3_200, 105.0, 1.0);
+    // Backfilled: the end of bar 1, an earlier open than it had, and bar 2
+    series.add(1_900, 102.0, 1.0);
+    series.add(1_050, 98.0, 1.0);
+    series.add(2_400, 104.0, 1.0);
+
+    let bars = series.since(0);
+    assert_eq!(bars.iter().map(|bar| bar.open_ms).collect::<Vec<_>>(), [1_000, 2_000, 3_000]);
+    assert_eq!((bars[0].open, bars[0].close, bars[0].low, bars[0].trades), (98.0, 102.0, 98.0, 3));
+    assert_eq!(bars[1].close, 104.0);
+}
+
+#[test]
+fn test_only_the_newest_bars_are_kept() {
+    let mut series = CandleSeries::new(1_000, 3);
+    for second in 0..5 {
+        series.add(second * 1_000, 100.0 + second as f64, 1.0);
+    }
+    assert_eq!(series.since(0).iter().map(|bar| bar.open_ms).collect::<Vec<_>>(), [2_000, 3_000, 4_000]);
+    // Too old to keep
+    series.add(500, 90.0, 1.0);
+    assert_eq!(series.len(), 3);
+    assert_eq!(series.since(3_000).len(), 2);
+}
+
+#[test]
+fn test_bar_twap_weighs_bars_equally() {
+    let mut series = CandleSeries::new(1_000, 10);
+    series.add(0, 100.0, 1.0);
+    // A burst of volume at one price counts as one bar
+    for _ in 0..50 {
+        series.add(1_500, 102.0, 10.0);
+    }
+    series.add(3_100, 104.0, 1.0);
+
+    // Closes 100, 102, 102 (carried), 104, 104 (carried to now)
+    let twap = bar_twap(&series.since(0), 1_000, 4_500).unwrap();
+    assert!((twap - 102.4).abs() < 1e-9, "{}", twap);
+    assert_eq!(bar_twap(&[], 1_000, 4_500), None);
+}
+
+#[test]
+fn test_candle_volatility_needs_enough_bars() {
+    let mut series = CandleSeries::new(1_000, 100);
+    for second in 0..5 {
+        series.add(second * 1_000, if second % 2 == 0 { 100.0 } else { 101.0 }, 1.0);
+    }
+    assert_eq!(candle_volatility(&series.since(0), 1_000), None);
+
+    for second in 5..30 {
+        series.add(second * 1_000, if second % 2 == 0 { 100.0 } else { 101.0 }, 1.0);
+    }
+    let vol = candle_volatility(&series.since(0), 1_000).unwrap();
+    assert!(vol > 0.0);
+}
+
+#[test]
+fn test_store_builds_every_interval() {
+    let store = CandleStore::new(vec![5_000, 1_000, 1_000], 100);
+    assert_eq!(store.intervals_ms(), [1_000, 5_000]);
+    for ms in [0, 1_200, 2_400, 6_000] {
+        store.add_trade("BTCUSDT", ms, 60_000.0, 0.1);
+    }
+    store.add_trade("spot:BTCUSDT", 0, 59_990.0, 1.0);
+
+    assert_eq!(store.candles("BTCUSDT", 1_000, 0).len(), 4);
+    assert_eq!(store.candles("BTCUSDT", 5_000, 0).len(), 2);
+    assert!(store.candles("BTCUSDT", 60_000, 0).is_empty());
+
+    let json = store.to_json();
+    assert_eq!(json["intervals"], serde_json::json!(["1s", "5s"]));
+    assert_eq!(json["symbols"]["BTCUSDT"]["5s"]["bars"], 2);
+    assert_eq!(json["symbols"]["spot:BTCUSDT"]["1s"]["latest"]["close"], 59_990.0);
+
+    let recent = store.candles_json("BTCUSDT", 1_000, 2);
+    assert_eq!(recent["interval"], "1s");
+    assert_eq!(recent["candles"].as_array().unwrap().len(), 2);
+    assert_eq!(recent["candles"][1]["open_ms"], 6_000);
+}
+
+#[test]
+fn test_intervals_parse() {
+    assert_eq!(parse_candle_interval("1s").unwrap(), 1_000);
+    assert_eq!(parse_candle_interval(" 5s").unwrap(), 5_000);
+    assert_eq!(parse_candle_interval("1m").unwrap(), 60_000);
+    assert_eq!(parse_candle_interval("250ms").unwrap(), 250);
+    assert!(parse_candle_interval("5").is_err());
+    assert!(parse_candle_interval("0s").is_err());
+    assert_eq!(candle_interval_label(60_000), "1m");
+    assert_eq!(candle_interval_label(5_000), "5s");
+}
+
+const FEED: &str = r#"
+[[feeds]]
+id = "BTCUSD-15M"
+source = "binance"
+symbol = "BTCUSDT"
+aggregation = "bar-twap"
+window_secs = 900
+contract = "0x1111111111111111111111111111111111111111"
+"#;
+
+#[test]
+fn test_bar_twap_feeds_default_to_one_second_candles() {
+    let file = FeedsFile::parse(FEED).unwrap();
+    assert_eq!(file.feeds[0].aggregation, Aggregation::BarTwap);
+    assert_eq!(file.feeds[0].candle_ms(), Some(1_000));
+
+    let file = FeedsFile::parse(&FEED.replace("window_secs = 900", "window_secs = 900\ncandle_secs = 5")).unwrap();
+    assert_eq!(file.feeds[0].candle_ms(), Some(5_000));
+}
+
+#[test]
+fn test_candle_options_are_validated() {
+    let err = FeedsFile::parse(&FEED.replace("source = \"binance\"", "source = \"pyth\"")).unwrap_err();
+    assert!(format!("{:#}", err).contains("need a binance source"), "{:#}", err);
+
+    let err = FeedsFile::parse(&FEED.replace("window_secs = 900", "window_secs = 30\ncandle_secs = 60")).unwrap_err();
+    assert!(format!("{:#}", err).contains("candle_secs must be between 1 and window_secs"), "{:#}", err);
+
+    // Candles are built from every trade, dust included
+    let err = FeedsFile::parse(&FEED.replace("window_secs = 900", "window_secs = 900\nmin_trade_notional = 100")).unwrap_err();
+    assert!(format!("{:#}", err).contains("min_trade_notional doesn't apply to bar-twap"), "{:#}", err);
+
+    // Trade-sampled realized vol doesn't use candles
+    let file = FeedsFile::parse(&FEED.replace("bar-twap", "realized-vol")).unwrap();
+    assert_eq!(file.feeds[0].candle_ms(), None);
+}
@@ -0,0 +1,106 @@
+//! Rate-of-change limit: clamping, catching up after gaps, the override
+//! after a sustained move, and `[feeds.change_limit]` validation.
+
+use oracle_common::{ChangeLimiter, FeedChangeLimit, FeedsFile};
+use std::time::{Duration, Instant};
+
+fn limiter(config: &str) -> ChangeLimiter {
+    let config: FeedChangeLimit = toml::from_str(config).unwrap();
+    ChangeLimiter::new("BTCUSD", &config, 200)
+}
+
+fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+#[test]
+fn test_clamps_moves_beyond_the_limit() {
+    let limiter = limiter("max_change_bps = 100\nper_ms = 1000");
+    let t0 = Instant::now();
+    // Nothing to measure from yet
+    assert_eq!(limiter.limit(100.0, t0), 100.0);
+    limiter.record_sent(100.0, t0);
+
+    assert_eq!(limiter.limit(100.5, t0 + secs(1)), 100.5);
+    assert!((limiter.limit(110.0, t0 + secs(1)) - 101.0).abs() < 1e-9);
+    assert!((limiter.limit(80.0, t0 + secs(1)) - 99.0).abs() < 1e-9);
+    limiter.record_sent(101.0, t0 + secs(1));
+
+    // Two periods since the last update: twice the allowance
+    assert!((limiter.limit(110.0, t0 + secs(3)) - 103.02).abs() < 1e-9);
+    assert_eq!(limiter.to_json()["clamped"], 3);
+}
+
+#[test]
+fn test_period_defaults_to_the_feed_interval() {
+    let limiter = limiter("max_change_bps = 50");
+    let t0 = Instant::now();
+    limiter.record_sent(1000.0, t0);
+    assert_eq!(limiter.to_json()["per_ms"], 200);
+    assert!((limiter.limit(1100.0, t0 + Duration::from_millis(100)) - 1005.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_sustained_moves_override_the_limit() {
+    let limiter = limiter("max_change_bps = 10\nper_ms = 1000\noverride_after_secs = 30");
+    let t0 = Instant::now();
+    limiter.record_sent(100.0, t0);
+
+    // Publishing every second, the clamped value creeps towards 120
+    for i in 1..=30 {
+        let now = t0 + secs(i);
+        let value = limiter.limit(120.0, now);
+        assert!(value < 104.0, "step {}: {}", i, value);
+        limiter.record_sent(value, now);
+    }
+    assert_eq!(limiter.limit(120.0, t0 + secs(31)), 120.0);
+    limiter.record_sent(120.0, t0 + secs(31));
+
+    let status = limiter.to_json();
+    assert_eq!((status["clamped"].as_u64(), status["overrides"].as_u64()), (Some(30), Some(1)));
+    assert!(status["clamping_ms"].is_null());
+    assert_eq!(limiter.limit(120.1, t0 + secs(32)), 120.1);
+}
+
+#[test]
+fn test_a_zero_last_value_does_not_pin_the_feed() {
+    let limiter = limiter("max_change_bps = 100\nper_ms = 1000");
+    let t0 = Instant::now();
+    limiter.record_sent(0.0, t0);
+
+    // No scale to measure a move from: published as computed
+    assert_eq!(limiter.limit(5.0, t0 + secs(1)), 5.0);
+    limiter.record_sent(5.0, t0 + secs(1));
+    assert!((limiter.limit(10.0, t0 + secs(2)) - 5.05).abs() < 1e-9);
+    assert_eq!(limiter.to_json()["clamped"], 1);
+}
+
+#[test]
+fn test_an_older_update_landing_late_keeps_the_newer_baseline() {
+    let limiter = limiter("max_change_bps = 100\nper_ms = 1000");
+    let t0 = Instant::now();
+    limiter.record_sent(100.0, t0 + secs(1));
+    // Sent earlier, confirmed after the one above
+    limiter.record_sent(90.0, t0);
+    assert!((limiter.limit(110.0, t0 + secs(2)) - 101.0).abs() < 1e-9);
+}
+
+const FEED: &str = r#"
+[[feeds]]
+id = "BTCUSD"
+source = "binance"
+symbol = "BTCUSDT"
+contract = "0x1111111111111111111111111111111111111111"
+
+[feeds.change_limit]
+max_change_bps = 200
+"#;
+
+#[test]
+fn test_change_limit_needs_an_unsigned_feed() {
+    assert!(FeedsFile::parse(FEED).unwrap().feeds[0].change_limit.is_some());
+
+    let signed = FEED.replace("contract =", "function = \"updatePrice(string,int256)\"\ncontract =");
+    let err = FeedsFile::parse(&signed).unwrap_err();
+    assert!(format!("{:#}", err).contains("change_limit needs an unsigned"), "{:#}", err);
+}
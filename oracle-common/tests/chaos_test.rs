@@ -0,0 +1,110 @@
+//! Chaos mode spec parsing, dice and the key-freezing submitter
+
+use alloy::consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::TxSigner;
+use alloy::primitives::{Address, Bytes, TxKind, U256};
+use alloy::signers::local::PrivateKeySigner;
+use async_trait::async_trait;
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::submission::TxSubmitter;
+use nonzu_sdk::types::SyncTransactionReceipt;
+use oracle_common::{encode_update_timestamp, Chaos, ChaosConfig, ChaosFault, ChaosSubmitter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_parse_spec() {
+    assert_eq!(ChaosConfig::parse("true").unwrap(), ChaosConfig::default());
+
+    let config = ChaosConfig::parse("ws_drop=0.5, delay=0.1:250ms, freeze_key=1:2s").unwrap();
+    assert_eq!(config.ws_drop, 0.5);
+    assert_eq!(config.delay, 0.1);
+    assert_eq!(config.delay_for, Duration::from_millis(250));
+    assert_eq!(config.freeze_key, 1.0);
+    assert_eq!(config.freeze_for, Duration::from_secs(2));
+    assert_eq!(config.corrupt_receipt, 0.0, "faults left out are off");
+
+    assert!(ChaosConfig::parse("explode=0.1").is_err());
+    assert!(ChaosConfig::parse("delay=1.5").is_err());
+    assert!(ChaosConfig::parse("delay").is_err());
+}
+
+#[test]
+fn test_rolls_follow_rates() {
+    let config = ChaosConfig { ws_drop: 1.0, delay: 0.25, ..ChaosConfig::none() };
+    let chaos = Chaos::new(config).with_seed(7);
+
+    for _ in 0..1000 {
+        assert!(chaos.roll(ChaosFault::WsDrop, "test"));
+        assert!(!chaos.roll(ChaosFault::CorruptReceipt, "test"));
+        chaos.roll(ChaosFault::Delay, "test");
+    }
+    assert_eq!(chaos.injected(ChaosFault::WsDrop), 1000);
+    assert_eq!(chaos.injected(ChaosFault::CorruptReceipt), 0);
+    let delays = chaos.injected(ChaosFault::Delay);
+    assert!((200..300).contains(&delays), "{} delays at 25%", delays);
+}
+
+#[test]
+fn test_seed_makes_rolls_repeatable() {
+    let config = ChaosConfig { delay: 0.5, ..ChaosConfig::none() };
+    let rolls = |chaos: Chaos| (0..64).map(|_| chaos.roll(ChaosFault::Delay, "test")).collect::<Vec<_>>();
+    assert_eq!(rolls(Chaos::new(config.clone()).with_seed(1)), rolls(Chaos::new(config).with_seed(1)));
+}
+
+/// Fails every submission, counting them
+#[derive(Default)]
+struct Unreachable(AtomicU64);
+
+#[async_trait]
+impl TxSubmitter for Unreachable {
+    async fn submit(&self, _raw_tx: Bytes) -> Result<SyncTransactionReceipt, RiseError> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Err(RiseError::Rpc("inner submitter".to_string()))
+    }
+}
+
+async fn signed_update(signer: &PrivateKeySigner) -> Bytes {
+    let mut tx = TxEip1559 {
+        chain_id: 11155931,
+        nonce: 0,
+        gas_limit: 60_000,
+        max_fee_per_gas: 300_000,
+        max_priority_fee_per_gas: 300_000,
+        to: TxKind::Call(Address::repeat_byte(0x42)),
+        value: U256::ZERO,
+        input: encode_update_timestamp(1_700_000_000_000),
+        access_list: Default::default(),
+    };
+    let signature = signer.sign_transaction(&mut tx).await.unwrap();
+    TxEnvelope::Eip1559(tx.into_signed(signature)).encoded_2718().into()
+}
+
+#[tokio::test]
+async fn test_frozen_key_stalls_then_times_out() {
+    let config = ChaosConfig { freeze_key: 1.0, freeze_for: Duration::from_millis(100), ..ChaosConfig::none() };
+    let chaos: &'static Chaos = Box::leak(Box::new(Chaos::new(config)));
+    let inner = Arc::new(Unreachable::default());
+    let submitter = ChaosSubmitter::new(inner.clone(), chaos);
+    let signer = PrivateKeySigner::random();
+
+    let started = std::time::Instant::now();
+    let error = submitter.submit(signed_update(&signer).await).await.unwrap_err();
+    assert!(error.to_string().contains("frozen"), "{}", error);
+    assert!(started.elapsed() >= Duration::from_millis(90), "held until the key thaws");
+    assert_eq!(inner.0.load(Ordering::Relaxed), 0, "nothing reached the node");
+    assert_eq!(chaos.frozen_for(signer.address()), None, "thawed afterwards");
+}
+
+#[tokio::test]
+async fn test_passes_through_when_nothing_rolls() {
+    let chaos: &'static Chaos = Box::leak(Box::new(Chaos::new(ChaosConfig::none())));
+    let inner = Arc::new(Unreachable::default());
+    let submitter = ChaosSubmitter::new(inner.clone(), chaos);
+
+    let error = submitter.submit(signed_update(&PrivateKeySigner::random()).await).await.unwrap_err();
+    assert!(error.to_string().contains("inner submitter"));
+    assert_eq!(inner.0.load(Ordering::Relaxed), 1);
+}
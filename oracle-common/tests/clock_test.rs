@@ -0,0 +1,53 @@
+//! SNTP exchanges against a local fake server, and NTP config checks
+
+use oracle_common::{query_sntp, time_source_from_env};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Answer one request with the local clock, echoing its transmit timestamp
+/// as the originate timestamp when `echo` is set
+async fn fake_server(echo: bool) -> String {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let mut request = [0u8; 48];
+        let (_, client) = socket.recv_from(&mut request).await.unwrap();
+        let mut response = [0u8; 48];
+        // LI = 0, VN = 4, Mode = 4 (server), stratum 1
+        response[0] = 0x24;
+        response[1] = 1;
+        if echo {
+            response[24..32].copy_from_slice(&request[40..48]);
+        }
+        // Receive and transmit at the client's own send time: no offset
+        response[32..40].copy_from_slice(&request[40..48]);
+        response[40..48].copy_from_slice(&request[40..48]);
+        socket.send_to(&response, client).await.unwrap();
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_reply_to_our_request_is_measured() {
+    let server = fake_server(true).await;
+    let sample = query_sntp(&server, Duration::from_secs(1)).await.unwrap();
+    assert!(sample.delay_ms >= 0.0 && sample.delay_ms < 1_000.0);
+    assert!(sample.offset_ms.abs() < 1_000.0, "offset {}ms", sample.offset_ms);
+}
+
+#[tokio::test]
+async fn test_reply_with_another_originate_is_rejected() {
+    let server = fake_server(false).await;
+    let err = query_sntp(&server, Duration::from_secs(1)).await.unwrap_err();
+    assert!(err.to_string().contains("originate"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_zero_poll_interval_is_rejected() {
+    std::env::set_var("TIME_SOURCE", "ntp");
+    std::env::set_var("NTP_POLL_SECS", "0");
+    let err = time_source_from_env().await.err().unwrap();
+    assert!(err.to_string().contains("NTP_POLL_SECS"), "{}", err);
+    std::env::remove_var("TIME_SOURCE");
+    std::env::remove_var("NTP_POLL_SECS");
+}
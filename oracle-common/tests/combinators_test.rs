@@ -0,0 +1,180 @@
+//! Trigger policies built from condition combinators
+
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    AllOf, AnyOf, Debounce, Deviation, Elapsed, Gated, MockClock, Not, Paused, Predicate, PricePoint, PriceSource,
+    Throttle, TriggerCondition,
+};
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fires on every poll, counting polls and completions
+#[derive(Default)]
+struct CountingTrigger {
+    polls: AtomicU64,
+    completions: AtomicU64,
+}
+
+#[async_trait]
+impl TxTrigger for CountingTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(TxRequest::new(Address::ZERO, vec![0x01].into())))
+    }
+
+    async fn on_complete(&self, _success: bool, _receipt: Option<&SyncTransactionReceipt>, _latency: Option<Duration>) {
+        self.completions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: "CountingTrigger".to_string(),
+            description: "Test trigger".to_string(),
+            trigger_type: "test".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+struct FixedPrice(RwLock<f64>);
+
+impl PriceSource for FixedPrice {
+    fn latest(&self) -> Option<PricePoint> {
+        Some(PricePoint { price: *self.0.read(), num_trades: 1, timestamp_ms: 0, confidence: None })
+    }
+}
+
+fn flag(name: &str, value: bool) -> (Arc<AtomicBool>, impl TriggerCondition) {
+    let flag = Arc::new(AtomicBool::new(value));
+    let read = flag.clone();
+    (flag, Predicate::new(name, move || read.load(Ordering::Relaxed)))
+}
+
+#[tokio::test]
+async fn test_all_any_not() {
+    let (_, yes) = flag("yes", true);
+    let (_, no) = flag("no", false);
+    assert!(!AllOf::new().with(yes).with(no).check().await);
+
+    let (_, yes) = flag("yes", true);
+    let (_, no) = flag("no", false);
+    assert!(AnyOf::new().with(yes).with(no).check().await);
+
+    let (_, no) = flag("no", false);
+    assert!(Not::new(no).check().await);
+
+    assert!(AllOf::new().check().await);
+    assert!(!AnyOf::new().check().await);
+}
+
+#[tokio::test]
+async fn test_describe() {
+    let (_, a) = flag("a", true);
+    let (_, b) = flag("b", true);
+    let (_, c) = flag("c", true);
+    let policy = AllOf::new().with(AnyOf::new().with(a).with(b)).with(Not::new(c));
+    assert_eq!(policy.describe(), "((a OR b) AND NOT c)");
+}
+
+#[tokio::test]
+async fn test_gate_skips_polling_the_trigger() {
+    let inner = Arc::new(CountingTrigger::default());
+    let (open, condition) = flag("open", false);
+    let gated = Gated::new(inner.clone(), condition);
+
+    assert!(gated.should_trigger().await.unwrap().is_none());
+    assert_eq!(inner.polls.load(Ordering::Relaxed), 0);
+
+    open.store(true, Ordering::Relaxed);
+    assert!(gated.should_trigger().await.unwrap().is_some());
+    gated.on_complete(true, None, None).await;
+    assert_eq!(inner.polls.load(Ordering::Relaxed), 1);
+    assert_eq!(inner.completions.load(Ordering::Relaxed), 1);
+    assert!(gated.metadata().description.ends_with("when open"));
+}
+
+#[tokio::test]
+async fn test_heartbeat_resets_on_success() {
+    let clock = MockClock::new(0);
+    let heartbeat = Elapsed::new(Duration::from_secs(30)).with_clock(clock.clone());
+    assert!(heartbeat.check().await, "due before the first update");
+
+    heartbeat.on_complete(true);
+    assert!(!heartbeat.check().await);
+    clock.advance(Duration::from_secs(29));
+    assert!(!heartbeat.check().await);
+
+    // Failures don't count as a heartbeat
+    heartbeat.on_complete(false);
+    clock.advance(Duration::from_secs(1));
+    assert!(heartbeat.check().await);
+}
+
+#[tokio::test]
+async fn test_deviation_tracks_published_price() {
+    let source = Arc::new(FixedPrice(RwLock::new(100.0)));
+    let deviation = Deviation::new(source.clone(), 10);
+    assert!(deviation.check().await, "publishes the first price");
+    deviation.on_complete(true);
+
+    *source.0.write() = 100.05;
+    assert!(!deviation.check().await);
+    *source.0.write() = 100.1;
+    assert!(deviation.check().await);
+
+    // A failed update keeps the old baseline
+    deviation.on_complete(false);
+    *source.0.write() = 100.05;
+    assert!(!deviation.check().await);
+}
+
+#[tokio::test]
+async fn test_debounce_needs_a_steady_signal() {
+    let clock = MockClock::new(0);
+    let (signal, condition) = flag("signal", true);
+    let debounce = Debounce::new(condition, Duration::from_secs(5)).with_clock(clock.clone());
+
+    assert!(!debounce.check().await);
+    clock.advance(Duration::from_secs(4));
+    assert!(!debounce.check().await);
+
+    // A flap restarts the hold period
+    signal.store(false, Ordering::Relaxed);
+    assert!(!debounce.check().await);
+    signal.store(true, Ordering::Relaxed);
+    clock.advance(Duration::from_secs(1));
+    assert!(!debounce.check().await);
+    clock.advance(Duration::from_secs(5));
+    assert!(debounce.check().await);
+}
+
+#[tokio::test]
+async fn test_throttle() {
+    let clock = MockClock::new(0);
+    let inner = Arc::new(CountingTrigger::default());
+    let throttle = Throttle::new(inner.clone(), Duration::from_secs(1)).with_clock(clock.clone());
+
+    assert!(throttle.should_trigger().await.unwrap().is_some());
+    assert!(throttle.should_trigger().await.unwrap().is_none());
+    clock.advance(Duration::from_millis(999));
+    assert!(throttle.should_trigger().await.unwrap().is_none());
+    clock.advance(Duration::from_millis(1));
+    assert!(throttle.should_trigger().await.unwrap().is_some());
+    assert_eq!(inner.polls.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn test_paused() {
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+    let running = Not::new(Paused::new(error_control.clone()));
+    assert!(running.check().await);
+
+    error_control.pause().await;
+    assert!(!running.check().await);
+    error_control.resume().await;
+    assert!(running.check().await);
+}
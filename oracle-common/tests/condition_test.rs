@@ -0,0 +1,119 @@
+//! On-chain conditions gating feed updates
+
+use alloy::primitives::U256;
+use oracle_common::{deviation_bps, FeedConfig, OnChainCondition};
+use std::time::Duration;
+
+fn feed(condition: &str) -> FeedConfig {
+    toml::from_str(&format!(
+        r#"
+        id = "BTCUSD"
+        source = "binance"
+        symbol = "BTCUSDT"
+        contract = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175"
+
+        [condition]
+        {}
+        "#,
+        condition
+    ))
+    .unwrap()
+}
+
+fn condition(config: &str) -> OnChainCondition {
+    let config = feed(config);
+    OnChainCondition::new(&config, config.condition.as_ref().unwrap()).unwrap()
+}
+
+fn word(value: u64) -> [u8; 32] {
+    U256::from(value).to_be_bytes()
+}
+
+#[test]
+fn test_deviation_bps() {
+    assert_eq!(deviation_bps(U256::from(10_000), U256::from(10_010)), Some(10));
+    assert_eq!(deviation_bps(U256::from(10_000), U256::from(9_990)), Some(10));
+    assert_eq!(deviation_bps(U256::from(10_000), U256::from(10_000)), Some(0));
+    assert_eq!(deviation_bps(U256::ZERO, U256::from(1)), None);
+    assert_eq!(deviation_bps(U256::from(1), U256::MAX), Some(u64::MAX));
+}
+
+#[test]
+fn test_fails_open_without_a_read() {
+    let condition = condition(r#"function = "needsUpdate(string)""#);
+    assert!(condition.allows(U256::from(1), None));
+
+    condition.record_read(None);
+    assert!(condition.allows(U256::from(1), None));
+}
+
+#[test]
+fn test_needs_update() {
+    let condition = condition(r#"function = "needsUpdate(string)""#);
+    condition.record_read(Some(&word(0)));
+    assert!(!condition.allows(U256::from(1), Some(Duration::from_secs(1))));
+
+    condition.record_read(Some(&word(1)));
+    assert!(condition.allows(U256::from(1), Some(Duration::from_secs(1))));
+
+    // The contract was just updated; the old read no longer asks for one
+    condition.record_published(U256::from(1));
+    assert!(!condition.allows(U256::from(1), Some(Duration::from_secs(1))));
+}
+
+#[test]
+fn test_deviation_threshold() {
+    let condition = condition(
+        r#"
+        kind = "deviation"
+        function = "getLatestPrice(string)"
+        deviation_bps = 10
+        "#,
+    );
+    condition.record_read(Some(&word(10_000)));
+    assert!(!condition.allows(U256::from(10_009), Some(Duration::from_secs(1))));
+    assert!(condition.allows(U256::from(10_010), Some(Duration::from_secs(1))));
+    assert!(condition.allows(U256::from(9_990), Some(Duration::from_secs(1))));
+
+    condition.record_published(U256::from(10_010));
+    assert!(!condition.allows(U256::from(10_015), Some(Duration::from_secs(1))));
+}
+
+#[test]
+fn test_heartbeat() {
+    let condition = condition(
+        r#"
+        kind = "deviation"
+        function = "getLatestPrice(string)"
+        deviation_bps = 50
+        heartbeat_secs = 60
+        "#,
+    );
+    condition.record_read(Some(&word(10_000)));
+    assert!(!condition.allows(U256::from(10_000), Some(Duration::from_secs(59))));
+    assert!(condition.allows(U256::from(10_000), Some(Duration::from_secs(60))));
+}
+
+#[test]
+fn test_heartbeat_only() {
+    let condition = condition(
+        r#"
+        kind = "deviation"
+        function = "getLatestPrice(string)"
+        heartbeat_secs = 60
+        "#,
+    );
+    condition.record_read(Some(&word(10_000)));
+    // Never published: the first update goes out
+    assert!(condition.allows(U256::from(20_000), None));
+    assert!(!condition.allows(U256::from(20_000), Some(Duration::from_secs(30))));
+}
+
+#[test]
+fn test_call_data() {
+    let no_args = feed(r#"function = "needsUpdate()""#);
+    assert_eq!(no_args.condition.unwrap().call_data("BTCUSD").unwrap().len(), 4);
+
+    let uint_arg = feed(r#"function = "needsUpdate(uint256)""#);
+    assert!(uint_arg.condition.unwrap().call_data("BTCUSD").is_err());
+}
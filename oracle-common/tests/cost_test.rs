@@ -0,0 +1,96 @@
+//! Cost projections per feed, and actual spend from the audit log
+
+use alloy::primitives::Address;
+use oracle_common::{read_audit_log, ActualCosts, AuditEntry, FeedConfig, FeedCost, DAY_MS};
+use std::io::Write;
+
+const GWEI: u64 = 1_000_000_000;
+const NOW_MS: u64 = 1_735_689_600_000;
+
+fn feed(extra: &str) -> FeedConfig {
+    toml::from_str(&format!(
+        "id = \"BTCUSD\"\nsource = \"binance\"\nsymbol = \"BTCUSDT\"\ncontract = \"0x5a569ad19272afa97103fd4dbadf33b2fcbaa175\"\n{}",
+        extra
+    ))
+    .unwrap()
+}
+
+fn entry(feed: &str, key: u8, submitted_at_ms: u64, result: &str) -> AuditEntry {
+    AuditEntry {
+        submitted_at_ms,
+        feed: Some(feed.to_string()),
+        from: Some(Address::repeat_byte(key)),
+        gas_limit: Some(100_000),
+        max_fee_per_gas: Some(GWEI as u128),
+        result: result.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_projection_scales_with_interval_gas_and_price() {
+    // One update per second at the 300k default gas limit
+    let cost = FeedCost::project(&feed("interval_ms = 1000"), None, GWEI);
+    assert_eq!((cost.updates_per_day, cost.gas_per_update), (86_400.0, 300_000));
+    assert_eq!(cost.daily_wei(), 86_400.0 * 300_000.0 * GWEI as f64);
+    assert_eq!(cost.monthly_wei(), cost.daily_wei() * 30.0);
+
+    let average = FeedCost::project(&feed("interval_ms = 1000"), Some(45_000), GWEI);
+    assert_eq!(average.daily_wei(), 86_400.0 * 45_000.0 * GWEI as f64);
+}
+
+#[test]
+fn test_feed_max_fee_caps_the_gas_price() {
+    let capped = FeedCost::project(&feed("max_fee_per_gas_wei = 500000000"), None, GWEI);
+    assert_eq!(capped.gas_price_wei, GWEI / 2);
+    let below = FeedCost::project(&feed("max_fee_per_gas_wei = 500000000"), None, GWEI / 10);
+    assert_eq!(below.gas_price_wei, GWEI / 10);
+}
+
+#[test]
+fn test_actuals_count_landed_transactions_in_the_window() {
+    let entries = vec![
+        entry("BTCUSD", 1, NOW_MS - 3 * DAY_MS, "confirmed"), // before the window
+        entry("BTCUSD", 1, NOW_MS - 2 * DAY_MS, "confirmed"),
+        entry("BTCUSD", 2, NOW_MS - DAY_MS, "reverted"),
+        entry("ETHUSD", 2, NOW_MS - DAY_MS, "error"), // never reached the chain
+        entry("ETHUSD", 2, NOW_MS - 1, "confirmed"),
+    ];
+    let actual = ActualCosts::from_entries(&entries, NOW_MS - 2 * DAY_MS - 1, NOW_MS);
+
+    assert_eq!(actual.days, 2.0);
+    assert_eq!(actual.per_feed["BTCUSD"].transactions, 2);
+    assert_eq!(actual.per_feed["ETHUSD"].transactions, 1);
+    assert_eq!(actual.per_key[&Address::repeat_byte(2)].transactions, 2);
+
+    let per_tx = 100_000.0 * GWEI as f64;
+    assert_eq!(actual.total().max_wei, 3.0 * per_tx);
+    assert_eq!(actual.per_day(&actual.per_feed["BTCUSD"]), Some(per_tx));
+}
+
+#[test]
+fn test_no_entries_means_no_daily_rate() {
+    let actual = ActualCosts::from_entries(&[], 0, NOW_MS);
+    assert_eq!(actual.days, 0.0);
+    assert_eq!(actual.per_day(&actual.total()), None);
+}
+
+#[test]
+fn test_rotated_audit_files_are_read_oldest_first() {
+    let dir = std::env::temp_dir().join(format!("cost-audit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("audit.jsonl");
+    let write = |path: &std::path::Path, entry: AuditEntry| {
+        let mut file = std::fs::File::create(path).unwrap();
+        writeln!(file, "{}", serde_json::to_string(&entry).unwrap()).unwrap();
+    };
+    write(&dir.join("audit.jsonl.200"), entry("ETHUSD", 1, 2, "confirmed"));
+    write(&dir.join("audit.jsonl.100"), entry("BTCUSD", 1, 1, "confirmed"));
+    write(&path, entry("SOLUSD", 1, 3, "confirmed"));
+    write(&dir.join("audit.jsonl.tmp"), entry("XRPUSD", 1, 4, "confirmed"));
+
+    let feeds: Vec<_> = read_audit_log(&path).unwrap().into_iter().filter_map(|e| e.feed).collect();
+    assert_eq!(feeds, ["BTCUSD", "ETHUSD", "SOLUSD"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
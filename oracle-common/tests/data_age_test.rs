@@ -0,0 +1,47 @@
+//! Data age: per-update ages at trigger and inclusion, in-flight ordering
+//! and the distributions in `/status`
+
+use oracle_common::{DataAge, DataAgeSample};
+
+const DATA_MS: u64 = 1_735_689_600_000;
+
+#[test]
+fn test_ages_are_measured_from_the_data_timestamp() {
+    let sample = DataAgeSample { data_ms: DATA_MS, triggered_ms: DATA_MS + 400, included_ms: DATA_MS + 450 };
+    assert_eq!((sample.at_trigger_ms(), sample.at_inclusion_ms()), (400, 450));
+
+    // Data stamped slightly ahead of the local clock doesn't underflow
+    let ahead = DataAgeSample { data_ms: DATA_MS, triggered_ms: DATA_MS - 5, included_ms: DATA_MS + 20 };
+    assert_eq!((ahead.at_trigger_ms(), ahead.at_inclusion_ms()), (0, 20));
+}
+
+#[test]
+fn test_completions_match_updates_oldest_first() {
+    let age = DataAge::new("BTCUSD");
+    age.sent_at(DATA_MS, DATA_MS + 100);
+    age.sent_at(DATA_MS + 200, DATA_MS + 300);
+
+    let first = age.completed_at(true, DATA_MS + 150).unwrap();
+    assert_eq!((first.at_trigger_ms(), first.at_inclusion_ms()), (100, 150));
+    // A failed update is dropped without a sample
+    assert_eq!(age.completed_at(false, DATA_MS + 400), None);
+    assert_eq!(age.completed_at(true, DATA_MS + 500), None);
+
+    let status = age.to_json();
+    assert_eq!(status["at_inclusion_ms"]["count"], 1);
+    assert_eq!(status["last"]["at_inclusion_ms"], 150);
+}
+
+#[test]
+fn test_distributions_summarize_landed_updates() {
+    let age = DataAge::new("BTCUSD");
+    for i in 0..100u64 {
+        age.record(DataAgeSample { data_ms: DATA_MS, triggered_ms: DATA_MS + i, included_ms: DATA_MS + 10 * i });
+    }
+
+    let status = age.to_json();
+    assert_eq!(status["at_trigger_ms"]["count"], 100);
+    assert_eq!(status["at_trigger_ms"]["max"], 99.0);
+    assert_eq!(status["at_inclusion_ms"]["p50"], 500.0);
+    assert_eq!(status["at_inclusion_ms"]["p99"], 980.0);
+}
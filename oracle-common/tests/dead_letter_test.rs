@@ -0,0 +1,71 @@
+//! Dead-letter records and the JSON-lines store
+
+use alloy::consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::TxSigner;
+use alloy::primitives::{Address, Bytes, TxKind, U256};
+use alloy::signers::local::PrivateKeySigner;
+use oracle_common::{encode_update_timestamp, DeadLetter, DeadLetterStore};
+use std::time::Duration;
+
+async fn signed_update(signer: &PrivateKeySigner, nonce: u64) -> Bytes {
+    let mut tx = TxEip1559 {
+        chain_id: 11155931,
+        nonce,
+        gas_limit: 60_000,
+        max_fee_per_gas: 300_000,
+        max_priority_fee_per_gas: 300_000,
+        to: TxKind::Call(Address::repeat_byte(0x42)),
+        value: U256::ZERO,
+        input: encode_update_timestamp(1_700_000_000_000),
+        access_list: Default::default(),
+    };
+    let signature = signer.sign_transaction(&mut tx).await.unwrap();
+    TxEnvelope::Eip1559(tx.into_signed(signature)).encoded_2718().into()
+}
+
+#[tokio::test]
+async fn test_dead_letter_decodes_transaction() {
+    let signer = PrivateKeySigner::random();
+    let raw = signed_update(&signer, 7).await;
+    let letter = DeadLetter::new(&raw, "nonce_gap", "missing nonce".to_string(), 1);
+
+    assert_eq!(letter.from, Some(signer.address()));
+    assert_eq!(letter.to, Some(Address::repeat_byte(0x42)));
+    assert_eq!(letter.nonce, Some(7));
+    assert_eq!(letter.gas_limit, Some(60_000));
+    assert_eq!(letter.calldata, encode_update_timestamp(1_700_000_000_000));
+    assert_eq!(letter.raw_tx, raw);
+
+    // Undecodable bytes are still recorded
+    let junk = DeadLetter::new(&Bytes::from_static(b"junk"), "other", "bad".to_string(), 1);
+    assert_eq!(junk.nonce, None);
+}
+
+#[tokio::test]
+async fn test_store_keeps_newest_entries() {
+    let path = std::env::temp_dir().join(format!("dead-letters-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let signer = PrivateKeySigner::random();
+
+    let store = DeadLetterStore::open(&path, 8).unwrap();
+    for nonce in 0..20 {
+        let raw = signed_update(&signer, nonce).await;
+        store.record(DeadLetter::new(&raw, "rpc_timeout", format!("timeout {}", nonce), nonce));
+    }
+
+    // Writes happen on a background thread
+    let mut letters = Vec::new();
+    for _ in 0..50 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        letters = DeadLetterStore::read(&path).unwrap();
+        if letters.last().and_then(|l| l.nonce) == Some(19) {
+            break;
+        }
+    }
+    assert!(letters.len() <= 8, "kept {} entries", letters.len());
+    assert_eq!(letters.last().unwrap().nonce, Some(19));
+    assert!(letters.windows(2).all(|w| w[0].nonce < w[1].nonce));
+
+    std::fs::remove_file(&path).unwrap();
+}
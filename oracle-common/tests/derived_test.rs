@@ -0,0 +1,98 @@
+//! Derived feeds: expression parsing, evaluation and feeds file validation
+
+use oracle_common::{DerivedExpr, DerivedSource, FeedsFile, PricePoint, PriceSource};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Source returning whatever the test last set
+#[derive(Default)]
+struct FixedSource(RwLock<Option<PricePoint>>);
+
+impl FixedSource {
+    fn set(&self, price: f64, timestamp_ms: u64, confidence: Option<f64>) {
+        *self.0.write() = Some(PricePoint { price, num_trades: timestamp_ms % 10, timestamp_ms, confidence });
+    }
+}
+
+impl PriceSource for FixedSource {
+    fn latest(&self) -> Option<PricePoint> {
+        self.0.read().clone()
+    }
+}
+
+fn sources(ids: &[&str]) -> HashMap<String, Arc<FixedSource>> {
+    ids.iter().map(|id| (id.to_string(), Arc::new(FixedSource::default()))).collect()
+}
+
+fn derived(expr: &str, sources: &HashMap<String, Arc<FixedSource>>) -> DerivedSource {
+    DerivedSource::new(expr, |id| sources.get(id).map(|s| s.clone() as Arc<dyn PriceSource>)).unwrap()
+}
+
+#[test]
+fn test_parse() {
+    let expr = DerivedExpr::parse("BTCUSD*EURUSD / GBPUSD").unwrap();
+    assert_eq!(expr.feed_ids().collect::<Vec<_>>(), ["BTCUSD", "EURUSD", "GBPUSD"]);
+    assert_eq!(DerivedExpr::parse("1 / EURUSD").unwrap().feed_ids().collect::<Vec<_>>(), ["EURUSD"]);
+
+    assert!(DerivedExpr::parse("").is_err());
+    assert!(DerivedExpr::parse("ETHUSD /").is_err());
+    assert!(DerivedExpr::parse("ETHUSD BTCUSD").is_err());
+    assert!(DerivedExpr::parse("2 / 4").is_err(), "no feed");
+    assert!(DerivedExpr::parse("0 * ETHUSD").is_err());
+}
+
+#[test]
+fn test_cross_rate() {
+    let sources = sources(&["ETHUSD", "BTCUSD"]);
+    let ethbtc = derived("ETHUSD / BTCUSD", &sources);
+    assert!(ethbtc.latest().is_none());
+
+    sources["ETHUSD"].set(3_400.0, 1_000_003, Some(3.4));
+    // Needs every input
+    assert!(ethbtc.latest().is_none());
+
+    sources["BTCUSD"].set(97_000.0, 1_000_001, Some(48.5));
+    let point = ethbtc.latest().unwrap();
+    assert!((point.price - 3_400.0 / 97_000.0).abs() < 1e-12);
+    // Oldest input's timestamp, thinnest input's trades
+    assert_eq!((point.timestamp_ms, point.num_trades), (1_000_001, 1));
+    // 0.1% + 0.05% relative confidence
+    assert!((point.confidence.unwrap() / point.price - 0.0015).abs() < 1e-12);
+}
+
+#[test]
+fn test_inverse_and_constants() {
+    let sources = sources(&["EURUSD"]);
+    sources["EURUSD"].set(1.25, 5, None);
+
+    assert_eq!(derived("1 / EURUSD", &sources).latest().unwrap().price, 0.8);
+    assert_eq!(derived("EURUSD * 100", &sources).latest().unwrap().price, 125.0);
+    assert!(derived("1 / EURUSD", &sources).latest().unwrap().confidence.is_none());
+
+    sources["EURUSD"].set(0.0, 5, None);
+    assert!(derived("1 / EURUSD", &sources).latest().is_none());
+}
+
+#[test]
+fn test_unknown_inputs_are_rejected() {
+    let sources = sources(&["ETHUSD"]);
+    assert!(DerivedSource::new("ETHUSD / BTCUSD", |id| sources.get(id).map(|s| s.clone() as Arc<dyn PriceSource>)).is_err());
+}
+
+fn feed(id: &str, source: &str, symbol: &str) -> String {
+    format!(
+        "[[feeds]]\nid = \"{}\"\nsource = \"{}\"\nsymbol = \"{}\"\ncontract = \"0x5a569ad19272afa97103fd4dbadf33b2fcbaa175\"\n\n",
+        id, source, symbol
+    )
+}
+
+#[test]
+fn test_inputs_must_be_declared_first() {
+    let base = feed("ETHUSD", "binance", "ETHUSDT") + &feed("BTCUSD", "binance", "BTCUSDT");
+    assert!(FeedsFile::parse(&(base.clone() + &feed("ETHBTC", "derived", "ETHUSD / BTCUSD"))).is_ok());
+
+    let error = FeedsFile::parse(&(feed("ETHBTC", "derived", "ETHUSD / BTCUSD") + &base)).unwrap_err();
+    assert!(format!("{:#}", error).contains("declared before"), "{:#}", error);
+    assert!(FeedsFile::parse(&(base + &feed("LOOP", "derived", "LOOP / BTCUSD"))).is_err());
+}
@@ -0,0 +1,329 @@
+//! Hand-written calldata matches the generic ABI encoder
+
+use alloy::primitives::{keccak256, Bytes, Uint, B256, I256, U256};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use oracle_common::{
+    encode_commit_chain, encode_relay_block, encode_relay_drand, encode_reveal, encode_string_call, encode_update_gas_prices,
+    encode_feed_call, encode_update_price, encode_update_price_signed, encode_update_price_with_confidence, encode_update_price_with_round,
+    encode_update_timestamp, encode_update_timestamp_with_round, function_selector, scale_price_signed, FeedConfig, FeedId,
+};
+use proptest::prelude::*;
+
+sol! {
+    function updatePrice(string feedId, uint256 price);
+    function updateValue(string feedId, int256 value);
+    function updateSignedValue(int256 value);
+    function updatePriceByKey(bytes32 feedId, uint256 price);
+    function updatePriceByKeyWithConfidence(bytes32 feedId, uint256 price, uint256 confidence);
+    function updatePriceByKeyWithRound(bytes32 feedId, uint256 price, uint80 roundId, uint256 updatedAt);
+    function needsUpdateByKey(bytes32 feedId);
+    function updateTimestamp(uint256 timestamp);
+    function updateTimestampWithRound(uint256 timestamp, uint256 round);
+    function updatePriceWithRound(string feedId, uint256 price, uint80 roundId, uint256 updatedAt);
+    function updatePriceWithConfidence(string feedId, uint256 price, uint256 confidence);
+    function updateGasPrices(uint256 baseFee, uint256 priorityFee, uint256 l1BaseFee, uint256 blobBaseFee);
+    function commitChain(uint256 epoch, bytes32 anchor);
+    function reveal(bytes32 preimage);
+    function relayDrand(uint64 drandRound, bytes signature);
+    function needsUpdate(string feedId);
+    function relayBlock(uint256 sourceChainId, uint256 number, bytes32 blockHash, bytes32 parentHash);
+}
+
+#[test]
+fn test_update_price_with_round_matches_abi_encoding() {
+    for feed_id in ["BTCUSD", "A_FEED_ID_LONGER_THAN_THIRTY_TWO_BYTES"] {
+        let price = U256::from(65_000u64) * U256::from(10u64).pow(U256::from(18u64));
+        let expected = updatePriceWithRoundCall {
+            feedId: feed_id.to_string(),
+            price,
+            roundId: Uint::<80, 2>::from(110_680_464_442_257_320_000u128),
+            updatedAt: U256::from(1_760_000_000u64),
+        }
+        .abi_encode();
+
+        let selector = function_selector("updatePriceWithRound(string,uint256,uint80,uint256)");
+        let encoded = encode_update_price_with_round(selector, feed_id, price, 110_680_464_442_257_320_000, 1_760_000_000);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "feed {}", feed_id);
+    }
+}
+
+#[test]
+fn test_update_price_with_confidence_matches_abi_encoding() {
+    for feed_id in ["SOLUSD", "A_FEED_ID_LONGER_THAN_THIRTY_TWO_BYTES"] {
+        let price = U256::from(150_250_000_000_000_000_000u128);
+        let confidence = U256::from(75_000_000_000_000_000u128);
+        let expected = updatePriceWithConfidenceCall { feedId: feed_id.to_string(), price, confidence }.abi_encode();
+
+        let selector = function_selector("updatePriceWithConfidence(string,uint256,uint256)");
+        let encoded = encode_update_price_with_confidence(selector, feed_id, price, confidence);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "feed {}", feed_id);
+    }
+}
+
+#[test]
+fn test_update_gas_prices_matches_abi_encoding() {
+    let expected = updateGasPricesCall {
+        baseFee: U256::from(1_000_000_000u64),
+        priorityFee: U256::from(100_000_000u64),
+        l1BaseFee: U256::from(30_000_000_000u64),
+        blobBaseFee: U256::from(1u64),
+    }
+    .abi_encode();
+
+    let selector = function_selector("updateGasPrices(uint256,uint256,uint256,uint256)");
+    let encoded = encode_update_gas_prices(selector, 1_000_000_000, 100_000_000, 30_000_000_000, 1);
+    assert_eq!(encoded.as_ref(), expected.as_slice());
+}
+
+#[test]
+fn test_randomness_calls_match_abi_encoding() {
+    let anchor = B256::repeat_byte(0xab);
+    let expected = commitChainCall { epoch: U256::from(3u64), anchor }.abi_encode();
+    let encoded = encode_commit_chain(function_selector("commitChain(uint256,bytes32)"), 3, anchor);
+    assert_eq!(encoded.as_ref(), expected.as_slice());
+
+    let expected = revealCall { preimage: anchor }.abi_encode();
+    let encoded = encode_reveal(function_selector("reveal(bytes32)"), anchor);
+    assert_eq!(encoded.as_ref(), expected.as_slice());
+
+    // quicknet signatures are 48 bytes, default-chain ones 96
+    for len in [48, 96] {
+        let signature = vec![0x5a; len];
+        let expected = relayDrandCall { drandRound: 12_345_678, signature: Bytes::from(signature.clone()) }.abi_encode();
+        let encoded = encode_relay_drand(function_selector("relayDrand(uint64,bytes)"), 12_345_678, &signature);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "{} byte signature", len);
+    }
+}
+
+#[test]
+fn test_relay_block_matches_abi_encoding() {
+    let (hash, parent) = (B256::repeat_byte(0xab), B256::repeat_byte(0xcd));
+    let expected = relayBlockCall { sourceChainId: U256::from(1u64), number: U256::from(21_000_000u64), blockHash: hash, parentHash: parent }
+        .abi_encode();
+    let selector = function_selector("relayBlock(uint256,uint256,bytes32,bytes32)");
+    assert_eq!(encode_relay_block(selector, 1, 21_000_000, hash, parent).as_ref(), expected.as_slice());
+}
+
+#[test]
+fn test_string_call_matches_abi_encoding() {
+    for feed_id in ["", "BTCUSD", "A_FEED_ID_LONGER_THAN_THIRTY_TWO_BYTES"] {
+        let expected = needsUpdateCall { feedId: feed_id.to_string() }.abi_encode();
+        let encoded = encode_string_call(function_selector("needsUpdate(string)"), feed_id);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "feed id {:?}", feed_id);
+    }
+}
+
+#[test]
+fn test_update_price_at_word_boundaries() {
+    let price = U256::from(1u64) << 200;
+    for len in [0, 1, 31, 32, 33, 63, 64, 255, 256, 300] {
+        let feed_id = "F".repeat(len);
+        let expected = updatePriceCall { feedId: feed_id.clone(), price }.abi_encode();
+        let encoded = encode_update_price(function_selector("updatePrice(string,uint256)"), &feed_id, price);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "{}-byte feed id", len);
+    }
+}
+
+#[test]
+fn test_signed_values_match_abi_encoding() {
+    let selector = function_selector("updateValue(string,int256)");
+    for value in [I256::ZERO, I256::MINUS_ONE, I256::try_from(-1_250_000_000_000_000i64).unwrap(), I256::MIN, I256::MAX] {
+        let expected = updateValueCall { feedId: "BTCFUNDING".to_string(), value }.abi_encode();
+        let encoded = encode_update_price_signed(selector, "BTCFUNDING", value);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "value {}", value);
+    }
+}
+
+#[test]
+fn test_scale_price_signed() {
+    assert_eq!(scale_price_signed(-0.000125, 8), Some(I256::try_from(-12_500).unwrap()));
+    assert_eq!(scale_price_signed(0.5, 2), Some(I256::try_from(50).unwrap()));
+    assert_eq!(scale_price_signed(-1.0, 18), Some(I256::try_from(-1_000_000_000_000_000_000i128).unwrap()));
+    assert_eq!(scale_price_signed(f64::NAN, 18), None);
+    assert_eq!(scale_price_signed(-1e30, 18), None);
+}
+
+fn feed(function: &str) -> FeedConfig {
+    toml::from_str(&format!(
+        r#"
+        id = "BTCFUNDING"
+        source = "rest"
+        symbol = "https://fapi.binance.com/fapi/v1/premiumIndex?symbol=BTCUSDT"
+        json_path = "$.lastFundingRate"
+        contract = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175"
+        function = "{}"
+        decimals = 8
+        "#,
+        function
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_signed_feeds_encode_twos_complement() {
+    let signed = feed("updateValue(string,int256)");
+    assert!(signed.signed());
+    let value = signed.scale_value(-0.000125).unwrap();
+    assert_eq!(signed.format_value(value), "-12500");
+    let decoded = updateValueCall::abi_decode(&signed.encode_call(value).unwrap(), true).unwrap();
+    assert_eq!(decoded.value, I256::try_from(-12_500).unwrap());
+
+    let value_only = feed("updateSignedValue(int256)");
+    let decoded = updateSignedValueCall::abi_decode(&value_only.encode_call(value_only.scale_value(-2.5).unwrap()).unwrap(), true).unwrap();
+    assert_eq!(decoded.value, I256::try_from(-250_000_000).unwrap());
+
+    // Unsigned feeds still refuse negative values
+    let unsigned = feed("updatePrice(string,uint256)");
+    assert!(!unsigned.signed());
+    assert_eq!(unsigned.scale_value(-0.000125), None);
+    assert_eq!(unsigned.format_value(unsigned.scale_value(1.5).unwrap()), "150000000");
+}
+
+#[test]
+fn test_feed_calls_match_abi_encoding_for_both_id_layouts() {
+    let price = U256::from(97_012_500_000_000_000_000_000u128);
+    let confidence = U256::from(48_500_000_000_000_000_000u128);
+    let key = keccak256("BTCUSD");
+    assert_eq!(FeedId::keccak("BTCUSD"), FeedId::Bytes32(key));
+
+    let selector = function_selector("updatePriceByKey(bytes32,uint256)");
+    let expected = updatePriceByKeyCall { feedId: key, price }.abi_encode();
+    assert_eq!(encode_feed_call(selector, FeedId::Bytes32(key), &[price]).as_ref(), expected.as_slice());
+    assert_eq!(expected.len(), 4 + 2 * 32);
+
+    let selector = function_selector("updatePriceByKeyWithConfidence(bytes32,uint256,uint256)");
+    let expected = updatePriceByKeyWithConfidenceCall { feedId: key, price, confidence }.abi_encode();
+    assert_eq!(encode_feed_call(selector, FeedId::Bytes32(key), &[price, confidence]).as_ref(), expected.as_slice());
+
+    for feed_id in ["", "BTCUSD", "A_FEED_ID_LONGER_THAN_THIRTY_TWO_BYTES"] {
+        let selector = function_selector("updatePrice(string,uint256)");
+        let expected = updatePriceCall { feedId: feed_id.to_string(), price }.abi_encode();
+        assert_eq!(encode_feed_call(selector, FeedId::String(feed_id), &[price]).as_ref(), expected.as_slice());
+
+        let selector = function_selector("updatePriceWithConfidence(string,uint256,uint256)");
+        let expected = updatePriceWithConfidenceCall { feedId: feed_id.to_string(), price, confidence }.abi_encode();
+        let encoded = encode_feed_call(selector, FeedId::String(feed_id), &[price, confidence]);
+        assert_eq!(encoded.as_ref(), expected.as_slice(), "feed {:?}", feed_id);
+    }
+}
+
+fn keyed_feed(id_encoding: &str, function: &str) -> anyhow::Result<FeedConfig> {
+    let feed: FeedConfig = toml::from_str(&format!(
+        r#"
+        id = "BTCUSD"
+        source = "binance"
+        symbol = "BTCUSDT"
+        contract = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175"
+        function = "{}"
+        id_encoding = "{}"
+        "#,
+        function, id_encoding
+    ))?;
+    feed.encoding()?;
+    Ok(feed)
+}
+
+#[test]
+fn test_id_encoding_is_selected_per_feed() {
+    let price = U256::from(97_000u64);
+    let keyed = keyed_feed("bytes32", "updatePriceByKey(bytes32,uint256)").unwrap();
+    let decoded = updatePriceByKeyCall::abi_decode(&keyed.encode_call(price).unwrap(), true).unwrap();
+    assert_eq!((decoded.feedId, decoded.price), (keccak256("BTCUSD"), price));
+
+    let named = keyed_feed("string", "updatePrice(string,uint256)").unwrap();
+    let decoded = updatePriceCall::abi_decode(&named.encode_call(price).unwrap(), true).unwrap();
+    assert_eq!(decoded.feedId, "BTCUSD");
+
+    // The signature has to agree with the configured layout
+    assert!(keyed_feed("bytes32", "updatePrice(string,uint256)").is_err());
+    assert!(keyed_feed("string", "updatePriceByKey(bytes32,uint256)").is_err());
+}
+
+#[test]
+fn test_feeds_publish_rounds_when_the_signature_takes_one() {
+    let price = U256::from(97_000u64);
+    let named = keyed_feed("string", "updatePriceWithRound(string,uint256,uint80,uint256)").unwrap();
+    assert!(named.takes_round());
+    let decoded = updatePriceWithRoundCall::abi_decode(&named.encode_update(price, U256::ZERO, 42, 1_760_000_000).unwrap(), true).unwrap();
+    assert_eq!(decoded.feedId, "BTCUSD");
+    assert_eq!((decoded.price, decoded.roundId, decoded.updatedAt), (price, Uint::<80, 2>::from(42u64), U256::from(1_760_000_000u64)));
+
+    let keyed = keyed_feed("bytes32", "updatePriceByKeyWithRound(bytes32,uint256,uint80,uint256)").unwrap();
+    let decoded = updatePriceByKeyWithRoundCall::abi_decode(&keyed.encode_update(price, U256::ZERO, 7, 1).unwrap(), true).unwrap();
+    assert_eq!((decoded.feedId, decoded.roundId), (keccak256("BTCUSD"), Uint::<80, 2>::from(7u64)));
+
+    assert!(!keyed_feed("string", "updatePrice(string,uint256)").unwrap().takes_round());
+    assert!(keyed_feed("string", "updatePriceWithRound(string,uint256,uint256,uint256)").is_err());
+}
+
+#[test]
+fn test_keyed_condition_reads() {
+    let condition: oracle_common::FeedCondition = toml::from_str(r#"function = "needsUpdateByKey(bytes32)""#).unwrap();
+    let expected = needsUpdateByKeyCall { feedId: keccak256("BTCUSD") }.abi_encode();
+    assert_eq!(condition.call_data("BTCUSD").unwrap().as_ref(), expected.as_slice());
+}
+
+/// Feed ids of every length around the 32-byte word size, ASCII or not
+fn feed_id() -> impl Strategy<Value = String> {
+    prop_oneof!["[A-Z0-9_/]{0,300}", ".{0,40}"]
+}
+
+fn any_u256() -> impl Strategy<Value = U256> {
+    any::<[u8; 32]>().prop_map(U256::from_be_bytes)
+}
+
+proptest! {
+    #[test]
+    fn prop_update_price_matches_abi_encoding(feed_id in feed_id(), price in any_u256()) {
+        let expected = updatePriceCall { feedId: feed_id.clone(), price }.abi_encode();
+        let encoded = encode_update_price(function_selector("updatePrice(string,uint256)"), &feed_id, price);
+        prop_assert_eq!(encoded.as_ref(), expected.as_slice());
+        let decoded = updatePriceCall::abi_decode(&encoded, true).unwrap();
+        prop_assert_eq!(decoded.feedId, feed_id);
+        prop_assert_eq!(decoded.price, price);
+    }
+
+    #[test]
+    fn prop_update_timestamp_matches_abi_encoding(timestamp in any::<u64>(), round in any::<u64>()) {
+        let expected = updateTimestampCall { timestamp: U256::from(timestamp) }.abi_encode();
+        prop_assert_eq!(encode_update_timestamp(timestamp).as_ref(), expected.as_slice());
+
+        let expected = updateTimestampWithRoundCall { timestamp: U256::from(timestamp), round: U256::from(round) }.abi_encode();
+        prop_assert_eq!(encode_update_timestamp_with_round(timestamp, round).as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn prop_price_variants_match_abi_encoding(
+        feed_id in feed_id(),
+        price in any_u256(),
+        confidence in any_u256(),
+        round_id in any::<u128>().prop_map(|r| r >> 48),
+        updated_at in any::<u64>(),
+    ) {
+        let expected = updatePriceWithRoundCall {
+            feedId: feed_id.clone(),
+            price,
+            roundId: Uint::<80, 2>::from(round_id),
+            updatedAt: U256::from(updated_at),
+        }
+        .abi_encode();
+        let encoded = encode_update_price_with_round(
+            function_selector("updatePriceWithRound(string,uint256,uint80,uint256)"),
+            &feed_id,
+            price,
+            round_id,
+            updated_at,
+        );
+        prop_assert_eq!(encoded.as_ref(), expected.as_slice());
+
+        let expected = updatePriceWithConfidenceCall { feedId: feed_id.clone(), price, confidence }.abi_encode();
+        let encoded = encode_update_price_with_confidence(
+            function_selector("updatePriceWithConfidence(string,uint256,uint256)"),
+            &feed_id,
+            price,
+            confidence,
+        );
+        prop_assert_eq!(encoded.as_ref(), expected.as_slice());
+    }
+}
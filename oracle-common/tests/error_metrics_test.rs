@@ -0,0 +1,55 @@
+//! Error counts by category over time
+
+use alloy::primitives::Address;
+use nonzu_sdk::error_handling::ErrorAction;
+use nonzu_sdk::RiseError;
+use oracle_common::{ErrorCategory, ErrorMetrics, ErrorPolicy};
+use std::time::Duration;
+
+const T0: u64 = 1_700_000_000 / 60 * 60;
+
+#[test]
+fn test_windows() {
+    let metrics = ErrorMetrics::new();
+    metrics.record_at(ErrorCategory::NonceGap, T0);
+    metrics.record_at(ErrorCategory::NonceGap, T0 + 10);
+    metrics.record_at(ErrorCategory::RpcTimeout, T0 + 9 * 60);
+    metrics.record_at(ErrorCategory::Connection, T0 + 14 * 60 + 30);
+
+    let now = T0 + 14 * 60 + 45;
+    let last_5m = metrics.recent_at(Duration::from_secs(300), now);
+    assert_eq!(last_5m.get(&ErrorCategory::NonceGap), None);
+    assert_eq!(last_5m.get(&ErrorCategory::RpcTimeout), None);
+    assert_eq!(last_5m.get(&ErrorCategory::Connection), Some(&1));
+
+    let last_15m = metrics.recent_at(Duration::from_secs(900), now);
+    assert_eq!(last_15m.get(&ErrorCategory::NonceGap), Some(&2));
+    assert_eq!(last_15m.get(&ErrorCategory::RpcTimeout), Some(&1));
+}
+
+#[test]
+fn test_old_buckets_expire_but_totals_remain() {
+    let metrics = ErrorMetrics::new();
+    metrics.record_at(ErrorCategory::Revert, T0);
+    metrics.record_at(ErrorCategory::Underpriced, T0 + 2 * 3600);
+
+    let recent = metrics.recent_at(Duration::from_secs(3600), T0 + 2 * 3600);
+    assert_eq!(recent.get(&ErrorCategory::Revert), None);
+    assert_eq!(metrics.total(ErrorCategory::Revert), 1);
+    assert_eq!(metrics.total(ErrorCategory::Underpriced), 1);
+}
+
+#[test]
+fn test_policy_records_every_error() {
+    let policy = ErrorPolicy::default();
+    let key = Address::repeat_byte(0x22);
+    policy.resolve(&RiseError::Rpc("request timed out".to_string()), key, 0, ErrorAction::Continue);
+    policy.resolve(&RiseError::TransactionUnderpriced { current: 1, required: 2 }, key, 0, ErrorAction::Continue);
+    policy.resolve(&RiseError::Rpc("request timed out".to_string()), key, 1, ErrorAction::Continue);
+
+    let metrics = policy.metrics();
+    assert_eq!(metrics.total(ErrorCategory::RpcTimeout), 2);
+    assert_eq!(metrics.total(ErrorCategory::Underpriced), 1);
+    assert_eq!(metrics.to_json()["total"]["rpc_timeout"], 2);
+    assert!(metrics.summary(Duration::from_secs(60)).contains("rpc_timeout=2"));
+}
@@ -0,0 +1,127 @@
+//! RISE error parser pack and custom parser registration
+
+use nonzu_sdk::RiseError;
+use oracle_common::{ErrorCategory, ErrorMessageParser, ErrorParsers, ErrorPolicy, ParsedError, RegexParser};
+use std::sync::Arc;
+
+fn missing_nonce(nonce: u64) -> String {
+    format!(
+        "The transaction was added to the mempool but wasn't processed due to a missing nonce. Please submit a transaction with nonce {} first.",
+        nonce
+    )
+}
+
+#[test]
+fn test_rise_missing_nonce_from_production_log() {
+    let parsers = ErrorParsers::rise();
+    for nonce in [1192696, 1173437, 1140309, 1167847, 1186689, 545078, 527132, 683159] {
+        let parsed = parsers.parse(&missing_nonce(nonce)).unwrap();
+        assert_eq!(parsed.category, ErrorCategory::NonceGap);
+        assert_eq!(parsed.parser, "rise_missing_nonce");
+        assert_eq!(parsed.expected_nonce, Some(nonce));
+    }
+
+    // Wrapped by the RPC client
+    let wrapped = format!("server returned an error response: error code -32000: {}", missing_nonce(683159));
+    assert_eq!(parsers.parse(&wrapped).unwrap().expected_nonce, Some(683159));
+}
+
+#[test]
+fn test_geth_style_nonce_messages() {
+    let parsers = ErrorParsers::rise();
+
+    let low = parsers.parse("nonce too low: next nonce 42, tx nonce 40").unwrap();
+    assert_eq!(low.category, ErrorCategory::NonceTooLow);
+    assert_eq!(low.expected_nonce, Some(42));
+    assert_eq!(low.tx_nonce, Some(40));
+
+    let bare = parsers.parse("Nonce too low").unwrap();
+    assert_eq!(bare.category, ErrorCategory::NonceTooLow);
+    assert_eq!(bare.expected_nonce, None);
+
+    assert_eq!(parsers.parse("already known").unwrap().category, ErrorCategory::NonceTooLow);
+    assert_eq!(parsers.parse("nonce too high").unwrap().category, ErrorCategory::NonceGap);
+}
+
+#[test]
+fn test_other_categories() {
+    let parsers = ErrorParsers::rise();
+    let category = |msg: &str| parsers.parse(msg).map(|p| p.category);
+
+    assert_eq!(category("replacement transaction underpriced"), Some(ErrorCategory::Underpriced));
+    assert_eq!(
+        category("insufficient funds for gas * price + value: have 10 want 20"),
+        Some(ErrorCategory::InsufficientFunds)
+    );
+    assert_eq!(category("Timed out waiting for receipt of 0xabc"), Some(ErrorCategory::RpcTimeout));
+    assert_eq!(
+        category("error sending request for url (https://testnet.riselabs.xyz/)"),
+        Some(ErrorCategory::Connection)
+    );
+    assert_eq!(category("something else entirely"), None);
+
+    let revert = parsers.parse("execution reverted: Timestamp too old").unwrap();
+    assert_eq!(revert.category, ErrorCategory::Revert);
+    assert_eq!(revert.detail.as_deref(), Some("Timestamp too old"));
+    assert_eq!(parsers.parse("execution reverted").unwrap().detail, None);
+}
+
+#[test]
+fn test_classify_prefers_typed_variants() {
+    let parsers = ErrorParsers::rise();
+    let typed = RiseError::ContractReverted { reason: "nonce too low".to_string(), data: None };
+    assert_eq!(parsers.classify(&typed), ErrorCategory::Revert);
+    assert_eq!(parsers.classify(&RiseError::Rpc(missing_nonce(1))), ErrorCategory::NonceGap);
+    assert_eq!(parsers.classify(&RiseError::Rpc("weird".to_string())), ErrorCategory::Other);
+}
+
+struct QuotaParser;
+
+impl ErrorMessageParser for QuotaParser {
+    fn name(&self) -> &str {
+        "quota"
+    }
+
+    fn parse(&self, message: &str) -> Option<ParsedError> {
+        message.contains("quota exceeded").then(|| ParsedError {
+            category: ErrorCategory::Connection,
+            parser: self.name().to_string(),
+            expected_nonce: None,
+            tx_nonce: None,
+            detail: None,
+        })
+    }
+}
+
+#[test]
+fn test_custom_parsers() {
+    let mut parsers = ErrorParsers::rise();
+    parsers.register(Arc::new(QuotaParser));
+    parsers.register_first(Arc::new(
+        RegexParser::new("pool_full", r"txpool is full", ErrorCategory::Underpriced).unwrap(),
+    ));
+
+    assert_eq!(parsers.parse("daily quota exceeded").unwrap().parser, "quota");
+    assert_eq!(parsers.parse("TxPool is full").unwrap().category, ErrorCategory::Underpriced);
+    assert!(RegexParser::new("bad", r"(unclosed", ErrorCategory::Other).is_err());
+
+    let policy = ErrorPolicy::default().with_parsers(parsers);
+    assert!(format!("{:?}", policy).contains("quota"));
+}
+
+#[test]
+fn test_fuzz_seed_corpus_classification() {
+    // Seeds are production messages named `<category>-<description>`
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/seeds/error_parsers");
+    let parsers = ErrorParsers::rise();
+    let mut seeds = 0;
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let expected: ErrorCategory = name.split('-').next().unwrap().parse().unwrap();
+        let message = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(parsers.classify(&RiseError::Rpc(message)), expected, "seed {}", name);
+        seeds += 1;
+    }
+    assert!(seeds >= 10);
+}
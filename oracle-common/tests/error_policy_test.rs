@@ -0,0 +1,110 @@
+//! ERROR_POLICY parsing and per-category action resolution
+
+use alloy::primitives::Address;
+use nonzu_sdk::error_handling::ErrorAction;
+use nonzu_sdk::RiseError;
+use oracle_common::{ErrorCategory, ErrorPolicy, FeedPauses, PolicyAction};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_parse_policy() {
+    let policy = ErrorPolicy::parse(
+        "nonce_gap=pause:1s, revert=skip, underpriced=retry:2, rpc-timeout=pause:500ms, insufficient_funds=remove-key",
+    )
+    .unwrap();
+
+    assert_eq!(policy.action_for(ErrorCategory::NonceGap), Some(PolicyAction::Pause(Some(Duration::from_secs(1)))));
+    assert_eq!(policy.action_for(ErrorCategory::Revert), Some(PolicyAction::Skip));
+    assert_eq!(policy.action_for(ErrorCategory::Underpriced), Some(PolicyAction::Retry { max_retries: 2 }));
+    assert_eq!(policy.action_for(ErrorCategory::RpcTimeout), Some(PolicyAction::Pause(Some(Duration::from_millis(500)))));
+    assert_eq!(policy.action_for(ErrorCategory::InsufficientFunds), Some(PolicyAction::RemoveKey));
+    assert_eq!(policy.action_for(ErrorCategory::Connection), None);
+}
+
+#[test]
+fn test_parse_rejects_unknown_entries() {
+    assert!(ErrorPolicy::parse("nonce=skip").is_err());
+    assert!(ErrorPolicy::parse("revert=explode").is_err());
+    assert!(ErrorPolicy::parse("revert").is_err());
+}
+
+#[test]
+fn test_classify_rise_errors() {
+    let missing_nonce = RiseError::Rpc(
+        "The transaction was added to the mempool but wasn't processed due to a missing nonce. Please submit a transaction with nonce 1192696 first.".to_string(),
+    );
+    assert_eq!(ErrorCategory::classify(&missing_nonce), ErrorCategory::NonceGap);
+    assert_eq!(
+        ErrorCategory::classify(&RiseError::TransactionUnderpriced { current: 1, required: 2 }),
+        ErrorCategory::Underpriced
+    );
+    assert_eq!(
+        ErrorCategory::classify(&RiseError::Rpc("error sending request: connection refused".to_string())),
+        ErrorCategory::Connection
+    );
+}
+
+#[test]
+fn test_resolve_actions() {
+    let key = Address::repeat_byte(0x11);
+    let policy = ErrorPolicy::default()
+        .with(ErrorCategory::Revert, PolicyAction::Retry { max_retries: 1 })
+        .with(ErrorCategory::Underpriced, PolicyAction::RemoveKey);
+    let revert = RiseError::ContractReverted { reason: "stale".to_string(), data: None };
+
+    assert!(matches!(policy.resolve(&revert, key, 0, ErrorAction::Continue), ErrorAction::Retry));
+    assert!(matches!(policy.resolve(&revert, key, 1, ErrorAction::Continue), ErrorAction::Continue));
+
+    let underpriced = RiseError::TransactionUnderpriced { current: 1, required: 2 };
+    assert!(matches!(policy.resolve(&underpriced, key, 0, ErrorAction::Continue), ErrorAction::RemoveKey(k) if k == key));
+
+    // Unlisted categories keep the SDK's choice
+    let timeout = RiseError::Rpc("request timed out".to_string());
+    assert!(matches!(
+        policy.resolve(&timeout, key, 0, ErrorAction::Pause(Duration::from_secs(3))),
+        ErrorAction::Pause(d) if d == Duration::from_secs(3)
+    ));
+}
+
+#[test]
+fn test_feed_scoped_errors_pause_only_the_failing_feed() {
+    let key = Address::repeat_byte(0x11);
+    let pauses = Arc::new(FeedPauses::default());
+    let policy = ErrorPolicy::default().with_feed_pauses(pauses.clone());
+    let revert = RiseError::ContractReverted { reason: "unknown selector".to_string(), data: None };
+    let pause = || ErrorAction::Pause(Duration::from_secs(3));
+
+    // The pool carries on; only the experimental feed sits out the pause
+    assert!(matches!(policy.resolve_for_feed(&revert, Some("EXPERIMENTAL"), key, 0, pause()), ErrorAction::Continue));
+    assert!(pauses.is_paused("EXPERIMENTAL"));
+    assert!(!pauses.is_paused("BTCUSD"));
+    assert!(pauses.remaining("EXPERIMENTAL").unwrap() > Duration::from_secs(2));
+    assert_eq!(pauses.to_json()["paused"]["EXPERIMENTAL"]["category"], "revert");
+
+    // Shared failures, and updates that aren't a feed's, still pause the pool
+    let nonce_gap = RiseError::Rpc("missing nonce 12 first".to_string());
+    assert!(matches!(policy.resolve_for_feed(&nonce_gap, Some("BTCUSD"), key, 0, pause()), ErrorAction::Pause(_)));
+    assert!(matches!(policy.resolve_for_feed(&revert, None, key, 0, pause()), ErrorAction::Pause(_)));
+    assert!(!pauses.is_paused("BTCUSD"));
+
+    // Non-pause actions are left alone
+    let skip = ErrorPolicy::default().with(ErrorCategory::Revert, PolicyAction::Skip).with_feed_pauses(pauses.clone());
+    assert!(matches!(skip.resolve_for_feed(&revert, Some("ETHUSD"), key, 0, ErrorAction::Continue), ErrorAction::Continue));
+    assert!(!pauses.is_paused("ETHUSD"));
+}
+
+#[test]
+fn test_feed_pauses_expire() {
+    let pauses = FeedPauses::parse("revert, nonce_gap").unwrap();
+    assert!(pauses.is_scoped(ErrorCategory::NonceGap));
+    assert!(!pauses.is_scoped(ErrorCategory::Connection));
+    assert!(!FeedPauses::parse("").unwrap().is_scoped(ErrorCategory::Revert));
+    assert!(FeedPauses::parse("reverts").is_err());
+
+    pauses.pause("BTCUSD", Duration::from_millis(20), ErrorCategory::Revert);
+    assert!(pauses.is_paused("BTCUSD"));
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(!pauses.is_paused("BTCUSD"));
+    assert_eq!(pauses.remaining("BTCUSD"), None);
+}
@@ -0,0 +1,158 @@
+//! Event sink records: streams, keys and payloads per event, the trades
+//! switch, NATS subjects, and counting what a failing broker loses.
+
+use alloy::primitives::{B256, U256};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use oracle_common::{
+    nats_subject, EventSink, EventSinkConfig, EventStream, EventSubscriber, OracleEvent, SinkRecord, StreamSink,
+};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct RecordingSink {
+    records: Mutex<Vec<SinkRecord>>,
+    fail: bool,
+}
+
+#[async_trait]
+impl StreamSink for RecordingSink {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn send(&self, records: &[SinkRecord]) -> Result<()> {
+        if self.fail {
+            bail!("broker down");
+        }
+        self.records.lock().extend_from_slice(records);
+        Ok(())
+    }
+}
+
+fn trade(symbol: &str, price: f64) -> OracleEvent {
+    OracleEvent::TradeReceived {
+        source: "binance".to_string(),
+        symbol: symbol.to_string(),
+        price,
+        quantity: 0.25,
+        timestamp_ms: 1_735_689_600_000,
+    }
+}
+
+fn payload(record: &SinkRecord) -> Value {
+    serde_json::from_str(&record.payload).unwrap()
+}
+
+async fn until(mut done: impl FnMut() -> bool) {
+    for _ in 0..200 {
+        if done() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("condition not reached within 2s");
+}
+
+#[tokio::test]
+async fn test_events_map_to_streams() {
+    let broker = Arc::new(RecordingSink::default());
+    let (sink, _handle) = EventSink::spawn(broker.clone(), EventSinkConfig::default());
+
+    sink.on_event(&trade("BTCUSDT", 97_010.0));
+    sink.on_event(&OracleEvent::ValueComputed { feed: "BTCUSD".to_string(), value: 97_012.5 });
+    sink.on_event(&OracleEvent::UpdatePublished {
+        feed: "BTCUSD".to_string(),
+        value: Some(97_012.5),
+        tx_hash: Some(B256::repeat_byte(0xab)),
+        block_number: Some(U256::from(1234)),
+        latency: Some(Duration::from_millis(4)),
+        drift_ms: 2,
+    });
+    sink.on_event(&OracleEvent::UpdateFailed { feed: "BTCUSD".to_string(), value: None, latency: None, drift_ms: 0 });
+    sink.on_event(&OracleEvent::Paused { scope: "rise".to_string() });
+
+    until(|| broker.records.lock().len() == 4).await;
+    let records = broker.records.lock().clone();
+    let streams: Vec<_> = records.iter().map(|r| (r.stream, r.topic.as_str(), r.key.as_str())).collect();
+    assert_eq!(
+        streams,
+        [
+            (EventStream::Trades, "nonzu.trades", "BTCUSDT"),
+            (EventStream::Prices, "nonzu.prices", "BTCUSD"),
+            (EventStream::Updates, "nonzu.updates", "BTCUSD"),
+            (EventStream::Updates, "nonzu.updates", "BTCUSD"),
+        ]
+    );
+
+    let trade = payload(&records[0]);
+    assert_eq!(trade["event"], "trade_received");
+    assert_eq!((trade["price"].as_f64(), trade["quantity"].as_f64()), (Some(97_010.0), Some(0.25)));
+    assert_eq!(trade["trade_time_ms"], 1_735_689_600_000u64);
+    assert!(trade["emitted_ms"].as_u64().unwrap() > 0);
+
+    assert_eq!(payload(&records[1])["event"], "price_computed");
+    let published = payload(&records[2]);
+    assert_eq!(published["event"], "update_published");
+    assert_eq!(published["block_number"], 1234);
+    assert_eq!(published["latency_ms"], 4.0);
+    assert_eq!(published["drift_ms"], 2);
+    assert_eq!(payload(&records[3])["event"], "update_failed");
+    assert_eq!(sink.sent(), 4);
+}
+
+#[tokio::test]
+async fn test_trades_can_be_left_out() {
+    let broker = Arc::new(RecordingSink::default());
+    let config = EventSinkConfig { prefix: "oracle-eu".to_string(), trades: false, ..Default::default() };
+    let (sink, _handle) = EventSink::spawn(broker.clone(), config);
+
+    assert!(sink.record(&trade("BTCUSDT", 97_010.0)).is_none());
+    let record = sink.record(&OracleEvent::ValueComputed { feed: "EURUSD".to_string(), value: 1.0842 }).unwrap();
+    assert_eq!(record.topic, "oracle-eu.prices");
+}
+
+#[tokio::test]
+async fn test_nats_subjects_keep_the_key_one_token() {
+    let broker = Arc::new(RecordingSink::default());
+    let (sink, _handle) = EventSink::spawn(broker, EventSinkConfig::default());
+
+    let record = sink.record(&OracleEvent::ValueComputed { feed: "rise/BTCUSD".to_string(), value: 1.0 }).unwrap();
+    assert_eq!(nats_subject(&record), "nonzu.prices.rise/BTCUSD");
+    let record = sink.record(&OracleEvent::ValueComputed { feed: "ETH.USD v2".to_string(), value: 1.0 }).unwrap();
+    assert_eq!(nats_subject(&record), "nonzu.prices.ETH_USD_v2");
+}
+
+#[tokio::test]
+async fn test_gaps_are_marked_on_the_updates_stream() {
+    let broker = Arc::new(RecordingSink::default());
+    let (sink, _handle) = EventSink::spawn(broker, EventSinkConfig::default());
+
+    let gap = OracleEvent::GapDetected { feed: "production".to_string(), from_ms: 1_735_689_600_000, to_ms: 1_735_689_612_500 };
+    let record = sink.record(&gap).unwrap();
+    assert_eq!((record.stream, record.key.as_str()), (EventStream::Updates, "production"));
+    let gap = payload(&record);
+    assert_eq!(gap["event"], "gap_detected");
+    assert_eq!((gap["gap_from_ms"].as_u64(), gap["gap_to_ms"].as_u64()), (Some(1_735_689_600_000), Some(1_735_689_612_500)));
+    assert_eq!(gap["gap_ms"], 12_500);
+}
+
+#[tokio::test]
+async fn test_failed_sends_and_full_queue_are_counted() {
+    let broker = Arc::new(RecordingSink { fail: true, ..Default::default() });
+    let config = EventSinkConfig { queue: 4, ..Default::default() };
+    let (sink, _handle) = EventSink::spawn(broker, config);
+
+    sink.on_event(&trade("BTCUSDT", 97_000.0));
+    until(|| sink.dropped() == 1).await;
+
+    // The sender now waits out the retry delay; the queue fills behind it
+    for i in 0..10 {
+        sink.on_event(&trade("BTCUSDT", 97_001.0 + i as f64));
+    }
+    assert_eq!(sink.dropped(), 1 + 6);
+    assert_eq!(sink.sent(), 0);
+}
@@ -0,0 +1,52 @@
+//! Event bus delivery and the history subscriber
+
+use oracle_common::{EventBus, EventSubscriber, OracleEvent, UpdateHistory};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Recorder {
+    kinds: Mutex<Vec<&'static str>>,
+}
+
+impl EventSubscriber for Recorder {
+    fn on_event(&self, event: &OracleEvent) {
+        self.kinds.lock().push(event.kind());
+    }
+}
+
+#[test]
+fn test_every_subscriber_sees_every_event() {
+    let bus = EventBus::default();
+    let first = Arc::new(Recorder::default());
+    let second = Arc::new(Recorder::default());
+    bus.subscribe(first.clone());
+    bus.subscribe(second.clone());
+
+    bus.publish(OracleEvent::Paused { scope: "rise".into() });
+    bus.publish(OracleEvent::Resumed { scope: "rise".into() });
+
+    assert_eq!(bus.subscriber_count(), 2);
+    assert_eq!(*first.kinds.lock(), vec!["paused", "resumed"]);
+    assert_eq!(*second.kinds.lock(), vec!["paused", "resumed"]);
+}
+
+#[test]
+fn test_history_records_completed_updates_only() {
+    let bus = EventBus::default();
+    let history = UpdateHistory::new(10);
+    bus.subscribe(history.clone());
+
+    bus.publish(OracleEvent::completed("BTCUSD", true, None, Some(65_000.0), Some(Duration::from_millis(40)), 2));
+    bus.publish(OracleEvent::completed("BTCUSD", false, None, Some(65_010.0), None, 0));
+    bus.publish(OracleEvent::WsReconnect { source: "binance".into(), error: None });
+
+    let updates = history.since(0);
+    assert_eq!(updates.len(), 2);
+    assert!(updates[0].success);
+    assert_eq!(updates[0].latency_ms, Some(40));
+    assert_eq!(updates[0].drift_ms, 2);
+    assert!(!updates[1].success);
+    assert_eq!(updates[1].value, Some(65_010.0));
+}
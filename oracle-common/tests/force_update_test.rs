@@ -0,0 +1,33 @@
+//! Forced updates: scope matching and clearing once sent
+
+use oracle_common::ForceUpdates;
+
+fn registry() -> ForceUpdates {
+    let force_updates = ForceUpdates::new();
+    for feed in ["rise/BTCUSD", "rise/ETHUSD", "rise-testnet/BTCUSD", "BTCUSD"] {
+        force_updates.register(feed);
+    }
+    force_updates
+}
+
+#[test]
+fn test_scopes_match_a_feed_a_target_or_everything() {
+    assert_eq!(registry().request("BTCUSD"), ["BTCUSD"]);
+    // A target's prefix doesn't match a longer target name
+    assert_eq!(registry().request("rise"), ["rise/BTCUSD", "rise/ETHUSD"]);
+    assert_eq!(registry().request("").len(), 4);
+    assert!(registry().request("XAUUSD").is_empty());
+}
+
+#[test]
+fn test_requests_stay_queued_until_sent() {
+    let force_updates = registry();
+    force_updates.request("rise/BTCUSD");
+    assert!(force_updates.is_requested("rise/BTCUSD"));
+    assert!(!force_updates.is_requested("rise/ETHUSD"));
+
+    assert!(force_updates.take("rise/BTCUSD"));
+    assert!(!force_updates.is_requested("rise/BTCUSD"));
+    assert!(!force_updates.take("rise/BTCUSD"));
+    assert_eq!(force_updates.to_json()["requested"].as_array().unwrap().len(), 0);
+}
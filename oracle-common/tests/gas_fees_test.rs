@@ -0,0 +1,78 @@
+//! Per-feed gas overrides: parsing and validation in the feeds file, the
+//! request metadata they travel in, and the balance check pricing them
+
+use alloy::primitives::{Address, Bytes, U256};
+use nonzu_sdk::traits::TxRequest;
+use oracle_common::{BalanceAwareSelector, FeedsFile, GasFees};
+
+const GWEI: u64 = 1_000_000_000;
+
+fn feed(id: &str, gas: &str) -> String {
+    format!(
+        "[[feeds]]\nid = \"{}\"\nsource = \"binance\"\nsymbol = \"BTCUSDT\"\ncontract = \"0x5a569ad19272afa97103fd4dbadf33b2fcbaa175\"\n{}\n\n",
+        id, gas
+    )
+}
+
+fn update() -> TxRequest {
+    TxRequest::new(Address::repeat_byte(0xaa), Bytes::new()).with_gas_limit(U256::from(1_000_000))
+}
+
+#[test]
+fn test_feeds_override_gas_independently() {
+    let file = FeedsFile::parse(&format!(
+        "{}{}",
+        feed("BATCH", "gas_limit = 1500000\nmax_fee_per_gas_wei = 2000000000\npriority_fee_wei = 500000000"),
+        feed("BTCUSD", "")
+    ))
+    .unwrap();
+
+    let batch = &file.feeds[0];
+    assert_eq!(batch.gas_limit, 1_500_000);
+    assert_eq!(batch.gas_fees(), GasFees::new(Some(2 * GWEI), Some(GWEI / 2)));
+
+    let single = &file.feeds[1];
+    assert_eq!(single.gas_limit, 300_000);
+    assert!(single.gas_fees().is_empty());
+}
+
+#[test]
+fn test_invalid_gas_overrides_are_rejected() {
+    let err = FeedsFile::parse(&feed("BATCH", "max_fee_per_gas_wei = 1000\npriority_fee_wei = 2000")).unwrap_err();
+    assert!(format!("{:#}", err).contains("priority_fee_wei (2000) exceeds max_fee_per_gas_wei (1000)"));
+    assert!(FeedsFile::parse(&feed("BATCH", "gas_limit = 0")).is_err());
+}
+
+#[test]
+fn test_overrides_travel_with_the_request() {
+    let plain = GasFees::default().apply(update());
+    assert!(plain.build_hook.is_none());
+    assert!(GasFees::from_request(&plain).is_empty());
+
+    let fees = GasFees::new(Some(3 * GWEI), None);
+    let request = fees.apply(update());
+    assert!(request.build_hook.is_some());
+    assert_eq!(GasFees::from_request(&request), fees);
+    assert_eq!(request.metadata.get("max_fee_per_gas").map(String::as_str), Some("3000000000"));
+}
+
+#[test]
+fn test_unset_fees_fall_back_to_the_defaults() {
+    assert_eq!(GasFees::default().resolve(GWEI, GWEI / 10), (GWEI, GWEI / 10));
+    assert_eq!(GasFees::new(Some(2 * GWEI), None).resolve(GWEI, GWEI / 10), (2 * GWEI, GWEI / 10));
+    // A lower max fee caps the default priority fee
+    assert_eq!(GasFees::new(Some(GWEI / 20), None).resolve(GWEI, GWEI / 10), (GWEI / 20, GWEI / 20));
+}
+
+#[test]
+fn test_balance_check_prices_the_override() {
+    let selector = BalanceAwareSelector::new(U256::ZERO);
+    assert_eq!(selector.max_cost(&update()), None);
+    assert_eq!(
+        selector.max_cost(&GasFees::new(Some(2 * GWEI), None).apply(update())),
+        Some(U256::from(2_000_000 * GWEI))
+    );
+
+    selector.update_gas_price(U256::from(GWEI));
+    assert_eq!(selector.max_cost(&update()), Some(U256::from(1_000_000 * GWEI)));
+}
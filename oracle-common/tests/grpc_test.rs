@@ -0,0 +1,188 @@
+//! The gRPC API over a real connection: latest prices and price streams fed
+//! by the event bus, stats, the token check on Pause / Resume, and forced updates.
+
+use alloy::primitives::{B256, U256};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use oracle_common::grpc::proto::oracle_client::OracleClient;
+use oracle_common::grpc::proto::{
+    ForceUpdateRequest, GetLatestPriceRequest, GetStatsRequest, PauseRequest, PriceEvent, PriceEventKind, ResumeRequest,
+    StreamPricesRequest,
+};
+use oracle_common::{publish_event, ForceUpdates, GrpcServer, OracleEvent, OracleStats};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::transport::Channel;
+use tonic::{Code, Request, Streaming};
+
+const TOKEN: &str = "s3cret";
+
+/// Serve `server` on a free port and connect to it. Tests share the global
+/// event bus, so each uses feed names of its own.
+async fn connect(server: GrpcServer) -> OracleClient<Channel> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(server.serve(listener));
+    let mut client = OracleClient::connect(url).await.unwrap();
+    // Answered once the server is up, and so subscribed to the event bus
+    client.get_stats(GetStatsRequest::default()).await.unwrap();
+    client
+}
+
+fn server() -> GrpcServer {
+    GrpcServer::new("127.0.0.1:0".parse().unwrap())
+}
+
+fn computed(feed: &str, value: f64) -> OracleEvent {
+    OracleEvent::ValueComputed { feed: feed.to_string(), value }
+}
+
+fn published(feed: &str, value: f64) -> OracleEvent {
+    OracleEvent::UpdatePublished {
+        feed: feed.to_string(),
+        value: Some(value),
+        tx_hash: Some(B256::repeat_byte(0xab)),
+        block_number: Some(U256::from(1234)),
+        latency: Some(Duration::from_millis(4)),
+        drift_ms: 0,
+    }
+}
+
+async fn next_event(stream: &mut Streaming<PriceEvent>) -> PriceEvent {
+    let event = tokio::time::timeout(Duration::from_secs(2), stream.message()).await.expect("no event within 2s");
+    event.unwrap().expect("stream ended")
+}
+
+fn admin<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request.metadata_mut().insert("authorization", format!("Bearer {}", token).parse().unwrap());
+    request
+}
+
+#[tokio::test]
+async fn test_latest_price_tracks_computed_and_published_values() {
+    let mut client = connect(server()).await;
+    let latest = |feed: &str| GetLatestPriceRequest { feed: feed.to_string() };
+
+    let missing = client.get_latest_price(latest("GRPC_LATEST")).await.unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+
+    publish_event(computed("GRPC_LATEST", 97_012.5));
+    let price = client.get_latest_price(latest("GRPC_LATEST")).await.unwrap().into_inner();
+    assert_eq!(price.computed.unwrap().value, Some(97_012.5));
+    assert!(price.published.is_none());
+
+    publish_event(published("GRPC_LATEST", 97_012.5));
+    publish_event(computed("GRPC_LATEST", 97_013.0));
+    let price = client.get_latest_price(latest("GRPC_LATEST")).await.unwrap().into_inner();
+    assert_eq!(price.computed.unwrap().value, Some(97_013.0));
+    let published = price.published.unwrap();
+    assert_eq!(published.kind(), PriceEventKind::Published);
+    assert_eq!(published.tx_hash, Some(B256::repeat_byte(0xab).to_string()));
+    assert_eq!(published.block_number, Some(1234));
+    assert_eq!(published.latency_ms, Some(4.0));
+}
+
+#[tokio::test]
+async fn test_stream_prices_filters_feeds() {
+    let mut client = connect(server()).await;
+    let request = StreamPricesRequest { feeds: vec!["GRPC_STREAM_ETH".to_string()] };
+    let mut stream = client.stream_prices(request).await.unwrap().into_inner();
+
+    publish_event(computed("GRPC_STREAM_BTC", 97_000.0));
+    publish_event(computed("GRPC_STREAM_ETH", 3_400.0));
+    publish_event(OracleEvent::Paused { scope: "rise".to_string() });
+    publish_event(published("GRPC_STREAM_ETH", 3_400.0));
+
+    let first = next_event(&mut stream).await;
+    assert_eq!((first.feed.as_str(), first.kind()), ("GRPC_STREAM_ETH", PriceEventKind::Computed));
+    assert_eq!(first.value, Some(3_400.0));
+    assert!(first.timestamp_ms > 0);
+    let second = next_event(&mut stream).await;
+    assert_eq!((second.feed.as_str(), second.kind()), ("GRPC_STREAM_ETH", PriceEventKind::Published));
+}
+
+#[tokio::test]
+async fn test_stats_per_trigger() {
+    let btc = OracleStats::shared();
+    {
+        let mut stats = btc.write();
+        stats.record_trigger();
+        stats.record_trigger();
+        stats.record_success(3, None, Some(Duration::from_millis(8)));
+        stats.record_failure();
+    }
+    let mut client = connect(server().with_stats("BTCUSD", btc).with_stats("ETHUSD", OracleStats::shared())).await;
+
+    let all = client.get_stats(GetStatsRequest::default()).await.unwrap().into_inner();
+    let names: Vec<_> = all.triggers.iter().map(|t| t.trigger.as_str()).collect();
+    assert_eq!(names, ["BTCUSD", "ETHUSD"]);
+
+    let request = GetStatsRequest { trigger: "BTCUSD".to_string() };
+    let btc = client.get_stats(request).await.unwrap().into_inner().triggers.remove(0);
+    assert_eq!((btc.total_triggers, btc.successful_updates, btc.failed_updates), (2, 1, 1));
+    assert_eq!(btc.success_rate, 50.0);
+    assert_eq!(btc.avg_latency_ms, 8.0);
+
+    let unknown = client.get_stats(GetStatsRequest { trigger: "XAUUSD".to_string() }).await.unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_need_the_admin_token() {
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+    let server = server().with_admin_token(Some(TOKEN.to_string())).with_control("rise", error_control.clone());
+    let mut client = connect(server).await;
+
+    let missing = client.pause(PauseRequest::default()).await.unwrap_err();
+    assert_eq!(missing.code(), Code::Unauthenticated);
+    let wrong = client.pause(admin(PauseRequest::default(), "guess")).await.unwrap_err();
+    assert_eq!(wrong.code(), Code::PermissionDenied);
+    assert!(!error_control.is_worker_pool_paused().await);
+
+    let paused = client.pause(admin(PauseRequest::default(), TOKEN)).await.unwrap().into_inner();
+    assert_eq!(paused.scopes.len(), 1);
+    assert_eq!((paused.scopes[0].scope.as_str(), paused.scopes[0].paused), ("rise", true));
+    assert!(error_control.is_worker_pool_paused().await);
+
+    let request = ResumeRequest { scope: "rise".to_string() };
+    let resumed = client.resume(admin(request, TOKEN)).await.unwrap().into_inner();
+    assert!(!resumed.scopes[0].paused);
+    assert!(!error_control.is_worker_pool_paused().await);
+
+    let request = PauseRequest { scope: "base".to_string() };
+    let unknown = client.pause(admin(request, TOKEN)).await.unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_admin_calls_refused_without_a_configured_token() {
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+    let mut client = connect(server().with_control("rise", error_control.clone())).await;
+
+    let refused = client.pause(admin(PauseRequest::default(), "")).await.unwrap_err();
+    assert_eq!(refused.code(), Code::PermissionDenied);
+    assert!(!error_control.is_worker_pool_paused().await);
+}
+
+#[tokio::test]
+async fn test_force_update_queues_the_feeds_in_scope() {
+    let force_updates = Arc::new(ForceUpdates::new());
+    for feed in ["rise/BTCUSD", "rise/ETHUSD", "base/BTCUSD"] {
+        force_updates.register(feed);
+    }
+    let server = server().with_admin_token(Some(TOKEN.to_string())).with_force_updates(Some(force_updates.clone()));
+    let mut client = connect(server).await;
+    let force = |feed: &str| ForceUpdateRequest { feed: feed.to_string() };
+
+    let missing = client.force_update(force("rise/BTCUSD")).await.unwrap_err();
+    assert_eq!(missing.code(), Code::Unauthenticated);
+    assert!(!force_updates.is_requested("rise/BTCUSD"));
+
+    let queued = client.force_update(admin(force("rise"), TOKEN)).await.unwrap().into_inner();
+    assert_eq!(queued.feeds, ["rise/BTCUSD", "rise/ETHUSD"]);
+    assert!(force_updates.is_requested("rise/ETHUSD"));
+    assert!(!force_updates.is_requested("base/BTCUSD"));
+
+    let unknown = client.force_update(admin(force("XAUUSD"), TOKEN)).await.unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+}
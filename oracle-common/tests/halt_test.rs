@@ -0,0 +1,102 @@
+//! Operator halts: counting held updates per trigger label, and lifting
+//! halts by scope
+
+use oracle_common::{EventSubscriber, HaltCondition, Halts, OracleEvent};
+use std::time::Duration;
+
+use HaltCondition::{QuorumLost, ReferenceDiverged};
+
+#[test]
+fn test_parse() {
+    let halts = Halts::parse("quorum_lost, reference-diverged:3", Duration::from_secs(60)).unwrap();
+    let rules = &halts.to_json()["rules"];
+    assert_eq!((rules["quorum_lost"].as_u64(), rules["reference_diverged"].as_u64()), (Some(1), Some(3)));
+    assert!(rules["update_failed"].is_null());
+    assert_eq!("update_failed".parse::<HaltCondition>().unwrap(), HaltCondition::UpdateFailed);
+
+    assert!(Halts::parse("", Duration::from_secs(60)).is_err());
+    assert!(Halts::parse("bounds_violated", Duration::from_secs(60)).is_err());
+    assert!(Halts::parse("quorum_lost:many", Duration::from_secs(60)).is_err());
+}
+
+#[test]
+fn test_repeated_conditions_halt_the_feed() {
+    let halts = Halts::parse("reference_diverged:3,quorum_lost", Duration::from_secs(60)).unwrap();
+
+    halts.held(ReferenceDiverged, "BTCUSD");
+    halts.held(ReferenceDiverged, "BTCUSD");
+    halts.held(ReferenceDiverged, "ETHUSD");
+    assert!(!halts.is_halted("BTCUSD"));
+    halts.held(ReferenceDiverged, "BTCUSD");
+    assert!(halts.is_halted("BTCUSD"));
+    assert!(halts.halted("BTCUSD").unwrap().contains("reference_diverged 3"));
+    assert!(!halts.is_halted("ETHUSD"));
+
+    // Unlisted conditions never halt
+    halts.on_event(&OracleEvent::UpdateFailed { feed: "ETHUSD".to_string(), value: None, latency: None, drift_ms: 0 });
+    assert!(!halts.is_halted("ETHUSD"));
+    halts.held(QuorumLost, "ETHUSD");
+    assert!(halts.is_halted("ETHUSD"));
+}
+
+#[test]
+fn test_occurrences_outside_the_window_are_forgotten() {
+    let halts = Halts::parse("reference_diverged:2", Duration::from_millis(20)).unwrap();
+    halts.held(ReferenceDiverged, "BTCUSD");
+    std::thread::sleep(Duration::from_millis(30));
+    halts.held(ReferenceDiverged, "BTCUSD");
+    assert!(!halts.is_halted("BTCUSD"));
+}
+
+#[test]
+fn test_resume_by_feed_target_or_everything() {
+    let halts = Halts::parse("quorum_lost", Duration::from_secs(60)).unwrap();
+    for feed in ["base/BTCUSD", "base/ETHUSD", "rise/BTCUSD"] {
+        halts.held(QuorumLost, feed);
+    }
+
+    assert_eq!(halts.resume("base/ETHUSD"), ["base/ETHUSD"]);
+    assert!(halts.resume("bas").is_empty(), "target names match whole");
+    assert_eq!(halts.resume("base"), ["base/BTCUSD"]);
+    assert!(halts.is_halted("rise/BTCUSD"));
+    assert_eq!(halts.resume(""), ["rise/BTCUSD"]);
+    assert!(halts.to_json()["halted"].as_object().unwrap().is_empty());
+
+    // Resuming starts the count afresh
+    halts.held(QuorumLost, "base/BTCUSD");
+    assert!(halts.is_halted("base/BTCUSD"));
+}
+
+#[test]
+fn test_each_target_halts_on_its_own_held_updates() {
+    let halts = Halts::parse("reference_diverged:3,quorum_lost:2", Duration::from_secs(60)).unwrap();
+
+    // Shared sources announce state changes under the bare feed id; those
+    // never match a trigger label and don't count
+    for _ in 0..5 {
+        halts.on_event(&OracleEvent::ReferenceDiverged {
+            feed: "BTCUSD".to_string(),
+            value: 101.0,
+            reference: 100.0,
+            divergence_bps: 100.0,
+        });
+        halts.on_event(&OracleEvent::QuorumLost { feed: "BTCUSD".to_string(), fresh: 1, agreeing: 1, required: 2 });
+    }
+    assert!(halts.to_json()["halted"].as_object().unwrap().is_empty());
+
+    // One sustained divergence: every update each target holds counts
+    for _ in 0..3 {
+        halts.held(ReferenceDiverged, "base/BTCUSD");
+    }
+    halts.held(ReferenceDiverged, "rise/BTCUSD");
+    assert!(halts.is_halted("base/BTCUSD"));
+    assert!(!halts.is_halted("rise/BTCUSD"));
+    halts.held(ReferenceDiverged, "rise/BTCUSD");
+    halts.held(ReferenceDiverged, "rise/BTCUSD");
+    assert!(halts.is_halted("rise/BTCUSD"));
+
+    halts.held(QuorumLost, "rise/ETHUSD");
+    halts.held(QuorumLost, "rise/ETHUSD");
+    assert!(halts.halted("rise/ETHUSD").unwrap().contains("quorum_lost 2"));
+    assert!(!halts.is_halted("base/ETHUSD"));
+}
@@ -0,0 +1,153 @@
+//! Trade and update exports: Parquet and CSV contents, empty intervals,
+//! retention and the trade cap.
+
+use arrow_array::{Array, BooleanArray, Float64Array, StringArray, UInt64Array};
+use oracle_common::{EventSubscriber, ExportFormat, HistoryExportConfig, HistoryExporter, OracleEvent};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+fn export_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("history-export-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn trade(symbol: &str, price: f64) -> OracleEvent {
+    OracleEvent::TradeReceived {
+        source: "binance".to_string(),
+        symbol: symbol.to_string(),
+        price,
+        quantity: 0.5,
+        timestamp_ms: 1_735_689_600_000,
+    }
+}
+
+fn feed_events(exporter: &HistoryExporter) {
+    exporter.on_event(&trade("BTCUSDT", 97_010.0));
+    exporter.on_event(&trade("ETHUSDT", 3_400.5));
+    exporter.on_event(&OracleEvent::ValueComputed { feed: "BTCUSD".to_string(), value: 97_012.5 });
+    exporter.on_event(&OracleEvent::completed("BTCUSD", true, None, Some(97_012.5), Some(Duration::from_millis(40)), 2));
+    exporter.on_event(&OracleEvent::completed("BTCUSD", false, None, None, None, 0));
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_parquet_export() {
+    let dir = export_dir("parquet");
+    let exporter = HistoryExporter::new(HistoryExportConfig::new(&dir)).unwrap();
+    feed_events(&exporter);
+    assert_eq!(exporter.pending(), (2, 2));
+
+    let files = exporter.export().unwrap();
+    assert_eq!(files.len(), 2);
+    assert!(file_name(&files[0]).starts_with("trades-") && file_name(&files[0]).ends_with(".parquet"));
+    assert!(file_name(&files[1]).starts_with("updates-"));
+    assert_eq!(exporter.pending(), (0, 0));
+
+    let trades = ParquetRecordBatchReaderBuilder::try_new(File::open(&files[0]).unwrap()).unwrap().build().unwrap();
+    let trades: Vec<_> = trades.map(Result::unwrap).collect();
+    assert_eq!(trades.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+    let symbols = trades[0].column_by_name("symbol").unwrap().as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!((symbols.value(0), symbols.value(1)), ("BTCUSDT", "ETHUSDT"));
+    let prices = trades[0].column_by_name("price").unwrap().as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(prices.value(1), 3_400.5);
+    let trade_times = trades[0].column_by_name("trade_time_ms").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(trade_times.value(0), 1_735_689_600_000);
+
+    let updates = ParquetRecordBatchReaderBuilder::try_new(File::open(&files[1]).unwrap()).unwrap().build().unwrap();
+    let updates = updates.map(Result::unwrap).next().unwrap();
+    assert_eq!(updates.num_rows(), 2);
+    let success = updates.column_by_name("success").unwrap().as_any().downcast_ref::<BooleanArray>().unwrap();
+    assert!(success.value(0) && !success.value(1));
+    let values = updates.column_by_name("value").unwrap().as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(values.value(0), 97_012.5);
+    assert!(values.is_null(1));
+    let latency = updates.column_by_name("latency_ms").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(latency.value(0), 40);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_csv_export() {
+    let dir = export_dir("csv");
+    let config = HistoryExportConfig { format: ExportFormat::Csv, ..HistoryExportConfig::new(&dir) };
+    let exporter = HistoryExporter::new(config).unwrap();
+    feed_events(&exporter);
+
+    let files = exporter.export().unwrap();
+    assert!(file_name(&files[0]).ends_with(".csv"));
+    let updates = std::fs::read_to_string(&files[1]).unwrap();
+    let lines: Vec<_> = updates.lines().collect();
+    assert_eq!(lines[0], "unix_ms,feed,success,value,latency_ms,drift_ms");
+    assert!(lines[1].ends_with(",BTCUSD,true,97012.5,40,2"), "{}", lines[1]);
+    assert!(lines[2].ends_with(",BTCUSD,false,,,0"), "{}", lines[2]);
+
+    let trades = std::fs::read_to_string(&files[0]).unwrap();
+    assert_eq!(trades.lines().next(), Some("unix_ms,source,symbol,price,quantity,trade_time_ms"));
+    assert_eq!(trades.lines().count(), 3);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_empty_intervals_write_nothing() {
+    let dir = export_dir("empty");
+    let exporter = HistoryExporter::new(HistoryExportConfig::new(&dir)).unwrap();
+    exporter.on_event(&OracleEvent::Paused { scope: "rise".to_string() });
+
+    assert!(exporter.export().unwrap().is_empty());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_retention_deletes_old_exports_only() {
+    let dir = export_dir("retention");
+    let config = HistoryExportConfig { retention: Some(Duration::from_secs(7 * 86_400)), ..HistoryExportConfig::new(&dir) };
+    let exporter = HistoryExporter::new(config).unwrap();
+
+    let month_ago = SystemTime::now() - Duration::from_secs(30 * 86_400);
+    for name in ["trades-20240101T000000.000Z.parquet", "updates-20240101T000000.000Z.csv", "notes.csv"] {
+        File::create(dir.join(name)).unwrap().set_modified(month_ago).unwrap();
+    }
+    File::create(dir.join("trades-20240102T000000.000Z.parquet")).unwrap();
+
+    feed_events(&exporter);
+    exporter.export().unwrap();
+
+    let mut names: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| file_name(&e.unwrap().path())).collect();
+    names.sort();
+    assert_eq!(names.len(), 4, "{:?}", names);
+    assert_eq!(names[0], "notes.csv");
+    assert_eq!(names[1], "trades-20240102T000000.000Z.parquet");
+    assert!(names[2].starts_with("trades-2") && names[3].starts_with("updates-2"));
+    assert!(!names.iter().any(|n| n.starts_with("trades-20240101") || n.starts_with("updates-20240101")));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_trades_beyond_the_cap_are_dropped() {
+    let dir = export_dir("cap");
+    let config = HistoryExportConfig { max_trades: 3, ..HistoryExportConfig::new(&dir) };
+    let exporter = HistoryExporter::new(config).unwrap();
+    for i in 0..5 {
+        exporter.on_event(&trade("BTCUSDT", 97_000.0 + i as f64));
+    }
+
+    assert_eq!(exporter.pending(), (3, 0));
+    assert_eq!(exporter.to_json()["dropped_trades"], 2);
+    // The cap applies per interval
+    exporter.export().unwrap();
+    exporter.on_event(&trade("BTCUSDT", 97_010.0));
+    assert_eq!(exporter.pending(), (1, 0));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,122 @@
+//! Key selection: balance weighting, and the pre-submit balance check that
+//! passes over keys that can't pay and skips a transaction no key can pay
+//! for before signing
+
+use alloy::primitives::{Address, Bytes, U256};
+use nonzu_sdk::management::KeySelector;
+use nonzu_sdk::traits::TxRequest;
+use oracle_common::{note_sending, BalanceAwareSelector, KeyRotation, RotationPolicy};
+use std::sync::Arc;
+
+const GWEI: u64 = 1_000_000_000;
+
+fn update(gas_limit: u64) -> TxRequest {
+    TxRequest::new(Address::repeat_byte(0xaa), Bytes::new())
+        .with_gas_limit(U256::from(gas_limit))
+        .with_metadata("feed_id", "BTCUSD")
+}
+
+fn rotation(balances: &[(Address, u64)]) -> (KeyRotation, Arc<BalanceAwareSelector>) {
+    let selector = Arc::new(BalanceAwareSelector::new(U256::ZERO));
+    for (address, balance) in balances {
+        selector.update_balance(*address, U256::from(*balance));
+    }
+    let rotation = KeyRotation::new(RotationPolicy::RoundRobin, selector.clone()).with_presubmit_check(true);
+    (rotation, selector)
+}
+
+#[test]
+fn test_keys_that_cannot_pay_are_skipped() {
+    let (poor, rich) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let (rotation, selector) = rotation(&[(poor, 50_000 * GWEI), (rich, 10_000_000 * GWEI)]);
+    selector.update_gas_price(U256::from(GWEI));
+    assert_eq!(selector.max_cost(&update(100_000)), Some(U256::from(100_000 * GWEI)));
+
+    for _ in 0..4 {
+        assert_eq!(rotation.select_for(&update(100_000), &[poor, rich]), Some(rich));
+        rotation.on_result(rich, true);
+    }
+    assert_eq!(rotation.preempted(), 0);
+}
+
+#[test]
+fn test_updates_no_key_can_pay_for_are_skipped() {
+    let key = Address::repeat_byte(1);
+    let (rotation, selector) = rotation(&[(key, 250_000 * GWEI)]);
+    selector.update_gas_price(U256::from(GWEI));
+
+    // Each selection reserves its cost until the next refresh
+    assert_eq!(rotation.select_for(&update(100_000), &[key]), Some(key));
+    rotation.on_result(key, true);
+    assert_eq!(rotation.select_for(&update(100_000), &[key]), Some(key));
+    rotation.on_result(key, true);
+    assert_eq!(rotation.select_for(&update(100_000), &[key]), None);
+    assert_eq!(rotation.preempted(), 1);
+
+    // A refresh showing the key topped up lets updates through again
+    selector.update_balance(key, U256::from(GWEI * GWEI));
+    assert_eq!(rotation.select_for(&update(100_000), &[key]), Some(key));
+}
+
+#[test]
+fn test_no_check_without_a_gas_price_or_when_disabled() {
+    let key = Address::repeat_byte(1);
+    let (rotation, selector) = rotation(&[(key, 1)]);
+    assert_eq!(rotation.select_for(&update(100_000), &[key]), Some(key));
+    rotation.on_result(key, true);
+
+    selector.update_gas_price(U256::from(GWEI));
+    let unchecked = KeyRotation::new(RotationPolicy::RoundRobin, selector);
+    assert_eq!(unchecked.select_for(&update(100_000), &[key]), Some(key));
+    assert_eq!(unchecked.preempted(), 0);
+}
+
+#[test]
+fn test_sdk_selection_prices_the_default_gas_limit() {
+    let (poor, rich) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let (rotation, selector) = rotation(&[(poor, 200_000 * GWEI), (rich, 10_000_000 * GWEI)]);
+    selector.update_gas_price(U256::from(GWEI));
+    assert_eq!(selector.default_cost(), Some(U256::from(300_000 * GWEI)));
+
+    // No request noted outside a worker task: priced at the default 300k gas
+    assert_eq!(rotation.select_key(&[poor, rich]), Some(rich));
+    // The rich key is held until its result comes back, and the poor one cannot pay
+    assert_eq!(rotation.select_key(&[poor, rich]), None);
+    rotation.on_result(rich, true);
+    assert_eq!(rotation.select_key(&[poor, rich]), Some(rich));
+}
+
+#[tokio::test]
+async fn test_sdk_selection_pins_the_admitted_request_feed() {
+    let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let selector = Arc::new(BalanceAwareSelector::new(U256::ZERO));
+    let rotation = Arc::new(KeyRotation::new(RotationPolicy::StickyPerFeed, selector));
+
+    // Each worker task notes its request when admitting it, then selects
+    let select = |feed: &'static str| {
+        let rotation = rotation.clone();
+        tokio::spawn(async move {
+            note_sending(&update(100_000).with_metadata("feed_id", feed));
+            let key = rotation.select_key(&[a, b]);
+            rotation.on_result(key.unwrap(), true);
+            key
+        })
+    };
+    let btc = select("BTCUSD").await.unwrap();
+    let eth = select("ETHUSD").await.unwrap();
+    assert_ne!(btc, eth);
+    assert_eq!(select("BTCUSD").await.unwrap(), btc);
+    assert_eq!(select("ETHUSD").await.unwrap(), eth);
+}
+
+#[test]
+fn test_unknown_balances_weigh_like_an_average_key() {
+    let (a, b, unknown) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+    let selector = BalanceAwareSelector::new(U256::ZERO);
+    selector.update_balance(a, U256::from(GWEI * GWEI));
+    selector.update_balance(b, U256::from(GWEI * GWEI));
+
+    let picks = (0..3_000).filter(|_| selector.select(&[a, b, unknown]) == Some(unknown)).count();
+    // A third of the picks, not one in 10^18
+    assert!((800..1_200).contains(&picks), "{}", picks);
+}
@@ -0,0 +1,35 @@
+//! Private keys: never printed, still usable for signing
+
+use oracle_common::{key_addresses, PrivateKey, PrivateKeys};
+
+// Anvil's first dev account
+const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+#[test]
+fn test_keys_are_redacted_in_debug_output() {
+    let keys = PrivateKeys::new(vec![PrivateKey::new(KEY)]);
+    let printed = format!("{:?} {:?}", keys[0], keys);
+    assert!(!printed.contains("ac0974bec39a17e3"), "{}", printed);
+    assert!(printed.contains("<redacted>"));
+}
+
+#[test]
+fn test_keys_derive_their_address() {
+    let keys = PrivateKeys::new(vec![PrivateKey::new(KEY)]);
+    assert_eq!(keys[0].address().unwrap(), ADDRESS.parse().unwrap());
+    assert_eq!(key_addresses(&keys).unwrap(), vec![ADDRESS.parse().unwrap()]);
+}
+
+#[test]
+fn test_invalid_keys_are_not_echoed_in_errors() {
+    let err = PrivateKey::new("0xnot-a-key-but-secret").signer().unwrap_err();
+    assert!(!err.to_string().contains("secret"), "{}", err);
+}
+
+#[test]
+fn test_keys_convert_for_the_orchestrator() {
+    let mut keys = PrivateKeys::new(vec![PrivateKey::new(KEY), PrivateKey::new(KEY)]);
+    keys.truncate(1);
+    assert_eq!(keys.into_sdk(), vec![KEY.to_string()]);
+}
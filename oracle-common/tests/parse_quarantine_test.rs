@@ -0,0 +1,47 @@
+//! Parse error quarantine: sampled logging, kept payloads and the periodic
+//! summary of rejects and unknown event types
+
+use oracle_common::ParseQuarantine;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_one_sample_is_logged_per_interval() {
+    let quarantine = ParseQuarantine::new("binance", Duration::from_secs(60));
+    let start = Instant::now();
+    assert!(quarantine.reject_at("expected value", "not json", start));
+    for i in 1..100 {
+        assert!(!quarantine.reject_at("expected value", "not json", start + Duration::from_millis(i)));
+    }
+    assert!(quarantine.reject_at("expected value", "not json", start + Duration::from_secs(60)));
+    assert_eq!(quarantine.rejected(), 101);
+}
+
+#[test]
+fn test_recent_payloads_are_kept_truncated() {
+    let quarantine = ParseQuarantine::new("binance", Duration::from_secs(60));
+    for i in 0..25 {
+        quarantine.reject(format!("bad {}", i), &"x".repeat(2000));
+    }
+    let kept = quarantine.quarantined();
+    assert_eq!(kept.len(), 20);
+    assert_eq!(kept[0].error, "bad 5");
+    assert_eq!(kept[19].payload.len(), 1024);
+    assert_eq!(quarantine.to_json()["rejected"], 25);
+}
+
+#[test]
+fn test_summary_reports_what_changed() {
+    let quarantine = ParseQuarantine::new("binance", Duration::from_secs(60));
+    assert_eq!(quarantine.summary(), None);
+
+    quarantine.reject("missing field `p`", "{}");
+    quarantine.unknown_event("aggTrade");
+    quarantine.unknown_event("aggTrade");
+    quarantine.unknown_event("depthUpdate");
+    assert_eq!(quarantine.summary().unwrap(), "1 rejected, unknown events: aggTrade=2, depthUpdate=1");
+    assert_eq!(quarantine.summary(), None);
+
+    quarantine.unknown_event("depthUpdate");
+    assert_eq!(quarantine.summary().unwrap(), "0 rejected, unknown events: depthUpdate=1");
+    assert_eq!(quarantine.unknown_events()["depthUpdate"], 2);
+}
@@ -0,0 +1,115 @@
+//! Deterministic tick/skip tests for PreciseTimer using a mock clock
+
+use oracle_common::{MockClock, PreciseTimer};
+use std::time::Duration;
+
+/// 2025-01-01 00:00:00.037 UTC - deliberately off an interval boundary
+const WALL_START_MS: u64 = 1_735_689_600_037;
+
+#[test]
+fn test_no_tick_before_first_interval() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(100, clock.clone());
+
+    assert_eq!(timer.should_tick(), None);
+    clock.advance_ms(99);
+    assert_eq!(timer.should_tick(), None);
+    assert_eq!(timer.time_until_next(), Duration::from_millis(1));
+}
+
+#[test]
+fn test_ticks_on_schedule() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(100, clock.clone());
+
+    for n in 1..=5 {
+        clock.advance_ms(100);
+        assert_eq!(timer.should_tick(), Some((n * 100, n * 100)));
+        assert_eq!(timer.should_tick(), None, "only one tick per interval");
+    }
+}
+
+#[test]
+fn test_late_tick_reports_drift_without_accumulating() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(100, clock.clone());
+
+    clock.advance_ms(130);
+    assert_eq!(timer.should_tick(), Some((100, 130)));
+
+    // The next target stays on the 100ms grid instead of 130 + 100
+    clock.advance_ms(70);
+    assert_eq!(timer.should_tick(), Some((200, 200)));
+}
+
+#[test]
+fn test_skips_missed_intervals() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(100, clock.clone());
+
+    // Stalled for several intervals: one tick, no burst of catch-up ticks
+    clock.advance_ms(450);
+    assert_eq!(timer.should_tick(), Some((100, 450)));
+    assert_eq!(timer.should_tick(), None);
+
+    clock.advance_ms(50);
+    assert_eq!(timer.should_tick(), Some((500, 500)));
+}
+
+#[test]
+fn test_is_due_does_not_consume_tick() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(100, clock.clone());
+
+    clock.advance_ms(100);
+    assert!(timer.is_due());
+    assert!(timer.is_due());
+    assert!(timer.should_tick().is_some());
+    assert!(!timer.is_due());
+}
+
+#[test]
+fn test_set_interval_keeps_scheduled_tick() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(100, clock.clone());
+
+    clock.advance_ms(100);
+    assert_eq!(timer.should_tick(), Some((100, 100)));
+
+    timer.set_interval(400);
+    assert_eq!(timer.interval_ms(), 400);
+
+    clock.advance_ms(100);
+    assert_eq!(timer.should_tick(), Some((200, 200)));
+    clock.advance_ms(300);
+    assert_eq!(timer.should_tick(), None);
+    clock.advance_ms(100);
+    assert_eq!(timer.should_tick(), Some((600, 600)));
+}
+
+#[test]
+fn test_aligned_ticks_land_on_wall_clock_boundaries() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::aligned_with_clock(100, clock.clone());
+
+    // 37ms past a boundary, so the first tick is 63ms away
+    assert_eq!(timer.time_until_next(), Duration::from_millis(63));
+    clock.advance_ms(63);
+    assert_eq!(timer.should_tick(), Some((100, 100)));
+    assert_eq!(clock_wall_phase(&clock, 100), 0);
+}
+
+#[test]
+fn test_aligned_timer_follows_wall_clock_steps() {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::aligned_with_clock(100, clock.clone());
+
+    // An NTP correction steps the wall clock forward past the boundary
+    clock.set_wall_ms(WALL_START_MS + 70);
+    assert_eq!(timer.should_tick(), Some((100, 107)));
+}
+
+fn clock_wall_phase(clock: &MockClock, interval_ms: u64) -> u64 {
+    use oracle_common::TimerClock;
+    clock.wall_ms() % interval_ms
+}
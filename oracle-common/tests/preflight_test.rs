@@ -0,0 +1,35 @@
+//! Preflight report and selector detection
+
+use oracle_common::{function_selector, has_selector, CheckResult, CheckStatus, PreflightReport};
+
+fn check(name: &str, status: CheckStatus) -> CheckResult {
+    CheckResult { name: name.to_string(), status, detail: String::new() }
+}
+
+#[test]
+fn test_selector_in_dispatcher() {
+    let selector = function_selector("updatePrice(string,uint256)");
+    // PUSH4 <selector> DUP2 EQ, as in solc's dispatcher
+    let mut code = vec![0x60, 0x80, 0x63];
+    code.extend_from_slice(&selector);
+    code.extend_from_slice(&[0x81, 0x14]);
+    assert!(has_selector(&code, selector));
+    assert!(!has_selector(&code, function_selector("updateTimestamp(uint256)")));
+
+    // The bytes alone, without PUSH4, don't count
+    assert!(!has_selector(&selector, selector));
+}
+
+#[test]
+fn test_warnings_pass_failures_dont() {
+    let mut report = PreflightReport {
+        checks: vec![check("RPC", CheckStatus::Pass), check("Clock", CheckStatus::Warn)],
+    };
+    assert!(report.passed());
+
+    report.checks.push(check("Balance", CheckStatus::Fail));
+    assert!(!report.passed());
+    assert_eq!(report.count(CheckStatus::Fail), 1);
+    assert!(report.table().contains("1 passed, 1 warnings, 1 failed"));
+    assert_eq!(report.to_json()["checks"][2]["status"], "fail");
+}
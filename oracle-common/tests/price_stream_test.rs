@@ -0,0 +1,139 @@
+//! The price stream WebSocket: event rendering and per-connection feed filters
+
+use alloy::primitives::{B256, U256};
+use futures_util::{SinkExt, StreamExt};
+use oracle_common::{EventSubscriber, OracleEvent, PriceStream};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn serve(stream: &Arc<PriceStream>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let router = stream.clone().router();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    url
+}
+
+/// Connect and wait until the stream has registered the connection
+async fn connect(stream: &PriceStream, url: &str) -> Client {
+    let before = stream.connections();
+    let (client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    for _ in 0..100 {
+        if stream.connections() > before {
+            return client;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("connection never registered");
+}
+
+async fn next_json(client: &mut Client) -> Value {
+    let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("no message within 2s")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+async fn send(client: &mut Client, request: Value) -> Value {
+    client.send(Message::Text(request.to_string())).await.unwrap();
+    next_json(client).await
+}
+
+fn computed(feed: &str, value: f64) -> OracleEvent {
+    OracleEvent::ValueComputed { feed: feed.to_string(), value }
+}
+
+#[tokio::test]
+async fn test_streams_computed_and_published_values() {
+    let stream = PriceStream::new(16);
+    let url = serve(&stream).await;
+    let mut client = connect(&stream, &url).await;
+
+    stream.on_event(&computed("BTCUSD", 97_012.5));
+    stream.on_event(&OracleEvent::UpdatePublished {
+        feed: "BTCUSD".to_string(),
+        value: Some(97_012.5),
+        tx_hash: Some(B256::repeat_byte(0xab)),
+        block_number: Some(U256::from(1234)),
+        latency: Some(Duration::from_millis(4)),
+        drift_ms: 0,
+    });
+    stream.on_event(&OracleEvent::Paused { scope: "rise".to_string() });
+    stream.on_event(&computed("BTCUSD", 97_013.0));
+
+    let first = next_json(&mut client).await;
+    assert_eq!(first["type"], "computed");
+    assert_eq!(first["feed"], "BTCUSD");
+    assert_eq!(first["value"], 97_012.5);
+    assert!(first["timestamp_ms"].as_u64().unwrap() > 0);
+
+    let published = next_json(&mut client).await;
+    assert_eq!(published["type"], "published");
+    assert_eq!(published["tx_hash"], json!(B256::repeat_byte(0xab)));
+    assert_eq!(published["block_number"], 1234);
+    assert_eq!(published["latency_ms"], 4.0);
+
+    // Other events aren't streamed
+    assert_eq!(next_json(&mut client).await["value"], 97_013.0);
+}
+
+#[tokio::test]
+async fn test_feed_filters_per_connection() {
+    let stream = PriceStream::new(16);
+    let url = serve(&stream).await;
+    let mut eth_only = connect(&stream, &format!("{}/?feeds=ETHUSD", url)).await;
+    let mut everything = connect(&stream, &url).await;
+
+    stream.on_event(&computed("BTCUSD", 97_000.0));
+    stream.on_event(&computed("ETHUSD", 3_400.0));
+    assert_eq!(next_json(&mut eth_only).await["feed"], "ETHUSD");
+    assert_eq!(next_json(&mut everything).await["feed"], "BTCUSD");
+    assert_eq!(next_json(&mut everything).await["feed"], "ETHUSD");
+
+    let ack = send(&mut eth_only, json!({ "subscribe": ["BTCUSD"] })).await;
+    assert_eq!(ack, json!({ "type": "subscribed", "feeds": ["BTCUSD", "ETHUSD"] }));
+    let ack = send(&mut eth_only, json!({ "unsubscribe": ["ETHUSD"] })).await;
+    assert_eq!(ack["feeds"], json!(["BTCUSD"]));
+
+    stream.on_event(&computed("ETHUSD", 3_401.0));
+    stream.on_event(&computed("BTCUSD", 97_001.0));
+    assert_eq!(next_json(&mut eth_only).await["value"], 97_001.0);
+}
+
+#[tokio::test]
+async fn test_bad_requests_get_an_error_and_keep_the_connection() {
+    let stream = PriceStream::new(16);
+    let url = serve(&stream).await;
+    let mut client = connect(&stream, &url).await;
+
+    let reply = send(&mut client, json!({ "subscribe": "BTCUSD" })).await;
+    assert_eq!(reply["type"], "error");
+
+    stream.on_event(&computed("BTCUSD", 97_000.0));
+    assert_eq!(next_json(&mut client).await["type"], "computed");
+}
+
+#[tokio::test]
+async fn test_disconnects_are_counted() {
+    let stream = PriceStream::new(16);
+    let url = serve(&stream).await;
+    let client = connect(&stream, &url).await;
+    assert_eq!(stream.connections(), 1);
+
+    drop(client);
+    for _ in 0..100 {
+        if stream.connections() == 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("connection still counted after the client went away");
+}
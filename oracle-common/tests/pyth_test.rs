@@ -0,0 +1,56 @@
+//! Hermes price update parsing and the Pyth feed source
+
+use oracle_common::{normalize_price_id, parse_hermes_event, PriceSource, PythFeedSource, PythPrice, PythPrices};
+use std::sync::Arc;
+
+const SOL_USD: &str = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+#[test]
+fn test_normalize_price_id() {
+    assert_eq!(normalize_price_id(&format!("0x{}", SOL_USD.to_uppercase())).unwrap(), SOL_USD);
+    assert_eq!(normalize_price_id(SOL_USD).unwrap(), SOL_USD);
+    assert!(normalize_price_id("0x1234").is_err());
+    assert!(normalize_price_id(&"zz".repeat(32)).is_err());
+}
+
+#[test]
+fn test_parse_hermes_event_scales_by_expo() {
+    let data = format!(
+        r#"{{"binary":{{"encoding":"hex","data":["504e4155"]}},"parsed":[{{"id":"{}","price":{{"price":"15025000000","conf":"7500000","expo":-8,"publish_time":1760000000}},"ema_price":{{"price":"15000000000","conf":"7000000","expo":-8,"publish_time":1760000000}}}}]}}"#,
+        SOL_USD
+    );
+    let updates = parse_hermes_event(&data).unwrap();
+    assert_eq!(updates.len(), 1);
+    let (id, price) = &updates[0];
+    assert_eq!(id, SOL_USD);
+    assert!((price.price - 150.25).abs() < 1e-9);
+    assert!((price.conf - 0.075).abs() < 1e-9);
+    assert_eq!(price.publish_time_ms, 1_760_000_000_000);
+}
+
+#[test]
+fn test_parse_hermes_event_rejects_garbage() {
+    assert!(parse_hermes_event("not json").is_err());
+    assert!(parse_hermes_event(&format!(
+        r#"{{"parsed":[{{"id":"{}","price":{{"price":"abc","conf":"1","expo":-8,"publish_time":1}}}}]}}"#,
+        SOL_USD
+    ))
+    .is_err());
+}
+
+#[test]
+fn test_feed_source_reports_confidence() {
+    let prices = Arc::new(PythPrices::default());
+    let source = PythFeedSource::new(SOL_USD.to_string(), prices.clone());
+    assert!(source.latest().is_none());
+
+    prices.set(SOL_USD, PythPrice { price: 150.25, conf: 0.075, publish_time_ms: 1_760_000_000_000 });
+    let point = source.latest().unwrap();
+    assert_eq!(point.price, 150.25);
+    assert_eq!(point.confidence, Some(0.075));
+    assert_eq!(point.timestamp_ms, 1_760_000_000_000);
+
+    // A non-positive aggregate is not published
+    prices.set(SOL_USD, PythPrice { price: 0.0, conf: 1.0, publish_time_ms: 1_760_000_001_000 });
+    assert!(source.latest().is_none());
+}
@@ -0,0 +1,41 @@
+//! Per-endpoint RPC token buckets
+
+use oracle_common::{MockClock, RateLimit, RateLimits, RpcClass, TokenBucket};
+use std::time::Duration;
+
+#[test]
+fn test_burst_then_refill() {
+    let clock = MockClock::new(0);
+    let bucket = TokenBucket::new("rpc", RateLimit::new(10.0).with_burst(4.0)).with_clock(clock.clone());
+    for _ in 0..4 {
+        assert!(bucket.try_acquire(RpcClass::Submission).is_ok());
+    }
+    let wait = bucket.try_acquire(RpcClass::Submission).unwrap_err();
+    assert!(wait.abs_diff(Duration::from_millis(100)) < Duration::from_micros(1));
+
+    clock.advance(Duration::from_millis(100));
+    assert!(bucket.try_acquire(RpcClass::Submission).is_ok());
+    assert!(bucket.try_acquire(RpcClass::Submission).is_err());
+}
+
+#[test]
+fn test_background_leaves_reserve_for_submission() {
+    let clock = MockClock::new(0);
+    let bucket = TokenBucket::new("rpc", RateLimit::new(10.0).with_burst(8.0)).with_clock(clock);
+    let background = (0..10).filter(|_| bucket.try_acquire(RpcClass::Background).is_ok()).count();
+    assert_eq!(background, 6, "a quarter of the burst is kept back");
+
+    assert!(bucket.try_acquire(RpcClass::Submission).is_ok());
+    assert!(bucket.try_acquire(RpcClass::Submission).is_ok());
+    assert!(bucket.try_acquire(RpcClass::Submission).is_err());
+}
+
+#[test]
+fn test_per_endpoint_overrides() {
+    let limits = RateLimits::new(Some(RateLimit::new(20.0))).with_override("riselabs", RateLimit::new(5.0));
+    assert_eq!(limits.for_url("https://testnet.riselabs.xyz"), Some(RateLimit::new(5.0)));
+    assert_eq!(limits.for_url("http://localhost:8545"), Some(RateLimit::new(20.0)));
+
+    let unlimited = RateLimits::new(None);
+    assert_eq!(unlimited.for_url("http://localhost:8545"), None);
+}
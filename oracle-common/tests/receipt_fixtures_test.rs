@@ -0,0 +1,156 @@
+//! Golden `eth_sendRawTransactionSync` responses in `tests/fixtures/receipts`
+//! - clean, reverted, never-landed, half-filled and oddly encoded receipts,
+//! plus results that can't be receipts at all - through receipt parsing and
+//! the validation checks.
+
+use alloy::primitives::{B256, U256};
+use axum::{routing::post, Json, Router};
+use nonzu_sdk::submission::TxSubmitter;
+use oracle_common::{parse_receipt, quantity, receipt_issues, RpcEndpoints, SyncSubmitter};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const TX_HASH: &str = "0x5b2a0c8f0d4e3a9b1f6c7d2e8a4b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b";
+
+/// How a fixture should come out
+enum Expected {
+    /// Parses, with this status, block number and gas used, and these issues
+    Receipt { success: bool, block: u64, gas_used: u64, issues: &'static [&'static str] },
+    /// Fails to parse with an error containing this
+    Invalid(&'static str),
+}
+
+fn fixtures() -> Vec<(&'static str, &'static str, Expected)> {
+    use Expected::*;
+    vec![
+        (
+            "success",
+            include_str!("fixtures/receipts/success.json"),
+            Receipt { success: true, block: 0x1a2b3c, gas_used: 0x7604, issues: &[] },
+        ),
+        (
+            "revert",
+            include_str!("fixtures/receipts/revert.json"),
+            Receipt { success: false, block: 0x1a2b3c, gas_used: 0x5b8d, issues: &[] },
+        ),
+        (
+            "zero_block",
+            include_str!("fixtures/receipts/zero_block.json"),
+            Receipt {
+                success: true,
+                block: 0,
+                gas_used: 0,
+                issues: &["block number is 0", "block hash is 0", "gas used is 0"],
+            },
+        ),
+        (
+            "partial_fields",
+            include_str!("fixtures/receipts/partial_fields.json"),
+            Receipt { success: true, block: 0x1a2b3c, gas_used: 0x7604, issues: &["block hash is 0"] },
+        ),
+        (
+            "quirky_numbers",
+            include_str!("fixtures/receipts/quirky_numbers.json"),
+            Receipt { success: true, block: 0x1a2b3c, gas_used: 30212, issues: &[] },
+        ),
+        ("null_result", include_str!("fixtures/receipts/null_result.json"), Invalid("expected a receipt object")),
+        ("missing_hash", include_str!("fixtures/receipts/missing_hash.json"), Invalid("transactionHash")),
+        ("bad_quantity", include_str!("fixtures/receipts/bad_quantity.json"), Invalid("gasUsed is not a quantity")),
+    ]
+}
+
+fn result(raw: &str) -> Value {
+    let response: Value = serde_json::from_str(raw).expect("fixture is JSON");
+    response["result"].clone()
+}
+
+fn tx_hash() -> B256 {
+    TX_HASH.parse().unwrap()
+}
+
+#[test]
+fn test_fixtures_parse_and_validate() {
+    for (name, raw, expected) in fixtures() {
+        let parsed = parse_receipt(result(raw));
+        match expected {
+            Expected::Receipt { success, block, gas_used, issues } => {
+                let receipt = parsed.unwrap_or_else(|e| panic!("{}: {}", name, e));
+                assert_eq!(receipt.transaction_hash, tx_hash(), "{}", name);
+                assert_eq!(receipt.is_success(), success, "{}", name);
+                assert_eq!(receipt.block_number, U256::from(block), "{}", name);
+                assert_eq!(receipt.gas_used, U256::from(gas_used), "{}", name);
+                assert_eq!(receipt_issues(&receipt, Some(tx_hash())), issues, "{}", name);
+            }
+            Expected::Invalid(error) => {
+                let e = parsed.err().unwrap_or_else(|| panic!("{} parsed", name));
+                assert!(e.contains(error), "{}: {}", name, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_receipt_for_another_transaction_is_flagged() {
+    let receipt = parse_receipt(result(include_str!("fixtures/receipts/success.json"))).unwrap();
+    let issues = receipt_issues(&receipt, Some(B256::repeat_byte(0x11)));
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].starts_with("receipt is for"), "{}", issues[0]);
+}
+
+#[test]
+fn test_quantity_encodings() {
+    let cases = [
+        (json!("0x1a"), Some(26)),
+        (json!("0X1A"), Some(26)),
+        (json!("0x001a"), Some(26)),
+        (json!("0x"), Some(0)),
+        (json!("26"), Some(26)),
+        (json!(26), Some(26)),
+        (json!(true), Some(1)),
+        (json!(false), Some(0)),
+        (json!("0xzz"), None),
+        (json!(-1), None),
+        (json!(null), None),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(quantity(&value), expected.map(U256::from), "{}", value);
+    }
+}
+
+/// Node answering every call with `response` verbatim
+async fn node(response: Value) -> String {
+    let app = Router::new().route(
+        "/",
+        post(move |Json(call): Json<Value>| {
+            let mut response = response.clone();
+            async move {
+                response["id"] = call["id"].clone();
+                Json(response)
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    url
+}
+
+#[tokio::test]
+async fn test_sync_submitter_handles_every_fixture() {
+    for (name, raw, expected) in fixtures() {
+        let url = node(serde_json::from_str(raw).unwrap()).await;
+        let submitter = SyncSubmitter::new(Arc::new(RpcEndpoints::new(vec![url])));
+        let submitted = submitter.submit(vec![0x02, 0xf8].into()).await;
+
+        match expected {
+            Expected::Receipt { block, .. } => {
+                let receipt = submitted.unwrap_or_else(|e| panic!("{}: {:?}", name, e));
+                assert_eq!(receipt.block_number, U256::from(block), "{}", name);
+            }
+            Expected::Invalid(_) => {
+                let e = submitted.err().unwrap_or_else(|| panic!("{} accepted", name));
+                assert!(e.to_string().contains("Invalid sync receipt"), "{}: {}", name, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! The Redis sink against a minimal RESP server: channels, latest-value keys
+//! and their expiry, and dropping events while Redis is unreachable.
+
+use alloy::primitives::{B256, U256};
+use oracle_common::{EventSubscriber, OracleEvent, RedisSink, RedisSinkConfig};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+type Commands = Arc<Mutex<Vec<Vec<String>>>>;
+
+/// Redis stand-in recording every command and answering `+OK`
+async fn fake_redis() -> (String, Commands) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    let commands = Commands::default();
+    let recorded = commands.clone();
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            tokio::spawn(serve(socket, recorded.clone()));
+        }
+    });
+    (url, commands)
+}
+
+async fn serve(socket: TcpStream, commands: Commands) {
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+    while let Some(command) = read_command(&mut reader).await {
+        commands.lock().unwrap().push(command);
+        if writer.write_all(b"+OK\r\n").await.is_err() {
+            return;
+        }
+    }
+}
+
+/// One `*<n>` array of `$<len>` bulk strings
+async fn read_command(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>) -> Option<Vec<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await.ok()?;
+        arg.truncate(len);
+        args.push(String::from_utf8(arg).ok()?);
+    }
+    Some(args)
+}
+
+/// Recorded `PUBLISH` and `SET` commands, once `count` have arrived
+async fn writes(commands: &Commands, count: usize) -> Vec<Vec<String>> {
+    for _ in 0..200 {
+        let writes: Vec<_> =
+            commands.lock().unwrap().iter().filter(|c| c[0] == "PUBLISH" || c[0] == "SET").cloned().collect();
+        if writes.len() >= count {
+            return writes;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected {} writes, got {:?}", count, commands.lock().unwrap());
+}
+
+fn computed(feed: &str, value: f64) -> OracleEvent {
+    OracleEvent::ValueComputed { feed: feed.to_string(), value }
+}
+
+#[tokio::test]
+async fn test_publishes_and_caches_prices() {
+    let (url, commands) = fake_redis().await;
+    let (sink, _handle) = RedisSink::spawn(RedisSinkConfig::new(url)).unwrap();
+
+    sink.on_event(&OracleEvent::Paused { scope: "rise".to_string() });
+    sink.on_event(&computed("BTCUSD", 97_012.5));
+    sink.on_event(&OracleEvent::UpdatePublished {
+        feed: "BTCUSD".to_string(),
+        value: Some(97_012.5),
+        tx_hash: Some(B256::repeat_byte(0xab)),
+        block_number: Some(U256::from(1234)),
+        latency: Some(Duration::from_millis(4)),
+        drift_ms: 0,
+    });
+
+    let writes = writes(&commands, 4).await;
+    assert_eq!(writes.len(), 4, "other events aren't published: {:?}", writes);
+    assert_eq!(writes[0][..2], ["PUBLISH", "nonzu:computed:BTCUSD"]);
+    let message: Value = serde_json::from_str(&writes[0][2]).unwrap();
+    assert_eq!((message["type"].as_str(), message["value"].as_f64()), (Some("computed"), Some(97_012.5)));
+    assert_eq!(writes[1][..2], ["SET", "nonzu:latest:computed:BTCUSD"]);
+    assert_eq!(writes[1][2], writes[0][2]);
+    assert_eq!(writes[1][3..], ["PX", "300000"]);
+
+    assert_eq!(writes[2][..2], ["PUBLISH", "nonzu:published:BTCUSD"]);
+    let message: Value = serde_json::from_str(&writes[2][2]).unwrap();
+    assert_eq!(message["block_number"], 1234);
+    assert_eq!(writes[3][1], "nonzu:latest:published:BTCUSD");
+
+    for _ in 0..100 {
+        if sink.published() == 2 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("published count is {}", sink.published());
+}
+
+#[tokio::test]
+async fn test_prefix_and_keys_without_expiry() {
+    let (url, commands) = fake_redis().await;
+    let config = RedisSinkConfig { prefix: "oracle-eu".to_string(), ttl: None, ..RedisSinkConfig::new(url) };
+    let (sink, _handle) = RedisSink::spawn(config).unwrap();
+
+    sink.on_event(&computed("EURUSD", 1.0842));
+    let writes = writes(&commands, 2).await;
+    assert_eq!(writes[0][1], "oracle-eu:computed:EURUSD");
+    assert_eq!(writes[1].len(), 3, "SET without PX: {:?}", writes[1]);
+    assert_eq!(writes[1][1], "oracle-eu:latest:computed:EURUSD");
+}
+
+#[tokio::test]
+async fn test_drops_events_beyond_the_queue_while_unreachable() {
+    // A port nobody listens on
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let config = RedisSinkConfig { queue: 2, ..RedisSinkConfig::new(url) };
+    let (sink, _handle) = RedisSink::spawn(config).unwrap();
+    for i in 0..5 {
+        sink.on_event(&computed("BTCUSD", 97_000.0 + i as f64));
+    }
+    assert_eq!(sink.dropped(), 3);
+    assert_eq!(sink.published(), 0);
+}
@@ -0,0 +1,117 @@
+//! Reference cross-checks: holding divergent updates, escalation events,
+//! failing open without a reference, and the REST reference.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use oracle_common::{
+    EventBus, EventSubscriber, FeedConfig, OracleEvent, ReferenceCheck, ReferenceOracle, RestReference,
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+struct UnusedReference;
+
+#[async_trait]
+impl ReferenceOracle for UnusedReference {
+    fn name(&self) -> String {
+        "test".to_string()
+    }
+
+    async fn latest_price(&self) -> Result<f64> {
+        bail!("read through record_read")
+    }
+}
+
+/// Reference events for one feed, so parallel tests don't see each other's
+#[derive(Default)]
+struct ReferenceEvents {
+    feed: String,
+    kinds: Mutex<Vec<&'static str>>,
+}
+
+impl EventSubscriber for ReferenceEvents {
+    fn on_event(&self, event: &OracleEvent) {
+        match event {
+            OracleEvent::ReferenceDiverged { feed, .. } | OracleEvent::ReferenceRestored { feed } if *feed == self.feed => {
+                self.kinds.lock().push(event.kind());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check(feed_id: &str, max_divergence_bps: u64) -> ReferenceCheck {
+    let feed: FeedConfig = toml::from_str(&format!(
+        r#"
+        id = "{}"
+        source = "binance"
+        symbol = "BTCUSDT"
+        contract = "0x5a569ad19272afa97103fd4dbadf33b2fcbaa175"
+
+        [reference]
+        kind = "chainlink"
+        address = "0xF4030086522a5bEEa4988F8cA5B36dbC97BeE88c"
+        max_divergence_bps = {}
+        "#,
+        feed_id, max_divergence_bps
+    ))
+    .unwrap();
+    ReferenceCheck::new(feed.id.clone(), Arc::new(UnusedReference), feed.reference.as_ref().unwrap())
+}
+
+#[test]
+fn test_fails_open_without_a_reference() {
+    let check = check("BTCUSD-open", 100);
+    assert!(check.allows(97_000.0));
+
+    check.record_read(None);
+    assert!(check.allows(50_000.0));
+    assert_eq!(check.to_json()["unchecked"], 2);
+    assert_eq!(check.to_json()["read_failures"], 1);
+}
+
+#[test]
+fn test_divergent_updates_are_held_and_escalated() {
+    let events = Arc::new(ReferenceEvents { feed: "BTCUSD-held".to_string(), ..Default::default() });
+    EventBus::global().subscribe(events.clone());
+    let check = check("BTCUSD-held", 100);
+    check.record_read(Some(97_000.0));
+
+    assert!(check.allows(97_500.0));
+    // 2% below the reference: held, escalated once
+    assert!(!check.allows(95_060.0));
+    assert!(!check.allows(95_000.0));
+    assert_eq!(*events.kinds.lock(), ["reference_diverged"]);
+
+    assert!(check.allows(96_950.0));
+    assert_eq!(*events.kinds.lock(), ["reference_diverged", "reference_restored"]);
+
+    let status = check.to_json();
+    assert_eq!((status["checked"].as_u64(), status["held"].as_u64()), (Some(4), Some(2)));
+    assert!(status["divergence_bps"]["max"].as_f64().unwrap() > 200.0);
+    assert!((status["last_divergence_bps"].as_f64().unwrap() + 5.15).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn test_rest_reference_reads_the_json_path() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v2/prices/BTC-USD/spot", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await;
+        let body = r#"{"data":{"amount":"97012.55","currency":"USD"}}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+
+    let reference = RestReference::new(url.clone(), "$.data.amount", 0).unwrap();
+    assert_eq!(reference.name(), format!("rest:{}", url));
+    assert_eq!(reference.latest_price().await.unwrap(), 97_012.55);
+}
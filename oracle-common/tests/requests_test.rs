@@ -0,0 +1,74 @@
+//! Matching request event logs to feeds
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+use oracle_common::RequestWatcher;
+use std::time::Duration;
+
+sol! {
+    event RequestPrice(string feedId);
+    event RequestPriceIndexed(string indexed feedId);
+    event RequestPriceFrom(string feedId, uint256 maxAge);
+}
+
+fn watcher(event: &str) -> RequestWatcher {
+    let watcher = RequestWatcher::new(Address::ZERO, event, Duration::from_secs(1));
+    watcher.watch("BTCUSD");
+    watcher.watch("ETHUSD");
+    watcher
+}
+
+fn topic(event: &str) -> B256 {
+    keccak256(event.as_bytes())
+}
+
+#[test]
+fn test_matches_non_indexed_feed_id() {
+    let watcher = watcher("RequestPrice(string)");
+    let data = RequestPrice { feedId: "ETHUSD".to_string() }.encode_data();
+    assert_eq!(watcher.match_log(&[RequestPrice::SIGNATURE_HASH], &data), Some("ETHUSD".to_string()));
+
+    let data = RequestPrice { feedId: "SOLUSD".to_string() }.encode_data();
+    assert_eq!(watcher.match_log(&[RequestPrice::SIGNATURE_HASH], &data), None, "not a watched feed");
+}
+
+#[test]
+fn test_matches_indexed_feed_id() {
+    let watcher = watcher("RequestPriceIndexed(string)");
+    let topics = [topic("RequestPriceIndexed(string)"), keccak256("BTCUSD")];
+    assert_eq!(watcher.match_log(&topics, &[]), Some("BTCUSD".to_string()));
+}
+
+#[test]
+fn test_feed_id_followed_by_more_params() {
+    let watcher = watcher("RequestPriceFrom(string,uint256)");
+    let data = RequestPriceFrom { feedId: "BTCUSD".to_string(), maxAge: U256::from(30) }.encode_data();
+    assert_eq!(watcher.match_log(&[topic("RequestPriceFrom(string,uint256)")], &data), Some("BTCUSD".to_string()));
+}
+
+#[test]
+fn test_ignores_other_events_and_garbage() {
+    let watcher = watcher("RequestPrice(string)");
+    let data = RequestPrice { feedId: "BTCUSD".to_string() }.encode_data();
+    assert_eq!(watcher.match_log(&[topic("Other(string)")], &data), None);
+    assert_eq!(watcher.match_log(&[], &data), None);
+    assert_eq!(watcher.match_log(&[RequestPrice::SIGNATURE_HASH], &[0xff; 40]), None);
+}
+
+#[test]
+fn test_pending_take_and_restore() {
+    let watcher = watcher("RequestPrice(string)");
+    let data = RequestPrice { feedId: "BTCUSD".to_string() }.encode_data();
+    watcher.record_log(&[RequestPrice::SIGNATURE_HASH], &data);
+    watcher.record_log(&[RequestPrice::SIGNATURE_HASH], &data);
+    assert_eq!(watcher.pending("BTCUSD"), 2);
+    assert_eq!(watcher.pending("ETHUSD"), 0);
+
+    assert_eq!(watcher.take("BTCUSD"), 2);
+    assert_eq!(watcher.pending("BTCUSD"), 0);
+
+    // The answering update failed
+    watcher.restore("BTCUSD", 2);
+    assert_eq!(watcher.pending("BTCUSD"), 2);
+}
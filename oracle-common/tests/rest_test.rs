@@ -0,0 +1,41 @@
+//! JSON path extraction for the REST poller source
+
+use oracle_common::{scale_down, JsonPath};
+use serde_json::json;
+
+#[test]
+fn test_extracts_nested_keys_and_indices() {
+    let body = json!({ "data": { "rates": [{ "value": 1.5 }, { "value": "2.25" }] } });
+    assert_eq!(JsonPath::parse("$.data.rates[0].value").unwrap().extract(&body).unwrap(), 1.5);
+    assert_eq!(JsonPath::parse("$.data.rates[1].value").unwrap().extract(&body).unwrap(), 2.25);
+    assert_eq!(JsonPath::parse("data.rates[0]['value']").unwrap().extract(&body).unwrap(), 1.5);
+}
+
+#[test]
+fn test_quoted_keys() {
+    let body = json!({ "pax-gold": { "usd": 2650.4 } });
+    assert_eq!(JsonPath::parse("$['pax-gold'].usd").unwrap().extract(&body).unwrap(), 2650.4);
+    assert_eq!(JsonPath::parse("$[\"pax-gold\"][\"usd\"]").unwrap().extract(&body).unwrap(), 2650.4);
+}
+
+#[test]
+fn test_missing_or_non_numeric_values() {
+    let body = json!({ "price": "n/a", "list": [] });
+    assert!(JsonPath::parse("$.price").unwrap().extract(&body).is_err());
+    assert!(JsonPath::parse("$.list[0]").unwrap().extract(&body).is_err());
+    assert!(JsonPath::parse("$.missing").unwrap().extract(&body).is_err());
+}
+
+#[test]
+fn test_invalid_paths() {
+    assert!(JsonPath::parse("$").is_err());
+    assert!(JsonPath::parse("$.a[0").is_err());
+    assert!(JsonPath::parse("$.a[x]").is_err());
+    assert!(JsonPath::parse("$..a").is_err());
+}
+
+#[test]
+fn test_scale_down() {
+    assert_eq!(scale_down(123_450_000.0, 8), 1.2345);
+    assert_eq!(scale_down(42.0, 0), 42.0);
+}
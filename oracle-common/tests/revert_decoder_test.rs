@@ -0,0 +1,70 @@
+//! Revert data decoding
+
+use alloy::primitives::{hex, Address, U256};
+use alloy::sol;
+use alloy::sol_types::{Panic, Revert, SolError};
+use nonzu_sdk::RiseError;
+use oracle_common::{RevertDecoder, RevertReason};
+
+sol! {
+    error RoundOutOfOrder(uint256 provided, uint256 current);
+    error UnauthorizedUpdater(address caller);
+    error StalePrice(bytes32 feedId, uint64 age);
+}
+
+#[test]
+fn test_decode_message_and_panic() {
+    let decoder = RevertDecoder::default();
+
+    let data = Revert { reason: "TimeOracle: zero address".to_string() }.abi_encode();
+    assert_eq!(decoder.decode(&data), RevertReason::Message("TimeOracle: zero address".to_string()));
+
+    let data = Panic { code: U256::from(0x11) }.abi_encode();
+    assert_eq!(decoder.decode(&data).to_string(), "Panic(0x11): arithmetic overflow or underflow");
+}
+
+#[test]
+fn test_decode_oracle_custom_errors() {
+    let decoder = RevertDecoder::default();
+
+    let data = RoundOutOfOrder { provided: U256::from(7), current: U256::from(9) }.abi_encode();
+    assert_eq!(decoder.decode(&data).to_string(), "RoundOutOfOrder(provided=7, current=9)");
+
+    let caller = Address::repeat_byte(0xab);
+    let data = UnauthorizedUpdater { caller }.abi_encode();
+    assert_eq!(decoder.decode(&data).to_string(), format!("UnauthorizedUpdater(caller={})", caller.to_checksum(None)));
+}
+
+#[test]
+fn test_decode_from_supplied_abi() {
+    let data = StalePrice { feedId: [1u8; 32].into(), age: 42 }.abi_encode();
+    assert!(matches!(RevertDecoder::default().decode(&data), RevertReason::Unknown(_)));
+
+    let artifact = r#"{"abi": [{"type": "error", "name": "StalePrice", "inputs": [
+        {"name": "feedId", "type": "bytes32", "internalType": "bytes32"},
+        {"name": "age", "type": "uint64", "internalType": "uint64"}
+    ]}]}"#;
+    let decoder = RevertDecoder::default().with_abi_json(artifact).unwrap();
+    assert_eq!(
+        decoder.decode(&data).to_string(),
+        format!("StalePrice(feedId=0x{}, age=42)", "01".repeat(32))
+    );
+}
+
+#[test]
+fn test_describe_errors() {
+    let decoder = RevertDecoder::default();
+
+    let data = RoundOutOfOrder { provided: U256::from(1), current: U256::from(2) }.abi_encode();
+    let reverted = RiseError::ContractReverted { reason: "execution reverted".to_string(), data: Some(data.clone().into()) };
+    assert_eq!(decoder.describe(&reverted).as_deref(), Some("RoundOutOfOrder(provided=1, current=2)"));
+
+    let no_data = RiseError::ContractReverted { reason: "stale".to_string(), data: None };
+    assert_eq!(decoder.describe(&no_data).as_deref(), Some("stale"));
+
+    // Revert data embedded in a node error message
+    let rpc = RiseError::Rpc(format!("execution reverted, data: \"0x{}\"", hex::encode(&data)));
+    assert_eq!(decoder.describe(&rpc).as_deref(), Some("RoundOutOfOrder(provided=1, current=2)"));
+
+    assert_eq!(decoder.describe(&RiseError::Rpc("request timed out".to_string())), None);
+}
@@ -0,0 +1,113 @@
+//! Round ids: counting per feed, reusing failed rounds, and continuing
+//! across clean restarts and crashes
+
+use oracle_common::RoundStore;
+
+fn state_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("rounds-{}-{}.json", name, std::process::id()))
+}
+
+/// Take the next round of `feed` and issue it
+fn publish(rounds: &RoundStore, feed: &str) -> u64 {
+    let round = rounds.next_round(feed).unwrap();
+    rounds.issue(feed, round);
+    round
+}
+
+#[test]
+fn test_rounds_count_per_feed() {
+    let rounds = RoundStore::in_memory();
+    assert_eq!(rounds.last_round("BTCUSD"), None);
+    assert_eq!((publish(&rounds, "BTCUSD"), publish(&rounds, "BTCUSD")), (1, 2));
+    assert_eq!(publish(&rounds, "base/BTCUSD"), 1);
+    assert_eq!(rounds.last_round("BTCUSD"), Some(2));
+    // Nowhere to write
+    rounds.flush().unwrap();
+}
+
+#[test]
+fn test_unissued_and_failed_rounds_are_reused() {
+    let rounds = RoundStore::in_memory();
+    assert_eq!(publish(&rounds, "BTCUSD"), 1);
+
+    // Not admitted: nothing issued, the next update takes the same round
+    assert_eq!(rounds.next_round("BTCUSD").unwrap(), 2);
+    assert_eq!(rounds.next_round("BTCUSD").unwrap(), 2);
+
+    // Failed on chain: handed to the next update
+    assert_eq!(publish(&rounds, "BTCUSD"), 2);
+    rounds.release("BTCUSD", 2);
+    assert_eq!(rounds.last_round("BTCUSD"), Some(1));
+    assert_eq!(publish(&rounds, "BTCUSD"), 2);
+
+    // A later round already went out: the failed one can't be reused
+    assert_eq!(publish(&rounds, "BTCUSD"), 3);
+    rounds.release("BTCUSD", 2);
+    assert_eq!(rounds.next_round("BTCUSD").unwrap(), 4);
+}
+
+#[test]
+fn test_rounds_continue_after_a_clean_restart() {
+    let path = state_path("restart");
+    let _ = std::fs::remove_file(&path);
+
+    let rounds = RoundStore::open(&path).unwrap().with_block(10);
+    for _ in 0..5 {
+        publish(&rounds, "ETHUSD");
+    }
+    publish(&rounds, "BTCUSD");
+    rounds.flush().unwrap();
+
+    let reopened = RoundStore::open(&path).unwrap();
+    assert_eq!(reopened.last_round("ETHUSD"), Some(5));
+    assert_eq!(publish(&reopened, "ETHUSD"), 6);
+    assert_eq!(publish(&reopened, "BTCUSD"), 2);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_a_crash_never_reuses_a_round() {
+    let path = state_path("crash");
+    let _ = std::fs::remove_file(&path);
+
+    let rounds = RoundStore::open(&path).unwrap().with_block(10);
+    // The first block is on disk before round 1 is used
+    assert_eq!(rounds.next_round("ETHUSD").unwrap(), 1);
+    assert!(path.exists());
+    for _ in 0..12 {
+        publish(&rounds, "ETHUSD");
+    }
+    // Crashed without a flush: continue past the reserved block
+    drop(rounds);
+    let reopened = RoundStore::open(&path).unwrap();
+    assert_eq!(reopened.last_round("ETHUSD"), Some(20));
+    assert_eq!(publish(&reopened, "ETHUSD"), 21);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_flush_only_writes_new_rounds() {
+    let path = state_path("dirty");
+    let _ = std::fs::remove_file(&path);
+    let rounds = RoundStore::open(&path).unwrap();
+    publish(&rounds, "ETHUSD");
+    rounds.flush().unwrap();
+
+    // A clean store leaves the file alone
+    std::fs::write(&path, "{\"ETHUSD\": 9}").unwrap();
+    rounds.flush().unwrap();
+    assert_eq!(RoundStore::open(&path).unwrap().last_round("ETHUSD"), Some(9));
+
+    publish(&rounds, "ETHUSD");
+    rounds.flush().unwrap();
+    assert_eq!(RoundStore::open(&path).unwrap().last_round("ETHUSD"), Some(2));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_corrupt_state_is_an_error() {
+    let path = state_path("corrupt");
+    std::fs::write(&path, "not json").unwrap();
+    assert!(RoundStore::open(&path).is_err());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,80 @@
+//! JSON-RPC batching of auxiliary calls against a local mock node
+
+use axum::{routing::post, Json, Router};
+use oracle_common::RpcBatcher;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Mock node echoing each call's first param; counts HTTP requests
+async fn mock_node(accept_batches: bool) -> (String, Arc<AtomicU64>) {
+    let requests = Arc::new(AtomicU64::new(0));
+    let counter = requests.clone();
+    let app = Router::new().route(
+        "/",
+        post(move |Json(body): Json<Value>| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                let answer = |call: &Value| json!({ "jsonrpc": "2.0", "id": call["id"], "result": call["params"][0] });
+                Json(match body.as_array() {
+                    Some(_) if !accept_batches => {
+                        json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32600, "message": "batch not supported" } })
+                    }
+                    Some(calls) => Value::Array(calls.iter().map(answer).collect()),
+                    None => answer(&body),
+                })
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (url, requests)
+}
+
+async fn call_many(batcher: &RpcBatcher, n: u64) -> Vec<Value> {
+    futures_util::future::join_all((0..n).map(|i| async move { batcher.call("echo", json!([i])).await.unwrap() })).await
+}
+
+#[tokio::test]
+async fn test_concurrent_calls_share_one_batch() {
+    let (url, requests) = mock_node(true).await;
+    let batcher = RpcBatcher::spawn(vec![url], Duration::from_millis(50), 20);
+
+    let results = call_many(&batcher, 5).await;
+    assert_eq!(results, (0..5).map(|i| json!(i)).collect::<Vec<_>>(), "results in call order");
+    assert_eq!(requests.load(Ordering::Relaxed), 1);
+    assert_eq!(batcher.to_json()["batches"], 1);
+}
+
+#[tokio::test]
+async fn test_max_batch_splits_calls() {
+    let (url, requests) = mock_node(true).await;
+    let batcher = RpcBatcher::spawn(vec![url], Duration::from_millis(50), 2);
+
+    call_many(&batcher, 4).await;
+    assert_eq!(requests.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn test_falls_back_when_batches_are_rejected() {
+    let (url, requests) = mock_node(false).await;
+    let batcher = RpcBatcher::spawn(vec![url], Duration::from_millis(50), 20);
+
+    let results = call_many(&batcher, 3).await;
+    assert_eq!(results, vec![json!(0), json!(1), json!(2)]);
+    // One rejected batch, then each call on its own
+    assert_eq!(requests.load(Ordering::Relaxed), 4);
+    assert_eq!(batcher.to_json()["batches_supported"], false);
+}
+
+#[tokio::test]
+async fn test_zero_interval_disables_batching() {
+    let (url, requests) = mock_node(true).await;
+    let batcher = RpcBatcher::spawn(vec![url], Duration::ZERO, 20);
+
+    call_many(&batcher, 3).await;
+    assert_eq!(requests.load(Ordering::Relaxed), 3);
+}
@@ -0,0 +1,11 @@
+//! `TOKIO_PIN_CORES` core lists
+
+use oracle_common::parse_core_list;
+
+#[test]
+fn test_core_lists_are_sorted_without_repeats() {
+    assert_eq!(parse_core_list("0,2-3").unwrap(), [0, 2, 3]);
+    assert_eq!(parse_core_list("3, 1-2, 0, 2").unwrap(), [0, 1, 2, 3]);
+    assert!(parse_core_list("3-1").is_err());
+    assert!(parse_core_list(" , ").is_err());
+}
@@ -0,0 +1,101 @@
+//! Smoothing: EWMA half-lives, Kalman gains, and validation of
+//! `[feeds.smoothing]` and `SMOOTHING`.
+
+use oracle_common::{FeedSmoothing, FeedsFile, Smoother};
+
+fn smoother(config: &str) -> Smoother {
+    let config: FeedSmoothing = toml::from_str(config).unwrap();
+    Smoother::new(&config)
+}
+
+#[test]
+fn test_ewma_weight_halves_every_half_life() {
+    let ewma = smoother("kind = \"ewma\"\nhalf_life_ms = 1000");
+    assert_eq!(ewma.smooth(100.0, 0), 100.0);
+    assert!((ewma.smooth(110.0, 1_000) - 105.0).abs() < 1e-9);
+    // The same data again doesn't pull any further
+    assert!((ewma.smooth(110.0, 1_000) - 105.0).abs() < 1e-9);
+    // Two half-lives: three quarters of the way
+    assert!((ewma.smooth(110.0, 3_000) - 108.75).abs() < 1e-9);
+
+    let json = ewma.to_json();
+    assert_eq!(json["kind"], "ewma");
+    assert_eq!(json["raw"], 110.0);
+    assert_eq!(json["samples"], 3);
+    assert!((json["smoothed"].as_f64().unwrap() - 108.75).abs() < 1e-9);
+}
+
+#[test]
+fn test_kalman_settles_on_a_steady_value() {
+    let kalman = smoother("kind = \"kalman\"\nprocess_noise_bps = 10\nmeasurement_noise_bps = 100");
+    kalman.smooth(100.0, 0);
+    let first = kalman.smooth(110.0, 1_000);
+    assert!(first > 104.0 && first < 106.0, "{}", first);
+    let first_gain = kalman.to_json()["gain"].as_f64().unwrap();
+
+    for second in 2..50 {
+        kalman.smooth(110.0, second * 1_000);
+    }
+    // Confident in its estimate by now: a new value moves it less
+    let gain = kalman.to_json()["gain"].as_f64().unwrap();
+    assert!(gain < first_gain / 2.0, "{} vs {}", gain, first_gain);
+    assert!((kalman.value().unwrap() - 110.0).abs() < 0.1);
+}
+
+#[test]
+fn test_more_process_noise_follows_faster() {
+    let slow = smoother("kind = \"kalman\"\nprocess_noise_bps = 1\nmeasurement_noise_bps = 50");
+    let fast = smoother("kind = \"kalman\"\nprocess_noise_bps = 50\nmeasurement_noise_bps = 50");
+    for second in 0..20 {
+        slow.smooth(100.0, second * 1_000);
+        fast.smooth(100.0, second * 1_000);
+    }
+    assert!(fast.smooth(105.0, 20_000) > slow.smooth(105.0, 20_000));
+}
+
+const FEED: &str = r#"
+[[feeds]]
+id = "BTCUSD"
+source = "binance"
+symbol = "BTCUSDT"
+contract = "0x1111111111111111111111111111111111111111"
+
+[feeds.smoothing]
+kind = "ewma"
+half_life_ms = 10000
+"#;
+
+#[test]
+fn test_smoothing_is_validated() {
+    assert!(FeedsFile::parse(FEED).unwrap().feeds[0].smoothing.is_some());
+
+    let err = FeedsFile::parse(&FEED.replace("half_life_ms = 10000", "")).unwrap_err();
+    assert!(format!("{:#}", err).contains("needs a positive half_life_ms"), "{:#}", err);
+
+    let err = FeedsFile::parse(&FEED.replace("kind = \"ewma\"", "kind = \"kalman\"")).unwrap_err();
+    assert!(format!("{:#}", err).contains("process_noise_bps and measurement_noise_bps"), "{:#}", err);
+
+    let signed = FEED
+        .replace("contract =", "function = \"updatePrice(string,int256)\"\ncontract =")
+        .replace("kind = \"ewma\"\nhalf_life_ms = 10000", "kind = \"kalman\"\nprocess_noise_bps = 5\nmeasurement_noise_bps = 20");
+    let err = FeedsFile::parse(&signed).unwrap_err();
+    assert!(format!("{:#}", err).contains("kalman smoothing needs an unsigned"), "{:#}", err);
+}
+
+#[test]
+fn test_smoothing_from_env() {
+    std::env::remove_var("SMOOTHING");
+    assert!(Smoother::from_env().unwrap().is_none());
+
+    std::env::set_var("SMOOTHING", "ewma");
+    assert!(Smoother::from_env().is_err());
+    std::env::set_var("SMOOTHING_HALF_LIFE_MS", "1000");
+    let ewma = Smoother::from_env().unwrap().unwrap();
+    ewma.smooth(100.0, 0);
+    assert!((ewma.smooth(110.0, 1_000) - 105.0).abs() < 1e-9);
+
+    std::env::set_var("SMOOTHING", "median");
+    assert!(Smoother::from_env().is_err());
+    std::env::remove_var("SMOOTHING");
+    std::env::remove_var("SMOOTHING_HALF_LIFE_MS");
+}
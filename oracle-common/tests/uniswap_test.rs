@@ -0,0 +1,32 @@
+//! Uniswap V3 tick math and pool symbols
+
+use oracle_common::{tick_to_price, twap_tick, UniswapPool};
+
+#[test]
+fn test_twap_tick_averages_cumulatives() {
+    assert_eq!(twap_tick(1_000, 1_000 + 200_000 * 300, 300), 200_000.0);
+    assert_eq!(twap_tick(-600, -1_200, 60), -10.0);
+}
+
+#[test]
+fn test_tick_to_price_adjusts_for_decimals() {
+    assert!((tick_to_price(0.0, 18, 18) - 1.0).abs() < 1e-12);
+
+    // USDC (6) / WETH (18) pool around $2,500 per ETH: token1 per token0 is
+    // 1/2500 WETH per USDC, i.e. tick = ln(1/2500 * 1e12) / ln(1.0001)
+    let tick = (1e12f64 / 2500.0).ln() / 1.0001f64.ln();
+    let weth_per_usdc = tick_to_price(tick, 6, 18);
+    assert!((1.0 / weth_per_usdc - 2500.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_parse_pool_symbol() {
+    let pool = UniswapPool::parse("Ethereum:0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").unwrap();
+    assert_eq!(pool.chain, "ethereum");
+    assert_eq!(pool.rpc_env_key(), "UNISWAP_RPC_ETHEREUM");
+    assert_eq!(UniswapPool::parse("arbitrum-one:0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").unwrap().rpc_env_key(), "UNISWAP_RPC_ARBITRUM_ONE");
+
+    assert!(UniswapPool::parse("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").is_err());
+    assert!(UniswapPool::parse(":0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").is_err());
+    assert!(UniswapPool::parse("ethereum:0x1234").is_err());
+}
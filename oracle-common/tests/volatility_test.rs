@@ -0,0 +1,32 @@
+//! Realized volatility of a trade window
+
+use oracle_common::{bar_closes, realized_volatility, MIN_RETURNS, SECONDS_PER_YEAR};
+
+#[test]
+fn test_bar_closes_take_last_trade_and_carry_forward() {
+    let trades = [(1_000, 100.0), (1_400, 101.0), (3_100, 102.0), (3_900, 103.0)];
+    assert_eq!(bar_closes(&trades, 1_000), vec![101.0, 101.0, 103.0]);
+    assert!(bar_closes(&[], 1_000).is_empty());
+}
+
+#[test]
+fn test_flat_prices_have_zero_volatility() {
+    let trades: Vec<(u64, f64)> = (0..=20).map(|i| (i * 1_000, 50_000.0)).collect();
+    assert_eq!(realized_volatility(&trades, 1_000), Some(0.0));
+}
+
+#[test]
+fn test_constant_return_is_annualized() {
+    // +/-1% alternating every second: each squared log return is ln(1.01)^2
+    let r = 1.01f64.ln();
+    let trades: Vec<(u64, f64)> = (0..=60).map(|i| (i * 1_000, 100.0 * if i % 2 == 0 { 1.0 } else { 1.01 })).collect();
+    let vol = realized_volatility(&trades, 1_000).unwrap();
+    let expected = (r * r * SECONDS_PER_YEAR).sqrt();
+    assert!((vol - expected).abs() / expected < 1e-9, "{} vs {}", vol, expected);
+}
+
+#[test]
+fn test_too_few_returns() {
+    let trades: Vec<(u64, f64)> = (0..MIN_RETURNS as u64).map(|i| (i * 1_000, 100.0 + i as f64)).collect();
+    assert_eq!(realized_volatility(&trades, 1_000), None);
+}
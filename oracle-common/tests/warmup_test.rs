@@ -0,0 +1,65 @@
+//! Warm-up and post-pause hold-off
+
+use oracle_common::{MockClock, WarmupConfig, WarmupGate};
+use std::time::Duration;
+
+fn gate(clock: &std::sync::Arc<MockClock>) -> WarmupGate {
+    let config = WarmupConfig { min_trades: 10, min_fill: 0.5, resume_holdoff: Duration::from_secs(1) };
+    WarmupGate::new("BTCUSD", config).with_clock(clock.clone())
+}
+
+#[test]
+fn test_waits_for_trades_and_window_fill() {
+    let gate = gate(&MockClock::new(0));
+    assert!(!gate.ready(5, Some(0.9)), "too few trades");
+    assert!(!gate.ready(50, Some(0.2)), "window too thin");
+    assert!(gate.ready(10, Some(0.5)));
+
+    // Once warm, a dip doesn't hold updates back
+    assert!(gate.ready(3, Some(0.1)));
+    assert!(gate.is_warm());
+}
+
+#[test]
+fn test_sources_without_a_window_are_ready() {
+    let gate = gate(&MockClock::new(0));
+    assert!(gate.ready(1, None));
+}
+
+#[test]
+fn test_holdoff_after_pause() {
+    let clock = MockClock::new(0);
+    let gate = gate(&clock);
+    assert!(gate.running(false));
+    assert!(gate.ready(10, Some(1.0)));
+
+    assert!(!gate.running(true));
+    clock.advance(Duration::from_secs(10));
+
+    // The ticks that came due during the pause are held back
+    assert!(!gate.running(false));
+    clock.advance(Duration::from_millis(999));
+    assert!(!gate.running(false));
+    clock.advance(Duration::from_millis(1));
+    assert!(gate.running(false));
+}
+
+#[test]
+fn test_pause_restarts_warmup() {
+    let clock = MockClock::new(0);
+    let gate = gate(&clock);
+    assert!(gate.ready(10, Some(1.0)));
+
+    gate.running(true);
+    assert!(!gate.is_warm());
+    assert!(!gate.ready(2, Some(1.0)));
+    assert!(gate.ready(10, Some(1.0)));
+}
+
+#[test]
+fn test_disabled() {
+    let gate = WarmupGate::new("BTCUSD", WarmupConfig::none());
+    assert!(gate.ready(0, Some(0.0)));
+    gate.running(true);
+    assert!(gate.running(false));
+}
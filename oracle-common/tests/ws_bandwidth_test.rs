@@ -0,0 +1,49 @@
+//! Websocket bandwidth counters
+
+use futures_util::{SinkExt, StreamExt};
+use oracle_common::{connect_ws, WsBandwidth};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+fn trade(id: u64) -> String {
+    format!(
+        r#"{{"stream":"btcusdt@trade","data":{{"e":"trade","E":{},"s":"BTCUSDT","t":{},"p":"65000.{}","q":"0.010","T":{},"m":true}}}}"#,
+        1_735_689_600_000 + id,
+        id,
+        id % 10,
+        1_735_689_600_000 + id
+    )
+}
+
+#[test]
+fn test_payload_is_counted() {
+    let bandwidth = WsBandwidth::new();
+    bandwidth.message(trade(1).as_bytes());
+    bandwidth.message(trade(2).as_bytes());
+    assert_eq!(bandwidth.payload_bytes(), (trade(1).len() + trade(2).len()) as u64);
+    assert_eq!(bandwidth.to_json()["messages"], 2);
+}
+
+#[tokio::test]
+async fn test_wire_bytes_are_counted_on_the_socket() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        for id in 0..10 {
+            ws.send(Message::Text(trade(id))).await.unwrap();
+        }
+    });
+
+    let bandwidth = Arc::new(WsBandwidth::new());
+    let (mut ws, _) = connect_ws(&format!("ws://{}/stream", addr), None, bandwidth.clone()).await.unwrap();
+    for _ in 0..10 {
+        let Some(Ok(Message::Text(text))) = ws.next().await else { panic!("expected a trade") };
+        bandwidth.message(text.as_bytes());
+    }
+    // Handshake response and frame headers on top of the payload
+    assert!(bandwidth.wire_in_bytes() > bandwidth.payload_bytes());
+    assert!(bandwidth.to_json()["wire_out_bytes"].as_u64().unwrap() > 0);
+}
@@ -0,0 +1,525 @@
+//! Config-driven oracle runner.
+//!
+//! Reads a feeds file (`FEEDS_FILE` or the first argument, default
+//! `feeds.toml`) and starts the sources and triggers it describes, so adding
+//! a price feed is a config change rather than a new crate.
+//!
+//! Sources are shared; every publish target (chain) gets its own triggers,
+//! orchestrator, worker keys and error control, so one VM can serve several
+//! chains from one TWAP pipeline without a failing chain pausing the others.
+//! Within a target, reverts (or whatever `FEED_SCOPED_ERRORS` lists) pause
+//! only the feed whose update failed.
+
+use anyhow::Result;
+use binance_oracle::source::{spawn_trade_pump, BinanceFeedSource};
+use binance_oracle::twap::TwapCalculator;
+use binance_oracle::websocket::{trade_buffer_key, trade_gaps_json, ws_url_from_env, BinanceWebSocketClient, TradeBackfill, TradeBuffer};
+use fx_oracle::quotes::FxSources;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    dry_run_enabled, init_telemetry, install_crypto_provider, key_rotation_from_env, load_private_keys,
+    oracle_error_handler_config, CandleStore, pipeline_depth_from_env, runtime_from_env, spawn_parse_summary_from_env, spawn_pause_watcher, submitter_for, BlendedSource, DerivedSource,
+    BinanceMarket, FeedConfig, FeedPauses, FeedTrigger, FeedsFile, ForceUpdates, InvertedSource, OnChainCondition, PriceSource, ReferenceCheck, RequestWatcher, PublishTarget, PythSources, RestSources, UniswapSources,
+    DryRunOrchestrator, ErrorPolicy, Halts, ReceiptVerifier, EventBus, EventSink, GrpcServer, Heartbeat, HistoryExporter, PriceStream,
+    RedisSink, ReorgDetector, RoundStore, RuntimeMonitor, ShutdownCoordinator, SourceKind, StatusServer, StatusSource, UpdateHistory, WsProxy,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Combined `/status` for every target and feed
+struct RunnerStatus {
+    targets: Vec<(String, Vec<Arc<FeedTrigger>>)>,
+    candles: Arc<CandleStore>,
+    /// Calculators leaving out dust trades, by feed and symbol
+    dust_filters: Vec<(String, String, Arc<TwapCalculator>)>,
+}
+
+impl StatusSource for RunnerStatus {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "oracle-runner",
+            "targets": self.targets.iter().map(|(name, triggers)| serde_json::json!({
+                "chain": name,
+                "feeds": triggers.iter().map(|t| t.status()).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "trade_gaps": trade_gaps_json(),
+            "candles": self.candles.to_json(),
+            "dust_trades": self.dust_filters.iter().map(|(feed, symbol, calculator)| serde_json::json!({
+                "feed": feed,
+                "symbol": symbol,
+                "filtered": calculator.dust_json(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Upstream connections, collecting what each source type has to subscribe to
+struct Upstreams {
+    binance: Vec<(BinanceMarket, String, Arc<TwapCalculator>)>,
+    /// Bars of every binance symbol, for `bar-twap` feeds and `/candles`
+    candles: Arc<CandleStore>,
+    /// Binance calculators with a `min_trade_notional`, for `/status`
+    dust_filters: Vec<(String, String, Arc<TwapCalculator>)>,
+    fx: FxSources,
+    pyth: PythSources,
+    uniswap: UniswapSources,
+    rest: RestSources,
+    /// Each feed's source so far, for derived feeds to read
+    feeds: HashMap<String, Arc<dyn PriceSource>>,
+}
+
+impl Upstreams {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            binance: Vec::new(),
+            candles: Arc::new(CandleStore::from_env()?),
+            dust_filters: Vec::new(),
+            fx: FxSources::from_env()?,
+            pyth: PythSources::new(),
+            uniswap: UniswapSources::new(),
+            rest: RestSources::new(),
+            feeds: HashMap::new(),
+        })
+    }
+
+    /// Source for `feed`'s own `source` / `symbol`
+    fn add(&mut self, feed: &FeedConfig) -> Result<Arc<dyn PriceSource>> {
+        let source: Arc<dyn PriceSource> = match feed.source {
+            SourceKind::Binance => {
+                let calculator = Arc::new(
+                    TwapCalculator::new(Duration::from_secs(feed.window_secs)).with_min_notional(feed.min_trade_notional),
+                );
+                if feed.min_trade_notional.is_some() {
+                    self.dust_filters.push((feed.id.clone(), feed.symbol.clone(), calculator.clone()));
+                }
+                self.binance.push((feed.market, feed.symbol.clone(), calculator.clone()));
+                let source = BinanceFeedSource::new(calculator, feed.aggregation);
+                match feed.candle_ms() {
+                    Some(interval_ms) => {
+                        if !self.candles.has_interval(interval_ms) {
+                            anyhow::bail!(
+                                "Feed '{}': candle_secs = {} is not one of CANDLE_INTERVALS",
+                                feed.id,
+                                interval_ms / 1000
+                            );
+                        }
+                        let key = trade_buffer_key(feed.market, &feed.symbol);
+                        let window = Duration::from_secs(feed.window_secs);
+                        Arc::new(source.with_candles(self.candles.clone(), key, interval_ms, window))
+                    }
+                    None => Arc::new(source),
+                }
+            }
+            SourceKind::ExchangerateHost | SourceKind::Tradermade => self.fx.add(feed)?,
+            SourceKind::Pyth => self.pyth.add(feed)?,
+            SourceKind::UniswapV3 => self.uniswap.add(feed)?,
+            SourceKind::Rest => self.rest.add(feed)?,
+            SourceKind::Derived => Arc::new(DerivedSource::new(&feed.symbol, |id| self.feeds.get(id).cloned())?),
+        };
+        Ok(if feed.invert { Arc::new(InvertedSource::new(source)) } else { source })
+    }
+
+    /// Source for `feed`, blended with its `blend` legs if it has any
+    fn add_feed(&mut self, feed: &FeedConfig) -> Result<Arc<dyn PriceSource>> {
+        let own = self.add(feed)?;
+        let source: Arc<dyn PriceSource> = if feed.blend.is_empty() {
+            own
+        } else {
+            let mut legs = vec![(own, feed.weight)];
+            for leg in feed.blend_legs() {
+                legs.push((self.add(&leg)?, leg.weight));
+            }
+            Arc::new(
+                BlendedSource::new(feed.id.clone(), legs)
+                    .with_max_age(feed.max_age_secs.map(Duration::from_secs))
+                    .with_max_deviation_bps(feed.max_blend_deviation_bps)
+                    .with_quorum(feed.quorum.clone()),
+            )
+        };
+        self.feeds.insert(feed.id.clone(), source.clone());
+        Ok(source)
+    }
+
+    fn spawn(self, shutdown: &mut ShutdownCoordinator) -> Result<()> {
+        if !self.binance.is_empty() {
+            // One connection per market, all feeding one buffer keyed by market and symbol
+            let mut markets: BTreeMap<BinanceMarket, Vec<String>> = BTreeMap::new();
+            for (market, symbol, _) in &self.binance {
+                markets.entry(*market).or_default().push(symbol.clone());
+            }
+
+            let trade_buffer = Arc::new(TradeBuffer::new(10000));
+            let proxy = WsProxy::from_env()?.map(Arc::new);
+            for (market, mut symbols) in markets {
+                symbols.sort();
+                symbols.dedup();
+                let ws_client = BinanceWebSocketClient::new(symbols, trade_buffer.clone())
+                    .with_market(market)
+                    .with_base_url(ws_url_from_env(market))
+                    .with_proxy(proxy.clone())
+                    .with_backfill(TradeBackfill::from_env(market)?.map(Arc::new));
+                shutdown.register(format!("binance {} websocket", market.as_str()), tokio::spawn(async move {
+                    if let Err(e) = ws_client.run().await {
+                        error!("WebSocket client error: {}", e);
+                    }
+                }));
+            }
+            let subscriptions = self
+                .binance
+                .into_iter()
+                .map(|(market, symbol, calculator)| (trade_buffer_key(market, &symbol), calculator))
+                .collect();
+            shutdown.register("trade pump", spawn_trade_pump(trade_buffer, subscriptions, Some(self.candles)));
+        }
+        self.fx.spawn(shutdown)?;
+        self.pyth.spawn(shutdown);
+        self.rest.spawn(shutdown);
+        self.uniswap.spawn(shutdown)
+    }
+}
+
+/// Triggers publishing every feed to `target`, each sharing the target's error control.
+/// With `qualify_names` events name the feed `<chain>/<feed>`. On-chain
+/// conditions and request events are read from the target chain, so each
+/// target polls its own; feeds sharing a request contract and event share a
+/// watcher. Reference checks and the round store are shared by every target;
+/// rounds are kept per trigger label, so each target numbers its own. Feeds
+/// sit out pauses the error policy charges to them alone (`feed_pauses`) and
+/// halts only an operator lifts (`halts`), and publish at once when an
+/// operator forces an update (`force_updates`).
+#[allow(clippy::too_many_arguments)]
+fn target_triggers(
+    feeds: &FeedsFile,
+    sources: &[Arc<dyn PriceSource>],
+    references: &[Option<Arc<ReferenceCheck>>],
+    rounds: &Option<Arc<RoundStore>>,
+    target: &PublishTarget,
+    error_control: &Arc<OrchestratorErrorControl>,
+    feed_pauses: &Arc<FeedPauses>,
+    halts: &Option<Arc<Halts>>,
+    force_updates: &Arc<ForceUpdates>,
+    verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    qualify_names: bool,
+    shutdown: &mut ShutdownCoordinator,
+) -> Result<Vec<Arc<FeedTrigger>>> {
+    let mut watchers: HashMap<(Address, String), RequestWatcher> = HashMap::new();
+    for feed in &feeds.feeds {
+        let config = target.feed_for(feed);
+        let Some(requests) = &config.requests else { continue };
+        let poll_interval = Duration::from_millis(requests.poll_ms.unwrap_or(config.interval_ms));
+        let watcher = watchers
+            .entry((requests.contract.unwrap_or(config.contract), requests.event.clone()))
+            .and_modify(|shared| shared.poll_at_least_every(poll_interval))
+            .or_insert_with(|| RequestWatcher::for_feed(config.contract, config.interval_ms, requests));
+        watcher.watch(&config.id);
+    }
+    let watchers: HashMap<_, _> = watchers.into_iter().map(|(key, watcher)| (key, Arc::new(watcher))).collect();
+    for watcher in watchers.values() {
+        shutdown.register("request watcher", watcher.spawn(target.chain.rpc_urls.clone()));
+    }
+
+    let mut triggers = Vec::new();
+    for ((feed, source), reference) in feeds.feeds.iter().zip(sources).zip(references) {
+        let config = target.feed_for(feed);
+        let requests = config
+            .requests
+            .as_ref()
+            .map(|requests| watchers[&(requests.contract.unwrap_or(config.contract), requests.event.clone())].clone());
+        let condition = match &feed.condition {
+            Some(condition) => {
+                let condition = Arc::new(OnChainCondition::new(&config, condition)?);
+                shutdown.register("condition reader", condition.spawn(target.chain.rpc_urls.clone()));
+                Some(condition)
+            }
+            None => None,
+        };
+        let trigger = FeedTrigger::new(config, source.clone(), error_control.clone())
+            .with_condition(condition)
+            .with_reference(reference.clone())
+            .with_rounds(rounds.clone())
+            .with_feed_pauses(Some(feed_pauses.clone()))
+            .with_halts(halts.clone())
+            .with_requests(requests)
+            .with_receipt_verifier(verifier.clone())
+            .with_reorg_detector(reorg_detector.clone());
+        let trigger = if qualify_names {
+            trigger.with_label(format!("{}/{}", target.chain.name, feed.id))
+        } else {
+            trigger
+        };
+        triggers.push(Arc::new(trigger.with_force_updates(Some(force_updates.clone()))));
+    }
+    Ok(triggers)
+}
+
+fn main() -> Result<()> {
+    install_crypto_provider();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    dotenv::dotenv().ok();
+
+    runtime_from_env()?.block_on(run())
+}
+
+async fn run() -> Result<()> {
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("oracle-runner")?;
+
+    let feeds_path = std::env::args()
+        .nth(1)
+        .or_else(|| std::env::var("FEEDS_FILE").ok())
+        .unwrap_or_else(|| "feeds.toml".to_string());
+    let feeds = FeedsFile::load(&feeds_path)?;
+    info!("📄 Loaded {} feeds from {}", feeds.feeds.len(), feeds_path);
+
+    let mut targets = feeds.publish_targets()?;
+    for target in &mut targets {
+        target.chain.resolve_chain_id().await?;
+    }
+    // SDK-wide defaults follow the first target; each orchestrator gets its own RPC
+    targets[0].chain.apply_sdk_defaults();
+    for target in &targets[1..] {
+        info!("🔗 Additional target: {} ({} RPC endpoint(s))", target.chain.name, target.chain.rpc_urls.len());
+    }
+
+    let mut shutdown = ShutdownCoordinator::new();
+    if let Some(handle) = spawn_parse_summary_from_env()? {
+        shutdown.register("parse error summary", handle);
+    }
+
+    // --- Sources ---
+    let mut upstreams = Upstreams::from_env()?;
+    let mut sources: Vec<Arc<dyn PriceSource>> = Vec::new();
+    for feed in &feeds.feeds {
+        sources.push(upstreams.add_feed(feed)?);
+        info!("📈 {} <- {:?} {} ({:?}, {}ms, {} decimals)",
+            feed.id, feed.source, feed.symbol, feed.aggregation, feed.interval_ms, feed.decimals);
+        for leg in &feed.blend {
+            info!("   ⚖️ blended with {:?} {} (weight {} vs {})", leg.source, leg.symbol, leg.weight, feed.weight);
+        }
+    }
+    let candles = upstreams.candles.clone();
+    let dust_filters = upstreams.dust_filters.clone();
+    upstreams.spawn(&mut shutdown)?;
+
+    // Independent prices updates are cross-checked against ([feeds.reference])
+    let mut references = Vec::new();
+    for feed in &feeds.feeds {
+        references.push(match &feed.reference {
+            Some(config) => {
+                let check = Arc::new(ReferenceCheck::for_feed(feed, config, targets[0].chain.rpc_url()).await?);
+                info!("🔎 {} cross-checked against a {:?} reference (max {}bps)", feed.id, config.kind, config.max_divergence_bps);
+                shutdown.register("reference reader", check.spawn());
+                Some(check)
+            }
+            None => None,
+        });
+    }
+
+    // Rounds published with each update continue across restarts (ROUND_STATE_PATH, ROUND_BLOCK)
+    let rounds = if feeds.feeds.iter().any(|feed| feed.takes_round()) {
+        Some(Arc::new(RoundStore::from_env()?))
+    } else {
+        None
+    };
+
+    // --- Triggers, one set per target ---
+    // Plugins fed by the event bus: dashboard history and, with
+    // HEARTBEAT_URLS, pings only while some target keeps publishing
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    if let Some((stream, handle)) = PriceStream::from_env()? {
+        shutdown.register("price stream", handle);
+        EventBus::global().subscribe(stream);
+    }
+    if let Some((sink, handle)) = RedisSink::from_env()? {
+        shutdown.register("redis sink", handle);
+        EventBus::global().subscribe(sink);
+    }
+    if let Some((sink, handle)) = EventSink::from_env().await? {
+        shutdown.register("event sink", handle);
+        EventBus::global().subscribe(sink);
+    }
+    let exporter = match HistoryExporter::from_env()? {
+        Some((exporter, handle)) => {
+            shutdown.register("history export", handle);
+            EventBus::global().subscribe(exporter.clone());
+            Some(exporter)
+        }
+        None => None,
+    };
+    // Feed-scoped failures (FEED_SCOPED_ERRORS) pause one feed, not the pool
+    let error_policy = ErrorPolicy::from_env()?;
+    // Severe, repeated conditions (HALT_ON) halt a feed until resumed over gRPC
+    let halts = Halts::from_env()?;
+    if let Some(halts) = &halts {
+        EventBus::global().subscribe(halts.clone());
+    }
+    // Out-of-band publishes requested over gRPC (`nonzu force-update`)
+    let force_updates = Arc::new(ForceUpdates::new());
+
+    // With several targets the same feed is charted once per chain
+    let qualify_names = targets.len() > 1;
+    let mut target_sets: Vec<(PublishTarget, Vec<Arc<FeedTrigger>>)> = Vec::new();
+    let mut controls = Vec::new();
+    for target in targets {
+        // Re-check confirmed updates a few blocks later (RECEIPT_VERIFY_BLOCKS)
+        let verifier = match ReceiptVerifier::from_env(target.chain.rpc_urls.clone())? {
+            Some((verifier, handle)) => {
+                shutdown.register("receipt verifier", handle);
+                Some(verifier)
+            }
+            None => None,
+        };
+        let reorg_detector = match ReorgDetector::from_env(target.chain.rpc_urls.clone())? {
+            Some((detector, handle)) => {
+                shutdown.register("reorg detector", handle);
+                Some(detector)
+            }
+            None => None,
+        };
+        let error_control = Arc::new(OrchestratorErrorControl::new());
+        shutdown.register("pause watcher", spawn_pause_watcher(target.chain.name.clone(), error_control.clone()));
+        let triggers = target_triggers(
+            &feeds,
+            &sources,
+            &references,
+            &rounds,
+            &target,
+            &error_control,
+            &error_policy.feed_pauses(),
+            &halts,
+            &force_updates,
+            verifier,
+            reorg_detector,
+            qualify_names,
+            &mut shutdown,
+        )?;
+        controls.push((target.chain.name.clone(), error_control));
+        target_sets.push((target, triggers));
+    }
+
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let status = Arc::new(RunnerStatus {
+            targets: target_sets.iter().map(|(t, triggers)| (t.chain.name.clone(), triggers.clone())).collect(),
+            candles: candles.clone(),
+            dust_filters,
+        });
+        let server = StatusServer::new(addr)
+            .with_status(status)
+            .with_candles(candles)
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+    if let Some(mut grpc) = GrpcServer::from_env()? {
+        for (_, triggers) in &target_sets {
+            for trigger in triggers {
+                grpc = grpc.with_stats(trigger.label(), trigger.stats());
+            }
+        }
+        for (scope, error_control) in controls {
+            grpc = grpc.with_control(scope, error_control);
+        }
+        grpc = grpc.with_halts(halts.clone()).with_force_updates(Some(force_updates.clone()));
+        shutdown.register("grpc server", grpc.spawn());
+    }
+
+    // Check triggers slightly faster than the fastest feed
+    let min_interval_ms = feeds.feeds.iter().map(|f| f.interval_ms).min().unwrap_or(200);
+    let check_interval = Duration::from_millis(min_interval_ms.saturating_sub(10).max(50));
+
+    if dry_run_enabled() {
+        for (target, triggers) in target_sets {
+            let private_keys = load_private_keys(&[target.key_prefix.as_str()])?;
+            let dry_run = DryRunOrchestrator::new(
+                triggers.into_iter().map(|t| t as Arc<dyn TxTrigger>).collect(),
+                &private_keys,
+                &target.chain,
+                check_interval,
+            ).await?;
+            shutdown.register("dry-run orchestrator", dry_run.spawn());
+        }
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down oracle runner (dry run)...");
+        shutdown.shutdown();
+        if let Some(rounds) = &rounds {
+            rounds.flush()?;
+        }
+        return Ok(());
+    }
+
+    let mut orchestrators = Vec::new();
+    for (target, triggers) in target_sets {
+        let private_keys = load_private_keys(&[target.key_prefix.as_str()])?;
+        info!("🔑 {}: {} private keys ({}*)", target.chain.name, private_keys.len(), target.key_prefix);
+
+        let (key_selector, balance_refresher) = key_rotation_from_env(target.chain.rpc_url().to_string(), &private_keys)?;
+        shutdown.register("balance refresher", balance_refresher);
+
+        let worker_count = pipeline_depth_from_env(private_keys.len())?;
+        let orchestrator = SimpleOrchestrator::new_with_config(
+            triggers.into_iter().map(|t| t as Arc<dyn TxTrigger>).collect(),
+            private_keys.into_sdk(),
+            worker_count, // 1 per chain on low-spec shared CPU unless PIPELINE_DEPTH is set
+            check_interval,
+            oracle_error_handler_config(),
+        ).await?
+        .with_rpc_url(target.chain.rpc_url())
+        .with_key_selector(key_selector.clone())
+        .with_submitter(key_selector.track_results(submitter_for(&target.chain)?))
+        .with_error_hook(error_policy.clone());
+
+        orchestrators.push((target.chain.name, orchestrator));
+    }
+
+    // Each feed holds its first update until its window has warmed up
+    let mut handles = Vec::new();
+    for (name, orchestrator) in orchestrators {
+        info!("🎯 Starting orchestrator for {}", name);
+        handles.push(orchestrator.run().await);
+    }
+    info!("✅ Oracle runner is running! Press Ctrl+C to stop.");
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down oracle runner...");
+    shutdown.shutdown();
+    for handle in handles {
+        handle.shutdown().await?;
+    }
+
+    // Write the exact last rounds, so a restart continues without a gap
+    if let Some(rounds) = &rounds {
+        rounds.flush()?;
+    }
+
+    // Trades and updates since the last periodic export
+    if let Some(exporter) = exporter {
+        exporter.export()?;
+    }
+
+    info!("👋 Oracle runner shutdown complete");
+    Ok(())
+}
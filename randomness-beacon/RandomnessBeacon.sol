@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+import "@openzeppelin/contracts/access/Ownable.sol";
+
+/**
+ * @title RandomnessBeacon
+ * @notice Publishes a new random value every round for games and raffles
+ * @dev Values come either from a commit-reveal hash chain (each reveal must hash
+ *      to the previous one, so the operator cannot choose it after committing)
+ *      or from relayed drand beacons (value = sha256(signature), verifiable
+ *      off-chain against the drand network's public key).
+ */
+contract RandomnessBeacon is Ownable {
+    // Hash chain in use; a new epoch is committed when one runs out
+    uint256 public epoch;
+
+    // Last revealed link (the anchor right after a commit)
+    bytes32 public lastReveal;
+
+    // Last relayed drand round
+    uint64 public lastDrandRound;
+
+    // Randomness rounds published so far
+    uint256 public round;
+
+    // Round => random value
+    mapping(uint256 => bytes32) public randomness;
+
+    // Block timestamp of the latest round
+    uint256 public lastUpdate;
+
+    // Authorized updaters (oracle runners)
+    mapping(address => bool) public authorizedUpdaters;
+
+    // Events
+    event ChainCommitted(uint256 indexed epoch, bytes32 anchor);
+    event RandomnessPublished(uint256 indexed round, bytes32 value, bytes32 preimage);
+    event DrandRelayed(uint256 indexed round, uint64 drandRound, bytes32 value, bytes signature);
+    event UpdaterAuthorized(address indexed updater, bool authorized);
+
+    // Errors
+    error UnauthorizedUpdater(address updater);
+    error StaleEpoch(uint256 provided, uint256 current);
+    error InvalidReveal(bytes32 preimage, bytes32 lastReveal);
+    error StaleRound(uint256 provided, uint256 current);
+    error NoRandomness();
+
+    modifier onlyAuthorized() {
+        if (!authorizedUpdaters[msg.sender] && msg.sender != owner()) {
+            revert UnauthorizedUpdater(msg.sender);
+        }
+        _;
+    }
+
+    constructor() Ownable(msg.sender) {
+        // Owner is automatically authorized
+        authorizedUpdaters[msg.sender] = true;
+    }
+
+    /**
+     * @notice Commits the anchor of a new hash chain
+     * @param newEpoch The chain's epoch, greater than the current one
+     * @param anchor The chain's first link; the next reveal must hash to it
+     */
+    function commitChain(uint256 newEpoch, bytes32 anchor) external onlyAuthorized {
+        if (newEpoch <= epoch) revert StaleEpoch(newEpoch, epoch);
+        epoch = newEpoch;
+        lastReveal = anchor;
+        emit ChainCommitted(newEpoch, anchor);
+    }
+
+    /**
+     * @notice Reveals the next link of the committed hash chain
+     * @dev The value mixes in the previous block hash so it is not fixed by the
+     *      chain alone
+     * @param preimage Link whose keccak256 is the last reveal
+     */
+    function reveal(bytes32 preimage) external onlyAuthorized {
+        if (keccak256(abi.encodePacked(preimage)) != lastReveal) revert InvalidReveal(preimage, lastReveal);
+        lastReveal = preimage;
+
+        bytes32 value = keccak256(abi.encodePacked(preimage, blockhash(block.number - 1)));
+        _publish(value);
+        emit RandomnessPublished(round, value, preimage);
+    }
+
+    /**
+     * @notice Relays a drand beacon
+     * @param drandRound The drand round, greater than the last relayed one
+     * @param signature The round's BLS signature; the value is its sha256
+     */
+    function relayDrand(uint64 drandRound, bytes calldata signature) external onlyAuthorized {
+        if (drandRound <= lastDrandRound) revert StaleRound(drandRound, lastDrandRound);
+        lastDrandRound = drandRound;
+
+        bytes32 value = sha256(signature);
+        _publish(value);
+        emit DrandRelayed(round, drandRound, value, signature);
+    }
+
+    /**
+     * @notice Latest random value
+     * @return currentRound The round number
+     * @return value The random value
+     * @return updatedAt Block timestamp of the round
+     */
+    function latest() external view returns (uint256 currentRound, bytes32 value, uint256 updatedAt) {
+        if (round == 0) revert NoRandomness();
+        return (round, randomness[round], lastUpdate);
+    }
+
+    /**
+     * @notice Checks if the latest value is stale
+     * @param maxAge Maximum age in seconds
+     * @return True if data is stale
+     */
+    function isStale(uint256 maxAge) external view returns (bool) {
+        if (lastUpdate == 0) return true;
+        return block.timestamp > lastUpdate + maxAge;
+    }
+
+    /**
+     * @notice Authorizes or revokes an updater
+     * @param updater The address to authorize/revoke
+     * @param authorized Whether to authorize or revoke
+     */
+    function setAuthorizedUpdater(address updater, bool authorized) external onlyOwner {
+        authorizedUpdaters[updater] = authorized;
+        emit UpdaterAuthorized(updater, authorized);
+    }
+
+    function _publish(bytes32 value) private {
+        round++;
+        randomness[round] = value;
+        lastUpdate = block.timestamp;
+    }
+}
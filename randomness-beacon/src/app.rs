@@ -0,0 +1,164 @@
+use anyhow::{bail, Context, Result};
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, init_telemetry, key_rotation_from_env, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, spawn_pause_watcher, submitter_for, DryRunOrchestrator, ErrorPolicy, EventBus, Heartbeat,
+    ReceiptVerifier, ReorgDetector, RuntimeMonitor, ShutdownCoordinator, StatusServer, UpdateHistory,
+};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::drand::DrandClient;
+use crate::hash_chain::master_seed_from_env;
+use crate::trigger::{BeaconMode, RandomnessTrigger};
+
+fn env_u64(key: &str, default: u64) -> Result<u64> {
+    match env::var(key) {
+        Ok(v) => v.parse().with_context(|| format!("Invalid {}", key)),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Run the randomness beacon until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `randomness-beacon` binary or `nonzu run randomness-beacon`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Randomness Beacon");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("randomness-beacon")?;
+
+    let chain = apply_sdk_defaults().await?;
+
+    let oracle_address = Address::from_str(
+        &env::var("RANDOMNESS_BEACON_ADDRESS").context("RANDOMNESS_BEACON_ADDRESS must be set in .env")?,
+    )?;
+    info!("📝 Beacon contract address: {}", oracle_address);
+
+    let private_keys = load_private_keys(&["RANDOMNESS_PRIVATE_KEY_", "PRIVATE_KEY_"])?;
+    info!("🔑 Loaded {} private keys", private_keys.len());
+
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+
+    let update_interval = Duration::from_millis(env_u64("RANDOMNESS_INTERVAL_MS", 2_000)?.max(1));
+
+    let mode = match env::var("RANDOMNESS_MODE").as_deref() {
+        Err(_) | Ok("commit-reveal") => {
+            let chain_length = env_u64("RANDOMNESS_CHAIN_LENGTH", 100_000)?;
+            if chain_length == 0 || chain_length > 10_000_000 {
+                bail!("RANDOMNESS_CHAIN_LENGTH must be between 1 and 10000000");
+            }
+            info!("🎲 Commit-reveal: a new {}-link hash chain per epoch, revealing every {:?}", chain_length, update_interval);
+            BeaconMode::CommitReveal { master_seed: master_seed_from_env()?, chain_length: chain_length as usize }
+        }
+        Ok("drand") => {
+            let client = Arc::new(DrandClient::from_env());
+            let poll_interval = Duration::from_millis(env_u64("DRAND_POLL_MS", 1_000)?.max(100));
+            info!("🎲 Relaying drand beacons from {}", client.url());
+            shutdown.register("drand poller", client.spawn(poll_interval));
+            BeaconMode::Drand(client)
+        }
+        Ok(other) => bail!("Unknown RANDOMNESS_MODE '{}' - expected commit-reveal or drand", other),
+    };
+
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    shutdown.register("pause watcher", spawn_pause_watcher("randomness-beacon", error_control.clone()));
+
+    let trigger = Arc::new(
+        RandomnessTrigger::new(oracle_address, chain.rpc_url(), mode, update_interval, error_control.clone())?
+            .with_receipt_verifier(receipt_verifier)
+            .with_reorg_detector(reorg_detector),
+    );
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let server = StatusServer::new(addr)
+            .with_status(trigger.clone())
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+
+    // Games want each round as soon as it is due; checks are cheap
+    let check_interval = Duration::from_millis(50).min(update_interval);
+
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(
+            vec![trigger as Arc<dyn TxTrigger>],
+            &private_keys,
+            &chain,
+            check_interval,
+        ).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down beacon (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+
+    // Reveals chain onto each other, so one is in flight at a time whatever
+    // PIPELINE_DEPTH says; extra workers only spread keys
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    info!("⚡ Using {} worker(s)", worker_count);
+
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        vec![trigger as Arc<dyn TxTrigger>],
+        private_keys.into_sdk(),
+        worker_count,
+        check_interval,
+        oracle_error_handler_config(),
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+
+    let handle = orchestrator.run().await;
+    info!("✅ Randomness Beacon is running! Press Ctrl+C to stop.");
+
+    shutdown.wait_for_signal().await?;
+
+    info!("🛑 Shutting down beacon...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+
+    info!("👋 Beacon shutdown complete");
+    Ok(())
+}
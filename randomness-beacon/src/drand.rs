@@ -0,0 +1,119 @@
+//! drand beacon relay.
+//!
+//! Polls `<DRAND_URL>/<chain hash>/public/latest`. A beacon's randomness is
+//! `sha256(signature)` and the signature is a BLS signature over the round,
+//! so anyone can verify a relayed value against the drand network's public
+//! key; the contract only recomputes the hash.
+
+use alloy::hex;
+use alloy::primitives::{Bytes, B256};
+use anyhow::{anyhow, Result};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// quicknet: 3s rounds, unchained BLS signatures on G1
+pub const QUICKNET_CHAIN_HASH: &str = "52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971";
+
+const DEFAULT_DRAND_URL: &str = "https://api.drand.sh";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrandBeacon {
+    pub round: u64,
+    pub randomness: B256,
+    pub signature: Bytes,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconResponse {
+    round: u64,
+    randomness: String,
+    signature: String,
+}
+
+/// `sha256(signature)`, the randomness drand derives from a signature
+pub fn randomness_of(signature: &[u8]) -> B256 {
+    B256::from_slice(&Sha256::digest(signature))
+}
+
+impl DrandBeacon {
+    /// Parse a `/public/latest` response, checking randomness matches the signature
+    pub fn parse(body: &str) -> Result<Self> {
+        let response: BeaconResponse = serde_json::from_str(body)?;
+        let signature = Bytes::from(hex::decode(&response.signature).map_err(|e| anyhow!("Invalid signature hex: {}", e))?);
+        let randomness = hex::decode(&response.randomness).map_err(|e| anyhow!("Invalid randomness hex: {}", e))?;
+        if randomness.len() != 32 {
+            return Err(anyhow!("Randomness must be 32 bytes, got {}", randomness.len()));
+        }
+        let randomness = B256::from_slice(&randomness);
+        if randomness_of(&signature) != randomness {
+            return Err(anyhow!("Round {} randomness is not sha256(signature)", response.round));
+        }
+        Ok(Self { round: response.round, randomness, signature })
+    }
+}
+
+/// Follows the latest beacon of one drand chain
+pub struct DrandClient {
+    url: String,
+    latest: Arc<RwLock<Option<DrandBeacon>>>,
+}
+
+impl DrandClient {
+    /// From `DRAND_URL` (default `https://api.drand.sh`) and
+    /// `DRAND_CHAIN_HASH` (default quicknet)
+    pub fn from_env() -> Self {
+        let base = std::env::var("DRAND_URL").unwrap_or_else(|_| DEFAULT_DRAND_URL.to_string());
+        let chain = std::env::var("DRAND_CHAIN_HASH").unwrap_or_else(|_| QUICKNET_CHAIN_HASH.to_string());
+        Self {
+            url: format!("{}/{}/public/latest", base.trim_end_matches('/'), chain),
+            latest: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn latest(&self) -> Option<DrandBeacon> {
+        self.latest.read().clone()
+    }
+
+    /// Poll the latest beacon every `interval` in the background
+    pub fn spawn(&self, interval: Duration) -> JoinHandle<()> {
+        let url = self.url.clone();
+        let latest = self.latest.clone();
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let body = match client.get(&url).timeout(Duration::from_secs(5)).send().await {
+                    Ok(response) => match response.error_for_status() {
+                        Ok(response) => response.text().await,
+                        Err(e) => Err(e),
+                    },
+                    Err(e) => Err(e),
+                };
+                match body.map_err(anyhow::Error::from).and_then(|body| DrandBeacon::parse(&body)) {
+                    Ok(beacon) => {
+                        let mut latest = latest.write();
+                        // CDN nodes can lag; never step back to an older round
+                        if latest.as_ref().map_or(true, |b| beacon.round > b.round) {
+                            if latest.is_none() {
+                                info!("🎲 drand beacon at round {}", beacon.round);
+                            }
+                            debug!("🎲 drand round {}: {}", beacon.round, beacon.randomness);
+                            *latest = Some(beacon);
+                        }
+                    }
+                    Err(e) => warn!("⚠️ Failed to fetch drand beacon: {}", e),
+                }
+            }
+        })
+    }
+}
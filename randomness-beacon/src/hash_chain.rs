@@ -0,0 +1,109 @@
+//! Hash chains for commit-reveal randomness.
+//!
+//! For each epoch a chain `link[n] = seed`, `link[i - 1] = keccak256(link[i])`
+//! is derived from the master seed. `link[0]` (the anchor) is committed
+//! on-chain, then each round reveals the next link, which the contract checks
+//! hashes to the previous one. Nobody without the seed can predict the next
+//! link, and the operator cannot change it once the anchor is committed.
+
+use alloy::hex;
+use alloy::primitives::{keccak256, B256};
+use anyhow::{anyhow, Context, Result};
+use rand::RngCore;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// All links of one epoch's chain, anchor first
+pub struct HashChain {
+    epoch: u64,
+    links: Vec<B256>,
+}
+
+/// Seed of `epoch`'s chain: `keccak256(master_seed ++ epoch)`
+pub fn epoch_seed(master_seed: B256, epoch: u64) -> B256 {
+    let mut preimage = [0u8; 40];
+    preimage[..32].copy_from_slice(master_seed.as_slice());
+    preimage[32..].copy_from_slice(&epoch.to_be_bytes());
+    keccak256(preimage)
+}
+
+/// Whether `preimage` is the link revealed after `previous`
+pub fn verify_link(previous: B256, preimage: B256) -> bool {
+    keccak256(preimage) == previous
+}
+
+impl HashChain {
+    /// Chain of `length` reveals for `epoch`
+    pub fn new(master_seed: B256, epoch: u64, length: usize) -> Self {
+        let mut links = vec![B256::ZERO; length + 1];
+        links[length] = epoch_seed(master_seed, epoch);
+        for i in (0..length).rev() {
+            links[i] = keccak256(links[i + 1]);
+        }
+        Self { epoch, links }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The value committed on-chain before the first reveal
+    pub fn anchor(&self) -> B256 {
+        self.links[0]
+    }
+
+    /// Number of reveals the chain allows
+    pub fn len(&self) -> usize {
+        self.links.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Link at `position` (0 is the anchor)
+    pub fn link(&self, position: usize) -> Option<B256> {
+        self.links.get(position).copied()
+    }
+
+    /// Position of `link` in the chain, e.g. the contract's last reveal
+    pub fn position_of(&self, link: B256) -> Option<usize> {
+        self.links.iter().position(|l| *l == link)
+    }
+}
+
+fn parse_seed(raw: &str) -> Result<B256> {
+    let bytes = hex::decode(raw.trim()).map_err(|e| anyhow!("Invalid seed hex: {}", e))?;
+    if bytes.len() != 32 {
+        return Err(anyhow!("Seed must be 32 bytes, got {}", bytes.len()));
+    }
+    Ok(B256::from_slice(&bytes))
+}
+
+/// Master seed from `RANDOMNESS_SEED`, or the file at `RANDOMNESS_SEED_PATH`
+/// (default `randomness-seed.hex`), generated there on first start
+pub fn master_seed_from_env() -> Result<B256> {
+    if let Ok(raw) = std::env::var("RANDOMNESS_SEED") {
+        return parse_seed(&raw).context("Invalid RANDOMNESS_SEED");
+    }
+
+    let path = std::env::var("RANDOMNESS_SEED_PATH").unwrap_or_else(|_| "randomness-seed.hex".to_string());
+    let path = Path::new(&path);
+    if path.exists() {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        return parse_seed(&raw).with_context(|| format!("Invalid seed in {}", path.display()));
+    }
+
+    let mut seed = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut seed);
+    std::fs::write(path, hex::encode_prefixed(seed)).with_context(|| format!("Failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)) {
+            warn!("⚠️ Could not restrict permissions of {}: {}", path.display(), e);
+        }
+    }
+    info!("🔐 Generated a new master seed in {} - back it up, reveals cannot continue without it", path.display());
+    Ok(B256::from(seed))
+}
@@ -0,0 +1,6 @@
+pub mod drand;
+pub mod hash_chain;
+pub mod trigger;
+mod app;
+
+pub use app::run;
@@ -0,0 +1,382 @@
+use alloy::primitives::{Bytes, B256};
+use alloy::providers::{ProviderBuilder, RootProvider};
+use alloy::sol;
+use alloy::transports::http::{Client, Http};
+use async_trait::async_trait;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::prelude::*;
+use oracle_common::{
+    encode_commit_chain, encode_relay_drand, encode_reveal, function_selector, publish_event, trace_completed, trace_fired,
+    CircuitBreaker, OracleEvent, OracleStats, PendingQueue, PreciseTimer, ReceiptVerifier, ReorgDetector, SharedStats,
+    StatusSource,
+};
+use parking_lot::{Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info, warn};
+
+use crate::drand::DrandClient;
+use crate::hash_chain::HashChain;
+
+sol!(
+    #[sol(rpc)]
+    interface IRandomnessBeacon {
+        function epoch() external view returns (uint256);
+        function lastReveal() external view returns (bytes32);
+        function lastDrandRound() external view returns (uint64);
+    }
+);
+
+/// Feed name in events, traces and the dashboard
+const FEED: &str = "RANDOMNESS";
+
+/// Where each round's randomness comes from
+pub enum BeaconMode {
+    /// Reveal the next link of a hash chain derived from a local seed
+    CommitReveal { master_seed: B256, chain_length: usize },
+    /// Relay each new drand round
+    Drand(Arc<DrandClient>),
+}
+
+impl BeaconMode {
+    fn name(&self) -> &'static str {
+        match self {
+            BeaconMode::CommitReveal { .. } => "commit-reveal",
+            BeaconMode::Drand(_) => "drand",
+        }
+    }
+}
+
+/// The update in flight. Reveals must land in order, so there is at most one.
+#[derive(Debug, Clone, Copy)]
+enum InFlight {
+    Commit { epoch: u64 },
+    Reveal { position: usize },
+    Drand { round: u64 },
+}
+
+/// What the contract holds, as last read or confirmed
+#[derive(Default)]
+struct OnChain {
+    /// Epoch stored on the contract (0 before the first commit)
+    epoch: u64,
+    /// Our chain for `epoch`; `None` if nothing is committed or the contract
+    /// holds a chain we cannot extend (the seed changed)
+    chain: Option<HashChain>,
+    /// Position of the contract's last reveal in `chain`
+    position: usize,
+    last_drand_round: u64,
+    /// Re-read the contract before the next update
+    stale: bool,
+}
+
+pub struct RandomnessTrigger {
+    oracle_address: Address,
+    contract: IRandomnessBeacon::IRandomnessBeaconInstance<Http<Client>, RootProvider<Http<Client>>>,
+    mode: BeaconMode,
+    timer: Arc<RwLock<PreciseTimer>>,
+    update_interval: Duration,
+    on_chain: RwLock<OnChain>,
+    in_flight: RwLock<Option<InFlight>>,
+    /// Chain whose anchor is being committed
+    committing: Mutex<Option<HashChain>>,
+    last_drift_ms: Arc<RwLock<i64>>,
+    error_control: Arc<OrchestratorErrorControl>,
+    stats: SharedStats,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    /// Set by the reorg detector when a published update was dropped
+    republish: Arc<AtomicBool>,
+}
+
+impl RandomnessTrigger {
+    pub fn new(
+        oracle_address: Address,
+        rpc_url: &str,
+        mode: BeaconMode,
+        update_interval: Duration,
+        error_control: Arc<OrchestratorErrorControl>,
+    ) -> anyhow::Result<Self> {
+        let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
+        Ok(Self {
+            oracle_address,
+            contract: IRandomnessBeacon::new(oracle_address, provider),
+            mode,
+            timer: Arc::new(RwLock::new(PreciseTimer::new(update_interval.as_millis() as u64))),
+            update_interval,
+            // Read the contract before the first update
+            on_chain: RwLock::new(OnChain { stale: true, ..Default::default() }),
+            in_flight: RwLock::new(None),
+            committing: Mutex::new(None),
+            last_drift_ms: Arc::new(RwLock::new(0)),
+            error_control,
+            stats: OracleStats::shared(),
+            pending: PendingQueue::for_interval(FEED, update_interval),
+            breaker: CircuitBreaker::from_env(FEED),
+            receipt_verifier: None,
+            reorg_detector: None,
+            republish: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn with_receipt_verifier(mut self, verifier: Option<Arc<ReceiptVerifier>>) -> Self {
+        self.receipt_verifier = verifier;
+        self
+    }
+
+    pub fn with_reorg_detector(mut self, detector: Option<Arc<ReorgDetector>>) -> Self {
+        self.reorg_detector = detector;
+        self
+    }
+
+    /// Read the epoch / last reveal or last drand round from the contract
+    async fn resync(&self) -> std::result::Result<(), alloy::contract::Error> {
+        match &self.mode {
+            BeaconMode::CommitReveal { master_seed, chain_length } => {
+                let epoch: u64 = self.contract.epoch().call().await?._0.saturating_to();
+                let last_reveal = self.contract.lastReveal().call().await?._0;
+
+                let mut on_chain = self.on_chain.write();
+                let chain = match on_chain.chain.take() {
+                    Some(chain) if chain.epoch() == epoch => Some(chain),
+                    _ if epoch == 0 => None,
+                    _ => Some(HashChain::new(*master_seed, epoch, *chain_length)),
+                };
+                on_chain.epoch = epoch;
+                on_chain.position = 0;
+                on_chain.chain = match chain {
+                    Some(chain) => match chain.position_of(last_reveal) {
+                        Some(position) => {
+                            on_chain.position = position;
+                            info!("🎲 Epoch {}: {} of {} reveals used", epoch, position, chain.len());
+                            Some(chain)
+                        }
+                        None => {
+                            warn!(
+                                "⚠️ The contract's last reveal is not on our chain for epoch {} (was the seed changed?) - committing a new chain",
+                                epoch
+                            );
+                            None
+                        }
+                    },
+                    None => None,
+                };
+                on_chain.stale = false;
+            }
+            BeaconMode::Drand(_) => {
+                let round = self.contract.lastDrandRound().call().await?._0;
+                let mut on_chain = self.on_chain.write();
+                on_chain.last_drand_round = round;
+                on_chain.stale = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Next commit or reveal: `(call data, update, gas limit, charted value)`
+    fn next_commit_reveal(&self, master_seed: B256, chain_length: usize) -> Option<(Bytes, InFlight, u64, f64)> {
+        let on_chain = self.on_chain.read();
+        match &on_chain.chain {
+            Some(chain) if on_chain.position < chain.len() => {
+                if !self.timer.read().is_due() {
+                    return None;
+                }
+                if let Some((target_time, actual_time)) = self.timer.write().should_tick() {
+                    *self.last_drift_ms.write() = actual_time as i64 - target_time as i64;
+                }
+                let position = on_chain.position + 1;
+                let link = chain.link(position)?;
+                let selector = function_selector("reveal(bytes32)");
+                Some((encode_reveal(selector, link), InFlight::Reveal { position }, 120_000, position as f64))
+            }
+            // Nothing committed yet, or the chain is used up: commit the next epoch
+            _ => {
+                let epoch = on_chain.epoch + 1;
+                let chain = HashChain::new(master_seed, epoch, chain_length);
+                let selector = function_selector("commitChain(uint256,bytes32)");
+                let call_data = encode_commit_chain(selector, epoch, chain.anchor());
+                info!("🔗 Committing hash chain for epoch {} ({} reveals, anchor {})", epoch, chain.len(), chain.anchor());
+                *self.committing.lock() = Some(chain);
+                Some((call_data, InFlight::Commit { epoch }, 100_000, 0.0))
+            }
+        }
+    }
+
+    fn next_drand(&self, client: &DrandClient) -> Option<(Bytes, InFlight, u64, f64)> {
+        let beacon = client.latest()?;
+        if beacon.round <= self.on_chain.read().last_drand_round {
+            return None;
+        }
+        let selector = function_selector("relayDrand(uint64,bytes)");
+        let call_data = encode_relay_drand(selector, beacon.round, &beacon.signature);
+        Some((call_data, InFlight::Drand { round: beacon.round }, 150_000, beacon.round as f64))
+    }
+}
+
+#[async_trait]
+impl TxTrigger for RandomnessTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>> {
+        let started = SystemTime::now();
+        if self.error_control.is_worker_pool_paused().await {
+            debug!("Worker pool paused, skipping trigger");
+            return Ok(None);
+        }
+        if self.in_flight.read().is_some() {
+            return Ok(None);
+        }
+
+        // A reorg dropped a published update: the contract is behind what we confirmed
+        if self.republish.swap(false, Ordering::Relaxed) {
+            self.on_chain.write().stale = true;
+        }
+        if self.on_chain.read().stale {
+            if let Err(e) = self.resync().await {
+                warn!("⚠️ Failed to read the randomness beacon contract: {}", e);
+                return Ok(None);
+            }
+        }
+
+        let next = match &self.mode {
+            BeaconMode::CommitReveal { master_seed, chain_length } => self.next_commit_reveal(*master_seed, *chain_length),
+            BeaconMode::Drand(client) => self.next_drand(client),
+        };
+        let Some((call_data, update, gas_limit, value)) = next else {
+            return Ok(None);
+        };
+
+        if !self.breaker.try_acquire() {
+            debug!("Circuit breaker open, skipping update");
+            self.committing.lock().take();
+            return Ok(None);
+        }
+
+        self.stats.write().record_trigger();
+        *self.in_flight.write() = Some(update);
+
+        let kind = match update {
+            InFlight::Commit { epoch } => {
+                info!("🚀 Committing epoch {}", epoch);
+                "randomness_commit"
+            }
+            InFlight::Reveal { position } => {
+                info!("🚀 Revealing link {}", position);
+                "randomness_reveal"
+            }
+            InFlight::Drand { round } => {
+                info!("🚀 Relaying drand round {}", round);
+                "randomness_drand"
+            }
+        };
+
+        let tx_request = TxRequest::new(self.oracle_address, call_data.clone())
+            .with_gas_limit(U256::from(gas_limit))
+            .with_priority(TxPriority::High)
+            .with_metadata("type", kind)
+            .with_metadata("value", value.to_string());
+
+        let admitted = self.pending.admit(tx_request).map(|(tx_request, _)| tx_request);
+        if admitted.is_some() {
+            trace_fired(FEED, &call_data, started);
+        } else {
+            *self.in_flight.write() = None;
+            self.committing.lock().take();
+        }
+        Ok(admitted)
+    }
+
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        let started = SystemTime::now();
+        self.pending.complete();
+        self.breaker.record(success);
+        let update = self.in_flight.write().take();
+        let drift_ms = match update {
+            Some(InFlight::Reveal { .. }) => *self.last_drift_ms.read(),
+            _ => 0,
+        };
+        let value = match update {
+            Some(InFlight::Reveal { position }) => Some(position as f64),
+            Some(InFlight::Drand { round }) => Some(round as f64),
+            _ => None,
+        };
+        publish_event(OracleEvent::completed(FEED, success, receipt, value, latency, drift_ms));
+
+        if success {
+            {
+                let mut on_chain = self.on_chain.write();
+                match update {
+                    Some(InFlight::Commit { epoch }) => {
+                        on_chain.epoch = epoch;
+                        on_chain.chain = self.committing.lock().take();
+                        on_chain.position = 0;
+                    }
+                    Some(InFlight::Reveal { position }) => on_chain.position = position,
+                    Some(InFlight::Drand { round }) => on_chain.last_drand_round = round,
+                    None => {}
+                }
+            }
+            self.stats.write().record_success(drift_ms, receipt.map(|r| r.gas_used), latency);
+            if let Some(receipt) = receipt {
+                self.stats.write().last_block = Some(receipt.block_number);
+                info!(
+                    "✅ Randomness update confirmed - tx: {}, block: {}, gas: {}",
+                    receipt.transaction_hash, receipt.block_number, receipt.gas_used
+                );
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(FEED, receipt, &self.stats);
+                }
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(FEED, receipt, Some(&self.republish));
+                }
+            }
+        } else {
+            self.committing.lock().take();
+            // The update may have landed anyway; re-read before building the next one
+            self.on_chain.write().stale = true;
+            self.stats.write().record_failure();
+            error!("❌ Randomness update failed");
+        }
+        trace_completed(FEED, receipt, success, started);
+    }
+
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: "RandomnessTrigger".to_string(),
+            description: format!("Publishes {} randomness each round", self.mode.name()),
+            trigger_type: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for RandomnessTrigger {
+    fn status(&self) -> serde_json::Value {
+        let on_chain = self.on_chain.read();
+        let source = match &self.mode {
+            BeaconMode::CommitReveal { .. } => serde_json::json!({
+                "epoch": on_chain.epoch,
+                "reveals_used": on_chain.position,
+                "reveals_left": on_chain.chain.as_ref().map(|c| c.len() - on_chain.position),
+                "update_interval_ms": self.update_interval.as_millis() as u64,
+            }),
+            BeaconMode::Drand(client) => serde_json::json!({
+                "url": client.url(),
+                "latest_round": client.latest().map(|b| b.round),
+                "last_relayed_round": on_chain.last_drand_round,
+            }),
+        };
+        serde_json::json!({
+            "oracle": "randomness-beacon",
+            "oracle_address": self.oracle_address.to_string(),
+            "mode": self.mode.name(),
+            "source": source,
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+        })
+    }
+}
@@ -0,0 +1,56 @@
+//! Hash chain links, positions and drand beacon checks
+
+use alloy::primitives::{keccak256, B256};
+use randomness_beacon::drand::{randomness_of, DrandBeacon};
+use randomness_beacon::hash_chain::{epoch_seed, verify_link, HashChain};
+
+#[test]
+fn test_each_reveal_hashes_to_the_previous_link() {
+    let chain = HashChain::new(B256::repeat_byte(7), 1, 100);
+    assert_eq!(chain.len(), 100);
+    assert_eq!(chain.link(0), Some(chain.anchor()));
+    assert_eq!(chain.link(100), Some(epoch_seed(B256::repeat_byte(7), 1)));
+    assert_eq!(chain.link(101), None);
+
+    for position in 1..=chain.len() {
+        let previous = chain.link(position - 1).unwrap();
+        let reveal = chain.link(position).unwrap();
+        assert!(verify_link(previous, reveal), "link {}", position);
+        assert_eq!(keccak256(reveal), previous);
+    }
+    assert!(!verify_link(chain.anchor(), chain.link(2).unwrap()));
+}
+
+#[test]
+fn test_position_of_resumes_after_restart() {
+    let chain = HashChain::new(B256::repeat_byte(1), 3, 50);
+    let rebuilt = HashChain::new(B256::repeat_byte(1), 3, 50);
+    assert_eq!(rebuilt.position_of(chain.link(17).unwrap()), Some(17));
+    assert_eq!(rebuilt.position_of(chain.anchor()), Some(0));
+
+    // Another seed or epoch gives an unrelated chain
+    assert_eq!(HashChain::new(B256::repeat_byte(2), 3, 50).position_of(chain.link(17).unwrap()), None);
+    assert_eq!(HashChain::new(B256::repeat_byte(1), 4, 50).position_of(chain.link(17).unwrap()), None);
+}
+
+#[test]
+fn test_drand_beacon_randomness_must_match_signature() {
+    let signature = vec![0xa5u8; 48];
+    let randomness = randomness_of(&signature);
+    let body = format!(
+        r#"{{"round":12345,"randomness":"{}","signature":"{}"}}"#,
+        alloy::hex::encode(randomness),
+        alloy::hex::encode(&signature)
+    );
+    let beacon = DrandBeacon::parse(&body).unwrap();
+    assert_eq!(beacon.round, 12345);
+    assert_eq!(beacon.randomness, randomness);
+    assert_eq!(beacon.signature.as_ref(), signature.as_slice());
+
+    let forged = format!(
+        r#"{{"round":12345,"randomness":"{}","signature":"{}"}}"#,
+        alloy::hex::encode(B256::repeat_byte(9)),
+        alloy::hex::encode(&signature)
+    );
+    assert!(DrandBeacon::parse(&forged).is_err());
+}
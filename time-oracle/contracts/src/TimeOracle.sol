@@ -17,6 +17,9 @@ contract TimeOracle is ITimeOracle, Ownable, Pausable {
     /// @notice The block timestamp when the oracle was last updated
     uint256 private _lastUpdateTime;
     
+    /// @notice Sequence number of the latest sequenced update
+    uint256 private _round;
+    
     /// @notice Mapping of authorized updaters
     mapping(address => bool) private _authorizedUpdaters;
     
@@ -29,6 +32,9 @@ contract TimeOracle is ITimeOracle, Ownable, Pausable {
     /// @notice Error thrown when timestamp validation fails
     error TimestampValidationFailed(string reason);
     
+    /// @notice Error thrown when a round does not advance past the current one
+    error RoundOutOfOrder(uint256 provided, uint256 current);
+    
     /// @notice Modifier to check if caller is authorized to update
     modifier onlyAuthorized() {
         if (!_authorizedUpdaters[msg.sender] && msg.sender != owner()) {
@@ -76,6 +82,55 @@ contract TimeOracle is ITimeOracle, Ownable, Pausable {
         emit TimeUpdated(timestamp, msg.sender);
     }
     
+    /**
+     * @notice Updates the oracle with a new timestamp and sequence number
+     * @param timestamp The new timestamp in milliseconds since Unix epoch
+     * @param round The sequence number of this update
+     * @dev Reverts on stale or replayed rounds so an out-of-order update never
+     *      overwrites a newer timestamp. Gaps are accepted; consumers detect
+     *      missed updates from non-consecutive rounds.
+     */
+    function updateTimestampWithRound(uint256 timestamp, uint256 round)
+        external
+        override
+        onlyAuthorized
+        whenNotPaused
+    {
+        if (round <= _round) {
+            revert RoundOutOfOrder(round, _round);
+        }
+        
+        _timestamp = timestamp;
+        _lastUpdateTime = block.timestamp;
+        _round = round;
+        
+        emit TimeUpdated(timestamp, msg.sender);
+        emit TimeUpdatedWithRound(round, timestamp, msg.sender);
+    }
+    
+    /**
+     * @notice Returns the sequence number of the latest update
+     * @return The latest round, or 0 if no sequenced update has been made
+     */
+    function getLatestRound() external view override returns (uint256) {
+        return _round;
+    }
+    
+    /**
+     * @notice Returns the latest round together with its timestamp
+     * @return round The latest round
+     * @return timestamp The latest timestamp in milliseconds
+     * @return updatedAt The block timestamp when the oracle was last updated
+     */
+    function getLatestRoundData()
+        external
+        view
+        override
+        returns (uint256 round, uint256 timestamp, uint256 updatedAt)
+    {
+        return (_round, _timestamp, _lastUpdateTime);
+    }
+    
     /**
      * @notice Checks if the oracle data is considered stale
      * @param maxAge Maximum age in seconds before data is considered stale
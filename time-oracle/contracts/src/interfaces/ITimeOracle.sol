@@ -12,6 +12,12 @@ interface ITimeOracle {
     /// @param updatedBy The address that performed the update
     event TimeUpdated(uint256 indexed timestamp, address indexed updatedBy);
 
+    /// @notice Emitted when the oracle time is updated with a sequence number
+    /// @param round The sequence number of this update
+    /// @param timestamp The new timestamp in milliseconds
+    /// @param updatedBy The address that performed the update
+    event TimeUpdatedWithRound(uint256 indexed round, uint256 timestamp, address indexed updatedBy);
+
     /// @notice Returns the latest timestamp in milliseconds
     /// @return The current timestamp in milliseconds since Unix epoch
     function getLatestTimestamp() external view returns (uint256);
@@ -24,6 +30,22 @@ interface ITimeOracle {
     /// @param timestamp The new timestamp in milliseconds since Unix epoch
     function updateTimestamp(uint256 timestamp) external;
 
+    /// @notice Updates the oracle with a new timestamp and sequence number
+    /// @dev Rounds must strictly increase; gaps are allowed and mark missed updates
+    /// @param timestamp The new timestamp in milliseconds since Unix epoch
+    /// @param round The sequence number of this update
+    function updateTimestampWithRound(uint256 timestamp, uint256 round) external;
+
+    /// @notice Returns the sequence number of the latest update
+    /// @return The latest round, or 0 if no sequenced update has been made
+    function getLatestRound() external view returns (uint256);
+
+    /// @notice Returns the latest round together with its timestamp
+    /// @return round The latest round
+    /// @return timestamp The latest timestamp in milliseconds
+    /// @return updatedAt The block timestamp when the oracle was last updated
+    function getLatestRoundData() external view returns (uint256 round, uint256 timestamp, uint256 updatedAt);
+
     /// @notice Checks if the oracle data is considered stale
     /// @param maxAge Maximum age in seconds before data is considered stale
     /// @return True if the data is stale, false otherwise
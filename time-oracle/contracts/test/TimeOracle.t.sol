@@ -13,6 +13,7 @@ contract TimeOracleTest is Test {
     address public unauthorizedUser = makeAddr("unauthorizedUser");
 
     event TimeUpdated(uint256 indexed timestamp, address indexed updatedBy);
+    event TimeUpdatedWithRound(uint256 indexed round, uint256 timestamp, address indexed updatedBy);
 
     function setUp() public {
         vm.prank(owner);
@@ -250,4 +251,52 @@ contract TimeOracleTest is Test {
         oracle.updateTimestamp(finalTimestamp);
         assertEq(oracle.getLatestTimestamp(), finalTimestamp);
     }
+
+    function testUpdateTimestampWithRound() public {
+        uint256 newTimestamp = block.timestamp * 1000 + 250;
+
+        vm.expectEmit(true, true, false, true);
+        emit TimeUpdatedWithRound(1, newTimestamp, owner);
+
+        vm.prank(owner);
+        oracle.updateTimestampWithRound(newTimestamp, 1);
+
+        (uint256 round, uint256 timestamp, uint256 updatedAt) = oracle.getLatestRoundData();
+        assertEq(round, 1);
+        assertEq(timestamp, newTimestamp);
+        assertEq(updatedAt, block.timestamp);
+        assertEq(oracle.getLatestRound(), 1);
+    }
+
+    function testRoundGapsAreAccepted() public {
+        vm.startPrank(owner);
+        oracle.updateTimestampWithRound(block.timestamp * 1000, 1);
+        oracle.updateTimestampWithRound(block.timestamp * 1000 + 300, 4);
+        vm.stopPrank();
+
+        assertEq(oracle.getLatestRound(), 4);
+    }
+
+    function testRevertOnOutOfOrderRound() public {
+        vm.startPrank(owner);
+        oracle.updateTimestampWithRound(block.timestamp * 1000 + 200, 5);
+
+        vm.expectRevert(abi.encodeWithSelector(TimeOracle.RoundOutOfOrder.selector, 4, 5));
+        oracle.updateTimestampWithRound(block.timestamp * 1000 + 100, 4);
+
+        vm.expectRevert(abi.encodeWithSelector(TimeOracle.RoundOutOfOrder.selector, 5, 5));
+        oracle.updateTimestampWithRound(block.timestamp * 1000 + 300, 5);
+        vm.stopPrank();
+
+        assertEq(oracle.getLatestTimestamp(), block.timestamp * 1000 + 200);
+    }
+
+    function testUnsequencedUpdateKeepsRound() public {
+        vm.startPrank(owner);
+        oracle.updateTimestampWithRound(block.timestamp * 1000, 7);
+        oracle.updateTimestamp(block.timestamp * 1000 + 100);
+        vm.stopPrank();
+
+        assertEq(oracle.getLatestRound(), 7);
+    }
 }
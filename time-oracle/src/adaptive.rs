@@ -0,0 +1,115 @@
+//! Adaptive update interval.
+//!
+//! When the VM is overloaded or the RPC is slow, ticks fire late and
+//! `PreciseTimer` keeps skipping missed intervals. With `ADAPTIVE_INTERVAL`
+//! enabled the oracle instead doubles its interval while the average drift
+//! stays above a threshold, and halves it back towards the configured
+//! interval once drift has been low for a full window.
+
+use anyhow::Result;
+use serde_json::json;
+use std::collections::VecDeque;
+use tracing::{info, warn};
+
+/// Ticks averaged before the interval is changed
+const DEFAULT_WINDOW: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveInterval {
+    base_ms: u64,
+    max_ms: u64,
+    /// Back off when the window's average drift exceeds this
+    drift_threshold_ms: f64,
+    window: usize,
+    current_ms: u64,
+    recent_drifts: VecDeque<i64>,
+    backoffs: u64,
+    recoveries: u64,
+}
+
+impl AdaptiveInterval {
+    pub fn new(base_ms: u64, max_ms: u64, drift_threshold_ms: f64) -> Self {
+        Self {
+            base_ms,
+            max_ms: max_ms.max(base_ms),
+            drift_threshold_ms,
+            window: DEFAULT_WINDOW,
+            current_ms: base_ms,
+            recent_drifts: VecDeque::with_capacity(DEFAULT_WINDOW),
+            backoffs: 0,
+            recoveries: 0,
+        }
+    }
+
+    /// `ADAPTIVE_INTERVAL`, with `ADAPTIVE_MAX_INTERVAL_MS` (default 8x the
+    /// base interval) and `ADAPTIVE_DRIFT_THRESHOLD_MS` (default half the base
+    /// interval). Returns `None` when disabled.
+    pub fn from_env(base_ms: u64) -> Result<Option<Self>> {
+        if !matches!(std::env::var("ADAPTIVE_INTERVAL").as_deref(), Ok("1") | Ok("true") | Ok("yes")) {
+            return Ok(None);
+        }
+        let max_ms = match std::env::var("ADAPTIVE_MAX_INTERVAL_MS") {
+            Ok(v) => v.parse()?,
+            Err(_) => base_ms * 8,
+        };
+        let drift_threshold_ms = match std::env::var("ADAPTIVE_DRIFT_THRESHOLD_MS") {
+            Ok(v) => v.parse()?,
+            Err(_) => base_ms as f64 / 2.0,
+        };
+        Ok(Some(Self::new(base_ms, max_ms, drift_threshold_ms)))
+    }
+
+    pub fn current_ms(&self) -> u64 {
+        self.current_ms
+    }
+
+    /// Record a tick's drift; returns the new interval when it changes
+    pub fn record(&mut self, drift_ms: i64) -> Option<u64> {
+        if self.recent_drifts.len() == self.window {
+            self.recent_drifts.pop_front();
+        }
+        self.recent_drifts.push_back(drift_ms.max(0));
+
+        // Back off quickly: a few late ticks are enough
+        let min_samples = (self.window / 4).max(1);
+        if self.recent_drifts.len() < min_samples {
+            return None;
+        }
+        let avg = self.recent_drifts.iter().sum::<i64>() as f64 / self.recent_drifts.len() as f64;
+
+        if avg > self.drift_threshold_ms && self.current_ms < self.max_ms {
+            let previous = self.current_ms;
+            self.current_ms = (self.current_ms * 2).min(self.max_ms);
+            self.backoffs += 1;
+            self.recent_drifts.clear();
+            warn!("🐢 Avg drift {:.1}ms > {:.1}ms, backing off interval {}ms -> {}ms",
+                avg, self.drift_threshold_ms, previous, self.current_ms);
+            return Some(self.current_ms);
+        }
+
+        // Recover slowly: a full window well under the threshold
+        if self.recent_drifts.len() == self.window
+            && avg < self.drift_threshold_ms / 4.0
+            && self.current_ms > self.base_ms
+        {
+            let previous = self.current_ms;
+            self.current_ms = (self.current_ms / 2).max(self.base_ms);
+            self.recoveries += 1;
+            self.recent_drifts.clear();
+            info!("🐇 Avg drift {:.1}ms recovered, interval {}ms -> {}ms", avg, previous, self.current_ms);
+            return Some(self.current_ms);
+        }
+
+        None
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "base_interval_ms": self.base_ms,
+            "max_interval_ms": self.max_ms,
+            "drift_threshold_ms": self.drift_threshold_ms,
+            "backoffs": self.backoffs,
+            "recoveries": self.recoveries,
+        })
+    }
+}
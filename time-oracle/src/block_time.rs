@@ -0,0 +1,130 @@
+//! Published timestamp vs. block timestamp monitoring.
+//!
+//! For a sample of confirmed updates, fetch the transaction (to read the
+//! timestamp we actually published) and its block (for `block.timestamp`),
+//! and keep the distribution of `published_ms - block_timestamp_ms`. This
+//! quantifies how far ahead of or behind the chain clock the oracle runs.
+//!
+//! `block.timestamp` has one-second resolution on most chains, so a healthy
+//! oracle shows deviations in `[0, 1000)` ms; anything outside that range
+//! means our clock or the sequencer's is off.
+
+use alloy::primitives::{B256, U256};
+use oracle_common::{Distribution, DistributionSummary, RpcEndpoints};
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Samples kept for the distribution (~3 hours at one per second)
+const MAX_SAMPLES: usize = 10_000;
+
+pub struct BlockTimeMonitor {
+    deviations_ms: RwLock<Distribution>,
+    sample_every: u64,
+    seen: AtomicU64,
+    queue: mpsc::UnboundedSender<(B256, U256)>,
+}
+
+impl BlockTimeMonitor {
+    /// Monitor checking one in `sample_every` confirmed updates against `rpc_urls`
+    pub fn spawn(rpc_urls: Vec<String>, sample_every: u64) -> (Arc<Self>, JoinHandle<()>) {
+        let (queue, mut receiver) = mpsc::unbounded_channel::<(B256, U256)>();
+        let monitor = Arc::new(Self {
+            deviations_ms: RwLock::new(Distribution::new(MAX_SAMPLES)),
+            sample_every: sample_every.max(1),
+            seen: AtomicU64::new(0),
+            queue,
+        });
+
+        let rpc = RpcEndpoints::new(rpc_urls);
+        let worker = monitor.clone();
+        let handle = tokio::spawn(async move {
+            let mut cached_block: Option<(U256, u64)> = None;
+            while let Some((tx_hash, block_number)) = receiver.recv().await {
+                match worker.measure(&rpc, tx_hash, block_number, &mut cached_block).await {
+                    Ok(deviation_ms) => {
+                        debug!("Block timestamp deviation for {}: {}ms", tx_hash, deviation_ms);
+                        worker.deviations_ms.write().record(deviation_ms as f64);
+                    }
+                    Err(e) => warn!("⚠️ Block timestamp check for {} failed: {}", tx_hash, e),
+                }
+            }
+        });
+
+        (monitor, handle)
+    }
+
+    /// Queue a confirmed update for checking (sampled)
+    pub fn observe(&self, tx_hash: B256, block_number: U256) {
+        if self.seen.fetch_add(1, Ordering::Relaxed) % self.sample_every == 0 {
+            let _ = self.queue.send((tx_hash, block_number));
+        }
+    }
+
+    async fn measure(
+        &self,
+        rpc: &RpcEndpoints,
+        tx_hash: B256,
+        block_number: U256,
+        cached_block: &mut Option<(U256, u64)>,
+    ) -> anyhow::Result<i64> {
+        let tx = rpc
+            .call("eth_getTransactionByHash", json!([tx_hash.to_string()]))
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        let published_ms = decode_published_timestamp(&tx)?;
+
+        let block_ts = match cached_block {
+            Some((number, ts)) if *number == block_number => *ts,
+            _ => {
+                let block = rpc
+                    .call("eth_getBlockByNumber", json!([format!("{:#x}", block_number), false]))
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                let ts = parse_hex_u64(&block["timestamp"])?;
+                *cached_block = Some((block_number, ts));
+                ts
+            }
+        };
+
+        Ok(published_ms as i64 - (block_ts * 1000) as i64)
+    }
+
+    pub fn summary(&self) -> DistributionSummary {
+        self.deviations_ms.read().summary()
+    }
+
+    pub fn log_summary(&self) {
+        let s = self.summary();
+        if s.count > 0 {
+            info!(
+                "⛓️ Block Timestamp Deviation - Samples: {}, Mean: {:.0}ms, P50: {:.0}ms, P99: {:.0}ms, Range: [{:.0}, {:.0}]ms",
+                s.count, s.mean, s.p50, s.p99, s.min, s.max
+            );
+        }
+    }
+}
+
+/// Timestamp argument of an `updateTimestamp(uint256)` transaction
+fn decode_published_timestamp(tx: &Value) -> anyhow::Result<u64> {
+    let input = tx["input"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("transaction has no input"))?;
+    let hex = input.trim_start_matches("0x");
+    if hex.len() < 8 + 64 {
+        anyhow::bail!("input too short for updateTimestamp");
+    }
+    // Low 8 bytes of the first 32-byte argument
+    Ok(u64::from_str_radix(&hex[8 + 48..8 + 64], 16)?)
+}
+
+fn parse_hex_u64(value: &Value) -> anyhow::Result<u64> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("expected hex quantity, got {}", value))?;
+    Ok(u64::from_str_radix(s.trim_start_matches("0x"), 16)?)
+}
@@ -0,0 +1,161 @@
+//! Publication gap detection.
+//!
+//! A pause, an open circuit breaker, a stalled RPC or a crash leaves the
+//! on-chain timestamp standing still, and when ticks resume the timer just
+//! carries on from the current interval. [`GapTracker`] compares every
+//! confirmed timestamp with the previous one; when more than
+//! `TIME_GAP_THRESHOLD_MS` passed between them it logs the exact bounds,
+//! publishes [`OracleEvent::GapDetected`] for alerting sinks and adds the gap
+//! to the periodic stats line and to `gaps` in `/status`.
+//!
+//! The tracker starts from the contract's `getLatestTimestamp()`, so downtime
+//! across a restart is reported with the first update after it.
+//!
+//! | Variable | Default | Meaning |
+//! |----------|---------|---------|
+//! | `TIME_GAP_THRESHOLD_MS` | 10x `UPDATE_INTERVAL_MS` | Time between published timestamps reported as a gap |
+
+use alloy::hex;
+use alloy::primitives::Address;
+use anyhow::{Context, Result};
+use oracle_common::{function_selector, publish_event, OracleEvent, RpcEndpoints};
+use parking_lot::Mutex;
+use serde_json::json;
+use tracing::{info, warn};
+
+/// A stretch with no timestamp published, bounded by the timestamps either side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    pub from_ms: u64,
+    pub to_ms: u64,
+}
+
+impl Gap {
+    pub fn duration_ms(&self) -> u64 {
+        self.to_ms - self.from_ms
+    }
+}
+
+#[derive(Debug, Default)]
+struct GapState {
+    last_published_ms: Option<u64>,
+    count: u64,
+    total_ms: u64,
+    max_ms: u64,
+    last: Option<Gap>,
+}
+
+pub struct GapTracker {
+    target: String,
+    threshold_ms: u64,
+    state: Mutex<GapState>,
+}
+
+impl GapTracker {
+    pub fn new(target: impl Into<String>, threshold_ms: u64) -> Self {
+        Self { target: target.into(), threshold_ms, state: Mutex::new(GapState::default()) }
+    }
+
+    /// `TIME_GAP_THRESHOLD_MS`, defaulting to 10x the update interval (above
+    /// the adaptive interval's 8x back-off, which is not a gap)
+    pub fn threshold_from_env(interval_ms: u64) -> Result<u64> {
+        match std::env::var("TIME_GAP_THRESHOLD_MS") {
+            Ok(v) => v.parse().context("Invalid TIME_GAP_THRESHOLD_MS"),
+            Err(_) => Ok(interval_ms.saturating_mul(10)),
+        }
+    }
+
+    pub fn threshold_ms(&self) -> u64 {
+        self.threshold_ms
+    }
+
+    /// Measure the first gap from `published_ms` (e.g. the on-chain timestamp)
+    pub fn with_last_published(self, published_ms: Option<u64>) -> Self {
+        self.state.lock().last_published_ms = published_ms.filter(|ms| *ms > 0);
+        self
+    }
+
+    /// The oracle's current `getLatestTimestamp()`, in ms
+    pub async fn latest_from_chain(rpc_urls: Vec<String>, oracle_address: Address) -> Result<u64> {
+        let rpc = RpcEndpoints::new(rpc_urls);
+        let result = rpc
+            .call(
+                "eth_call",
+                json!([
+                    {
+                        "to": oracle_address.to_string(),
+                        "data": format!("0x{}", hex::encode(function_selector("getLatestTimestamp()"))),
+                    },
+                    "latest"
+                ]),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("getLatestTimestamp() failed: {:?}", e))?;
+
+        let hex_result = result
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("unexpected getLatestTimestamp() result: {}", result))?
+            .trim_start_matches("0x");
+        if hex_result.len() < 64 {
+            anyhow::bail!("getLatestTimestamp() returned no data");
+        }
+        Ok(u64::from_str_radix(&hex_result[48..64], 16)?)
+    }
+
+    /// Record a confirmed timestamp; returns the gap it closes, if any
+    pub fn record(&self, published_ms: u64) -> Option<Gap> {
+        let gap = {
+            let mut state = self.state.lock();
+            let previous = state.last_published_ms;
+            // Pipelined updates can confirm out of order; only newer ones count
+            if previous.is_some_and(|last| published_ms <= last) {
+                return None;
+            }
+            state.last_published_ms = Some(published_ms);
+            let from_ms = previous?;
+            let gap = Gap { from_ms, to_ms: published_ms };
+            if gap.duration_ms() <= self.threshold_ms {
+                return None;
+            }
+            state.count += 1;
+            state.total_ms += gap.duration_ms();
+            state.max_ms = state.max_ms.max(gap.duration_ms());
+            state.last = Some(gap);
+            gap
+        };
+
+        warn!(
+            "🕳️ [{}] Publication gap of {}ms: nothing published between timestamps {} and {}",
+            self.target, gap.duration_ms(), gap.from_ms, gap.to_ms
+        );
+        publish_event(OracleEvent::GapDetected { feed: self.target.clone(), from_ms: gap.from_ms, to_ms: gap.to_ms });
+        Some(gap)
+    }
+
+    /// Gap totals for the periodic stats output
+    pub fn log_summary(&self) {
+        let state = self.state.lock();
+        if let Some(last) = state.last {
+            info!(
+                "🕳️ [{}] Gaps - Count: {}, Total: {}ms, Max: {}ms, Last: {}..{}",
+                self.target, state.count, state.total_ms, state.max_ms, last.from_ms, last.to_ms
+            );
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let state = self.state.lock();
+        json!({
+            "threshold_ms": self.threshold_ms,
+            "last_published_ms": state.last_published_ms,
+            "count": state.count,
+            "total_ms": state.total_ms,
+            "max_ms": state.max_ms,
+            "last": state.last.map(|gap| json!({
+                "from_ms": gap.from_ms,
+                "to_ms": gap.to_ms,
+                "duration_ms": gap.duration_ms(),
+            })),
+        })
+    }
+}
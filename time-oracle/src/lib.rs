@@ -0,0 +1,614 @@
+//! Time Oracle Example
+//!
+//! This example demonstrates a high-frequency time oracle that updates
+//! an on-chain timestamp every 100ms using nonzu-sdk's advanced features:
+//! Updated: 2025-09-26 - Fixed function selector
+//! - Multi-key rotation for avoiding nonce conflicts
+//! - Precise timing with drift compensation
+//! - Circuit breaker for failure recovery (stops after consecutive failures,
+//!   probes with a single update after a cool-down)
+//! - Comprehensive error handling
+
+use nonzu_sdk::prelude::*;
+use nonzu_sdk::traits::TxBuildHook;
+use nonzu_sdk::types::rise_tx::RiseTransactionRequest;
+use alloy::primitives::{Address, U256};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use parking_lot::RwLock;
+use tracing::{info, error, debug, warn};
+use anyhow::Result;
+use nonzu_sdk::error_handling::OrchestratorErrorControl;
+use nonzu_sdk::RiseError;
+use async_trait::async_trait;
+use oracle_common::{
+    apply_sdk_defaults, dry_run_enabled, encode_update_timestamp, encode_update_timestamp_with_round,
+    key_rotation_from_env, publish_event, spawn_pause_watcher, CircuitBreaker, DataAge, DataAgeSample, DryRunOrchestrator, ErrorPolicy, EventBus, Heartbeat, OracleEvent, load_private_keys, oracle_error_handler_config,
+    pipeline_depth_from_env, submitter_for, OracleStats, PendingQueue, PreciseTimer, ReceiptVerifier, ReorgDetector,
+    RuntimeMonitor, SchedulerMode, SharedStats, ShutdownCoordinator, StatusServer, StatusSource, SystemTimerClock, TimeSource, UpdateHistory, time_source_from_env, init_telemetry, trace_built, trace_completed, trace_fired,
+};
+
+pub mod adaptive;
+pub mod block_time;
+pub mod gaps;
+pub mod sequence;
+pub mod targets;
+
+use adaptive::AdaptiveInterval;
+use block_time::BlockTimeMonitor;
+use gaps::GapTracker;
+use sequence::RoundSequencer;
+use targets::{targets_from_env, OracleTarget};
+
+// --- Fresh Timestamp Build Hook ---
+
+/// Simple build hook that uses the current timestamp at submission time
+#[derive(Clone)]
+struct FreshTimestampHook {
+    time_source: Arc<dyn TimeSource>,
+    /// Target name, for pipeline traces
+    target: String,
+    /// Sequence number assigned at trigger time, when sequencing is enabled
+    round: Option<u64>,
+    /// The timestamp actually sent, for gap tracking in `on_complete`
+    published_ms: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl TxBuildHook for FreshTimestampHook {
+    async fn on_build(
+        &self,
+        _tx_request: &TxRequest,
+        mut tx: RiseTransactionRequest,
+    ) -> Result<RiseTransactionRequest, RiseError> {
+        debug!("FreshTimestampHook::on_build called");
+        let started = SystemTime::now();
+        
+        // Get the current timestamp at submission time (NTP-corrected if configured)
+        let current_timestamp_ms = self.time_source.now_ms();
+        
+        debug!("Current timestamp: {}ms", current_timestamp_ms);
+        
+        // Update the calldata with the fresh timestamp
+        let call_data = encode_timestamp_call(current_timestamp_ms, self.round);
+        self.published_ms.store(current_timestamp_ms, Ordering::Relaxed);
+        if let Some(placeholder) = &tx.data {
+            trace_built(&self.target, placeholder, &call_data, started);
+        }
+        tx.data = Some(call_data);
+        
+        debug!("Updated tx data with timestamp");
+        Ok(tx)
+    }
+}
+
+fn encode_timestamp_call(timestamp_ms: u64, round: Option<u64>) -> alloy::primitives::Bytes {
+    match round {
+        Some(round) => encode_update_timestamp_with_round(timestamp_ms, round),
+        None => encode_update_timestamp(timestamp_ms),
+    }
+}
+
+// --- Fresh Timestamp Build Hook ---
+
+// --- Time Oracle Trigger ---
+
+/// Settings shared by the triggers of every target
+#[derive(Clone)]
+struct TriggerConfig {
+    update_interval_ms: u64,
+    error_control: Arc<OrchestratorErrorControl>,
+    scheduler: SchedulerMode,
+    align_ticks: bool,
+    time_source: Arc<dyn TimeSource>,
+    block_time: Option<Arc<BlockTimeMonitor>>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    gap_threshold_ms: u64,
+}
+
+/// Time oracle trigger that updates one target's timestamp every 100ms
+#[derive(Clone)]
+struct TimeOracleTrigger {
+    target: OracleTarget,
+    timer: Arc<RwLock<PreciseTimer>>,
+    update_interval_ms: u64,
+    stats: SharedStats,
+    error_control: Arc<OrchestratorErrorControl>,
+    scheduler: SchedulerMode,
+    time_source: Arc<dyn TimeSource>,
+    block_time: Option<Arc<BlockTimeMonitor>>,
+    receipt_verifier: Option<Arc<ReceiptVerifier>>,
+    reorg_detector: Option<Arc<ReorgDetector>>,
+    sequencer: Option<Arc<RoundSequencer>>,
+    adaptive: Option<Arc<RwLock<AdaptiveInterval>>>,
+    last_drift_ms: Arc<RwLock<i64>>,
+    /// Drift, round and sent timestamp (filled in by the build hook) of each
+    /// submitted tick, oldest first - with pipelining several can be
+    /// outstanding when `on_complete` runs
+    pending_ticks: Arc<RwLock<VecDeque<(i64, Option<u64>, Arc<AtomicU64>)>>>,
+    pending: Arc<PendingQueue>,
+    breaker: Arc<CircuitBreaker>,
+    gaps: Arc<GapTracker>,
+    data_age: DataAge,
+}
+
+impl TimeOracleTrigger {
+    fn new(target: OracleTarget, config: &TriggerConfig) -> Self {
+        let update_interval_ms = config.update_interval_ms;
+        // Aligned ticks follow the (possibly NTP-corrected) time source
+        let clock = Arc::new(SystemTimerClock::new(config.time_source.clone()));
+        let timer = if config.align_ticks {
+            PreciseTimer::aligned_with_clock(update_interval_ms, clock)
+        } else {
+            PreciseTimer::with_clock(update_interval_ms, clock)
+        };
+        Self {
+            timer: Arc::new(RwLock::new(timer)),
+            update_interval_ms,
+            stats: OracleStats::shared(),
+            error_control: config.error_control.clone(),
+            scheduler: config.scheduler,
+            time_source: config.time_source.clone(),
+            block_time: config.block_time.clone(),
+            receipt_verifier: config.receipt_verifier.clone(),
+            reorg_detector: config.reorg_detector.clone(),
+            sequencer: None,
+            adaptive: None,
+            last_drift_ms: Arc::new(RwLock::new(0)),
+            pending_ticks: Arc::new(RwLock::new(VecDeque::new())),
+            pending: PendingQueue::for_interval(&target.name, Duration::from_millis(update_interval_ms)),
+            breaker: CircuitBreaker::from_env(target.name.clone()),
+            gaps: Arc::new(GapTracker::new(target.name.clone(), config.gap_threshold_ms)),
+            data_age: DataAge::new(target.name.clone()),
+            target,
+        }
+    }
+
+    fn with_sequencer(mut self, sequencer: Option<RoundSequencer>) -> Self {
+        self.sequencer = sequencer.map(Arc::new);
+        self
+    }
+
+    fn with_adaptive(mut self, adaptive: Option<AdaptiveInterval>) -> Self {
+        self.adaptive = adaptive.map(|a| Arc::new(RwLock::new(a)));
+        self
+    }
+
+    /// Measure the first publication gap from the on-chain timestamp
+    fn with_last_published(mut self, published_ms: Option<u64>) -> Self {
+        self.gaps = Arc::new(
+            GapTracker::new(self.target.name.clone(), self.gaps.threshold_ms()).with_last_published(published_ms),
+        );
+        self
+    }
+
+    fn print_stats(&self) {
+        let stats = self.stats.read();
+        if stats.total_triggers > 0 && stats.total_triggers % 10 == 0 {
+            let success_rate = stats.success_rate();
+            let avg_drift = stats.avg_drift_ms();
+            
+            info!("📊 [{}] Oracle Stats - Triggers: {}, Success: {:.1}%, Avg Drift: {:.1}ms, Max Drift: {}ms, Interval: {}ms",
+                self.target.name, stats.total_triggers, success_rate, avg_drift, stats.max_drift_ms, self.timer.read().interval_ms());
+            
+            if let (Some(min_gas), Some(max_gas)) = (stats.min_gas_used, stats.max_gas_used) {
+                info!("⛽ [{}] Gas Usage - Min: {}, Max: {}", self.target.name, min_gas, max_gas);
+            }
+            
+            if let (Some(offset), Some(uncertainty)) = (self.time_source.offset_ms(), self.time_source.uncertainty_ms()) {
+                info!("🕰️ Clock - Offset: {:+.3}ms, Uncertainty: ±{:.3}ms", offset, uncertainty);
+            }
+            
+            if let Some(block_time) = &self.block_time {
+                block_time.log_summary();
+            }
+            
+            self.gaps.log_summary();
+        }
+    }
+}
+
+#[async_trait]
+impl TxTrigger for TimeOracleTrigger {
+    async fn should_trigger(&self) -> Result<Option<TxRequest>, RiseError> {
+        debug!("TimeOracleTrigger::should_trigger called");
+        
+        if self.error_control.is_worker_pool_paused().await {
+            debug!("Worker pool paused, skipping trigger");
+            return Ok(None);
+        }
+
+        // Wake exactly at the next deadline rather than whenever the next poll lands
+        let (deadline, interval_ms) = {
+            let timer = self.timer.read();
+            (timer.next_deadline(), timer.interval_ms())
+        };
+        self.scheduler
+            .wait_for(deadline, Duration::from_millis(interval_ms))
+            .await;
+        // The trigger stage starts at the tick, not while sleeping towards it
+        let started = SystemTime::now();
+
+        let mut timer = self.timer.write();
+        if let Some((target_time, actual_time)) = timer.should_tick() {
+            debug!("Timer tick! Creating transaction request...");
+            
+            // The tick is consumed either way; an open breaker just skips it
+            if !self.breaker.try_acquire() {
+                debug!("Circuit breaker open, skipping tick");
+                return Ok(None);
+            }
+            
+            // Calculate and store drift
+            let drift_ms = actual_time as i64 - target_time as i64;
+            *self.last_drift_ms.write() = drift_ms;
+            if let Some(adaptive) = &self.adaptive {
+                if let Some(interval_ms) = adaptive.write().record(drift_ms) {
+                    timer.set_interval(interval_ms);
+                }
+            }
+            let round = self.sequencer.as_ref().map(|s| s.next_round());
+            let published_ms = Arc::new(AtomicU64::new(0));
+            self.pending_ticks.write().push_back((drift_ms, round, published_ms.clone()));
+            debug!("Current drift: {}ms (target: {}ms, actual: {}ms)", drift_ms, target_time, actual_time);
+            
+            self.stats.write().record_trigger();
+            
+            // We don't need to calculate timestamps here anymore
+            // The build hook will use the fresh timestamp at submission time
+            
+            // Create placeholder calldata - will be replaced by build hook
+            let placeholder_timestamp = 0u64;
+            let call_data = encode_timestamp_call(placeholder_timestamp, round);
+            
+            // Use only the timestamp hook - gas is handled by SDK defaults
+            let timestamp_hook = Arc::new(FreshTimestampHook {
+                time_source: self.time_source.clone(),
+                target: self.target.name.clone(),
+                round,
+                published_ms,
+            });
+            
+            // The round variant writes one more slot and emits a second event
+            let gas_limit = self.target.gas_limit.unwrap_or(if round.is_some() { 80_000 } else { 60_000 });
+            let tx_request = TxRequest::new(self.target.address, call_data.clone())
+                .with_gas_limit(U256::from(gas_limit))
+                .with_metadata("target", self.target.name.clone())
+                .with_priority(TxPriority::High)
+                .with_build_hook(timestamp_hook);
+            
+            debug!("Created TxRequest with id: {}", tx_request.id);
+            let Some((tx_request, _)) = self.pending.admit(tx_request) else {
+                if let (Some((_, Some(round), _)), Some(sequencer)) = (self.pending_ticks.write().pop_back(), &self.sequencer) {
+                    sequencer.complete(round, false);
+                }
+                return Ok(None);
+            };
+            trace_fired(&self.target.name, &call_data, started);
+            Ok(Some(tx_request))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    async fn on_complete(&self, success: bool, receipt: Option<&SyncTransactionReceipt>, latency: Option<Duration>) {
+        debug!("TimeOracleTrigger::on_complete called - success: {}", success);
+        let started = SystemTime::now();
+        
+        self.pending.complete();
+        self.breaker.record(success);
+        let (drift_ms, round, published_ms) = self
+            .pending_ticks
+            .write()
+            .pop_front()
+            .unwrap_or_else(|| (*self.last_drift_ms.read(), None, Arc::new(AtomicU64::new(0))));
+        if let (Some(round), Some(sequencer)) = (round, &self.sequencer) {
+            sequencer.complete(round, success);
+        }
+        publish_event(OracleEvent::completed(&self.target.name, success, receipt, None, latency, drift_ms));
+        
+        if success {
+            
+            if let Some(receipt) = receipt {
+                info!("✅ [{}] Transaction confirmed! tx_hash: {}, block: {}, gas_used: {}", 
+                    self.target.name, receipt.transaction_hash, receipt.block_number, receipt.gas_used);
+                self.stats.write().last_block = Some(receipt.block_number);
+                if let Some(block_time) = &self.block_time {
+                    block_time.observe(receipt.transaction_hash, receipt.block_number);
+                }
+                if let Some(verifier) = &self.receipt_verifier {
+                    verifier.watch(&self.target.name, receipt, &self.stats);
+                }
+                // The next tick publishes a fresh timestamp anyway, so no re-publish flag
+                if let Some(detector) = &self.reorg_detector {
+                    detector.watch(&self.target.name, receipt, None);
+                }
+            } else {
+                warn!("⚠️ Success reported but no receipt provided");
+            }
+            
+            // Log transaction latency
+            if let Some(lat) = latency {
+                let lat_ms = lat.as_millis();
+                info!("⏱️ Transaction latency: {}ms", lat_ms);
+            }
+
+            // Zero when the build hook never ran (e.g. a tx that was not rebuilt)
+            let published_ms = published_ms.load(Ordering::Relaxed);
+            if published_ms > 0 {
+                self.gaps.record(published_ms);
+                // The timestamp is the data, captured when the tx was built
+                let included_ms = self.time_source.now_ms();
+                self.data_age.record(DataAgeSample { data_ms: published_ms, triggered_ms: published_ms, included_ms });
+                info!("🕐 [{}] Timestamp age at inclusion: {}ms", self.target.name, included_ms.saturating_sub(published_ms));
+            }
+
+            self.stats.write().record_success(drift_ms, receipt.map(|r| r.gas_used), latency);
+            self.print_stats();
+        } else {
+            self.stats.write().record_failure();
+            error!("❌ [{}] Oracle update failed", self.target.name);
+            self.print_stats();
+        }
+        trace_completed(&self.target.name, receipt, success, started);
+    }
+    
+    fn metadata(&self) -> TriggerMetadata {
+        TriggerMetadata {
+            name: "TimeOracle".to_string(),
+            description: format!("Updates {} timestamp every {}ms", self.target.name, self.update_interval_ms),
+            trigger_type: "oracle".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+}
+
+impl StatusSource for TimeOracleTrigger {
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "oracle": "time-oracle",
+            "target": self.target.name,
+            "oracle_address": self.target.address.to_string(),
+            "update_interval_ms": self.update_interval_ms,
+            "effective_interval_ms": self.timer.read().interval_ms(),
+            "adaptive_interval": self.adaptive.as_ref().map(|a| a.read().to_json()),
+            "last_drift_ms": *self.last_drift_ms.read(),
+            "clock_offset_ms": self.time_source.offset_ms(),
+            "clock_uncertainty_ms": self.time_source.uncertainty_ms(),
+            "time_source": self.time_source.status(),
+            "block_timestamp_deviation_ms": self.block_time.as_ref().map(|b| b.summary()),
+            "receipt_verification": self.receipt_verifier.as_ref().map(|v| v.to_json()),
+            "reorgs": self.reorg_detector.as_ref().map(|d| d.to_json()),
+            "rounds": self.sequencer.as_ref().map(|s| s.to_json()),
+            "gaps": self.gaps.to_json(),
+            "data_age": self.data_age.to_json(),
+            "stats": self.stats.read().to_json(),
+            "queue": self.pending.to_json(),
+            "circuit_breaker": self.breaker.to_json(),
+        })
+    }
+}
+
+/// `/status` for all targets; a single target keeps the flat layout
+struct TimeOracleStatus {
+    triggers: Vec<Arc<TimeOracleTrigger>>,
+}
+
+impl StatusSource for TimeOracleStatus {
+    fn status(&self) -> serde_json::Value {
+        match self.triggers.as_slice() {
+            [trigger] => trigger.status(),
+            triggers => serde_json::json!({
+                "oracle": "time-oracle",
+                "targets": triggers.iter().map(|t| t.status()).collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+/// Run the time oracle until Ctrl+C / SIGTERM.
+///
+/// Expects the crypto provider, logging and environment to be set up by the
+/// caller (the `time-oracle` binary or `nonzu run time-oracle`).
+pub async fn run() -> Result<()> {
+    info!("🚀 Starting Time Oracle with 100ms updates");
+    // Pipeline traces (OTEL_EXPORTER_OTLP_ENDPOINT), flushed when run returns
+    let _telemetry = init_telemetry("time-oracle")?;
+    
+    // Set SDK defaults early
+    let chain = apply_sdk_defaults().await?;
+    
+    let update_interval_ms: u64 = std::env::var("UPDATE_INTERVAL_MS")
+        .unwrap_or_else(|_| "100".to_string())
+        .parse()?;
+    
+    let oracle_address = std::env::var("ORACLE_ADDRESS")
+        .or_else(|_| std::env::var("TIME_ORACLE_ADDRESS"))
+        .unwrap_or_else(|_| "0x2B10C76b470F69ef1330EDE9Dd0a068D685Cd034".to_string())
+        .parse::<Address>()?;
+    let targets = targets_from_env(oracle_address)?;
+    
+    let private_keys = match load_private_keys(&["TIME_ORACLE_PRIVATE_KEY_", "PRIVATE_KEY_"]) {
+        Ok(keys) => keys,
+        Err(e) => {
+            error!("{}", e);
+            return Ok(());
+        }
+    };
+    
+    for target in &targets {
+        info!("📍 Oracle Address ({}): {}", target.name, target.address);
+    }
+    info!("🔑 Using {} keys for rotation", private_keys.len());
+    info!("⏱️ Update Interval: {}ms", update_interval_ms);
+    
+    // Key rotation policy - underfunded keys are skipped without being removed
+    let mut shutdown = ShutdownCoordinator::new();
+    let (key_selector, balance_refresher) = key_rotation_from_env(chain.rpc_url().to_string(), &private_keys)?;
+    shutdown.register("balance refresher", balance_refresher);
+    
+    // Set up error control for coordinating pause/resume
+    let error_control = Arc::new(OrchestratorErrorControl::new());
+    
+    // --- Create trigger and orchestrator ---
+    let scheduler = SchedulerMode::from_env()?;
+    let align_ticks = matches!(std::env::var("ALIGN_TICKS").as_deref(), Ok("1") | Ok("true") | Ok("yes"));
+    if align_ticks {
+        info!("🕛 Ticks aligned to wall-clock multiples of {}ms", update_interval_ms);
+    }
+    let (time_source, ntp_refresher) = time_source_from_env().await?;
+    if let Some(handle) = ntp_refresher {
+        shutdown.register("ntp refresher", handle);
+    }
+    
+    // Compare published timestamps with block.timestamp for 1 in N updates (0 disables)
+    let block_ts_sample_every: u64 = std::env::var("BLOCK_TS_SAMPLE_EVERY")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()?;
+    let block_time = if block_ts_sample_every > 0 {
+        let (monitor, handle) = BlockTimeMonitor::spawn(chain.rpc_urls.clone(), block_ts_sample_every);
+        shutdown.register("block timestamp monitor", handle);
+        Some(monitor)
+    } else {
+        None
+    };
+
+    // Re-check confirmed updates a few blocks later (RECEIPT_VERIFY_BLOCKS)
+    let receipt_verifier = match ReceiptVerifier::from_env(chain.rpc_urls.clone())? {
+        Some((verifier, handle)) => {
+            shutdown.register("receipt verifier", handle);
+            Some(verifier)
+        }
+        None => None,
+    };
+    let reorg_detector = match ReorgDetector::from_env(chain.rpc_urls.clone())? {
+        Some((detector, handle)) => {
+            shutdown.register("reorg detector", handle);
+            Some(detector)
+        }
+        None => None,
+    };
+    
+    // Plugins fed by the event bus: dashboard history and, with
+    // HEARTBEAT_URLS, pings only while updates keep landing
+    let history = UpdateHistory::from_env();
+    EventBus::global().subscribe(history.clone());
+    if let Some((heartbeat, handle)) = Heartbeat::from_env()? {
+        shutdown.register("heartbeat", handle);
+        EventBus::global().subscribe(heartbeat);
+    }
+    shutdown.register("pause watcher", spawn_pause_watcher("time-oracle", error_control.clone()));
+    
+    let config = TriggerConfig {
+        update_interval_ms,
+        error_control: error_control.clone(),
+        scheduler,
+        align_ticks,
+        time_source,
+        block_time,
+        receipt_verifier,
+        reorg_detector,
+        gap_threshold_ms: GapTracker::threshold_from_env(update_interval_ms)?,
+    };
+    
+    // One trigger per target; all share the keys, error control and clock
+    let sequence_numbers = matches!(std::env::var("SEQUENCE_NUMBERS").as_deref(), Ok("1") | Ok("true") | Ok("yes"));
+    let mut triggers = Vec::new();
+    for target in targets {
+        // Sequence numbers need a TimeOracle with updateTimestampWithRound
+        let sequencer = if sequence_numbers {
+            let sequencer = RoundSequencer::from_chain(chain.rpc_urls.clone(), target.address).await?;
+            info!("🔢 Sequence numbers enabled for {}, starting at round {}", target.name, sequencer.peek_next());
+            Some(sequencer)
+        } else {
+            None
+        };
+        // Gaps across this restart are measured from the last timestamp on chain
+        let last_published = match GapTracker::latest_from_chain(chain.rpc_urls.clone(), target.address).await {
+            Ok(published_ms) => Some(published_ms),
+            Err(e) => {
+                warn!("⚠️ Could not read {}'s latest timestamp, the gap across this restart goes unmeasured: {:#}", target.name, e);
+                None
+            }
+        };
+        let trigger = TimeOracleTrigger::new(target, &config)
+            .with_sequencer(sequencer)
+            .with_last_published(last_published)
+            .with_adaptive(AdaptiveInterval::from_env(update_interval_ms)?);
+        triggers.push(Arc::new(trigger));
+    }
+
+    let error_policy = ErrorPolicy::from_env()?;
+    if let Some(reporter) = error_policy.metrics().spawn_reporter_from_env()? {
+        shutdown.register("error metrics reporter", reporter);
+    }
+    if let Some(addr) = StatusServer::addr_from_env() {
+        let status = Arc::new(TimeOracleStatus { triggers: triggers.clone() });
+        let server = StatusServer::new(addr)
+            .with_status(status)
+            .with_error_metrics(error_policy.metrics())
+            .with_dashboard(history);
+        let server = match RuntimeMonitor::from_env()? {
+            Some((monitor, handle)) => {
+                shutdown.register("runtime probe", handle);
+                server.with_runtime_metrics(monitor)
+            }
+            None => server,
+        };
+        shutdown.register("status server", server.spawn());
+    }
+    let triggers: Vec<Arc<dyn TxTrigger>> = triggers.into_iter().map(|t| t as Arc<dyn TxTrigger>).collect();
+
+    // Deadline mode polls once per interval and the trigger sleeps to the exact tick
+    let check_interval = scheduler.check_interval(Duration::from_millis(update_interval_ms));
+    info!("⏰ Scheduler: {:?} (trigger check every {:?})", scheduler, check_interval);
+    
+    if dry_run_enabled() {
+        let dry_run = DryRunOrchestrator::new(
+            triggers,
+            &private_keys,
+            &chain,
+            check_interval,
+        ).await?;
+        shutdown.register("dry-run orchestrator", dry_run.spawn());
+        shutdown.wait_for_signal().await?;
+        info!("🛑 Shutting down Time Oracle (dry run)...");
+        shutdown.shutdown();
+        return Ok(());
+    }
+    
+    // --- Configure Error Handling ---
+    let error_handler_config = oracle_error_handler_config();
+    
+    // Create orchestrator with custom error handling
+    // For low-spec VMs: use 1 worker to avoid context switching overhead
+    let worker_count = pipeline_depth_from_env(private_keys.len())?;
+    let orchestrator = SimpleOrchestrator::new_with_config(
+        triggers,
+        private_keys.into_sdk(),
+        worker_count, // 1 on low-spec shared CPU unless PIPELINE_DEPTH is set
+        check_interval,
+        error_handler_config,
+    ).await?
+    .with_key_selector(key_selector.clone())
+    .with_submitter(key_selector.track_results(submitter_for(&chain)?))
+    .with_error_hook(error_policy);
+    
+    info!("🎯 Starting orchestrator...");
+    let handle = orchestrator.run().await;
+    
+    info!("⚡ Time Oracle is running! Press Ctrl+C to stop.");
+    
+    shutdown.wait_for_signal().await?;
+    
+    info!("🛑 Shutting down Time Oracle...");
+    shutdown.shutdown();
+    handle.shutdown().await?;
+    
+    info!("✅ Time Oracle stopped successfully");
+    
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Sequence numbers for timestamp updates.
+//!
+//! With `SEQUENCE_NUMBERS` enabled every update is sent as
+//! `updateTimestampWithRound(timestamp, round)`. The contract rejects rounds
+//! that do not advance, so a late transaction can never overwrite a newer
+//! timestamp, and consumers spot missed updates as gaps between rounds.
+//!
+//! Rounds continue from the on-chain `getLatestRound()` so a restart never
+//! reuses a sequence number.
+
+use alloy::hex;
+use alloy::primitives::Address;
+use anyhow::Result;
+use oracle_common::{RpcEndpoints, GET_LATEST_ROUND_SELECTOR};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::warn;
+
+#[derive(Debug, Default)]
+struct Confirmations {
+    last_confirmed: Option<u64>,
+    confirmed: u64,
+    failed: u64,
+    /// Rounds skipped between consecutive confirmations
+    missed: u64,
+    /// Confirmations for a round older than one already confirmed
+    out_of_order: u64,
+}
+
+pub struct RoundSequencer {
+    next: AtomicU64,
+    confirmations: Mutex<Confirmations>,
+}
+
+impl RoundSequencer {
+    pub fn new(first_round: u64) -> Self {
+        Self {
+            next: AtomicU64::new(first_round.max(1)),
+            confirmations: Mutex::new(Confirmations::default()),
+        }
+    }
+
+    /// Continue after the oracle's latest on-chain round
+    pub async fn from_chain(rpc_urls: Vec<String>, oracle_address: Address) -> Result<Self> {
+        let rpc = RpcEndpoints::new(rpc_urls);
+        let result = rpc
+            .call(
+                "eth_call",
+                json!([
+                    {
+                        "to": oracle_address.to_string(),
+                        "data": format!("0x{}", hex::encode(GET_LATEST_ROUND_SELECTOR)),
+                    },
+                    "latest"
+                ]),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("getLatestRound() failed (is the TimeOracle upgraded?): {:?}", e))?;
+
+        let hex_result = result
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("unexpected getLatestRound() result: {}", result))?
+            .trim_start_matches("0x");
+        if hex_result.len() < 64 {
+            anyhow::bail!("getLatestRound() returned no data - contract predates sequence numbers");
+        }
+        let latest = u64::from_str_radix(&hex_result[48..64], 16)?;
+        Ok(Self::new(latest + 1))
+    }
+
+    /// Assign the next round
+    pub fn next_round(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Round the next update will carry
+    pub fn peek_next(&self) -> u64 {
+        self.next.load(Ordering::Relaxed)
+    }
+
+    /// Record the outcome of the update carrying `round`
+    pub fn complete(&self, round: u64, success: bool) {
+        let mut c = self.confirmations.lock();
+        if !success {
+            c.failed += 1;
+            return;
+        }
+
+        c.confirmed += 1;
+        match c.last_confirmed {
+            Some(last) if round <= last => {
+                c.out_of_order += 1;
+                warn!("⚠️ Round {} confirmed after round {}", round, last);
+            }
+            Some(last) => {
+                c.missed += round - last - 1;
+                c.last_confirmed = Some(round);
+            }
+            None => c.last_confirmed = Some(round),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let c = self.confirmations.lock();
+        json!({
+            "next_round": self.next.load(Ordering::Relaxed),
+            "last_confirmed_round": c.last_confirmed,
+            "confirmed": c.confirmed,
+            "failed": c.failed,
+            "missed_rounds": c.missed,
+            "out_of_order": c.out_of_order,
+        })
+    }
+}
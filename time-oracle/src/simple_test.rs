@@ -79,6 +79,7 @@ pub async fn build_update_transaction(
     oracle_address: Address,
     signer: &PrivateKeySigner,
     nonce: u64,
+    chain_id: u64,
 ) -> Result<Bytes> {
     info!("🔧 Building transaction...");
     
@@ -104,7 +105,7 @@ pub async fn build_update_transaction(
     
     // Build EIP-1559 transaction
     let mut tx = TxEip1559 {
-        chain_id: 11155931, // RISE testnet
+        chain_id,
         nonce,
         gas_limit: 60_000,
         max_fee_per_gas: 300_000, // 0.0003 gwei
@@ -153,6 +154,9 @@ pub async fn run_simple_test() -> Result<()> {
     let rpc_url = std::env::var("RPC_URL")
         .unwrap_or_else(|_| "https://testnet.riselabs.xyz".to_string());
     
+    // Sign for the chain the RPC actually serves, checked against NETWORK / CHAIN_ID
+    let chain_id = oracle_common::ChainConfig::verified_chain_id(&rpc_url).await?;
+
     // Create client
     let client = SimpleRiseClient::new(rpc_url);
     
@@ -164,7 +168,8 @@ pub async fn run_simple_test() -> Result<()> {
     info!("🔢 Using test nonce: {}", test_nonce);
     
     // Build transaction
-    let raw_tx = build_update_transaction(oracle_address, &signer, test_nonce).await?;
+    let raw_tx = build_update_transaction(oracle_address, &signer, test_nonce, chain_id).await?;
+    let tx_hash = alloy::primitives::keccak256(&raw_tx);
     
     // Send transaction and measure timing
     info!("📡 === SENDING TRANSACTION ===");
@@ -207,15 +212,9 @@ pub async fn run_simple_test() -> Result<()> {
                 info!("📜 From: {:?}", receipt.from);
                 info!("📜 To: {:?}", receipt.to);
                 
-                // Check if receipt looks valid
-                if receipt.gas_used == U256::ZERO {
-                    error!("⚠️  WARNING: Gas used is 0 - this looks suspicious!");
-                }
-                if receipt.effective_gas_price == U256::ZERO {
-                    error!("⚠️  WARNING: Gas price is 0 - this looks suspicious!");
-                }
-                if receipt.block_number == U256::ZERO {
-                    error!("⚠️  WARNING: Block number is 0 - this looks suspicious!");
+                // Same checks the submission path applies (RECEIPT_VALIDATION)
+                for issue in oracle_common::receipt_issues(&receipt, Some(tx_hash)) {
+                    error!("⚠️  WARNING: {} - this looks suspicious!", issue);
                 }
             }
             Err(e) => {
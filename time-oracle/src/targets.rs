@@ -0,0 +1,82 @@
+//! TimeOracle contract instances updated by one process.
+//!
+//! `ORACLE_TARGETS` lists several contracts (e.g. staging and production, or
+//! per-dapp clocks) as comma-separated `name=address[:gas_limit]` entries:
+//!
+//! ```text
+//! ORACLE_TARGETS=production=0x2B10...d034,staging=0x9e7F...baa4:80000
+//! ```
+//!
+//! Every target gets its own trigger, timer and stats, and is updated on
+//! every tick. Without `ORACLE_TARGETS` the single `ORACLE_ADDRESS` is used.
+
+use alloy::primitives::Address;
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone)]
+pub struct OracleTarget {
+    pub name: String,
+    pub address: Address,
+    /// Gas limit override; the default depends on the update variant
+    pub gas_limit: Option<u64>,
+}
+
+impl OracleTarget {
+    pub fn new(name: impl Into<String>, address: Address) -> Self {
+        Self { name: name.into(), address, gas_limit: None }
+    }
+
+    /// Parse one `name=address[:gas_limit]` entry
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (name, rest) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid oracle target '{}' (expected name=address[:gas_limit])", spec))?;
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Oracle target '{}' has an empty name", spec);
+        }
+
+        let (address, gas_limit) = match rest.split_once(':') {
+            Some((address, gas)) => (address, Some(gas.trim().parse::<u64>().map_err(|e| {
+                anyhow!("Invalid gas limit in oracle target '{}': {}", spec, e)
+            })?)),
+            None => (rest, None),
+        };
+        let address = address
+            .trim()
+            .parse::<Address>()
+            .map_err(|e| anyhow!("Invalid address in oracle target '{}': {}", spec, e))?;
+
+        Ok(Self { name: name.to_string(), address, gas_limit })
+    }
+}
+
+/// Targets from `ORACLE_TARGETS`, or just `default_address`
+pub fn targets_from_env(default_address: Address) -> Result<Vec<OracleTarget>> {
+    let Ok(specs) = std::env::var("ORACLE_TARGETS") else {
+        return Ok(vec![OracleTarget::new("default", default_address)]);
+    };
+
+    let targets = specs
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(OracleTarget::parse)
+        .collect::<Result<Vec<_>>>()?;
+    if targets.is_empty() {
+        bail!("ORACLE_TARGETS is set but lists no targets");
+    }
+
+    let mut names = HashSet::new();
+    let mut addresses = HashSet::new();
+    for target in &targets {
+        if !names.insert(target.name.as_str()) {
+            bail!("Duplicate oracle target name '{}'", target.name);
+        }
+        if !addresses.insert(target.address) {
+            bail!("Oracle target address {} is listed twice", target.address);
+        }
+    }
+    Ok(targets)
+}
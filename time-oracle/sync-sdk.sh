@@ -19,9 +19,20 @@ echo "📝 Cleaning up SDK Cargo.toml..."
 sed -i.bak '/\[workspace\]/,/^$/d' vendor/nonzu-sdk/Cargo.toml
 rm vendor/nonzu-sdk/Cargo.toml.bak
 
-# Update our Cargo.toml to use the vendored SDK
+# Copy the shared oracle-common crate
+echo "📁 Copying oracle-common..."
+mkdir -p vendor/oracle-common
+cp -r ../oracle-common/src ../oracle-common/proto ../oracle-common/build.rs ../oracle-common/Cargo.toml vendor/oracle-common/
+sed -i.bak 's|nonzu-sdk = { workspace = true }|nonzu-sdk = { path = "../nonzu-sdk" }|' vendor/oracle-common/Cargo.toml
+rm vendor/oracle-common/Cargo.toml.bak
+
+# Update our Cargo.toml to use the vendored crates instead of the workspace
 echo "📝 Updating time-oracle Cargo.toml..."
-sed -i.bak 's|path = "../../"|path = "vendor/nonzu-sdk"|' Cargo.toml
+sed -i.bak \
+    -e 's|nonzu-sdk = { workspace = true }|nonzu-sdk = { path = "vendor/nonzu-sdk" }|' \
+    -e 's|oracle-common = { workspace = true }|oracle-common = { path = "vendor/oracle-common" }|' \
+    Cargo.toml
+rm Cargo.toml.bak
 
 echo "✅ SDK sync complete!"
 echo ""
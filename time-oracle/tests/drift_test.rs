@@ -0,0 +1,148 @@
+//! Drift compensation of the oracle's tick loop, simulated on a mock clock:
+//! poll the timer, "process" each tick for a while, repeat. Covers the
+//! properties documented on `PreciseTimer` across interval/delay
+//! combinations - targets stay on the interval grid, delays shorter than the
+//! interval don't accumulate into drift, and longer ones skip intervals
+//! instead of bursting to catch up.
+
+use oracle_common::{MockClock, PreciseTimer};
+
+const WALL_START_MS: u64 = 1_735_689_600_037;
+
+/// How often the loop checks the timer when idle
+const POLL_MS: u64 = 5;
+
+/// `(target_ms, actual_ms)` of every tick
+struct Run {
+    ticks: Vec<(u64, u64)>,
+}
+
+impl Run {
+    fn drifts(&self) -> impl Iterator<Item = u64> + '_ {
+        self.ticks.iter().map(|(target, actual)| actual - target)
+    }
+
+    fn max_drift(&self) -> u64 {
+        self.drifts().max().unwrap_or(0)
+    }
+
+    fn avg_drift(&self) -> f64 {
+        self.drifts().sum::<u64>() as f64 / self.ticks.len() as f64
+    }
+
+    /// Intervals passed over without a tick
+    fn skipped(&self, interval_ms: u64) -> u64 {
+        self.ticks.windows(2).map(|w| (w[1].0 - w[0].0) / interval_ms - 1).sum()
+    }
+}
+
+/// Drive the loop for `duration_ms`, spending `delay(n)` on the n-th tick
+fn simulate(interval_ms: u64, duration_ms: u64, mut delay: impl FnMut(usize) -> u64) -> Run {
+    let clock = MockClock::new(WALL_START_MS);
+    let mut timer = PreciseTimer::with_clock(interval_ms, clock.clone());
+    let mut ticks = Vec::new();
+    let mut elapsed_ms = 0;
+
+    while elapsed_ms < duration_ms {
+        if let Some(tick) = timer.should_tick() {
+            let processing = delay(ticks.len());
+            ticks.push(tick);
+            clock.advance_ms(processing);
+            elapsed_ms += processing;
+        }
+        clock.advance_ms(POLL_MS);
+        elapsed_ms += POLL_MS;
+    }
+    Run { ticks }
+}
+
+/// `(interval_ms, delay_ms)` pairs where processing fits inside the interval
+const WITHIN_INTERVAL: [(u64, u64); 6] = [(100, 0), (100, 10), (100, 90), (50, 30), (250, 200), (1000, 400)];
+
+/// Pairs where processing outlasts the interval
+const OVER_INTERVAL: [(u64, u64); 5] = [(50, 60), (50, 130), (100, 150), (100, 480), (250, 900)];
+
+#[test]
+fn test_targets_stay_on_the_interval_grid() {
+    for (interval, delay) in WITHIN_INTERVAL.into_iter().chain(OVER_INTERVAL) {
+        let run = simulate(interval, 10_000, |_| delay);
+        for pair in run.ticks.windows(2) {
+            let (previous, next) = (pair[0].0, pair[1].0);
+            assert_eq!(next % interval, 0, "{}ms/{}ms: target {} off the grid", interval, delay, next);
+            assert!(next > previous, "{}ms/{}ms: target {} repeated", interval, delay, next);
+        }
+    }
+}
+
+#[test]
+fn test_delays_within_interval_do_not_accumulate() {
+    for (interval, delay) in WITHIN_INTERVAL {
+        let duration = 20_000;
+        let run = simulate(interval, duration, |_| delay);
+
+        // Every tick fires, and no later than the poll that first sees it due
+        let expected = duration / interval;
+        assert!(
+            run.ticks.len() as u64 >= expected - 1,
+            "{}ms/{}ms: {} ticks, expected {}",
+            interval,
+            delay,
+            run.ticks.len(),
+            expected
+        );
+        assert_eq!(run.skipped(interval), 0, "{}ms/{}ms skipped", interval, delay);
+        assert!(run.max_drift() < POLL_MS, "{}ms/{}ms: max drift {}ms", interval, delay, run.max_drift());
+    }
+}
+
+#[test]
+fn test_delays_over_interval_skip_instead_of_bursting() {
+    for (interval, delay) in OVER_INTERVAL {
+        let duration = 20_000;
+        let run = simulate(interval, duration, |_| delay);
+
+        assert!(run.skipped(interval) > 0, "{}ms/{}ms never skipped", interval, delay);
+        // No catch-up: consecutive ticks are at least one processing time apart
+        for pair in run.ticks.windows(2) {
+            assert!(pair[1].1 - pair[0].1 >= delay, "{}ms/{}ms burst at {:?}", interval, delay, pair);
+        }
+        // ...and no idling either: the next tick is due within an interval of finishing
+        let cycle = delay.max(interval) + POLL_MS;
+        assert!(
+            run.ticks.len() as u64 >= duration / cycle - 1,
+            "{}ms/{}ms: {} ticks in {}ms",
+            interval,
+            delay,
+            run.ticks.len(),
+            duration
+        );
+        assert!(run.max_drift() <= delay + POLL_MS, "{}ms/{}ms: max drift {}ms", interval, delay, run.max_drift());
+    }
+}
+
+#[test]
+fn test_jittered_delays_keep_average_drift_bounded() {
+    for seed in [1u32, 7, 42, 1234] {
+        // 5-50ms of processing, like network jitter on a send
+        let mut rng = seed;
+        let run = simulate(100, 30_000, |_| {
+            rng = rng.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            5 + (rng % 46) as u64
+        });
+
+        assert_eq!(run.skipped(100), 0, "seed {} skipped", seed);
+        assert!(run.max_drift() < POLL_MS, "seed {}: max drift {}ms", seed, run.max_drift());
+        assert!(run.avg_drift() < POLL_MS as f64 / 2.0 + 1.0, "seed {}: avg drift {:.2}ms", seed, run.avg_drift());
+    }
+}
+
+#[test]
+fn test_occasional_stall_recovers_on_the_grid() {
+    // One 1s stall in an otherwise quick 100ms loop
+    let run = simulate(100, 5_000, |n| if n == 10 { 1_000 } else { 10 });
+
+    let after_stall = &run.ticks[11..];
+    assert!(after_stall[0].1 - after_stall[0].0 <= 1_000 + POLL_MS, "stalled tick reports the lateness");
+    assert!(after_stall[1..].iter().all(|(target, actual)| actual - target < POLL_MS), "then back on schedule");
+    assert_eq!(run.skipped(100), 9, "the stalled intervals are skipped, not replayed");
+}
@@ -0,0 +1,52 @@
+//! Publication gaps: which intervals count, out-of-order confirmations, and
+//! gaps across a restart measured from the on-chain timestamp.
+
+use time_oracle::gaps::{Gap, GapTracker};
+
+const START_MS: u64 = 1_735_689_600_000;
+
+#[test]
+fn test_regular_updates_are_not_gaps() {
+    let gaps = GapTracker::new("production", 1_000);
+    for tick in 0..20 {
+        assert_eq!(gaps.record(START_MS + tick * 100), None);
+    }
+    // Exactly the threshold is still on time
+    assert_eq!(gaps.record(START_MS + 1_900 + 1_000), None);
+    assert_eq!(gaps.to_json()["count"], 0);
+}
+
+#[test]
+fn test_gaps_are_recorded_with_their_bounds() {
+    let gaps = GapTracker::new("production", 1_000);
+    gaps.record(START_MS);
+    assert_eq!(gaps.record(START_MS + 4_500), Some(Gap { from_ms: START_MS, to_ms: START_MS + 4_500 }));
+    gaps.record(START_MS + 4_600);
+    assert_eq!(gaps.record(START_MS + 6_600).map(|g| g.duration_ms()), Some(2_000));
+
+    let status = gaps.to_json();
+    assert_eq!((status["count"].as_u64(), status["total_ms"].as_u64(), status["max_ms"].as_u64()), (Some(2), Some(6_500), Some(4_500)));
+    assert_eq!(status["last"]["from_ms"], START_MS + 4_600);
+    assert_eq!(status["last"]["to_ms"], START_MS + 6_600);
+    assert_eq!(status["last_published_ms"], START_MS + 6_600);
+}
+
+#[test]
+fn test_late_confirmations_of_older_timestamps_are_ignored() {
+    let gaps = GapTracker::new("production", 1_000);
+    gaps.record(START_MS);
+    gaps.record(START_MS + 200);
+    assert_eq!(gaps.record(START_MS + 100), None);
+    assert_eq!(gaps.record(START_MS + 1_150), None);
+    assert_eq!(gaps.to_json()["last_published_ms"], START_MS + 1_150);
+}
+
+#[test]
+fn test_gap_across_a_restart_is_measured_from_the_chain() {
+    let gaps = GapTracker::new("production", 1_000).with_last_published(Some(START_MS));
+    assert_eq!(gaps.record(START_MS + 90_000), Some(Gap { from_ms: START_MS, to_ms: START_MS + 90_000 }));
+
+    // A never-updated oracle reads 0: nothing to measure from
+    let fresh = GapTracker::new("production", 1_000).with_last_published(Some(0));
+    assert_eq!(fresh.record(START_MS), None);
+}